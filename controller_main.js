@@ -9,13 +9,29 @@ import init, {
 
 // Shared timing constants (matching shared::timing in Rust)
 const REFRESH_RATE_HZ = 60;
-const WIN_BLANK_DURATION_FRAMES = 60; // 1 second at 60fps
+
+// Game phases (matching shared::Phase in Rust)
+const Phase = {
+  PLAYING: 0,
+  WON: 1,
+  FAILED: 2,
+  INTER_TRIAL: 3,
+  RESETTING: 4,
+  STANDBY: 5,
+};
+
+// What the game does once the inter-trial interval is over (matching shared::PostWinMode in Rust)
+const PostWinMode = {
+  AUTO_RETURN: 0,
+  HOLD_BLANK: 1,
+};
 
 // Win state machine states
 const WinState = {
   PLAYING: "PLAYING",
   WAITING_FOR_ANIMATION_END: "WAITING_FOR_ANIMATION_END",
-  BLANK_SCREEN_ACTIVE: "BLANK_SCREEN_ACTIVE",
+  // The game blanks the screen for the inter-trial interval itself
+  INTER_TRIAL: "INTER_TRIAL",
 };
 
 // Application State
@@ -35,7 +51,6 @@ let inputs = {
 
 // Win state machine
 let winState = WinState.PLAYING;
-let blankStartFrame = 0;
 
 // Touch state - virtual button system (instant like keyboard)
 let touchState = {
@@ -135,9 +150,6 @@ async function start() {
   await loadTrials();
 
   document.getElementById("status-bar").innerText = "Ready";
-  console.log(
-    `Frame-based timing: ${WIN_BLANK_DURATION_FRAMES} frames = ${(WIN_BLANK_DURATION_FRAMES / REFRESH_RATE_HZ).toFixed(2)}s at ${REFRESH_RATE_HZ}Hz`
-  );
 
   // Create shared memory
   const sharedPtr = create_shared_memory_wasm();
@@ -493,9 +505,14 @@ function setupInput() {
         handled = true;
         break;
       case "KeyR":
-        writeGameStructure(getCurrentTrial());
+        if (winState === WinState.INTER_TRIAL) {
+          // Ends the interval with the next trial (the only way out of a held blank)
+          writeGameStructure(trials[(currentTrialIndex + 1) % trials.length]);
+        } else {
+          writeGameStructure(getCurrentTrial());
+          winState = WinState.PLAYING;
+        }
         inputs.reset = 1;
-        winState = WinState.PLAYING;
         handled = true;
         break;
       case "KeyB":
@@ -547,7 +564,6 @@ function gameLoop() {
   if (appState === "GAME" && running) {
     const hasWon = checkWinCondition(); // Replaces readGameHasWon with local inference
     const isAnimating = readGameIsAnimating();
-    const currentFrame = readGameFrameNumber();
 
     // Win state machine (frame-based timing)
     switch (winState) {
@@ -610,23 +626,20 @@ function gameLoop() {
           if (isAnimating) {
             this.animStarted = true;
           } else if (this.animStarted) {
-            // Started and now finished!
-            console.log(
-              `Animation complete. Activating blank screen for ${WIN_BLANK_DURATION_FRAMES} frames`
-            );
+            // Started and now finished: the game has started the blank inter-trial interval
+            console.log("Animation complete. Inter-trial interval started");
             this.animStarted = false; // Reset for next time
 
-            // Prepare next trial config
-            const nextTrialIndex = (currentTrialIndex + 1) % trials.length;
-            writeGameStructure(trials[nextTrialIndex]);
-
-            // Send commands: reset + blank_screen + stop_rendering
-            inputs.reset = 1;
-            inputs.blankScreen = 1;
-            inputs.stopRendering = 1;
+            if (readGamePostWinMode() === PostWinMode.HOLD_BLANK) {
+              console.log("Holding blank until the next reset (R)");
+            } else {
+              // Load the next trial under the blank, the game shows it once the interval is over
+              const nextTrialIndex = (currentTrialIndex + 1) % trials.length;
+              writeGameStructure(trials[nextTrialIndex]);
+              inputs.reset = 1;
+            }
 
-            blankStartFrame = currentFrame;
-            winState = WinState.BLANK_SCREEN_ACTIVE;
+            winState = WinState.INTER_TRIAL;
           } else {
             // Hasn't started yet. Keep sending trigger?
             inputs.animationDoor = 1;
@@ -637,27 +650,10 @@ function gameLoop() {
         }
         break;
 
-      case WinState.BLANK_SCREEN_ACTIVE:
-        const framesElapsed = currentFrame - blankStartFrame;
-
-        if (framesElapsed >= WIN_BLANK_DURATION_FRAMES) {
-          console.log(`Blank screen complete (${framesElapsed} frames). Resuming.`);
-
-          // Send commands: blank_screen (toggle off) + resume_rendering
-          inputs.blankScreen = 1; // Toggle logic? No, SHM command is 'blank_screen', if it's a trigger to toggle? 
-          // Python: "blank_screen" command sets PendingBlankScreen=true.
-          // CommandHandler: if blank_screen.swap(...) -> pending_blank = true.
-          // Logic: PendingBlank usually toggles visibility or sets it?
-          // Actually, looking at rust code (not shown in depth), it likely just handles the event.
-          // Let's assume sending it again disables it or we manually disable.
-          // Wait, 'blank_screen' in SharedCommands is AtomicBool.
-          // If we write 1, game reads it.
-          // We should probably rely on the Game Node logic.
-          // Python toggles: "auto_blank = True" -> triggers['blank'] = True.
-          // If logic is "toggle", then sending it again toggles off.
-
-          inputs.blankScreen = 1;
-          inputs.resumeRendering = 1;
+      case WinState.INTER_TRIAL:
+        // The game lifts the blank itself once the interval is over (after the reset, when holding)
+        if (readGamePhase() === Phase.PLAYING) {
+          console.log("Inter-trial interval over");
 
           // Advance trial index
           currentTrialIndex = (currentTrialIndex + 1) % trials.length;
@@ -747,6 +743,18 @@ function readGameIsAnimating() {
   return view.getUint8(0) !== 0;
 }
 
+function readGamePhase() {
+  if (offsets.phase === undefined) return Phase.PLAYING;
+  const view = new DataView(memory.buffer, pointers.gameStructure + offsets.phase);
+  return view.getUint32(0, true);
+}
+
+function readGamePostWinMode() {
+  if (offsets.post_win_mode === undefined) return PostWinMode.AUTO_RETURN;
+  const view = new DataView(memory.buffer, pointers.gameStructure + offsets.post_win_mode);
+  return view.getUint32(0, true);
+}
+
 function readGameFrameNumber() {
   if (!offsets.frame_number) return 0;
   const view = new DataView(memory.buffer, pointers.gameStructure + offsets.frame_number);
@@ -1164,9 +1172,14 @@ function setupInput() {
         handled = true;
         break;
       case "KeyR":
-        writeGameStructure(getCurrentTrial());
+        if (winState === WinState.INTER_TRIAL) {
+          // Ends the interval with the next trial (the only way out of a held blank)
+          writeGameStructure(trials[(currentTrialIndex + 1) % trials.length]);
+        } else {
+          writeGameStructure(getCurrentTrial());
+          winState = WinState.PLAYING;
+        }
         inputs.reset = 1;
-        winState = WinState.PLAYING;
         handled = true;
         break;
       case "KeyB":
@@ -1218,7 +1231,6 @@ function gameLoop() {
   if (appState === "GAME" && running) {
     const hasWon = readGameHasWon();
     const isAnimating = readGameIsAnimating();
-    const currentFrame = readGameFrameNumber();
 
     // Win state machine (frame-based timing)
     switch (winState) {
@@ -1233,33 +1245,26 @@ function gameLoop() {
 
       case WinState.WAITING_FOR_ANIMATION_END:
         if (!isAnimating) {
-          console.log(
-            `Animation complete. Activating blank screen for ${WIN_BLANK_DURATION_FRAMES} frames`
-          );
+          // The game has started the blank inter-trial interval
+          console.log("Animation complete. Inter-trial interval started");
 
-          // Prepare next trial config
-          const nextTrialIndex = (currentTrialIndex + 1) % trials.length;
-          writeGameStructure(trials[nextTrialIndex]);
-
-          // Send commands: reset + blank_screen + stop_rendering
-          inputs.reset = 1;
-          inputs.blankScreen = 1;
-          inputs.stopRendering = 1;
+          if (readGamePostWinMode() === PostWinMode.HOLD_BLANK) {
+            console.log("Holding blank until the next reset (R)");
+          } else {
+            // Load the next trial under the blank, the game shows it once the interval is over
+            const nextTrialIndex = (currentTrialIndex + 1) % trials.length;
+            writeGameStructure(trials[nextTrialIndex]);
+            inputs.reset = 1;
+          }
 
-          blankStartFrame = currentFrame;
-          winState = WinState.BLANK_SCREEN_ACTIVE;
+          winState = WinState.INTER_TRIAL;
         }
         break;
 
-      case WinState.BLANK_SCREEN_ACTIVE:
-        const framesElapsed = currentFrame - blankStartFrame;
-
-        if (framesElapsed >= WIN_BLANK_DURATION_FRAMES) {
-          console.log(`Blank screen complete (${framesElapsed} frames). Resuming.`);
-
-          // Send commands: blank_screen (toggle off) + resume_rendering
-          inputs.blankScreen = 1;
-          inputs.resumeRendering = 1;
+      case WinState.INTER_TRIAL:
+        // The game lifts the blank itself once the interval is over (after the reset, when holding)
+        if (readGamePhase() === Phase.PLAYING) {
+          console.log("Inter-trial interval over");
 
           // Advance trial index
           currentTrialIndex = (currentTrialIndex + 1) % trials.length;
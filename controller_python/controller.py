@@ -1,5 +1,6 @@
 import sys
 import time
+import argparse
 import math
 import json
 import os
@@ -18,7 +19,35 @@ except ImportError:
 
 # ─── Constants imported from shared/src/constants.rs via monkey_shared ───
 REFRESH_RATE_HZ = monkey_shared.REFRESH_RATE_HZ
-WIN_BLANK_DURATION_FRAMES = monkey_shared.WIN_BLANK_DURATION_FRAMES
+
+# Seconds without a game heartbeat before the game counts as lost, and between reconnect attempts
+GAME_LOST_SECS = 2.0
+RECONNECT_INTERVAL_SECS = 1.0
+
+# Milliseconds a released arrow key waits before stopping latched motion: Tk's key autorepeat sends
+# release/press pairs while a key is held, and the press of the pair cancels the stop
+KEY_RELEASE_DEBOUNCE_MS = 50
+
+# Shared memory region of the game, game_node's --shm-name default
+DEFAULT_SHM_NAME = "monkey_game"
+
+# Value of an idle u32 command (set_pyramid_type, probe_face, ...) in the command readback
+IDLE_COMMAND_VALUE = 0xFFFFFFFF
+
+PHASE_NAMES = {
+    monkey_shared.PHASE_PLAYING: "playing",
+    monkey_shared.PHASE_WON: "won",
+    monkey_shared.PHASE_FAILED: "failed",
+    monkey_shared.PHASE_INTER_TRIAL: "inter-trial",
+    monkey_shared.PHASE_RESETTING: "resetting",
+    monkey_shared.PHASE_STANDBY: "standby",
+}
+
+RENDER_QUALITY_NAMES = {
+    monkey_shared.RENDER_QUALITY_LOW: "low (no MSAA, no shadows)",
+    monkey_shared.RENDER_QUALITY_MEDIUM: "medium (2x MSAA, shadows)",
+    monkey_shared.RENDER_QUALITY_HIGH: "high (4x MSAA, shadows)",
+}
 
 # UI Colors
 BG_COLOR = "#1e1e1e"
@@ -41,22 +70,158 @@ DEFAULT_CONFIG = {
     "base_radius": monkey_shared.PYRAMID_BASE_RADIUS,
     "height": monkey_shared.PYRAMID_HEIGHT,
     "start_orient": monkey_shared.PYRAMID_START_ANGLE_OFFSET_RAD,
+    # 0 = explicit geometry above, 1 = randomized: the game draws base_radius, height and start_orient
+    # within these [min, max] ranges from geometry_seed, and the applied config reports the drawn values
+    "config_mode": monkey_shared.CONFIG_MODE,
+    "geometry_seed": monkey_shared.GEOMETRY_SEED,
+    "base_radius_range": list(monkey_shared.PYRAMID_BASE_RADIUS_RANGE),
+    "height_range": list(monkey_shared.PYRAMID_HEIGHT_RANGE),
+    "start_orient_range": list(monkey_shared.PYRAMID_START_ORIENT_RANGE),
     "target_door": monkey_shared.PYRAMID_TARGET_DOOR_INDEX,
+    # PyramidType code: 0 = Type1, 1 = Type2, 2 = Type3 (same color, target by decoration)
+    "pyramid_type": monkey_shared.PYRAMID_TYPE,
+    # Alignment target: 0 = door (target_door), 1 = face (target_face)
+    "target_kind": monkey_shared.TARGET_KIND,
+    "target_face": monkey_shared.PYRAMID_TARGET_FACE_INDEX,
     "colors": DEFAULT_COLORS,
+    # Color space of all config colors: 0 = sRGB (as picked in color tools), 1 = linear (e.g. calibrated)
+    "color_space": monkey_shared.COLOR_SPACE,
     # Per-face arrays [3] matching Rust SharedGameStructure
     "decorations_count": list(monkey_shared.PYRAMID_DECORATIONS_COUNT),
     "decorations_size": list(monkey_shared.PYRAMID_DECORATIONS_SIZE),
+    # Optional per-face [min, max] count drawn from the face seed (max 0 = fixed decorations_count)
+    "decorations_count_range": [list(r) for r in monkey_shared.PYRAMID_DECORATIONS_COUNT_RANGE],
+    # Multiplier of the decoration spacing (edge margin, center distance), > 1 = looser packing
+    "decoration_spacing": monkey_shared.PYRAMID_DECORATIONS_SPACING,
+    # Decorations spin on their faces at this rate, radians per second (0 = static)
+    "decoration_spin_rate": monkey_shared.PYRAMID_DECORATION_SPIN_RATE,
+    # DECORATION_MOTION_NONE, DECORATION_MOTION_SPIN (at decoration_spin_rate) or DECORATION_MOTION_PULSE
+    "decoration_motion": monkey_shared.PYRAMID_DECORATION_MOTION,
+    # Pulse size swing as a fraction of the decoration size, and its frequency in Hz
+    "decoration_pulse_amplitude": monkey_shared.PYRAMID_DECORATION_PULSE_AMPLITUDE,
+    "decoration_pulse_hz": monkey_shared.PYRAMID_DECORATION_PULSE_HZ,
+    # Mirror every face's decorations about its vertical axis, so their layout carries no left/right cue
+    "decoration_symmetry": monkey_shared.PYRAMID_DECORATION_SYMMETRY,
+    # RGBA of each face's decorations, for counterbalancing; a face with alpha 0 draws its color from
+    # its seed, and the applied config reports the color drawn
+    "decoration_colors": [list(color) for color in monkey_shared.PYRAMID_DECORATION_COLORS],
+    # Largest offset of each RGB channel of a decoration from its face's color, drawn per decoration
+    # from the face seed (0 = one color per face)
+    "decoration_color_jitter": monkey_shared.PYRAMID_DECORATION_COLOR_JITTER,
+    # Border along the edges of every face, in world units (0 = none), and its RGBA color
+    "outline_width": monkey_shared.PYRAMID_OUTLINE_WIDTH,
+    "outline_color": list(monkey_shared.PYRAMID_OUTLINE_COLOR),
+    # Door geometry (validated by the shared library)
+    "door_hole_radius_fraction": monkey_shared.DOOR_HOLE_RADIUS_FRACTION,
+    "door_frame_thickness": monkey_shared.DOOR_FRAME_THICKNESS,
+    "door_inset": monkey_shared.DOOR_INSET,
+    # Reward weight of a win at each door, echoed as reward_weight so the reward can be scaled by door
+    "door_reward_weights": list(monkey_shared.DOOR_REWARD_WEIGHTS),
     "cosine_alignment_threshold": monkey_shared.COSINE_ALIGNMENT_TO_WIN,
     "door_anim_fade_out": monkey_shared.DOOR_ANIM_FADE_OUT,
     "door_anim_stay_open": monkey_shared.DOOR_ANIM_STAY_OPEN,
     "door_anim_fade_in": monkey_shared.DOOR_ANIM_FADE_IN,
+    # Glow curve within the fades: EASING_LINEAR, EASING_EASE_IN_OUT or EASING_EXPONENTIAL
+    "door_anim_easing": monkey_shared.DOOR_ANIM_EASING,
     "main_spotlight_intensity": monkey_shared.SPOTLIGHT_LIGHT_INTENSITY,
     "max_spotlight_intensity": monkey_shared.MAX_SPOTLIGHT_INTENSITY,
     "ambient_brightness": monkey_shared.GLOBAL_AMBIENT_LIGHT_INTENSITY,
+    # Main spotlight cone (outer angle in radians, at most pi/2)
+    "spotlight_outer_angle": monkey_shared.SPOTLIGHT_OUTER_ANGLE,
+    "spotlight_range": monkey_shared.SPOTLIGHT_RANGE,
+    # Winning door glow RGBA, all 0 = the spotlight's own color
+    "win_glow_color": list(monkey_shared.WIN_GLOW_COLOR),
+    # Linear distance fog on the scene camera (RGBA, start < end in world units)
+    "fog_enabled": monkey_shared.FOG_ENABLED,
+    "fog_color": list(monkey_shared.FOG_COLOR),
+    "fog_start": monkey_shared.FOG_START,
+    "fog_end": monkey_shared.FOG_END,
+    # Per-face luminance flicker tagging (0 Hz = off)
+    "flicker_hz": list(monkey_shared.PYRAMID_FLICKER_HZ),
+    "flicker_depth": list(monkey_shared.PYRAMID_FLICKER_DEPTH),
+    # Shaping: auto-win after dwelling near the target door (0 = explicit check only)
+    "shaping_dwell_secs": monkey_shared.SHAPING_DWELL_SECS,
+    # Catch trials (no target, never won) must end via max_attempts or trial_timeout_secs (0 = unlimited)
+    "is_catch": monkey_shared.IS_CATCH,
+    "max_attempts": monkey_shared.MAX_ATTEMPTS,
+    "trial_timeout_secs": monkey_shared.TRIAL_TIMEOUT_SECS,
+    # Checks while the screen is blanked count as rejected_checks instead of attempts
+    "suppress_blanked_attempts": monkey_shared.SUPPRESS_BLANKED_ATTEMPTS,
+    # Orbit radius band a check must be made from to win (max 0 = no radius requirement)
+    "win_radius_min": monkey_shared.WIN_RADIUS_MIN,
+    "win_radius_max": monkey_shared.WIN_RADIUS_MAX,
+    # Direction the pyramid must be turning in at the check to win, from its recent yaw
+    # (-1 decreasing, as the left arrow turns it; 1 increasing; 0 = any, also still)
+    "required_approach_sign": monkey_shared.REQUIRED_APPROACH_SIGN,
+    # Seconds after the reset before a check can win (0 = from the start)
+    "min_view_secs": monkey_shared.MIN_VIEW_SECS,
+    # Blank gap between a check and its feedback (door animation, score bar), in ms (0 = immediate)
+    "feedback_delay_ms": monkey_shared.FEEDBACK_DELAY_MS,
+    # Frames after an accepted [check, reset, blank] during which the game ignores repeats, for
+    # bouncing levers (0 = accept every activation)
+    "debounce_frames": list(monkey_shared.DEBOUNCE_FRAMES),
+    # Inter-trial interval run by the game after a won or failed trial. Without a reset or queued
+    # next trial by its end, mode 0 returns to playing and mode 1 holds the blank until reset
+    "iti_frames": monkey_shared.ITI_FRAMES,
+    "post_win_mode": monkey_shared.POST_WIN_MODE,
+    # What pausing hides: 0 = the camera (blank), 1 = the game entities only, 2 = nothing (frozen)
+    "pause_mode": monkey_shared.PAUSE_MODE,
+    # Overlay UI scale, 0 = scale with the viewport
+    "ui_scale": monkey_shared.UI_SCALE,
+    # Score bar at the top or bottom edge, its size and an optional alignment percentage
+    "score_bar_anchor": monkey_shared.SCORE_BAR_ANCHOR,
+    "score_bar_width_percent": monkey_shared.SCORE_BAR_WIDTH_PERCENT,
+    "score_bar_height": monkey_shared.SCORE_BAR_HEIGHT,
+    "score_bar_show_numeric": monkey_shared.SCORE_BAR_SHOW_NUMERIC,
+    # Aiming reticle (style: 0 = dot, 1 = cross, 2 = circle)
+    "reticle_enabled": monkey_shared.RETICLE_ENABLED,
+    "reticle_size": monkey_shared.RETICLE_SIZE,
+    "reticle_color": list(monkey_shared.RETICLE_COLOR),
+    "reticle_style": monkey_shared.RETICLE_STYLE,
+    "reticle_alignment_tint": monkey_shared.RETICLE_ALIGNMENT_TINT,
+    # Always-on hot/cold bar tracking the live target alignment (gain > 0, smoothing in [0, 1))
+    "live_feedback_enabled": monkey_shared.LIVE_FEEDBACK_ENABLED,
+    "live_feedback_gain": monkey_shared.LIVE_FEEDBACK_GAIN,
+    "live_feedback_smoothing": monkey_shared.LIVE_FEEDBACK_SMOOTHING,
+    # Inverted control mappings (applied by the game at reset)
+    "control_mirror_rotation": monkey_shared.CONTROL_MIRROR_ROTATION,
+    "control_mirror_zoom": monkey_shared.CONTROL_MIRROR_ZOOM,
+    # Mirror-image presentation, flipped left to right (logged yaws stay unmirrored)
+    "mirror_x": monkey_shared.MIRROR_X,
+    # Camera and pyramid yaw back at the trial's start on reset; off, they carry over between trials
+    "recenter_on_reset": monkey_shared.RECENTER_ON_RESET,
+    # Perspective projection (0 = engine default), FOV in degrees within CAMERA_3D_FOV_RANGE_DEGREES
+    "camera_fov_degrees": monkey_shared.CAMERA_3D_FOV_DEGREES,
+    "camera_near": monkey_shared.CAMERA_3D_NEAR,
+    "camera_far": monkey_shared.CAMERA_3D_FAR,
+    # 0 = perspective, 1 = orthographic (zoom scales the projection within ortho_scale_range)
+    "projection_mode": monkey_shared.CAMERA_3D_PROJECTION_MODE,
+    "ortho_scale_range": list(monkey_shared.CAMERA_3D_ORTHO_SCALE_RANGE),
+    # Ground distractors (shape: 0 = cube, 1 = sphere, 2 = pyramid, 3 = mixed)
+    "distractor_count": monkey_shared.DISTRACTOR_COUNT,
+    "distractor_shape": monkey_shared.DISTRACTOR_SHAPE,
+    "distractor_size_range": list(monkey_shared.DISTRACTOR_SIZE_RANGE),
+    "distractor_color": list(monkey_shared.DISTRACTOR_COLOR),
+    "distractor_ring": list(monkey_shared.DISTRACTOR_RING),
+    "distractor_seed": monkey_shared.DISTRACTOR_SEED,
+    # Curved backdrop wall behind the pyramid (False for an empty background)
+    "backdrop_enabled": monkey_shared.BACKDROP_ENABLED,
+    # Archived stimulus description to replay instead of the seeds (None for a procedural pyramid),
+    # and the hash it must have (0 to skip the check)
+    "stimulus_file": None,
+    "stimulus_expected_hash": 0,
+    # Unique ID of the trial (None for none); the game echoes its hash as trial_id_hash and logs it,
+    # the results log keeps both
+    "trial_id": None,
 }
 
+# JSON Schema of the trial fields for trial_schema, from the game: the types the config is stored as,
+# with the enum codes and ranges it is checked against (see json_api::config_field_schemas)
+TRIAL_FIELD_SCHEMAS = json.loads(monkey_shared.config_field_schemas())
+
 DEFAULT_STATE = {
-    "phase": 0,
+    # A fresh game stands by until the first reset loads a trial
+    "phase": monkey_shared.PHASE_STANDBY,
     "frame_number": 0,
     "elapsed_secs": 0.0,
     "camera_radius": 0.0,
@@ -65,8 +230,27 @@ DEFAULT_STATE = {
     "nr_attempts": 0,
     "cosine_alignment": None,
     "is_animating": False,
+    "inputs_locked": False,
+    # Direction the pyramid is turning in (-1, 0, 1) and whether the last check missed the required one
+    "approach_sign": 0,
+    "win_blocked_by_approach": False,
+    # Trigger activations the game ignored inside their debounce window, over the session
+    "debounced_count": 0,
+    # Checks the game refused in standby, before the first trial was loaded
+    "standby_checks": 0,
+    # Commands the game read set on its latest frame, and per command the frames it was
+    "commands_seen": [],
+    "commands_seen_counts": {},
+    # Session context written by this controller and echoed by the game
+    "current_trial_index": 0,
+    "total_trials": 0,
+    "block_index": 0,
+    "session_id": 0,
+    "subject_id": 0,
     "has_won": False,
     "win_elapsed_secs": None,
+    "reward_weight": 0.0,
+    "render_quality": monkey_shared.RENDER_QUALITY_HIGH,
     # Config part of the structure (read back)
     "decoration_seeds": [0, 0, 0],
     "base_radius": 0.0,
@@ -75,8 +259,184 @@ DEFAULT_STATE = {
     "target_door": 0,
 }
 
-def load_trials(trials_path="trials.jsonl"):
-    """Load trials from JSONL file."""
+def sample_face_colors(palette, seed, pyramid_type=monkey_shared.PYRAMID_TYPE_1):
+    """Sample the 3 face colors from a palette, seeded (see monkey_shared.sample_face_colors).
+    Type2 (two faces same color) samples two colors and duplicates the first onto the third face,
+    Type3 uses a single color for all faces."""
+    return [list(color) for color in monkey_shared.sample_face_colors(palette, seed, pyramid_type)]
+
+
+def load_color_palette(palette_path):
+    """Load a color palette (JSON list of RGBA lists). Returns None to keep the default colors."""
+    if not palette_path:
+        return None
+    try:
+        with open(palette_path, 'r') as f:
+            palette = json.load(f)
+        if not palette or any(len(c) != 4 for c in palette):
+            raise ValueError("expected a non-empty list of [r, g, b, a] colors")
+        print(f"Loaded color palette with {len(palette)} colors from {palette_path}")
+        return palette
+    except Exception as e:
+        print(f"Failed to load color palette: {e}. Using default colors.")
+        return None
+
+
+def generated_palette(kind, size=monkey_shared.PALETTE_SIZE, luma=monkey_shared.PALETTE_LUMA, seed=0):
+    """Color palette generated by monkey_shared at Rec.709 luma `luma`: "equiluminant" spreads `size` colors
+    evenly around the hue wheel from a seeded start, "deuteranopia" is the deuteranopia-safe preset."""
+    if kind == "deuteranopia":
+        palette = [list(color) for color in monkey_shared.deuteranopia_safe_palette(luma)]
+    else:
+        palette = [list(color) for color in monkey_shared.generate_palette(size, luma, seed=seed)]
+    lumas = ", ".join(f"{monkey_shared.rec709_luma(color):.3f}" for color in palette)
+    print(f"Generated {kind} palette with {len(palette)} colors (Rec.709 luma {lumas})")
+    return palette
+
+
+def trial_from_json(t, color_palette=None):
+    """One trial dict from a trials file entry, filling missing fields from DEFAULT_CONFIG.
+    Trials without colors sample them from color_palette (seeded by color_seed, default the first
+    decoration seed), or fall back to the default colors when no palette is given."""
+    if "colors" in t:
+        colors = t["colors"]
+    elif color_palette:
+        seeds = t.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"])
+        colors = sample_face_colors(color_palette, t.get("color_seed", seeds[0]),
+                                    t.get("pyramid_type", monkey_shared.PYRAMID_TYPE_1))
+    else:
+        colors = DEFAULT_CONFIG["colors"]
+    # Randomized trials draw their geometry in the game, so the explicit values may be left out
+    config_mode = t.get("config_mode", DEFAULT_CONFIG["config_mode"])
+    explicit = config_mode == monkey_shared.CONFIG_MODE_EXPLICIT
+    geometry = lambda key: t[key] if explicit else t.get(key, DEFAULT_CONFIG[key])
+    return {
+        "decoration_seeds": t.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"]),
+        "base_radius": geometry("base_radius"),
+        "height": geometry("height"),
+        "start_orient": geometry("start_orient"),
+        "config_mode": config_mode,
+        "geometry_seed": t.get("geometry_seed", DEFAULT_CONFIG["geometry_seed"]),
+        "base_radius_range": t.get("base_radius_range", DEFAULT_CONFIG["base_radius_range"]),
+        "height_range": t.get("height_range", DEFAULT_CONFIG["height_range"]),
+        "start_orient_range": t.get("start_orient_range", DEFAULT_CONFIG["start_orient_range"]),
+        "target_door": t["target_door"],
+        "pyramid_type": t.get("pyramid_type", DEFAULT_CONFIG["pyramid_type"]),
+        "target_kind": t.get("target_kind", DEFAULT_CONFIG["target_kind"]),
+        "target_face": t.get("target_face", DEFAULT_CONFIG["target_face"]),
+        "colors": colors,
+        "decorations_count": t.get("decorations_count", DEFAULT_CONFIG["decorations_count"]),
+        "decorations_size": t.get("decorations_size", DEFAULT_CONFIG["decorations_size"]),
+        "decorations_count_range": t.get("decorations_count_range", DEFAULT_CONFIG["decorations_count_range"]),
+        "decoration_spacing": t.get("decoration_spacing", DEFAULT_CONFIG["decoration_spacing"]),
+        "decoration_spin_rate": t.get("decoration_spin_rate", DEFAULT_CONFIG["decoration_spin_rate"]),
+        "decoration_motion": t.get("decoration_motion", DEFAULT_CONFIG["decoration_motion"]),
+        "decoration_pulse_amplitude": t.get("decoration_pulse_amplitude", DEFAULT_CONFIG["decoration_pulse_amplitude"]),
+        "decoration_pulse_hz": t.get("decoration_pulse_hz", DEFAULT_CONFIG["decoration_pulse_hz"]),
+        "decoration_symmetry": t.get("decoration_symmetry", DEFAULT_CONFIG["decoration_symmetry"]),
+        "decoration_colors": t.get("decoration_colors", DEFAULT_CONFIG["decoration_colors"]),
+        "decoration_color_jitter": t.get("decoration_color_jitter", DEFAULT_CONFIG["decoration_color_jitter"]),
+        "outline_width": t.get("outline_width", DEFAULT_CONFIG["outline_width"]),
+        "outline_color": t.get("outline_color", DEFAULT_CONFIG["outline_color"]),
+        "door_hole_radius_fraction": t.get("door_hole_radius_fraction", DEFAULT_CONFIG["door_hole_radius_fraction"]),
+        "door_frame_thickness": t.get("door_frame_thickness", DEFAULT_CONFIG["door_frame_thickness"]),
+        "door_inset": t.get("door_inset", DEFAULT_CONFIG["door_inset"]),
+        "door_reward_weights": t.get("door_reward_weights", DEFAULT_CONFIG["door_reward_weights"]),
+        "cosine_alignment_threshold": t.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
+        "door_anim_fade_out": t.get("door_anim_fade_out", DEFAULT_CONFIG["door_anim_fade_out"]),
+        "door_anim_stay_open": t.get("door_anim_stay_open", DEFAULT_CONFIG["door_anim_stay_open"]),
+        "door_anim_fade_in": t.get("door_anim_fade_in", DEFAULT_CONFIG["door_anim_fade_in"]),
+        "door_anim_easing": t.get("door_anim_easing", DEFAULT_CONFIG["door_anim_easing"]),
+        "main_spotlight_intensity": t.get("main_spotlight_intensity", DEFAULT_CONFIG["main_spotlight_intensity"]),
+        "max_spotlight_intensity": t.get("max_spotlight_intensity", DEFAULT_CONFIG["max_spotlight_intensity"]),
+        "ambient_brightness": t.get("ambient_brightness", DEFAULT_CONFIG["ambient_brightness"]),
+        "spotlight_outer_angle": t.get("spotlight_outer_angle", DEFAULT_CONFIG["spotlight_outer_angle"]),
+        "spotlight_range": t.get("spotlight_range", DEFAULT_CONFIG["spotlight_range"]),
+        "win_glow_color": t.get("win_glow_color", DEFAULT_CONFIG["win_glow_color"]),
+        "fog_enabled": t.get("fog_enabled", DEFAULT_CONFIG["fog_enabled"]),
+        "fog_color": t.get("fog_color", DEFAULT_CONFIG["fog_color"]),
+        "fog_start": t.get("fog_start", DEFAULT_CONFIG["fog_start"]),
+        "fog_end": t.get("fog_end", DEFAULT_CONFIG["fog_end"]),
+        "flicker_hz": t.get("flicker_hz", DEFAULT_CONFIG["flicker_hz"]),
+        "flicker_depth": t.get("flicker_depth", DEFAULT_CONFIG["flicker_depth"]),
+        "shaping_dwell_secs": t.get("shaping_dwell_secs", DEFAULT_CONFIG["shaping_dwell_secs"]),
+        "is_catch": t.get("is_catch", DEFAULT_CONFIG["is_catch"]),
+        "max_attempts": t.get("max_attempts", DEFAULT_CONFIG["max_attempts"]),
+        "trial_timeout_secs": t.get("trial_timeout_secs", DEFAULT_CONFIG["trial_timeout_secs"]),
+        "suppress_blanked_attempts": t.get("suppress_blanked_attempts", DEFAULT_CONFIG["suppress_blanked_attempts"]),
+        "win_radius_min": t.get("win_radius_min", DEFAULT_CONFIG["win_radius_min"]),
+        "win_radius_max": t.get("win_radius_max", DEFAULT_CONFIG["win_radius_max"]),
+        "required_approach_sign": t.get("required_approach_sign", DEFAULT_CONFIG["required_approach_sign"]),
+        "min_view_secs": t.get("min_view_secs", DEFAULT_CONFIG["min_view_secs"]),
+        "feedback_delay_ms": t.get("feedback_delay_ms", DEFAULT_CONFIG["feedback_delay_ms"]),
+        "debounce_frames": t.get("debounce_frames", DEFAULT_CONFIG["debounce_frames"]),
+        "iti_frames": t.get("iti_frames", DEFAULT_CONFIG["iti_frames"]),
+        "post_win_mode": t.get("post_win_mode", DEFAULT_CONFIG["post_win_mode"]),
+        "pause_mode": t.get("pause_mode", DEFAULT_CONFIG["pause_mode"]),
+        "ui_scale": t.get("ui_scale", DEFAULT_CONFIG["ui_scale"]),
+        "score_bar_anchor": t.get("score_bar_anchor", DEFAULT_CONFIG["score_bar_anchor"]),
+        "score_bar_width_percent": t.get("score_bar_width_percent", DEFAULT_CONFIG["score_bar_width_percent"]),
+        "score_bar_height": t.get("score_bar_height", DEFAULT_CONFIG["score_bar_height"]),
+        "score_bar_show_numeric": t.get("score_bar_show_numeric", DEFAULT_CONFIG["score_bar_show_numeric"]),
+        "reticle_enabled": t.get("reticle_enabled", DEFAULT_CONFIG["reticle_enabled"]),
+        "reticle_size": t.get("reticle_size", DEFAULT_CONFIG["reticle_size"]),
+        "reticle_color": t.get("reticle_color", DEFAULT_CONFIG["reticle_color"]),
+        "reticle_style": t.get("reticle_style", DEFAULT_CONFIG["reticle_style"]),
+        "reticle_alignment_tint": t.get("reticle_alignment_tint", DEFAULT_CONFIG["reticle_alignment_tint"]),
+        "live_feedback_enabled": t.get("live_feedback_enabled", DEFAULT_CONFIG["live_feedback_enabled"]),
+        "live_feedback_gain": t.get("live_feedback_gain", DEFAULT_CONFIG["live_feedback_gain"]),
+        "live_feedback_smoothing": t.get("live_feedback_smoothing", DEFAULT_CONFIG["live_feedback_smoothing"]),
+        "control_mirror_rotation": t.get("control_mirror_rotation", DEFAULT_CONFIG["control_mirror_rotation"]),
+        "control_mirror_zoom": t.get("control_mirror_zoom", DEFAULT_CONFIG["control_mirror_zoom"]),
+        "mirror_x": t.get("mirror_x", DEFAULT_CONFIG["mirror_x"]),
+        "recenter_on_reset": t.get("recenter_on_reset", DEFAULT_CONFIG["recenter_on_reset"]),
+        "camera_fov_degrees": t.get("camera_fov_degrees", DEFAULT_CONFIG["camera_fov_degrees"]),
+        "camera_near": t.get("camera_near", DEFAULT_CONFIG["camera_near"]),
+        "camera_far": t.get("camera_far", DEFAULT_CONFIG["camera_far"]),
+        "projection_mode": t.get("projection_mode", DEFAULT_CONFIG["projection_mode"]),
+        "ortho_scale_range": t.get("ortho_scale_range", DEFAULT_CONFIG["ortho_scale_range"]),
+        "color_space": t.get("color_space", DEFAULT_CONFIG["color_space"]),
+        "distractor_count": t.get("distractor_count", DEFAULT_CONFIG["distractor_count"]),
+        "distractor_shape": t.get("distractor_shape", DEFAULT_CONFIG["distractor_shape"]),
+        "distractor_size_range": t.get("distractor_size_range", DEFAULT_CONFIG["distractor_size_range"]),
+        "distractor_color": t.get("distractor_color", DEFAULT_CONFIG["distractor_color"]),
+        "distractor_ring": t.get("distractor_ring", DEFAULT_CONFIG["distractor_ring"]),
+        "distractor_seed": t.get("distractor_seed", DEFAULT_CONFIG["distractor_seed"]),
+        "backdrop_enabled": t.get("backdrop_enabled", DEFAULT_CONFIG["backdrop_enabled"]),
+        "stimulus_file": t.get("stimulus_file", DEFAULT_CONFIG["stimulus_file"]),
+        "stimulus_expected_hash": t.get("stimulus_expected_hash", DEFAULT_CONFIG["stimulus_expected_hash"]),
+        "trial_id": t.get("trial_id", DEFAULT_CONFIG["trial_id"]),
+    }
+
+
+def trial_schema():
+    """JSON Schema of a trials file entry, derived from what trial_from_json accepts: the fields and
+    defaults of DEFAULT_CONFIG, required when trial_from_json fails without them, with TRIAL_FIELD_SCHEMAS."""
+    properties = {}
+    required = []
+    for key, default in trial_from_json(DEFAULT_CONFIG).items():
+        try:
+            trial_from_json({k: v for k, v in DEFAULT_CONFIG.items() if k != key})
+        except KeyError:
+            required.append(key)
+        properties[key] = {**TRIAL_FIELD_SCHEMAS.get(key, {}), "default": default}
+    # Only read when sampling colors from a palette
+    properties["color_seed"] = {"type": "integer", "minimum": 0, "default": DEFAULT_CONFIG["decoration_seeds"][0]}
+    return {
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Monkey 3D Game trial",
+        "description": "One line of a trials JSONL file; missing fields take the defaults",
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        # trial_from_json ignores unknown keys
+        "additionalProperties": True,
+    }
+
+
+def load_trials(trials_path="trials.jsonl", color_palette=None, tutorial_seed=None, tutorial_decoration_seed=None):
+    """Load trials from JSONL file (see trial_from_json).
+    When the file is missing and tutorial_seed is given, the built-in tutorial sequence is used."""
     trials = []
     # Try relative to script directory first
     script_dir = os.path.dirname(os.path.abspath(__file__))
@@ -87,29 +447,15 @@ def load_trials(trials_path="trials.jsonl"):
         # Fallback to current directory
         trial_file = trials_path
 
+    if tutorial_seed is not None and not os.path.exists(trial_file):
+        return tutorial_trials(tutorial_seed, tutorial_decoration_seed)
+
     try:
         with open(trial_file, 'r') as f:
             for line in f:
                 line = line.strip()
                 if line:
-                    t = json.loads(line)
-                    trials.append({
-                        "decoration_seeds": t.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"]),
-                        "base_radius": t["base_radius"],
-                        "height": t["height"],
-                        "start_orient": t["start_orient"],
-                        "target_door": t["target_door"],
-                        "colors": t["colors"],
-                        "decorations_count": t.get("decorations_count", DEFAULT_CONFIG["decorations_count"]),
-                        "decorations_size": t.get("decorations_size", DEFAULT_CONFIG["decorations_size"]),
-                        "cosine_alignment_threshold": t.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
-                        "door_anim_fade_out": t.get("door_anim_fade_out", DEFAULT_CONFIG["door_anim_fade_out"]),
-                        "door_anim_stay_open": t.get("door_anim_stay_open", DEFAULT_CONFIG["door_anim_stay_open"]),
-                        "door_anim_fade_in": t.get("door_anim_fade_in", DEFAULT_CONFIG["door_anim_fade_in"]),
-                        "main_spotlight_intensity": t.get("main_spotlight_intensity", DEFAULT_CONFIG["main_spotlight_intensity"]),
-                        "max_spotlight_intensity": t.get("max_spotlight_intensity", DEFAULT_CONFIG["max_spotlight_intensity"]),
-                        "ambient_brightness": t.get("ambient_brightness", DEFAULT_CONFIG["ambient_brightness"]),
-                    })
+                    trials.append(trial_from_json(json.loads(line), color_palette))
         print(f"Loaded {len(trials)} trials from {trial_file}")
     except Exception as e:
         print(f"Failed to load trials: {e}. Using DEFAULT_CONFIG.")
@@ -117,19 +463,42 @@ def load_trials(trials_path="trials.jsonl"):
     return trials
 
 
+def tutorial_trials(seed, decoration_seed=None):
+    """Built-in easy-to-hard ramp for new animals, deterministic from the seeds: `seed` draws the
+    geometry (start orientation, target door), `decoration_seed` the face decorations. Without a
+    decoration seed, `seed` draws both, as a single seed always did."""
+    trials = [{**DEFAULT_CONFIG, **t} for t in monkey_shared.generate_tutorial_trials(seed, decoration_seed)]
+    if decoration_seed is None:
+        print(f"Generated {len(trials)} tutorial trials from seed {seed}")
+    else:
+        print(f"Generated {len(trials)} tutorial trials from geometry seed {seed}, decoration seed {decoration_seed}")
+    return trials
+
+
 class SharedMemory:
-    def __init__(self):
+    def __init__(self, name=DEFAULT_SHM_NAME):
+        self.name = name
         self.inner = None
         self.connect()
 
     def connect(self):
         try:
-            self.inner = monkey_shared.SharedMemoryWrapper("monkey_game")
+            self.inner = monkey_shared.SharedMemoryWrapper(self.name)
             print("Connected to shared memory interface.")
         except Exception as exc:
             print(f"SHM Connection Error: {exc}")
             self.inner = None
 
+    def reopen(self):
+        """Map the game's segment afresh without resetting it, e.g. after the game restarted and recreated
+        it. Returns True on success, keeping the old mapping otherwise."""
+        try:
+            self.inner = monkey_shared.SharedMemoryWrapper.open(self.name)
+            return True
+        except Exception as exc:
+            print(f"SHM Reopen Error: {exc}")
+            return False
+
     def read_game_state(self):
         if not self.inner:
             self.connect()
@@ -146,7 +515,18 @@ class SharedMemory:
             self.inner = None
             return DEFAULT_STATE.copy()
 
-    def write_commands(self, rotate_left, rotate_right, zoom_in, zoom_out, check, reset, blank_screen=False, stop_rendering=False, resume_rendering=False, animation_door=False):
+    def read_commands(self):
+        """Command readback: {"raw", "seen", "seen_mask", "counts"} (see SharedMemoryWrapper.read_commands),
+        None when not connected."""
+        if not self.inner:
+            return None
+        try:
+            return self.inner.read_commands()
+        except Exception as exc:
+            print(f"SHM Read Error: {exc}")
+            return None
+
+    def write_commands(self, rotate_left, rotate_right, zoom_in, zoom_out, check, reset, blank_screen=False, stop_rendering=False, resume_rendering=False, animation_door=False, step_frame=False, debug_normals=False, dump_config=False, clear_attempts=False, queue_next=False, set_pyramid_type=None, promote_staged=False, log_categories=None, show_threshold_cone=False, dump_geometry=False):
         if not self.inner:
             self.connect()
             if not self.inner:
@@ -162,22 +542,216 @@ class SharedMemory:
                 bool(blank_screen),
                 bool(stop_rendering),
                 bool(resume_rendering),
-                bool(animation_door)
+                bool(animation_door),
+                step_frame=bool(step_frame),
+                debug_normals=bool(debug_normals),
+                dump_config=bool(dump_config),
+                clear_attempts=bool(clear_attempts),
+                queue_next=bool(queue_next),
+                set_pyramid_type=None if set_pyramid_type is None else int(set_pyramid_type),
+                promote_staged=bool(promote_staged),
+                log_categories=None if log_categories is None else int(log_categories),
+                show_threshold_cone=bool(show_threshold_cone),
+                dump_geometry=bool(dump_geometry),
             )
         except Exception as exc:
             print(f"SHM Write Error: {exc}")
             self.inner = None
 
+    def next_frame(self):
+        """(frame, seconds until it is readable) for the next game frame, from the frames read so far,
+        or None before any."""
+        if not self.inner:
+            return None
+        return self.inner.next_frame()
+
+    def seconds_until_frame(self, frame):
+        """Seconds until the game frame `frame` is readable (negative once past), or None before any frame was read."""
+        if not self.inner:
+            return None
+        return self.inner.seconds_until_frame(frame)
+
+    def latched_motion(self, method, *args):
+        """Call one of the latched motion helpers (set_motion_latched, start_rotation, stop_rotation,
+        start_zoom, stop_zoom) on the SHM wrapper."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            getattr(self.inner, method)(*args)
+        except Exception as exc:
+            print(f"SHM Motion Error: {exc}")
+            self.inner = None
+
+    def set_controller_watchdog(self, timeout_frames, blank=False):
+        """Have the game stop the camera (and with `blank`, blank the screen) after `timeout_frames`
+        frames without a command write from this controller, 0 to disarm it."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            self.inner.set_controller_watchdog(int(timeout_frames), bool(blank))
+        except Exception as exc:
+            print(f"SHM Watchdog Error: {exc}")
+            self.inner = None
+
+    def write_session_info(self, current_trial_index, total_trials, block_index=0, session_id=0, subject_id=0):
+        """Write the session context the game echoes into its logs and HUD (see SharedMemoryWrapper.write_session_info)."""
+        if not self.inner:
+            return
+        try:
+            self.inner.write_session_info(current_trial_index, total_trials, block_index, session_id, subject_id)
+        except Exception as exc:
+            print(f"SHM Session Info Error: {exc}")
+            self.inner = None
+
+    def set_global_yaw_offset(self, radians):
+        """Turn the game's pyramid by `radians` on top of the controls, to line it up with a rotated display."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            self.inner.set_global_yaw_offset(float(radians))
+        except Exception as exc:
+            print(f"SHM Yaw Offset Error: {exc}")
+            self.inner = None
+
+    def probe_decorations(self, face, color, duration_secs=monkey_shared.PROBE_DURATION_SECS):
+        """Recolor the decorations of `face` with `color` (RGBA) for `duration_secs`, then revert them."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            self.inner.probe_decorations(int(face), [float(c) for c in color], float(duration_secs))
+        except ValueError as exc:
+            print(f"Invalid probe: {exc}")
+        except Exception as exc:
+            print(f"SHM Probe Error: {exc}")
+            self.inner = None
+
+    def set_render_quality(self, level):
+        """Switch the game's MSAA and shadows to RENDER_QUALITY_LOW, _MEDIUM or _HIGH, live."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            self.inner.set_render_quality(int(level))
+        except ValueError as exc:
+            print(f"Invalid render quality: {exc}")
+
+    def show_reference_image(self, index):
+        """Show reference image `index` full screen (None hides it); the next reset hides it too."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return
+        try:
+            if index is None:
+                self.inner.hide_reference_image()
+            else:
+                self.inner.show_reference_image(int(index))
+        except ValueError as exc:
+            print(f"Invalid reference image: {exc}")
+        except Exception as exc:
+            print(f"SHM Reference Image Error: {exc}")
+            self.inner = None
+
     def write_reset_config(self, decoration_seeds, base_radius, height, start_orient, target_door, colors,
                            decorations_count, decorations_size,
                            cosine_alignment_threshold,
                            door_anim_fade_out, door_anim_stay_open, door_anim_fade_in,
-                           main_spotlight_intensity, max_spotlight_intensity, ambient_brightness):
-        """Write config to shared memory. decorations_count: [u32;3], decorations_size: [f32;3]."""
+                           main_spotlight_intensity, max_spotlight_intensity, ambient_brightness,
+                           flicker_hz=DEFAULT_CONFIG["flicker_hz"], flicker_depth=DEFAULT_CONFIG["flicker_depth"],
+                           decorations_count_range=DEFAULT_CONFIG["decorations_count_range"],
+                           decoration_spacing=DEFAULT_CONFIG["decoration_spacing"],
+                           decoration_spin_rate=DEFAULT_CONFIG["decoration_spin_rate"],
+                           decoration_motion=DEFAULT_CONFIG["decoration_motion"],
+                           decoration_pulse_amplitude=DEFAULT_CONFIG["decoration_pulse_amplitude"],
+                           decoration_pulse_hz=DEFAULT_CONFIG["decoration_pulse_hz"],
+                           decoration_symmetry=DEFAULT_CONFIG["decoration_symmetry"],
+                           decoration_colors=DEFAULT_CONFIG["decoration_colors"],
+                           decoration_color_jitter=DEFAULT_CONFIG["decoration_color_jitter"],
+                           outline_width=DEFAULT_CONFIG["outline_width"],
+                           outline_color=DEFAULT_CONFIG["outline_color"],
+                           shaping_dwell_secs=DEFAULT_CONFIG["shaping_dwell_secs"],
+                           suppress_blanked_attempts=DEFAULT_CONFIG["suppress_blanked_attempts"],
+                           is_catch=DEFAULT_CONFIG["is_catch"], max_attempts=DEFAULT_CONFIG["max_attempts"],
+                           trial_timeout_secs=DEFAULT_CONFIG["trial_timeout_secs"],
+                           win_radius_min=DEFAULT_CONFIG["win_radius_min"],
+                           win_radius_max=DEFAULT_CONFIG["win_radius_max"],
+                           required_approach_sign=DEFAULT_CONFIG["required_approach_sign"],
+                           min_view_secs=DEFAULT_CONFIG["min_view_secs"],
+                           feedback_delay_ms=DEFAULT_CONFIG["feedback_delay_ms"],
+                           debounce_frames=DEFAULT_CONFIG["debounce_frames"],
+                           iti_frames=DEFAULT_CONFIG["iti_frames"],
+                           post_win_mode=DEFAULT_CONFIG["post_win_mode"],
+                           pause_mode=DEFAULT_CONFIG["pause_mode"],
+                           door_anim_easing=DEFAULT_CONFIG["door_anim_easing"],
+                           ui_scale=DEFAULT_CONFIG["ui_scale"],
+                           score_bar_anchor=DEFAULT_CONFIG["score_bar_anchor"],
+                           score_bar_width_percent=DEFAULT_CONFIG["score_bar_width_percent"],
+                           score_bar_height=DEFAULT_CONFIG["score_bar_height"],
+                           score_bar_show_numeric=DEFAULT_CONFIG["score_bar_show_numeric"],
+                           reticle_enabled=DEFAULT_CONFIG["reticle_enabled"], reticle_size=DEFAULT_CONFIG["reticle_size"],
+                           reticle_color=DEFAULT_CONFIG["reticle_color"], reticle_style=DEFAULT_CONFIG["reticle_style"],
+                           reticle_alignment_tint=DEFAULT_CONFIG["reticle_alignment_tint"],
+                           live_feedback_enabled=DEFAULT_CONFIG["live_feedback_enabled"],
+                           live_feedback_gain=DEFAULT_CONFIG["live_feedback_gain"],
+                           live_feedback_smoothing=DEFAULT_CONFIG["live_feedback_smoothing"],
+                           control_mirror_rotation=DEFAULT_CONFIG["control_mirror_rotation"],
+                           control_mirror_zoom=DEFAULT_CONFIG["control_mirror_zoom"],
+                           mirror_x=DEFAULT_CONFIG["mirror_x"],
+                           recenter_on_reset=DEFAULT_CONFIG["recenter_on_reset"],
+                           distractor_count=DEFAULT_CONFIG["distractor_count"],
+                           distractor_shape=DEFAULT_CONFIG["distractor_shape"],
+                           distractor_size_range=DEFAULT_CONFIG["distractor_size_range"],
+                           distractor_color=DEFAULT_CONFIG["distractor_color"],
+                           distractor_ring=DEFAULT_CONFIG["distractor_ring"],
+                           distractor_seed=DEFAULT_CONFIG["distractor_seed"],
+                           pyramid_type=DEFAULT_CONFIG["pyramid_type"],
+                           door_hole_radius_fraction=DEFAULT_CONFIG["door_hole_radius_fraction"],
+                           door_frame_thickness=DEFAULT_CONFIG["door_frame_thickness"],
+                           door_inset=DEFAULT_CONFIG["door_inset"],
+                           door_reward_weights=DEFAULT_CONFIG["door_reward_weights"],
+                           target_kind=DEFAULT_CONFIG["target_kind"],
+                           target_face=DEFAULT_CONFIG["target_face"],
+                           camera_fov_degrees=DEFAULT_CONFIG["camera_fov_degrees"],
+                           camera_near=DEFAULT_CONFIG["camera_near"],
+                           camera_far=DEFAULT_CONFIG["camera_far"],
+                           spotlight_outer_angle=DEFAULT_CONFIG["spotlight_outer_angle"],
+                           spotlight_range=DEFAULT_CONFIG["spotlight_range"],
+                           win_glow_color=DEFAULT_CONFIG["win_glow_color"],
+                           fog_enabled=DEFAULT_CONFIG["fog_enabled"],
+                           fog_color=DEFAULT_CONFIG["fog_color"],
+                           fog_start=DEFAULT_CONFIG["fog_start"],
+                           fog_end=DEFAULT_CONFIG["fog_end"],
+                           projection_mode=DEFAULT_CONFIG["projection_mode"],
+                           ortho_scale_range=DEFAULT_CONFIG["ortho_scale_range"],
+                           color_space=DEFAULT_CONFIG["color_space"],
+                           backdrop_enabled=DEFAULT_CONFIG["backdrop_enabled"],
+                           config_mode=DEFAULT_CONFIG["config_mode"],
+                           geometry_seed=DEFAULT_CONFIG["geometry_seed"],
+                           base_radius_range=DEFAULT_CONFIG["base_radius_range"],
+                           height_range=DEFAULT_CONFIG["height_range"],
+                           start_orient_range=DEFAULT_CONFIG["start_orient_range"],
+                           stimulus_file=DEFAULT_CONFIG["stimulus_file"],
+                           stimulus_expected_hash=DEFAULT_CONFIG["stimulus_expected_hash"],
+                           trial_id=DEFAULT_CONFIG["trial_id"],
+                           staged=False):
+        """Write config to shared memory. decorations_count: [u32;3], decorations_size: [f32;3].
+        With staged=True it goes to the staged block, applied once promoted (see stage_trial_config)."""
         if not self.inner:
             self.connect()
             if not self.inner:
                 return False
+        if staged and self.inner.staged_pending():
+            print("SHM Config: previous staged config not promoted yet")
+            return False
         try:
             self.inner.write_game_structure(
                 [int(x) for x in decoration_seeds],
@@ -195,6 +769,87 @@ class SharedMemory:
                 float(main_spotlight_intensity),
                 float(ambient_brightness),
                 float(max_spotlight_intensity),
+                flicker_hz=[float(x) for x in flicker_hz],
+                flicker_depth=[float(x) for x in flicker_depth],
+                decorations_count_range=[[int(x) for x in r] for r in decorations_count_range],
+                decoration_spacing=float(decoration_spacing),
+                decoration_spin_rate=float(decoration_spin_rate),
+                decoration_motion=int(decoration_motion),
+                decoration_pulse_amplitude=float(decoration_pulse_amplitude),
+                decoration_pulse_hz=float(decoration_pulse_hz),
+                decoration_symmetry=bool(decoration_symmetry),
+                decoration_colors=[[float(x) for x in color] for color in decoration_colors],
+                decoration_color_jitter=float(decoration_color_jitter),
+                outline_width=float(outline_width),
+                outline_color=[float(x) for x in outline_color],
+                shaping_dwell_secs=float(shaping_dwell_secs),
+                suppress_blanked_attempts=bool(suppress_blanked_attempts),
+                is_catch=bool(is_catch),
+                max_attempts=int(max_attempts),
+                trial_timeout_secs=float(trial_timeout_secs),
+                win_radius_min=float(win_radius_min),
+                win_radius_max=float(win_radius_max),
+                required_approach_sign=int(required_approach_sign),
+                min_view_secs=float(min_view_secs),
+                feedback_delay_ms=int(feedback_delay_ms),
+                debounce_frames=[int(x) for x in debounce_frames],
+                iti_frames=int(iti_frames),
+                post_win_mode=int(post_win_mode),
+                pause_mode=int(pause_mode),
+                door_anim_easing=int(door_anim_easing),
+                ui_scale=float(ui_scale),
+                score_bar_anchor=int(score_bar_anchor),
+                score_bar_width_percent=float(score_bar_width_percent),
+                score_bar_height=float(score_bar_height),
+                score_bar_show_numeric=bool(score_bar_show_numeric),
+                reticle_enabled=bool(reticle_enabled),
+                reticle_size=float(reticle_size),
+                reticle_color=[float(x) for x in reticle_color],
+                reticle_style=int(reticle_style),
+                reticle_alignment_tint=bool(reticle_alignment_tint),
+                live_feedback_enabled=bool(live_feedback_enabled),
+                live_feedback_gain=float(live_feedback_gain),
+                live_feedback_smoothing=float(live_feedback_smoothing),
+                control_mirror_rotation=bool(control_mirror_rotation),
+                control_mirror_zoom=bool(control_mirror_zoom),
+                mirror_x=bool(mirror_x),
+                recenter_on_reset=bool(recenter_on_reset),
+                distractor_count=int(distractor_count),
+                distractor_shape=int(distractor_shape),
+                distractor_size_range=[float(x) for x in distractor_size_range],
+                distractor_color=[float(x) for x in distractor_color],
+                distractor_ring=[float(x) for x in distractor_ring],
+                distractor_seed=int(distractor_seed),
+                pyramid_type=int(pyramid_type),
+                door_hole_radius_fraction=float(door_hole_radius_fraction),
+                door_frame_thickness=float(door_frame_thickness),
+                door_inset=float(door_inset),
+                door_reward_weights=[float(x) for x in door_reward_weights],
+                target_kind=int(target_kind),
+                target_face=int(target_face),
+                camera_fov_degrees=float(camera_fov_degrees),
+                camera_near=float(camera_near),
+                camera_far=float(camera_far),
+                spotlight_outer_angle=float(spotlight_outer_angle),
+                spotlight_range=float(spotlight_range),
+                win_glow_color=[float(x) for x in win_glow_color],
+                fog_enabled=bool(fog_enabled),
+                fog_color=[float(x) for x in fog_color],
+                fog_start=float(fog_start),
+                fog_end=float(fog_end),
+                projection_mode=int(projection_mode),
+                ortho_scale_range=[float(x) for x in ortho_scale_range],
+                color_space=int(color_space),
+                backdrop_enabled=bool(backdrop_enabled),
+                config_mode=int(config_mode),
+                geometry_seed=int(geometry_seed),
+                base_radius_range=[float(x) for x in base_radius_range],
+                height_range=[float(x) for x in height_range],
+                start_orient_range=[float(x) for x in start_orient_range],
+                stimulus_file=None if stimulus_file is None else str(stimulus_file),
+                stimulus_expected_hash=int(stimulus_expected_hash),
+                trial_id=None if trial_id is None else str(trial_id),
+                staged=bool(staged),
             )
             return True
         except Exception as exc:
@@ -202,9 +857,250 @@ class SharedMemory:
             self.inner = None
             return False
 
+    def write_trial_config(self, trial, staged=False):
+        """Write a trial dict to shared memory, filling missing fields from DEFAULT_CONFIG."""
+        return self.write_reset_config(**{k: trial.get(k, v) for k, v in DEFAULT_CONFIG.items()}, staged=staged)
+
+    def stage_trial_config(self, trial):
+        """Write a trial dict to the staged block. Send promote_staged=True (with or before the reset)
+        to make it the config of the next reset, so the game never reads a half-written config."""
+        return self.write_trial_config(trial, staged=True)
+
+    def reset_and_wait(self, trial, timeout=1.0):
+        """Write a trial config, trigger a reset and block until the game acknowledges it and the new
+        trial is playing. Returns True once it is live, False on timeout or SHM error."""
+        if not self.inner:
+            self.connect()
+            if not self.inner:
+                return False
+        try:
+            last_generation = self.inner.read_trial_generation()
+            if not self.stage_trial_config(trial):
+                return False
+            self.write_commands(False, False, False, False, False, True, promote_staged=True)  # reset=True
+            acked = self.inner.wait_trial_start(last_generation, timeout)
+            self.write_commands(False, False, False, False, False, False)  # release reset
+            if not acked:
+                print(f"Reset not acknowledged within {timeout}s")
+            return acked
+        except Exception as exc:
+            print(f"SHM Reset Error: {exc}")
+            self.inner = None
+            return False
+
+
+def push_config(trial_json, color_palette=None, timeout=2.0, shm_name=DEFAULT_SHM_NAME):
+    """--config-only: write one trial (a JSON file or inline JSON object) to the running game,
+    reset into it and wait for the acknowledgement. Returns the process exit code."""
+    try:
+        if os.path.exists(trial_json):
+            with open(trial_json, 'r') as f:
+                trial_json = f.read()
+        trial = trial_from_json(json.loads(trial_json), color_palette)
+    except (OSError, ValueError, KeyError) as exc:
+        print(f"Invalid trial config: {exc}")
+        return 1
+
+    print("Writing config:")
+    print(json.dumps(trial, indent=2))
+    shm = SharedMemory(shm_name)
+    if not shm.reset_and_wait(trial, timeout):
+        print("Reset not acknowledged, is the game running?")
+        return 1
+    print(f"Reset acknowledged (trial_generation {shm.inner.read_trial_generation()})")
+    return 0
+
+
+# --selftest trial (over DEFAULT_CONFIG): target door 2 of a pyramid turned by 0.7 rad, nothing ending the trial but a win
+SELFTEST_TRIAL = {
+    "start_orient": 0.7,
+    "target_door": 2,
+    "target_kind": monkey_shared.TARGET_KIND_DOOR,
+    "is_catch": False,
+    "max_attempts": 0,
+    "trial_timeout_secs": 0.0,
+    "shaping_dwell_secs": 0.0,
+    "win_radius_min": 0.0,
+    "win_radius_max": 0.0,
+    "required_approach_sign": 0,
+    "min_view_secs": 0.0,
+    "suppress_blanked_attempts": True,
+    "post_win_mode": monkey_shared.POST_WIN_AUTO_RETURN,
+    "control_mirror_rotation": False,
+    "control_mirror_zoom": False,
+    "mirror_x": False,
+    "recenter_on_reset": True,
+    "projection_mode": monkey_shared.PROJECTION_PERSPECTIVE,
+}
+SELFTEST_ROTATE_FRAMES = 20
+SELFTEST_ZOOM_FRAMES = 10
+SELFTEST_TOLERANCE = 1e-3
+
+
+class SelfTestFailure(Exception):
+    pass
+
+
+def wrap_angle(radians):
+    """Angle wrapped to [-pi, pi), like the emitted pyramid_yaw"""
+    return (radians + math.pi) % math.tau - math.pi
+
+
+def target_yaw(state, start_orient, target_door):
+    """pyramid_yaw turning door `target_door` of a pyramid built at `start_orient` towards the camera.
+    Door i faces start_orient + pi/2 + (i + 0.5) * TAU / BASE_NR_SIDES in the XZ plane (angle from +X
+    towards +Z), a yaw turns it back by that much and the global yaw offset turns it on top."""
+    door = start_orient + math.pi / 2 + (target_door + 0.5) * math.tau / monkey_shared.BASE_NR_SIDES
+    x, _, z = state["camera_position"]
+    return wrap_angle(door - math.atan2(z, x) - state["global_yaw_offset"])
+
+
+def run_selftest(timeout=2.0, shm_name=DEFAULT_SHM_NAME):
+    """--selftest: script every command against the running game and check the emitted state after
+    each step, as a living specification of the protocol. Motion is stepped frame by frame while
+    paused, so its amounts are exact. Run it with no other controller attached.
+    Returns the process exit code: 0 on success, 1 on a mismatch, 2 without a game."""
+    shm = SharedMemory(shm_name)
+    if not shm.inner or shm.inner.read_next_frame(0, timeout) is None:
+        print("No frames from the game, is it running?")
+        return 2
+
+    def expect(what, ok, detail=""):
+        print(f"{'ok  ' if ok else 'FAIL'} {what}" + (f" ({detail})" if detail else ""))
+        if not ok:
+            raise SelfTestFailure(what)
+
+    def write(**flags):
+        held = dict(rotate_left=False, rotate_right=False, zoom_in=False, zoom_out=False, check=False, reset=False)
+        held.update(flags)
+        shm.write_commands(**held)
+
+    def next_state(last):
+        state = shm.inner.read_next_frame(last, timeout)
+        if state is None:
+            raise SelfTestFailure(f"no frame after {last} within {timeout}s")
+        return state
+
+    def step(**held):
+        """One frame of the paused game with the `held` commands, and its state"""
+        last = shm.read_game_state()["frame_number"]
+        write(stop_rendering=True, step_frame=True, **held)
+        return next_state(last)
+
+    def pulse(**command):
+        """A trigger held for one frame of the running game (blank_screen toggles on every frame it is
+        read, also while paused, so it cannot be stepped)"""
+        last = shm.read_game_state()["frame_number"]
+        write(**command)
+        next_state(last)
+        write()
+
+    try:
+        # Known config and reset
+        trial = trial_from_json({**DEFAULT_CONFIG, **SELFTEST_TRIAL})
+        expect("reset acknowledged", shm.reset_and_wait(trial, timeout))
+        state = step()
+        expect("playing after the reset", state["phase"] == monkey_shared.PHASE_PLAYING, PHASE_NAMES.get(state["phase"]))
+        expect("no attempts after the reset", state["nr_attempts"] == 0, f"{state['nr_attempts']}")
+        expect("yaw starts at 0", abs(state["pyramid_yaw"]) < SELFTEST_TOLERANCE, f"{state['pyramid_yaw']:.4f}")
+
+        # Per frame steps at the game's frame rate (speeds are per nominal frame)
+        frame_hz = state["display_hz"] or monkey_shared.REFRESH_RATE_HZ
+        rotate_step = monkey_shared.CAMERA_3D_SPEED_ROTATE * monkey_shared.REFRESH_RATE_HZ / frame_hz
+        zoom_step = monkey_shared.CAMERA_3D_SPEED_ZOOM * monkey_shared.REFRESH_RATE_HZ / frame_hz
+
+        # Rotate a fixed number of frames
+        for _ in range(SELFTEST_ROTATE_FRAMES):
+            state = step(rotate_right=True)
+        expected = wrap_angle(SELFTEST_ROTATE_FRAMES * rotate_step)
+        expect(f"yaw after {SELFTEST_ROTATE_FRAMES} frames right", abs(state["pyramid_yaw"] - expected) < SELFTEST_TOLERANCE,
+               f"{state['pyramid_yaw']:.4f} vs {expected:.4f}")
+
+        # Zoom out, the yaw stays
+        radius = state["camera_radius"]
+        for _ in range(SELFTEST_ZOOM_FRAMES):
+            state = step(zoom_out=True)
+        expected = radius + SELFTEST_ZOOM_FRAMES * zoom_step
+        expect(f"radius after {SELFTEST_ZOOM_FRAMES} frames out", abs(state["camera_radius"] - expected) < SELFTEST_TOLERANCE,
+               f"{state['camera_radius']:.4f} vs {expected:.4f}")
+
+        # Check away from the target: an attempt, not a win
+        threshold = trial["cosine_alignment_threshold"]
+        expect("misaligned before the first check", state["cosine_alignment"] < threshold, f"{state['cosine_alignment']:.3f}")
+        state = step(check=True)
+        expect("failed check counts an attempt", state["nr_attempts"] == 1, f"{state['nr_attempts']}")
+        expect("failed check does not win", state["phase"] == monkey_shared.PHASE_PLAYING and state["win_elapsed_secs"] == 0,
+               PHASE_NAMES.get(state["phase"]))
+
+        # Its door feedback locks the controls until it is over
+        deadline = time.monotonic() + 10 * timeout
+        while state["inputs_locked"]:
+            if time.monotonic() > deadline:
+                raise SelfTestFailure("controls still locked after the failed check")
+            state = step()
+
+        # Rotate to the target door, computed from start_orient
+        target = target_yaw(state, trial["start_orient"], trial["target_door"])
+        for _ in range(int(math.pi / rotate_step) + 2):
+            error = wrap_angle(target - state["pyramid_yaw"])
+            if abs(error) <= rotate_step / 2:
+                break
+            state = step(rotate_right=error > 0, rotate_left=error < 0)
+        error = wrap_angle(target - state["pyramid_yaw"])
+        expect("yaw at the target", abs(error) <= rotate_step / 2 + SELFTEST_TOLERANCE, f"{state['pyramid_yaw']:.4f} vs {target:.4f}")
+        expect("aligned at the target", state["cosine_alignment"] >= threshold, f"{state['cosine_alignment']:.3f}")
+
+        # Check at the target: a win
+        state = step(check=True)
+        expect("aligned check counts an attempt", state["nr_attempts"] == 2, f"{state['nr_attempts']}")
+        expect("aligned check wins", state["phase"] == monkey_shared.PHASE_WON and state["win_elapsed_secs"] > 0,
+               PHASE_NAMES.get(state["phase"]))
+
+        # Running again: the door animation and inter-trial interval return to playing
+        write(resume_rendering=True)
+        deadline = time.monotonic() + 10 * timeout
+        while state["phase"] != monkey_shared.PHASE_PLAYING:
+            if time.monotonic() > deadline:
+                raise SelfTestFailure(f"still {PHASE_NAMES.get(state['phase'])} after the win")
+            state = next_state(state["frame_number"])
+        write()
+        expect("fresh round acknowledged", shm.reset_and_wait(trial, timeout))
+
+        # Blank: checks are rejected, not counted, until the blank is lifted again
+        rejected = shm.read_game_state()["rejected_checks"]
+        pulse(blank_screen=True)
+        state = step(check=True)
+        expect("blanked check rejected", state["rejected_checks"] == rejected + 1 and state["nr_attempts"] == 0,
+               f"rejected {state['rejected_checks']}, attempts {state['nr_attempts']}")
+        pulse(blank_screen=True)
+        state = step(check=True)
+        expect("check counted once unblanked", state["rejected_checks"] == rejected + 1 and state["nr_attempts"] == 1,
+               f"rejected {state['rejected_checks']}, attempts {state['nr_attempts']}")
+
+        # Pause holds the frame counter, resume lets it run
+        write(stop_rendering=True)
+        time.sleep(0.1)
+        paused_frame = shm.read_game_state()["frame_number"]
+        time.sleep(0.25)
+        state = shm.read_game_state()
+        expect("paused frame counter holds", state["frame_number"] == paused_frame, f"{paused_frame} -> {state['frame_number']}")
+        write(resume_rendering=True)
+        state = next_state(paused_frame)
+        write()
+        expect("resumed frame counter runs", state["frame_number"] > paused_frame, f"{state['frame_number']}")
+    except SelfTestFailure as exc:
+        write()
+        print(f"Self test failed: {exc}")
+        return 1
+    print("Self test passed")
+    return 0
+
 
 class MonkeyGameController(tk.Tk):
-    def __init__(self):
+    def __init__(self, trials_path="trials.jsonl", color_palette=None, tutorial_seed=None, staircase=False,
+                 latched_motion=False, yaw_offset_degrees=0.0, tutorial_decoration_seed=None,
+                 controller_timeout_frames=monkey_shared.CONTROLLER_TIMEOUT_FRAMES, blank_when_lost=False,
+                 resume_session=None, subject_id=0, block_index=0, shm_name=DEFAULT_SHM_NAME):
         super().__init__()
         self.title("Monkey 3D Game Controller (Target FSM Monitor)")
         self.geometry("1400x900")
@@ -220,12 +1116,26 @@ class MonkeyGameController(tk.Tk):
         self.machine.add_transition('start_anim', 'won', 'animating') # Usually implies win -> anim
         self.machine.add_transition('start_blank', 'animating', 'blank')
         self.machine.add_transition('reset_game', 'blank', 'playing')
+        self.machine.add_transition('end_trial', 'playing', 'blank') # Max attempts / timeout, no win
         
         # Manual overrides (for robustness)
         self.machine.add_transition('force_reset', '*', 'playing')
         self.machine.add_transition('force_anim', 'playing', 'animating') # If we detect anim in playing (e.g. door opening?)
 
-        self.shm_wrapper = SharedMemory()
+        self.shm_wrapper = SharedMemory(shm_name)
+        # Arrow keys start/stop latched motion instead of holding the level commands every frame
+        self.latched_motion = latched_motion
+        # Debounced latched stops ("stop_rotation"/"stop_zoom" -> Tk after id), see on_key_release
+        self.pending_latched_stops = {}
+        self.yaw_offset_degrees = yaw_offset_degrees
+        self.controller_timeout_frames = controller_timeout_frames
+        self.blank_when_lost = blank_when_lost
+        # Session context shown by the game (written with every trial, see write_session_info)
+        self.subject_id = subject_id
+        self.block_index = block_index
+        self.apply_session_settings()
+        # Game frames per second: the display refresh rate once the game reports it (vsync)
+        self.frame_hz = REFRESH_RATE_HZ
         self.inputs = {
             "rotate_left": False, "rotate_right": False,
             "zoom_in": False, "zoom_out": False
@@ -233,21 +1143,73 @@ class MonkeyGameController(tk.Tk):
         self.triggers = {
             "check": False, "reset": False, 
             "blank": False, "pause": False, "resume": False,
-            "animation_door": False, "retry": False
+            "animation_door": False, "retry": False, "step": False,
+            "dump": False, "dump_geometry": False, "clear_attempts": False, "queue_next": False, "flip_type": False,
+            "promote_staged": False, "reference_image": False, "render_quality": False
         }
         
         # Configuration
-        self.trials = load_trials()
+        self.trials = load_trials(trials_path, color_palette, tutorial_seed, tutorial_decoration_seed)
         self.current_trial_index = 0
         self.color_entries = []
+
+        # Adaptive threshold (--staircase): stepped after each outcome, logged per session
+        self.staircase = None
+        if staircase:
+            start = self.trials[0].get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"])
+            self.staircase = monkey_shared.Staircase(start)
+            self.staircase_log = f"staircase_{time.strftime('%Y%m%d_%H%M%S')}.csv"
+            with open(self.staircase_log, "w") as f:
+                f.write("trial,won,threshold,next_threshold,reversals\n")
+            print(f"Staircase from threshold {self.staircase.threshold:.3f}, logging to {self.staircase_log}")
         
+        # One JSON line per finished trial: outcome and the game's frame timing during it
+        self.results_log = f"results_{time.strftime('%Y%m%d_%H%M%S')}.jsonl"
+
+        # Progress saved for crash recovery at every trial start and end; a resumed session replays
+        # its interrupted trial and keeps appending to its logs
+        if resume_session:
+            self.session_path = resume_session
+            self.session = monkey_shared.SessionState.load(resume_session)
+            self.current_trial_index = self.session.trial_index
+            self.results_log = self.session.results_log
+            if self.session.staircase is not None:
+                self.staircase = self.session.staircase
+                self.staircase_log = self.session.staircase_log
+            elif self.staircase:
+                self.session.staircase_log = self.staircase_log
+            print(f"Resumed session {resume_session} at trial {self.current_trial_index} "
+                  f"({self.session.wins}/{self.session.trials_done} won)")
+        else:
+            self.session_path = f"session_{time.strftime('%Y%m%d_%H%M%S')}.json"
+            self.session = monkey_shared.SessionState(self.results_log, getattr(self, "staircase_log", ""))
+        self.save_session()
+        # Session ID: the start time of the session file name (e.g. 20261017143005), 0 for other names
+        self.session_id = int("".join(c for c in os.path.basename(self.session_path) if c.isdigit()) or 0) % 2**64
+
         # Automation State
-        self.blank_start_frame = 0
         self.inferred_win = False
+        # trial_generation of the trial that ended, the blank lasts until a newer one plays
+        self.ended_at_generation = None
+
+        # Worst relative drift of the game's fixed update rate in the current trial
+        self.trial_max_drift = 0.0
+
+        # Game link: last config sent to play (rewritten after a reconnect) and the game's heartbeat
+        self.current_trial = None
+        self.game_heartbeat = None
+        self.game_heartbeat_at = time.monotonic()
+        self.game_lost_at = None
+        self.next_reopen_at = 0.0
         
         # State capture for Pause/Resume
         self.paused_state = None  # Will hold (config, yaw, camera)
         self.is_paused = False
+
+        # Debug overlay of the alignment normals (toggled with G)
+        self.debug_normals = False
+        # Overlay of the winning region around the target normal (toggled with K)
+        self.show_threshold_cone = False
         
         # UI Setup
         self.setup_ui()
@@ -257,7 +1219,73 @@ class MonkeyGameController(tk.Tk):
         self.bind_all("<KeyRelease>", self.on_key_release, add="+")
         
         # Loop
-        self.after(16, self.loop)
+        self.after_next_frame(self.loop)
+
+    def apply_session_settings(self):
+        """Write the settings held for the whole session, which a restarted game no longer has."""
+        if self.latched_motion:
+            self.shm_wrapper.latched_motion("set_motion_latched", True)
+        self.shm_wrapper.set_global_yaw_offset(math.radians(self.yaw_offset_degrees))
+        # Every loop writes the commands, which feeds the game's watchdog
+        self.shm_wrapper.set_controller_watchdog(self.controller_timeout_frames, self.blank_when_lost)
+
+    def watch_game(self, state):
+        """Follow the game's heartbeat. Once it has not moved for GAME_LOST_SECS the game counts as lost
+        and the segment is reopened every RECONNECT_INTERVAL_SECS, since a restarted game maps a new one.
+        A heartbeat moving again (or starting over, when the game restarted in place) reconnects.
+        Returns False while the game is lost."""
+        now = time.monotonic()
+        heartbeat = state.get("game_heartbeat")
+        if heartbeat != self.game_heartbeat:
+            restarted = (self.game_lost_at is not None
+                         or (heartbeat is not None and self.game_heartbeat is not None and heartbeat < self.game_heartbeat))
+            self.game_heartbeat = heartbeat
+            self.game_heartbeat_at = now
+            if restarted:
+                lost_secs = now - self.game_lost_at if self.game_lost_at is not None else 0.0
+                self.game_lost_at = None
+                self.resume_after_reconnect(lost_secs)
+            return True
+        if self.game_lost_at is None:
+            if now - self.game_heartbeat_at < GAME_LOST_SECS:
+                return True
+            self.game_lost_at = now
+            print(f"Game lost: no heartbeat for {GAME_LOST_SECS:.0f} s, reconnecting")
+        if now >= self.next_reopen_at:
+            self.next_reopen_at = now + RECONNECT_INTERVAL_SECS
+            self.shm_wrapper.reopen()
+        return False
+
+    def resume_after_reconnect(self, lost_secs):
+        """Restore the session in the (re)started game and reset into the trial that was playing or
+        staged (before the first trial the game keeps its own). The trial index is left as it is, so
+        the interrupted trial is replayed, not skipped, and its outcome is not recorded."""
+        print(f"Reconnected to the game after {lost_secs:.1f} s, restarting trial {self.current_trial_index}")
+        self.apply_session_settings()
+        if self.current_trial is not None:
+            self.triggers["promote_staged"] = self.shm_wrapper.stage_trial_config(self.current_trial)
+            self.triggers["reset"] = True
+        self.inferred_win = False
+        self.ended_at_generation = None
+        self.trial_max_drift = 0.0
+        self.force_reset() # -> playing
+
+    def after_next_frame(self, callback):
+        """Run callback once the game's next frame is readable (after one period at the display rate until frames are seen)."""
+        upcoming = self.shm_wrapper.next_frame()
+        delay = upcoming[1] if upcoming else 1.0 / self.frame_hz
+        self.after(max(1, round(delay * 1000)), callback)
+
+    def after_frames(self, frames, callback):
+        """Run callback on the `frames`-th upcoming game frame, so delays follow the game's frame clock
+        (periods at the display rate until frames are seen)."""
+        upcoming = self.shm_wrapper.next_frame()
+        delay = None
+        if upcoming:
+            delay = self.shm_wrapper.seconds_until_frame(upcoming[0] - 1 + frames)
+        if delay is None:
+            delay = frames / self.frame_hz
+        self.after(max(1, round(delay * 1000)), callback)
 
     def setup_ui(self):
         # Main Layout: 2 Columns (Left: Controls/Data, Right: FSM)
@@ -342,6 +1370,7 @@ class MonkeyGameController(tk.Tk):
             
             ("playing", "playing", "Reset (Manual)", "edge_manual_reset"),
             ("playing", "animating", "Anim Active", "edge_play_anim"),
+            ("playing", "blank", "Out of Attempts/Time", "edge_play_blank"),
             ("animating", "playing", "Anim Done", "edge_anim_play")
         ]
         
@@ -428,6 +1457,13 @@ class MonkeyGameController(tk.Tk):
             ("B (Blank)", "blank"),
             ("P (Pause)", "pause"),
             ("O (Resume)", "resume"),
+            ("N (Step Frame)", "step"),
+            ("J (Dump Config)", "dump"),
+            ("V (Dump Geometry)", "dump_geometry"),
+            ("A (Clear Attempts)", "clear_attempts"),
+            ("T (Flip Type 1/2)", "flip_type"),
+            ("I (Ref Image)", "reference_image"),
+            ("M (Render Quality)", "render_quality"),
         ]
         
         for i, (label, key) in enumerate(input_layout):
@@ -456,6 +1492,8 @@ class MonkeyGameController(tk.Tk):
             "Seeds": str(trial.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"])),
             "Target Door": trial.get("target_door"),
             "Threshold": trial.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
+            "Win Radius": (f"[{trial.get('win_radius_min', 0.0)}, {trial['win_radius_max']}]"
+                           if trial.get("win_radius_max") else "any"),
             "Decors Count": str(trial.get("decorations_count", DEFAULT_CONFIG["decorations_count"])),
             "Decors Size": str(trial.get("decorations_size", DEFAULT_CONFIG["decorations_size"])),
             "Spot Intensity": f"{trial.get('main_spotlight_intensity', DEFAULT_CONFIG['main_spotlight_intensity']):.1e}",
@@ -479,14 +1517,54 @@ class MonkeyGameController(tk.Tk):
         st_data = {
             "Frame": state.get("frame_number"),
             "Time": f"{state.get('elapsed_secs', 0.0):.2f}s",
-            "Attempts": state.get("attempts", 0),
+            "Attempts": f"{state.get('nr_attempts', 0)} ({state.get('rejected_checks', 0)} rejected)",
             "Alignment": align_str,
+            "Target": ("none (catch)" if state.get("is_catch")
+                       else f"face {state.get('target_face')}" if state.get("target_kind") == monkey_shared.TARGET_KIND_FACE
+                       else f"door {state.get('target_door')}"),
             "Angle (Rad)": f"{state.get('current_angle', 0.0):.4f}",
+            "Best Door": f"{state.get('best_door', 0)} of {state.get('num_doors', 0)}",
+            "Live Feedback": ("shown" if state.get("live_feedback_visible") else "hidden") if state.get("live_feedback_enabled") else "off",
             "Yaw (Rad)": f"{state.get('pyramid_yaw_rad', 0.0):.4f}",
             "Animating": str(state.get("is_animating", False)),
-            "Cam Radius": f"{state.get('camera_radius', 0.0):.2f}",
+            "Inputs": "locked" if state.get("inputs_locked") else "free",
+            "Shaping Dwell": f"{state.get('shaping_dwell_elapsed', 0.0):.2f}s",
+            "Phase": (PHASE_NAMES.get(state.get("phase"), "?")
+                      + (f" ({state.get('iti_remaining_frames', 0)}/{state.get('iti_frames', 0)} frames left)"
+                         if state.get("phase") == monkey_shared.PHASE_INTER_TRIAL else "")),
+            "Radius @ Check": f"{state.get('radius_at_check', 0.0):.2f}" + (" (outside band)" if state.get("win_blocked_by_radius") else "")
+                              + (" (too early)" if state.get("check_too_early") else "")
+                              + (" (wrong approach)" if state.get("win_blocked_by_approach") else ""),
+            "Approach": f"{state.get('approach_sign', 0):+d}" + (f" (needs {state['required_approach_sign']:+d})"
+                                                                  if state.get("required_approach_sign") else ""),
+            "Cam Radius": f"{state.get('camera_radius', 0.0):.2f}" + (" (ortho scale)" if state.get("camera_radius_is_scale") else ""),
+            "Pyramid Yaw": (f"{math.degrees(state.get('pyramid_yaw', 0.0)):.1f}° "
+                            f"(rig offset {math.degrees(state.get('global_yaw_offset', 0.0)):+.1f}°)"),
+            "Fixed Rate": (f"{state['measured_hz']:.2f}" if state.get("measured_hz") else "measuring")
+                          + f" / {state.get('fixed_hz') or REFRESH_RATE_HZ:.2f} Hz",
+            "Display": f"{state['display_hz']:.2f} Hz" if state.get("display_hz") else "unknown",
+            "Frame Drops": (f"{state.get('dropped_frames_trial', 0)} dropped, {state.get('late_frames_trial', 0)} late, "
+                            f"worst {state.get('worst_frame_ms', 0.0):.1f} ms"),
+            "Render Quality": RENDER_QUALITY_NAMES.get(state.get("render_quality"), "unknown"),
+            "FOV": f"{state.get('active_fov_degrees', 0.0):.1f}° @ {state.get('window_resolution', [0, 0])}",
+            "Latched Motion": (f"rotate {state.get('latched_rotation', 0):+d}, zoom {state.get('latched_zoom', 0):+d}"
+                               if self.latched_motion else "off"),
+            "Overrides": (f"{state.get('override_activations', 0)} activations, {state.get('override_actions', 0)} actions"
+                          if state.get("override_activations") else "none"),
+            "Controller Outages": str(state.get("controller_outages", 0)),
+            "Debounced": str(state.get("debounced_count", 0)),
+            "Standby Checks": str(state.get("standby_checks", 0)),
+            "Session": (f"trial {state.get('current_trial_index', 0)}/{state.get('total_trials', 0)}, "
+                        f"block {state.get('block_index', 0)}, subject {state.get('subject_id', 0)}"),
             "FSM State": self.state.upper()
         }
+        # Written vs read back: a command written but never seen did not reach the game
+        readback = self.shm_wrapper.read_commands()
+        if readback is not None:
+            written = [name for name, value in readback["raw"].items() if value not in (0, IDLE_COMMAND_VALUE)]
+            st_data["Cmds Written"] = ", ".join(written) or "none"
+            st_data["Cmds Seen"] = ", ".join(readback["seen"]) or "none"
+            st_data["Cmds Counts"] = ", ".join(f"{name} {count}" for name, count in readback["counts"].items() if count) or "none"
         
         if not self.tree_state.get_children():
             for k, v in st_data.items():
@@ -499,12 +1577,21 @@ class MonkeyGameController(tk.Tk):
     def loop(self):
         # 1. Read Game State
         state = self.shm_wrapper.read_game_state()
-        current_frame = state.get("frame_number", 0)
         is_animating = state.get("is_animating", False)
         current_alignment = state.get("cosine_alignment")
+
+        measured_hz = state.get("measured_hz", 0.0)
+        if measured_hz:
+            fixed_hz = state.get("fixed_hz") or REFRESH_RATE_HZ
+            self.trial_max_drift = max(self.trial_max_drift, abs(measured_hz / fixed_hz - 1.0))
+        self.frame_hz = state.get("display_hz") or REFRESH_RATE_HZ
+
+        if not self.watch_game(state):
+            self.process_inputs_and_update_ui(state)
+            self.after_next_frame(self.loop)
+            return
         
         auto_reset = False
-        auto_blank = False
         auto_stop = False
         auto_resume = False
         auto_anim = False # Triggers animation_door
@@ -530,7 +1617,7 @@ class MonkeyGameController(tk.Tk):
             else:
                  # Just update UI and wait
                  self.process_inputs_and_update_ui(state, auto_stop, auto_resume)
-                 self.after(16, self.loop)
+                 self.after_next_frame(self.loop)
                  return
 
         # ---------------------------------------------------------
@@ -545,14 +1632,48 @@ class MonkeyGameController(tk.Tk):
                 # We ALWAYS trigger animation (door open) visually per request
                 auto_anim = True 
                 
-                # Check if it counts as a WIN
-                if current_alignment is not None and current_alignment <= 1.5:
-                    if current_alignment > threshold:
+                # Check if it counts as a WIN (never on a catch trial, nor before a trial is loaded)
+                if state.get("phase") == monkey_shared.PHASE_STANDBY:
+                    print("Check refused: the game is in standby, R loads the first trial")
+                elif state.get("is_catch"):
+                    print(f"Catch Trial Check: non-win (alignment {current_alignment})")
+                elif current_alignment is not None and current_alignment <= 1.5:
+                    # Optional radius band (max 0 = no requirement), from the orbit position like the game
+                    radius_min = state.get("win_radius_min", 0.0)
+                    radius_max = state.get("win_radius_max", 0.0)
+                    cam_pos = state.get("camera_position", [0.0, 0.0, 0.0])
+                    radius = math.hypot(cam_pos[0], cam_pos[2])
+                    in_band = not radius_max or radius_min <= radius <= radius_max
+                    # Nor before the minimum viewing time since the reset
+                    too_early = state.get("elapsed_secs", 0.0) < state.get("min_view_secs", 0.0)
+                    # Nor turning the wrong way, when an approach direction is required
+                    required_sign = state.get("required_approach_sign", 0)
+                    approached = not required_sign or state.get("approach_sign", 0) == required_sign
+                    if current_alignment > threshold and in_band and approached and not too_early:
                         print(f"Valid Win: {current_alignment:.4f} > {threshold}")
                         self.inferred_win = True
                         self.win_game() # -> won
+                    elif current_alignment > threshold and too_early:
+                        print(f"Check Failed: {state.get('elapsed_secs', 0.0):.2f}s into the trial, "
+                              f"before min_view_secs {state.get('min_view_secs')}")
+                    elif current_alignment > threshold and not approached:
+                        print(f"Check Failed: approach {state.get('approach_sign', 0):+d}, required {required_sign:+d}")
+                    elif current_alignment > threshold:
+                        print(f"Check Failed: radius {radius:.2f} outside [{radius_min}, {radius_max}]")
                     else:
                         print(f"Check Failed: {current_alignment:.4f} < {threshold}")
+            elif state.get("win_elapsed_secs"):
+                # Shaping auto-win: the game triggered the win itself after the dwell
+                print(f"Shaping Win: dwelt {state.get('shaping_dwell_elapsed', 0.0):.2f}s near target")
+                self.inferred_win = True
+                self.win_game() # -> won
+            elif state.get("phase") == monkey_shared.PHASE_FAILED:
+                # The game ended the trial: out of attempts or time (the only way a catch trial ends)
+                self.inferred_win = False
+                self.record_outcome(state, won=False)
+                self.end_trial() # -> blank
+                self.ended_at_generation = state.get("trial_generation")
+                self.stage_next_trial()
                 
         elif self.state == 'won':
             if is_animating:
@@ -563,54 +1684,136 @@ class MonkeyGameController(tk.Tk):
         elif self.state == 'animating':
             if not is_animating:
                 if self.inferred_win:
+                    # The game runs the inter-trial interval once the win animation ends
                     self.start_blank() # -> blank
-                    self.blank_start_frame = current_frame
-                    # Prepare next trial
-                    self.current_trial_index += 1
-                    trial = self.trials[self.current_trial_index % len(self.trials)]
-                    
-                    # Ensure commands_seq > 0 before writing config (required by Rust guard)
-                    self.shm_wrapper.write_commands(
-                        False, False, False, False,
-                        False, True, False, False, False, False  # reset=True
-                    )
-                    self.shm_wrapper.write_reset_config(
-                        trial.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"]), trial["base_radius"], 
-                        trial["height"], trial["start_orient"], trial["target_door"], trial["colors"],
-                        trial.get("decorations_count", DEFAULT_CONFIG["decorations_count"]),
-                        trial.get("decorations_size", DEFAULT_CONFIG["decorations_size"]),
-                        trial.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
-                        trial.get("door_anim_fade_out", DEFAULT_CONFIG["door_anim_fade_out"]),
-                        trial.get("door_anim_stay_open", DEFAULT_CONFIG["door_anim_stay_open"]),
-                        trial.get("door_anim_fade_in", DEFAULT_CONFIG["door_anim_fade_in"]),
-                        trial.get("main_spotlight_intensity", DEFAULT_CONFIG["main_spotlight_intensity"]),
-                        trial.get("max_spotlight_intensity", DEFAULT_CONFIG["max_spotlight_intensity"]),
-                        trial.get("ambient_brightness", DEFAULT_CONFIG["ambient_brightness"])
-                    )
-                    auto_reset = True
-                    auto_blank = True
+                    self.ended_at_generation = state.get("trial_generation")
+                    self.record_outcome(state, won=True)
+                    if state.get("post_win_mode") == monkey_shared.POST_WIN_AUTO_RETURN:
+                        self.stage_next_trial()
+                    else:
+                        print("Holding blank until the next reset (R)")
                 else:
                     self.force_reset() # -> playing (Animation done, back to game)
 
         elif self.state == 'blank':
-            if (current_frame - self.blank_start_frame) >= WIN_BLANK_DURATION_FRAMES:
-                auto_blank = True # Toggle OFF (Actually Reset clears it? No, Blank is separate)
-                # Wait, blank command toggles. If we want it OFF, we send it again if active?
-                # Actually reset handles clean slate? 
-                # Let's just send reset.
+            # Follow the game through the inter-trial interval until the next trial's generation plays
+            if (state.get("trial_generation") != self.ended_at_generation
+                    and state.get("phase") == monkey_shared.PHASE_PLAYING):
+                print("Inter-trial interval over")
                 self.reset_game() # -> playing
 
         # Apply triggers
         if auto_reset: self.triggers['reset'] = True
-        if auto_blank: self.triggers['blank'] = True
         if auto_stop: self.triggers['pause'] = True
         if auto_resume: self.triggers['resume'] = True
         if auto_anim: self.triggers['animation_door'] = True
 
         self.process_inputs_and_update_ui(state)
-        self.after(16, self.loop)
+        self.after_next_frame(self.loop)
+
+    def record_outcome(self, state, won):
+        """Log the trial's result with the config the game applied, flag it if the game's frame timing
+        drifted or dropped frames, then step the staircase (if enabled) with its outcome. Catch trials carry no information."""
+        drift_flagged = self.trial_max_drift > monkey_shared.FIXED_RATE_DRIFT_TOLERANCE
+        if drift_flagged:
+            print(f"Timing flag: trial {self.current_trial_index} fixed rate drifted up to {self.trial_max_drift:.1%}")
+        if state.get("dropped_frames_trial"):
+            print(f"Timing flag: trial {self.current_trial_index} dropped {state['dropped_frames_trial']} frames "
+                  f"(worst {state.get('worst_frame_ms', 0.0):.1f} ms)")
+        if state.get("stimulus_file_status", 0) > monkey_shared.STIMULUS_FILE_LOADED:
+            print(f"Stimulus flag: trial {self.current_trial_index} could not replay {state.get('requested', {}).get('stimulus_file')} "
+                  f"(status {state['stimulus_file_status']}), showed the procedural pyramid")
+        # The config the game actually showed, and where it differs from the one requested
+        applied = state.get("applied", {})
+        requested = state.get("requested", {})
+        adjusted = {key: [requested[key], value] for key, value in applied.items()
+                    if key in requested and requested[key] != value}
+        # Scale of the reward to dispense, from the weight of the door the win was credited to
+        reward_weight = state.get("reward_weight", 0.0) if won else 0.0
+        if won:
+            print(f"Reward weight: {reward_weight:g}")
+        with open(self.results_log, "a") as f:
+            f.write(json.dumps({
+                "trial": self.current_trial_index,
+                "total_trials": len(self.trials),
+                "block": self.block_index,
+                "session_id": self.session_id,
+                "subject_id": self.subject_id,
+                "trial_id": (self.current_trial or {}).get("trial_id"),
+                "trial_id_hash": state.get("trial_id_hash", 0),
+                "won": won,
+                "reward_weight": reward_weight,
+                "is_catch": state.get("is_catch", False),
+                "attempts": state.get("nr_attempts", 0),
+                "elapsed_secs": state.get("elapsed_secs", 0.0),
+                "max_fixed_rate_drift": self.trial_max_drift,
+                "fixed_rate_flagged": drift_flagged,
+                "late_frames": state.get("late_frames_trial", 0),
+                "dropped_frames": state.get("dropped_frames_trial", 0),
+                "worst_frame_ms": state.get("worst_frame_ms", 0.0),
+                "render_quality": state.get("render_quality", monkey_shared.RENDER_QUALITY_HIGH),
+                "reset_ack": state.get("reset_ack", 0),
+                "stimulus_hash": state.get("stimulus_hash", 0),
+                "stimulus_file_status": state.get("stimulus_file_status", 0),
+                "check_too_early": state.get("check_too_early", False),
+                "applied": applied,
+                "adjusted": adjusted,
+            }) + "\n")
+        self.trial_max_drift = 0.0
+        self.session.record_trial(won)
+        if self.staircase and not state.get("is_catch"):
+            threshold = state.get("cosine_alignment_threshold", self.staircase.threshold)
+            next_threshold = self.staircase.update(won)
+            print(f"Staircase: {'win' if won else 'loss'} at {threshold:.3f} -> {next_threshold:.3f}")
+            with open(self.staircase_log, "a") as f:
+                f.write(f"{self.current_trial_index},{int(won)},{threshold:.4f},{next_threshold:.4f},{self.staircase.reversals}\n")
+        self.save_session()
+
+    def write_session_info(self):
+        """Tell the game where the session is: the trial about to play out of the list, block and IDs."""
+        self.shm_wrapper.write_session_info(self.current_trial_index, len(self.trials), self.block_index,
+                                            self.session_id, self.subject_id)
+
+    def save_session(self):
+        """Write the session progress, kept whole on disk whenever the write is interrupted."""
+        self.session.trial_index = self.current_trial_index
+        self.session.staircase = self.staircase
+        try:
+            self.session.save(self.session_path)
+        except OSError as exc:
+            print(f"Session save failed: {exc}")
+
+    def with_staircase(self, trial):
+        """The trial with the staircase's current threshold, when the staircase is enabled."""
+        if not self.staircase:
+            return trial
+        return dict(trial, cosine_alignment_threshold=self.staircase.threshold)
+
+    def stage_next_trial(self):
+        """Write the next trial's config and queue it: the game applies it when the inter-trial interval ends."""
+        self.current_trial_index += 1
+        trial = self.with_staircase(self.trials[self.current_trial_index % len(self.trials)])
+        self.current_trial = trial
+        self.save_session()
+        self.write_session_info()
+        if self.shm_wrapper.stage_trial_config(trial):
+            self.triggers['promote_staged'] = True
+        self.triggers['queue_next'] = True
 
     def process_inputs_and_update_ui(self, state, f_stop=False, f_resume=False):
+        # Live Type1 <-> Type2 switch on the current trial, for A/B comparison
+        flip_type = None
+        if self.triggers["flip_type"]:
+            flip_type = (monkey_shared.PYRAMID_TYPE_1 if state.get("pyramid_type") == monkey_shared.PYRAMID_TYPE_2
+                         else monkey_shared.PYRAMID_TYPE_2)
+        # Toggle the first reference image (e.g. a fixation target between trials)
+        if self.triggers["reference_image"]:
+            self.shm_wrapper.show_reference_image(None if state.get("reference_image") is not None else 0)
+        # Cycle the render quality (low -> medium -> high -> low), e.g. when frames drop on a slower rig
+        if self.triggers["render_quality"]:
+            level = (state.get("render_quality", monkey_shared.RENDER_QUALITY_HIGH) + 1) % 3
+            self.shm_wrapper.set_render_quality(level)
+            print(f"Render quality: {RENDER_QUALITY_NAMES[level]}")
         # Write to SHM
         self.shm_wrapper.write_commands(
             self.inputs["rotate_left"], self.inputs["rotate_right"],
@@ -618,9 +1821,19 @@ class MonkeyGameController(tk.Tk):
             self.triggers["check"],
             self.triggers["reset"],
             self.triggers["blank"],
-            self.triggers["pause"] or f_stop,
+            # Hold stop_rendering while paused so single steps re-pause
+            self.triggers["pause"] or f_stop or self.is_paused,
             self.triggers["resume"] or f_resume,
-            self.triggers["animation_door"]
+            self.triggers["animation_door"],
+            step_frame=self.triggers["step"] and self.is_paused,
+            debug_normals=self.debug_normals,
+            dump_config=self.triggers["dump"],
+            dump_geometry=self.triggers["dump_geometry"],
+            clear_attempts=self.triggers["clear_attempts"],
+            queue_next=self.triggers["queue_next"],
+            set_pyramid_type=flip_type,
+            promote_staged=self.triggers["promote_staged"],
+            show_threshold_cone=self.show_threshold_cone,
         )
         
         # Clear triggers
@@ -631,9 +1844,25 @@ class MonkeyGameController(tk.Tk):
         self.highlight_node(self.state)
         self.highlight_arrow("edge_win", active=(self.state == 'won'))
 
+    def latched_stop_later(self, method):
+        """Stop latched motion after KEY_RELEASE_DEBOUNCE_MS, unless a key press cancels it first (autorepeat)."""
+        self.cancel_latched_stop(method)
+        self.pending_latched_stops[method] = self.after(KEY_RELEASE_DEBOUNCE_MS, self.latched_stop_now, method)
+
+    def latched_stop_now(self, method):
+        del self.pending_latched_stops[method]
+        self.shm_wrapper.latched_motion(method)
+
+    def cancel_latched_stop(self, method):
+        after_id = self.pending_latched_stops.pop(method, None)
+        if after_id is not None:
+            self.after_cancel(after_id)
+
     def on_key_release(self, event):
         key = event.keysym.lower()
-        if key == "left": self.inputs["rotate_left"] = False
+        if self.latched_motion and key in ("left", "right"): self.latched_stop_later("stop_rotation")
+        elif self.latched_motion and key in ("up", "down"): self.latched_stop_later("stop_zoom")
+        elif key == "left": self.inputs["rotate_left"] = False
         elif key == "right": self.inputs["rotate_right"] = False
         elif key == "up": self.inputs["zoom_in"] = False
         elif key == "down": self.inputs["zoom_out"] = False
@@ -643,36 +1872,29 @@ class MonkeyGameController(tk.Tk):
         elif key == "b": self.triggers["blank"] = False
         elif key == "p": self.triggers["pause"] = False
         elif key == "o": self.triggers["resume"] = False
+        elif key == "n": self.triggers["step"] = False
+        elif key == "j": self.triggers["dump"] = False
+        elif key == "v": self.triggers["dump_geometry"] = False
+        elif key == "a": self.triggers["clear_attempts"] = False
+        elif key == "t": self.triggers["flip_type"] = False
+        elif key == "i": self.triggers["reference_image"] = False
+        elif key == "m": self.triggers["render_quality"] = False
 
     def trigger_reset_config(self):
         # Pick next trial
-        trial = self.trials[self.current_trial_index % len(self.trials)]
+        trial = self.with_staircase(self.trials[self.current_trial_index % len(self.trials)])
+        self.save_session()
         self.current_trial_index += 1
+        self.current_trial = trial
+        self.write_session_info()
         
-        # Ensure commands_seq > 0 by sending a write_commands first (required by Rust guard)
+        print(f"Sending Reset Config (Trial {self.current_trial_index}, id {trial.get('trial_id')})")
+        promote = self.shm_wrapper.stage_trial_config(trial)
         self.shm_wrapper.write_commands(
             self.inputs["rotate_left"], self.inputs["rotate_right"],
             self.inputs["zoom_in"], self.inputs["zoom_out"],
-            False, True, False, False, False, False  # reset=True
-        )
-        
-        print(f"Sending Reset Config (Trial {self.current_trial_index})")
-        self.shm_wrapper.write_reset_config(
-            trial.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"]),
-            trial["base_radius"],
-            trial["height"],
-            trial["start_orient"],
-            trial["target_door"],
-            trial["colors"],
-            trial.get("decorations_count", DEFAULT_CONFIG["decorations_count"]),
-            trial.get("decorations_size", DEFAULT_CONFIG["decorations_size"]),
-            trial.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
-            trial.get("door_anim_fade_out", DEFAULT_CONFIG["door_anim_fade_out"]),
-            trial.get("door_anim_stay_open", DEFAULT_CONFIG["door_anim_stay_open"]),
-            trial.get("door_anim_fade_in", DEFAULT_CONFIG["door_anim_fade_in"]),
-            trial.get("main_spotlight_intensity", DEFAULT_CONFIG["main_spotlight_intensity"]),
-            trial.get("max_spotlight_intensity", DEFAULT_CONFIG["max_spotlight_intensity"]),
-            trial.get("ambient_brightness", DEFAULT_CONFIG["ambient_brightness"])
+            False, True, False, False, False, False,  # reset=True
+            promote_staged=promote,
         )
 
     def trigger_retry(self):
@@ -701,32 +1923,21 @@ class MonkeyGameController(tk.Tk):
                 "config": trial.copy()
             }
             
-            # 4. Ensure commands_seq > 0 before writing config (required by Rust guard)
+            # 4. Stage the Reset Config (Initial Layout) and promote it with the reset
+            self.current_trial = trial
+            promote = self.shm_wrapper.stage_trial_config(trial)
             self.shm_wrapper.write_commands(
                 False, False, False, False,
-                False, True, False, False, False, False  # reset=True
-            )
-            # Send Reset Config (Initial Layout)
-            self.shm_wrapper.write_reset_config(
-                trial.get("decoration_seeds", DEFAULT_CONFIG["decoration_seeds"]), trial["base_radius"], 
-                trial["height"], trial["start_orient"], trial["target_door"], trial["colors"],
-                trial.get("decorations_count", DEFAULT_CONFIG["decorations_count"]),
-                trial.get("decorations_size", DEFAULT_CONFIG["decorations_size"]),
-                trial.get("cosine_alignment_threshold", DEFAULT_CONFIG["cosine_alignment_threshold"]),
-                trial.get("door_anim_fade_out", DEFAULT_CONFIG["door_anim_fade_out"]),
-                trial.get("door_anim_stay_open", DEFAULT_CONFIG["door_anim_stay_open"]),
-                trial.get("door_anim_fade_in", DEFAULT_CONFIG["door_anim_fade_in"]),
-                trial.get("main_spotlight_intensity", DEFAULT_CONFIG["main_spotlight_intensity"]),
-                trial.get("max_spotlight_intensity", DEFAULT_CONFIG["max_spotlight_intensity"]),
-                trial.get("ambient_brightness", DEFAULT_CONFIG["ambient_brightness"])
+                False, True, False, False, False, False,  # reset=True
+                promote_staged=promote,
             )
             
             # 5. Send Commands: Reset + Blank
             self.triggers["reset"] = True
             self.triggers["blank"] = True # Set blank=True
             
-            # 6. Schedule Unblank (0.2s)
-            self.after(200, self.unblank_callback)
+            # 6. Schedule Unblank (0.2s, on a frame boundary)
+            self.after_frames(round(0.2 * self.frame_hz), self.unblank_callback)
             
         except Exception as e:
             print(f"Retry error: {e}")
@@ -752,7 +1963,13 @@ class MonkeyGameController(tk.Tk):
 
     def on_key_press(self, event):
         key = event.keysym.lower()
-        if key == "left": self.inputs["rotate_left"] = True
+        if self.latched_motion and key in ("left", "right"):
+            self.cancel_latched_stop("stop_rotation")
+            self.shm_wrapper.latched_motion("start_rotation", -1 if key == "left" else 1)
+        elif self.latched_motion and key in ("up", "down"):
+            self.cancel_latched_stop("stop_zoom")
+            self.shm_wrapper.latched_motion("start_zoom", -1 if key == "up" else 1)
+        elif key == "left": self.inputs["rotate_left"] = True
         elif key == "right": self.inputs["rotate_right"] = True
         elif key == "up": self.inputs["zoom_in"] = True
         elif key == "down": self.inputs["zoom_out"] = True
@@ -767,10 +1984,100 @@ class MonkeyGameController(tk.Tk):
         elif key == "b": self.triggers["blank"] = True
         elif key == "p": self.triggers["pause"] = True
         elif key == "o": self.triggers["resume"] = True
+        elif key == "n": self.triggers["step"] = True
+        elif key == "j":
+            self.triggers["dump"] = True
+            print(f"Dumping current trial config to {monkey_shared.CONFIG_DUMP_PATH}")
+        elif key == "v":
+            self.triggers["dump_geometry"] = True
+            print(f"Dumping the geometry on screen to {monkey_shared.GEOMETRY_DUMP_DIR}/")
+        elif key == "a":
+            self.triggers["clear_attempts"] = True
+            print("Clearing attempts (stimulus kept)")
+        elif key == "t":
+            self.triggers["flip_type"] = True
+            print("Flipping pyramid type (Type1 <-> Type2)")
+        elif key == "i":
+            self.triggers["reference_image"] = True
+            print("Toggling reference image")
+        elif key == "m":
+            self.triggers["render_quality"] = True
+        elif key == "g":
+            self.debug_normals = not self.debug_normals
+            print(f"Debug normals: {self.debug_normals}")
+        elif key == "k":
+            self.show_threshold_cone = not self.show_threshold_cone
+            print(f"Threshold cone: {self.show_threshold_cone}")
         elif key == "q": self.destroy()
 
 if __name__ == "__main__":
-    app = MonkeyGameController()
+    parser = argparse.ArgumentParser(description="Monkey 3D Game Controller")
+    parser.add_argument("--trials", default="trials.jsonl", help="Trials JSONL file")
+    parser.add_argument("--shm-name", default=DEFAULT_SHM_NAME, metavar="NAME",
+                        help="Shared memory region of the game to drive (the game's --shm-name)")
+    palette_source = parser.add_mutually_exclusive_group()
+    palette_source.add_argument("--color-palette", default=None,
+                                help="JSON list of RGBA colors to sample face colors from for trials without colors")
+    palette_source.add_argument("--palette", choices=["equiluminant", "deuteranopia"], default=None,
+                                help="Sample the face colors of trials without colors from a generated palette: "
+                                     "equiluminant colors evenly spread in hue, or the deuteranopia-safe preset")
+    parser.add_argument("--palette-size", type=int, default=monkey_shared.PALETTE_SIZE, metavar="N",
+                        help="Colors in the equiluminant palette (trials sample 3 of them)")
+    parser.add_argument("--palette-luma", type=float, default=monkey_shared.PALETTE_LUMA, metavar="LUMA",
+                        help=f"Rec.709 luma of the generated palette, in {monkey_shared.PALETTE_LUMA_RANGE}")
+    parser.add_argument("--palette-seed", type=int, default=0, metavar="SEED",
+                        help="Seed of the equiluminant palette's first hue")
+    parser.add_argument("--tutorial", type=int, nargs="?", const=0, default=None, metavar="SEED",
+                        help="Use the built-in tutorial sequence (seeded) when the trials file is missing")
+    parser.add_argument("--tutorial-decoration-seed", type=int, default=None, metavar="SEED",
+                        help="Draw the tutorial's face decorations from this seed instead of the --tutorial one, "
+                             "keeping its geometry fixed")
+    parser.add_argument("--staircase", action="store_true",
+                        help="Adapt the alignment threshold trial to trial (2-down-1-up), logged to staircase_<time>.csv")
+    parser.add_argument("--latched-motion", action="store_true",
+                        help="Arrow keys start/stop rotation and zoom in the game, so a late controller frame does not stutter it")
+    parser.add_argument("--controller-timeout", type=int, default=monkey_shared.CONTROLLER_TIMEOUT_FRAMES, metavar="FRAMES",
+                        help="Game frames without a command from this controller before the game stops the camera (0 = never)")
+    parser.add_argument("--blank-when-lost", action="store_true",
+                        help="Also blank the game's screen when it loses this controller")
+    parser.add_argument("--yaw-offset", type=float, default=0.0, metavar="DEGREES",
+                        help="Turn the game's pyramid by this yaw on top of the controls, to line it up with a rotated display")
+    parser.add_argument("--resume-session", metavar="SESSION_JSON", default=None,
+                        help="Continue the session saved in this file after a crash: its trial, counts, staircase and logs")
+    parser.add_argument("--subject-id", type=int, default=0, metavar="ID",
+                        help="Numeric ID of the animal, shown in the game's logs and HUD and kept in the results log")
+    parser.add_argument("--block", type=int, default=0, metavar="INDEX",
+                        help="Block of the experiment this session runs, shown and logged like the subject ID")
+    parser.add_argument("--config-only", metavar="TRIAL_JSON", default=None,
+                        help="Write one trial (JSON file or inline object) to the running game, reset into it and exit")
+    parser.add_argument("--trial-schema", action="store_true",
+                        help="Print the JSON Schema of a trials file entry (fields, types, ranges, defaults) and exit")
+    parser.add_argument("--selftest", action="store_true",
+                        help="Script every command against the running game, check the emitted state and exit nonzero on a mismatch")
+    args = parser.parse_args()
+
+    if args.palette:
+        try:
+            color_palette = generated_palette(args.palette, args.palette_size, args.palette_luma, args.palette_seed)
+        except ValueError as e:
+            parser.error(f"--palette: {e}")
+    else:
+        color_palette = load_color_palette(args.color_palette)
+
+    if args.trial_schema:
+        print(json.dumps(trial_schema(), indent=2))
+        sys.exit(0)
+
+    if args.config_only is not None:
+        sys.exit(push_config(args.config_only, color_palette, shm_name=args.shm_name))
+
+    if args.selftest:
+        sys.exit(run_selftest(shm_name=args.shm_name))
+
+    app = MonkeyGameController(args.trials, color_palette, args.tutorial, args.staircase,
+                               args.latched_motion, args.yaw_offset, args.tutorial_decoration_seed,
+                               args.controller_timeout, args.blank_when_lost, args.resume_session,
+                               args.subject_id, args.block, args.shm_name)
     app.mainloop()
 
 
@@ -0,0 +1,213 @@
+"""Tests of the controller against the monkey_shared bindings, and end to end against the headless
+game binary (`cargo build -p game_node`, or the one MONKEY_GAME_BIN names).
+
+Run from controller_python with `python -m unittest test_controller`, after building the bindings
+(see controller.py)."""
+import contextlib
+import json
+import os
+import subprocess
+import sys
+import tempfile
+import time
+import unittest
+from unittest import mock
+
+import controller
+import monkey_shared
+
+REPO = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+GAME_BIN = os.environ.get("MONKEY_GAME_BIN", os.path.join(REPO, "target", "debug", "game_node"))
+TIMEOUT_SECS = 30.0
+
+
+def wait_until(what, condition, timeout=TIMEOUT_SECS):
+    """condition()'s first truthy result, polled until `timeout`"""
+    deadline = time.monotonic() + timeout
+    while not (result := condition()):
+        if time.monotonic() > deadline:
+            raise AssertionError(f"timed out waiting for {what}")
+        time.sleep(0.005)
+    return result
+
+
+class HeadlessGame:
+    """The --headless game on its own region `name` while in a with block, killed at its end. The
+    region is created by the game, so a stale one from an earlier run is removed first, and again
+    on exit, as a game restarted elsewhere maps a new one."""
+
+    def __init__(self, name):
+        self.name = name
+        self.path = os.path.join(tempfile.gettempdir(), f"monkey_shm_{name}")
+
+    def __enter__(self):
+        with contextlib.suppress(FileNotFoundError):
+            os.remove(self.path)
+        log_dir = os.path.join(tempfile.gettempdir(), "monkey_game_test_logs")
+        self.process = subprocess.Popen([GAME_BIN, "--headless", "--shm-name", self.name, "--log-dir", log_dir],
+                                        stdout=subprocess.DEVNULL, stderr=subprocess.DEVNULL)
+        # Up once its region is mapped and its heartbeat moves
+        self.shm = wait_until("the game's shared memory", self.open)
+        wait_until("the game's heartbeat", lambda: self.shm.read_game_structure()["game_heartbeat"] > 0)
+        return self
+
+    def open(self):
+        try:
+            return monkey_shared.SharedMemoryWrapper.open(self.name)
+        except OSError:
+            return None
+
+    def __exit__(self, *exc):
+        self.process.kill()
+        self.process.wait()
+        with contextlib.suppress(FileNotFoundError):
+            os.remove(self.path)
+
+
+@unittest.skipUnless(os.path.exists(GAME_BIN), f"no game binary at {GAME_BIN}")
+class GameTestCase(unittest.TestCase):
+    pass
+
+
+class TrialSchemaTest(unittest.TestCase):
+    def test_every_trial_field_has_a_schema_with_its_default(self):
+        schema = controller.trial_schema()
+        for key, default in controller.trial_from_json(controller.DEFAULT_CONFIG).items():
+            self.assertEqual(schema["properties"][key]["default"], default, key)
+        self.assertTrue(schema["additionalProperties"])
+
+    def test_bounds_come_from_the_game(self):
+        properties = controller.trial_schema()["properties"]
+        for key, fragment in controller.TRIAL_FIELD_SCHEMAS.items():
+            if key in properties:
+                self.assertEqual({k: v for k, v in properties[key].items() if k != "default"}, fragment, key)
+
+    def test_a_field_without_a_game_schema_is_permissive(self):
+        schemas = {k: v for k, v in controller.TRIAL_FIELD_SCHEMAS.items() if k != "start_orient"}
+        with mock.patch.object(controller, "TRIAL_FIELD_SCHEMAS", schemas):
+            properties = controller.trial_schema()["properties"]
+        self.assertEqual(properties["start_orient"], {"default": controller.DEFAULT_CONFIG["start_orient"]})
+
+
+class PushConfigTest(GameTestCase):
+    def test_pushed_trial_keeps_playing_after_the_controller_leaves(self):
+        trial = {**controller.DEFAULT_CONFIG, "target_door": 2, "base_radius": 2.75, "trial_id": "config-only"}
+        with HeadlessGame("test_controller_config_only") as game:
+            first_ack = game.shm.read_reset_ack()
+            first_generation = game.shm.read_trial_generation()
+            self.assertEqual(controller.push_config(json.dumps(trial), shm_name=game.name), 0)
+
+            ack = game.shm.read_reset_ack()
+            self.assertNotEqual(ack, first_ack)
+            state = game.shm.read_game_structure()
+            self.assertEqual(state["trial_generation"], first_generation + 1)
+            self.assertEqual(state["phase"], monkey_shared.PHASE_PLAYING)
+            self.assertEqual(state["target_door"], 2)
+            self.assertAlmostEqual(state["base_radius"], 2.75)
+            self.assertEqual(state["trial_id_hash"], monkey_shared.trial_id_hash("config-only"))
+
+            # The reset is released, so the game plays on without another one
+            frame = state["frame_number"]
+            wait_until("more frames", lambda: game.shm.read_game_structure()["frame_number"] > frame + 10)
+            self.assertEqual(game.shm.read_reset_ack(), ack)
+            self.assertFalse(game.shm.read_commands()["raw"]["reset"])
+
+    def test_fails_without_a_game(self):
+        name = "test_controller_config_only_no_game"
+        trial = json.dumps(controller.DEFAULT_CONFIG)
+        try:
+            self.assertEqual(controller.push_config(trial, timeout=0.2, shm_name=name), 1)
+        finally:
+            with contextlib.suppress(FileNotFoundError):
+                os.remove(os.path.join(tempfile.gettempdir(), f"monkey_shm_{name}"))
+
+    def test_rejects_an_invalid_trial(self):
+        self.assertEqual(controller.push_config("{not json", shm_name="test_controller_unused"), 1)
+
+
+class ControllerLink:
+    """MonkeyGameController's link to the game (heartbeat watch and reconnect) without its window"""
+    watch_game = controller.MonkeyGameController.watch_game
+    resume_after_reconnect = controller.MonkeyGameController.resume_after_reconnect
+    apply_session_settings = controller.MonkeyGameController.apply_session_settings
+
+    def __init__(self, shm_name, trial):
+        self.shm_wrapper = controller.SharedMemory(shm_name)
+        self.latched_motion = False
+        self.yaw_offset_degrees = 0.0
+        self.controller_timeout_frames = 0
+        self.blank_when_lost = False
+        self.current_trial = trial
+        self.current_trial_index = 3
+        self.triggers = {"reset": False, "promote_staged": False}
+        self.game_heartbeat = None
+        self.game_heartbeat_at = time.monotonic()
+        self.game_lost_at = None
+        self.next_reopen_at = 0.0
+        self.forced_resets = 0
+
+    def force_reset(self):
+        self.forced_resets += 1
+
+    def poll(self):
+        """One controller loop: watch the game, then write the triggers as the loop does"""
+        live = self.watch_game(self.shm_wrapper.read_game_state())
+        if live:
+            self.shm_wrapper.write_commands(False, False, False, False, False, self.triggers["reset"],
+                                            promote_staged=self.triggers["promote_staged"])
+            self.triggers["promote_staged"] = False
+        return live
+
+
+class ReconnectTest(GameTestCase):
+    @mock.patch.multiple(controller, GAME_LOST_SECS=0.2, RECONNECT_INTERVAL_SECS=0.05)
+    def test_a_restarted_game_gets_the_trial_that_was_playing(self):
+        name = "test_controller_reconnect"
+        trial = {**controller.DEFAULT_CONFIG, "target_door": 2, "trial_id": "reconnect"}
+        with HeadlessGame(name):
+            link = ControllerLink(name, trial)
+            wait_until("the game's heartbeat", lambda: link.poll() and link.game_heartbeat)
+        self.assertEqual(link.forced_resets, 0)
+
+        # The game is gone with its region: lost once the heartbeat stops, reopened every interval
+        wait_until("the game to count as lost", lambda: not link.poll())
+        self.assertFalse(link.shm_wrapper.reopen(), "no region to reopen")
+
+        # A new game maps a new region, which the link finds and resets into the trial
+        with HeadlessGame(name) as game:
+            last_ack = game.shm.read_reset_ack()
+            wait_until("the reconnect", lambda: link.poll() and link.forced_resets)
+            self.assertIsNone(link.game_lost_at)
+            self.assertTrue(link.triggers["reset"])
+            wait_until("the reset", lambda: link.poll() and game.shm.read_reset_ack() != last_ack)
+            link.triggers["reset"] = False
+            link.poll()
+
+            state = game.shm.read_game_structure()
+            self.assertEqual(state["target_door"], 2)
+            self.assertEqual(state["trial_id_hash"], monkey_shared.trial_id_hash("reconnect"))
+            self.assertEqual(link.current_trial_index, 3, "the interrupted trial is replayed")
+
+
+class SelfTestTest(GameTestCase):
+    def selftest(self, name):
+        """Exit status of `controller.py --selftest` on the region `name`"""
+        script = os.path.join(os.path.dirname(os.path.abspath(__file__)), "controller.py")
+        return subprocess.run([sys.executable, script, "--selftest", "--shm-name", name],
+                              stdout=subprocess.DEVNULL, timeout=TIMEOUT_SECS).returncode
+
+    def test_passes_against_the_headless_game(self):
+        with HeadlessGame("test_controller_selftest") as game:
+            self.assertEqual(self.selftest(game.name), 0)
+
+    def test_fails_without_a_game(self):
+        name = "test_controller_selftest_no_game"
+        try:
+            self.assertEqual(self.selftest(name), 2)
+        finally:
+            with contextlib.suppress(FileNotFoundError):
+                os.remove(os.path.join(tempfile.gettempdir(), f"monkey_shm_{name}"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,223 @@
+//! Command line arguments of the native game binary
+//! Window size or fullscreen, the monitor to open on, vsync, HiDPI scale, title and the shared
+//! memory name. Defaults match the usual rig: borderless fullscreen on the primary monitor with vsync.
+//! `--match-display` runs the fixed logic at the monitor's refresh rate (see display_rate.rs).
+//! `--headless` runs without any window (see headless.rs), ignoring the window options.
+//! `--stimulus-archive DIR` writes every trial's stimulus description to DIR (see stimulus.rs).
+//! `--lights FILE` replaces the overhead spotlight with the lights of a JSON file (see SetupConfig).
+//! `--autostart` plays the compiled-in trial at launch instead of standing by for a controller reset.
+//! `--log-dir DIR` writes the session log (see macros.rs) to DIR instead of `logs/`.
+//! `--profile [FILE]` times every reset into a CSV, in builds with the `profiling` feature (see profiling.rs).
+//!
+//! Monitors are only known once winit has enumerated them, so the window is moved onto the
+//! `--monitor` on the first frame, or an out of range one is reported (with the detected monitors)
+//! and the game exits with an error.
+
+use bevy::prelude::*;
+use bevy::window::{Monitor, MonitorSelection, PresentMode, PrimaryMonitor, PrimaryWindow, WindowMode, WindowPosition, WindowResolution};
+use clap::Parser;
+
+use crate::command_handler::{ShmName, DEFAULT_SHM_NAME};
+use crate::utils::setup::SetupConfig;
+use crate::utils::systems_logic::Autostart;
+use crate::utils::stimulus::StimulusArchive;
+use std::path::PathBuf;
+
+#[derive(Parser, Resource, Debug, Clone)]
+#[command(name = "game_node", about = "Monkey 3D Game node")]
+pub struct Cli {
+    /// Run in a WIDTHxHEIGHT window (physical pixels) instead of borderless fullscreen
+    #[arg(long, value_name = "WxH", value_parser = parse_window_size)]
+    pub windowed: Option<(u32, u32)>,
+    /// Monitor to open on, by index from left to right, then top to bottom (default: primary)
+    #[arg(long, value_name = "INDEX")]
+    pub monitor: Option<usize>,
+    /// Present without waiting for vsync
+    #[arg(long)]
+    pub no_vsync: bool,
+    /// Shared memory region to create, the controller must open the same name
+    #[arg(long, value_name = "NAME", default_value = DEFAULT_SHM_NAME)]
+    pub shm_name: String,
+    /// Window title
+    #[arg(long, default_value = "Monkey 3D Game")]
+    pub title: String,
+    /// Override the OS scale factor (1.0 maps one logical pixel to one physical pixel)
+    #[arg(long, value_name = "SCALE", value_parser = parse_hidpi_scale)]
+    pub hidpi_scale: Option<f32>,
+    /// Run the fixed update logic at the monitor's refresh rate instead of the nominal rate
+    #[arg(long)]
+    pub match_display: bool,
+    /// Run without a window, renderer or input, driven only through shared memory
+    #[arg(long)]
+    pub headless: bool,
+    /// Write each trial's complete stimulus description to a file in DIR (see stimulus.rs)
+    #[arg(long, value_name = "DIR")]
+    pub stimulus_archive: Option<PathBuf>,
+    /// Scene lights from a JSON file, `{"lights": [{"position": [x, y, z], ...}]}` (see setup.rs)
+    #[arg(long = "lights", value_name = "FILE", value_parser = parse_setup_config)]
+    pub setup_config: Option<SetupConfig>,
+    /// Start the compiled-in trial at launch instead of standing by until a controller resets (demo)
+    #[arg(long)]
+    pub autostart: bool,
+    /// Directory of the session log file (default: logs/ in the working directory)
+    #[arg(long, value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+    /// Time every reset's sections into a CSV (default: reset_profile.csv in the log directory)
+    #[cfg(feature = "profiling")]
+    #[arg(long, value_name = "FILE")]
+    pub profile: Option<Option<PathBuf>>,
+}
+
+impl Cli {
+    /// Primary window described by the arguments
+    pub fn window(&self) -> Window {
+        let monitor = self.monitor.map_or(MonitorSelection::Primary, MonitorSelection::Index);
+        let mut resolution = match self.windowed {
+            Some((width, height)) => WindowResolution::new(width, height),
+            None => WindowResolution::default(),
+        };
+        if let Some(scale) = self.hidpi_scale {
+            resolution = resolution.with_scale_factor_override(scale);
+        }
+
+        Window {
+            title: self.title.clone(),
+            fit_canvas_to_parent: true,
+            prevent_default_event_handling: true,
+            mode: match self.windowed {
+                Some(_) => WindowMode::Windowed,
+                None => WindowMode::BorderlessFullscreen(monitor),
+            },
+            position: WindowPosition::Centered(monitor),
+            resolution,
+            present_mode: if self.no_vsync { PresentMode::AutoNoVsync } else { PresentMode::AutoVsync },
+            ..default()
+        }
+    }
+}
+
+/// Shares the arguments with the app: shared memory name, launch log and monitor placement
+impl Plugin for Cli {
+    fn build(&self, app: &mut App) {
+        if let Some(dir) = &self.log_dir {
+            crate::utils::macros::set_log_dir(dir.clone());
+        }
+        app.insert_resource(ShmName(self.shm_name.clone()))
+            .insert_resource(self.clone())
+            .add_systems(Startup, log_launch_options)
+            .add_systems(Update, place_on_monitor);
+        if let Some(dir) = &self.stimulus_archive {
+            app.insert_resource(StimulusArchive(dir.clone()));
+        }
+        if let Some(setup_config) = &self.setup_config {
+            app.insert_resource(setup_config.clone());
+        }
+        if self.autostart {
+            app.insert_resource(Autostart);
+        }
+        #[cfg(feature = "profiling")]
+        if let Some(csv) = &self.profile {
+            let csv = csv.clone().unwrap_or_else(crate::profiling::default_profile_path);
+            app.add_plugins(crate::profiling::ProfilingPlugin { csv });
+        }
+    }
+}
+
+/// Parses `WIDTHxHEIGHT`, e.g. `1920x1080`
+pub fn parse_window_size(value: &str) -> Result<(u32, u32), String> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got '{}'", value))?;
+    let parse = |s: &str| s.trim().parse::<u32>().ok().filter(|v| *v > 0);
+    match (parse(width), parse(height)) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(format!("expected two positive integers as WIDTHxHEIGHT, got '{}'", value)),
+    }
+}
+
+fn parse_hidpi_scale(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(scale) if scale.is_finite() && scale > 0.0 => Ok(scale),
+        _ => Err(format!("expected a positive scale factor, got '{}'", value)),
+    }
+}
+
+/// Reads and parses a SetupConfig file, so a bad one fails at launch
+fn parse_setup_config(path: &str) -> Result<SetupConfig, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path, e))?;
+    SetupConfig::from_json(&json).map_err(|e| format!("'{}': {}", path, e))
+}
+
+fn log_launch_options(cli: Res<Cli>) {
+    if cli.headless {
+        info!("Launch: headless, shared memory '{}', autostart {}", cli.shm_name, cli.autostart);
+        return;
+    }
+    let window = match cli.windowed {
+        Some((width, height)) => format!("windowed {}x{}", width, height),
+        None => "borderless fullscreen".to_string(),
+    };
+    let monitor = cli.monitor.map_or("primary".to_string(), |index| index.to_string());
+    let scale = cli.hidpi_scale.map_or("OS".to_string(), |scale| scale.to_string());
+    let lights = cli.setup_config.as_ref().map_or("overhead spotlight".to_string(), |config| format!("{} configured", config.lights.len()));
+    info!(
+        "Launch: {} on monitor {}, vsync {}, HiDPI scale {}, title '{}', shared memory '{}', lights: {}, autostart {}",
+        window, monitor, !cli.no_vsync, scale, cli.title, cli.shm_name, lights, cli.autostart
+    );
+}
+
+/// `--monitor` order of two monitors: left to right, then top to bottom, then by name. Entities
+/// follow winit's enumeration order, which the OS does not keep between launches.
+pub fn monitor_order(a: &Monitor, b: &Monitor) -> std::cmp::Ordering {
+    (a.physical_position.x, a.physical_position.y, &a.name).cmp(&(b.physical_position.x, b.physical_position.y, &b.name))
+}
+
+/// Moves the window onto the `--monitor` in monitor_order, or exits with an error listing the
+/// detected monitors if it is out of range
+fn place_on_monitor(
+    cli: Res<Cli>,
+    monitors: Query<(Entity, &Monitor, Has<PrimaryMonitor>)>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut checked: Local<bool>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let Some(index) = cli.monitor else { return };
+    // Nothing to check against until winit has spawned the monitors
+    if *checked || monitors.is_empty() {
+        return;
+    }
+    *checked = true;
+
+    let mut detected: Vec<_> = monitors.iter().collect();
+    detected.sort_by(|(_, a, _), (_, b, _)| monitor_order(a, b));
+    if let Some((entity, _, _)) = detected.get(index) {
+        // The window opened on winit's index, which may be another monitor
+        let monitor = MonitorSelection::Entity(*entity);
+        for mut window in &mut windows {
+            if let WindowMode::BorderlessFullscreen(_) = window.mode {
+                window.mode = WindowMode::BorderlessFullscreen(monitor);
+            }
+            window.position = WindowPosition::Centered(monitor);
+        }
+        return;
+    }
+
+    let list: Vec<String> = detected
+        .iter()
+        .enumerate()
+        .map(|(i, (_, monitor, primary))| {
+            format!(
+                "  {}: {} {}x{} at {},{}{}",
+                i,
+                monitor.name.as_deref().unwrap_or("(unnamed)"),
+                monitor.physical_width,
+                monitor.physical_height,
+                monitor.physical_position.x,
+                monitor.physical_position.y,
+                if *primary { " (primary)" } else { "" }
+            )
+        })
+        .collect();
+    error!("--monitor {} is out of range, detected monitors:\n{}", index, list.join("\n"));
+    exit.write(AppExit::error());
+}
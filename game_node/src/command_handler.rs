@@ -6,11 +6,23 @@ use core::sync::atomic::Ordering;
 #[cfg(not(target_arch = "wasm32"))]
 use shared::create_shared_memory;
 use shared::constants::camera_3d_constants::{CAMERA_3D_SPEED_ROTATE, CAMERA_3D_SPEED_ZOOM};
-use shared::SharedMemoryHandle;
+use shared::{DebouncedCommand, SeenCommand, DEBOUNCED_COMMAND_COUNT, SharedCommands, SharedGameStructure, SharedMemoryHandle, NO_IMAGE_CHANGE, NO_LOG_CATEGORIES_CHANGE, NO_PROBE_FACE, NO_PYRAMID_TYPE_CHANGE, NO_RENDER_QUALITY_CHANGE};
+
+use crate::display_rate::DisplayRate;
+use crate::log_event;
+use crate::utils::macros::set_log_categories;
+use crate::utils::systems_logic::BlankScreenState;
+use core::time::Duration;
 
 #[derive(Resource)]
 pub struct SharedMemResource(pub SharedMemoryHandle);
 
+pub const DEFAULT_SHM_NAME: &str = "monkey_game";
+
+/// Name of the shared memory region created at startup, `DEFAULT_SHM_NAME` when absent
+#[derive(Resource)]
+pub struct ShmName(pub String);
+
 #[derive(Resource, Default)]
 pub struct PendingReset(pub bool);
 
@@ -32,6 +44,258 @@ pub struct RenderingPaused(pub bool);
 #[derive(Resource, Default)]
 pub struct PendingAnimation(pub bool);
 
+#[derive(Resource, Default)]
+pub struct DebugNormals(pub bool);
+
+#[derive(Resource, Default)]
+pub struct ShowThresholdCone(pub bool);
+
+#[derive(Resource, Default)]
+pub struct PendingDumpConfig(pub bool);
+
+#[derive(Resource, Default)]
+pub struct PendingDumpGeometry(pub bool);
+
+#[derive(Resource, Default)]
+pub struct PendingClearAttempts(pub bool);
+
+/// Staged next trial to apply when the inter-trial interval ends
+#[derive(Resource, Default)]
+pub struct PendingQueueNext(pub bool);
+
+/// Requested live pyramid type (PyramidType code)
+#[derive(Resource, Default)]
+pub struct PendingPyramidType(pub Option<u32>);
+
+/// Face whose decorations get the probe color, see handle_probe_command
+#[derive(Resource, Default)]
+pub struct PendingProbe(pub Option<u32>);
+
+/// Reference image to show (REFERENCE_IMAGES index) or HIDE_IMAGE, see apply_reference_image
+#[derive(Resource, Default)]
+pub struct PendingReferenceImage(pub Option<u32>);
+
+/// Requested render quality (RenderQuality code), see handle_render_quality_command
+#[derive(Resource, Default)]
+pub struct PendingRenderQuality(pub Option<u32>);
+
+/// Yaw (radians) held by the controller on top of the controls, see apply_global_yaw_offset
+#[derive(Resource, Default)]
+pub struct GlobalYawOffset(pub f32);
+
+/// Latched rotation and zoom directions (-1, 0 or 1, signed like the level commands) and the
+/// controller heartbeat watchdog that stops them when the controller goes quiet
+#[derive(Resource, Default)]
+pub struct LatchedMotion {
+    pub rotation: i32,
+    pub zoom: i32,
+    last_heartbeat: u32,
+    quiet_frames: u32,
+}
+
+impl LatchedMotion {
+    /// Applies this frame's starts and stops. Outside latched mode they are dropped and nothing moves
+    pub fn update(&mut self, commands: &SharedCommands, rate: &DisplayRate) {
+        let take = |flag: &core::sync::atomic::AtomicBool| flag.swap(false, Ordering::Relaxed);
+        let (rotate_stop, rotate_left, rotate_right) =
+            (take(&commands.rotate_stop), take(&commands.rotate_start_left), take(&commands.rotate_start_right));
+        let (zoom_stop, zoom_in, zoom_out) =
+            (take(&commands.zoom_stop), take(&commands.zoom_start_in), take(&commands.zoom_start_out));
+
+        let heartbeat = commands.heartbeat.load(Ordering::Relaxed);
+        if heartbeat != self.last_heartbeat {
+            self.last_heartbeat = heartbeat;
+            self.quiet_frames = 0;
+        } else {
+            self.quiet_frames = self.quiet_frames.saturating_add(1);
+        }
+
+        if !commands.motion_latched.load(Ordering::Relaxed) {
+            (self.rotation, self.zoom) = (0, 0);
+            return;
+        }
+
+        // Stops first, so a stop and a new start in the same frame leave the start
+        if rotate_stop {
+            self.rotation = 0;
+        }
+        if rotate_left {
+            self.rotation = -1;
+        }
+        if rotate_right {
+            self.rotation = 1;
+        }
+        if zoom_stop {
+            self.zoom = 0;
+        }
+        if zoom_in {
+            self.zoom = -1;
+        }
+        if zoom_out {
+            self.zoom = 1;
+        }
+
+        let timeout = rate.frames(commands.motion_latch_timeout_frames.load(Ordering::Relaxed));
+        if timeout > 0 && self.quiet_frames >= timeout && (self.rotation != 0 || self.zoom != 0) {
+            log_event!(error, "No controller heartbeat for {} frames, stopping latched motion", self.quiet_frames);
+            (self.rotation, self.zoom) = (0, 0);
+        }
+    }
+}
+
+/// Check requests counted so far from `SharedCommands::check_requests`, to tell the checks the
+/// controller asked for from the ones the game saw
+#[derive(Resource, Default)]
+pub struct CheckRequests {
+    seen: u32,
+    /// Whether the check flag was seen on the previous frame
+    check_was_seen: bool,
+    /// A check flag that rose before its request was counted, owed to the next request
+    early_check: bool,
+}
+
+impl CheckRequests {
+    /// Takes the request count read this frame and whether the check flag was seen with it, and
+    /// returns the requests dropped: all new ones but the one check the flag triggers. The controller
+    /// sets the flag before counting the request, so a flag rising with no new request is kept as a
+    /// credit for the request that follows
+    pub fn reconcile(&mut self, requests: u32, check_seen: bool) -> u32 {
+        let new = requests.wrapping_sub(self.seen);
+        self.seen = requests;
+        let rising = check_seen && !self.check_was_seen;
+        self.check_was_seen = check_seen;
+        if new == 0 {
+            self.early_check |= rising;
+            return 0;
+        }
+        let matched = u32::from(check_seen) + u32::from(core::mem::take(&mut self.early_check));
+        new.saturating_sub(matched)
+    }
+}
+
+/// Debounce windows of the trigger commands, see `SharedGameStructure::debounce_frames`
+#[derive(Resource, Default)]
+pub struct CommandDebounce {
+    /// Per DebouncedCommand, frames since its last accepted activation (None before the first)
+    since_accepted: [Option<u32>; DEBOUNCED_COMMAND_COUNT],
+    /// `reset_ack` of the trial the windows belong to
+    trial: u32,
+}
+
+impl CommandDebounce {
+    /// Starts a frame of the trial acknowledged as `trial` (its `reset_ack`): a new trial closes every window but the reset's,
+    /// so its first activations are never lost to the previous trial's. The reset window stays open,
+    /// it belongs to the reset that started the trial and a bounce of that reset must not reset again
+    pub fn tick(&mut self, trial: u32) {
+        for since in self.since_accepted.iter_mut().flatten() {
+            *since = since.saturating_add(1);
+        }
+        if trial != self.trial {
+            self.trial = trial;
+            let reset = self.since_accepted[DebouncedCommand::Reset as usize];
+            self.since_accepted = [None; DEBOUNCED_COMMAND_COUNT];
+            self.since_accepted[DebouncedCommand::Reset as usize] = reset;
+        }
+    }
+
+    /// Whether an activation of `command` read this frame is accepted, given its window in frames
+    pub fn accept(&mut self, command: DebouncedCommand, window: u32) -> bool {
+        let since = &mut self.since_accepted[command as usize];
+        if since.is_some_and(|since| since < window) {
+            return false;
+        }
+        *since = Some(0);
+        true
+    }
+}
+
+/// The commands one reader accepts on a frame, shared by the SHM and socket readers so a command is
+/// seen, debounced and reported back the same whichever path it came by
+pub(crate) struct CommandIntake<'a> {
+    /// Game region the reads are reported to, None without shared memory
+    gs_game: Option<&'a SharedGameStructure>,
+    debounce: &'a mut CommandDebounce,
+    rate: &'a DisplayRate,
+    /// SeenCommand bits read set so far this frame
+    seen: u32,
+}
+
+impl<'a> CommandIntake<'a> {
+    pub(crate) fn new(gs_game: Option<&'a SharedGameStructure>, debounce: &'a mut CommandDebounce, rate: &'a DisplayRate) -> Self {
+        Self { gs_game, debounce, rate, seen: 0 }
+    }
+
+    /// Records `command` as read this frame when `set`, and passes `set` on
+    pub(crate) fn see(&mut self, command: SeenCommand, set: bool) -> bool {
+        if set {
+            self.seen |= command.bit();
+        }
+        set
+    }
+
+    /// Whether a trigger read `set` is acted on. An activation inside its command's window is seen
+    /// but only counted in `debounced_count`
+    pub(crate) fn accept_trigger(&mut self, command: DebouncedCommand, set: bool) -> bool {
+        let seen = match command {
+            DebouncedCommand::CheckAlignment => SeenCommand::CheckAlignment,
+            DebouncedCommand::Reset => SeenCommand::Reset,
+            DebouncedCommand::BlankScreen => SeenCommand::BlankScreen,
+        };
+        if !self.see(seen, set) {
+            return false;
+        }
+        let Some(gs_game) = self.gs_game else { return true };
+        let window = self.rate.frames(gs_game.debounce_frames[command as usize].load(Ordering::Relaxed));
+        if self.debounce.accept(command, window) {
+            return true;
+        }
+        let total = gs_game.debounced_count.fetch_add(1, Ordering::Relaxed) + 1;
+        log_event!(commands, "{:?} activation debounced ({} this session)", command, total);
+        false
+    }
+
+    /// accept_trigger for check_alignment. A reader with a request count (read before the flag)
+    /// passes it to have the requests beyond the one check the flag triggers counted in `dropped_checks`
+    pub(crate) fn accept_check(&mut self, requests: Option<(&mut CheckRequests, u32)>, set: bool) -> bool {
+        let accepted = self.accept_trigger(DebouncedCommand::CheckAlignment, set);
+        if let (Some((check_requests, requests)), Some(gs_game)) = (requests, self.gs_game) {
+            let dropped = check_requests.reconcile(requests, set);
+            if dropped > 0 {
+                let total = gs_game.dropped_checks.fetch_add(dropped, Ordering::Relaxed) + dropped;
+                log_event!(commands, "{} check request(s) dropped before the game read them ({} this session)", dropped, total);
+            }
+        }
+        accepted
+    }
+
+    /// Reports the frame's reads in `commands_seen`. The first reader of the frame replaces the mask
+    /// and later ones add to it, with each command counted once per frame in `commands_seen_counts`
+    pub(crate) fn finish(self, first: bool) {
+        let Some(gs_game) = self.gs_game else { return };
+        let before = if first {
+            gs_game.commands_seen.store(self.seen, Ordering::Relaxed);
+            0
+        } else {
+            gs_game.commands_seen.fetch_or(self.seen, Ordering::Relaxed)
+        };
+        let new = self.seen & !before;
+        for (index, count) in gs_game.commands_seen_counts.iter().enumerate() {
+            if new & (1 << index) != 0 {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Controller heartbeat watchdog for the level commands, see `SharedCommands::controller_timeout_frames`
+#[derive(Resource, Default)]
+pub struct ControllerWatchdog {
+    last_heartbeat: u32,
+    quiet_frames: u32,
+    /// Real time the controller was declared lost at, while it is
+    lost_since: Option<Duration>,
+}
+
 pub struct CommandHandlerPlugin;
 
 impl Plugin for CommandHandlerPlugin {
@@ -41,30 +305,49 @@ impl Plugin for CommandHandlerPlugin {
             .init_resource::<PendingZoom>()
             .init_resource::<PendingCheckAlignment>()
             .init_resource::<PendingBlankScreen>()
-            .init_resource::<PendingBlankScreen>()
             .init_resource::<RenderingPaused>()
             .init_resource::<PendingAnimation>()
+            .init_resource::<DebugNormals>()
+            .init_resource::<ShowThresholdCone>()
+            .init_resource::<PendingDumpConfig>()
+            .init_resource::<PendingDumpGeometry>()
+            .init_resource::<PendingClearAttempts>()
+            .init_resource::<PendingQueueNext>()
+            .init_resource::<PendingPyramidType>()
+            .init_resource::<PendingRenderQuality>()
+            .init_resource::<PendingProbe>()
+            .init_resource::<PendingReferenceImage>()
+            .init_resource::<GlobalYawOffset>()
+            .init_resource::<LatchedMotion>()
+            .init_resource::<ControllerWatchdog>()
+            .init_resource::<CheckRequests>()
+            .init_resource::<CommandDebounce>()
+            .init_resource::<DisplayRate>()
             .add_systems(Startup, init_shared_memory_system)
             .add_systems(
                 PreUpdate,
-                (clear_pending_actions, read_shared_memory).chain(),
+                (clear_pending_actions, read_shared_memory, watch_controller_heartbeat).chain(),
             );
     }
 }
 
 #[cfg_attr(target_arch = "wasm32", allow(unused_variables, unused_mut))]
-fn init_shared_memory_system(mut commands: Commands) {
-    let name = "monkey_game";
+fn init_shared_memory_system(mut commands: Commands, existing: Option<Res<SharedMemResource>>, shm_name: Option<Res<ShmName>>) {
+    // Keep a handle inserted before startup (e.g. tests using their own region)
+    if existing.is_some() {
+        return;
+    }
+    let name = shm_name.as_ref().map_or(DEFAULT_SHM_NAME, |n| n.0.as_str());
 
     #[cfg(not(target_arch = "wasm32"))]
     {
         match create_shared_memory(name) {
             Ok(handle) => {
-                info!("Shared Memory initialized successfully.");
+                log_event!(shm, "Shared Memory '{}' initialized successfully.", name);
                 commands.insert_resource(SharedMemResource(handle));
             }
             Err(e) => {
-                error!("Failed to initialize shared memory: {}", e);
+                log_event!(error, "Failed to initialize shared memory: {}", e);
             }
         }
     }
@@ -89,7 +372,24 @@ fn clear_pending_actions(
     rendering_paused.0 = false;
 }
 
-fn read_shared_memory(
+/// Rotation and zoom step per frame, negated when the mirrored mappings are latched
+pub(crate) fn control_speeds(gs_game: Option<&SharedGameStructure>, rate: &DisplayRate) -> (f32, f32) {
+    let mirrored = |flag: fn(&SharedGameStructure) -> bool| gs_game.is_some_and(flag);
+    let rotate_speed = if mirrored(|gs| gs.control_mirror_rotation.load(Ordering::Relaxed)) {
+        -CAMERA_3D_SPEED_ROTATE
+    } else {
+        CAMERA_3D_SPEED_ROTATE
+    };
+    let zoom_speed = if mirrored(|gs| gs.control_mirror_zoom.load(Ordering::Relaxed)) {
+        -CAMERA_3D_SPEED_ZOOM
+    } else {
+        CAMERA_3D_SPEED_ZOOM
+    };
+    // Speeds are per nominal frame, the same per second at any display rate
+    (rate.per_frame(rotate_speed), rate.per_frame(zoom_speed))
+}
+
+pub(crate) fn read_shared_memory(
     shm_res: Option<Res<SharedMemResource>>,
     mut pending_reset: ResMut<PendingReset>,
     mut pending_rotation: ResMut<PendingRotation>,
@@ -98,46 +398,198 @@ fn read_shared_memory(
     mut pending_blank: ResMut<PendingBlankScreen>,
     mut rendering_paused: ResMut<RenderingPaused>,
     mut pending_anim: ResMut<PendingAnimation>,
+    (mut debug_normals, mut show_threshold_cone): (ResMut<DebugNormals>, ResMut<ShowThresholdCone>),
+    (mut pending_dump, mut pending_dump_geometry): (ResMut<PendingDumpConfig>, ResMut<PendingDumpGeometry>),
+    mut pending_clear_attempts: ResMut<PendingClearAttempts>,
+    mut pending_queue_next: ResMut<PendingQueueNext>,
+    (mut pending_pyramid_type, mut pending_probe, mut pending_image, mut check_requests, mut pending_quality): (
+        ResMut<PendingPyramidType>,
+        ResMut<PendingProbe>,
+        ResMut<PendingReferenceImage>,
+        ResMut<CheckRequests>,
+        ResMut<PendingRenderQuality>,
+    ),
+    mut global_yaw_offset: ResMut<GlobalYawOffset>,
+    (mut latched_motion, mut debounce, mut reset_held): (ResMut<LatchedMotion>, ResMut<CommandDebounce>, Local<bool>),
+    display_rate: Res<DisplayRate>,
 ) {
     let Some(shm_res) = shm_res else { return };
     let shm = shm_res.0.get();
+    let gs_game = &shm.game_structure_game;
+
+    // Bouncing triggers: an activation inside its command's window is counted, not acted on
+    debounce.tick(gs_game.reset_ack.load(Ordering::Relaxed));
+    let mut intake = CommandIntake::new(Some(gs_game), &mut debounce, &display_rate);
+
+    // Mirrored mappings come from the game region, so they only change at reset
+    let (rotate_speed, zoom_speed) = control_speeds(Some(&shm.game_structure_game), &display_rate);
 
     // Read commands from shared memory and apply pending
-    if shm.commands.rotate_left.load(Ordering::Relaxed) {
-        pending_rotation.0 -= CAMERA_3D_SPEED_ROTATE;
+    if intake.see(SeenCommand::RotateLeft, shm.commands.rotate_left.load(Ordering::Relaxed)) {
+        pending_rotation.0 -= rotate_speed;
     }
-    if shm.commands.rotate_right.load(Ordering::Relaxed) {
-        pending_rotation.0 += CAMERA_3D_SPEED_ROTATE;
+    if intake.see(SeenCommand::RotateRight, shm.commands.rotate_right.load(Ordering::Relaxed)) {
+        pending_rotation.0 += rotate_speed;
     }
-    if shm.commands.zoom_in.load(Ordering::Relaxed) {
-        pending_zoom.0 -= CAMERA_3D_SPEED_ZOOM;
+    if intake.see(SeenCommand::ZoomIn, shm.commands.zoom_in.load(Ordering::Relaxed)) {
+        pending_zoom.0 -= zoom_speed;
     }
-    if shm.commands.zoom_out.load(Ordering::Relaxed) {
-        pending_zoom.0 += CAMERA_3D_SPEED_ZOOM;
+    if intake.see(SeenCommand::ZoomOut, shm.commands.zoom_out.load(Ordering::Relaxed)) {
+        pending_zoom.0 += zoom_speed;
     }
 
+    // Latched motion keeps going between controller writes, on top of the level commands
+    latched_motion.update(&shm.commands, &display_rate);
+    pending_rotation.0 += latched_motion.rotation as f32 * rotate_speed;
+    pending_zoom.0 += latched_motion.zoom as f32 * zoom_speed;
+    shm.game_structure_game.latched_rotation.store(latched_motion.rotation, Ordering::Relaxed);
+    shm.game_structure_game.latched_zoom.store(latched_motion.zoom, Ordering::Relaxed);
+
     // Read Trigger Inputs (swap to clear after reading)
-    if shm.commands.check_alignment.load( Ordering::Relaxed) {
+    // The request count is read first: the controller bumps it after setting the flag
+    let requests = shm.commands.check_requests.load(Ordering::Acquire);
+    let check = shm.commands.check_alignment.load(Ordering::Relaxed);
+    if intake.accept_check(Some((&mut check_requests, requests)), check) {
         pending_check.0 = true;
     }
 
     // New rendering control commands
-    if shm.commands.blank_screen.load( Ordering::Relaxed) {
+    if intake.accept_trigger(DebouncedCommand::BlankScreen, shm.commands.blank_screen.load(Ordering::Relaxed)) {
         pending_blank.0 = true;
     }
-    if shm.commands.stop_rendering.load( Ordering::Relaxed) {
+    if intake.see(SeenCommand::StopRendering, shm.commands.stop_rendering.load(Ordering::Relaxed)) {
         rendering_paused.0 = true;
     }
-    if shm.commands.resume_rendering.load(Ordering::Relaxed) {
+    if intake.see(SeenCommand::ResumeRendering, shm.commands.resume_rendering.load(Ordering::Relaxed)) {
+        rendering_paused.0 = false;
+    }
+    // Single step: unpause for this tick only, stop_rendering re-pauses on the next one.
+    // Swapped so a step held across several frames still advances exactly one.
+    if intake.see(SeenCommand::StepFrame, shm.commands.step_frame.swap(false, Ordering::Relaxed)) && rendering_paused.0 {
         rendering_paused.0 = false;
     }
 
-    if shm.commands.animation_door.load(Ordering::Relaxed) {
+    if intake.see(SeenCommand::AnimationDoor, shm.commands.animation_door.load(Ordering::Relaxed)) {
         pending_anim.0 = true;
     }
 
-    if shm.commands.reset.load(Ordering::Relaxed) {
+    // Reset is read before the promotion: the controller latches promote_staged before setting
+    // reset, so a reset seen here always comes with its staged config. A controller holds it until
+    // the acknowledgement, so only the frame it is set on resets (and is acknowledged)
+    let reset = intake.see(SeenCommand::Reset, shm.commands.reset.load(Ordering::Acquire));
+    if intake.accept_trigger(DebouncedCommand::Reset, reset && !*reset_held) {
         pending_reset.0 = true;
     }
+    *reset_held = reset;
+    // Cleared only after the copy, since the controller waits for it before writing the next staged config
+    if intake.see(SeenCommand::PromoteStaged, shm.commands.promote_staged.load(Ordering::Acquire)) {
+        shm.game_structure_control.reset_all_fields(&shm.game_structure_staged);
+        shm.commands.promote_staged.store(false, Ordering::Release);
+    }
+
+    // Debug overlay stays on while the flag is held
+    debug_normals.0 = intake.see(SeenCommand::DebugNormals, shm.commands.debug_normals.load(Ordering::Relaxed));
+    show_threshold_cone.0 = intake.see(SeenCommand::ShowThresholdCone, shm.commands.show_threshold_cone.load(Ordering::Relaxed));
+
+    // Consumed on read, so each request dumps exactly one line (the handler clears the pending flag)
+    if intake.see(SeenCommand::DumpConfig, shm.commands.dump_config.swap(false, Ordering::Relaxed)) {
+        pending_dump.0 = true;
+    }
+    if intake.see(SeenCommand::DumpGeometry, shm.commands.dump_geometry.swap(false, Ordering::Relaxed)) {
+        pending_dump_geometry.0 = true;
+    }
+    if intake.see(SeenCommand::ClearAttempts, shm.commands.clear_attempts.swap(false, Ordering::Relaxed)) {
+        pending_clear_attempts.0 = true;
+    }
+    if intake.see(SeenCommand::QueueNext, shm.commands.queue_next.swap(false, Ordering::Relaxed)) {
+        pending_queue_next.0 = true;
+    }
+    let pyramid_type = shm.commands.set_pyramid_type.swap(NO_PYRAMID_TYPE_CHANGE, Ordering::Relaxed);
+    if intake.see(SeenCommand::SetPyramidType, pyramid_type != NO_PYRAMID_TYPE_CHANGE) {
+        pending_pyramid_type.0 = Some(pyramid_type);
+    }
+    // Acquire: the probe color and duration are written before the face
+    let probe_face = shm.commands.probe_face.swap(NO_PROBE_FACE, Ordering::Acquire);
+    if intake.see(SeenCommand::ProbeFace, probe_face != NO_PROBE_FACE) {
+        pending_probe.0 = Some(probe_face);
+    }
+    let show_image = shm.commands.show_image.swap(NO_IMAGE_CHANGE, Ordering::Relaxed);
+    if intake.see(SeenCommand::ShowImage, show_image != NO_IMAGE_CHANGE) {
+        pending_image.0 = Some(show_image);
+    }
+    let render_quality = shm.commands.render_quality.swap(NO_RENDER_QUALITY_CHANGE, Ordering::Relaxed);
+    if intake.see(SeenCommand::RenderQuality, render_quality != NO_RENDER_QUALITY_CHANGE) {
+        pending_quality.0 = Some(render_quality);
+    }
+    // Held, like debug_normals; compared first so the resource only changes with the offset
+    let yaw_offset = f32::from_bits(shm.commands.global_yaw_offset.load(Ordering::Relaxed));
+    let yaw_offset = if yaw_offset.is_finite() { yaw_offset } else { 0.0 };
+    if global_yaw_offset.0 != yaw_offset {
+        global_yaw_offset.0 = yaw_offset;
+    }
+    let log_categories = shm.commands.log_categories.swap(NO_LOG_CATEGORIES_CHANGE, Ordering::Relaxed);
+    if intake.see(SeenCommand::LogCategories, log_categories != NO_LOG_CATEGORIES_CHANGE) {
+        set_log_categories(log_categories);
+        log_event!(commands, "Log categories set to {:#07b}", log_categories);
+    }
+
+    // What the game read, for controllers to compare with what they wrote
+    intake.finish(true);
+}
+
+/// Stops the camera once the controller goes quiet: a controller hung while holding a rotate or zoom
+/// flag would otherwise keep it moving forever. The flags are cleared in shared memory, so motion
+/// only resumes once the controller writes them again
+pub(crate) fn watch_controller_heartbeat(
+    shm_res: Option<Res<SharedMemResource>>,
+    mut watchdog: ResMut<ControllerWatchdog>,
+    mut pending_rotation: ResMut<PendingRotation>,
+    mut pending_zoom: ResMut<PendingZoom>,
+    mut pending_blank: ResMut<PendingBlankScreen>,
+    mut latched_motion: ResMut<LatchedMotion>,
+    blank_state: Option<Res<BlankScreenState>>,
+    display_rate: Res<DisplayRate>,
+    time: Res<Time<Real>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let shm = shm_res.0.get();
+    let commands = &shm.commands;
+    let gs_game = &shm.game_structure_game;
+
+    let heartbeat = commands.heartbeat.load(Ordering::Relaxed);
+    if heartbeat != watchdog.last_heartbeat {
+        watchdog.last_heartbeat = heartbeat;
+        watchdog.quiet_frames = 0;
+        if let Some(since) = watchdog.lost_since.take() {
+            gs_game.controller_lost.store(false, Ordering::Relaxed);
+            log_event!(shm, "Controller heartbeat back after {:.2} s", (time.elapsed() - since).as_secs_f32());
+        }
+        return;
+    }
+    watchdog.quiet_frames = watchdog.quiet_frames.saturating_add(1);
 
+    let timeout = display_rate.frames(commands.controller_timeout_frames.load(Ordering::Relaxed));
+    if timeout == 0 || watchdog.quiet_frames < timeout {
+        return;
+    }
+
+    if watchdog.lost_since.is_none() {
+        watchdog.lost_since = Some(time.elapsed());
+        gs_game.controller_lost.store(true, Ordering::Relaxed);
+        gs_game.controller_outages.fetch_add(1, Ordering::Relaxed);
+        log_event!(error, "No controller heartbeat for {} frames, stopping the camera", watchdog.quiet_frames);
+        // Blanking toggles, so only request it when the screen is not blank already
+        if commands.controller_lost_blank.load(Ordering::Relaxed) && !blank_state.is_some_and(|state| state.is_active) {
+            pending_blank.0 = true;
+        }
+    }
+
+    for flag in [&commands.rotate_left, &commands.rotate_right, &commands.zoom_in, &commands.zoom_out] {
+        flag.store(false, Ordering::Relaxed);
+    }
+    pending_rotation.0 = 0.0;
+    pending_zoom.0 = 0.0;
+    (latched_motion.rotation, latched_motion.zoom) = (0, 0);
+    gs_game.latched_rotation.store(0, Ordering::Relaxed);
+    gs_game.latched_zoom.store(0, Ordering::Relaxed);
 }
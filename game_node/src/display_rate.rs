@@ -0,0 +1,100 @@
+//! Display refresh rate
+//! The game is tuned for REFRESH_RATE_HZ, but rigs with 120/144 Hz panels render (and count frames)
+//! faster under vsync. The refresh rate of the game's monitor is read once winit has enumerated the
+//! monitors, kept in `DisplayRate` and emitted as `display_hz`. Frame counts from the config
+//! (the motion latch timeout) and per frame speeds are nominal and converted to it. The inter-trial
+//! interval (`iti_frames`) counts fixed ticks instead.
+//!
+//! With `--match-display` the fixed timestep is set to the display rate as well, otherwise the fixed
+//! logic keeps running at REFRESH_RATE_HZ (see fixed_rate_diagnostics.rs for the divergence warning).
+
+use bevy::prelude::*;
+use bevy::window::{Monitor, PrimaryMonitor};
+use core::sync::atomic::Ordering;
+
+use crate::cli::monitor_order;
+use crate::command_handler::SharedMemResource;
+use shared::constants::game_constants::REFRESH_RATE_HZ;
+use shared::constants::timing::frames_at_rate;
+
+/// Refresh rate of the game's monitor, None until detected (and without a window)
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct DisplayRate {
+    pub display_hz: Option<f64>,
+}
+
+impl DisplayRate {
+    /// Rate frames are counted at: the display rate once known, the nominal rate before
+    pub fn frame_hz(&self) -> f64 {
+        self.display_hz.unwrap_or(REFRESH_RATE_HZ)
+    }
+
+    /// Frames at the active rate lasting as long as `frames` nominal frames. Windows and timeouts are
+    /// configured in nominal frames and converted here, so they last as long at any display rate
+    pub fn frames(&self, frames: u32) -> u32 {
+        frames_at_rate(frames, self.frame_hz())
+    }
+
+    /// Scales an amount per nominal frame (speeds) so it adds up to the same amount per second
+    pub fn per_frame(&self, amount: f32) -> f32 {
+        amount * (REFRESH_RATE_HZ / self.frame_hz()) as f32
+    }
+}
+
+/// Which monitor to read and whether to match the fixed timestep to it (from the command line)
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct DisplayRatePlugin {
+    /// Monitor index the window opens on, the primary monitor when None
+    pub monitor: Option<usize>,
+    /// Run the fixed logic at the display rate instead of REFRESH_RATE_HZ
+    pub match_display: bool,
+}
+
+impl Plugin for DisplayRatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DisplayRate>()
+            .insert_resource(*self)
+            .add_systems(Update, detect_display_rate);
+    }
+}
+
+/// Reads the refresh rate of the window's monitor once the monitors are known
+fn detect_display_rate(
+    settings: Res<DisplayRatePlugin>,
+    monitors: Query<(&Monitor, Has<PrimaryMonitor>)>,
+    mut rate: ResMut<DisplayRate>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    shm_res: Option<Res<SharedMemResource>>,
+    mut detected: Local<bool>,
+) {
+    if *detected || monitors.is_empty() {
+        return;
+    }
+    *detected = true;
+
+    // Same order as the CLI places the window in, an out of range index is reported there
+    let monitor = match settings.monitor {
+        Some(index) => {
+            let mut listed: Vec<_> = monitors.iter().map(|(monitor, _)| monitor).collect();
+            listed.sort_by(|a, b| monitor_order(a, b));
+            listed.get(index).copied()
+        }
+        None => monitors.iter().find(|(_, primary)| *primary).map(|(monitor, _)| monitor),
+    };
+    let Some(millihertz) = monitor.and_then(|monitor| monitor.refresh_rate_millihertz).filter(|mhz| *mhz > 0) else {
+        warn!("Display refresh rate unknown, frame counts stay at the nominal {} Hz", REFRESH_RATE_HZ);
+        return;
+    };
+
+    let hz = millihertz as f64 / 1000.0;
+    rate.display_hz = Some(hz);
+    if let Some(shm_res) = shm_res {
+        shm_res.0.get().game_structure_game.display_hz.store((hz as f32).to_bits(), Ordering::Relaxed);
+    }
+    if settings.match_display {
+        fixed_time.set_timestep_hz(hz);
+        info!("Display refresh rate {:.2} Hz, fixed update rate matched to it", hz);
+    } else {
+        info!("Display refresh rate {:.2} Hz, fixed update rate {} Hz", hz, REFRESH_RATE_HZ);
+    }
+}
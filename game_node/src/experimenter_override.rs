@@ -0,0 +1,130 @@
+//! Experimenter override
+//! Keyboard fallback for when the controller is unresponsive. Ctrl+Shift+E enters override mode,
+//! shown by a red border: B blanks/unblanks, P/O pause/resume, R resets into the config in the
+//! controller region (the last one written or promoted) and Esc leaves. Keys do nothing outside it.
+//!
+//! Actions go through the same pending resources as the shared memory commands, and every
+//! activation and action is logged and counted in the game region so analysis can flag the session.
+
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystems;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+
+use crate::command_handler::{read_shared_memory, PendingBlankScreen, PendingReset, RenderingPaused, SharedMemResource};
+use shared::constants::game_constants::{OVERRIDE_BORDER_COLOR, OVERRIDE_BORDER_THICKNESS};
+
+/// Whether override mode is on, and whether it holds the game paused
+#[derive(Resource, Default)]
+pub struct ExperimenterOverride {
+    pub active: bool,
+    pub paused: bool,
+}
+
+/// Marker component for the override mode border
+#[derive(Component)]
+pub struct OverrideBorder;
+
+/// Pending resources an override action writes into
+#[derive(SystemParam)]
+struct OverridePending<'w> {
+    reset: ResMut<'w, PendingReset>,
+    blank: ResMut<'w, PendingBlankScreen>,
+    rendering_paused: ResMut<'w, RenderingPaused>,
+}
+
+pub struct ExperimenterOverridePlugin;
+
+impl Plugin for ExperimenterOverridePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExperimenterOverride>()
+            .add_systems(PreUpdate, handle_experimenter_keys.after(read_shared_memory).after(InputSystems));
+    }
+}
+
+fn handle_experimenter_keys(
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    mut state: ResMut<ExperimenterOverride>,
+    mut pending: OverridePending,
+    shm_res: Option<Res<SharedMemResource>>,
+    mut commands: Commands,
+    border_query: Query<Entity, With<OverrideBorder>>,
+) {
+    let Some(keys) = keys else { return };
+    let gs_game = shm_res.as_ref().map(|shm| &shm.0.get().game_structure_game);
+
+    if !state.active {
+        let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if ctrl && shift && keys.just_pressed(KeyCode::KeyE) {
+            state.active = true;
+            if let Some(gs_game) = gs_game {
+                gs_game.override_activations.fetch_add(1, Ordering::Relaxed);
+            }
+            warn!("Experimenter override: activated");
+            spawn_override_border(&mut commands);
+        }
+        return;
+    }
+
+    let action = |name: &str| {
+        if let Some(gs_game) = gs_game {
+            gs_game.override_actions.fetch_add(1, Ordering::Relaxed);
+        }
+        warn!("Experimenter override: {}", name);
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        // Pausing is handed back to the controller
+        *state = ExperimenterOverride::default();
+        for entity in &border_query {
+            commands.entity(entity).despawn();
+        }
+        warn!("Experimenter override: deactivated");
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyB) {
+        pending.blank.0 = true;
+        action("toggle blank");
+    }
+    if keys.just_pressed(KeyCode::KeyP) {
+        state.paused = true;
+        action("pause");
+    }
+    if keys.just_pressed(KeyCode::KeyO) {
+        state.paused = false;
+        pending.rendering_paused.0 = false;
+        action("resume");
+    }
+    if keys.just_pressed(KeyCode::KeyR) {
+        pending.reset.0 = true;
+        action("reset");
+    }
+
+    // Held every frame, since the pending pause is cleared each frame like the SHM flag
+    if state.paused {
+        pending.rendering_paused.0 = true;
+    }
+}
+
+fn spawn_override_border(commands: &mut Commands) {
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            top: Val::Px(0.0),
+            border: UiRect::all(Val::Px(OVERRIDE_BORDER_THICKNESS)),
+            ..default()
+        },
+        BorderColor::all(Color::srgba(
+            OVERRIDE_BORDER_COLOR[0],
+            OVERRIDE_BORDER_COLOR[1],
+            OVERRIDE_BORDER_COLOR[2],
+            OVERRIDE_BORDER_COLOR[3],
+        )),
+        GlobalZIndex(1002), // Above the blank overlay and the reference image
+        OverrideBorder,
+    ));
+}
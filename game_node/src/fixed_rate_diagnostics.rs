@@ -0,0 +1,148 @@
+//! Fixed update rate diagnostic
+//! Counts fixed updates and rendered frames against real time over a rolling window, so frame drops
+//! that the fixed timestep cannot catch up on show as a rate below the configured fixed rate
+//! (REFRESH_RATE_HZ, or the display rate with --match-display). The rate is registered as a Bevy
+//! diagnostic (`fixed_update_hz`), emitted as `measured_hz` next to the configured `fixed_hz`, and a
+//! warning is logged when the drift stays beyond the tolerance. Rendering at a different rate than
+//! the fixed logic (a 144 Hz panel under vsync) is warned about the same way.
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use std::collections::VecDeque;
+
+use crate::command_handler::SharedMemResource;
+use shared::constants::game_constants::{FIXED_RATE_DRIFT_TOLERANCE, FIXED_RATE_SUSTAIN_SECS, FIXED_RATE_WINDOW_SECS};
+
+pub const FIXED_UPDATE_HZ: DiagnosticPath = DiagnosticPath::const_new("fixed_update_hz");
+
+/// Fixed updates and rendered frames counted so far and the (real time, fixed count, frame count)
+/// samples of the current window
+#[derive(Resource, Default)]
+pub struct FixedRateMonitor {
+    fixed_updates: u64,
+    frames: u64,
+    samples: VecDeque<(Duration, u64, u64)>,
+    render_hz: Option<f32>,
+    drift: SustainedDrift,
+    divergence: SustainedDrift,
+}
+
+impl FixedRateMonitor {
+    pub fn count_fixed_update(&mut self) {
+        self.fixed_updates += 1;
+    }
+
+    /// Samples the counts at real time `now`, once per rendered frame, returning the fixed rate over
+    /// the last window once one has passed
+    pub fn sample(&mut self, now: Duration) -> Option<f32> {
+        let window = Duration::from_secs_f32(FIXED_RATE_WINDOW_SECS);
+        self.frames += 1;
+        self.samples.push_back((now, self.fixed_updates, self.frames));
+        // Keep the newest sample that is at least a window old as the start
+        while self.samples.len() > 2 && now - self.samples[1].0 >= window {
+            self.samples.pop_front();
+        }
+
+        let (start, start_count, start_frames) = *self.samples.front()?;
+        let span = (now - start).as_secs_f32();
+        if span < window.as_secs_f32() {
+            return None;
+        }
+        self.render_hz = Some((self.frames - start_frames) as f32 / span);
+        Some((self.fixed_updates - start_count) as f32 / span)
+    }
+
+    /// Rendered frames per second over the last window, None before the first window has passed
+    pub fn render_rate(&self) -> Option<f32> {
+        self.render_hz
+    }
+}
+
+/// When a relative drift started exceeding the tolerance and whether it has been warned about
+#[derive(Default)]
+struct SustainedDrift {
+    since: Option<Duration>,
+    warned: bool,
+}
+
+enum DriftChange {
+    /// Beyond the tolerance for the sustain time, for the given seconds
+    Sustained(f32),
+    /// Back within the tolerance after a warning
+    Recovered,
+}
+
+impl SustainedDrift {
+    fn track(&mut self, now: Duration, drift: f32) -> Option<DriftChange> {
+        if drift.abs() <= FIXED_RATE_DRIFT_TOLERANCE {
+            self.since = None;
+            return core::mem::take(&mut self.warned).then_some(DriftChange::Recovered);
+        }
+        let lasted = (now - *self.since.get_or_insert(now)).as_secs_f32();
+        if self.warned || lasted < FIXED_RATE_SUSTAIN_SECS {
+            return None;
+        }
+        self.warned = true;
+        Some(DriftChange::Sustained(lasted))
+    }
+}
+
+pub struct FixedRateDiagnosticsPlugin;
+
+impl Plugin for FixedRateDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedRateMonitor>()
+            .register_diagnostic(Diagnostic::new(FIXED_UPDATE_HZ).with_suffix(" Hz"))
+            // Counted whether or not the game is paused, the fixed loop itself keeps running
+            .add_systems(FixedUpdate, |mut monitor: ResMut<FixedRateMonitor>| monitor.count_fixed_update())
+            .add_systems(Update, measure_fixed_rate);
+    }
+}
+
+fn measure_fixed_rate(
+    time: Res<Time<Real>>,
+    fixed_time: Res<Time<Fixed>>,
+    mut monitor: ResMut<FixedRateMonitor>,
+    mut diagnostics: Diagnostics,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let now = time.elapsed();
+    let fixed_hz = 1.0 / fixed_time.timestep().as_secs_f32();
+    let gs_game = shm_res.as_ref().map(|shm_res| &shm_res.0.get().game_structure_game);
+    if let Some(gs_game) = gs_game {
+        gs_game.fixed_hz.store(fixed_hz.to_bits(), Ordering::Relaxed);
+    }
+    let Some(hz) = monitor.sample(now) else { return };
+
+    diagnostics.add_measurement(&FIXED_UPDATE_HZ, || hz as f64);
+    if let Some(gs_game) = gs_game {
+        gs_game.measured_hz.store(hz.to_bits(), Ordering::Relaxed);
+    }
+
+    let drift = hz / fixed_hz - 1.0;
+    match monitor.drift.track(now, drift) {
+        Some(DriftChange::Sustained(secs)) => warn!(
+            "Fixed update rate {:.2} Hz, {:+.1}% off the configured {:.2} Hz for {:.1}s: frame timing is unreliable",
+            hz,
+            drift * 100.0,
+            fixed_hz,
+            secs
+        ),
+        Some(DriftChange::Recovered) => info!("Fixed update rate back to {:.2} Hz", hz),
+        None => {}
+    }
+
+    let Some(render_hz) = monitor.render_rate() else { return };
+    let divergence = render_hz / fixed_hz - 1.0;
+    match monitor.divergence.track(now, divergence) {
+        Some(DriftChange::Sustained(secs)) => warn!(
+            "Rendering at {:.2} Hz but the fixed logic runs at {:.2} Hz for {:.1}s: run with --match-display \
+             to align them",
+            render_hz, fixed_hz, secs
+        ),
+        Some(DriftChange::Recovered) => info!("Render rate back in line with the fixed rate ({:.2} Hz)", render_hz),
+        None => {}
+    }
+}
@@ -0,0 +1,77 @@
+//! Frame drop detection
+//! Measures the real time of every rendered frame against the expected period at the display rate
+//! (see display_rate.rs). Frames longer than FRAME_LATE_FACTOR periods are late, longer than
+//! FRAME_DROP_FACTOR periods dropped (a vsync interval was missed). The counts and the worst frame
+//! time are kept per trial, starting over at every reset (the frame spanning the reset counts for
+//! neither trial), and emitted as `late_frames_trial`,
+//! `dropped_frames_trial` and `worst_frame_ms` so timing-sensitive trials can be flagged.
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+
+use crate::command_handler::SharedMemResource;
+use crate::display_rate::DisplayRate;
+use crate::log_event;
+use shared::constants::game_constants::{FRAME_DROP_FACTOR, FRAME_LATE_FACTOR};
+
+/// Late and dropped frames and the longest frame of the current trial
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct FrameDropStats {
+    pub late_frames: u32,
+    pub dropped_frames: u32,
+    pub worst_frame: Duration,
+    /// reset_ack of the trial the counts belong to
+    reset_ack: u32,
+}
+
+impl FrameDropStats {
+    /// Classifies one frame of real time `delta` against the expected `period`
+    pub fn record(&mut self, delta: Duration, period: Duration) {
+        self.worst_frame = self.worst_frame.max(delta);
+        if delta > period.mul_f32(FRAME_DROP_FACTOR) {
+            self.dropped_frames += 1;
+        } else if delta > period.mul_f32(FRAME_LATE_FACTOR) {
+            self.late_frames += 1;
+        }
+    }
+}
+
+pub struct FrameDropPlugin;
+
+impl Plugin for FrameDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameDropStats>()
+            .init_resource::<DisplayRate>()
+            .add_systems(Update, detect_frame_drops);
+    }
+}
+
+fn detect_frame_drops(
+    time: Res<Time<Real>>,
+    display_rate: Res<DisplayRate>,
+    mut stats: ResMut<FrameDropStats>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    // A new trial starts counting from scratch. Its first delta spans the reset frame, setup_round
+    // included, so it is left out rather than flagged as a drop of the new trial
+    let reset_ack = gs_game.reset_ack.load(Ordering::Acquire);
+    if reset_ack != stats.reset_ack {
+        *stats = FrameDropStats { reset_ack, ..default() };
+    } else {
+        let dropped = stats.dropped_frames;
+        let period = Duration::from_secs_f64(1.0 / display_rate.frame_hz());
+        stats.record(time.delta(), period);
+        if stats.dropped_frames > dropped {
+            log_event!(error, "Dropped frame: {:.1} ms (expected {:.1} ms)", time.delta_secs() * 1000.0, period.as_secs_f32() * 1000.0);
+        }
+    }
+
+    gs_game.late_frames_trial.store(stats.late_frames, Ordering::Relaxed);
+    gs_game.dropped_frames_trial.store(stats.dropped_frames, Ordering::Relaxed);
+    let worst_ms = stats.worst_frame.as_secs_f32() * 1000.0;
+    gs_game.worst_frame_ms.store(worst_ms.to_bits(), Ordering::Relaxed);
+}
@@ -0,0 +1,41 @@
+//! Headless game
+//! The command handler, game logic and state emitter over shared memory, with no window, renderer,
+//! cursor or keyboard. Frames run at `REFRESH_RATE_HZ` from the schedule runner, so CI and batch
+//! stimulus checks can drive full trials through the controller API (`--headless`).
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::command_handler::CommandHandlerPlugin;
+use crate::fixed_rate_diagnostics::FixedRateDiagnosticsPlugin;
+use crate::frame_drops::FrameDropPlugin;
+use crate::state_emitter::StateEmitterPlugin;
+use crate::utils::objects::{DoorWinEntities, RoundStartTimestamp};
+use crate::utils::systems_logic::SystemsLogicPlugin;
+use shared::constants::game_constants::REFRESH_RATE_HZ;
+
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / REFRESH_RATE_HZ))),
+            AssetPlugin::default(),
+        ))
+        // Meshes and materials are still created by the round setup, they are just never rendered
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .init_resource::<UiScale>()
+        .insert_resource(Time::<Fixed>::from_hz(REFRESH_RATE_HZ))
+        .insert_resource(DoorWinEntities::default())
+        .insert_resource(RoundStartTimestamp::default())
+        .add_plugins((
+            CommandHandlerPlugin,
+            SystemsLogicPlugin,
+            StateEmitterPlugin,
+            FixedRateDiagnosticsPlugin,
+            FrameDropPlugin,
+        ));
+    }
+}
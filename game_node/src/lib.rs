@@ -1,4 +1,6 @@
 //! Declaration of the utils modules for monkey_3d_game.
+// Bevy systems take their resources and queries as arguments
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -10,9 +12,43 @@ use bevy::{
     window::*,
 };
 
+// Re-export the JSON controller API for WASM, driven with the Python controller's schema
+#[cfg(target_arch = "wasm32")]
+pub use shared::{command, read_config_json, read_state_json, write_config_json};
+
+/// Command line arguments of the native game binary
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+
 /// Command handler for receiving commands from the Controller
 pub mod command_handler;
 
+/// Optional command reader over a Unix domain socket
+#[cfg(all(feature = "socket_commands", unix))]
+pub mod socket_commands;
+
+/// Optional read-only HTTP status endpoint for monitoring
+#[cfg(all(feature = "status_http", not(target_arch = "wasm32")))]
+pub mod status_http;
+
+/// Timing of the reset sections, to find reset hitches (timed in builds with the `profiling` feature)
+pub mod profiling;
+
+/// Refresh rate of the game's monitor and frame counts converted to it
+pub mod display_rate;
+
+/// Experimenter keyboard override for when the Controller is unresponsive
+pub mod experimenter_override;
+
+/// Measured fixed update rate and its drift from the nominal rate
+pub mod fixed_rate_diagnostics;
+
+/// Late and dropped frames per trial
+pub mod frame_drops;
+
+/// Windowless app for CI and batch runs over shared memory
+pub mod headless;
+
 /// State emitter for sending game state to the Controller
 pub mod state_emitter;
 
@@ -21,12 +57,17 @@ pub mod web_adapter;
 
 /// Various utility functions, constants, and objects
 pub mod utils {
+    pub mod alignment;
     pub mod camera;
     pub mod debug_functions;
+    pub mod distractors;
     pub mod game_functions;
+    pub mod geometry_dump;
     pub mod macros;
+    pub mod mirror;
     pub mod objects;
     pub mod pyramid;
     pub mod setup;
+    pub mod stimulus;
     pub mod systems_logic;
 }
\ No newline at end of file
@@ -12,10 +12,18 @@ use bevy::{
 use shared::{create_shared_memory_wasm, WebSharedMemory};
 
 
+#[cfg(not(target_arch = "wasm32"))]
+use clap::Parser;
+
 use shared::constants::game_constants::REFRESH_RATE_HZ;
 
 use game_node::{
     command_handler::CommandHandlerPlugin,
+    display_rate::DisplayRatePlugin,
+    experimenter_override::ExperimenterOverridePlugin,
+    fixed_rate_diagnostics::FixedRateDiagnosticsPlugin,
+    frame_drops::FrameDropPlugin,
+    headless::HeadlessPlugin,
     state_emitter::StateEmitterPlugin,
     web_adapter::WebAdapterPlugin,
     utils::{
@@ -27,14 +35,27 @@ use game_node::{
 
 /// Entry point for the application
 fn main() {
+    // Native builds take the window and shared memory settings from the command line (see cli.rs)
+    #[cfg(not(target_arch = "wasm32"))]
+    let cli = game_node::cli::Cli::parse();
+    #[cfg(not(target_arch = "wasm32"))]
+    if cli.headless {
+        run_headless(cli);
+        return;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let window = Some(cli.window());
+    #[cfg(not(target_arch = "wasm32"))]
+    let display_rate = DisplayRatePlugin { monitor: cli.monitor, match_display: cli.match_display };
+    #[cfg(target_arch = "wasm32")]
+    let display_rate = DisplayRatePlugin::default();
+
+    #[cfg(target_arch = "wasm32")]
     let window = Some(Window {
         title: "Monkey 3D Game".into(),
-        #[cfg(target_arch = "wasm32")]
         canvas: Some("#game-canvas".into()),
         fit_canvas_to_parent: true,
         prevent_default_event_handling: true,
-        #[cfg(not(target_arch = "wasm32"))]
-        mode: WindowMode::BorderlessFullscreen(MonitorSelection::Primary),
         present_mode: PresentMode::AutoVsync,
         ..default()
     });
@@ -45,23 +66,55 @@ fn main() {
         ..default()
     });
 
-    App::new()
-        .add_plugins((
-            DefaultPlugins.set(WindowPlugin {
-                primary_window: window,
-                primary_cursor_options: cursor,
-                ..default()
-            }),
-            LogDiagnosticsPlugin::default(),
-            FrameTimeDiagnosticsPlugin::default(),
-            CommandHandlerPlugin, // Read shared memory and init bevy resources, preupdate
-            SystemsLogicPlugin,   // Game logic systems, update
-            DebugFunctionsPlugin, // Debug functions, update
-            StateEmitterPlugin,   // Write shared memory, update timing, init timing resource, postupdate
-            WebAdapterPlugin, 
-        ))
-        .insert_resource(Time::<Fixed>::from_hz(REFRESH_RATE_HZ)) 
-        .insert_resource(DoorWinEntities::default())
-        .insert_resource(RoundStartTimestamp::default())
-        .run();
-}
\ No newline at end of file
+    let mut app = App::new();
+    app.add_plugins((
+        DefaultPlugins.set(WindowPlugin {
+            primary_window: window,
+            primary_cursor_options: cursor,
+            ..default()
+        }),
+        LogDiagnosticsPlugin::default(),
+        FrameTimeDiagnosticsPlugin::default(),
+        CommandHandlerPlugin, // Read shared memory and init bevy resources, preupdate
+        SystemsLogicPlugin,   // Game logic systems, update
+        DebugFunctionsPlugin, // Debug functions, update
+        StateEmitterPlugin,   // Write shared memory, update timing, init timing resource, postupdate
+        WebAdapterPlugin, 
+        ExperimenterOverridePlugin, // Keyboard fallback for the experimenter, preupdate
+        FixedRateDiagnosticsPlugin, // Measured fixed update rate, update
+        display_rate,               // Monitor refresh rate, update
+        FrameDropPlugin,            // Late and dropped frames per trial, update
+    ))
+    .insert_resource(Time::<Fixed>::from_hz(REFRESH_RATE_HZ)) 
+    .insert_resource(DoorWinEntities::default())
+    .insert_resource(RoundStartTimestamp::default());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_plugins(cli);
+
+    // Alternative IPC path for socket-based tooling, coexisting with shared memory
+    #[cfg(all(feature = "socket_commands", unix))]
+    app.add_plugins(game_node::socket_commands::SocketCommandsPlugin);
+
+    // Read-only monitoring over HTTP
+    #[cfg(all(feature = "status_http", not(target_arch = "wasm32")))]
+    app.add_plugins(game_node::status_http::StatusHttpPlugin);
+
+    app.run();
+}
+
+/// Windowless game for CI and batch runs, driven only through shared memory
+#[cfg(not(target_arch = "wasm32"))]
+fn run_headless(cli: game_node::cli::Cli) {
+    let mut app = App::new();
+    app.add_plugins((bevy::log::LogPlugin::default(), HeadlessPlugin, cli));
+
+    #[cfg(all(feature = "socket_commands", unix))]
+    app.add_plugins(game_node::socket_commands::SocketCommandsPlugin);
+
+    #[cfg(all(feature = "status_http", not(target_arch = "wasm32")))]
+    app.add_plugins(game_node::status_http::StatusHttpPlugin);
+
+    app.run();
+}
+
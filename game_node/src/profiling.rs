@@ -0,0 +1,197 @@
+//! Reset profiling
+//! Resets stutter with many decorations. With `--profile`, the sections of every reset are timed
+//! (`profile_scope!` in setup_round, spawn_pyramid and the face decorations generated and spawned
+//! within it) into the ResetSections resource, one CSV row per reset is appended with the
+//! decorations placed, and the slowest resets so far are logged. The reset code takes the resource
+//! as an `Option`, so nothing is timed without a ProfilingPlugin; the sections are only timed in
+//! builds with the `profiling` feature, without it `profile_scope!` expands to nothing.
+
+use bevy::platform::time::Instant;
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::command_handler::SharedMemResource;
+use crate::log_event;
+use shared::constants::game_constants::{RESET_PROFILE_FILE, RESET_PROFILE_WORST_KEPT};
+
+/// Timed section of a reset. The face decorations run inside spawn_pyramid, which runs inside setup_round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Setup = 0,
+    SpawnPyramid = 1,
+    FaceDecorations = 2,
+}
+
+/// Section names, as CSV columns (in ms)
+pub const SECTION_NAMES: [&str; 3] = ["setup_round", "spawn_pyramid", "face_decorations"];
+
+/// Section times of the reset in progress, inserted by ProfilingPlugin. Atomics, so the nested
+/// scopes of a reset can all add to it through a shared reference
+#[derive(Resource, Default)]
+pub struct ResetSections {
+    /// Time spent in each section since the last reset was recorded, summed over its scopes
+    nanos: [AtomicU64; 3],
+    /// A setup_round finished since the last reset was recorded
+    reset_done: AtomicBool,
+}
+
+impl ResetSections {
+    /// Times `section` until the returned timer is dropped
+    pub fn start(&self, section: Section) -> SectionTimer<'_> {
+        SectionTimer { sections: self, section, start: Instant::now() }
+    }
+
+    /// Section times of the last reset, in ms, once one has finished; the next reset starts from zero
+    pub fn take(&self) -> Option<[f64; 3]> {
+        if !self.reset_done.swap(false, Ordering::Acquire) {
+            return None;
+        }
+        Some(std::array::from_fn(|i| self.nanos[i].swap(0, Ordering::Relaxed) as f64 / 1e6))
+    }
+}
+
+/// Adds the time until it is dropped to its section (see `profile_scope!`)
+pub struct SectionTimer<'a> {
+    sections: &'a ResetSections,
+    section: Section,
+    start: Instant,
+}
+
+impl Drop for SectionTimer<'_> {
+    fn drop(&mut self) {
+        self.sections.nanos[self.section as usize].fetch_add(self.start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        if self.section == Section::Setup {
+            self.sections.reset_done.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// One profiled reset
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResetTiming {
+    /// Trial generation the reset started
+    pub reset_ack: u32,
+    /// Time in each section, in ms, indexed by Section
+    pub sections_ms: [f64; 3],
+    /// Decorations placed per face
+    pub decorations: [u32; 3],
+}
+
+impl ResetTiming {
+    pub fn csv_header() -> String {
+        let sections: Vec<String> = SECTION_NAMES.iter().map(|name| format!("{}_ms", name)).collect();
+        format!("reset_ack,{},decorations_0,decorations_1,decorations_2", sections.join(","))
+    }
+
+    pub fn csv_row(&self) -> String {
+        let [s0, s1, s2] = self.sections_ms;
+        let [d0, d1, d2] = self.decorations;
+        format!("{},{:.3},{:.3},{:.3},{},{},{}", self.reset_ack, s0, s1, s2, d0, d1, d2)
+    }
+
+    pub fn total_ms(&self) -> f64 {
+        self.sections_ms[Section::Setup as usize]
+    }
+}
+
+/// CSV of the profiled resets, and the slowest ones so far
+#[derive(Resource)]
+pub struct ResetProfiler {
+    path: PathBuf,
+    file: Option<File>,
+    worst: Vec<ResetTiming>,
+    resets: u32,
+}
+
+impl ResetProfiler {
+    /// Creates (truncates) the CSV at `path` and writes its header
+    pub fn create(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = File::create(&path)?;
+        writeln!(file, "{}", ResetTiming::csv_header())?;
+        Ok(Self { path, file: Some(file), worst: Vec::new(), resets: 0 })
+    }
+
+    /// Slowest resets so far, slowest first (at most RESET_PROFILE_WORST_KEPT)
+    pub fn worst(&self) -> &[ResetTiming] {
+        &self.worst
+    }
+
+    pub fn resets(&self) -> u32 {
+        self.resets
+    }
+
+    /// Appends the reset to the CSV and returns its rank among the slowest, if it is one of them
+    pub fn record(&mut self, timing: ResetTiming) -> Option<usize> {
+        self.resets += 1;
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = writeln!(file, "{}", timing.csv_row()) {
+                log_event!(error, "Reset profile: failed to write {} ({}), CSV disabled", self.path.display(), e);
+                self.file = None;
+            }
+        }
+        let rank = self.worst.iter().position(|worst| timing.total_ms() > worst.total_ms()).unwrap_or(self.worst.len());
+        if rank >= RESET_PROFILE_WORST_KEPT {
+            return None;
+        }
+        self.worst.insert(rank, timing);
+        self.worst.truncate(RESET_PROFILE_WORST_KEPT);
+        Some(rank)
+    }
+}
+
+/// Default CSV, RESET_PROFILE_FILE in the log directory
+pub fn default_profile_path() -> PathBuf {
+    crate::utils::macros::log_dir().join(RESET_PROFILE_FILE)
+}
+
+/// Times every reset into the CSV at `csv` (see ResetProfiler)
+pub struct ProfilingPlugin {
+    pub csv: PathBuf,
+}
+
+impl Plugin for ProfilingPlugin {
+    fn build(&self, app: &mut App) {
+        match ResetProfiler::create(self.csv.clone()) {
+            Ok(profiler) => {
+                info!("Reset profiling to {}", self.csv.display());
+                app.insert_resource(profiler).init_resource::<ResetSections>().add_systems(Last, record_reset_timing);
+            }
+            Err(e) => error!("Reset profiling disabled, cannot create {}: {}", self.csv.display(), e),
+        }
+    }
+}
+
+/// Records the reset that ran this frame, if any, with the decorations it placed
+fn record_reset_timing(
+    mut profiler: ResMut<ResetProfiler>,
+    sections: Res<ResetSections>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some(sections_ms) = sections.take() else { return };
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let timing = ResetTiming {
+        reset_ack: gs_game.reset_ack.load(Ordering::Acquire),
+        sections_ms,
+        decorations: std::array::from_fn(|i| gs_game.decorations_placed[i].load(Ordering::Relaxed)),
+    };
+    if let Some(rank) = profiler.record(timing.clone()) {
+        let [setup, pyramid, decorations] = timing.sections_ms;
+        log_event!(
+            reset,
+            "Slow reset #{} of {}: setup_round {:.2} ms, spawn_pyramid {:.2} ms, face decorations {:.2} ms, decorations {:?}",
+            rank + 1,
+            profiler.resets(),
+            setup,
+            pyramid,
+            decorations,
+            timing.decorations
+        );
+    }
+}
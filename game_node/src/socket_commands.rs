@@ -0,0 +1,398 @@
+//! Socket command reader
+//! Optional alternative to the shared memory commands for tooling that speaks sockets.
+//! Listens on the Unix domain socket at `$MONKEY_GAME_SOCKET` and writes into the same pending
+//! resources as the SHM reader, running right after it so both paths coexist.
+//!
+//! Messages are newline-separated command names matching the `SharedCommands` fields
+//! (`rotate_left`, `reset`, ...). A bare name triggers the command for one frame, while
+//! `<name> 1` holds it like a set SHM flag until `<name> 0` or the client disconnects. The u32
+//! commands take their value instead (`show_image 2`) and fire once.
+//!
+//! Commands go through the same intake as the SHM ones: triggers are debounced with the shared
+//! windows, and every command read is reported in `commands_seen`.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::collections::HashSet;
+use std::io::{ErrorKind, Read};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use crate::command_handler::{
+    control_speeds, read_shared_memory, CommandDebounce, CommandIntake, DebugNormals, PendingAnimation,
+    PendingBlankScreen, PendingCheckAlignment, PendingClearAttempts, PendingDumpConfig, PendingDumpGeometry,
+    PendingProbe, PendingPyramidType, PendingQueueNext, PendingReferenceImage, PendingRenderQuality, PendingReset,
+    PendingRotation, PendingZoom, RenderingPaused, SharedMemResource, ShowThresholdCone,
+};
+use crate::display_rate::DisplayRate;
+use shared::constants::game_constants::{COMMAND_SOCKET_ENV, COMMAND_SOCKET_MAX_LINE};
+use shared::{DebouncedCommand, SeenCommand};
+
+/// Commands accepted over the socket, one per `SharedCommands` flag or u32 command
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SocketCommand {
+    RotateLeft,
+    RotateRight,
+    ZoomIn,
+    ZoomOut,
+    CheckAlignment,
+    BlankScreen,
+    StopRendering,
+    ResumeRendering,
+    StepFrame,
+    AnimationDoor,
+    Reset,
+    DebugNormals,
+    ShowThresholdCone,
+    DumpConfig,
+    DumpGeometry,
+    ClearAttempts,
+    QueueNext,
+    PromoteStaged,
+    SetPyramidType(u32),
+    ProbeFace(u32),
+    ShowImage(u32),
+    RenderQuality(u32),
+}
+
+impl SocketCommand {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rotate_left" => Some(Self::RotateLeft),
+            "rotate_right" => Some(Self::RotateRight),
+            "zoom_in" => Some(Self::ZoomIn),
+            "zoom_out" => Some(Self::ZoomOut),
+            "check_alignment" => Some(Self::CheckAlignment),
+            "blank_screen" => Some(Self::BlankScreen),
+            "stop_rendering" => Some(Self::StopRendering),
+            "resume_rendering" => Some(Self::ResumeRendering),
+            "step_frame" => Some(Self::StepFrame),
+            "animation_door" => Some(Self::AnimationDoor),
+            "reset" => Some(Self::Reset),
+            "debug_normals" => Some(Self::DebugNormals),
+            "show_threshold_cone" => Some(Self::ShowThresholdCone),
+            "dump_config" => Some(Self::DumpConfig),
+            "dump_geometry" => Some(Self::DumpGeometry),
+            "clear_attempts" => Some(Self::ClearAttempts),
+            "queue_next" => Some(Self::QueueNext),
+            "promote_staged" => Some(Self::PromoteStaged),
+            _ => None,
+        }
+    }
+
+    /// The commands taking a value, named like their u32 `SharedCommands` fields
+    fn with_value(name: &str) -> Option<fn(u32) -> Self> {
+        match name {
+            "set_pyramid_type" => Some(Self::SetPyramidType),
+            "probe_face" => Some(Self::ProbeFace),
+            "show_image" => Some(Self::ShowImage),
+            "render_quality" => Some(Self::RenderQuality),
+            _ => None,
+        }
+    }
+
+    /// Commands consumed on read in SHM too, so holding them still fires once
+    fn is_one_shot(self) -> bool {
+        matches!(
+            self,
+            Self::StepFrame
+                | Self::DumpConfig
+                | Self::DumpGeometry
+                | Self::ClearAttempts
+                | Self::QueueNext
+                | Self::PromoteStaged
+                | Self::SetPyramidType(_)
+                | Self::ProbeFace(_)
+                | Self::ShowImage(_)
+                | Self::RenderQuality(_)
+        )
+    }
+
+    /// The command reported in `commands_seen` when this one is read
+    fn seen(self) -> SeenCommand {
+        match self {
+            Self::RotateLeft => SeenCommand::RotateLeft,
+            Self::RotateRight => SeenCommand::RotateRight,
+            Self::ZoomIn => SeenCommand::ZoomIn,
+            Self::ZoomOut => SeenCommand::ZoomOut,
+            Self::CheckAlignment => SeenCommand::CheckAlignment,
+            Self::BlankScreen => SeenCommand::BlankScreen,
+            Self::StopRendering => SeenCommand::StopRendering,
+            Self::ResumeRendering => SeenCommand::ResumeRendering,
+            Self::StepFrame => SeenCommand::StepFrame,
+            Self::AnimationDoor => SeenCommand::AnimationDoor,
+            Self::Reset => SeenCommand::Reset,
+            Self::DebugNormals => SeenCommand::DebugNormals,
+            Self::ShowThresholdCone => SeenCommand::ShowThresholdCone,
+            Self::DumpConfig => SeenCommand::DumpConfig,
+            Self::DumpGeometry => SeenCommand::DumpGeometry,
+            Self::ClearAttempts => SeenCommand::ClearAttempts,
+            Self::QueueNext => SeenCommand::QueueNext,
+            Self::PromoteStaged => SeenCommand::PromoteStaged,
+            Self::SetPyramidType(_) => SeenCommand::SetPyramidType,
+            Self::ProbeFace(_) => SeenCommand::ProbeFace,
+            Self::ShowImage(_) => SeenCommand::ShowImage,
+            Self::RenderQuality(_) => SeenCommand::RenderQuality,
+        }
+    }
+}
+
+/// Parses one message into its command and, when given, the held state (`1` pressed, `0` released).
+/// A command taking a value carries it, and is never held
+pub fn parse_message(line: &str) -> Result<(SocketCommand, Option<bool>), String> {
+    let mut parts = line.split_whitespace();
+    let name = parts.next().ok_or("empty message")?;
+    let (command, held) = if let Some(command) = SocketCommand::with_value(name) {
+        let value = parts.next().ok_or_else(|| format!("expected a value after {name}"))?;
+        let value = value.parse().map_err(|_| format!("expected an unsigned integer after {name}, got {value:?}"))?;
+        (command(value), None)
+    } else {
+        let command = SocketCommand::from_name(name).ok_or_else(|| format!("unknown command {name:?}"))?;
+        let held = match parts.next() {
+            None => None,
+            Some("1") => Some(true),
+            Some("0") => Some(false),
+            Some(value) => return Err(format!("expected 0 or 1 after {name}, got {value:?}")),
+        };
+        (command, held)
+    };
+    if parts.next().is_some() {
+        return Err(format!("trailing data after {name}"));
+    }
+    Ok((command, held))
+}
+
+struct SocketClient {
+    stream: UnixStream,
+    buffer: Vec<u8>,
+    held: HashSet<SocketCommand>,
+}
+
+/// Listening socket and its connected clients. The socket file is removed on drop
+#[derive(Resource)]
+pub struct CommandSocket {
+    listener: UnixListener,
+    path: PathBuf,
+    clients: Vec<SocketClient>,
+}
+
+impl CommandSocket {
+    /// Binds a non-blocking listener, replacing a stale socket file left by a previous run. Anything
+    /// else at `path`, a regular file or a socket another game still listens on, is left alone
+    pub fn bind(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(std::io::Error::new(ErrorKind::AlreadyExists, format!("{} is not a socket", path.display())));
+            }
+            if UnixStream::connect(&path).is_ok() {
+                return Err(std::io::Error::new(ErrorKind::AddrInUse, format!("{} is in use", path.display())));
+            }
+            std::fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, path, clients: Vec::new() })
+    }
+
+    /// Accepts new clients and drains their messages. Returns the one-frame pulses, and updates
+    /// each client's held set. Disconnected clients are dropped along with what they held.
+    fn poll(&mut self) -> Vec<SocketCommand> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if let Err(e) = stream.set_nonblocking(true) {
+                        warn!("Command socket: dropping client, cannot set non-blocking: {}", e);
+                        continue;
+                    }
+                    info!("Command socket: client connected");
+                    self.clients.push(SocketClient { stream, buffer: Vec::new(), held: HashSet::new() });
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    warn!("Command socket: accept failed: {}", e);
+                    break;
+                }
+            }
+        }
+
+        let mut pulses = Vec::new();
+        self.clients.retain_mut(|client| {
+            let connected = client.read_available();
+            for line in client.take_lines() {
+                match parse_message(&line) {
+                    Ok((command, _)) if command.is_one_shot() => pulses.push(command),
+                    Ok((command, None)) => pulses.push(command),
+                    Ok((command, Some(true))) => {
+                        client.held.insert(command);
+                    }
+                    Ok((command, Some(false))) => {
+                        client.held.remove(&command);
+                    }
+                    Err(e) => warn!("Command socket: ignoring malformed message {:?}: {}", line, e),
+                }
+            }
+            if !connected {
+                info!("Command socket: client disconnected");
+            }
+            connected
+        });
+        pulses
+    }
+
+    fn held(&self) -> impl Iterator<Item = SocketCommand> + '_ {
+        self.clients.iter().flat_map(|client| client.held.iter().copied())
+    }
+}
+
+impl Drop for CommandSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl SocketClient {
+    /// Reads everything available without blocking. Returns false once the client is gone
+    fn read_available(&mut self) -> bool {
+        let mut chunk = [0u8; 1024];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return false,
+                Ok(n) => self.buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return true,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    warn!("Command socket: read failed: {}", e);
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Splits off the complete lines. An overlong unterminated message is discarded
+    fn take_lines(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            match std::str::from_utf8(&raw) {
+                Ok(text) if !text.trim().is_empty() => lines.push(text.trim().to_string()),
+                Ok(_) => {}
+                Err(_) => warn!("Command socket: ignoring non UTF-8 message"),
+            }
+        }
+        if self.buffer.len() > COMMAND_SOCKET_MAX_LINE {
+            warn!("Command socket: discarding {} bytes without a newline", self.buffer.len());
+            self.buffer.clear();
+        }
+        lines
+    }
+}
+
+/// Pending resources a socket command writes into
+#[derive(SystemParam)]
+struct PendingCommands<'w> {
+    reset: ResMut<'w, PendingReset>,
+    rotation: ResMut<'w, PendingRotation>,
+    zoom: ResMut<'w, PendingZoom>,
+    check: ResMut<'w, PendingCheckAlignment>,
+    blank: ResMut<'w, PendingBlankScreen>,
+    rendering_paused: ResMut<'w, RenderingPaused>,
+    anim: ResMut<'w, PendingAnimation>,
+    debug_normals: ResMut<'w, DebugNormals>,
+    show_threshold_cone: ResMut<'w, ShowThresholdCone>,
+    dump: ResMut<'w, PendingDumpConfig>,
+    dump_geometry: ResMut<'w, PendingDumpGeometry>,
+    clear_attempts: ResMut<'w, PendingClearAttempts>,
+    queue_next: ResMut<'w, PendingQueueNext>,
+    pyramid_type: ResMut<'w, PendingPyramidType>,
+    probe: ResMut<'w, PendingProbe>,
+    image: ResMut<'w, PendingReferenceImage>,
+    quality: ResMut<'w, PendingRenderQuality>,
+}
+
+pub struct SocketCommandsPlugin;
+
+impl Plugin for SocketCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, bind_command_socket)
+            .add_systems(PreUpdate, read_socket_commands.after(read_shared_memory));
+    }
+}
+
+fn bind_command_socket(mut commands: Commands, existing: Option<Res<CommandSocket>>) {
+    // Keep a socket inserted before startup (e.g. tests using their own path)
+    if existing.is_some() {
+        return;
+    }
+    let Ok(path) = std::env::var(COMMAND_SOCKET_ENV) else {
+        info!("Command socket disabled: {} is not set", COMMAND_SOCKET_ENV);
+        return;
+    };
+    match CommandSocket::bind(&path) {
+        Ok(socket) => {
+            info!("Command socket listening on {}", path);
+            commands.insert_resource(socket);
+        }
+        Err(e) => error!("Failed to bind command socket {}: {}", path, e),
+    }
+}
+
+fn read_socket_commands(
+    socket: Option<ResMut<CommandSocket>>,
+    shm_res: Option<Res<SharedMemResource>>,
+    display_rate: Res<DisplayRate>,
+    mut debounce: ResMut<CommandDebounce>,
+    mut pending: PendingCommands,
+) {
+    let Some(mut socket) = socket else { return };
+    let pulses = socket.poll();
+    let shm = shm_res.as_ref().map(|shm| shm.0.get());
+    let gs_game = shm.map(|shm| &shm.game_structure_game);
+    let (rotate_speed, zoom_speed) = control_speeds(gs_game, &display_rate);
+    // After the SHM reader, so its reads this frame are kept and added to
+    let mut intake = CommandIntake::new(gs_game, &mut debounce, &display_rate);
+
+    // Held commands first, pulses after, in the order read_shared_memory applies its flags, so a
+    // step_frame or resume_rendering pulse wins over a held stop_rendering
+    for command in socket.held().chain(pulses) {
+        let accepted = match command {
+            SocketCommand::CheckAlignment => intake.accept_check(None, true),
+            SocketCommand::BlankScreen => intake.accept_trigger(DebouncedCommand::BlankScreen, true),
+            SocketCommand::Reset => intake.accept_trigger(DebouncedCommand::Reset, true),
+            _ => intake.see(command.seen(), true),
+        };
+        if !accepted {
+            continue;
+        }
+        match command {
+            SocketCommand::RotateLeft => pending.rotation.0 -= rotate_speed,
+            SocketCommand::RotateRight => pending.rotation.0 += rotate_speed,
+            SocketCommand::ZoomIn => pending.zoom.0 -= zoom_speed,
+            SocketCommand::ZoomOut => pending.zoom.0 += zoom_speed,
+            SocketCommand::CheckAlignment => pending.check.0 = true,
+            SocketCommand::BlankScreen => pending.blank.0 = true,
+            SocketCommand::StopRendering => pending.rendering_paused.0 = true,
+            SocketCommand::ResumeRendering => pending.rendering_paused.0 = false,
+            SocketCommand::StepFrame => pending.rendering_paused.0 = false,
+            SocketCommand::AnimationDoor => pending.anim.0 = true,
+            SocketCommand::Reset => pending.reset.0 = true,
+            SocketCommand::DebugNormals => pending.debug_normals.0 = true,
+            SocketCommand::ShowThresholdCone => pending.show_threshold_cone.0 = true,
+            SocketCommand::DumpConfig => pending.dump.0 = true,
+            SocketCommand::DumpGeometry => pending.dump_geometry.0 = true,
+            SocketCommand::ClearAttempts => pending.clear_attempts.0 = true,
+            SocketCommand::QueueNext => pending.queue_next.0 = true,
+            // Copied right away like the SHM flag, the reset that reads it runs later in the frame
+            SocketCommand::PromoteStaged => match shm {
+                Some(shm) => shm.game_structure_control.reset_all_fields(&shm.game_structure_staged),
+                None => warn!("Command socket: promote_staged needs shared memory"),
+            },
+            SocketCommand::SetPyramidType(pyramid_type) => pending.pyramid_type.0 = Some(pyramid_type),
+            SocketCommand::ProbeFace(face) => pending.probe.0 = Some(face),
+            SocketCommand::ShowImage(image) => pending.image.0 = Some(image),
+            SocketCommand::RenderQuality(quality) => pending.quality.0 = Some(quality),
+        }
+    }
+    intake.finish(false);
+}
@@ -1,8 +1,17 @@
 //! This module collects game state and writes it to atomic shared memory
 
 use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
 use crate::command_handler::{SharedMemResource, RenderingPaused};
-use crate::utils::objects::{BaseDoor, RoundStartTimestamp};
+use crate::display_rate::DisplayRate;
+use crate::log_event;
+use crate::utils::alignment::xz_alignment;
+use crate::utils::camera::controls_yaw;
+use crate::utils::game_functions::{AlignmentTargets, best_door, flicker_phase, target_normal};
+use crate::utils::macros::set_event_frame;
+use crate::utils::objects::{Mirrored, PersistentCamera, Pyramid, RoundStartTimestamp, YawOffset};
+
+use shared::snapshot::StateSnapshot;
 
 use core::sync::atomic::Ordering;
 
@@ -10,13 +19,19 @@ use core::sync::atomic::Ordering;
 #[derive(Resource, Default)]
 pub struct FrameCounterResource(pub u64);
 
+/// Set by the reset handler, acknowledged to the controller once the new round state is emitted
+#[derive(Resource, Default)]
+pub struct PendingResetAck(pub bool);
+
 // Update the shared memory game state after every game loop update.
 pub struct StateEmitterPlugin;
 
 impl Plugin for StateEmitterPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FrameCounterResource>()
-           .add_systems(PostUpdate, (increment_frame_counter, emit_state_to_shm).chain());
+           .init_resource::<DisplayRate>()
+           .init_resource::<PendingResetAck>()
+           .add_systems(PostUpdate, (increment_frame_counter, emit_viewport_to_shm, emit_pyramid_yaw, emit_session_info, emit_state_to_shm, publish_state_snapshot).chain());
     }
 }
 
@@ -30,23 +45,95 @@ fn increment_frame_counter(
         }
     }
     counter.0 += 1;
+    set_event_frame(counter.0);
+}
+
+/// Emit the active projection, the primary window's resolution and vsync, so visual angles can be
+/// computed offline
+fn emit_viewport_to_shm(
+    camera_query: Query<&Projection, With<PersistentCamera>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    // An orthographic projection has no FOV, it is emitted as 0
+    let active = match camera_query.single() {
+        Ok(Projection::Perspective(perspective)) => Some((perspective.fov.to_degrees(), perspective.near, perspective.far)),
+        Ok(Projection::Orthographic(orthographic)) => Some((0.0, orthographic.near, orthographic.far)),
+        _ => None,
+    };
+    if let Some((fov, near, far)) = active {
+        gs_game.active_fov_degrees.store(fov.to_bits(), Ordering::Relaxed);
+        gs_game.active_near.store(near.to_bits(), Ordering::Relaxed);
+        gs_game.active_far.store(far.to_bits(), Ordering::Relaxed);
+    }
+
+    if let Ok(window) = window_query.single() {
+        gs_game.window_width.store(window.physical_width(), Ordering::Relaxed);
+        gs_game.window_height.store(window.physical_height(), Ordering::Relaxed);
+        let vsync = matches!(window.present_mode, PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed);
+        gs_game.vsync.store(vsync, Ordering::Relaxed);
+    }
+}
+
+type PyramidYaw = (&'static Transform, Option<&'static YawOffset>, Has<Mirrored>);
+
+/// Emit the pyramid yaw from the controls, i.e. without the global yaw offset, and the offset applied.
+/// A mirrored pyramid turns the other way, its yaw is emitted as in the unmirrored scene
+fn emit_pyramid_yaw(
+    pyramid_query: Query<PyramidYaw, With<Pyramid>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let Some((transform, offset, mirrored)) = pyramid_query.iter().next() else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let yaw = controls_yaw(transform, offset, mirrored);
+    gs_game.pyramid_yaw.store(yaw.to_bits(), Ordering::Relaxed);
+    gs_game.global_yaw_offset.store(offset.map_or(0.0, |offset| offset.0).to_bits(), Ordering::Relaxed);
+}
+
+/// Echo the controller's session context into the game region, and log it with every new trial.
+/// The game never acts on it
+fn emit_session_info(shm_res: Option<Res<SharedMemResource>>, pending_reset_ack: Res<PendingResetAck>) {
+    let Some(shm_res) = shm_res else { return };
+    let shm = shm_res.0.get();
+    let (gs_control, gs_game) = (&shm.game_structure_control, &shm.game_structure_game);
+    let trial = gs_control.current_trial_index.load(Ordering::Relaxed);
+    let total = gs_control.total_trials.load(Ordering::Relaxed);
+    let block = gs_control.block_index.load(Ordering::Relaxed);
+    let session = gs_control.session_id.load(Ordering::Relaxed);
+    let subject = gs_control.subject_id.load(Ordering::Relaxed);
+    gs_game.current_trial_index.store(trial, Ordering::Relaxed);
+    gs_game.total_trials.store(total, Ordering::Relaxed);
+    gs_game.block_index.store(block, Ordering::Relaxed);
+    gs_game.session_id.store(session, Ordering::Relaxed);
+    gs_game.subject_id.store(subject, Ordering::Relaxed);
+
+    // Acknowledged this frame by emit_state_to_shm
+    if pending_reset_ack.0 {
+        log_event!(reset, "Session {} subject {}: trial {}/{}, block {}", session, subject, trial, total, block);
+    }
 }
 
 // Write state of the game to shared memory to be read by controller
 fn emit_state_to_shm(
     time: Res<Time>,
-    frame_counter: Res<FrameCounterResource>,
+    (frame_counter, display_rate): (Res<FrameCounterResource>, Res<DisplayRate>),
     round_start: Res<RoundStartTimestamp>,
-    camera_query: Query<&Transform, With<Camera3d>>,
-    door_query: Query<(&BaseDoor, &Transform)>,
+    camera_query: Query<(&Transform, &Projection), With<Camera3d>>,
+    targets: AlignmentTargets,
     shm_res: Option<Res<SharedMemResource>>,
+    mut pending_reset_ack: ResMut<PendingResetAck>,
 ) {
     let Some(shm_res) = shm_res else { return };
     let shm = shm_res.0.get();
     let gs_game = &shm.game_structure_game;
 
-    // Time & Frame
-    gs_game.frame_number.store(frame_counter.0, Ordering::Relaxed);
+    // Time (the frame number is published last, below)
+    gs_game.game_heartbeat.fetch_add(1, Ordering::Relaxed);
 
     // Elapsed time
     let elapsed = if let Some(start) = round_start.0 {
@@ -56,42 +143,61 @@ fn emit_state_to_shm(
     };
     gs_game.elapsed_secs.store(elapsed.to_bits(), Ordering::Relaxed);
 
+    // Flicker phase per face, for offline alignment with the recordings
+    for i in 0..3 {
+        let hz = f32::from_bits(gs_game.flicker_hz[i].load(Ordering::Relaxed));
+        gs_game.flicker_phase[i].store(flicker_phase(frame_counter.0, hz, display_rate.frame_hz()).to_bits(), Ordering::Relaxed);
+    }
+
     // Camera
-    if let Ok(camera_transform) = camera_query.single() {
+    if let Ok((camera_transform, projection)) = camera_query.single() {
         let pos = camera_transform.translation;
-        let radius = pos.xz().length();
+        // Orthographic zoom changes the scale, not the orbit, so the scale is emitted in place of the radius
+        let (radius, is_scale) = match projection {
+            Projection::Orthographic(orthographic) => (orthographic.scale, true),
+            _ => (pos.xz().length(), false),
+        };
         gs_game.camera_radius.store(radius.to_bits(), Ordering::Relaxed);
+        gs_game.camera_radius_is_scale.store(is_scale, Ordering::Relaxed);
         gs_game.camera_x.store(pos.x.to_bits(), Ordering::Relaxed);
         gs_game.camera_y.store(pos.y.to_bits(), Ordering::Relaxed);
         gs_game.camera_z.store(pos.z.to_bits(), Ordering::Relaxed);
     }
 
-    // Continuous Alignment Calculation
-    let current_alignment; 
-    let current_angle;
-    
-    let target_door_idx = gs_game.target_door.load(Ordering::Relaxed) as usize;
-
-    if let Ok(camera_transform) = camera_query.single() {
-        let camera_forward = camera_transform.forward();
-        let camera_forward_xz = Vec3::new(camera_forward.x, 0.0, camera_forward.z).normalize_or_zero();
-
-        // Find target door
-        for (door, door_transform) in &door_query {
-            if door.door_index == target_door_idx {
-                let door_normal_world = door_transform.rotation * door.normal;
-                let door_normal_xz = Vec3::new(door_normal_world.x, 0.0, door_normal_world.z).normalize_or_zero();
-                
-                let alignment = door_normal_xz.dot(camera_forward_xz);
-                current_alignment = alignment;
-                // Angle in radians (0 to PI) using acos, clamping to safe range
-                current_angle = alignment.clamp(-1.0, 1.0).acos();
-
-                gs_game.current_alignment.store(current_alignment.to_bits(), Ordering::Relaxed);
-                gs_game.current_angle.store(current_angle.to_bits(), Ordering::Relaxed);
-                break;
-            }
+    // Continuous Alignment Calculation, against the target door or face
+    if let Ok((camera_transform, _)) = camera_query.single() {
+        if let Some(normal) = target_normal(gs_game, &targets) {
+            let current_alignment = xz_alignment(normal, *camera_transform.forward());
+            // Angle in radians (0 to PI) using acos, clamping to safe range
+            let current_angle = current_alignment.clamp(-1.0, 1.0).acos();
+
+            gs_game.current_alignment.store(current_alignment.to_bits(), Ordering::Relaxed);
+            gs_game.current_angle.store(current_angle.to_bits(), Ordering::Relaxed);
+        }
+        if let Some(door) = best_door(&targets, *camera_transform.forward()) {
+            gs_game.best_door.store(door as u32, Ordering::Relaxed);
         }
     }
 
+    // Targets actually spawned, so controllers can size what they read per face or door
+    let (faces, doors) = targets.iter().fold((0, 0), |(faces, doors), (_, door, face)| {
+        (faces + u32::from(face.is_some()), doors + u32::from(door.is_some()))
+    });
+    gs_game.num_faces.store(faces, Ordering::Relaxed);
+    gs_game.num_doors.store(doors, Ordering::Relaxed);
+
+    // Acknowledge a completed reset after the round state, so the controller never sees the ack before it
+    if pending_reset_ack.0 {
+        pending_reset_ack.0 = false;
+        gs_game.reset_ack.fetch_add(1, Ordering::Release);
+    }
+    // Publish the frame last: a controller that reads the new number (Acquire) sees this frame's payload
+    gs_game.frame_number.store(frame_counter.0, Ordering::Release);
+}
+
+/// Publish the frame's state as one snapshot, once every field above has been emitted
+fn publish_state_snapshot(shm_res: Option<Res<SharedMemResource>>) {
+    let Some(shm_res) = shm_res else { return };
+    let shm = shm_res.0.get();
+    shm.state_snapshot.publish(&StateSnapshot::capture(&shm.game_structure_game));
 }
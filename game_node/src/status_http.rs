@@ -0,0 +1,166 @@
+//! HTTP status endpoint
+//! Read-only monitoring for lab dashboards: `GET /status` (or `/`) returns the latest published
+//! state snapshot (see shared/src/snapshot.rs) as JSON. Served from its own thread, which only reads
+//! the double-buffered snapshot, so a slow or stuck client never holds up the render loop. Commands
+//! still go through shared memory (or the socket reader).
+//!
+//! Listens on STATUS_HTTP_HOST at the port in `$MONKEY_GAME_STATUS_PORT`, STATUS_HTTP_DEFAULT_PORT
+//! when unset.
+
+use bevy::prelude::*;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::command_handler::SharedMemResource;
+use shared::constants::game_constants::{STATUS_HTTP_DEFAULT_PORT, STATUS_HTTP_HOST, STATUS_HTTP_PORT_ENV};
+use shared::snapshot::StateSnapshot;
+use shared::SharedMemoryHandle;
+
+/// How often the idle server thread checks for clients and for shutdown
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Longest a client may take to send its request line
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Status object served: the summary fields dashboards poll, and the full snapshot under `state`.
+/// `trial_index` is the trial generation (`trial_generation`); all fields are `null` before the first frame
+pub fn status_json(snapshot: Option<&StateSnapshot>) -> String {
+    match snapshot {
+        Some(snapshot) => format!(
+            "{{\"phase\": {}, \"frame\": {}, \"trial_index\": {}, \"attempts\": {}, \"has_won\": {}, \"state\": {}}}",
+            snapshot.phase,
+            snapshot.frame_number,
+            snapshot.trial_generation,
+            snapshot.attempts,
+            snapshot.win_elapsed_secs != 0.0,
+            snapshot.to_json(),
+        ),
+        None => "{\"phase\": null, \"frame\": null, \"trial_index\": null, \"attempts\": null, \"has_won\": null, \"state\": null}"
+            .to_string(),
+    }
+}
+
+/// Running status server. Dropping it stops the thread and closes the port
+#[derive(Resource)]
+pub struct StatusServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StatusServer {
+    /// Binds `addr` (port 0 picks a free one) and serves the snapshot of `handle` from a new thread
+    pub fn spawn(addr: &str, handle: SharedMemoryHandle) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name("status_http".to_string())
+            .spawn(move || serve(listener, handle, thread_stop))?;
+        Ok(Self { addr, stop, thread: Some(thread) })
+    }
+
+    /// Address actually bound
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for StatusServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, handle: SharedMemoryHandle, stop: Arc<AtomicBool>) {
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if let Err(e) = respond(stream, &handle) {
+                    debug!("Status endpoint: client dropped: {}", e);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => {
+                warn!("Status endpoint: accept failed: {}", e);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// Answers one request and closes the connection
+fn respond(mut stream: TcpStream, handle: &SharedMemoryHandle) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+
+    // Only the request line matters, headers and body are ignored
+    let mut request = Vec::new();
+    let mut chunk = [0u8; 512];
+    while !request.contains(&b'\n') && request.len() < 4096 {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&chunk[..n]);
+    }
+    let line = String::from_utf8_lossy(&request);
+    let mut parts = line.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/" | "/status")) => ("200 OK", status_json(handle.read_snapshot().as_ref())),
+        (Some("GET"), Some(_)) => ("404 Not Found", "{\"error\": \"not found, try /status\"}".to_string()),
+        _ => ("405 Method Not Allowed", "{\"error\": \"read-only endpoint, only GET is served\"}".to_string()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+pub struct StatusHttpPlugin;
+
+impl Plugin for StatusHttpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, start_status_server);
+    }
+}
+
+fn start_status_server(mut commands: Commands, shm_res: Option<Res<SharedMemResource>>, existing: Option<Res<StatusServer>>) {
+    // Keep a server inserted before startup (e.g. tests on a free port)
+    if existing.is_some() {
+        return;
+    }
+    let Some(shm_res) = shm_res else {
+        warn!("Status endpoint disabled: no shared memory");
+        return;
+    };
+    let port = match std::env::var(STATUS_HTTP_PORT_ENV) {
+        Ok(value) => match value.parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => {
+                error!("Status endpoint disabled: {}={:?} is not a port", STATUS_HTTP_PORT_ENV, value);
+                return;
+            }
+        },
+        Err(_) => STATUS_HTTP_DEFAULT_PORT,
+    };
+    let addr = format!("{}:{}", STATUS_HTTP_HOST, port);
+    match StatusServer::spawn(&addr, shm_res.0.clone()) {
+        Ok(server) => {
+            info!("Status endpoint serving http://{}/status", server.local_addr());
+            commands.insert_resource(server);
+        }
+        Err(e) => error!("Failed to bind status endpoint {}: {}", addr, e),
+    }
+}
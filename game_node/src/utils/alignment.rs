@@ -0,0 +1,66 @@
+//! Alignment geometry shared by the win check, the emitted state and the feedback displays.
+//! Pure functions of directions, so the conventions can be tested without spawning a pyramid:
+//! target normals point inward (into the pyramid), everything is projected to the XZ plane, and
+//! a check wins when the cosine with the camera forward is strictly above the threshold.
+
+use bevy::prelude::*;
+use core::f32::consts::{PI, TAU};
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+
+/// Cosine between an inward target normal and the camera forward, both projected to the XZ plane.
+/// Most positive = target facing the camera (seen from outside).
+pub fn xz_alignment(normal_world: Vec3, camera_forward: Vec3) -> f32 {
+    let normal_xz = Vec3::new(normal_world.x, 0.0, normal_world.z).normalize_or_zero();
+    let camera_forward_xz = Vec3::new(camera_forward.x, 0.0, camera_forward.z).normalize_or_zero();
+    normal_xz.dot(camera_forward_xz)
+}
+
+/// Inward normal of door `door_index` of a base built at `start_orient`, before any yaw.
+/// Door i spans the base side from angle i * TAU / BASE_NR_SIDES + start_orient + PI / 2 to the next
+/// one (angles from +X towards +Z), so it faces out at the middle of that span.
+pub fn door_normal(start_orient: f32, door_index: usize) -> Vec3 {
+    let angle = (door_index as f32 + 0.5) * std::f32::consts::TAU / BASE_NR_SIDES as f32
+        + start_orient
+        + std::f32::consts::FRAC_PI_2;
+    -Vec3::new(angle.cos(), 0.0, angle.sin())
+}
+
+/// Index of the door the camera faces most directly, from (door index, world inward normal) pairs.
+/// `None` without doors.
+pub fn best_aligned_door(camera_forward: Vec3, doors: impl IntoIterator<Item = (usize, Vec3)>) -> Option<usize> {
+    doors
+        .into_iter()
+        .map(|(index, normal)| (index, xz_alignment(normal, camera_forward)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// Whether door `door_index`, at `alignment` with the camera, wins against the configured cosine
+/// threshold: only the `target` door can, however directly another one is faced
+pub fn is_win(alignment: f32, threshold: f32, door_index: usize, target: usize) -> bool {
+    door_index == target && alignment > threshold
+}
+
+/// Half-angle (radians) of the window around the target normal within which `is_win` holds:
+/// 0 when nothing can win, PI when everything but the exact opposite does
+pub fn threshold_half_angle(threshold: f32) -> f32 {
+    threshold.clamp(-1.0, 1.0).acos()
+}
+
+/// Direction a yaw history (radians, oldest first) turns in: the sign of its net change, with
+/// every step wrapped to (-PI, PI], or 0 when the net change is within `min_turn`
+pub fn approach_sign(yaws: impl IntoIterator<Item = f32>, min_turn: f32) -> i32 {
+    let mut yaws = yaws.into_iter();
+    let Some(first) = yaws.next() else { return 0 };
+    let (turn, _) = yaws.fold((0.0, first), |(turn, last), yaw| {
+        let step = (yaw - last + PI).rem_euclid(TAU) - PI;
+        (turn + step, yaw)
+    });
+    if turn > min_turn {
+        1
+    } else if turn < -min_turn {
+        -1
+    } else {
+        0
+    }
+}
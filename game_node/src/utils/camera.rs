@@ -1,32 +1,126 @@
 //! Implementation of a 3D first-person orbit camera plugin for monkey_3d_game.
 
-use crate::command_handler::{PendingRotation, PendingZoom};
+use crate::command_handler::{GlobalYawOffset, PendingRotation, PendingZoom};
+use crate::log_event;
 
-use crate::utils::objects::RotableComponent;
+use crate::utils::alignment::approach_sign;
+use crate::utils::objects::{CarriedYaw, Mirrored, Pyramid, RotableComponent, YawHistory, YawOffset};
+use core::f32::consts::{PI, TAU};
 use std::sync::atomic::Ordering;
 use bevy::prelude::*;
 use crate::command_handler::SharedMemResource;
+use bevy::camera::ScalingMode;
+use shared::{ProjectionMode, SharedGameStructure};
 use shared::constants::camera_3d_constants::{
-    CAMERA_3D_INITIAL_Y, CAMERA_3D_MAX_RADIUS, CAMERA_3D_MIN_RADIUS,
+    CAMERA_3D_FOV_RANGE_DEGREES, CAMERA_3D_INITIAL_Y, CAMERA_3D_MAX_RADIUS, CAMERA_3D_MIN_RADIUS,
+    CAMERA_3D_ORTHO_VIEWPORT_HEIGHT,
 };
+use shared::constants::game_constants::{APPROACH_MIN_YAW, APPROACH_WINDOW_FRAMES};
+
+/// Projection from the trial's mode, FOV and clip planes; zero fields keep the Bevy default.
+/// The FOV is clamped to the supported range, and inverted clip planes fall back to the defaults.
+/// Orthographic trials start at scale 1 (clamped to the configured scale range).
+pub fn configured_projection(gs_game: &SharedGameStructure) -> Projection {
+    let fov_degrees = f32::from_bits(gs_game.camera_fov_degrees.load(Ordering::Relaxed));
+    let near = f32::from_bits(gs_game.camera_near.load(Ordering::Relaxed));
+    let far = f32::from_bits(gs_game.camera_far.load(Ordering::Relaxed));
+
+    // Overrides the default clip planes only when the configured pair is consistent
+    let clip_planes = |default_near: f32, default_far: f32| {
+        let near = if near > 0.0 { near } else { default_near };
+        let far = if far > 0.0 { far } else { default_far };
+        if near < far {
+            (near, far)
+        } else {
+            log_event!(error, "Camera near plane {} is not below far plane {}, using defaults", near, far);
+            (default_near, default_far)
+        }
+    };
+
+    match ProjectionMode::from_u32(gs_game.projection_mode.load(Ordering::Relaxed)) {
+        Some(ProjectionMode::Orthographic) => {
+            let [min_scale, max_scale] = ortho_scale_range(gs_game);
+            let mut orthographic = OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical { viewport_height: CAMERA_3D_ORTHO_VIEWPORT_HEIGHT },
+                scale: 1.0_f32.clamp(min_scale, max_scale),
+                ..OrthographicProjection::default_3d()
+            };
+            (orthographic.near, orthographic.far) = clip_planes(orthographic.near, orthographic.far);
+            Projection::Orthographic(orthographic)
+        }
+        _ => {
+            let mut perspective = PerspectiveProjection::default();
+            if fov_degrees > 0.0 {
+                perspective.fov = fov_degrees
+                    .clamp(CAMERA_3D_FOV_RANGE_DEGREES[0], CAMERA_3D_FOV_RANGE_DEGREES[1])
+                    .to_radians();
+            }
+            (perspective.near, perspective.far) = clip_planes(perspective.near, perspective.far);
+            Projection::Perspective(perspective)
+        }
+    }
+}
+
+/// Orthographic min/max scale of the current trial
+fn ortho_scale_range(gs_game: &SharedGameStructure) -> [f32; 2] {
+    let min = f32::from_bits(gs_game.ortho_scale_range[0].load(Ordering::Relaxed));
+    let max = f32::from_bits(gs_game.ortho_scale_range[1].load(Ordering::Relaxed));
+    [min, max.max(min)]
+}
 
-/// Apply rotation to all rotable entities by the given delta (in radians).
+type RotableTransform = (&'static mut Transform, Has<Mirrored>);
+
+/// Apply rotation to all rotable entities by the given delta (in radians), the other way for mirrored ones.
 pub fn apply_rotation(
     delta: f32,
-    rot_entities: &mut Query<&mut Transform, (With<RotableComponent>, Without<Camera3d>)>,
+    rot_entities: &mut Query<RotableTransform, (With<RotableComponent>, Without<Camera3d>)>,
 ) {
-    for mut rot_entity_transform in rot_entities.iter_mut() {
+    for (mut rot_entity_transform, mirrored) in rot_entities.iter_mut() {
         let (mut yaw, _, _) = rot_entity_transform.rotation.to_euler(EulerRot::YXZ);
-        yaw += delta;
+        yaw += if mirrored { -delta } else { delta };
         rot_entity_transform.rotation = Quat::from_rotation_y(yaw);
     }
 }
 
-/// Apply zoom to the camera by the given delta.
-pub fn apply_zoom(delta: f32, camera_query: &mut Query<&mut Transform, With<Camera3d>>) {
-    let Ok(mut transform) = camera_query.single_mut() else {
+type RotableWithOffset = (Entity, &'static mut Transform, Option<&'static YawOffset>, Has<Mirrored>);
+
+/// System that turns the rotable entities by the change in the global yaw offset, and new ones
+/// (spawned by a reset) by all of it plus the yaw carried over from the previous round.
+/// Runs while paused too, it lines up the rig rather than the trial.
+pub fn apply_global_yaw_offset(
+    mut commands: Commands,
+    offset: Res<GlobalYawOffset>,
+    carried: Res<CarriedYaw>,
+    mut rot_entities: Query<RotableWithOffset, (With<RotableComponent>, Without<Camera3d>)>,
+) {
+    for (entity, mut transform, applied, mirrored) in rot_entities.iter_mut() {
+        let delta = offset.0 - applied.map_or(-carried.0, |applied| applied.0);
+        if applied.is_some() && delta == 0.0 {
+            continue;
+        }
+        transform.rotate_y(if mirrored { -delta } else { delta });
+        commands.entity(entity).try_insert(YawOffset(offset.0));
+    }
+}
+
+/// Apply zoom to the camera by the given delta (in orbit radius units).
+/// With an orthographic projection the delta scales the projection instead, mapped so the full
+/// radius range spans the full scale range; the camera stays in place.
+pub fn apply_zoom(
+    delta: f32,
+    camera_query: &mut Query<(&mut Transform, &mut Projection), With<Camera3d>>,
+    ortho_scale_range: [f32; 2],
+) {
+    let Ok((mut transform, mut projection)) = camera_query.single_mut() else {
         return;
     };
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        let [min_scale, max_scale] = ortho_scale_range;
+        let scale_per_radius = (max_scale - min_scale) / (CAMERA_3D_MAX_RADIUS - CAMERA_3D_MIN_RADIUS);
+        orthographic.scale = (orthographic.scale + delta * scale_per_radius).clamp(min_scale, max_scale);
+        return;
+    }
+
     let (yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
     let mut radius = transform.translation.xz().length();
 
@@ -37,50 +131,75 @@ pub fn apply_zoom(delta: f32, camera_query: &mut Query<&mut Transform, With<Came
     transform.look_at(Vec3::ZERO, Vec3::Y);
 }
 
-/// System that applies pending rotation from commands.
-pub fn apply_pending_rotation(
-    pending: Res<PendingRotation>,
-    mut rot_entities: Query<&mut Transform, (With<RotableComponent>, Without<Camera3d>)>,
-    shm_res: Option<Res<SharedMemResource>>,
-
-) {
-    // Read shared memory
-    let Some(shm_res) = shm_res else {
-        error!("Shared Memory not initialized in setup_round");
-        return;
-    };
+/// Yaw of a rotable entity from the controls alone, in (-PI, PI]: without the global yaw offset and,
+/// for a mirrored entity, as in the unmirrored scene
+pub fn controls_yaw(transform: &Transform, offset: Option<&YawOffset>, mirrored: bool) -> f32 {
+    let offset = offset.map_or(0.0, |offset| offset.0);
+    let (yaw, _, _) = transform.rotation.to_euler(EulerRot::YXZ);
+    let yaw = if mirrored { -yaw } else { yaw };
+    (yaw - offset + PI).rem_euclid(TAU) - PI
+}
 
-    let shm = shm_res.0.get();
+type PyramidYaw = (Entity, &'static Transform, Option<&'static YawOffset>, Has<Mirrored>);
 
-    let is_animating = shm.game_structure_game.is_animating.load(Ordering::Relaxed);
+/// System that records the pyramid's controls yaw on every played frame and emits the direction it
+/// turns in (`approach_sign`), which a check with `required_approach_sign` must match to win
+pub fn track_approach(
+    mut history: ResMut<YawHistory>,
+    pyramid_query: Query<PyramidYaw, With<Pyramid>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some((entity, transform, offset, mirrored)) = pyramid_query.iter().next() else { return };
+    // A new round's pyramid starts from its own yaw
+    if history.pyramid != Some(entity) {
+        history.pyramid = Some(entity);
+        history.yaws.clear();
+    }
+    if history.yaws.len() == APPROACH_WINDOW_FRAMES {
+        history.yaws.pop_front();
+    }
+    history.yaws.push_back(controls_yaw(transform, offset, mirrored));
 
+    if let Some(shm_res) = shm_res {
+        let sign = approach_sign(history.yaws.iter().copied(), APPROACH_MIN_YAW);
+        shm_res.0.get().game_structure_game.approach_sign.store(sign, Ordering::Relaxed);
+    }
+}
 
+/// System that applies pending rotation from commands (not run while the inputs are locked, see
+/// update_inputs_lock). The lock is only updated at the end of the frame, so a door animation
+/// started this frame is checked here too.
+pub fn apply_pending_rotation(
+    pending: Res<PendingRotation>,
+    mut rot_entities: Query<RotableTransform, (With<RotableComponent>, Without<Camera3d>)>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let is_animating = shm_res.is_some_and(|shm_res| shm_res.0.get().game_structure_game.is_animating.load(Ordering::Relaxed));
     if is_animating || pending.0.abs() < 0.0001 {
         return;
     }
     apply_rotation(pending.0, &mut rot_entities);
 }
 
-/// System that applies pending zoom from commands.
+/// System that applies pending zoom from commands (not run while the inputs are locked, nor during
+/// a door animation, as for rotation).
 pub fn apply_pending_zoom(
     pending: Res<PendingZoom>,
-    mut camera_query: Query<&mut Transform, With<Camera3d>>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<Camera3d>>,
     shm_res: Option<Res<SharedMemResource>>,
 ) {
 
     // Read shared memory
     let Some(shm_res) = shm_res else {
-        error!("Shared Memory not initialized in setup_round");
+        log_event!(error, "Shared Memory not initialized in setup_round");
         return;
     };
 
     let shm = shm_res.0.get();
 
     let is_animating = shm.game_structure_game.is_animating.load(Ordering::Relaxed);
-
-
     if is_animating || pending.0.abs() < 0.0001 {
         return;
     }
-    apply_zoom(pending.0, &mut camera_query);
+    apply_zoom(pending.0, &mut camera_query, ortho_scale_range(&shm.game_structure_game));
 }
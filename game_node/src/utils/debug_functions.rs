@@ -1,17 +1,38 @@
 //! Debug functions for the game.
 use bevy::{prelude::*, window::*};
 
+use crate::command_handler::{DebugNormals, SharedMemResource, ShowThresholdCone};
+use crate::utils::alignment::threshold_half_angle;
+use crate::utils::game_functions::{target_normal, AlignmentTargets};
+use crate::utils::objects::{BaseDoor, FaceMarker};
+use core::sync::atomic::Ordering;
+use shared::constants::{object_constants::GROUND_Y, pyramid_constants::BASE_HEIGHT};
+use shared::SEEN_COMMAND_NAMES;
+
 pub struct DebugFunctionsPlugin;
 
 impl Plugin for DebugFunctionsPlugin {
     /// Builds the plugin by adding the `toggle_vsync` system to the app.
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (toggle_vsync, visualize_lights));
+        app.add_systems(
+            Update,
+            (
+                toggle_vsync,
+                visualize_lights,
+                visualize_normals,
+                visualize_threshold_cone,
+                (toggle_commands_hud, update_commands_hud).chain(),
+            ),
+        );
     }
 }
 
+/// Text overlay of the session context and the command readback, toggled with 'C'
+#[derive(Component)]
+pub struct CommandsHud;
+
 /// Toggles VSync when the 'V' key is pressed.
-fn toggle_vsync(input: Res<ButtonInput<KeyCode>>, mut window: Query<&mut Window>) {
+fn toggle_vsync(input: Res<ButtonInput<KeyCode>>, mut window: Query<&mut Window, With<PrimaryWindow>>) {
     if input.just_pressed(KeyCode::KeyV) {
         let mut window = window.single_mut().unwrap();
 
@@ -46,3 +67,141 @@ fn visualize_lights(
         }
     }
 }
+
+/// Draws the XZ-projected vectors compared by the alignment check while `debug_normals` is set:
+/// door normals (target in green), face normals (in the face color) and the camera forward.
+fn visualize_normals(
+    mut gizmos: Gizmos,
+    debug_normals: Res<DebugNormals>,
+    shm_res: Option<Res<SharedMemResource>>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+    door_query: Query<(&BaseDoor, &Transform)>,
+    face_query: Query<(&FaceMarker, &Transform)>,
+) {
+    if !debug_normals.0 {
+        return;
+    }
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let radius = f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed));
+    let height = f32::from_bits(gs_game.height.load(Ordering::Relaxed));
+    let target_door_idx = gs_game.target_door.load(Ordering::Relaxed) as usize;
+    let flat = |v: Vec3| Vec3::new(v.x, 0.0, v.z).normalize_or_zero();
+
+    // Door normals point inward, so start them outside the base and point at the pyramid
+    for (door, transform) in &door_query {
+        let normal = flat(transform.rotation * door.normal);
+        let start = -normal * radius * 2.5 + Vec3::Y * (GROUND_Y + BASE_HEIGHT);
+        let color = if door.door_index == target_door_idx {
+            Color::srgb(0.0, 1.0, 0.0)
+        } else {
+            Color::srgb(1.0, 1.0, 0.0)
+        };
+        gizmos.arrow(start, start + normal * radius, color);
+    }
+
+    // Face normals point inward too
+    for (face, transform) in &face_query {
+        let normal = flat(transform.rotation * face.normal);
+        let start = -normal * radius * 1.5 + Vec3::Y * (GROUND_Y + BASE_HEIGHT + height / 3.0);
+        gizmos.arrow(start, start + normal * radius, face.base_color);
+    }
+
+    if let Ok(camera_transform) = camera_query.single() {
+        let forward = flat(*camera_transform.forward());
+        let start = Vec3::new(camera_transform.translation.x, GROUND_Y + BASE_HEIGHT, camera_transform.translation.z)
+            + forward * 2.0;
+        gizmos.arrow(start, start + forward * 3.0, Color::WHITE);
+    }
+}
+
+/// Draws the winning region of the alignment check while `show_threshold_cone` is set. A check wins
+/// when the XZ camera forward lies within `acos(threshold)` of the target normal, so an orbiting
+/// camera looking at the pyramid wins from inside this wedge, drawn out to the camera's radius.
+fn visualize_threshold_cone(
+    mut gizmos: Gizmos,
+    show_threshold_cone: Res<ShowThresholdCone>,
+    shm_res: Option<Res<SharedMemResource>>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+    targets: AlignmentTargets,
+) {
+    const ARC_SEGMENTS: usize = 48;
+
+    if !show_threshold_cone.0 {
+        return;
+    }
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let Some(normal) = target_normal(gs_game, &targets) else { return };
+
+    // The camera looks along the inward normal, so the wedge opens on the opposite side
+    let outward = -Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero();
+    let half_angle = threshold_half_angle(f32::from_bits(gs_game.cosine_alignment_threshold.load(Ordering::Relaxed)));
+    let radius = camera_query
+        .single()
+        .map_or(f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed)) * 3.0, |camera| camera.translation.xz().length());
+    let center = Vec3::Y * (GROUND_Y + BASE_HEIGHT);
+    let edge = |angle: f32| center + Quat::from_rotation_y(angle) * outward * radius;
+    let color = Color::srgb(0.0, 1.0, 0.0);
+
+    gizmos.line(center, edge(-half_angle), color);
+    gizmos.line(center, edge(half_angle), color);
+    gizmos.linestrip(
+        (0..=ARC_SEGMENTS).map(|i| edge(-half_angle + 2.0 * half_angle * i as f32 / ARC_SEGMENTS as f32)),
+        color,
+    );
+    gizmos.arrow(edge(0.0), center, Color::WHITE);
+}
+
+/// Shows or hides the command readback HUD when the 'C' key is pressed.
+fn toggle_commands_hud(mut commands: Commands, input: Res<ButtonInput<KeyCode>>, hud: Query<Entity, With<CommandsHud>>) {
+    if !input.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    if let Ok(entity) = hud.single() {
+        commands.entity(entity).despawn();
+        info!("Commands HUD: false");
+        return;
+    }
+    commands.spawn((
+        Text::new(""),
+        TextFont { font_size: 14.0, ..default() },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            bottom: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(4.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        GlobalZIndex(1003), // Above every game overlay
+        CommandsHud,
+    ));
+    info!("Commands HUD: true");
+}
+
+/// Heads the list with the controller's session context, then lists each command's raw value,
+/// whether the game read it set this frame and how many frames it was
+fn update_commands_hud(shm_res: Option<Res<SharedMemResource>>, mut hud: Query<&mut Text, With<CommandsHud>>) {
+    let Ok(mut text) = hud.single_mut() else { return };
+    let Some(shm_res) = shm_res else { return };
+    let shm = shm_res.0.get();
+    let gs_game = &shm.game_structure_game;
+    let seen = gs_game.commands_seen.load(Ordering::Relaxed);
+    let mut lines = format!(
+        "trial {}/{}  block {}  subject {}  session {}\n\ncommand              raw   seen  count",
+        gs_game.current_trial_index.load(Ordering::Relaxed),
+        gs_game.total_trials.load(Ordering::Relaxed),
+        gs_game.block_index.load(Ordering::Relaxed),
+        gs_game.subject_id.load(Ordering::Relaxed),
+        gs_game.session_id.load(Ordering::Relaxed),
+    );
+    for (index, (name, raw)) in SEEN_COMMAND_NAMES.iter().zip(shm.commands.raw_values()).enumerate() {
+        let mark = if seen & (1 << index) != 0 { "*" } else { "" };
+        let count = gs_game.commands_seen_counts[index].load(Ordering::Relaxed);
+        lines.push_str(&format!("\n{name:<20} {raw:<5} {mark:<5} {count}"));
+    }
+    text.0 = lines;
+}
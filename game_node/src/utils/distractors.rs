@@ -0,0 +1,145 @@
+//! Distractor objects scattered on the ground around the pyramid.
+//! They are fixed in the world (not rotable) and carry no door/face components, so the
+//! alignment math never sees them.
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::log;
+use crate::utils::game_functions::config_color;
+use crate::utils::objects::{Distractor, GameEntity};
+use shared::constants::{
+    camera_3d_constants::CAMERA_3D_MIN_RADIUS, object_constants::GROUND_Y,
+    pyramid_constants::BASE_RADIUS,
+};
+use shared::{DistractorShape, SharedGameStructure};
+
+/// Maximum number of draws per distractor before giving up on it
+const MAX_PLACEMENT_ATTEMPTS: usize = 30;
+
+/// A realized distractor: ground position (XZ), size and concrete shape
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DistractorPlacement {
+    pub position: Vec2,
+    pub size: f32,
+    pub shape: DistractorShape,
+}
+
+/// Deterministically places up to `count` distractors from `seed` inside the ring `[inner, outer]`.
+/// The ring is clamped so objects never reach the wooden base or the camera orbit, and
+/// distractors never overlap each other.
+pub fn distractor_layout(
+    seed: u64,
+    count: u32,
+    shape: DistractorShape,
+    size_range: [f32; 2],
+    ring: [f32; 2],
+) -> Vec<DistractorPlacement> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (min_size, max_size) = (size_range[0].min(size_range[1]), size_range[0].max(size_range[1]));
+
+    // Keep objects clear of the base footprint and inside the camera orbit
+    let inner = ring[0].max(BASE_RADIUS + max_size);
+    let outer = ring[1].min(CAMERA_3D_MIN_RADIUS - max_size);
+    if count == 0 || inner >= outer || max_size <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut placements: Vec<DistractorPlacement> = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            // Uniform over the ring area
+            let radius = rng.random_range((inner * inner)..(outer * outer)).sqrt();
+            let size = if max_size > min_size { rng.random_range(min_size..max_size) } else { max_size };
+            let position = Vec2::new(radius * angle.sin(), radius * angle.cos());
+
+            let overlaps = placements
+                .iter()
+                .any(|p| p.position.distance(position) < p.size + size);
+            if overlaps {
+                continue;
+            }
+
+            let shape = match shape {
+                DistractorShape::Mixed => match rng.random_range(0..3) {
+                    0 => DistractorShape::Cube,
+                    1 => DistractorShape::Sphere,
+                    _ => DistractorShape::Pyramid,
+                },
+                fixed => fixed,
+            };
+            placements.push(DistractorPlacement { position, size, shape });
+            break;
+        }
+    }
+    placements
+}
+
+/// FNV-1a hash of a realized layout, emitted so the stimulus can be reconstructed and checked offline
+pub fn distractor_layout_hash(placements: &[DistractorPlacement]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for p in placements {
+        for word in [p.position.x.to_bits(), p.position.y.to_bits(), p.size.to_bits(), p.shape as u32] {
+            for byte in word.to_le_bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+    }
+    hash
+}
+
+/// Spawns the configured distractors and stores the layout hash in the game region
+pub fn spawn_distractors(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    gs_game: &SharedGameStructure,
+) {
+    let f = |a: &core::sync::atomic::AtomicU32| f32::from_bits(a.load(Ordering::Relaxed));
+    let placements = distractor_layout(
+        gs_game.distractor_seed.load(Ordering::Relaxed),
+        gs_game.distractor_count.load(Ordering::Relaxed),
+        DistractorShape::from_u32(gs_game.distractor_shape.load(Ordering::Relaxed)),
+        [f(&gs_game.distractor_size_range[0]), f(&gs_game.distractor_size_range[1])],
+        [f(&gs_game.distractor_ring[0]), f(&gs_game.distractor_ring[1])],
+    );
+
+    let hash = distractor_layout_hash(&placements);
+    gs_game.distractor_hash.store(hash, Ordering::Relaxed);
+    if placements.is_empty() {
+        return;
+    }
+
+    let material = materials.add(StandardMaterial {
+        base_color: config_color(gs_game, gs_game.distractor_color.each_ref().map(f)),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+
+    for p in &placements {
+        // Sizes are the object's full extent; every shape rests on the ground
+        let mesh = match p.shape {
+            DistractorShape::Sphere => meshes.add(Sphere::new(p.size / 2.0).mesh().uv(16, 8)),
+            DistractorShape::Pyramid => meshes.add(Cone::new(p.size / 2.0, p.size).mesh().resolution(4)),
+            _ => meshes.add(Cuboid::from_length(p.size)),
+        };
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(p.position.x, GROUND_Y + p.size / 2.0, p.position.y),
+            Distractor,
+            GameEntity,
+        ));
+    }
+
+    log!(
+        "🧱 Spawned {} distractors (layout hash {:016x}): {:?}",
+        placements.len(),
+        hash,
+        placements.iter().map(|p| (p.position.x, p.position.y, p.size, p.shape)).collect::<Vec<_>>()
+    );
+}
@@ -1,16 +1,28 @@
 //! Core game and UI functions.
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 
-use crate::command_handler::PendingCheckAlignment;
+use crate::command_handler::{PendingCheckAlignment, PendingProbe, PendingPyramidType, PendingRenderQuality, RenderingPaused};
+use crate::log;
+use crate::log_event;
 use crate::command_handler::SharedMemResource;
+use crate::utils::alignment::{best_aligned_door, is_win, xz_alignment};
+use crate::display_rate::DisplayRate;
+use crate::state_emitter::FrameCounterResource;
 use crate::utils::objects::{
-    BaseDoor, DoorWinEntities, GameEntity, HoleEmissive, HoleLight, ScoreBarFill,
-    ScoreBarUI, UIEntity,
+    AnimatedDecoration, BaseDoor, BaseFrame, DecorationMarker, DecorationProbe, DoorWinEntities, FaceMarker, GameEntity, HoleEmissive, HoleLight, LiveFeedbackBar, LiveFeedbackFill, Mirrored,
+    PersistentCamera, RadiusMarker, ReticlePart, RoundStartTimestamp, ScoreBarFill, ScoreBarText, ScoreBarUI, UIEntity,
 };
+use crate::utils::pyramid::door_for_face;
+use crate::utils::systems_logic::{BlankScreenState, TrialPhase};
 use core::sync::atomic::Ordering;
+use core::time::Duration;
+use shared::{derive_pyramid_faces, AnimationEasing, ColorSpace, DecorationMotion, Phase, PyramidType, RenderQuality, ReticleStyle, ScoreBarAnchor, SharedGameStructure, TargetKind};
+use shared::constants::camera_3d_constants::{CAMERA_3D_MAX_RADIUS, CAMERA_3D_MIN_RADIUS};
 use shared::constants::game_constants::{
-    SCORE_BAR_BORDER_THICKNESS, SCORE_BAR_HEIGHT, SCORE_BAR_TOP_OFFSET, SCORE_BAR_WIDTH_PERCENT,
-    UI_REFERENCE_HEIGHT,
+    LIVE_FEEDBACK_BAR_BOTTOM_OFFSET, LIVE_FEEDBACK_BAR_HEIGHT, LIVE_FEEDBACK_BAR_WIDTH_PERCENT, RADIUS_MARKER_GAP,
+    RADIUS_MARKER_HEIGHT, RETICLE_THICKNESS, SCORE_BAR_BORDER_THICKNESS, SHAPING_COSINE_TOLERANCE, SCORE_BAR_TEXT_GAP, SCORE_BAR_TOP_OFFSET,
+    UI_REFERENCE_HEIGHT, UI_REFERENCE_WIDTH, UI_SCALE_RANGE,
 };
 
 /// Helper to despawn ui entities given a mutable commands reference
@@ -27,16 +39,30 @@ pub fn cleanup_game_entities(mut commands: Commands, query: Query<Entity, With<G
     }
 }
 
+/// Doors and faces the alignment check can target
+pub type AlignmentTargets<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Transform, Option<&'static BaseDoor>, Option<&'static FaceMarker>),
+    Or<(With<BaseDoor>, With<FaceMarker>)>,
+>;
+
+/// Hole lights and emissive discs, with their parent frame
+pub type HoleEntities<'w, 's> =
+    Query<'w, 's, (Entity, &'static ChildOf, Has<HoleLight>), Or<(With<HoleLight>, With<HoleEmissive>)>>;
+
 /// Applies pending check alignment
 pub fn apply_pending_check_alignment(
     pending: Res<PendingCheckAlignment>,
     shm_res: Option<Res<SharedMemResource>>,
     camera_query: Query<&Transform, With<Camera3d>>,
-    door_query: Query<(Entity, &BaseDoor, &Transform)>,
+    targets: AlignmentTargets,
     mut commands: Commands,
-    time: Res<Time>,
+    (time, round_start): (Res<Time>, Option<Res<RoundStartTimestamp>>),
     ui_query: Query<Entity, With<UIEntity>>,
     mut door_win_entities: ResMut<DoorWinEntities>,
+    blank_state: Option<Res<BlankScreenState>>,
+    trial: Option<Res<TrialPhase>>,
 ) {
     let Some(shm_res) = shm_res else { return };
     let shm = shm_res.0.get();
@@ -47,6 +73,27 @@ pub fn apply_pending_check_alignment(
         return;
     }
 
+    // No trial is loaded before the first reset, so there is nothing to check against
+    let phase = trial.map_or(Phase::Playing, |trial| trial.phase);
+    if phase == Phase::Standby {
+        let refused = gs_game.standby_checks.fetch_add(1, Ordering::Relaxed) + 1;
+        log_event!(error, "Check refused in standby, no trial loaded yet ({} refused)", refused);
+        return;
+    }
+    // Once the trial has ended, through its interval, there is nothing left to win or count
+    if phase != Phase::Playing {
+        log_event!(commands, "Check ignored, the trial has ended ({:?})", phase);
+        return;
+    }
+
+    // A check while blanked (e.g. a bumped button) is rejected rather than counted, when configured.
+    // Checks while rendering is paused never get here, this system does not run then
+    let blanked = blank_state.is_some_and(|state| state.is_active);
+    if blanked && gs_game.suppress_blanked_attempts.load(Ordering::Relaxed) {
+        gs_game.rejected_checks.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+
     // Increment attempt counter
     let attempts = gs_game.attempts.load(Ordering::Relaxed) + 1;
     gs_game.attempts.store(attempts, Ordering::Relaxed);
@@ -55,70 +102,245 @@ pub fn apply_pending_check_alignment(
         return;
     };
 
-    // Get local camera direction
-    let camera_forward = camera_transform.forward();
-
-    // Project camera forward to XZ plane
-    let camera_forward_xz = Vec3::new(camera_forward.x, 0.0, camera_forward.z).normalize();
+    // Alignment of the target (door or face, depending on the target kind) with the camera
+    let measured = indexed_target_normal(gs_game, &targets);
+    let winning_alignment = measured.map_or(-1.0, |(_, normal)| xz_alignment(normal, *camera_transform.forward()));
 
-    let mut best_alignment = -1.0;
-    let mut _best_door_index = 0;
-    let mut winning_door_alignment = -1.0;
-
-    // Determine target door from SHM
-    let target_door_idx = gs_game.target_door.load(Ordering::Relaxed);
-
-    for (_, door, door_transform) in &door_query {
-        // Get door normal in world space
-        let door_normal_world = door_transform.rotation * door.normal;
+    // Store alignment for score bar animation AND SHM
+    gs_game
+        .current_alignment
+        .store(winning_alignment.to_bits(), Ordering::Relaxed);
 
-        // Project to XZ plane
-        let door_normal_xz = Vec3::new(door_normal_world.x, 0.0, door_normal_world.z).normalize();
+    // Orbit radius criterion, reported separately so an aligned miss can be told apart
+    let radius = camera_transform.translation.xz().length();
+    let viewed_secs = viewed_secs(&time, round_start.as_deref());
+    let eligibility = win_eligibility(gs_game, radius, viewed_secs);
+    let threshold = f32::from_bits(gs_game.cosine_alignment_threshold.load(Ordering::Relaxed));
+    let aligned = measured.is_some_and(|(index, _)| is_win(winning_alignment, threshold, index, target_index(gs_game)));
+    gs_game.radius_at_check.store(radius.to_bits(), Ordering::Relaxed);
+    gs_game.win_blocked_by_radius.store(aligned && !eligibility.in_band, Ordering::Relaxed);
 
-        // Calculate alignment (dot product)
-        let alignment = door_normal_xz.dot(camera_forward_xz);
+    // Approach direction criterion, reported like the radius band
+    gs_game.win_blocked_by_approach.store(aligned && !eligibility.approached, Ordering::Relaxed);
 
-        // Most positive = door facing toward camera (from outside)
-        if alignment > best_alignment {
-            best_alignment = alignment;
-            _best_door_index = door.door_index;
-        }
-
-        // Save the alignment for the target door
-        if door.door_index as u32 == target_door_idx {
-            winning_door_alignment = alignment;
-        }
+    // Minimum viewing time: a check too soon after the reset counts as an attempt but cannot win
+    gs_game.check_too_early.store(eligibility.too_early, Ordering::Relaxed);
+    if eligibility.too_early {
+        let min_view_secs = f32::from_bits(gs_game.min_view_secs.load(Ordering::Relaxed));
+        log!("⏳ Check #{} after {:.2}s, before min_view_secs {:.2}s: non-win", attempts, viewed_secs, min_view_secs);
     }
 
-    // Store alignment for score bar animation AND SHM
-    gs_game
-        .current_alignment
-        .store(winning_door_alignment.to_bits(), Ordering::Relaxed);
+    // Catch trial: nothing is the target, so the check is a non-win whatever the alignment
+    let is_catch = gs_game.is_catch.load(Ordering::Relaxed);
+    if is_catch {
+        log!("🎣 Catch trial check #{}: non-win (alignment {:.4})", attempts, winning_alignment);
+    }
 
     // Player wins
-    if winning_door_alignment > f32::from_bits(gs_game.cosine_alignment_threshold.load(Ordering::Relaxed)) {
+    if aligned && eligibility.met() && !is_catch {
         // Player wins! Set win time in SHM to trigger win state
-        gs_game.win_time.store(time.elapsed().as_secs_f32().to_bits(), Ordering::Relaxed);
+        record_win(gs_game, time.elapsed().as_secs_f32());
     }
 
     // Every alignment check triggers the door animation on the winning light/emissive
-    gs_game.is_animating.store(true, Ordering::Relaxed);
-    door_win_entities.animation_start_time = Some(time.elapsed());
+    start_feedback(gs_game, &mut door_win_entities, time.elapsed());
 
     // Clean old UI and spawn new (Score Bar)
     despawn_ui_helper(&mut commands, &ui_query);
-    spawn_score_bar(&mut commands);
+    spawn_score_bar(&mut commands, gs_game);
+    spawn_radius_marker(&mut commands, gs_game, radius);
+}
+
+/// Orbit radius band `[min, max]` a check must be made from to win, `None` when not required
+pub fn win_radius_band(gs_game: &SharedGameStructure) -> Option<[f32; 2]> {
+    let max = f32::from_bits(gs_game.win_radius_max.load(Ordering::Relaxed));
+    (max > 0.0).then(|| [f32::from_bits(gs_game.win_radius_min.load(Ordering::Relaxed)), max])
+}
+
+/// Seconds the current round has been on screen, 0 before its first frame
+fn viewed_secs(time: &Time, round_start: Option<&RoundStartTimestamp>) -> f32 {
+    round_start
+        .and_then(|start| start.0)
+        .map_or(0.0, |start| (time.elapsed() - start).as_secs_f32())
+}
+
+/// Criteria besides the alignment itself that a win must meet, the same for an explicit check and
+/// the shaping dwell
+struct WinEligibility {
+    /// Within the orbit radius band (`win_radius_min`..=`win_radius_max`), or no band is required
+    in_band: bool,
+    /// Turning the way `required_approach_sign` requires, or no direction is required
+    approached: bool,
+    /// Before `min_view_secs` of the round have been viewed
+    too_early: bool,
+}
+
+impl WinEligibility {
+    fn met(&self) -> bool {
+        self.in_band && self.approached && !self.too_early
+    }
+}
+
+/// Win criteria at orbit `radius`, `viewed_secs` into the round
+fn win_eligibility(gs_game: &SharedGameStructure, radius: f32, viewed_secs: f32) -> WinEligibility {
+    let required_sign = gs_game.required_approach_sign.load(Ordering::Relaxed);
+    WinEligibility {
+        in_band: win_radius_band(gs_game).is_none_or(|[min, max]| (min..=max).contains(&radius)),
+        approached: required_sign == 0 || gs_game.approach_sign.load(Ordering::Relaxed) == required_sign,
+        too_early: viewed_secs < f32::from_bits(gs_game.min_view_secs.load(Ordering::Relaxed)),
+    }
+}
+
+/// World-space inward normal of the alignment target: the target door, or the target face in face mode.
+/// `None` while the target is not spawned.
+pub fn target_normal(gs_game: &SharedGameStructure, targets: &AlignmentTargets) -> Option<Vec3> {
+    indexed_target_normal(gs_game, targets).map(|(_, normal)| normal)
+}
+
+/// Index of the alignment target: the target door, or the target face in face mode
+pub fn target_index(gs_game: &SharedGameStructure) -> usize {
+    match TargetKind::from_u32(gs_game.target_kind.load(Ordering::Relaxed)) {
+        Some(TargetKind::Face) => gs_game.target_face.load(Ordering::Relaxed) as usize,
+        _ => gs_game.target_door.load(Ordering::Relaxed) as usize,
+    }
+}
+
+/// target_normal along with the index of the door or face it was found on
+fn indexed_target_normal(gs_game: &SharedGameStructure, targets: &AlignmentTargets) -> Option<(usize, Vec3)> {
+    let face_mode = TargetKind::from_u32(gs_game.target_kind.load(Ordering::Relaxed)) == Some(TargetKind::Face);
+    let target = target_index(gs_game);
+
+    targets.iter().find_map(|(transform, door, face)| match (door, face) {
+        (_, Some(face)) if face_mode && face.face_index == target => Some((face.face_index, transform.rotation * face.normal)),
+        (Some(door), _) if !face_mode && door.door_index == target => Some((door.door_index, transform.rotation * door.normal)),
+        _ => None,
+    })
 }
 
-/// Spawns the energy score bar at the top center of the screen
-pub fn spawn_score_bar(commands: &mut Commands) {
-    // Container for the score bar (centered at top)
+/// Door a win is credited to: the target door, or in face mode the door under the target face
+pub fn winning_door(gs_game: &SharedGameStructure) -> usize {
+    match TargetKind::from_u32(gs_game.target_kind.load(Ordering::Relaxed)) {
+        Some(TargetKind::Face) => door_for_face(gs_game.target_face.load(Ordering::Relaxed) as usize),
+        _ => gs_game.target_door.load(Ordering::Relaxed) as usize,
+    }
+}
+
+/// Marks the round won at `elapsed_secs`, reporting the winning door's reward weight along with it
+fn record_win(gs_game: &SharedGameStructure, elapsed_secs: f32) {
+    let reward_weight = gs_game
+        .door_reward_weights
+        .get(winning_door(gs_game))
+        .map_or(1.0, |weight| f32::from_bits(weight.load(Ordering::Relaxed)));
+    gs_game.reward_weight.store(reward_weight.to_bits(), Ordering::Relaxed);
+    gs_game.win_time.store(elapsed_secs.to_bits(), Ordering::Relaxed);
+}
+
+/// Index of the door the camera faces most directly. `None` while no door is spawned.
+pub fn best_door(targets: &AlignmentTargets, camera_forward: Vec3) -> Option<usize> {
+    best_aligned_door(
+        camera_forward,
+        targets.iter().filter_map(|(transform, door, _)| door.map(|door| (door.door_index, transform.rotation * door.normal))),
+    )
+}
+
+/// Live feedback bar level in [0, 1]: full when aligned, emptying `gain` times faster as the camera turns away
+pub fn live_feedback_level(alignment: f32, gain: f32) -> f32 {
+    (1.0 - (1.0 - alignment) * gain).clamp(0.0, 1.0)
+}
+
+/// Advances the shaping dwell timer: accumulates while the alignment is within tolerance, restarts otherwise
+pub fn accumulate_dwell(dwell_elapsed: f32, delta_secs: f32, alignment: f32, tolerance: f32) -> f32 {
+    if alignment > tolerance {
+        dwell_elapsed + delta_secs
+    } else {
+        0.0
+    }
+}
+
+/// Shaping auto-win: triggers a win once the camera has dwelt near the target door long enough
+pub fn apply_shaping_dwell(
+    shm_res: Option<Res<SharedMemResource>>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+    targets: AlignmentTargets,
+    (time, round_start): (Res<Time>, Option<Res<RoundStartTimestamp>>),
+    mut door_win_entities: ResMut<DoorWinEntities>,
+    trial: Option<Res<TrialPhase>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    // Disabled (explicit-check-only), a catch trial, no trial playing (standby, or ended and in its
+    // interval), or the round is already won / animating
+    let dwell_secs = f32::from_bits(gs_game.shaping_dwell_secs.load(Ordering::Relaxed));
+    if dwell_secs <= 0.0
+        || trial.is_some_and(|trial| trial.phase != Phase::Playing)
+        || gs_game.is_catch.load(Ordering::Relaxed)
+        || gs_game.is_animating.load(Ordering::Relaxed)
+        || gs_game.win_time.load(Ordering::Relaxed) != 0
+    {
+        return;
+    }
+
+    let Ok(camera_transform) = camera_query.single() else {
+        return;
+    };
+    // Alignment with the target door (or face), counted only while an explicit check could win (within
+    // the radius band, turning the required way, after the minimum viewing time), so the dwell cannot
+    // win where a check could not
+    let radius = camera_transform.translation.xz().length();
+    let eligible = win_eligibility(gs_game, radius, viewed_secs(&time, round_start.as_deref())).met();
+    let alignment = target_normal(gs_game, &targets)
+        .filter(|_| eligible)
+        .map_or(-1.0, |normal| xz_alignment(normal, *camera_transform.forward()));
+
+    let dwell_elapsed = accumulate_dwell(
+        f32::from_bits(gs_game.shaping_dwell_elapsed.load(Ordering::Relaxed)),
+        time.delta_secs(),
+        alignment,
+        SHAPING_COSINE_TOLERANCE,
+    );
+    gs_game.shaping_dwell_elapsed.store(dwell_elapsed.to_bits(), Ordering::Relaxed);
+
+    if dwell_elapsed >= dwell_secs {
+        // Same win path as an explicit check, without counting an attempt
+        record_win(gs_game, time.elapsed().as_secs_f32());
+        start_feedback(gs_game, &mut door_win_entities, time.elapsed());
+    }
+}
+
+/// Starts the door animation of a decided check `feedback_delay_ms` after `now`. The gap counts as
+/// animating, so the camera stays put, and is flagged as `feedback_pending` until the onset.
+fn start_feedback(gs_game: &SharedGameStructure, door_win_entities: &mut DoorWinEntities, now: Duration) {
+    let delay = Duration::from_millis(gs_game.feedback_delay_ms.load(Ordering::Relaxed).into());
+    gs_game.feedback_pending.store(!delay.is_zero(), Ordering::Relaxed);
+    gs_game.is_animating.store(true, Ordering::Relaxed);
+    door_win_entities.animation_start_time = Some(now + delay);
+}
+
+/// Vertical placement of a score bar row `offset` pixels from the configured edge, as (top, bottom)
+fn score_bar_edge(gs_game: &SharedGameStructure, offset: f32) -> (Val, Val) {
+    match ScoreBarAnchor::from_u32(gs_game.score_bar_anchor.load(Ordering::Relaxed)).unwrap_or_default() {
+        ScoreBarAnchor::Top => (Val::Px(offset), Val::Auto),
+        ScoreBarAnchor::Bottom => (Val::Auto, Val::Px(offset)),
+    }
+}
+
+/// Spawns the energy score bar centered at the top or bottom edge of the screen, sized by the config,
+/// with the alignment percentage to its right when `score_bar_show_numeric` is set
+pub fn spawn_score_bar(commands: &mut Commands, gs_game: &SharedGameStructure) {
+    let width = f32::from_bits(gs_game.score_bar_width_percent.load(Ordering::Relaxed));
+    let height = f32::from_bits(gs_game.score_bar_height.load(Ordering::Relaxed));
+    let show_numeric = gs_game.score_bar_show_numeric.load(Ordering::Relaxed);
+    let (top, bottom) = score_bar_edge(gs_game, SCORE_BAR_TOP_OFFSET);
+
+    // Container for the score bar (centered horizontally)
     commands
         .spawn((
             Node {
                 position_type: PositionType::Absolute,
                 width: Val::Percent(100.0),
-                top: Val::Px(SCORE_BAR_TOP_OFFSET),
+                top,
+                bottom,
                 justify_content: JustifyContent::Center,
                 ..default()
             },
@@ -129,8 +351,8 @@ pub fn spawn_score_bar(commands: &mut Commands) {
             parent
                 .spawn((
                     Node {
-                        width: Val::Percent(SCORE_BAR_WIDTH_PERCENT),
-                        height: Val::Px(SCORE_BAR_HEIGHT),
+                        width: Val::Percent(width),
+                        height: Val::Px(height),
                         border: UiRect::all(Val::Px(SCORE_BAR_BORDER_THICKNESS)),
                         padding: UiRect::all(Val::Px(2.0)),
                         ..default()
@@ -149,10 +371,328 @@ pub fn spawn_score_bar(commands: &mut Commands) {
                         BackgroundColor(Color::srgba(0.2, 0.6, 1.0, 0.3)), // Dim cyan glow when empty
                         ScoreBarFill,
                     ));
+                    // Percentage outside the bar's right end, so the bar stays centered
+                    if show_numeric {
+                        bar_parent.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(100.0),
+                                margin: UiRect::left(Val::Px(SCORE_BAR_TEXT_GAP)),
+                                ..default()
+                            },
+                            Text::new(""),
+                            TextFont { font_size: height, ..default() },
+                            TextColor(Color::WHITE),
+                            ScoreBarText,
+                        ));
+                    }
+                });
+        });
+}
+
+/// Spawns the radius criterion marker next to the score bar: the zoom range as a track, the
+/// win band highlighted on it, and a tick at the check radius (green inside the band, red outside).
+/// Nothing is spawned when no radius band is required.
+pub fn spawn_radius_marker(commands: &mut Commands, gs_game: &SharedGameStructure, radius: f32) {
+    let Some([min, max]) = win_radius_band(gs_game) else {
+        return;
+    };
+    let percent = |r: f32| {
+        ((r - CAMERA_3D_MIN_RADIUS) / (CAMERA_3D_MAX_RADIUS - CAMERA_3D_MIN_RADIUS) * 100.0).clamp(0.0, 100.0)
+    };
+    let tick_color = if (min..=max).contains(&radius) {
+        Color::srgba(0.2, 1.0, 0.3, 0.9)
+    } else {
+        Color::srgba(1.0, 0.2, 0.2, 0.9)
+    };
+
+    // Next to the score bar, on the screen side (under a top bar, above a bottom one)
+    let bar_height = f32::from_bits(gs_game.score_bar_height.load(Ordering::Relaxed));
+    let (top, bottom) = score_bar_edge(gs_game, SCORE_BAR_TOP_OFFSET + bar_height + RADIUS_MARKER_GAP);
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top,
+                bottom,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            UIEntity,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(f32::from_bits(gs_game.score_bar_width_percent.load(Ordering::Relaxed))),
+                        height: Val::Px(RADIUS_MARKER_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.5)),
+                    RadiusMarker,
+                ))
+                .with_children(|track| {
+                    // Accepted band
+                    track.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(percent(min)),
+                            width: Val::Percent(percent(max) - percent(min)),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.2, 0.6, 1.0, 0.4)),
+                    ));
+                    // Radius at the check
+                    track.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Percent(percent(radius)),
+                            width: Val::Px(2.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(tick_color),
+                    ));
+                });
+        });
+}
+
+/// A config color read in the round's color space: sRGB as given, or linear values used as is
+pub fn config_color(gs: &SharedGameStructure, [r, g, b, a]: [f32; 4]) -> Color {
+    match ColorSpace::from_u32(gs.color_space.load(Ordering::Relaxed)) {
+        Some(ColorSpace::Linear) => Color::linear_rgba(r, g, b, a),
+        _ => Color::srgba(r, g, b, a),
+    }
+}
+
+/// Config RGBA of `color` in the region's color space, the inverse of `config_color`
+pub fn config_rgba(gs: &SharedGameStructure, color: Color) -> [f32; 4] {
+    match ColorSpace::from_u32(gs.color_space.load(Ordering::Relaxed)) {
+        Some(ColorSpace::Linear) => color.to_linear().to_f32_array(),
+        _ => color.to_srgba().to_f32_array(),
+    }
+}
+
+/// Spawns the aiming reticle at the screen center, above the scene but below the blank overlay
+pub fn spawn_reticle(commands: &mut Commands, gs_game: &SharedGameStructure) {
+    if !gs_game.reticle_enabled.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let size = f32::from_bits(gs_game.reticle_size.load(Ordering::Relaxed));
+    let c = |i: usize| f32::from_bits(gs_game.reticle_color[i].load(Ordering::Relaxed));
+    let color = config_color(gs_game, [c(0), c(1), c(2), c(3)]);
+    let style = ReticleStyle::from_u32(gs_game.reticle_style.load(Ordering::Relaxed));
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            GlobalZIndex(500), // Below the blank screen overlay
+            GameEntity,
+        ))
+        .with_children(|parent| match style {
+            ReticleStyle::Dot => {
+                parent.spawn((
+                    Node {
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                    ReticlePart { base_color: color, outline: false },
+                ));
+            }
+            ReticleStyle::Cross => {
+                // Horizontal and vertical bars overlapping at the center
+                for (width, height) in [(size, RETICLE_THICKNESS), (RETICLE_THICKNESS, size)] {
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(width),
+                            height: Val::Px(height),
+                            ..default()
+                        },
+                        BackgroundColor(color),
+                        ReticlePart { base_color: color, outline: false },
+                    ));
+                }
+            }
+            ReticleStyle::Circle => {
+                parent.spawn((
+                    Node {
+                        width: Val::Px(size),
+                        height: Val::Px(size),
+                        border: UiRect::all(Val::Px(RETICLE_THICKNESS)),
+                        border_radius: BorderRadius::MAX,
+                        ..default()
+                    },
+                    BorderColor::all(color),
+                    ReticlePart { base_color: color, outline: true },
+                ));
+            }
+        });
+}
+
+/// Alignment with the door the camera faces most directly, whichever it is. `None` while no door is spawned.
+pub fn best_door_alignment<'a>(doors: impl IntoIterator<Item = (&'a BaseDoor, &'a Transform)>, camera_forward: Vec3) -> Option<f32> {
+    doors
+        .into_iter()
+        .map(|(door, door_transform)| xz_alignment(door_transform.rotation * door.normal, camera_forward))
+        .max_by(f32::total_cmp)
+}
+
+/// Tints the reticle toward green as the camera lines up with a door and the alignment approaches
+/// the win threshold. It follows the best-facing door, whichever it is, so the tint never tells
+/// the target apart from the other doors
+pub fn update_reticle_tint(
+    shm_res: Option<Res<SharedMemResource>>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+    door_query: Query<(&BaseDoor, &Transform)>,
+    mut reticle_query: Query<(&ReticlePart, &mut BackgroundColor, &mut BorderColor)>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    if !gs_game.reticle_alignment_tint.load(Ordering::Relaxed) {
+        return;
+    }
+    let Some(alignment) = camera_query.single().ok().and_then(|camera| best_door_alignment(door_query, *camera.forward())) else {
+        return;
+    };
+
+    let threshold = f32::from_bits(gs_game.cosine_alignment_threshold.load(Ordering::Relaxed));
+    let t = if threshold > 0.0 {
+        (alignment / threshold).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+
+    for (part, mut bg_color, mut border_color) in &mut reticle_query {
+        let tinted = part.base_color.mix(&Color::srgba(0.0, 1.0, 0.0, part.base_color.alpha()), t);
+        if part.outline {
+            *border_color = BorderColor::all(tinted);
+        } else {
+            *bg_color = BackgroundColor(tinted);
+        }
+    }
+}
+
+/// Spawns the slim live feedback bar at the bottom of the screen, starting hidden and empty
+pub fn spawn_live_feedback_bar(commands: &mut Commands, gs_game: &SharedGameStructure) {
+    if !gs_game.live_feedback_enabled.load(Ordering::Relaxed) {
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Px(LIVE_FEEDBACK_BAR_BOTTOM_OFFSET),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            Visibility::Hidden,
+            GlobalZIndex(500), // Below the blank screen overlay
+            LiveFeedbackBar,
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Percent(LIVE_FEEDBACK_BAR_WIDTH_PERCENT),
+                        height: Val::Px(LIVE_FEEDBACK_BAR_HEIGHT),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.3)),
+                ))
+                .with_children(|bar_parent| {
+                    bar_parent.spawn((
+                        Node {
+                            width: Val::Percent(0.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.2, 0.4, 1.0, 0.6)),
+                        LiveFeedbackFill::default(),
+                    ));
                 });
         });
 }
 
+/// Drives the live feedback bar from the instantaneous target alignment, cold blue when turned
+/// away and hot red when aligned. Hidden during door animations and blanking.
+pub fn update_live_feedback_bar(
+    shm_res: Option<Res<SharedMemResource>>,
+    blank_state: Res<BlankScreenState>,
+    camera_query: Query<&Transform, With<Camera3d>>,
+    targets: AlignmentTargets,
+    mut bar_query: Query<&mut Visibility, With<LiveFeedbackBar>>,
+    mut fill_query: Query<(&mut Node, &mut BackgroundColor, &mut LiveFeedbackFill)>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let Ok(mut visibility) = bar_query.single_mut() else {
+        gs_game.live_feedback_visible.store(false, Ordering::Relaxed);
+        return;
+    };
+    let visible = !gs_game.is_animating.load(Ordering::Relaxed) && !blank_state.is_active;
+    *visibility = if visible { Visibility::Inherited } else { Visibility::Hidden };
+    gs_game.live_feedback_visible.store(visible, Ordering::Relaxed);
+
+    let (Ok(camera_transform), Some(normal)) = (camera_query.single(), target_normal(gs_game, &targets)) else {
+        return;
+    };
+    let Ok((mut node, mut bg_color, mut fill)) = fill_query.single_mut() else {
+        return;
+    };
+
+    let gain = f32::from_bits(gs_game.live_feedback_gain.load(Ordering::Relaxed));
+    let smoothing = f32::from_bits(gs_game.live_feedback_smoothing.load(Ordering::Relaxed));
+    let target = live_feedback_level(xz_alignment(normal, *camera_transform.forward()), gain);
+    fill.level = fill.level * smoothing + target * (1.0 - smoothing);
+
+    node.width = Val::Percent(fill.level * 100.0);
+    *bg_color = BackgroundColor(Color::srgba(0.2, 0.4, 1.0, 0.6).mix(&Color::srgba(1.0, 0.3, 0.1, 0.8), fill.level));
+}
+
+/// Door glow intensity (0.0 to 1.0) `elapsed` seconds into the animation, given the ends of its
+/// three phases. The fades follow `easing`, the closing one playing the curve back from 1 to 0
+pub fn door_animation_intensity(
+    elapsed: f32,
+    fade_out_end: f32,
+    stay_open_end: f32,
+    fade_in_end: f32,
+    easing: AnimationEasing,
+) -> f32 {
+    if elapsed < fade_out_end {
+        // Phase 1: Fade Out (Opening) - 0.0 to 1.0
+        easing.apply(elapsed / fade_out_end)
+    } else if elapsed < stay_open_end {
+        // Phase 2: Stay Open - 1.0
+        1.0
+    } else if elapsed < fade_in_end {
+        // Phase 3: Fade In (Closing) - 1.0 to 0.0
+        easing.apply(1.0 - (elapsed - stay_open_end) / (fade_in_end - stay_open_end))
+    } else {
+        // Animation finished
+        0.0
+    }
+}
+
 /// Handles the light animation
 pub fn handle_door_animation(
     mut door_win_entities: ResMut<DoorWinEntities>,
@@ -182,6 +722,12 @@ pub fn handle_door_animation(
         gs_game.is_animating.store(false, Ordering::Relaxed);
         return;
     };
+    // A delayed feedback shows nothing until its onset
+    let pending = time.elapsed() < start_time;
+    gs_game.feedback_pending.store(pending, Ordering::Relaxed);
+    if pending {
+        return;
+    }
     let elapsed = (time.elapsed() - start_time).as_secs_f32();
 
     // Config values from SHM
@@ -208,24 +754,14 @@ pub fn handle_door_animation(
     };
 
     // Calculate animation intensity (0.0 to 1.0)
-    let intensity_factor = if elapsed < fade_out_end {
-        // Phase 1: Fade Out (Opening) - 0.0 to 1.0
-        elapsed / fade_out_end
-    } else if elapsed < stay_open_end {
-        // Phase 2: Stay Open - 1.0
-        1.0
-    } else if elapsed < fade_in_end {
-        // Phase 3: Fade In (Closing) - 1.0 to 0.0
-        1.0 - ((elapsed - stay_open_end) / f32::from_bits(gs_game.door_anim_fade_in.load(Ordering::Relaxed)))
-    } else {
-        // Animation finished
-        0.0
-    };
+    let easing = AnimationEasing::from_u32(gs_game.door_anim_easing.load(Ordering::Relaxed)).unwrap_or_default();
+    let intensity_factor = door_animation_intensity(elapsed, fade_out_end, stay_open_end, fade_in_end, easing);
 
     // Max intensity values 
     let max_spotlight_intensity = f32::from_bits(gs_game.max_spotlight_intensity.load(Ordering::Relaxed));
 
-    if intensity_factor > 0.0 {
+    // In progress until the fade-in ends, also on its first frame where nothing has elapsed yet
+    if elapsed < fade_in_end {
         
         // Animation is in progress — update spotlight
         *light_visibility = Visibility::Visible;
@@ -233,6 +769,12 @@ pub fn handle_door_animation(
         let light_intensity = max_spotlight_intensity * intensity_factor;
         spotlight.intensity = light_intensity;
 
+        // Configured glow color, if any, replaces the spotlight's own for the light and the emissive
+        let glow = gs_game.win_glow_color.each_ref().map(|c| f32::from_bits(c.load(Ordering::Relaxed)));
+        if glow.iter().any(|&c| c != 0.0) {
+            spotlight.color = config_color(gs_game, glow);
+        }
+
         // Also update emissive material
         if let Some(emissive_entity) = door_win_entities.winning_emissive {
             if let Ok((mut emissive_visibility, material_handle)) =
@@ -282,6 +824,7 @@ pub fn update_score_bar_animation(
     shm_res: Option<Res<SharedMemResource>>,
     time: Res<Time>,
     mut fill_query: Query<(&mut Node, &mut BackgroundColor), With<ScoreBarFill>>,
+    mut text_query: Query<&mut Text, With<ScoreBarText>>,
 ) {
     let Ok((mut node, mut bg_color)) = fill_query.single_mut() else {
         return;
@@ -305,7 +848,8 @@ pub fn update_score_bar_animation(
         let Some(start_time) = door_win_entities.animation_start_time else {
             return;
         };
-        let elapsed = (time.elapsed() - start_time).as_secs_f32();
+        // Empty until a delayed feedback starts
+        let elapsed = time.elapsed().saturating_sub(start_time).as_secs_f32();
 
         let fade_out_end = f32::from_bits(
             shm.game_structure_game
@@ -333,6 +877,9 @@ pub fn update_score_bar_animation(
     };
 
     node.width = Val::Percent(current_width);
+    for mut text in &mut text_query {
+        text.0 = if is_animating { format!("{current_width:.0}%") } else { String::new() };
+    }
 
     // Color gradient based on alignment quality (cyan -> yellow -> white)
     let color = if alignment_normalized < 0.5 {
@@ -356,18 +903,310 @@ pub fn update_score_bar_animation(
     *bg_color = BackgroundColor(color);
 }
 
-/// Updates UI scale based on window size for responsive design
-/// Targets 1080p (1920x1080) as the reference resolution
-pub fn update_ui_scale(mut ui_scale: ResMut<UiScale>, window_query: Query<&Window>) {
-    let Ok(window) = window_query.single() else {
+/// Flicker phase in cycles [0, 1) of a face flickering at `hz`, phase-locked to the frame counter
+/// counting at `frame_hz` (see DisplayRate::frame_hz)
+pub fn flicker_phase(frame_number: u64, hz: f32, frame_hz: f64) -> f32 {
+    if hz <= 0.0 {
+        return 0.0;
+    }
+    (frame_number as f64 * hz as f64 / frame_hz).fract() as f32
+}
+
+/// Modulates each face color sinusoidally at its configured flicker frequency (frequency tagging).
+/// While paused or blanked the faces show their spawned color, so no frame is frozen mid-swing.
+pub fn apply_face_flicker(
+    frame_counter: Res<FrameCounterResource>,
+    display_rate: Res<DisplayRate>,
+    blank_state: Res<BlankScreenState>,
+    rendering_paused: Res<RenderingPaused>,
+    shm_res: Option<Res<SharedMemResource>>,
+    face_query: Query<(&FaceMarker, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let suspended = blank_state.is_active || rendering_paused.0;
+
+    for (face, material_handle) in &face_query {
+        let hz = f32::from_bits(gs_game.flicker_hz[face.face_index].load(Ordering::Relaxed));
+        if hz <= 0.0 {
+            continue;
+        }
+        let gain = if suspended {
+            1.0
+        } else {
+            let depth = f32::from_bits(gs_game.flicker_depth[face.face_index].load(Ordering::Relaxed));
+            // The counter is bumped in PostUpdate, so this frame is emitted as frame_counter + 1
+            let phase = flicker_phase(frame_counter.0 + 1, hz, display_rate.frame_hz());
+            1.0 + depth * (phase * std::f32::consts::TAU).sin()
+        };
+
+        let base = face.base_color.to_linear();
+        let color = Color::LinearRgba(LinearRgba::new(base.red * gain, base.green * gain, base.blue * gain, base.alpha));
+        // Only touched when it changes, so a held face does not re-upload its material every frame
+        if materials.get(&material_handle.0).is_some_and(|material| material.base_color != color) {
+            if let Some(material) = materials.get_mut(&material_handle.0) {
+                material.base_color = color;
+            }
+        }
+    }
+}
+
+/// Pose (spin angle in radians, scale factor) of a decoration at `phase` (cycles) after `secs` of
+/// motion. A spin at rate 0 stays put, whatever the phase, so the default config keeps decorations static
+pub fn decoration_pose(
+    motion: DecorationMotion,
+    spin_rate: f32,
+    pulse_amplitude: f32,
+    pulse_hz: f32,
+    phase: f32,
+    secs: f32,
+) -> (f32, f32) {
+    match motion {
+        DecorationMotion::None => (0.0, 1.0),
+        DecorationMotion::Spin if spin_rate == 0.0 => (0.0, 1.0),
+        DecorationMotion::Spin => (std::f32::consts::TAU * phase + spin_rate * secs, 1.0),
+        DecorationMotion::Pulse => {
+            (0.0, 1.0 + pulse_amplitude * (std::f32::consts::TAU * (pulse_hz * secs + phase)).sin())
+        }
+    }
+}
+
+/// Moves every decoration under the trial's `decoration_motion` at the fixed timestep, spinning it
+/// around its face normal in the face's frame (so the spin follows any rotation of the face or the
+/// pyramid) or pulsing its size. The motion clock `decoration_motion_secs` stops while the screen is
+/// blanked. Mirrored decorations spin the other way, like their mirror image
+pub fn animate_decorations(
+    time: Res<Time>,
+    shm_res: Option<Res<SharedMemResource>>,
+    blank_state: Res<BlankScreenState>,
+    mut decorations: Query<(&mut AnimatedDecoration, &mut Transform, Has<Mirrored>)>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    if blank_state.is_active {
         return;
+    }
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let f = |field: &core::sync::atomic::AtomicU32| f32::from_bits(field.load(Ordering::Relaxed));
+    let secs = f(&gs_game.decoration_motion_secs) + time.delta_secs();
+    gs_game.decoration_motion_secs.store(secs.to_bits(), Ordering::Relaxed);
+
+    let motion = DecorationMotion::from_u32(gs_game.decoration_motion.load(Ordering::Relaxed)).unwrap_or_default();
+    let (rate, amplitude, hz) =
+        (f(&gs_game.decoration_spin_rate), f(&gs_game.decoration_pulse_amplitude), f(&gs_game.decoration_pulse_hz));
+    for (mut animated, mut transform, mirrored) in &mut decorations {
+        let (angle, scale) = decoration_pose(motion, rate, amplitude, hz, animated.phase, secs);
+        if angle != animated.angle {
+            let turn = angle - animated.angle;
+            transform.rotate_axis(animated.axis, if mirrored { -turn } else { turn });
+            animated.angle = angle;
+        }
+        if scale != animated.scale {
+            transform.scale *= scale / animated.scale;
+            animated.scale = scale;
+        }
+    }
+}
+
+/// Switches the render quality live: MSAA on the scene camera and shadows on every spotlight. Lights
+/// spawned later (the door lights of each round) get the shadows of the current level. The applied
+/// level is emitted as `render_quality`; an unknown one is ignored.
+pub fn handle_render_quality_command(
+    mut pending: ResMut<PendingRenderQuality>,
+    shm_res: Option<Res<SharedMemResource>>,
+    mut commands: Commands,
+    camera_query: Query<Entity, With<PersistentCamera>>,
+    mut spotlights: Query<&mut SpotLight>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let requested = pending.0.take();
+    if let Some(code) = requested {
+        if RenderQuality::from_u32(code).is_some() {
+            gs_game.render_quality.store(code, Ordering::Relaxed);
+        } else {
+            log_event!(error, "set_render_quality: unknown render quality {}", code);
+        }
+    }
+    let quality = RenderQuality::from_u32(gs_game.render_quality.load(Ordering::Relaxed)).unwrap_or_default();
+    let shadows = quality != RenderQuality::Low;
+
+    if requested.is_some() {
+        let msaa = match quality {
+            RenderQuality::Low => Msaa::Off,
+            RenderQuality::Medium => Msaa::Sample2,
+            RenderQuality::High => Msaa::Sample4,
+        };
+        for camera in &camera_query {
+            commands.entity(camera).insert(msaa);
+        }
+        log_event!(commands, "Render quality set to {:?}", quality);
+    }
+    for mut light in &mut spotlights {
+        if (requested.is_some() || light.is_added()) && light.shadows_enabled != shadows {
+            light.shadows_enabled = shadows;
+        }
+    }
+}
+
+/// Switches the live pyramid between Type1 and Type2: re-derives the face colors and target face
+/// from the trial config (controller region) and recolors the faces in place. Type3 also changes
+/// the decoration shapes, which needs a full respawn, so it is left to the next reset.
+pub fn handle_set_pyramid_type_command(
+    mut pending: ResMut<PendingPyramidType>,
+    shm_res: Option<Res<SharedMemResource>>,
+    mut face_query: Query<(&mut FaceMarker, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut door_win_entities: ResMut<DoorWinEntities>,
+    hole_query: HoleEntities,
+    frame_query: Query<&BaseFrame>,
+) {
+    let Some(code) = pending.0.take() else { return };
+    let Some(shm_res) = shm_res else { return };
+    let shm = shm_res.0.get();
+    let gs_game = &shm.game_structure_game;
+
+    let pyramid_type = match PyramidType::try_from(code) {
+        Ok(pyramid_type @ (PyramidType::Type1 | PyramidType::Type2)) => pyramid_type,
+        Ok(PyramidType::Type3) => {
+            log_event!(error, "set_pyramid_type: only Type1 (0) and Type2 (1) can be switched live, got {}", code);
+            return;
+        }
+        Err(e) => {
+            log_event!(error, "set_pyramid_type ignored: {}", e);
+            return;
+        }
     };
+    // The winning door entities are in use until the animation ends
+    if gs_game.is_animating.load(Ordering::Relaxed) {
+        log_event!(error, "set_pyramid_type ignored: door animation running");
+        return;
+    }
 
-    // Calculate scale based on window height (reference: 1080p)
-    let scale = window.height() / UI_REFERENCE_HEIGHT;
+    let gs_ctrl = &shm.game_structure_control;
+    let configured: [[f32; 4]; 3] = std::array::from_fn(|face| {
+        std::array::from_fn(|c| f32::from_bits(gs_ctrl.colors[face * 4 + c].load(Ordering::Relaxed)))
+    });
+    let (colors, target_face) =
+        derive_pyramid_faces(pyramid_type, configured, gs_ctrl.target_face.load(Ordering::Relaxed));
 
-    // Clamp scale to reasonable bounds (0.5x to 2.0x)
-    let clamped_scale = scale.clamp(0.5, 2.0);
+    for (face, color) in colors.iter().enumerate() {
+        for (c, value) in color.iter().enumerate() {
+            gs_game.colors[face * 4 + c].store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+    gs_game.target_face.store(target_face, Ordering::Relaxed);
+    gs_game.pyramid_type.store(pyramid_type as u32, Ordering::Relaxed);
+
+    for (mut face, material_handle) in &mut face_query {
+        face.base_color = config_color(gs_game, colors[face.face_index]);
+        if let Some(material) = materials.get_mut(&material_handle.0) {
+            material.base_color = face.base_color;
+        }
+    }
+
+    // In face mode the door under the target face animates on a win, as in setup_round
+    if TargetKind::from_u32(gs_game.target_kind.load(Ordering::Relaxed)) == Some(TargetKind::Face) {
+        let target_door = door_for_face(target_face as usize);
+        door_win_entities.winning_light = None;
+        door_win_entities.winning_emissive = None;
+        for (entity, child_of, is_light) in &hole_query {
+            if frame_query.get(child_of.parent()).is_ok_and(|frame| frame.door_index == target_door) {
+                if is_light {
+                    door_win_entities.winning_light = Some(entity);
+                } else {
+                    door_win_entities.winning_emissive = Some(entity);
+                }
+            }
+        }
+    }
+
+    log!("🔺 Pyramid switched live to {:?}, target face {}", pyramid_type, target_face);
+}
+
+/// Recolors the decorations of a probed face with the probe color, then restores their colors once
+/// the probe duration has passed. A new probe first reverts the one running
+pub fn handle_probe_command(
+    time: Res<Time>,
+    mut pending: ResMut<PendingProbe>,
+    mut probe: ResMut<DecorationProbe>,
+    shm_res: Option<Res<SharedMemResource>>,
+    decoration_query: Query<(&DecorationMarker, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let started = pending.0.take();
+    let expired = probe.face.is_some() && probe.timer.tick(time.delta()).is_finished();
+    if started.is_none() && !expired {
+        return;
+    }
+
+    // Revert: materials of a round reset meanwhile are gone, nothing to restore there
+    for (id, color) in probe.originals.drain(..) {
+        if let Some(material) = materials.get_mut(id) {
+            material.base_color = color;
+        }
+    }
+    if let Some(face) = probe.face.take() {
+        log_event!(commands, "Decoration probe on face {} reverted", face);
+    }
 
-    ui_scale.0 = clamped_scale;
+    let Some(face) = started else { return };
+    let Some(shm_res) = shm_res else { return };
+    if face > 2 {
+        log_event!(error, "Decoration probe ignored: face must be 0, 1 or 2, got {}", face);
+        return;
+    }
+    let shm = shm_res.0.get();
+    let rgba = std::array::from_fn(|c| f32::from_bits(shm.commands.probe_color[c].load(Ordering::Relaxed)));
+    let color = config_color(&shm.game_structure_game, rgba);
+    let duration = f32::from_bits(shm.commands.probe_duration_secs.load(Ordering::Relaxed));
+
+    for (decoration, material_handle) in &decoration_query {
+        if decoration.face_index != face as usize {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material_handle.0) {
+            probe.originals.push((material_handle.id(), material.base_color));
+            material.base_color = color;
+        }
+    }
+    probe.face = Some(face as usize);
+    probe.timer = Timer::from_seconds(duration.max(0.0), TimerMode::Once);
+    log_event!(commands, "Decoration probe on face {} for {:.3}s ({} decorations)", face, duration, probe.originals.len());
+}
+
+/// UiScale for a viewport of `size` logical pixels: the smaller of its width and height ratios to
+/// the reference resolution (UI_REFERENCE_WIDTH x UI_REFERENCE_HEIGHT), so the UI fits both ways on
+/// ultrawide and tall screens alike, clamped to UI_SCALE_RANGE
+pub fn ui_scale_for(size: Vec2) -> f32 {
+    let scale = (size.x / UI_REFERENCE_WIDTH).min(size.y / UI_REFERENCE_HEIGHT);
+    scale.clamp(UI_SCALE_RANGE[0], UI_SCALE_RANGE[1])
+}
+
+/// Updates UI scale from the primary window for responsive design, other windows (a second display
+/// for the experimenter) leave it alone. When the scene camera renders to a viewport of the window,
+/// the UI scales with that viewport. A positive `ui_scale` in the round's config overrides it
+pub fn update_ui_scale(
+    mut ui_scale: ResMut<UiScale>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<&Camera, With<PersistentCamera>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Ok(window) = window_query.single() else {
+        return;
+    };
+
+    let configured = shm_res.map_or(0.0, |shm_res| f32::from_bits(shm_res.0.get().game_structure_game.ui_scale.load(Ordering::Relaxed)));
+    if configured > 0.0 && configured.is_finite() {
+        ui_scale.0 = configured;
+        return;
+    }
+
+    let viewport = camera_query.single().ok().and_then(|camera| camera.viewport.as_ref());
+    let size = match viewport {
+        Some(viewport) => viewport.physical_size.as_vec2() / window.scale_factor(),
+        None => window.size(),
+    };
+    ui_scale.0 = ui_scale_for(size);
 }
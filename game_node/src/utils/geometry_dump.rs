@@ -0,0 +1,239 @@
+//! Read-back of the exact geometry on screen, for stimulus verification. On `dump_geometry` the
+//! meshes of the pyramid faces and of every decoration are read from their assets, as rendered
+//! (mirrored ones included), with the entities' transforms and the colors of their materials, and
+//! written to a JSON file in the GeometryDumpDir.
+//!
+//! Written with serde_json, which gives floats in their shortest exact form, so `GeometryDump::from_json`
+//! reads back the same bits and an offline viewer can rebuild the scene without the generator: face transforms are
+//! in world space, decoration transforms relative to their face.
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::command_handler::{PendingDumpGeometry, SharedMemResource};
+use crate::log_event;
+use crate::state_emitter::FrameCounterResource;
+use crate::utils::objects::{DecorationMarker, FaceMarker};
+use shared::constants::game_constants::GEOMETRY_DUMP_DIR;
+
+/// Directory the dumps are written to, GEOMETRY_DUMP_DIR by default
+#[derive(Resource, Clone, Debug)]
+pub struct GeometryDumpDir(pub PathBuf);
+
+impl Default for GeometryDumpDir {
+    fn default() -> Self {
+        Self(PathBuf::from(GEOMETRY_DUMP_DIR))
+    }
+}
+
+/// Triangle list of a mesh: vertex positions and normals, and three indices per triangle
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    /// Vertices of `mesh`, None without positions. Missing normals are left empty, and a mesh
+    /// without indices gets its vertices in order
+    pub fn from_mesh(mesh: &Mesh) -> Option<Self> {
+        let float3 = |attribute| match mesh.attribute(attribute) {
+            Some(VertexAttributeValues::Float32x3(values)) => Some(values.clone()),
+            _ => None,
+        };
+        let positions = float3(Mesh::ATTRIBUTE_POSITION)?;
+        let indices = match mesh.indices() {
+            Some(indices) => indices.iter().map(|index| index as u32).collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        Some(Self { normals: float3(Mesh::ATTRIBUTE_NORMAL).unwrap_or_default(), positions, indices })
+    }
+}
+
+/// Translation, rotation (quaternion xyzw) and scale of an entity
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DumpedTransform {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl From<&Transform> for DumpedTransform {
+    fn from(transform: &Transform) -> Self {
+        Self {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            scale: transform.scale.to_array(),
+        }
+    }
+}
+
+impl DumpedTransform {
+    pub fn to_transform(self) -> Transform {
+        Transform {
+            translation: Vec3::from_array(self.translation),
+            rotation: Quat::from_array(self.rotation),
+            scale: Vec3::from_array(self.scale),
+        }
+    }
+}
+
+/// A pyramid face as rendered: color (sRGB RGBA of its material), world transform and mesh
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FaceGeometry {
+    pub face: u32,
+    pub color: [f32; 4],
+    pub transform: DumpedTransform,
+    pub mesh: MeshData,
+}
+
+/// A decoration as rendered: face, shape (DecorationShape order), size, color (sRGB RGBA of its
+/// material), transform relative to its face, and mesh
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecorationGeometry {
+    pub face: u32,
+    pub shape: u32,
+    pub size: f32,
+    pub color: [f32; 4],
+    pub transform: DumpedTransform,
+    pub mesh: MeshData,
+}
+
+/// The geometry of a trial at one frame
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeometryDump {
+    /// Trial generation (`reset_ack`) and frame it was taken at
+    pub trial: u32,
+    pub frame: u64,
+    /// Stimulus hash of the trial, to match the dump to its archive description
+    pub stimulus_hash: u64,
+    /// Sorted by face
+    pub faces: Vec<FaceGeometry>,
+    /// Sorted by face, then position
+    pub decorations: Vec<DecorationGeometry>,
+}
+
+impl GeometryDump {
+    /// Dump of `faces` and `decorations`, sorted
+    pub fn new(
+        trial: u32,
+        frame: u64,
+        stimulus_hash: u64,
+        mut faces: Vec<FaceGeometry>,
+        mut decorations: Vec<DecorationGeometry>,
+    ) -> Self {
+        faces.sort_by_key(|face| face.face);
+        decorations.sort_by(|a, b| {
+            a.face.cmp(&b.face).then_with(|| {
+                let (a, b) = (a.transform.translation, b.transform.translation);
+                a.iter().zip(&b).fold(core::cmp::Ordering::Equal, |order, (x, y)| order.then(x.total_cmp(y)))
+            })
+        });
+        Self { trial, frame, stimulus_hash, faces, decorations }
+    }
+
+    /// File name of the dump in its GeometryDumpDir
+    pub fn file_name(&self) -> String {
+        format!("trial_{:05}_frame_{}.json", self.trial, self.frame)
+    }
+
+    /// JSON object of the dump
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a geometry dump serializes")
+    }
+
+    /// Dump read back from `to_json`
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let dump: Self = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let meshes = dump.faces.iter().map(|f| &f.mesh).chain(dump.decorations.iter().map(|d| &d.mesh));
+        for mesh in meshes {
+            if mesh.indices.iter().any(|&index| index as usize >= mesh.positions.len()) {
+                return Err(format!("mesh index out of its {} vertices", mesh.positions.len()));
+            }
+        }
+        Ok(dump)
+    }
+}
+
+/// sRGB RGBA of a material, None when it is not loaded
+fn material_color(materials: &Assets<StandardMaterial>, material: Option<&MeshMaterial3d<StandardMaterial>>) -> Option<[f32; 4]> {
+    materials.get(&material?.0).map(|material| material.base_color.to_srgba().to_f32_array())
+}
+
+/// Writes the current round's geometry to the GeometryDumpDir (see GeometryDump)
+pub fn handle_dump_geometry_command(
+    mut pending: ResMut<PendingDumpGeometry>,
+    face_query: Query<(&FaceMarker, &Transform, &Mesh3d, Option<&MeshMaterial3d<StandardMaterial>>)>,
+    decoration_query: Query<(&DecorationMarker, &Transform, &Mesh3d, Option<&MeshMaterial3d<StandardMaterial>>)>,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    frame: Res<FrameCounterResource>,
+    dir: Res<GeometryDumpDir>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    if !pending.0 {
+        return;
+    }
+    pending.0 = false;
+
+    let Some(shm_res) = shm_res else { return };
+    if face_query.is_empty() {
+        log_event!(error, "No pyramid on screen, no geometry dumped");
+        return;
+    }
+    let mesh = |handle: &Mesh3d| meshes.get(&handle.0).and_then(MeshData::from_mesh).unwrap_or_default();
+
+    let faces = face_query
+        .iter()
+        .map(|(face, transform, mesh_handle, material)| FaceGeometry {
+            face: face.face_index as u32,
+            color: material_color(&materials, material).unwrap_or(face.base_color.to_srgba().to_f32_array()),
+            transform: transform.into(),
+            mesh: mesh(mesh_handle),
+        })
+        .collect();
+    let decorations = decoration_query
+        .iter()
+        .map(|(marker, transform, mesh_handle, material)| DecorationGeometry {
+            face: marker.face_index as u32,
+            shape: marker.shape as u32,
+            size: marker.size,
+            color: material_color(&materials, material).unwrap_or(marker.color),
+            transform: transform.into(),
+            mesh: mesh(mesh_handle),
+        })
+        .collect();
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let dump = GeometryDump::new(
+        gs_game.reset_ack.load(Ordering::Acquire),
+        frame.0,
+        gs_game.stimulus_hash.load(Ordering::Relaxed),
+        faces,
+        decorations,
+    );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = dir.0.join(dump.file_name());
+        let result = std::fs::create_dir_all(&dir.0).and_then(|()| std::fs::write(&path, dump.to_json() + "\n"));
+        match result {
+            Ok(()) => log_event!(
+                commands,
+                "📐 Dumped the geometry of {} faces and {} decorations to {}",
+                dump.faces.len(),
+                dump.decorations.len(),
+                path.display()
+            ),
+            Err(e) => log_event!(error, "Failed to dump the geometry to {}: {}", path.display(), e),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = dir;
+        log_event!(commands, "📐 Geometry (no file system on web): {}", dump.to_json());
+    }
+}
@@ -1,4 +1,22 @@
 //! Macros for the game, such as for cross-platform logging.
+//!
+//! `log_event!` logs by category (commands, reset, animation, shm, error). The enabled categories
+//! start from the LOG_CATEGORIES_ENV variable (all when unset) and the controller can change them
+//! through `SharedCommands::log_categories`. Native events go to the console and to a session log
+//! file in LOG_DIR (or the directory set with `--log-dir`), stamped with the time since launch and
+//! the round frame; on web they go to the browser console, grouped by category.
+//!
+//! The categories and their mask parsing are shared::LogCategory, so controllers can set them. The
+//! macros stay here: shared has no log call sites, and the backend needs the game's frame counter.
+
+use bevy::platform::time::Instant;
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use shared::constants::game_constants::LOG_CATEGORIES_ENV;
+use shared::LOG_CATEGORIES_ALL;
+pub use shared::LogCategory;
 
 /// A macro for cross-platform logging (native and web platforms)
 #[macro_export]
@@ -11,3 +29,219 @@ macro_rules! log {
         println!($($t)*);
     }};
 }
+
+/// Logs an event of a category if it is enabled, e.g. `log_event!(reset, "Round started")`
+#[macro_export]
+macro_rules! log_event {
+    ($category:ident, $($t:tt)*) => {
+        $crate::utils::macros::write_event($crate::log_category!($category), format_args!($($t)*))
+    };
+}
+
+/// Times the rest of the enclosing block as a reset profiling section into an
+/// `Option<&ResetSections>`, e.g. `profile_scope!(sections, Setup)` (see profiling.rs). Expands to
+/// nothing without the `profiling` feature
+#[macro_export]
+macro_rules! profile_scope {
+    ($sections:expr, $section:ident) => {
+        #[cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+        let _profile_scope = $sections.map(|sections| sections.start($crate::profiling::Section::$section));
+        #[cfg(not(all(feature = "profiling", not(target_arch = "wasm32"))))]
+        let _ = $sections;
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! log_category {
+    (commands) => { $crate::utils::macros::LogCategory::Commands };
+    (reset) => { $crate::utils::macros::LogCategory::Reset };
+    (animation) => { $crate::utils::macros::LogCategory::Animation };
+    (shm) => { $crate::utils::macros::LogCategory::Shm };
+    (error) => { $crate::utils::macros::LogCategory::Error };
+}
+
+struct EventLog {
+    categories: AtomicU32,
+    start: Instant,
+    #[cfg(not(target_arch = "wasm32"))]
+    dir: Mutex<std::path::PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    file: Mutex<Option<session::SessionLog>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    file_failed: AtomicBool,
+    #[cfg(target_arch = "wasm32")]
+    console_group: Mutex<Option<LogCategory>>,
+}
+
+static EVENT_LOG: OnceLock<EventLog> = OnceLock::new();
+/// Round frame stamped on events, kept by the state emitter's frame counter
+static EVENT_FRAME: AtomicU64 = AtomicU64::new(0);
+
+fn event_log() -> &'static EventLog {
+    EVENT_LOG.get_or_init(|| {
+        let categories = match std::env::var(LOG_CATEGORIES_ENV) {
+            Ok(value) => LogCategory::parse_mask(&value).unwrap_or_else(|e| {
+                log!("{} ignored ({}), logging all categories", LOG_CATEGORIES_ENV, e);
+                LOG_CATEGORIES_ALL
+            }),
+            Err(_) => LOG_CATEGORIES_ALL,
+        };
+        EventLog {
+            categories: AtomicU32::new(categories),
+            start: Instant::now(),
+            #[cfg(not(target_arch = "wasm32"))]
+            dir: Mutex::new(std::path::PathBuf::from(shared::constants::game_constants::LOG_DIR)),
+            #[cfg(not(target_arch = "wasm32"))]
+            file: Mutex::new(None),
+            #[cfg(not(target_arch = "wasm32"))]
+            file_failed: AtomicBool::new(false),
+            #[cfg(target_arch = "wasm32")]
+            console_group: Mutex::new(None),
+        }
+    })
+}
+
+/// Enabled categories, as a LogCategory bitmask
+pub fn log_categories() -> u32 {
+    event_log().categories.load(Ordering::Relaxed)
+}
+
+pub fn set_log_categories(mask: u32) {
+    event_log().categories.store(mask & LOG_CATEGORIES_ALL, Ordering::Relaxed);
+}
+
+pub fn set_event_frame(frame: u64) {
+    EVENT_FRAME.store(frame, Ordering::Relaxed);
+}
+
+/// Directory of the session log (and the default reset profile), LOG_DIR unless set
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log_dir() -> std::path::PathBuf {
+    event_log().dir.lock().map(|dir| dir.clone()).unwrap_or_else(|_| shared::constants::game_constants::LOG_DIR.into())
+}
+
+/// Writes the session log to `dir` instead of LOG_DIR. Only takes effect before the first event
+/// is written, since the session file is created then
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_log_dir(dir: impl Into<std::path::PathBuf>) {
+    if let Ok(mut current) = event_log().dir.lock() {
+        *current = dir.into();
+    }
+}
+
+/// Session log file events are written to, once the first one has been
+#[cfg(not(target_arch = "wasm32"))]
+pub fn session_log_path() -> Option<std::path::PathBuf> {
+    let file = event_log().file.lock().ok()?;
+    file.as_ref().map(|log| log.path().to_path_buf())
+}
+
+/// Backend of `log_event!`
+pub fn write_event(category: LogCategory, message: fmt::Arguments) {
+    let event_log = event_log();
+    if event_log.categories.load(Ordering::Relaxed) & category.bit() == 0 {
+        return;
+    }
+    let line = format!(
+        "[{:>10.3}s f{}] [{}] {}",
+        event_log.start.elapsed().as_secs_f64(),
+        EVENT_FRAME.load(Ordering::Relaxed),
+        category.name(),
+        message
+    );
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if category == LogCategory::Error {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
+        }
+        if event_log.file_failed.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(mut file) = event_log.file.lock() else { return };
+        if file.is_none() {
+            match session::SessionLog::create(&log_dir()) {
+                Ok(log) => *file = Some(log),
+                Err(e) => {
+                    // Console only from here on
+                    eprintln!("Failed to create the session log in {}: {}", log_dir().display(), e);
+                    event_log.file_failed.store(true, Ordering::Relaxed);
+                    return;
+                }
+            }
+        }
+        if let Some(Err(e)) = file.as_mut().map(|log| log.write_line(&line)) {
+            eprintln!("Failed to write the session log: {}", e);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        // Consecutive events of one category share a console group
+        if let Ok(mut group) = event_log.console_group.lock() {
+            if *group != Some(category) {
+                if group.is_some() {
+                    web_sys::console::group_end();
+                }
+                web_sys::console::group_1(&category.name().into());
+                *group = Some(category);
+            }
+        }
+        web_sys::console::log_1(&line.into());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod session {
+    use shared::constants::game_constants::{LOG_FILE_KEEP, LOG_FILE_MAX_BYTES};
+    use std::fs::{self, File};
+    use std::io::{self, Write};
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// `session_<unix secs>_<pid>.log`, rotated to `.1` (newest) up to `.LOG_FILE_KEEP` once full
+    pub struct SessionLog {
+        path: PathBuf,
+        file: File,
+        written: u64,
+    }
+
+    impl SessionLog {
+        pub fn create(dir: &Path) -> io::Result<Self> {
+            fs::create_dir_all(dir)?;
+            let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            let path = dir.join(format!("session_{}_{}.log", started, std::process::id()));
+            Ok(Self { file: File::create(&path)?, path, written: 0 })
+        }
+
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+            let len = line.len() as u64 + 1;
+            if self.written > 0 && self.written + len > LOG_FILE_MAX_BYTES {
+                self.rotate()?;
+            }
+            writeln!(self.file, "{}", line)?;
+            self.written += len;
+            Ok(())
+        }
+
+        fn rotate(&mut self) -> io::Result<()> {
+            let rotated = |i: usize| PathBuf::from(format!("{}.{}", self.path.display(), i));
+            for i in (1..LOG_FILE_KEEP).rev() {
+                if rotated(i).exists() {
+                    fs::rename(rotated(i), rotated(i + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated(1))?;
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Mirror-image presentation (`mirror_x`): the round's scene is reflected left to right, across
+//! the vertical plane of the camera's start position (world x = 0), as soon as it is spawned.
+//!
+//! A negative scale would draw every mesh inside out, since culling and double-sided lighting
+//! follow the triangle winding. Each entity gets a reflected copy of its mesh with the winding
+//! reversed instead, and its transform is conjugated by the reflection, which keeps it a proper
+//! rotation. Conjugated, a yaw shows as the opposite yaw: rotations of mirrored entities are applied
+//! with the sign flipped and the emitted yaw flips it back, so logged yaws stay those of the
+//! unmirrored scene.
+
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+
+use crate::command_handler::SharedMemResource;
+use crate::log_event;
+use crate::utils::objects::{BaseDoor, FaceMarker, GameEntity, Mirrored, AnimatedDecoration};
+
+/// Reflection of a vector across x = 0
+pub fn mirror_vec(v: Vec3) -> Vec3 {
+    Vec3::new(-v.x, v.y, v.z)
+}
+
+/// Transform conjugated by the reflection across x = 0: translation reflected, rotation turned the
+/// other way around the reflected axis. Applied to a reflected mesh it places the mirror image
+pub fn mirror_transform(transform: &Transform) -> Transform {
+    let rotation = transform.rotation;
+    Transform {
+        translation: mirror_vec(transform.translation),
+        rotation: Quat::from_xyzw(rotation.x, -rotation.y, -rotation.z, rotation.w),
+        scale: transform.scale,
+    }
+}
+
+/// Mesh reflected across its x = 0 plane, with the triangle winding reversed so the same side
+/// stays the front
+pub fn mirror_mesh(mesh: &Mesh) -> Mesh {
+    let mut mirrored = mesh.clone();
+    for attribute in [Mesh::ATTRIBUTE_POSITION, Mesh::ATTRIBUTE_NORMAL] {
+        if let Some(VertexAttributeValues::Float32x3(values)) = mirrored.attribute_mut(attribute) {
+            values.iter_mut().for_each(|value| value[0] = -value[0]);
+        }
+    }
+    // The reflection also flips the handedness of the tangent frame
+    if let Some(VertexAttributeValues::Float32x4(tangents)) = mirrored.attribute_mut(Mesh::ATTRIBUTE_TANGENT) {
+        tangents.iter_mut().for_each(|tangent| (tangent[0], tangent[3]) = (-tangent[0], -tangent[3]));
+    }
+
+    if mirrored.primitive_topology() != PrimitiveTopology::TriangleList {
+        return mirrored;
+    }
+    match mirrored.indices_mut() {
+        Some(Indices::U16(indices)) => indices.chunks_exact_mut(3).for_each(|triangle| triangle.swap(1, 2)),
+        Some(Indices::U32(indices)) => indices.chunks_exact_mut(3).for_each(|triangle| triangle.swap(1, 2)),
+        None => {
+            let vertices = mirrored.count_vertices() as u32;
+            let indices = (0..vertices / 3).flat_map(|triangle| [3 * triangle, 3 * triangle + 2, 3 * triangle + 1]);
+            mirrored.insert_indices(Indices::U32(indices.collect()));
+        }
+    }
+    mirrored
+}
+
+type MirrorableEntity = (
+    Entity,
+    &'static mut Transform,
+    Option<&'static mut Mesh3d>,
+    Option<&'static mut FaceMarker>,
+    Option<&'static mut BaseDoor>,
+    Option<&'static mut AnimatedDecoration>,
+);
+
+/// 3D entities of the round not reflected yet
+type Unmirrored = (With<GameEntity>, Without<Mirrored>, Without<Node>);
+
+/// Reflects the 3D entities of a `mirror_x` round as they spawn, marking them `Mirrored`
+pub fn mirror_new_entities(
+    mut commands: Commands,
+    shm_res: Option<Res<SharedMemResource>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut entities: Query<MirrorableEntity, Unmirrored>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    if !shm_res.0.get().game_structure_game.mirror_x.load(Ordering::Relaxed) {
+        return;
+    }
+
+    for (entity, mut transform, mesh, face, door, animated) in &mut entities {
+        *transform = mirror_transform(&transform);
+        if let Some(mut mesh) = mesh {
+            match meshes.get(&mesh.0).map(mirror_mesh) {
+                Some(mirrored) => mesh.0 = meshes.add(mirrored),
+                None => log_event!(error, "Mesh of {:?} not loaded, it is not mirrored", entity),
+            }
+        }
+        // World-space directions, read together with the entity's rotation
+        if let Some(mut face) = face {
+            face.normal = mirror_vec(face.normal);
+        }
+        if let Some(mut door) = door {
+            door.normal = mirror_vec(door.normal);
+        }
+        if let Some(mut animated) = animated {
+            animated.axis = Dir3::new_unchecked(mirror_vec(animated.axis.as_vec3()));
+        }
+        commands.entity(entity).insert(Mirrored);
+    }
+}
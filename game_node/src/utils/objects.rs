@@ -1,5 +1,6 @@
 //! This file defines the various objects, resources, and components used in the game.
 use bevy::prelude::*;
+use std::collections::VecDeque;
 use std::time::Duration;
 
 
@@ -12,6 +13,18 @@ pub enum DecorationShape {
     Triangle,
 }
 
+impl DecorationShape {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Circle),
+            1 => Some(Self::Square),
+            2 => Some(Self::Star),
+            3 => Some(Self::Triangle),
+            _ => None,
+        }
+    }
+}
+
 /// Single decoration on a pyramid face with barycentric coordinates relative to the triangle vertices (top, corner1, corner2)
 #[derive(Clone, Debug)]
 pub struct Decoration {
@@ -27,6 +40,14 @@ pub struct DecorationSet {
     pub decorations: Vec<Decoration>,
 }
 
+/// Door construction parameters for the wooden base, latched per trial
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DoorGeometry {
+    pub hole_radius_fraction: f32, // Hole diameter as a fraction of the panel's smaller side
+    pub frame_thickness: f32,      // Depth of the hole tunnel into the base
+    pub door_inset: f32,           // Door glow distance behind the panel surface
+}
+
 /// The current winning doors and animation state
 #[derive(Resource, Default)]
 pub struct DoorWinEntities {
@@ -43,18 +64,87 @@ pub struct DoorWinEntities {
 #[derive(Resource, Default)]
 pub struct RoundStartTimestamp(pub Option<Duration>);
 
+/// Controls yaw (radians) the round's rotable entities start at: the previous round's when the
+/// trial does not recenter on reset, else 0 (set in setup_round, applied by apply_global_yaw_offset)
+#[derive(Resource, Default)]
+pub struct CarriedYaw(pub f32);
+
+/// Controls yaw of the round's pyramid over the last APPROACH_WINDOW_FRAMES played frames (oldest
+/// first), for the approach direction. Starts over with every new pyramid
+#[derive(Resource, Default)]
+pub struct YawHistory {
+    pub pyramid: Option<Entity>,
+    pub yaws: VecDeque<f32>,
+}
+
 /// Pyramid component
 #[derive(Component)]
 pub struct Pyramid;
 
+/// Component to mark a pyramid face, keeping the color it was spawned with
+#[derive(Component)]
+pub struct FaceMarker {
+    pub face_index: usize,
+    pub normal: Vec3, // In world coordinates, pointing into the pyramid (like BaseDoor::normal)
+    pub base_color: Color,
+}
+
+/// Decoration mesh on the face `face_index`, with its own material. Shape, size and color (sRGB
+/// RGBA) are kept for the stimulus description
+#[derive(Component)]
+pub struct DecorationMarker {
+    pub face_index: usize,
+    pub shape: DecorationShape,
+    pub size: f32,
+    pub color: [f32; 4],
+}
+
+/// Decoration moving in place under the trial's `decoration_motion`: `axis` is its face normal in the
+/// face's frame, `phase` its offset in cycles drawn from the face seed, and `angle` and `scale` the
+/// spin and pulse currently applied to its transform
+#[derive(Component)]
+pub struct AnimatedDecoration {
+    pub axis: Dir3,
+    pub phase: f32,
+    pub angle: f32,
+    pub scale: f32,
+}
+
+/// Border along the edges of face `face_index`, a child of the face. Left out of the alignment
+#[derive(Component)]
+pub struct FaceOutline {
+    pub face_index: usize,
+}
+
+/// Decoration probe in progress: the face, the recolored materials with the colors to restore, and
+/// how long the probe color stays
+#[derive(Resource, Default)]
+pub struct DecorationProbe {
+    pub face: Option<usize>,
+    pub originals: Vec<(AssetId<StandardMaterial>, Color)>,
+    pub timer: Timer,
+}
+
 // A component that marks an entity to be rotated by the camera controls
 #[derive(Component)]
 pub struct RotableComponent;
 
+/// Global yaw offset already applied to a rotable entity, entities spawned by a reset start without it
+#[derive(Component)]
+pub struct YawOffset(pub f32);
+
+/// Entity reflected for a `mirror_x` round, its yaw turns the other way (see mirror.rs)
+#[derive(Component)]
+pub struct Mirrored;
+
 // A component that marks a pointlight as being one of the hole
 #[derive(Component)]
 pub struct HoleLight;
 
+/// Marks a scene spotlight spawned from the SetupConfig lights (or the default overhead one)
+#[derive(Component)]
+pub struct SceneLight;
+
 // A component that marks an emissive mesh as being the hole glow effect
 #[derive(Component)]
 pub struct HoleEmissive;
@@ -63,6 +153,18 @@ pub struct HoleEmissive;
 #[derive(Component)]
 pub struct GameEntity;
 
+/// Visibility of a game entity before a PauseMode::HideGameEntities pause hid it, restored on resume
+#[derive(Component)]
+pub struct HiddenByPause(pub Visibility);
+
+/// Marks a ground distractor (fixed in the world, ignored by the alignment math)
+#[derive(Component)]
+pub struct Distractor;
+
+/// Marks the curved backdrop wall (persistent, shown or hidden per trial by `backdrop_enabled`)
+#[derive(Component)]
+pub struct Backdrop;
+
 /// A component that marks an entity as a UI entity
 #[derive(Component)]
 pub struct UIEntity;
@@ -91,3 +193,25 @@ pub struct ScoreBarUI;
 // Component marking the fill bar inside the ScoreBarUI
 #[derive(Component)]
 pub struct ScoreBarFill;
+// Component marking the alignment percentage shown next to the ScoreBarUI
+#[derive(Component)]
+pub struct ScoreBarText;
+// Component marking the radius criterion track drawn under the ScoreBarUI
+#[derive(Component)]
+pub struct RadiusMarker;
+
+/// Component marking a colored part of the aiming reticle (outline parts are tinted via their border)
+#[derive(Component)]
+pub struct ReticlePart {
+    pub base_color: Color,
+    pub outline: bool,
+}
+
+/// Component marking the live feedback bar container, hidden during animations and blanking
+#[derive(Component)]
+pub struct LiveFeedbackBar;
+/// Component marking the fill inside the LiveFeedbackBar, carrying the smoothed level in [0, 1]
+#[derive(Component, Default)]
+pub struct LiveFeedbackFill {
+    pub level: f32,
+}
@@ -1,8 +1,8 @@
 //! Logic for spawning the pyramid base with interactive doors.
 
 use crate::utils::objects::{
-    BaseDoor, BaseFrame, Decoration, DecorationSet, DecorationShape, GameEntity, HoleEmissive,
-    HoleLight, Pyramid, RotableComponent,
+    BaseDoor, BaseFrame, Decoration, DecorationMarker, DecorationSet, DecorationShape, DoorGeometry, FaceMarker,
+    FaceOutline, GameEntity, HoleEmissive, HoleLight, Pyramid, RotableComponent, AnimatedDecoration,
 };
 use bevy::prelude::*;
 use shared::constants::{object_constants::GROUND_Y, pyramid_constants::*};
@@ -10,6 +10,10 @@ use shared::constants::{object_constants::GROUND_Y, pyramid_constants::*};
 use rand::{Rng, RngCore};
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
+use shared::PyramidType;
+use crate::utils::stimulus::StimulusDecoration;
+use crate::profile_scope;
+use crate::profiling::ResetSections;
 
 /// Creates a pentagon mesh for the hole emissive effect
 fn create_pentagon_mesh(
@@ -75,6 +79,7 @@ pub fn spawn_pyramid_base(
     materials: &mut ResMut<Assets<StandardMaterial>>,
     p_start_orientation_rad: f32, // Replaced GameState
     target_door: usize,           // Target door index for winning door entities
+    door_geometry: DoorGeometry,
 ) -> (Option<Entity>, Option<Entity>) {
     let base_radius = BASE_RADIUS;
     let angle_increment = std::f32::consts::TAU / BASE_NR_SIDES as f32;
@@ -112,13 +117,21 @@ pub fn spawn_pyramid_base(
 
         // Create the frame mesh with a pentagonal hole (also returns computed values to avoid redundant calculations)
         let (frame_mesh, normal, local_right, local_up, center, pentagon_radius) =
-            create_frame_with_hole(bottom_outer_1, bottom_outer_2, top_outer_1, top_outer_2);
+            create_frame_with_hole(
+                bottom_outer_1,
+                bottom_outer_2,
+                top_outer_1,
+                top_outer_2,
+                door_geometry.hole_radius_fraction,
+                door_geometry.frame_thickness,
+            );
 
         // Light position is at the center of the frame
         let light_pos = center;
 
-        // Create emissive pentagon mesh - offset center slightly inward to prevent z-fighting
-        let pentagon_center_inset = center + normal * 0.01; // Slightly inward from frame surface
+        // Create emissive pentagon mesh - moved behind the frame surface by the door inset, against the outward
+        // normal (the default is negative, slightly in front of the surface to prevent z-fighting)
+        let pentagon_center_inset = center - normal * door_geometry.door_inset;
         let pentagon_mesh = create_pentagon_mesh(
             pentagon_center_inset,
             pentagon_radius,
@@ -263,12 +276,15 @@ fn create_top_lid_mesh(radius: f32, sides: usize, start_orientation: f32) -> Mes
     mesh
 }
 
-/// Creates a rectangular frame mesh with a pentagonal hole cut out in the center
+/// Creates a rectangular frame mesh with a pentagonal hole cut out in the center.
+/// With a positive `thickness` the hole is extruded into a tunnel reaching into the base.
 fn create_frame_with_hole(
     bottom_left: Vec3,
     bottom_right: Vec3,
     top_left: Vec3,
     top_right: Vec3,
+    hole_fraction: f32,
+    thickness: f32,
 ) -> (Mesh, Vec3, Vec3, Vec3, Vec3, f32) {
     let mut mesh = Mesh::new(
         bevy::mesh::PrimitiveTopology::TriangleList,
@@ -288,8 +304,7 @@ fn create_frame_with_hole(
     let normal = -side_vec.cross(up_vec).normalize();
 
     // Create pentagon hole vertices (scaled down from center)
-    let hole_scale = 0.4; // Pentagon is 40% of the panel size
-    let pentagon_radius = (width.min(height) * hole_scale) / 2.0;
+    let pentagon_radius = (width.min(height) * hole_fraction) / 2.0;
 
     // Pentagon vertices (5 points)
     let pentagon_points = 5;
@@ -348,6 +363,23 @@ fn create_frame_with_hole(
 
     indices.extend_from_slice(&[1, 5, 4]);
 
+    // Tunnel walls: one quad per pentagon edge with its own vertices, facing the hole axis
+    if thickness > 0.0 {
+        let depth = -normal * thickness;
+        for i in 0..pentagon_points {
+            let a = pentagon_vertices[i];
+            let b = pentagon_vertices[(i + 1) % pentagon_points];
+            let wall_normal = (center - (a + b) / 2.0).normalize();
+
+            let base = positions.len() as u32;
+            for vertex in [a, b, b + depth, a + depth] {
+                positions.push(vertex.to_array());
+                normals.push(wall_normal.to_array());
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+    }
+
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
     mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
     mesh.insert_indices(bevy::mesh::Indices::U32(indices));
@@ -356,7 +388,19 @@ fn create_frame_with_hole(
 }
 
 /// Spawns a triangular prism.
-/// Returns `(Option<Entity>, Option<Entity>)` = (winning_light, winning_emissive) for the target door.
+/// Decorations depend on the config only: face i draws from a fresh `ChaCha8Rng` seeded with
+/// `decoration_seeds[i]` (stream 0 for the shapes and placements, stream 1 for a ranged count, see
+/// `face_decoration_count`, stream 2 for the motion phases and stream 3 for the color jitter, both
+/// in spawn order), so a trial looks and moves the same whatever ran before it in the session.
+/// `decoration_color_jitter` offsets each RGB channel of every drawn decoration by up to that much
+/// from its face's decoration color (0 keeps one color per face).
+/// With `decoration_symmetry` each face's layout is mirror-symmetric about its vertical axis (see
+/// `generate_symmetric_decoration_set`) and holds the same total count as the two triangles would.
+/// With `replay` (decorations of an archived stimulus, see stimulus.rs) nothing is drawn: exactly
+/// those decorations are spawned instead.
+/// A face's `decoration_colors` entry, if any, colors its decorations instead of the drawn color.
+/// Returns (winning_light, winning_emissive) for the target door, the decorations placed per face
+/// and the decoration color of each face (None for replayed faces, whose decorations keep their own).
 pub fn spawn_pyramid(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
@@ -368,8 +412,18 @@ pub fn spawn_pyramid(
     p_colors: [Color; 3],
     decoration_counts: [u32; 3],
     decoration_sizes: [f32; 3],
+    decoration_spacing: f32,
+    decoration_symmetry: bool,
+    decoration_colors: [Option<Color>; 3],
+    decoration_color_jitter: f32,
     target_door: usize,
-) -> (Option<Entity>, Option<Entity>) {
+    pyramid_type: PyramidType,
+    door_geometry: DoorGeometry,
+    outline: Option<(f32, Color)>,
+    replay: Option<&[StimulusDecoration]>,
+    sections: Option<&ResetSections>,
+) -> (Option<Entity>, Option<Entity>, [u32; 3], [Option<Color>; 3]) {
+    profile_scope!(sections, SpawnPyramid);
     let height_y = p_height;
 
     // Build the symmetric triangular vertices for the BASE.
@@ -438,6 +492,15 @@ pub fn spawn_pyramid(
 
     // Generate Decoration Sets
 
+    // Type3: the target face carries its own decoration shape, the other two faces share another one
+    let shape_overrides: [Option<DecorationShape>; 3] = if pyramid_type == PyramidType::Type3 {
+        let target_face = face_for_door(target_door);
+        let (target_shape, other_shape) = type3_decoration_shapes(decoration_seeds[target_face]);
+        std::array::from_fn(|i| Some(if i == target_face { target_shape } else { other_shape }))
+    } else {
+        [None; 3]
+    };
+
     let mut dec_sets: Vec<Option<DecorationSet>> = Vec::new();
 
     // Indices for the loop below to generate sets for Face 0 and Face 1
@@ -445,6 +508,11 @@ pub fn spawn_pyramid(
     // Tri A: (TopLeft, BaseLeft, BaseRight)
     // Tri B: (TopLeft, BaseRight, TopRight)
     for i in 0..3 {
+        profile_scope!(sections, FaceDecorations);
+        if replay.is_some() {
+            dec_sets.extend([None, None]);
+            continue;
+        }
         // Create a fresh RNG from the per-face seed so identical seeds produce identical aesthetics
         let mut face_rng = ChaCha8Rng::seed_from_u64(decoration_seeds[i]);
         // Generate for first 2 faces
@@ -455,25 +523,49 @@ pub fn spawn_pyramid(
         let bl = base_corners[i];
         let br = base_corners[next];
 
+        // One set over the whole face, kept in Set A's coordinates
+        if decoration_symmetry {
+            dec_sets.push(Some(generate_symmetric_decoration_set(
+                &mut face_rng,
+                tl,
+                bl,
+                tr,
+                decoration_counts[i],
+                decoration_sizes[i],
+                decoration_spacing,
+                shape_overrides[i],
+                decoration_colors[i],
+            )));
+            dec_sets.push(None);
+            continue;
+        }
+
         // Set A (Bottom-Left Triangle)
-        dec_sets.push(Some(generate_decoration_set(
+        let set_a = generate_decoration_set(
             &mut face_rng,
             tl,
             bl,
             br,
             decoration_counts[i],
             decoration_sizes[i],
-        )));
+            decoration_spacing,
+            shape_overrides[i],
+            decoration_colors[i],
+        );
 
-        // Set B (Top-Right Triangle)
-        dec_sets.push(Some(generate_decoration_set(
+        // Set B (Top-Right Triangle), in Set A's color so the face has a single one
+        let set_b = generate_decoration_set(
             &mut face_rng,
             tl,
             br,
             tr,
             decoration_counts[i],
             decoration_sizes[i],
-        )));
+            decoration_spacing,
+            shape_overrides[i],
+            Some(set_a.color),
+        );
+        dec_sets.extend([Some(set_a), Some(set_b)]);
     }
 
     // Spawn the pyramid faces
@@ -532,11 +624,43 @@ pub fn spawn_pyramid(
                 })),
                 Transform::default(),
                 Pyramid,
+                FaceMarker {
+                    face_index: i,
+                    normal,
+                    base_color: p_colors[i],
+                },
                 RotableComponent,
                 GameEntity,
             ))
             .id();
 
+        // Outline (width, color) along the face's edges, following it as a child
+        if let Some((width, color)) = outline {
+            let outline_mesh = create_face_outline_mesh([tl, bl, br, tr], normal, width);
+            commands.entity(face_entity).with_children(|parent| {
+                parent.spawn((
+                    Mesh3d(meshes.add(outline_mesh)),
+                    MeshMaterial3d(materials.add(StandardMaterial {
+                        base_color: color,
+                        unlit: true,
+                        cull_mode: None,
+                        double_sided: true,
+                        ..default()
+                    })),
+                    Transform::default(),
+                    FaceOutline { face_index: i },
+                    GameEntity,
+                ));
+            });
+        }
+
+        // Motion phases, one per decoration in the order they are spawned
+        let mut phase_rng = ChaCha8Rng::seed_from_u64(decoration_seeds[i]);
+        phase_rng.set_stream(2);
+        let mut color_rng = ChaCha8Rng::seed_from_u64(decoration_seeds[i]);
+        color_rng.set_stream(3);
+        let mut jitter = ColorJitter { amount: decoration_color_jitter, rng: &mut color_rng };
+
         // Apply Set A to the first virtual triangle (TL, BL, BR)
         if let Some(ref set_a) = dec_sets[i * 2] {
             spawn_decorations_from_set(
@@ -544,11 +668,15 @@ pub fn spawn_pyramid(
                 meshes,
                 materials,
                 face_entity,
+                i,
                 set_a,
                 tl,
                 bl,
                 br,
                 normal,
+                &mut phase_rng,
+                &mut jitter,
+                sections,
             );
         }
 
@@ -559,20 +687,123 @@ pub fn spawn_pyramid(
                 meshes,
                 materials,
                 face_entity,
+                i,
                 set_b,
                 tl,
                 br,
                 tr,
                 normal,
+                &mut phase_rng,
+                &mut jitter,
+                sections,
+            );
+        }
+
+        // Archived decorations, at their recorded positions
+        for decoration in replay.into_iter().flatten().filter(|d| d.face as usize == i) {
+            profile_scope!(sections, FaceDecorations);
+            let marker = DecorationMarker {
+                face_index: i,
+                shape: DecorationShape::from_u32(decoration.shape).unwrap_or(DecorationShape::Circle),
+                size: decoration.size,
+                color: decoration.color,
+            };
+            spawn_decoration(
+                commands,
+                meshes,
+                materials,
+                face_entity,
+                marker,
+                Vec3::from_array(decoration.position),
+                normal,
+                phase_rng.random(),
             );
         }
     }
 
     // Spawn the base and capture winning door entities
-    let (winning_light, winning_emissive) = spawn_pyramid_base(commands, meshes, materials, p_orientation_rad, target_door);
+    let (winning_light, winning_emissive) = spawn_pyramid_base(
+        commands,
+        meshes,
+        materials,
+        p_orientation_rad,
+        target_door,
+        door_geometry,
+    );
     // Max intensity not vital here or pass it in
 
-    (winning_light, winning_emissive)
+    // Each face is decorated as two triangles
+    let placed = std::array::from_fn(|i| match replay {
+        Some(decorations) => decorations.iter().filter(|d| d.face as usize == i).count() as u32,
+        None => dec_sets[i * 2..i * 2 + 2].iter().flatten().map(|set| set.decorations.len() as u32).sum(),
+    });
+    let colors = std::array::from_fn(|i| dec_sets[i * 2].as_ref().map(|set| set.color));
+    (winning_light, winning_emissive, placed, colors)
+}
+
+/// Strips of `width` along the edges of a face quad, inside the face and lifted off it (outward,
+/// against `normal`, which points into the pyramid) so they never z-fight with it
+fn create_face_outline_mesh(corners: [Vec3; 4], normal: Vec3, width: f32) -> Mesh {
+    let center = corners.iter().sum::<Vec3>() / 4.0;
+    let lift = -normal * PYRAMID_OUTLINE_OFFSET;
+    let mut positions = Vec::with_capacity(16);
+    let mut indices: Vec<u32> = Vec::with_capacity(24);
+    for (i, &start) in corners.iter().enumerate() {
+        let end = corners[(i + 1) % 4];
+        // Across the edge, toward the center of the face
+        let inward = (center - start).reject_from(end - start).normalize_or_zero() * width;
+        let first = positions.len() as u32;
+        positions.extend([start, end, end + inward, start + inward].map(|corner| (corner + lift).to_array()));
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    let mut mesh = Mesh::new(
+        bevy::mesh::PrimitiveTopology::TriangleList,
+        Default::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![normal.to_array(); positions.len()]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(bevy::mesh::Indices::U32(indices));
+    mesh
+}
+
+/// Door centered under a pyramid face (inverse of `face_for_door` for odd doors)
+pub fn door_for_face(face: usize) -> usize {
+    let doors_per_face = BASE_NR_SIDES / 3;
+    (face * doors_per_face + BASE_NR_SIDES - doors_per_face / 2) % BASE_NR_SIDES
+}
+
+/// Index of the pyramid face looking toward a base door.
+/// Odd doors face a face center, even doors sit under a corner and map to the following face.
+pub fn face_for_door(door: usize) -> usize {
+    let doors_per_face = BASE_NR_SIDES / 3;
+    ((door + doors_per_face / 2) / doors_per_face) % 3
+}
+
+/// Decoration count of a face: drawn from `range` ([min, max], inclusive) with the face seed, or the
+/// fixed `count` when the range is disabled (max 0). Uses its own stream so the placement draws are unchanged.
+pub fn face_decoration_count(seed: u64, count: u32, range: [u32; 2]) -> u32 {
+    let [min, max] = range;
+    if max == 0 {
+        return count;
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(1);
+    rng.random_range(min.min(max)..=max)
+}
+
+/// Two distinct decoration shapes (target, non-target) drawn from the target face seed.
+fn type3_decoration_shapes(seed: u64) -> (DecorationShape, DecorationShape) {
+    const SHAPES: [DecorationShape; 4] = [
+        DecorationShape::Circle,
+        DecorationShape::Square,
+        DecorationShape::Star,
+        DecorationShape::Triangle,
+    ];
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let target = (rng.next_u64() % 4) as usize;
+    let other = (target + 1 + (rng.next_u64() % 3) as usize) % 4;
+    (SHAPES[target], SHAPES[other])
 }
 
 /// Generates a decoration set for a pyramid face using Poisson-like sampling.
@@ -584,6 +815,9 @@ fn generate_decoration_set(
     corner2: Vec3,
     count: u32,
     size: f32, // New Arg
+    spacing: f32,
+    shape_override: Option<DecorationShape>,
+    color_override: Option<Color>,
 ) -> DecorationSet {
     // Determine the number of decorations to generate.
     let decoration_count = count as usize;
@@ -608,6 +842,8 @@ fn generate_decoration_set(
         2 => DecorationShape::Star,
         _ => DecorationShape::Triangle,
     };
+    // Drawn anyway so overriding the shape leaves the rest of the set unchanged
+    let shape = shape_override.unwrap_or(shape);
 
     // Choose a random vibrant color, which will be the same for all decorations on this face.
     let color = Color::srgb(
@@ -615,15 +851,17 @@ fn generate_decoration_set(
         rng.random_range(0.2..0.22),
         rng.random_range(0.2..0.22),
     );
+    // Drawn anyway too, so a configured color leaves the placement unchanged
+    let color = color_override.unwrap_or(color);
 
     while successful_placements < decoration_count
-        && (total_attempts as usize) < (decoration_count as usize) * MAX_PLACEMENT_ATTEMPTS
+        && (total_attempts as usize) < decoration_count * MAX_PLACEMENT_ATTEMPTS
     {
         total_attempts += 1;
 
         // Generate a random position using barycentric coordinates to ensure the point is inside the triangle.
         let (world_position, is_valid) =
-            sample_point_in_triangle(rng, top, corner1, corner2, size, &decorations_world);
+            sample_point_in_triangle(rng, top, corner1, corner2, size, spacing, &decorations_world);
 
         // Skip this attempt if the position overlaps with existing decorations or is too close to the edges.
         if !is_valid {
@@ -664,6 +902,85 @@ fn generate_decoration_set(
     }
 }
 
+/// Generates a decoration set for a whole face quad, mirror-symmetric about its vertical axis (from
+/// the middle of the top edge to the middle of the base edge): each point is drawn in the left half
+/// and mirrored into the right one, and the pair is kept only if both pass the spacing rules against
+/// every decoration placed so far, mirrors included, and against each other. Places up to `count`
+/// pairs, the total two triangles would hold. Shape and color are drawn (or overridden) as in
+/// `generate_decoration_set`.
+/// Positions are stored in barycentric coordinates of Set A's triangle (top, corner1 and the base
+/// corner under `top_right`), which extend past it over the rest of the face.
+fn generate_symmetric_decoration_set(
+    rng: &mut ChaCha8Rng,
+    top: Vec3,
+    corner1: Vec3,
+    top_right: Vec3,
+    count: u32,
+    size: f32,
+    spacing: f32,
+    shape_override: Option<DecorationShape>,
+    color_override: Option<Color>,
+) -> DecorationSet {
+    const MAX_PLACEMENT_ATTEMPTS: usize = 30;
+    let pair_count = count as usize;
+
+    let shape = match rng.next_u64() % 4 {
+        0 => DecorationShape::Circle,
+        1 => DecorationShape::Square,
+        2 => DecorationShape::Star,
+        _ => DecorationShape::Triangle,
+    };
+    let shape = shape_override.unwrap_or(shape);
+    let color = Color::srgb(
+        rng.random_range(0.2..0.22),
+        rng.random_range(0.2..0.22),
+        rng.random_range(0.2..0.22),
+    );
+    let color = color_override.unwrap_or(color);
+
+    // The face rectangle spans `across` (left to right) and `down` (top to base) from `top`, its base
+    // corner on the right is top + across + down, and the point (u across, v down) has these weights
+    let across = top_right - top;
+    let down = corner1 - top;
+    let (width, height) = (across.length(), down.length());
+    let barycentric = |u: f32, v: f32| Vec3::new(1.0 - v, v - u, u);
+    let edge_margin = size * 1.5 * spacing;
+
+    let mut decorations_world: Vec<(Vec3, f32)> = Vec::new();
+    let mut decorations: Vec<Decoration> = Vec::new();
+    let mut total_attempts = 0;
+    while decorations.len() < pair_count * 2 && total_attempts < pair_count * 2 * MAX_PLACEMENT_ATTEMPTS {
+        total_attempts += 1;
+        let u = rng.random_range(0.0..0.5_f32);
+        let v = rng.random_range(0.0..1.0_f32);
+        let position = top + across * u + down * v;
+        let mirrored = top + across * (1.0 - u) + down * v;
+
+        // Margins to the left edge (and so the right one for the mirror), the top and the base
+        if u * width < edge_margin || v * height < edge_margin || (1.0 - v) * height < edge_margin {
+            continue;
+        }
+        let pair_too_close = overlaps_decorations(position, size, spacing, &[(mirrored, size)]);
+        if pair_too_close
+            || overlaps_decorations(position, size, spacing, &decorations_world)
+            || overlaps_decorations(mirrored, size, spacing, &decorations_world)
+        {
+            continue;
+        }
+
+        for (point, (u, v)) in [(position, (u, v)), (mirrored, (1.0 - u, v))] {
+            decorations.push(Decoration { barycentric: barycentric(u, v), size });
+            decorations_world.push((point, size));
+        }
+    }
+
+    DecorationSet {
+        shape,
+        color,
+        decorations,
+    }
+}
+
 /// Spawns decorations from a decoration set onto a face
 /// Reconstructs world positions from barycentric coordinates relative to the given triangle vertices
 fn spawn_decorations_from_set(
@@ -671,55 +988,119 @@ fn spawn_decorations_from_set(
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
     parent_face: Entity,
+    face_index: usize,
     decoration_set: &DecorationSet,
     top: Vec3,
     corner1: Vec3,
     corner2: Vec3,
     face_normal: Vec3,
+    phase_rng: &mut ChaCha8Rng,
+    jitter: &mut ColorJitter,
+    sections: Option<&ResetSections>,
 ) {
+    profile_scope!(sections, FaceDecorations);
     for decoration in &decoration_set.decorations {
         // Reconstruct world position from barycentric coordinates
         let position = decoration.barycentric.x * top
             + decoration.barycentric.y * corner1
             + decoration.barycentric.z * corner2;
 
-        let mesh = create_decoration_mesh(decoration_set.shape, decoration.size);
-
-        // Calculate the rotation to align the decoration with the face plane
-        let base_rotation = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
-        let normal_rotation = Quat::from_rotation_arc(Vec3::Y, face_normal);
-        let final_rotation = normal_rotation * base_rotation;
-
         // Offset slightly away from face surface to prevent z-fighting
         let offset_position = position - face_normal * 0.01;
 
-        // Spawn the decoration as a child of the face
-        commands.entity(parent_face).with_children(|parent| {
-            parent.spawn((
-                Mesh3d(meshes.add(mesh)),
-                MeshMaterial3d(materials.add(StandardMaterial {
-                    base_color: decoration_set.color,
-                    reflectance: 0.0,
-                    ..default()
-                })),
-                Transform {
-                    translation: offset_position,
-                    rotation: final_rotation,
-                    scale: Vec3::ONE,
-                },
-                GameEntity,
-            ));
-        });
+        let marker = DecorationMarker {
+            face_index,
+            shape: decoration_set.shape,
+            size: decoration.size,
+            color: jitter.apply(decoration_set.color.to_srgba().to_f32_array()),
+        };
+        spawn_decoration(
+            commands,
+            meshes,
+            materials,
+            parent_face,
+            marker,
+            offset_position,
+            face_normal,
+            phase_rng.random(),
+        );
+    }
+}
+
+/// Per-decoration color variation of a face, drawn from its own stream of the face seed
+struct ColorJitter<'a> {
+    /// Largest offset of each RGB channel
+    amount: f32,
+    rng: &'a mut ChaCha8Rng,
+}
+
+impl ColorJitter<'_> {
+    /// Offsets the RGB channels of an sRGBA color, clamped to [0, 1]; unchanged (and nothing drawn)
+    /// without jitter
+    fn apply(&mut self, [r, g, b, a]: [f32; 4]) -> [f32; 4] {
+        if self.amount <= 0.0 {
+            return [r, g, b, a];
+        }
+        let mut offset = |channel: f32| (channel + self.rng.random_range(-self.amount..=self.amount)).clamp(0.0, 1.0);
+        [offset(r), offset(g), offset(b), a]
     }
 }
 
-/// Samples a random point inside a triangle using barycentric coordinates, with collision checking against existing decorations
+/// Spawns the decoration `marker` describes as a child of its face at `translation`, lying in the
+/// face plane, with its motion `phase` (cycles)
+fn spawn_decoration(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    parent_face: Entity,
+    marker: DecorationMarker,
+    translation: Vec3,
+    face_normal: Vec3,
+    phase: f32,
+) {
+    let mesh = create_decoration_mesh(marker.shape, marker.size);
+    let [r, g, b, a] = marker.color;
+
+    // Calculate the rotation to align the decoration with the face plane
+    let base_rotation = Quat::from_rotation_x(std::f32::consts::FRAC_PI_2);
+    let normal_rotation = Quat::from_rotation_arc(Vec3::Y, face_normal);
+    let final_rotation = normal_rotation * base_rotation;
+
+    // Spawn the decoration as a child of the face
+    commands.entity(parent_face).with_children(|parent| {
+        parent.spawn((
+            Mesh3d(meshes.add(mesh)),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::srgba(r, g, b, a),
+                reflectance: 0.0,
+                ..default()
+            })),
+            Transform {
+                translation,
+                rotation: final_rotation,
+                scale: Vec3::ONE,
+            },
+            marker,
+            AnimatedDecoration {
+                axis: Dir3::new(face_normal).unwrap_or(Dir3::Y),
+                phase,
+                angle: 0.0,
+                scale: 1.0,
+            },
+            GameEntity,
+        ));
+    });
+}
+
+/// Samples a random point inside a triangle using barycentric coordinates, with collision checking against existing decorations.
+/// `spacing` scales the edge margin and the distances between decorations
 fn sample_point_in_triangle(
     rng: &mut ChaCha8Rng,
     v0: Vec3,
     v1: Vec3,
     v2: Vec3,
     size: f32,
+    spacing: f32,
     existing_decorations: &[(Vec3, f32)],
 ) -> (Vec3, bool) {
     // Generate random barycentric coordinates using the square root method for a uniform distribution
@@ -735,7 +1116,7 @@ fn sample_point_in_triangle(
     let position = v0 * w0 + v1 * w1 + v2 * w2;
 
     // Set a minimum distance from the edges, proportional to the decoration's size
-    let edge_margin = size * 1.5;
+    let edge_margin = size * 1.5 * spacing;
 
     // Check if the point is too close to the triangle's edges.
     let dist_to_edge_01 = point_to_line_segment_distance(position, v0, v1);
@@ -749,19 +1130,17 @@ fn sample_point_in_triangle(
         return (position, false);
     }
 
-    // Check for overlap with existing decorations (Poisson disk constraint)
-    let min_spacing = size * 2.0; // The minimum distance between decoration centers
-
-    for (existing_pos, existing_size) in existing_decorations {
-        let distance = position.distance(*existing_pos);
-        let required_distance = (size + existing_size) * 1.2; // Add 20% extra spacing.
+    (position, !overlaps_decorations(position, size, spacing, existing_decorations))
+}
 
-        if distance < required_distance.max(min_spacing) {
-            return (position, false);
-        }
-    }
+/// Whether a decoration of `size` at `position` comes too close to any existing one (Poisson disk constraint)
+fn overlaps_decorations(position: Vec3, size: f32, spacing: f32, existing_decorations: &[(Vec3, f32)]) -> bool {
+    let min_spacing = size * 2.0 * spacing; // The minimum distance between decoration centers
 
-    (position, true)
+    existing_decorations.iter().any(|(existing_pos, existing_size)| {
+        let required_distance = (size + existing_size) * 1.2 * spacing; // Add 20% extra spacing.
+        position.distance(*existing_pos) < required_distance.max(min_spacing)
+    })
 }
 
 /// Calculates the minimum distance from a point to a line segment
@@ -5,16 +5,27 @@ use bevy::asset::RenderAssetUsages;
 use bevy::mesh::Indices;
 use bevy::render::render_resource::PrimitiveTopology;
 
-use crate::log;
+use crate::{log_event, profile_scope};
 use crate::utils::objects::*;
-use crate::utils::pyramid::spawn_pyramid;
+use crate::utils::camera::configured_projection;
+use crate::utils::distractors::spawn_distractors;
+use crate::utils::game_functions::{config_color, config_rgba, spawn_live_feedback_bar, spawn_reticle, spawn_score_bar, winning_door};
+use crate::utils::pyramid::{face_decoration_count, spawn_pyramid};
+use crate::utils::stimulus::load_stimulus_file;
 use shared::constants::{
-    lighting_constants::{GLOBAL_AMBIENT_LIGHT_INTENSITY, SPOTLIGHT_LIGHT_INTENSITY},
+    lighting_constants::{GLOBAL_AMBIENT_LIGHT_INTENSITY, SPOTLIGHT_LIGHT_INTENSITY, SPOTLIGHT_OUTER_ANGLE, SPOTLIGHT_POSITION},
+    game_constants::{SCORE_BAR_ANCHOR, SCORE_BAR_HEIGHT, SCORE_BAR_WIDTH_PERCENT},
     object_constants::GROUND_Y,
+    pyramid_constants::{DOOR_FRAME_THICKNESS, DOOR_HOLE_RADIUS_FRACTION, DOOR_INSET, DOOR_REWARD_WEIGHTS, PYRAMID_DECORATIONS_SPACING},
 };
 
 use crate::command_handler::SharedMemResource;
-use core::sync::atomic::Ordering;
+use crate::profiling::ResetSections;
+use shared::{ConfigMode, DecorationMotion, PyramidType, SharedGameStructure, validate_decoration_colors, validate_decoration_pulse, validate_door_geometry, validate_door_reward_weights, validate_fog, validate_geometry_ranges, validate_score_bar};
+use core::sync::atomic::{AtomicU32, Ordering};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::Value;
 
 /// Initial game scene, with the camera, ground, lights, and the pyramid.
 /// Setup the persistent entitites across resets.
@@ -36,6 +47,7 @@ pub fn setup_environment(
 
     // Curved Background
     commands.spawn((
+        Backdrop,
         Mesh3d(meshes.add(create_extended_semicircle_mesh(9.0, 10.0, 20.0, 64))),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::srgb(0.2, 0.2, 0.2),
@@ -46,20 +58,11 @@ pub fn setup_environment(
             ..default()
         })),
         Transform::from_xyz(0.0, GROUND_Y, 0.0),
+        // Shown or hidden per trial in setup_round
+        Visibility::Inherited,
     ));
 
-    // Main Spotlight
-    commands.spawn((
-        SpotLight {
-            intensity: SPOTLIGHT_LIGHT_INTENSITY, // Default start value
-            shadows_enabled: true,
-            outer_angle: std::f32::consts::PI / 3.0,
-            range: 45.0,
-            radius: 0.0,
-            ..default()
-        },
-        Transform::from_xyz(0.0, 15.0, 0.0).looking_at(Vec3::ZERO, -Vec3::Y),
-    ));
+    // Spotlights are spawned per round from the SetupConfig, see spawn_scene_lights
 
     // Ambient Light
     commands.insert_resource(GlobalAmbientLight {
@@ -68,7 +71,167 @@ pub fn setup_environment(
         affects_lightmapped_meshes: true,
     });
 
-    log!("🌍 Environment Setup Complete");
+    log_event!(reset, "🌍 Environment Setup Complete");
+}
+
+/// One scene spotlight of the SetupConfig.
+///
+/// Coordinates are Bevy world units with +Y up: the ground plane is at y = GROUND_Y, the pyramid
+/// stands centered on the Y axis and the camera orbits it, starting on +Z. Every light aims at the
+/// origin, so its position alone sets the angle it lights the pyramid from (the default overhead
+/// spotlight is at SPOTLIGHT_POSITION, straight above).
+#[derive(Clone, Debug, PartialEq)]
+pub struct LightConfig {
+    pub position: Vec3,
+    /// Luminous power in lumens, as SpotLight::intensity
+    pub intensity: f32,
+    /// Outer cone angle in radians, in (0, PI/2]
+    pub angle: f32,
+    pub color: Color,
+}
+
+/// Scene configuration read when a round is set up, loaded at launch (`--lights`, see cli.rs).
+/// Without lights the single overhead spotlight is kept, driven by the trial's spotlight fields
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct SetupConfig {
+    pub lights: Vec<LightConfig>,
+}
+
+impl SetupConfig {
+    /// Parses `{"lights": [{"position": [x, y, z], "intensity": lm, "angle": rad, "color": [r, g, b]}, ...]}`.
+    /// Only `position` is required: intensity and angle default to SPOTLIGHT_LIGHT_INTENSITY and
+    /// SPOTLIGHT_OUTER_ANGLE, the sRGB color to white
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(json).map_err(|e| format!("not JSON: {e}"))?;
+        let root = root.as_object().ok_or("expected an object")?;
+        let lights = match root.get("lights") {
+            Some(lights) => lights
+                .as_array()
+                .ok_or("lights: expected an array")?
+                .iter()
+                .enumerate()
+                .map(|(i, light)| light_config(light).map_err(|e| format!("lights[{i}]: {e}")))
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self { lights })
+    }
+}
+
+/// `N` finite numbers from the array `value`
+fn finite_numbers<const N: usize>(value: &Value, key: &str) -> Result<[f32; N], String> {
+    let numbers = value
+        .as_array()
+        .ok_or_else(|| format!("{key}: expected an array"))?
+        .iter()
+        .map(|v| v.as_f64().map(|v| v as f32).filter(|v| v.is_finite()).ok_or_else(|| format!("{key}: expected finite numbers")))
+        .collect::<Result<Vec<f32>, _>>()?;
+    let len = numbers.len();
+    numbers.try_into().map_err(|_| format!("{key}: expected {N} numbers, got {len}"))
+}
+
+fn light_config(light: &Value) -> Result<LightConfig, String> {
+    let light = light.as_object().ok_or("expected an object")?;
+    let float = |key: &str, default: f32| -> Result<f32, String> {
+        match light.get(key) {
+            Some(value) => {
+                value.as_f64().map(|v| v as f32).filter(|v| v.is_finite()).ok_or_else(|| format!("{key}: expected a finite number"))
+            }
+            None => Ok(default),
+        }
+    };
+
+    let position = Vec3::from_array(finite_numbers(light.get("position").ok_or("missing position")?, "position")?);
+    if position == Vec3::ZERO {
+        return Err("position: the light aims at the origin, so it cannot sit on it".to_string());
+    }
+    let intensity = float("intensity", SPOTLIGHT_LIGHT_INTENSITY)?;
+    if intensity < 0.0 {
+        return Err(format!("intensity: expected at least 0, got {intensity}"));
+    }
+    let angle = float("angle", SPOTLIGHT_OUTER_ANGLE)?;
+    if angle <= 0.0 || angle > std::f32::consts::FRAC_PI_2 {
+        return Err(format!("angle: expected in (0, PI/2], got {angle}"));
+    }
+    let color = match light.get("color") {
+        Some(color) => {
+            let [r, g, b] = finite_numbers(color, "color")?;
+            if ![r, g, b].iter().all(|c| (0.0..=1.0).contains(c)) {
+                return Err("color: expected sRGB values in [0, 1]".to_string());
+            }
+            Color::srgb(r, g, b)
+        }
+        None => Color::WHITE,
+    };
+    Ok(LightConfig { position, intensity, angle, color })
+}
+
+/// Spawns the round's spotlights: the configured ones, or the overhead one with the trial's
+/// intensity and cone. All take the trial's range and are removed with the round
+fn spawn_scene_lights(commands: &mut Commands, lights: &[LightConfig], main_intensity: f32, outer_angle: f32, range: f32) {
+    let spot = |intensity: f32, outer_angle: f32, color: Color| SpotLight {
+        color,
+        intensity,
+        shadows_enabled: true,
+        outer_angle,
+        inner_angle: 0.0,
+        range,
+        radius: 0.0,
+        ..default()
+    };
+    if lights.is_empty() {
+        let [x, y, z] = SPOTLIGHT_POSITION;
+        commands.spawn((
+            spot(main_intensity, outer_angle, Color::WHITE),
+            // Straight down, so the up vector cannot be +Y
+            Transform::from_xyz(x, y, z).looking_at(Vec3::ZERO, -Vec3::Y),
+            SceneLight,
+            GameEntity,
+        ));
+        return;
+    }
+    for light in lights {
+        commands.spawn((
+            spot(light.intensity, light.angle, light.color),
+            Transform::from_translation(light.position).looking_at(Vec3::ZERO, Vec3::Y),
+            SceneLight,
+            GameEntity,
+        ));
+    }
+}
+
+/// Base radius, height and start orientation drawn uniformly within their [min, max] `ranges`, in
+/// that order, from a fresh `ChaCha8Rng` seeded with `seed`: the same seed always gives the same pyramid
+pub fn draw_geometry(seed: u64, ranges: [[f32; 2]; 3]) -> [f32; 3] {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    ranges.map(|[min, max]| min + rng.random::<f32>() * (max - min))
+}
+
+/// In Randomized mode, replaces the game region's base radius, height and start orientation with
+/// ones drawn from its ranges and geometry seed, so the applied config reports them. Invalid ranges
+/// or an unknown mode fall back to the explicit values, echoed as Explicit
+fn apply_config_mode(gs_game: &SharedGameStructure) {
+    let config_mode = gs_game.config_mode.load(Ordering::Relaxed);
+    match ConfigMode::from_u32(config_mode) {
+        Some(ConfigMode::Explicit) => return,
+        Some(ConfigMode::Randomized) => {
+            let range = |range: &[AtomicU32; 2]| range.each_ref().map(|bound| f32::from_bits(bound.load(Ordering::Relaxed)));
+            let ranges = [range(&gs_game.base_radius_range), range(&gs_game.height_range), range(&gs_game.start_orient_range)];
+            match validate_geometry_ranges(ranges[0], ranges[1], ranges[2]) {
+                Ok(()) => {
+                    let [radius, height, orient] = draw_geometry(gs_game.geometry_seed.load(Ordering::Relaxed), ranges);
+                    gs_game.base_radius.store(radius.to_bits(), Ordering::Relaxed);
+                    gs_game.height.store(height.to_bits(), Ordering::Relaxed);
+                    gs_game.start_orient.store(orient.to_bits(), Ordering::Relaxed);
+                    log_event!(reset, "Randomized geometry: radius {:.3}, height {:.3}, orientation {:.3} rad", radius, height, orient);
+                    return;
+                }
+                Err(e) => log_event!(error, "Invalid geometry ranges ({}), using the explicit values", e),
+            }
+        }
+        None => log_event!(error, "Unknown config mode {}, using the explicit values", config_mode),
+    }
+    gs_game.config_mode.store(ConfigMode::Explicit as u32, Ordering::Relaxed);
 }
 
 /// Setup a specific game trial.
@@ -77,17 +240,21 @@ pub fn setup_round(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    mut camera_query: Query<&mut Transform, With<PersistentCamera>>,
-    mut spotlight_query: Query<&mut SpotLight, (Without<HoleLight>, Without<GameEntity>)>,
+    mut camera_query: Query<(Entity, &mut Transform, &mut Projection), With<PersistentCamera>>,
+    setup_config: Option<Res<SetupConfig>>,
+    mut backdrop_query: Query<&mut Visibility, With<Backdrop>>,
     ambient_light: Option<ResMut<GlobalAmbientLight>>,
     shm_res: Option<Res<SharedMemResource>>,
     mut round_start: ResMut<crate::utils::objects::RoundStartTimestamp>,
+    mut carried_yaw: ResMut<CarriedYaw>,
     time: Res<Time>,
     mut door_win_entities: ResMut<DoorWinEntities>,
+    reset_sections: Option<Res<ResetSections>>,
 ) {
+    profile_scope!(reset_sections.as_deref(), Setup);
     // Read shared memory
     let Some(shm_res) = shm_res else {
-        error!("Shared Memory not initialized in setup_round");
+        log_event!(error, "Shared Memory not initialized in setup_round");
         return;
     };
 
@@ -100,65 +267,225 @@ pub fn setup_round(
     let gs_ctrl = &shm.game_structure_control;
     // Reset all fields of game structure
     let gs_game = &shm.game_structure_game;
+    // Controls yaw of the round ending, before the reset overwrites the emitted one
+    let last_yaw = f32::from_bits(gs_game.pyramid_yaw.load(Ordering::Relaxed));
     gs_game.reset_all_fields(gs_ctrl);
+    // A pre-baked stimulus overrides the generated config before anything is read from it
+    let replay = load_stimulus_file(gs_game);
 
     // Update all the game resoruces based on the new configuration
-    let mut decoration_seeds = [0u64; 3];
-    for i in 0..3 {
-        decoration_seeds[i] = gs_game.decoration_seeds[i].load(Ordering::Relaxed);
-    }
+    let decoration_seeds = gs_game.decoration_seeds.each_ref().map(|seed| seed.load(Ordering::Relaxed));
 
     let main_intensity = f32::from_bits(gs_game.main_spotlight_intensity.load(Ordering::Relaxed));
     let ambient_intensity = f32::from_bits(gs_game.ambient_brightness.load(Ordering::Relaxed));
-    // Update Lights
-    for mut spot in spotlight_query.iter_mut() {
-        spot.intensity = main_intensity;
-    }
+    let spot_outer_angle = f32::from_bits(gs_game.spotlight_outer_angle.load(Ordering::Relaxed));
+    let spot_range = f32::from_bits(gs_game.spotlight_range.load(Ordering::Relaxed));
+    // Update Lights. An app without a SetupConfig (e.g. one assembled without SystemsLogicPlugin)
+    // still gets the overhead spotlight
+    let lights = match &setup_config {
+        Some(setup_config) => setup_config.lights.as_slice(),
+        None => {
+            log_event!(reset, "No SetupConfig, spawning the default overhead spotlight");
+            &[]
+        }
+    };
+    spawn_scene_lights(&mut commands, lights, main_intensity, spot_outer_angle, spot_range);
 
     if let Some(mut ambient) = ambient_light {
         ambient.brightness = ambient_intensity;
     }
 
-    // Reset the persistent camera position
-    if let Ok(mut camera_transform) = camera_query.single_mut() {
-        *camera_transform = Transform::from_xyz(
-            f32::from_bits(gs_ctrl.camera_x.load(Ordering::Relaxed)),
-            f32::from_bits(gs_ctrl.camera_y.load(Ordering::Relaxed)),
-            f32::from_bits(gs_ctrl.camera_z.load(Ordering::Relaxed)),
-        )
-        .looking_at(Vec3::ZERO, Vec3::Y);
+    // Backdrop wall, hidden for an empty background
+    let backdrop_enabled = gs_game.backdrop_enabled.load(Ordering::Relaxed);
+    for mut visibility in backdrop_query.iter_mut() {
+        *visibility = if backdrop_enabled { Visibility::Inherited } else { Visibility::Hidden };
     }
 
-    gs_game.win_time.store(0, Ordering::Relaxed);
+    // Reset the persistent camera position and projection. Without recentering the camera stays
+    // where the last trial left it and the new pyramid starts at the last one's yaw
+    let recenter = gs_game.recenter_on_reset.load(Ordering::Relaxed);
+    if let Ok((camera, mut camera_transform, mut projection)) = camera_query.single_mut() {
+        if recenter {
+            *camera_transform = Transform::from_xyz(
+                f32::from_bits(gs_ctrl.camera_x.load(Ordering::Relaxed)),
+                f32::from_bits(gs_ctrl.camera_y.load(Ordering::Relaxed)),
+                f32::from_bits(gs_ctrl.camera_z.load(Ordering::Relaxed)),
+            )
+            .looking_at(Vec3::ZERO, Vec3::Y);
+        }
+        *projection = configured_projection(gs_game);
+
+        // Distance fog on the scene camera only, so the UI and blank overlay stay clear.
+        // Invalid distances turn it off, echoed back
+        let fog_start = f32::from_bits(gs_game.fog_start.load(Ordering::Relaxed));
+        let fog_end = f32::from_bits(gs_game.fog_end.load(Ordering::Relaxed));
+        if gs_game.fog_enabled.load(Ordering::Relaxed) {
+            if let Err(e) = validate_fog(fog_start, fog_end) {
+                log_event!(error, "Invalid fog ({}), disabling it", e);
+                gs_game.fog_enabled.store(false, Ordering::Relaxed);
+            }
+        }
+        if gs_game.fog_enabled.load(Ordering::Relaxed) {
+            let rgba = gs_game.fog_color.each_ref().map(|channel| f32::from_bits(channel.load(Ordering::Relaxed)));
+            commands.entity(camera).insert(DistanceFog {
+                color: config_color(gs_game, rgba),
+                falloff: FogFalloff::Linear { start: fog_start, end: fog_end },
+                ..default()
+            });
+        } else {
+            commands.entity(camera).remove::<DistanceFog>();
+        }
+    }
+    carried_yaw.0 = if recenter { 0.0 } else { last_yaw };
 
+    gs_game.win_time.store(0, Ordering::Relaxed);
+    gs_game.reward_weight.store(0, Ordering::Relaxed);
+    gs_game.radius_at_check.store(0, Ordering::Relaxed);
+    gs_game.win_blocked_by_radius.store(false, Ordering::Relaxed);
+    gs_game.win_blocked_by_approach.store(false, Ordering::Relaxed);
+    gs_game.check_too_early.store(false, Ordering::Relaxed);
+    gs_game.feedback_pending.store(false, Ordering::Relaxed);
+
+    apply_config_mode(gs_game);
     let radius = f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed));
     let height = f32::from_bits(gs_game.height.load(Ordering::Relaxed));
     let orient = f32::from_bits(gs_game.start_orient.load(Ordering::Relaxed));
 
-    let mut colors = [Color::WHITE; 3];
-    for i in 0..3 {
-        let r = f32::from_bits(gs_game.colors[i * 4 + 0].load(Ordering::Relaxed));
-        let g = f32::from_bits(gs_game.colors[i * 4 + 1].load(Ordering::Relaxed));
-        let b = f32::from_bits(gs_game.colors[i * 4 + 2].load(Ordering::Relaxed));
-        let a = f32::from_bits(gs_game.colors[i * 4 + 3].load(Ordering::Relaxed));
-        colors[i] = Color::srgba(r, g, b, a);
+    let pyramid_type_code = gs_game.pyramid_type.load(Ordering::Relaxed);
+    let pyramid_type = PyramidType::try_from(pyramid_type_code).unwrap_or_else(|e| {
+        log_event!(error, "Invalid pyramid type ({}), using Type1", e);
+        PyramidType::Type1
+    });
+    // Echo the type actually built, so an unknown code never reads back as applied
+    gs_game.pyramid_type.store(pyramid_type as u32, Ordering::Relaxed);
+
+    // Face colors and target face as configured: only the live type switch derives them from the
+    // type. Type3 faces are told apart by decoration only, echoed back with face 0's color
+    let mut face_colors: [[f32; 4]; 3] = core::array::from_fn(|i| {
+        core::array::from_fn(|c| f32::from_bits(gs_game.colors[i * 4 + c].load(Ordering::Relaxed)))
+    });
+    if pyramid_type == PyramidType::Type3 {
+        face_colors = [face_colors[0]; 3];
+        for (slot, value) in gs_game.colors.iter().zip(face_colors.as_flattened()) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
     }
+    let colors: [Color; 3] = face_colors.map(|rgba| config_color(gs_game, rgba));
 
+    // Per-face ranges draw the count from the face seed; the drawn count is echoed back
     let mut decoration_counts = [0; 3];
     for i in 0..3 {
-        decoration_counts[i] = gs_game.decorations_count[i].load(Ordering::Relaxed);
+        let range = [
+            gs_game.decorations_count_range[i * 2].load(Ordering::Relaxed),
+            gs_game.decorations_count_range[i * 2 + 1].load(Ordering::Relaxed),
+        ];
+        decoration_counts[i] =
+            face_decoration_count(decoration_seeds[i], gs_game.decorations_count[i].load(Ordering::Relaxed), range);
+        gs_game.decorations_count[i].store(decoration_counts[i], Ordering::Relaxed);
     }
 
-    let mut decoration_sizes = [0.0; 3];
-    for i in 0..3 {
-        decoration_sizes[i] = f32::from_bits(gs_game.decorations_size[i].load(Ordering::Relaxed));
+    let decoration_sizes = gs_game.decorations_size.each_ref().map(|size| f32::from_bits(size.load(Ordering::Relaxed)));
+    let mut decoration_spacing = f32::from_bits(gs_game.decoration_spacing.load(Ordering::Relaxed));
+    if !(decoration_spacing > 0.0 && decoration_spacing.is_finite()) {
+        log_event!(error, "Invalid decoration spacing {}, using {}", decoration_spacing, PYRAMID_DECORATIONS_SPACING);
+        decoration_spacing = PYRAMID_DECORATIONS_SPACING;
+        gs_game.decoration_spacing.store(decoration_spacing.to_bits(), Ordering::Relaxed);
     }
+    let mut outline_width = f32::from_bits(gs_game.outline_width.load(Ordering::Relaxed));
+    if !(outline_width >= 0.0 && outline_width.is_finite()) {
+        log_event!(error, "Invalid outline width {}, drawing no outline", outline_width);
+        outline_width = 0.0;
+        gs_game.outline_width.store(outline_width.to_bits(), Ordering::Relaxed);
+    }
+    let ui_scale = f32::from_bits(gs_game.ui_scale.load(Ordering::Relaxed));
+    if !(ui_scale >= 0.0 && ui_scale.is_finite()) {
+        log_event!(error, "Invalid UI scale {}, scaling with the viewport", ui_scale);
+        gs_game.ui_scale.store(0.0f32.to_bits(), Ordering::Relaxed);
+    }
+    // Decoration motion; an unknown motion or pulse falls back to static decorations, echoed back
+    let motion_code = gs_game.decoration_motion.load(Ordering::Relaxed);
+    if DecorationMotion::from_u32(motion_code).is_none() {
+        log_event!(error, "Unknown decoration motion code {}, keeping decorations still", motion_code);
+        gs_game.decoration_motion.store(DecorationMotion::None as u32, Ordering::Relaxed);
+    }
+    if let Err(e) = validate_decoration_pulse(
+        f32::from_bits(gs_game.decoration_pulse_amplitude.load(Ordering::Relaxed)),
+        f32::from_bits(gs_game.decoration_pulse_hz.load(Ordering::Relaxed)),
+    ) {
+        log_event!(error, "Invalid decoration pulse ({}), not pulsing", e);
+        gs_game.decoration_pulse_amplitude.store(0.0f32.to_bits(), Ordering::Relaxed);
+        gs_game.decoration_pulse_hz.store(0.0f32.to_bits(), Ordering::Relaxed);
+    }
+    gs_game.decoration_motion_secs.store(0.0f32.to_bits(), Ordering::Relaxed);
+    // Configured decoration colors (alpha > 0) replace the drawn ones; invalid ones are all drawn
+    let mut decoration_rgba: [[f32; 4]; 3] = core::array::from_fn(|i| {
+        core::array::from_fn(|c| f32::from_bits(gs_game.decoration_colors[i * 4 + c].load(Ordering::Relaxed)))
+    });
+    if let Err(e) = validate_decoration_colors(&decoration_rgba) {
+        log_event!(error, "Invalid decoration colors ({}), drawing them from the seeds", e);
+        decoration_rgba = [[0.0; 4]; 3];
+    }
+    let decoration_colors = decoration_rgba.map(|rgba| (rgba[3] > 0.0).then(|| config_color(gs_game, rgba)));
+    let mut decoration_color_jitter = f32::from_bits(gs_game.decoration_color_jitter.load(Ordering::Relaxed));
+    if !(0.0..=1.0).contains(&decoration_color_jitter) {
+        log_event!(error, "Invalid decoration color jitter {}, using one color per face", decoration_color_jitter);
+        decoration_color_jitter = 0.0;
+        gs_game.decoration_color_jitter.store(decoration_color_jitter.to_bits(), Ordering::Relaxed);
+    }
+    let outline = (outline_width > 0.0).then(|| {
+        let color = gs_game.outline_color.each_ref().map(|channel| f32::from_bits(channel.load(Ordering::Relaxed)));
+        (outline_width, config_color(gs_game, color))
+    });
 
-    // Read target door from shared memory
-    let target_door = gs_game.target_door.load(Ordering::Relaxed) as usize;
+    // Door geometry; degenerate values fall back to the defaults, and the applied values are echoed back
+    let mut door_geometry = DoorGeometry {
+        hole_radius_fraction: f32::from_bits(gs_game.door_hole_radius_fraction.load(Ordering::Relaxed)),
+        frame_thickness: f32::from_bits(gs_game.door_frame_thickness.load(Ordering::Relaxed)),
+        door_inset: f32::from_bits(gs_game.door_inset.load(Ordering::Relaxed)),
+    };
+    if let Err(e) = validate_door_geometry(
+        radius,
+        door_geometry.hole_radius_fraction,
+        door_geometry.frame_thickness,
+        door_geometry.door_inset,
+    ) {
+        log_event!(error, "Invalid door geometry ({}), using defaults", e);
+        door_geometry = DoorGeometry {
+            hole_radius_fraction: DOOR_HOLE_RADIUS_FRACTION,
+            frame_thickness: DOOR_FRAME_THICKNESS,
+            door_inset: DOOR_INSET,
+        };
+        gs_game.door_hole_radius_fraction.store(DOOR_HOLE_RADIUS_FRACTION.to_bits(), Ordering::Relaxed);
+        gs_game.door_frame_thickness.store(DOOR_FRAME_THICKNESS.to_bits(), Ordering::Relaxed);
+        gs_game.door_inset.store(DOOR_INSET.to_bits(), Ordering::Relaxed);
+    }
+
+    // Reward weights are only read on a win, so invalid ones fall back to the defaults here
+    let door_reward_weights = gs_game.door_reward_weights.each_ref().map(|weight| f32::from_bits(weight.load(Ordering::Relaxed)));
+    if let Err(e) = validate_door_reward_weights(&door_reward_weights) {
+        log_event!(error, "Invalid door reward weights ({}), using defaults", e);
+        for (weight, default) in gs_game.door_reward_weights.iter().zip(DOOR_REWARD_WEIGHTS) {
+            weight.store(default.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    // Score bar layout; an invalid one falls back to the default bar, echoed back
+    if let Err(e) = validate_score_bar(
+        gs_game.score_bar_anchor.load(Ordering::Relaxed),
+        f32::from_bits(gs_game.score_bar_width_percent.load(Ordering::Relaxed)),
+        f32::from_bits(gs_game.score_bar_height.load(Ordering::Relaxed)),
+    ) {
+        log_event!(error, "Invalid score bar layout ({}), using the default one", e);
+        gs_game.score_bar_anchor.store(SCORE_BAR_ANCHOR, Ordering::Relaxed);
+        gs_game.score_bar_width_percent.store(SCORE_BAR_WIDTH_PERCENT.to_bits(), Ordering::Relaxed);
+        gs_game.score_bar_height.store(SCORE_BAR_HEIGHT.to_bits(), Ordering::Relaxed);
+    }
+
+    // Read target door from shared memory; in face mode the door under the target face animates on a win
+    let target_door = winning_door(gs_game);
     
     // Spawn the pyramid and capture winning door entities
-    let (winning_light, winning_emissive) = spawn_pyramid(
+    let (winning_light, winning_emissive, decorations_placed, shown_decoration_colors) = spawn_pyramid(
         &mut commands,
         &mut meshes,
         &mut materials,
@@ -169,15 +496,56 @@ pub fn setup_round(
         colors,
         decoration_counts,
         decoration_sizes,
+        decoration_spacing,
+        gs_game.decoration_symmetry.load(Ordering::Relaxed),
+        decoration_colors,
+        decoration_color_jitter,
         target_door,
+        pyramid_type,
+        door_geometry,
+        outline,
+        replay.as_ref().map(|description| description.decorations.as_slice()),
+        reset_sections.as_deref(),
     );
 
+    for (placed, count) in gs_game.decorations_placed.iter().zip(decorations_placed) {
+        placed.store(count, Ordering::Relaxed);
+    }
+    // The colors shown are echoed back, drawn ones included, so every trial's colors can be recovered
+    for (face, color) in shown_decoration_colors.iter().enumerate() {
+        let rgba = color.map_or(decoration_rgba[face], |color| config_rgba(gs_game, color));
+        for (channel, value) in rgba.iter().enumerate() {
+            gs_game.decoration_colors[face * 4 + channel].store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+    // A replayed round draws nothing, its counts are the archived ones
+    if replay.is_some() {
+        for (drawn, count) in gs_game.decorations_count.iter().zip(decorations_placed) {
+            drawn.store(count, Ordering::Relaxed);
+        }
+    }
+
     // Populate DoorWinEntities with the target door's entities and reset timer
     door_win_entities.winning_light = winning_light;
     door_win_entities.winning_emissive = winning_emissive;
     door_win_entities.animation_start_time = None;
 
-    log!("🎮 Round Started! target_door={}, winning_light={:?}, winning_emissive={:?}", target_door, winning_light, winning_emissive);
+    // Ground distractors around the pyramid
+    spawn_distractors(&mut commands, &mut meshes, &mut materials, gs_game);
+
+    // Aiming reticle and bars (despawned with the other game entities on the next reset)
+    spawn_reticle(&mut commands, gs_game);
+    spawn_live_feedback_bar(&mut commands, gs_game);
+    spawn_score_bar(&mut commands, gs_game);
+
+    log_event!(
+        reset,
+        "🎮 Round Started! trial_id_hash={:016x}, target_door={}, winning_light={:?}, winning_emissive={:?}",
+        gs_game.trial_id_hash.load(Ordering::Relaxed),
+        target_door,
+        winning_light,
+        winning_emissive
+    );
 }
 
 
@@ -0,0 +1,371 @@
+//! Complete description of the stimulus shown in a trial, for archiving with the behavioral records.
+//! Built from the applied config and the decorations actually spawned, with a stable 64-bit hash
+//! written to `stimulus_hash`, so an archive file can be matched to the trial it belongs to.
+//!
+//! The hash is FNV-1a over the little-endian bytes of the fields (floats as their bits), in a fixed
+//! order with the decorations sorted, so it does not depend on the platform or on the order the
+//! entities are queried in.
+//!
+//! The presentation fields (decoration motion, mirroring, outline and fog) were added later. They are
+//! hashed as one block, left out while it is all zeros, so a static, unmirrored, unoutlined and
+//! fog-free stimulus keeps the hash it was archived with before them. The decoration colors came with
+//! `version` 1: a version 0 archive has none, is hashed without them and replays its decorations in
+//! LEGACY_DECORATION_COLOR.
+//!
+//! A trial can also replay an archived description (`stimulus_file`): the pyramid is then built
+//! from it, every decoration at its recorded place, without drawing anything from the seeds.
+
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicU32, Ordering};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::command_handler::SharedMemResource;
+use crate::log_event;
+use crate::state_emitter::PendingResetAck;
+use crate::utils::alignment::door_normal;
+use crate::utils::objects::{DecorationMarker, DecorationShape};
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+use shared::{DecorationMotion, SharedGameStructure, StimulusFileStatus};
+
+/// Format version of the descriptions written now, see the module doc
+pub const STIMULUS_DESCRIPTION_VERSION: u32 = 1;
+
+/// Decoration color of a version 0 archive: the dark grey the generator drew its decorations in then
+pub const LEGACY_DECORATION_COLOR: [f32; 4] = [0.21, 0.21, 0.21, 1.0];
+
+fn legacy_decoration_color() -> [f32; 4] {
+    LEGACY_DECORATION_COLOR
+}
+
+/// Directory the per-trial stimulus descriptions are written to. Without it only the hash is emitted
+#[derive(Resource, Clone, Debug)]
+pub struct StimulusArchive(pub PathBuf);
+
+/// A spawned decoration: face, shape (DecorationShape order), full size, sRGB color and position in
+/// the pyramid's frame, before any yaw
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StimulusDecoration {
+    pub face: u32,
+    pub shape: u32,
+    pub size: f32,
+    #[serde(default = "legacy_decoration_color")]
+    pub color: [f32; 4],
+    pub position: [f32; 3],
+}
+
+/// Everything needed to redraw the stimulus of a trial
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StimulusDescription {
+    /// STIMULUS_DESCRIPTION_VERSION when built, 0 for archives written before the field
+    #[serde(default)]
+    pub version: u32,
+    pub pyramid_type: u32,
+    pub target_kind: u32,
+    pub target_door: u32,
+    pub target_face: u32,
+    pub decoration_seeds: [u64; 3],
+    pub base_radius: f32,
+    pub height: f32,
+    pub start_orient: f32,
+    /// Face colors (RGBA) in `color_space`
+    pub colors: [[f32; 4]; 3],
+    pub color_space: u32,
+    /// Door hole radius fraction, frame thickness and inset
+    pub door_geometry: [f32; 3],
+    /// Inward normal of every door, see alignment::door_normal
+    pub door_normals: [[f32; 3]; BASE_NR_SIDES],
+    /// Sorted by face, then position
+    pub decorations: Vec<StimulusDecoration>,
+    /// Layout hash of the distractors, which are fully determined by their seed and config
+    pub distractor_hash: u64,
+    /// DecorationMotion shown as u32, None for still decorations, with the spin rate (rad/s) of a
+    /// spin and the amplitude and frequency of a pulse (0 otherwise)
+    #[serde(default)]
+    pub decoration_motion: u32,
+    #[serde(default)]
+    pub decoration_spin_rate: f32,
+    #[serde(default)]
+    pub decoration_pulse: [f32; 2],
+    /// Shown mirrored left to right; the positions above are those of the unmirrored scene
+    #[serde(default)]
+    pub mirror_x: bool,
+    /// Face outline width and RGBA in `color_space`, all 0 without an outline
+    #[serde(default)]
+    pub outline_width: f32,
+    #[serde(default)]
+    pub outline_color: [f32; 4],
+    /// Distance fog RGBA in `color_space` and start/end distances, all 0 without fog
+    #[serde(default)]
+    pub fog_enabled: bool,
+    #[serde(default)]
+    pub fog_color: [f32; 4],
+    #[serde(default)]
+    pub fog_range: [f32; 2],
+}
+
+impl StimulusDescription {
+    /// Description of the stimulus built from the applied config `gs_game` and its spawned decorations
+    pub fn build(gs_game: &SharedGameStructure, mut decorations: Vec<StimulusDecoration>) -> Self {
+        let f = |a: &AtomicU32| f32::from_bits(a.load(Ordering::Relaxed));
+        let start_orient = f(&gs_game.start_orient);
+        let spin_rate = f(&gs_game.decoration_spin_rate);
+        let pulse = [f(&gs_game.decoration_pulse_amplitude), f(&gs_game.decoration_pulse_hz)];
+        let (motion, decoration_spin_rate, decoration_pulse) =
+            match DecorationMotion::from_u32(gs_game.decoration_motion.load(Ordering::Relaxed)).unwrap_or_default() {
+                DecorationMotion::Spin if spin_rate != 0.0 => (DecorationMotion::Spin, spin_rate, [0.0; 2]),
+                DecorationMotion::Pulse => (DecorationMotion::Pulse, 0.0, pulse),
+                _ => (DecorationMotion::None, 0.0, [0.0; 2]),
+            };
+        let outline_width = f(&gs_game.outline_width);
+        let fog_enabled = gs_game.fog_enabled.load(Ordering::Relaxed);
+        decorations.sort_by(|a, b| {
+            a.face.cmp(&b.face).then_with(|| {
+                a.position.iter().zip(&b.position).fold(core::cmp::Ordering::Equal, |order, (x, y)| order.then(x.total_cmp(y)))
+            })
+        });
+        Self {
+            version: STIMULUS_DESCRIPTION_VERSION,
+            pyramid_type: gs_game.pyramid_type.load(Ordering::Relaxed),
+            target_kind: gs_game.target_kind.load(Ordering::Relaxed),
+            target_door: gs_game.target_door.load(Ordering::Relaxed),
+            target_face: gs_game.target_face.load(Ordering::Relaxed),
+            decoration_seeds: gs_game.decoration_seeds.each_ref().map(|s| s.load(Ordering::Relaxed)),
+            base_radius: f(&gs_game.base_radius),
+            height: f(&gs_game.height),
+            start_orient,
+            colors: core::array::from_fn(|face| core::array::from_fn(|c| f(&gs_game.colors[face * 4 + c]))),
+            color_space: gs_game.color_space.load(Ordering::Relaxed),
+            door_geometry: [
+                f(&gs_game.door_hole_radius_fraction),
+                f(&gs_game.door_frame_thickness),
+                f(&gs_game.door_inset),
+            ],
+            door_normals: core::array::from_fn(|door| door_normal(start_orient, door).to_array()),
+            decorations,
+            distractor_hash: gs_game.distractor_hash.load(Ordering::Relaxed),
+            decoration_motion: motion as u32,
+            decoration_spin_rate,
+            decoration_pulse,
+            mirror_x: gs_game.mirror_x.load(Ordering::Relaxed),
+            outline_width,
+            outline_color: if outline_width > 0.0 { gs_game.outline_color.each_ref().map(f) } else { [0.0; 4] },
+            fog_enabled,
+            fog_color: if fog_enabled { gs_game.fog_color.each_ref().map(f) } else { [0.0; 4] },
+            fog_range: if fog_enabled { [f(&gs_game.fog_start), f(&gs_game.fog_end)] } else { [0.0; 2] },
+        }
+    }
+
+    /// FNV-1a hash of the description, stable across platforms and runs. The door normals are left out:
+    /// they follow from `start_orient`, and their trigonometry may differ in the last bit between platforms
+    pub fn hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let mut write = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        };
+        let floats = |values: &[f32]| values.iter().flat_map(|v| v.to_bits().to_le_bytes()).collect::<Vec<u8>>();
+
+        for word in [self.pyramid_type, self.target_kind, self.target_door, self.target_face, self.color_space] {
+            write(&word.to_le_bytes());
+        }
+        for seed in self.decoration_seeds {
+            write(&seed.to_le_bytes());
+        }
+        write(&floats(&[self.base_radius, self.height, self.start_orient]));
+        write(&floats(self.colors.as_flattened()));
+        write(&floats(&self.door_geometry));
+        // The count first, so decorations cannot shift into the fields after them
+        write(&(self.decorations.len() as u64).to_le_bytes());
+        for d in &self.decorations {
+            write(&d.face.to_le_bytes());
+            write(&d.shape.to_le_bytes());
+            write(&floats(&[d.size]));
+            if self.version >= 1 {
+                write(&floats(&d.color));
+            }
+            write(&floats(&d.position));
+        }
+        write(&self.distractor_hash.to_le_bytes());
+
+        let mut presentation = self.decoration_motion.to_le_bytes().to_vec();
+        presentation.extend(floats(&[self.decoration_spin_rate]));
+        presentation.extend(floats(&self.decoration_pulse));
+        presentation.push(self.mirror_x as u8);
+        presentation.extend(floats(&[self.outline_width]));
+        presentation.extend(floats(&self.outline_color));
+        presentation.push(self.fog_enabled as u8);
+        presentation.extend(floats(&self.fog_color));
+        presentation.extend(floats(&self.fog_range));
+        if presentation.iter().any(|&byte| byte != 0) {
+            write(&presentation);
+        }
+        hash
+    }
+
+    /// JSON object of the description
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a stimulus description serializes")
+    }
+
+    /// Description read back from `to_json`, or from an archive file (its `stimulus` object)
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let mut root: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let json = match root.get_mut("stimulus") {
+            Some(stimulus) => stimulus.take(),
+            None => root,
+        };
+        let description: Self = serde_json::from_value(json).map_err(|e| e.to_string())?;
+        if let Some(decoration) = description
+            .decorations
+            .iter()
+            .find(|d| d.face >= 3 || DecorationShape::from_u32(d.shape).is_none())
+        {
+            return Err(format!("invalid decoration {decoration:?}"));
+        }
+        Ok(description)
+    }
+
+    /// Writes the stimulus config into the game region, so the round, the emitted config and the
+    /// alignment checks follow the description
+    pub fn apply_to(&self, gs_game: &SharedGameStructure) {
+        gs_game.pyramid_type.store(self.pyramid_type, Ordering::Relaxed);
+        gs_game.target_kind.store(self.target_kind, Ordering::Relaxed);
+        gs_game.target_door.store(self.target_door, Ordering::Relaxed);
+        gs_game.target_face.store(self.target_face, Ordering::Relaxed);
+        for (slot, seed) in gs_game.decoration_seeds.iter().zip(self.decoration_seeds) {
+            slot.store(seed, Ordering::Relaxed);
+        }
+        gs_game.base_radius.store(self.base_radius.to_bits(), Ordering::Relaxed);
+        gs_game.height.store(self.height.to_bits(), Ordering::Relaxed);
+        gs_game.start_orient.store(self.start_orient.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs_game.colors.iter().zip(self.colors.as_flattened()) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs_game.color_space.store(self.color_space, Ordering::Relaxed);
+        let [hole_radius_fraction, frame_thickness, inset] = self.door_geometry;
+        gs_game.door_hole_radius_fraction.store(hole_radius_fraction.to_bits(), Ordering::Relaxed);
+        gs_game.door_frame_thickness.store(frame_thickness.to_bits(), Ordering::Relaxed);
+        gs_game.door_inset.store(inset.to_bits(), Ordering::Relaxed);
+        gs_game.decoration_motion.store(self.decoration_motion, Ordering::Relaxed);
+        gs_game.decoration_spin_rate.store(self.decoration_spin_rate.to_bits(), Ordering::Relaxed);
+        let [amplitude, hz] = self.decoration_pulse;
+        gs_game.decoration_pulse_amplitude.store(amplitude.to_bits(), Ordering::Relaxed);
+        gs_game.decoration_pulse_hz.store(hz.to_bits(), Ordering::Relaxed);
+        gs_game.mirror_x.store(self.mirror_x, Ordering::Relaxed);
+        gs_game.outline_width.store(self.outline_width.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs_game.outline_color.iter().zip(self.outline_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs_game.fog_enabled.store(self.fog_enabled, Ordering::Relaxed);
+        for (slot, value) in gs_game.fog_color.iter().zip(self.fog_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        let [fog_start, fog_end] = self.fog_range;
+        gs_game.fog_start.store(fog_start.to_bits(), Ordering::Relaxed);
+        gs_game.fog_end.store(fog_end.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Archive file name of trial generation `trial` (its `reset_ack`). `reset_ack` starts over with
+    /// every launch, so the stimulus hash keeps another session's trial with the same seeds apart
+    pub fn file_name(&self, trial: u32) -> String {
+        let [s0, s1, s2] = self.decoration_seeds;
+        format!("trial_{trial:05}_seeds_{s0}_{s1}_{s2}_{:016x}.json", self.hash())
+    }
+}
+
+/// Line of an archive file: the description with the trial it was shown in and its hash
+#[derive(Serialize)]
+struct ArchiveLine<'a> {
+    trial: u32,
+    stimulus_hash: u64,
+    stimulus: &'a StimulusDescription,
+}
+
+/// Describes the stimulus of a freshly set up round once its entities exist, emits its hash and,
+/// with a StimulusArchive, writes the description to the trial's file
+pub fn archive_stimulus(
+    pending_reset_ack: Res<PendingResetAck>,
+    decoration_query: Query<(&DecorationMarker, &Transform)>,
+    archive: Option<Res<StimulusArchive>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    if !pending_reset_ack.0 {
+        return;
+    }
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let decorations = decoration_query
+        .iter()
+        .map(|(marker, transform)| StimulusDecoration {
+            face: marker.face_index as u32,
+            shape: marker.shape as u32,
+            size: marker.size,
+            color: marker.color,
+            position: transform.translation.to_array(),
+        })
+        .collect();
+    let description = StimulusDescription::build(gs_game, decorations);
+    let hash = description.hash();
+    gs_game.stimulus_hash.store(hash, Ordering::Relaxed);
+
+    let Some(archive) = archive else { return };
+    // Acknowledged later this frame, as the next generation
+    let trial = gs_game.reset_ack.load(Ordering::Relaxed).wrapping_add(1);
+    let line = serde_json::to_string(&ArchiveLine { trial, stimulus_hash: hash, stimulus: &description })
+        .expect("a stimulus description serializes");
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::io::Write;
+        // Never replaces an earlier archive: a name taken already holds this same stimulus
+        let path = archive.0.join(description.file_name(trial));
+        let result = std::fs::create_dir_all(&archive.0)
+            .and_then(|()| std::fs::File::options().write(true).create_new(true).open(&path))
+            .and_then(|mut file| file.write_all((line + "\n").as_bytes()));
+        match result {
+            Ok(()) => log_event!(reset, "🗄️ Archived stimulus {:016x} to {}", hash, path.display()),
+            Err(e) => log_event!(error, "Failed to archive stimulus to {}: {}", path.display(), e),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    log_event!(reset, "🗄️ Stimulus {:016x} (no file system on web): {}", hash, line);
+}
+
+/// Loads the round's `stimulus_file` into the game region (see apply_to) and emits the outcome as
+/// `stimulus_file_status`. None without a file, or when it cannot be replayed: the pyramid is then
+/// generated from the config as usual
+pub fn load_stimulus_file(gs_game: &SharedGameStructure) -> Option<StimulusDescription> {
+    let Some(path) = gs_game.stimulus_file() else {
+        gs_game.stimulus_file_status.store(StimulusFileStatus::Procedural as u32, Ordering::Relaxed);
+        return None;
+    };
+    let expected = gs_game.stimulus_expected_hash.load(Ordering::Relaxed);
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(|e| (StimulusFileStatus::Missing, e.to_string()))
+        .and_then(|text| StimulusDescription::from_json(&text).map_err(|e| (StimulusFileStatus::Invalid, e)))
+        .and_then(|description| match description.hash() {
+            hash if expected != 0 && hash != expected => {
+                Err((StimulusFileStatus::HashMismatch, format!("hash {hash:016x}, expected {expected:016x}")))
+            }
+            _ => Ok(description),
+        });
+
+    match loaded {
+        Ok(description) => {
+            description.apply_to(gs_game);
+            gs_game.stimulus_file_status.store(StimulusFileStatus::Loaded as u32, Ordering::Relaxed);
+            log_event!(reset, "🗄️ Replaying stimulus {:016x} from {}", description.hash(), path);
+            Some(description)
+        }
+        Err((status, e)) => {
+            gs_game.stimulus_file_status.store(status as u32, Ordering::Relaxed);
+            log_event!(error, "Cannot replay stimulus file {} ({}), generating the pyramid from the config", path, e);
+            None
+        }
+    }
+}
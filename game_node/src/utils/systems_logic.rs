@@ -1,21 +1,37 @@
 //! Game logic wrapped up using the various plugins.
 //!
 use crate::command_handler::SharedMemResource;
-use crate::command_handler::{PendingAnimation, PendingBlankScreen, PendingReset, RenderingPaused};
-use crate::state_emitter::FrameCounterResource;
-use crate::utils::camera::{apply_pending_rotation, apply_pending_zoom};
+use crate::command_handler::{
+    PendingAnimation, PendingBlankScreen, PendingClearAttempts, PendingDumpConfig, PendingQueueNext,
+    PendingReferenceImage, PendingReset, RenderingPaused,
+};
+use crate::display_rate::DisplayRate;
+use crate::state_emitter::{FrameCounterResource, PendingResetAck};
+use crate::profiling::ResetSections;
+use crate::utils::camera::{apply_global_yaw_offset, apply_pending_rotation, apply_pending_zoom, configured_projection, track_approach};
 use crate::utils::game_functions::{
-    apply_pending_check_alignment, handle_door_animation, spawn_score_bar,
-    update_score_bar_animation, update_ui_scale,
+    animate_decorations, apply_face_flicker, apply_pending_check_alignment, apply_shaping_dwell, handle_door_animation,
+    handle_probe_command, handle_render_quality_command, handle_set_pyramid_type_command,
+    update_live_feedback_bar, update_reticle_tint, update_score_bar_animation, update_ui_scale,
 };
 use crate::utils::objects::{
-    DoorWinEntities, GameEntity, PersistentCamera, RoundStartTimestamp,
-    UIEntity,
+    Backdrop, CarriedYaw, DecorationProbe, DoorWinEntities, GameEntity, HiddenByPause, PersistentCamera, RoundStartTimestamp,
+    UIEntity, YawHistory,
 };
-use crate::utils::setup::setup_environment;
+use crate::utils::setup::{setup_environment, SetupConfig};
+use crate::utils::geometry_dump::{handle_dump_geometry_command, GeometryDumpDir};
+use crate::utils::mirror::mirror_new_entities;
+use crate::utils::stimulus::archive_stimulus;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use crate::utils::setup::setup_round;
+use crate::log_event;
 use core::sync::atomic::Ordering;
+use shared::constants::game_constants::{CONFIG_DUMP_PATH, REFERENCE_IMAGES};
+use shared::constants::timing::frames_at_rate;
+use shared::json_api::config_arguments;
+use shared::{PauseMode, Phase, PostWinMode, HIDE_IMAGE};
+use std::path::PathBuf;
 
 // Plugin for managing all the game systems.config
 pub struct SystemsLogicPlugin;
@@ -24,58 +40,117 @@ impl Plugin for SystemsLogicPlugin {
     /// Builds the plugin by adding the systems to the app.
     fn build(&self, app: &mut App) {
         app.init_resource::<BlankScreenState>()
+            .init_resource::<DisplayRate>()
+            .init_resource::<DecorationProbe>()
+            .init_resource::<TrialPhase>()
+            .init_resource::<ConfigDumpPath>()
+            .init_resource::<CarriedYaw>()
+            .init_resource::<YawHistory>()
+            .init_resource::<InputsLocked>()
+            .init_resource::<SetupConfig>()
+            .init_resource::<GeometryDumpDir>()
             // Spawn persistent camera and static environment once at startup
-            .add_systems(Startup, (spawn_persistent_camera, setup_environment))
+            .add_systems(Startup, (spawn_persistent_camera, setup_environment, load_reference_images))
             // Global UI responsiveness system (runs every frame)
-            .add_systems(Update, update_ui_scale)
+            .add_systems(Update, update_ui_scale.run_if(any_with_component::<PrimaryWindow>))
             // Command driven
             .add_systems(
                 Update,
-                (handle_reset_command, handle_animation_door_command),
+                (
+                    // After the trial phase, which queues the reset of a staged next trial this frame
+                    // (pending actions are cleared before the next one)
+                    handle_reset_command.after(update_trial_phase),
+                    // Once the new round's entities are spawned
+                    archive_stimulus.after(handle_reset_command),
+                    // After the archive, which describes the unmirrored stimulus
+                    mirror_new_entities.after(archive_stimulus),
+                    handle_animation_door_command,
+                    handle_dump_config_command,
+                    // Reads the meshes as shown, mirrored ones included
+                    handle_dump_geometry_command.after(mirror_new_entities),
+                    handle_clear_attempts_command,
+                    handle_set_pyramid_type_command,
+                    // Sees the door lights of a round spawned this frame
+                    handle_render_quality_command.after(handle_reset_command),
+                    handle_probe_command,
+                    // Sees the reset queued by the trial phase, hidden before the new trial's first frame
+                    apply_reference_image.after(update_trial_phase).before(handle_reset_command),
+                ),
             )
+            // Live feedback bar tracks the alignment at the fixed timestep
+            .add_systems(FixedUpdate, update_live_feedback_bar.run_if(is_not_paused))
+            // Decoration motion runs on the fixed clock too, so it replays the same from the config
+            .add_systems(FixedUpdate, animate_decorations.run_if(is_not_paused))
+            // So does the inter-trial interval, which lasts the same time whatever the render rate
+            .add_systems(FixedUpdate, count_iti_ticks.run_if(is_not_paused))
             // Rendering control systems (run any time)
             .add_systems(Update, (apply_blank_screen, handle_rendering_pause))
+            // Also while paused, to show the faces unmodulated
+            .add_systems(Update, apply_face_flicker.after(handle_rendering_pause))
+            // Rig alignment, also while paused
+            .add_systems(Update, apply_global_yaw_offset.after(apply_pending_rotation))
             // Input and Logic Systems
             .add_systems(
                 Update,
                 (
-                    // Command-driven systems
-                    // We removed is_not_animating check for now as checking SHM atomic every frame in run condition is OK but we can just simplify.
+                    // Command-driven systems, the camera controls only while the inputs are free
                     (
-                        apply_pending_rotation,
-                        apply_pending_zoom,
+                        apply_pending_rotation.run_if(inputs_unlocked),
+                        apply_pending_zoom.run_if(inputs_unlocked),
+                        // Once the frame's turn and offset are applied, for the check below
+                        track_approach.after(apply_global_yaw_offset),
                         apply_pending_check_alignment,
+                        apply_shaping_dwell,
                         handle_door_animation,
                         update_score_bar_animation,
+                        update_reticle_tint,
+                        // After the door animation, so the blank is up before a reset can follow the win
+                        update_trial_phase.after(handle_door_animation),
                     )
+                        .chain()
                         .run_if(is_not_paused),
-
-                ).chain(),
+                    // Once the frame's check and phase are settled, for the next frame's controls
+                    update_inputs_lock.after(update_trial_phase).after(handle_reset_command),
+                    update_standby_screen.after(update_trial_phase),
+                ),
             );
     }
 }
 
+/// 3D game entities hidden in PauseMode::HideGameEntities (the reticle and bars are UI and stay)
+type PausableGameEntity = (With<GameEntity>, Without<Node>, Without<PersistentCamera>);
+
 fn is_not_paused(rendering_paused: Res<RenderingPaused>) -> bool {
     !rendering_paused.0
 }
 
+/// Whether rotation and zoom commands are ignored, see update_inputs_lock
+#[derive(Resource, Default)]
+pub struct InputsLocked(pub bool);
+
+fn inputs_unlocked(inputs_locked: Res<InputsLocked>) -> bool {
+    !inputs_locked.0
+}
+
 /// This camera persists across resets to avoid artifacts.
 fn spawn_persistent_camera(mut commands: Commands, shm_res: Option<Res<SharedMemResource>>) {
     // Get initial of camera
-    let (camera_3d_initial_x, camera_3d_initial_y, camera_3d_initial_z) = if let Some(ref shm_res) = shm_res {
+    let (camera_3d_initial_x, camera_3d_initial_y, camera_3d_initial_z, projection) = if let Some(ref shm_res) = shm_res {
         let shm = shm_res.0.get();
         (
             f32::from_bits(shm.game_structure_game.camera_x.load(Ordering::Relaxed)),
             f32::from_bits(shm.game_structure_game.camera_y.load(Ordering::Relaxed)),
             f32::from_bits(shm.game_structure_game.camera_z.load(Ordering::Relaxed)),
+            configured_projection(&shm.game_structure_game),
         )
     } else {
-        (0.0, 0.0, 0.0)
+        (0.0, 0.0, 0.0, Projection::default())
     };
 
 
     commands.spawn((
         Camera3d::default(),
+        projection,
         Transform::from_xyz(
             camera_3d_initial_x,
             camera_3d_initial_y,
@@ -92,10 +167,45 @@ pub struct BlankScreenState {
     pub is_active: bool,
 }
 
+/// Trial phase run by the game. The PostWinMode is latched when the inter-trial interval
+/// starts, since the controller may reset into the next trial (and its config) during it
+#[derive(Resource, Default)]
+pub struct TrialPhase {
+    pub phase: Phase,
+    /// Fixed ticks left in the inter-trial interval, counted down by count_iti_ticks
+    pub iti_remaining: u32,
+    mode: PostWinMode,
+    /// The queued next trial has been applied, waiting for its reset to land
+    queued: bool,
+    /// `reset_ack` when the trial ended. A different ack means the next trial has been loaded
+    ended_at_ack: Option<u32>,
+    /// `reset_ack` of the last trial counted in `trial_generation` once it started playing
+    playing_ack: Option<u32>,
+}
+
+/// Starts the first trial from the compiled-in config at launch instead of waiting in the Standby
+/// phase for a controller reset (the `--autostart` of the standalone demo)
+#[derive(Resource)]
+pub struct Autostart;
+
 /// Marker component for the blank screen overlay entity
 #[derive(Component)]
 pub struct BlankScreenOverlay;
 
+/// JSONL file `dump_config` appends the trial config to, CONFIG_DUMP_PATH by default
+#[derive(Resource, Clone, Debug)]
+pub struct ConfigDumpPath(pub PathBuf);
+
+impl Default for ConfigDumpPath {
+    fn default() -> Self {
+        Self(PathBuf::from(CONFIG_DUMP_PATH))
+    }
+}
+
+/// Marker component for the dimmed screen shown in the Standby phase
+#[derive(Component)]
+pub struct StandbyOverlay;
+
 /// Helper function to spawn a fullscreen black overlay
 fn spawn_blank_overlay(commands: &mut Commands) {
     commands.spawn((
@@ -114,6 +224,76 @@ fn spawn_blank_overlay(commands: &mut Commands) {
 }
 
 
+/// Reference images, loaded once at startup in REFERENCE_IMAGES order
+#[derive(Resource, Default)]
+pub struct ReferenceImages(pub Vec<Handle<Image>>);
+
+/// Marker component for the reference image overlay, with the REFERENCE_IMAGES index it shows
+#[derive(Component)]
+pub struct ReferenceImageOverlay {
+    pub index: u32,
+}
+
+/// Loads the reference images. Without an image asset store (headless) the handles stay default,
+/// so the overlay is still spawned and reported, just never drawn
+fn load_reference_images(
+    mut commands: Commands,
+    asset_server: Option<Res<AssetServer>>,
+    images: Option<Res<Assets<Image>>>,
+) {
+    let handles = match (asset_server, images) {
+        (Some(asset_server), Some(_)) => REFERENCE_IMAGES.iter().map(|path| asset_server.load(*path)).collect(),
+        _ => vec![Handle::default(); REFERENCE_IMAGES.len()],
+    };
+    commands.insert_resource(ReferenceImages(handles));
+}
+
+/// Shows or hides the full screen reference image requested through `show_image`. Every reset hides
+/// it first, so a show arriving with the reset is the new trial's
+fn apply_reference_image(
+    mut commands: Commands,
+    mut pending_image: ResMut<PendingReferenceImage>,
+    pending_reset: Res<PendingReset>,
+    images: Option<Res<ReferenceImages>>,
+    overlay_query: Query<Entity, With<ReferenceImageOverlay>>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let request = match pending_image.0.take() {
+        Some(request) => request,
+        None if pending_reset.0 && !overlay_query.is_empty() => HIDE_IMAGE,
+        None => return,
+    };
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let mut shown = HIDE_IMAGE;
+    if request == HIDE_IMAGE {
+        log_event!(commands, "Reference image hidden");
+    } else if let Some(handle) = images.as_ref().and_then(|images| images.0.get(request as usize)) {
+        commands.spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Px(0.0),
+                ..default()
+            },
+            ImageNode::new(handle.clone()),
+            GlobalZIndex(1001), // Above the blank overlay
+            ReferenceImageOverlay { index: request },
+        ));
+        shown = request;
+        log_event!(commands, "Reference image {} shown ({})", request, REFERENCE_IMAGES[request as usize]);
+    } else {
+        log_event!(error, "No reference image {}, hiding it", request);
+    }
+
+    if let Some(shm_res) = shm_res {
+        shm_res.0.get().game_structure_game.reference_image.store(shown, Ordering::Relaxed);
+    }
+}
 
 /// Reset state
 fn handle_reset_command(
@@ -123,14 +303,17 @@ fn handle_reset_command(
     materials: ResMut<Assets<StandardMaterial>>,
     time: Res<Time>,
     mut frame_counter: ResMut<FrameCounterResource>,
-    camera_query: Query<&mut Transform, With<PersistentCamera>>,
+    camera_query: Query<(Entity, &mut Transform, &mut Projection), With<PersistentCamera>>,
     game_entities: Query<Entity, With<GameEntity>>,
     ambient_light: Option<ResMut<GlobalAmbientLight>>,
     shm_res: Option<Res<SharedMemResource>>,
     ui_entities: Query<Entity, With<UIEntity>>,
-    spotlight_query: Query<&mut SpotLight, (Without<crate::utils::objects::HoleLight>, Without<GameEntity>)>,
-    round_start: ResMut<RoundStartTimestamp>,
+    setup_config: Option<Res<SetupConfig>>,
+    backdrop_query: Query<&mut Visibility, With<Backdrop>>,
+    // Round timing and starting yaw, set by setup_round, and its section times when profiling
+    (round_start, carried_yaw, reset_sections): (ResMut<RoundStartTimestamp>, ResMut<CarriedYaw>, Option<Res<ResetSections>>),
     mut door_win_entities: ResMut<DoorWinEntities>,
+    mut pending_reset_ack: ResMut<PendingResetAck>,
 ) {
     
     if !pending_reset.0 {
@@ -149,7 +332,17 @@ fn handle_reset_command(
 
     // Clear is_animating flag in SHM
     if let Some(ref shm_res) = shm_res {
-        shm_res.0.get().game_structure_game.is_animating.store(false, Ordering::Relaxed);
+        let gs_game = &shm_res.0.get().game_structure_game;
+        gs_game.is_animating.store(false, Ordering::Relaxed);
+        // An ended trial (or one whose interval is over), like the standby before the first one, keeps
+        // its phase until the next phase update starts the new trial: emitted as Resetting on this frame instead
+        let phase = Phase::from_u32(gs_game.phase.load(Ordering::Relaxed));
+        let interval_over = gs_game.iti_remaining_frames.load(Ordering::Relaxed) == 0;
+        if matches!(phase, Some(Phase::Won | Phase::Failed | Phase::Standby))
+            || (phase == Some(Phase::InterTrial) && interval_over)
+        {
+            gs_game.phase.store(Phase::Resetting as u32, Ordering::Relaxed);
+        }
     }
 
     despawn_all_game_and_ui(commands.reborrow(), game_entities, ui_entities);
@@ -160,18 +353,74 @@ fn handle_reset_command(
         meshes,
         materials,
         camera_query,
-        spotlight_query,
+        setup_config,
+        backdrop_query,
         ambient_light,
         shm_res,
         round_start,
+        carried_yaw,
         time,
         door_win_entities,
+        reset_sections,
     );
 
-    spawn_score_bar(&mut commands);
+    pending_reset_ack.0 = true;
+}
+
+
+/// Appends the current trial's config to the dump file, as the arguments `apply_config_json` takes,
+/// so a seed-generated trial can be replayed as an explicit config
+fn handle_dump_config_command(
+    mut pending_dump: ResMut<PendingDumpConfig>,
+    dump_path: Res<ConfigDumpPath>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    if !pending_dump.0 {
+        return;
+    }
+    pending_dump.0 = false;
 
+    let Some(shm_res) = shm_res else { return };
+    let line = serde_json::Value::Object(config_arguments(&shm_res.0.get().game_structure_game)).to_string();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::io::Write;
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&dump_path.0)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        match result {
+            Ok(()) => log_event!(commands, "💾 Dumped trial config to {}: {}", dump_path.0.display(), line),
+            Err(e) => log_event!(error, "Failed to dump trial config to {}: {}", dump_path.0.display(), e),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = dump_path;
+        log_event!(commands, "💾 Trial config (no file system on web): {}", line);
+    }
 }
 
+/// Forgives spurious checks: zeroes the attempts and rejected checks, leaving the stimulus,
+/// camera and timers untouched, then acknowledges through `clear_attempts_ack`
+fn handle_clear_attempts_command(
+    mut pending_clear: ResMut<PendingClearAttempts>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    if !pending_clear.0 {
+        return;
+    }
+    pending_clear.0 = false;
+
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    gs_game.attempts.store(0, Ordering::Relaxed);
+    gs_game.rejected_checks.store(0, Ordering::Relaxed);
+    gs_game.clear_attempts_ack.fetch_add(1, Ordering::Release);
+    log_event!(commands, "🧹 Attempts cleared");
+}
 
 /// System to handle animation door command
 fn handle_animation_door_command(
@@ -189,7 +438,7 @@ fn handle_animation_door_command(
     let shm = shm_res.0.get();
 
     if shm.game_structure_game.is_animating.load(Ordering::Relaxed) {
-        info!("Animation door command ignored: already animating");
+        log_event!(animation, "Animation door command ignored: already animating");
         return;
     }
 
@@ -198,12 +447,12 @@ fn handle_animation_door_command(
     let found_emissive = door_win_entities.winning_emissive;
 
     if found_light.is_none() && found_emissive.is_none() {
-        warn!("Animation door command: no winning door entities found (not populated in setup_round)");
+        log_event!(error, "Animation door command: no winning door entities found (not populated in setup_round)");
         return;
     }
 
     // Only start animation if we found at least one entity
-    info!("Starting door animation from pre-populated entities: light={:?}, emissive={:?}", found_light, found_emissive);
+    log_event!(animation, "Starting door animation from pre-populated entities: light={:?}, emissive={:?}", found_light, found_emissive);
     door_win_entities.animation_start_time = Some(time.elapsed());
     shm.game_structure_game
         .is_animating
@@ -224,34 +473,220 @@ fn apply_blank_screen(
         if blank_state.is_active {
             // Spawn black fullscreen overlay
             spawn_blank_overlay(&mut commands);
-            info!("Blank screen activated");
+            log_event!(commands, "Blank screen activated");
         } else {
             // Despawn the overlay
             for entity in overlay_query.iter() {
                 commands.entity(entity).despawn();
             }
-            info!("Blank screen deactivated");
+            log_event!(commands, "Blank screen deactivated");
+        }
+    }
+}
+
+/// Advances the trial phase: the Standby of a fresh launch lasts until the first reset. Playing
+/// ends in Won (win time set) or Failed (out of attempts or time), which turn into the blank inter-trial interval once the door animation is over. After
+/// `iti_frames` (nominal, converted to fixed ticks) the blank lifts if the controller reset into the next trial meanwhile, or applies
+/// the queued next trial; otherwise the PostWinMode decides between returning and holding.
+fn update_trial_phase(
+    mut commands: Commands,
+    mut trial: ResMut<TrialPhase>,
+    mut blank_state: ResMut<BlankScreenState>,
+    overlay_query: Query<Entity, With<BlankScreenOverlay>>,
+    mut pending_reset: ResMut<PendingReset>,
+    mut pending_queue_next: ResMut<PendingQueueNext>,
+    (shm_res, fixed_time): (Option<Res<SharedMemResource>>, Res<Time<Fixed>>),
+    autostart: Option<Res<Autostart>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let reset_ack = gs_game.reset_ack.load(Ordering::Acquire);
+    let reset_since_end = trial.ended_at_ack.is_some_and(|ack| ack != reset_ack);
+
+    match trial.phase {
+        Phase::Standby => {
+            // Left once the first reset has landed (emitted as Resetting), which --autostart requests itself
+            if Phase::from_u32(gs_game.phase.load(Ordering::Relaxed)) != Some(Phase::Resetting) {
+                pending_reset.0 |= autostart.is_some();
+                return;
+            }
+            trial.phase = Phase::Playing;
+            log_event!(reset, "First trial loaded, leaving standby");
+        }
+        // A finished trial stays finished until the next one is loaded
+        Phase::Playing if trial.ended_at_ack != Some(reset_ack) => {
+            let max_attempts = gs_game.max_attempts.load(Ordering::Relaxed);
+            let timeout = f32::from_bits(gs_game.trial_timeout_secs.load(Ordering::Relaxed));
+            let elapsed = f32::from_bits(gs_game.elapsed_secs.load(Ordering::Relaxed));
+            if gs_game.win_time.load(Ordering::Relaxed) != 0 {
+                trial.phase = Phase::Won;
+                trial.ended_at_ack = Some(reset_ack);
+            } else if (max_attempts > 0 && gs_game.attempts.load(Ordering::Relaxed) >= max_attempts)
+                || (timeout > 0.0 && elapsed >= timeout)
+            {
+                trial.phase = Phase::Failed;
+                trial.ended_at_ack = Some(reset_ack);
+                log_event!(reset, "⌛ Trial failed: out of attempts or time");
+            }
         }
+        Phase::Playing => {}
+        Phase::Won | Phase::Failed => {
+            if reset_since_end {
+                // Reset before the interval started: straight into the new trial
+                trial.phase = Phase::Playing;
+            } else if !gs_game.is_animating.load(Ordering::Relaxed) {
+                trial.phase = Phase::InterTrial;
+                let fixed_hz = 1.0 / fixed_time.timestep().as_secs_f64();
+                trial.iti_remaining = frames_at_rate(gs_game.iti_frames.load(Ordering::Relaxed), fixed_hz);
+                gs_game.iti_remaining_frames.store(trial.iti_remaining, Ordering::Relaxed);
+                trial.mode = PostWinMode::from_u32(gs_game.post_win_mode.load(Ordering::Relaxed)).unwrap_or_default();
+                trial.queued = false;
+                if !blank_state.is_active {
+                    blank_state.is_active = true;
+                    spawn_blank_overlay(&mut commands);
+                }
+                log_event!(reset, "⬛ Inter-trial interval started ({} fixed ticks, {:?})", trial.iti_remaining, trial.mode);
+            }
+        }
+        Phase::InterTrial => {
+            if trial.iti_remaining > 0 {
+                // Still blank
+            } else if reset_since_end || (!trial.queued && !pending_queue_next.0 && trial.mode == PostWinMode::AutoReturn) {
+                trial.phase = Phase::Playing;
+                if blank_state.is_active {
+                    blank_state.is_active = false;
+                    for entity in overlay_query.iter() {
+                        commands.entity(entity).despawn();
+                    }
+                }
+                log_event!(reset, "⬜ Inter-trial interval over");
+            } else if pending_queue_next.0 {
+                // Apply the staged config, the blank lifts once the reset has landed
+                pending_queue_next.0 = false;
+                pending_reset.0 = true;
+                trial.queued = true;
+            }
+        }
+        // Only ever emitted by handle_reset_command, never tracked here
+        Phase::Resetting => {}
+    }
+
+    gs_game.phase.store(trial.phase as u32, Ordering::Relaxed);
+    // Count a newly loaded trial once it plays (a reset mid-trial, or the first frame out of standby or
+    // an interval), after its phase, so a controller that sees the new generation sees it playing
+    if trial.phase == Phase::Playing && trial.playing_ack != Some(reset_ack) {
+        trial.playing_ack = Some(reset_ack);
+        gs_game.trial_generation.fetch_add(1, Ordering::Release);
     }
 }
 
-/// System to handle rendering pause - hides/shows the persistent camera
+/// Counts the inter-trial interval down by one fixed tick and emits the ticks left
+fn count_iti_ticks(mut trial: ResMut<TrialPhase>, shm_res: Option<Res<SharedMemResource>>) {
+    if trial.phase == Phase::InterTrial {
+        trial.iti_remaining = trial.iti_remaining.saturating_sub(1);
+    }
+    if let Some(shm_res) = shm_res {
+        shm_res.0.get().game_structure_game.iti_remaining_frames.store(trial.iti_remaining, Ordering::Relaxed);
+    }
+}
+
+/// Dims the screen while in standby, so an idle game is not mistaken for a trial
+fn update_standby_screen(
+    mut commands: Commands,
+    trial: Res<TrialPhase>,
+    overlay_query: Query<Entity, With<StandbyOverlay>>,
+) {
+    let standby = trial.phase == Phase::Standby;
+    if standby && overlay_query.is_empty() {
+        commands
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.0),
+                    top: Val::Px(0.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+                GlobalZIndex(999), // Below the blank overlay
+                StandbyOverlay,
+            ))
+            .with_child((
+                Text::new("STANDBY: waiting for the first trial config"),
+                TextFont { font_size: 18.0, ..default() },
+                TextColor(Color::srgb(0.4, 0.4, 0.4)),
+            ));
+    } else if !standby {
+        for entity in overlay_query.iter() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Locks the camera controls from the frame a check is decided, through its feedback delay and
+/// door animation, until the inter-trial blank lifts, and emits the lock
+fn update_inputs_lock(
+    mut inputs_locked: ResMut<InputsLocked>,
+    trial: Res<TrialPhase>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+    let locked = gs_game.is_animating.load(Ordering::Relaxed)
+        || matches!(trial.phase, Phase::Won | Phase::Failed | Phase::InterTrial);
+    if inputs_locked.0 != locked {
+        inputs_locked.0 = locked;
+        log_event!(commands, "{} camera controls", if locked { "🔒 Locked" } else { "🔓 Unlocked" });
+    }
+    gs_game.inputs_locked.store(locked, Ordering::Relaxed);
+}
+
+/// System to handle rendering pause - hides what the trial's pause_mode asks for while paused
 fn handle_rendering_pause(
+    mut commands: Commands,
     rendering_paused: Res<RenderingPaused>,
-    mut visibility_query: Query<&mut Visibility, With<PersistentCamera>>,
+    mut camera_query: Query<&mut Visibility, With<PersistentCamera>>,
+    game_query: Query<(Entity, Option<&Visibility>, Option<&HiddenByPause>), PausableGameEntity>,
+    shm_res: Option<Res<SharedMemResource>>,
 ) {
-    // Only act when the resource has changed
-    if !rendering_paused.is_changed() {
+    if !rendering_paused.0 {
+        // Only act when the resource has changed, undoing whichever mode the pause used
+        if rendering_paused.is_changed() {
+            for mut visibility in camera_query.iter_mut() {
+                *visibility = Visibility::Visible;
+            }
+            for (entity, _, hidden) in game_query.iter() {
+                if let Some(HiddenByPause(previous)) = hidden {
+                    commands.entity(entity).try_insert(*previous).try_remove::<HiddenByPause>();
+                }
+            }
+        }
         return;
     }
 
-    // When paused, we can hide the 3D camera to stop rendering
-    for mut visibility in visibility_query.iter_mut() {
-        if rendering_paused.0 {
-            *visibility = Visibility::Hidden;
-        } else {
-            *visibility = Visibility::Visible;
+    let mode = shm_res
+        .and_then(|shm_res| PauseMode::from_u32(shm_res.0.get().game_structure_game.pause_mode.load(Ordering::Relaxed)))
+        .unwrap_or_default();
+    match mode {
+        // When paused, we can hide the 3D camera to stop rendering
+        PauseMode::HideCamera if rendering_paused.is_changed() => {
+            for mut visibility in camera_query.iter_mut() {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        // Every frame, so entities spawned by a reset during the pause stay hidden too
+        PauseMode::HideGameEntities => {
+            for (entity, visibility, hidden) in game_query.iter() {
+                if hidden.is_none() {
+                    let previous = visibility.copied().unwrap_or_default();
+                    commands.entity(entity).try_insert((HiddenByPause(previous), Visibility::Hidden));
+                }
+            }
         }
+        _ => {}
     }
 }
 
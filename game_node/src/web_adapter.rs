@@ -1,21 +1,293 @@
+//! Web adapter for the browser build: attaches the WASM shared memory, pauses the game while the
+//! page is hidden, reads a gamepad, and saves the session's progress.
+//!
+//! A hidden tab throttles requestAnimationFrame to about 1 Hz or stops it, and the fixed timestep
+//! would catch up with a burst of updates on refocus. So while the page is hidden the game is
+//! paused (`RenderingPaused`) and virtual time stops, which keeps door animations still and the
+//! hidden time out of `elapsed_secs`; the overstep is dropped on refocus. The game emits
+//! `tab_hidden` and the seconds hidden during the trial, so a controller can invalidate the trial.
+//!
+//! The first connected gamepad of the browser's Gamepad API is polled every frame and feeds the same
+//! pending commands as the controller: the stick moves the camera like the rotate and zoom buttons,
+//! scaled by how far it is pushed, and the check button requests one check per press.
+//!
+//! Every SESSION_SAVE_INTERVAL_FRAMES frames a small progress record (trial, wins, attempts) is
+//! saved to localStorage. After a reload the page calls `restore_session()` to get the record of
+//! the interrupted session, and re-stages its trial through the usual config and reset if it wants
+//! to; `clear_session()` deletes the record, and the game starts its count over when it finds the
+//! record gone at the next save. Storage failures are logged and otherwise ignored.
+
 use bevy::prelude::*;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-#[cfg(target_arch = "wasm32")]
-use crate::command_handler::SharedMemResource;
+use shared::constants::camera_3d_constants::{GAMEPAD_DEAD_ZONE, GAMEPAD_ROTATE_GAIN, GAMEPAD_ZOOM_GAIN};
+use shared::constants::game_constants::SESSION_SAVE_INTERVAL_FRAMES;
+use shared::SharedGameStructure;
+
+use crate::command_handler::{
+    control_speeds, read_shared_memory, watch_controller_heartbeat, PendingCheckAlignment, PendingRotation,
+    PendingZoom, RenderingPaused, SharedMemResource,
+};
+use crate::display_rate::DisplayRate;
+use crate::log_event;
 
 #[cfg(target_arch = "wasm32")]
 use shared::open_shared_memory;
 
-/// Plugin to add wasm shared memory to the Bevy systems
+/// Page visibility, and the hidden intervals of the current trial
+#[derive(Resource, Default, Debug)]
+pub struct TabVisibility {
+    /// Set by the page's visibilitychange listener (by tests natively)
+    pub hidden: Arc<AtomicBool>,
+    /// Real time the page was hidden at, while it is
+    hidden_since: Option<Duration>,
+    /// Hidden time of the current trial, without the ongoing interval
+    hidden_trial: Duration,
+    /// reset_ack of the trial the hidden time belongs to
+    reset_ack: u32,
+}
+
+/// First connected gamepad, as polled from the browser this frame (set by tests natively)
+#[derive(Resource, Default, Debug)]
+pub struct GamepadInput {
+    /// Id of the pad, None while no pad is connected
+    pub pad: Option<String>,
+    /// Raw stick axes, -1 to 1
+    pub rotate_axis: f32,
+    pub zoom_axis: f32,
+    pub check_button: bool,
+    /// Pad and button of the previous frame, for the connection logs and the press edge
+    last_pad: Option<String>,
+    check_was_pressed: bool,
+}
+
+/// Progress of the session in this page, saved as a `SessionRecord`
+#[derive(Resource, Default, Debug)]
+pub struct SessionProgress {
+    /// Trials won so far, carried over from the restored record
+    pub wins: u32,
+    /// Last record saved
+    pub last_saved: Option<SessionRecord>,
+    /// trial_generation of the last trial counted as won
+    won_trial: Option<u32>,
+    /// The wins were carried over from a restored record
+    restored: bool,
+    frames: u32,
+}
+
+/// Session progress as saved to localStorage under SESSION_STORAGE_KEY
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Written as a hex string, JS numbers cannot hold all 64 bits
+    #[serde(with = "hex_u64")]
+    pub trial_id_hash: u64,
+    /// trial_generation of the trial being played on the saving page
+    pub generation: u32,
+    pub wins: u32,
+    pub attempts: u32,
+    pub elapsed_secs: f32,
+    /// Wall clock time of the save, milliseconds since the Unix epoch
+    pub saved_at_ms: f64,
+}
+
+impl SessionRecord {
+    pub fn capture(gs_game: &SharedGameStructure, wins: u32, saved_at_ms: f64) -> Self {
+        Self {
+            trial_id_hash: gs_game.trial_id_hash.load(Ordering::Relaxed),
+            generation: gs_game.trial_generation.load(Ordering::Relaxed),
+            wins,
+            attempts: gs_game.attempts.load(Ordering::Relaxed),
+            elapsed_secs: f32::from_bits(gs_game.elapsed_secs.load(Ordering::Relaxed)),
+            saved_at_ms,
+        }
+    }
+
+    /// JSON object of the record
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a session record serializes")
+    }
+
+    /// Parses a record written by to_json, None for anything else
+    pub fn from_json(json: &str) -> Option<Self> {
+        serde_json::from_str(json).ok()
+    }
+
+    /// Saved at most `max_age_secs` before `now_ms` (a record from the future is not fresh either)
+    pub fn is_fresh(&self, now_ms: f64, max_age_secs: f64) -> bool {
+        let age_ms = now_ms - self.saved_at_ms;
+        (0.0..=max_age_secs * 1000.0).contains(&age_ms)
+    }
+}
+
+/// u64 as a 16 digit hex string
+mod hex_u64 {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{value:016x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        u64::from_str_radix(&hex, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+impl SessionProgress {
+    /// Starts the count over, as after `clear_session()`
+    pub fn start_over(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Whether a record of this session should be in storage: one was saved or restored
+    pub fn has_record(&self) -> bool {
+        self.last_saved.is_some() || self.restored
+    }
+}
+
+/// Plugin to add wasm shared memory to the Bevy systems, to pause while the page is hidden, to
+/// read a gamepad, and to save the session's progress
 pub struct WebAdapterPlugin;
 
 impl Plugin for WebAdapterPlugin {
-    fn build(&self, _app: &mut App) {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TabVisibility>()
+            .init_resource::<GamepadInput>()
+            // After the commands, which clear and set the pause every frame
+            .add_systems(PreUpdate, track_tab_visibility.after(read_shared_memory))
+            // After the watchdog too: the pad is local input, it keeps working when the controller is lost
+            .add_systems(PreUpdate, apply_gamepad.after(watch_controller_heartbeat))
+            .init_resource::<SessionProgress>()
+            .add_systems(Last, save_session_progress);
+
         #[cfg(target_arch = "wasm32")]
-        _app.add_systems(Startup, init_web_shm);
+        app.add_systems(Startup, (init_web_shm, listen_visibility_change, restore_session_progress))
+            .add_systems(PreUpdate, poll_gamepad.before(apply_gamepad));
+    }
+}
+
+/// Wall clock time in milliseconds since the Unix epoch
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0.0, |since| since.as_secs_f64() * 1000.0)
+}
+
+/// Browser storage of the session record. Every failure (storage disabled, quota, private mode)
+/// is logged and turns the call into a no-op
+#[cfg(target_arch = "wasm32")]
+mod session_storage {
+    use shared::constants::game_constants::SESSION_STORAGE_KEY;
+    use std::sync::OnceLock;
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        match web_sys::window().map(|window| window.local_storage()) {
+            Some(Ok(Some(storage))) => Some(storage),
+            Some(Err(e)) => {
+                bevy::log::warn!("localStorage unavailable: {:?}", e);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Record stored when the page loaded, before this page saved over it
+    pub fn previous() -> Option<String> {
+        static PREVIOUS: OnceLock<Option<String>> = OnceLock::new();
+        PREVIOUS
+            .get_or_init(|| local_storage().and_then(|storage| storage.get_item(SESSION_STORAGE_KEY).ok().flatten()))
+            .clone()
+    }
+
+    pub fn save(json: &str) {
+        if let Some(Err(e)) = local_storage().map(|storage| storage.set_item(SESSION_STORAGE_KEY, json)) {
+            bevy::log::warn!("Cannot save the session: {:?}", e);
+        }
+    }
+
+    pub fn clear() {
+        if let Some(Err(e)) = local_storage().map(|storage| storage.remove_item(SESSION_STORAGE_KEY)) {
+            bevy::log::warn!("Cannot clear the session: {:?}", e);
+        }
+    }
+
+    /// Whether the storage is readable and holds no record, i.e. clear() ran since the last save
+    pub fn is_cleared() -> bool {
+        local_storage().is_some_and(|storage| matches!(storage.get_item(SESSION_STORAGE_KEY), Ok(None)))
     }
 }
 
+/// Record of the session interrupted by the last reload, as JSON (see `SessionRecord::to_json`),
+/// or undefined when there is none or it is older than `max_age_secs` (SESSION_MAX_AGE_SECS by
+/// default)
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn restore_session(max_age_secs: Option<f64>) -> Option<String> {
+    use shared::constants::game_constants::SESSION_MAX_AGE_SECS;
+    let max_age_secs = max_age_secs.unwrap_or(SESSION_MAX_AGE_SECS as f64);
+    let record = SessionRecord::from_json(&session_storage::previous()?)?;
+    record.is_fresh(now_ms(), max_age_secs).then(|| record.to_json())
+}
+
+/// Deletes the saved session; the game starts its win count over at its next save
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn clear_session() {
+    session_storage::clear();
+}
+
+/// Carries the wins of a fresh interrupted session over, so the saved score keeps counting
+#[cfg(target_arch = "wasm32")]
+fn restore_session_progress(mut progress: ResMut<SessionProgress>) {
+    use shared::constants::game_constants::SESSION_MAX_AGE_SECS;
+    let Some(record) = session_storage::previous().as_deref().and_then(SessionRecord::from_json) else {
+        return;
+    };
+    if record.is_fresh(now_ms(), SESSION_MAX_AGE_SECS as f64) {
+        progress.wins = record.wins;
+        progress.restored = true;
+        log_event!(shm, "Previous session found: trial {:016x}, {} wins", record.trial_id_hash, record.wins);
+    }
+}
+
+/// Counts the session's wins and saves its record every SESSION_SAVE_INTERVAL_FRAMES frames
+fn save_session_progress(mut progress: ResMut<SessionProgress>, shm_res: Option<Res<SharedMemResource>>) {
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    let trial = gs_game.trial_generation.load(Ordering::Relaxed);
+    if gs_game.win_time.load(Ordering::Relaxed) != 0 && progress.won_trial != Some(trial) {
+        progress.won_trial = Some(trial);
+        progress.wins += 1;
+    }
+
+    if SESSION_SAVE_INTERVAL_FRAMES == 0 {
+        return;
+    }
+    progress.frames += 1;
+    if progress.frames < SESSION_SAVE_INTERVAL_FRAMES {
+        return;
+    }
+    progress.frames = 0;
+    // Only this page writes the record, so a missing one was deleted by clear_session()
+    #[cfg(target_arch = "wasm32")]
+    if progress.has_record() && session_storage::is_cleared() {
+        log_event!(shm, "Session cleared, starting the win count over");
+        progress.start_over();
+    }
+    let record = SessionRecord::capture(gs_game, progress.wins, now_ms());
+    #[cfg(target_arch = "wasm32")]
+    session_storage::save(&record.to_json());
+    progress.last_saved = Some(record);
+}
+
 #[cfg(target_arch = "wasm32")]
 fn init_web_shm(mut commands: Commands) {
     match open_shared_memory("monkey_game") {
@@ -28,3 +300,149 @@ fn init_web_shm(mut commands: Commands) {
         }
     }
 }
+
+/// Mirrors `document.hidden` into `TabVisibility::hidden` for the lifetime of the page
+#[cfg(target_arch = "wasm32")]
+fn listen_visibility_change(visibility: Res<TabVisibility>) {
+    use wasm_bindgen::{closure::Closure, JsCast};
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        warn!("No document, tab visibility is not tracked");
+        return;
+    };
+    visibility.hidden.store(document.hidden(), Ordering::Relaxed);
+
+    let hidden = visibility.hidden.clone();
+    let listener_document = document.clone();
+    let listener = Closure::<dyn FnMut()>::new(move || {
+        hidden.store(listener_document.hidden(), Ordering::Relaxed);
+    });
+    if let Err(e) = document.add_event_listener_with_callback("visibilitychange", listener.as_ref().unchecked_ref()) {
+        warn!("Cannot listen to visibilitychange: {:?}", e);
+    }
+    // Leaked on purpose, the listener stays registered for the lifetime of the page
+    listener.forget();
+}
+
+/// Reads the first connected pad of `navigator.getGamepads()`
+#[cfg(target_arch = "wasm32")]
+fn poll_gamepad(mut gamepad: ResMut<GamepadInput>) {
+    use shared::constants::camera_3d_constants::{GAMEPAD_CHECK_BUTTON, GAMEPAD_ROTATE_AXIS, GAMEPAD_ZOOM_AXIS};
+    use wasm_bindgen::JsCast;
+
+    // Disconnected slots are null or report connected = false
+    let pad = web_sys::window().and_then(|window| window.navigator().get_gamepads().ok()).and_then(|pads| {
+        pads.iter().filter_map(|pad| pad.dyn_into::<web_sys::Gamepad>().ok()).find(|pad| pad.connected())
+    });
+    let Some(pad) = pad else {
+        gamepad.pad = None;
+        return;
+    };
+    let axes = pad.axes();
+    let axis = |index: u32| axes.get(index).as_f64().unwrap_or(0.0) as f32;
+    gamepad.rotate_axis = axis(GAMEPAD_ROTATE_AXIS);
+    gamepad.zoom_axis = axis(GAMEPAD_ZOOM_AXIS);
+    gamepad.check_button = pad
+        .buttons()
+        .get(GAMEPAD_CHECK_BUTTON)
+        .dyn_into::<web_sys::GamepadButton>()
+        .is_ok_and(|button| button.pressed());
+    gamepad.pad = Some(pad.id());
+}
+
+/// Stick deflection past the dead zone, rescaled so motion starts from 0 at its edge
+fn past_dead_zone(axis: f32) -> f32 {
+    if !axis.is_finite() || axis.abs() <= GAMEPAD_DEAD_ZONE {
+        return 0.0;
+    }
+    (axis.signum() * (axis.abs() - GAMEPAD_DEAD_ZONE) / (1.0 - GAMEPAD_DEAD_ZONE)).clamp(-1.0, 1.0)
+}
+
+/// Turns the pad into pending rotation, zoom and checks, and emits whether one is connected
+fn apply_gamepad(
+    mut gamepad: ResMut<GamepadInput>,
+    mut pending_rotation: ResMut<PendingRotation>,
+    mut pending_zoom: ResMut<PendingZoom>,
+    mut pending_check: ResMut<PendingCheckAlignment>,
+    display_rate: Res<DisplayRate>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    if gamepad.pad != gamepad.last_pad {
+        match &gamepad.pad {
+            Some(id) => log_event!(commands, "🎮 Gamepad connected: {}", id),
+            None => log_event!(commands, "🎮 Gamepad disconnected"),
+        }
+        gamepad.last_pad = gamepad.pad.clone();
+    }
+    let gs_game = shm_res.as_ref().map(|shm_res| &shm_res.0.get().game_structure_game);
+    if let Some(gs_game) = gs_game {
+        gs_game.gamepad_connected.store(gamepad.pad.is_some(), Ordering::Relaxed);
+    }
+    if gamepad.pad.is_none() {
+        gamepad.check_was_pressed = false;
+        return;
+    }
+
+    // Same speeds and mirroring as the rotate and zoom buttons
+    let (rotate_speed, zoom_speed) = control_speeds(gs_game, &display_rate);
+    pending_rotation.0 += past_dead_zone(gamepad.rotate_axis) * GAMEPAD_ROTATE_GAIN * rotate_speed;
+    pending_zoom.0 += past_dead_zone(gamepad.zoom_axis) * GAMEPAD_ZOOM_GAIN * zoom_speed;
+
+    if gamepad.check_button && !gamepad.check_was_pressed {
+        pending_check.0 = true;
+    }
+    gamepad.check_was_pressed = gamepad.check_button;
+}
+
+/// Pauses the game and virtual time while the page is hidden, resumes without a catch-up burst,
+/// and emits the hidden state and time of the trial
+fn track_tab_visibility(
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut visibility: ResMut<TabVisibility>,
+    mut rendering_paused: ResMut<RenderingPaused>,
+    shm_res: Option<Res<SharedMemResource>>,
+) {
+    let now = real_time.elapsed();
+    let hidden = visibility.hidden.load(Ordering::Relaxed);
+
+    match visibility.hidden_since {
+        None if hidden => {
+            visibility.hidden_since = Some(now);
+            virtual_time.pause();
+            log_event!(commands, "🙈 Tab hidden, pausing the game");
+        }
+        Some(since) if !hidden => {
+            visibility.hidden_trial += now.saturating_sub(since);
+            visibility.hidden_since = None;
+            // Virtual time did not advance meanwhile; the leftover overstep would still run on its own
+            let overstep = fixed_time.overstep();
+            fixed_time.discard_overstep(overstep);
+            virtual_time.unpause();
+            log_event!(commands, "👀 Tab visible again after {:.2}s, resuming", now.saturating_sub(since).as_secs_f32());
+        }
+        _ => {}
+    }
+    if hidden {
+        rendering_paused.0 = true;
+    }
+
+    let Some(shm_res) = shm_res else { return };
+    let gs_game = &shm_res.0.get().game_structure_game;
+
+    // A new trial counts from scratch, an ongoing hidden interval from its reset
+    let reset_ack = gs_game.reset_ack.load(Ordering::Acquire);
+    if reset_ack != visibility.reset_ack {
+        visibility.reset_ack = reset_ack;
+        visibility.hidden_trial = Duration::ZERO;
+        if hidden {
+            visibility.hidden_since = Some(now);
+        }
+    }
+
+    let ongoing = visibility.hidden_since.map_or(Duration::ZERO, |since| now.saturating_sub(since));
+    let hidden_secs = (visibility.hidden_trial + ongoing).as_secs_f32();
+    gs_game.tab_hidden.store(hidden, Ordering::Relaxed);
+    gs_game.tab_hidden_secs_trial.store(hidden_secs.to_bits(), Ordering::Relaxed);
+}
@@ -0,0 +1,150 @@
+//! Alignment geometry swept over the camera yaw in 1° steps: the target door wins exactly within the
+//! angular window of the cosine threshold, for several start orientations and every pyramid type,
+//! and the pure door normals match the doors the game spawns.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::alignment::{best_aligned_door, door_normal, is_win, xz_alignment};
+use game_node::utils::objects::{BaseDoor, PersistentCamera};
+use shared::constants::game_constants::COSINE_ALIGNMENT_TO_WIN;
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+use shared::{PyramidType, SharedMemoryHandle};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+const START_ORIENTS: [f32; 5] = [0.0, 0.7, -1.3, 2.0, PI];
+const THRESHOLDS: [f32; 3] = [COSINE_ALIGNMENT_TO_WIN, 0.8, 0.99];
+const PYRAMID_TYPES: [PyramidType; 3] = [PyramidType::Type1, PyramidType::Type2, PyramidType::Type3];
+
+/// Direction door `door` faces out to (angle from +X towards +Z)
+fn door_angle(start_orient: f32, door: usize) -> f32 {
+    (door as f32 + 0.5) * TAU / BASE_NR_SIDES as f32 + start_orient + FRAC_PI_2
+}
+
+/// Forward of a camera on the orbit at `angle`, looking at the center
+fn orbit_forward(angle: f32) -> Vec3 {
+    -Vec3::new(angle.cos(), 0.0, angle.sin())
+}
+
+fn angular_distance(a: f32, b: f32) -> f32 {
+    ((a - b + PI).rem_euclid(TAU) - PI).abs()
+}
+
+#[test]
+fn alignment_signs() {
+    let normal = door_normal(0.0, 0);
+    let facing = door_angle(0.0, 0);
+    assert!((xz_alignment(normal, orbit_forward(facing)) - 1.0).abs() < 1e-6, "seen from outside");
+    assert!((xz_alignment(normal, orbit_forward(facing + PI)) + 1.0).abs() < 1e-6, "seen from behind");
+    // Pitch does not count, only the XZ projection
+    let pitched = orbit_forward(facing) + Vec3::Y * 0.5;
+    assert!((xz_alignment(normal, pitched) - 1.0).abs() < 1e-6);
+    // Strictly above the threshold
+    assert!(!is_win(0.95, 0.95, 0, 0));
+    assert!(is_win(0.9501, 0.95, 0, 0));
+    // Only the target door wins, however well aligned another one is
+    assert!(!is_win(1.0, 0.95, 1, 0));
+    assert_eq!(best_aligned_door(Vec3::NEG_Z, []), None);
+}
+
+#[test]
+fn target_door_wins_exactly_within_the_window() {
+    for start_orient in START_ORIENTS {
+        for threshold in THRESHOLDS {
+            let window = threshold.acos();
+            for door in 0..BASE_NR_SIDES {
+                let mut winning_degrees = 0;
+                for degree in 0..360 {
+                    let camera = (degree as f32).to_radians();
+                    let distance = angular_distance(camera, door_angle(start_orient, door));
+                    assert!((distance - window).abs() > 1e-5, "sweep hits the window edge within float precision");
+
+                    let alignment = xz_alignment(door_normal(start_orient, door), orbit_forward(camera));
+                    assert_eq!(
+                        is_win(alignment, threshold, door, door),
+                        distance < window,
+                        "orient {start_orient}, threshold {threshold}, door {door}, camera {degree}°"
+                    );
+                    // The same door never wins while another one is the target
+                    let other = (door + 1) % BASE_NR_SIDES;
+                    assert!(!is_win(alignment, threshold, door, other));
+                    winning_degrees += usize::from(distance < window);
+
+                    // Within half the door spacing the door is also the one faced most directly
+                    if distance < PI / BASE_NR_SIDES as f32 - 1e-3 {
+                        let doors = (0..BASE_NR_SIDES).map(|i| (i, door_normal(start_orient, i)));
+                        assert_eq!(best_aligned_door(orbit_forward(camera), doors), Some(door));
+                    }
+                }
+                // About twice the window, whatever the orientation
+                let expected = 2.0 * window.to_degrees();
+                assert!((winning_degrees as f32 - expected).abs() <= 1.0, "{winning_degrees} vs {expected}");
+            }
+        }
+    }
+}
+
+/// Puts the camera on its orbit at `angle`, same radius and height, looking at the center
+fn place_camera(app: &mut App, angle: f32) {
+    let world = app.world_mut();
+    let mut camera = world.query_filtered::<&mut Transform, With<PersistentCamera>>();
+    let mut transform = camera.single_mut(world).expect("persistent camera");
+    let radius = transform.translation.xz().length();
+    let height = transform.translation.y;
+    *transform = Transform::from_xyz(radius * angle.cos(), height, radius * angle.sin())
+        .looking_at(Vec3::new(0.0, height, 0.0), Vec3::Y);
+}
+
+/// Resets into a fresh round and checks once from `angle`, returning whether it won
+fn check_from(app: &mut App, handle: &SharedMemoryHandle, angle: f32) -> bool {
+    common::reset(app, handle);
+    app.update();
+    place_camera(app, angle);
+    common::check_alignment(app, handle);
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1);
+    gs_game.win_time.load(Ordering::Relaxed) != 0
+}
+
+#[test]
+fn spawned_doors_match_the_geometry_and_win_at_the_window_edges() {
+    let (mut app, handle) = common::headless_app("test_alignment_geometry");
+    let gs_ctrl = &handle.get().game_structure_control;
+    let target = 2;
+    gs_ctrl.target_door.store(target as u32, Ordering::Relaxed);
+    gs_ctrl.cosine_alignment_threshold.store(COSINE_ALIGNMENT_TO_WIN.to_bits(), Ordering::Relaxed);
+    let window = COSINE_ALIGNMENT_TO_WIN.acos();
+
+    for pyramid_type in PYRAMID_TYPES {
+        for start_orient in START_ORIENTS {
+            gs_ctrl.pyramid_type.store(pyramid_type as u32, Ordering::Relaxed);
+            gs_ctrl.start_orient.store(start_orient.to_bits(), Ordering::Relaxed);
+            common::reset(&mut app, &handle);
+            app.update();
+
+            let world = app.world_mut();
+            let doors: Vec<(usize, Vec3)> = world
+                .query::<(&Transform, &BaseDoor)>()
+                .iter(world)
+                .map(|(transform, door)| (door.door_index, transform.rotation * door.normal))
+                .collect();
+            assert_eq!(doors.len(), BASE_NR_SIDES);
+            for (index, normal) in doors {
+                let expected = door_normal(start_orient, index);
+                assert!(
+                    xz_alignment(normal, expected) > 1.0 - 1e-5,
+                    "{pyramid_type:?}, orient {start_orient}: door {index} normal {normal} vs {expected}"
+                );
+            }
+
+            // A degree inside the window wins through the game's check, a degree outside does not
+            let facing = door_angle(start_orient, target);
+            let inside = window - 1f32.to_radians();
+            let outside = window + 1f32.to_radians();
+            for side in [-1.0, 1.0] {
+                assert!(check_from(&mut app, &handle, facing + side * inside), "{pyramid_type:?}, orient {start_orient}");
+                assert!(!check_from(&mut app, &handle, facing + side * outside), "{pyramid_type:?}, orient {start_orient}");
+            }
+        }
+    }
+}
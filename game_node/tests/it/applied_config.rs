@@ -0,0 +1,43 @@
+//! Applied config: the game echoes what it actually showed, down to the decorations placed per face
+//! when a crowded face runs out of room, next to the config as requested in the controller region.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use shared::constants::pyramid_constants::DOOR_HOLE_RADIUS_FRACTION;
+
+fn spawned_per_face(app: &mut App) -> [u32; 3] {
+    let world = app.world_mut();
+    let mut counts = [0; 3];
+    for decoration in world.query::<&DecorationMarker>().iter(world) {
+        counts[decoration.face_index] += 1;
+    }
+    counts
+}
+
+#[test]
+fn placed_decorations_and_fallbacks_are_echoed() {
+    let (mut app, handle) = common::headless_app("test_applied_config");
+    let gs_ctrl = &handle.get().game_structure_control;
+    let gs_game = &handle.get().game_structure_game;
+    let placed = || -> [u32; 3] { std::array::from_fn(|face| gs_game.decorations_placed[face].load(Ordering::Relaxed)) };
+
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(placed(), spawned_per_face(&mut app));
+
+    // A crowded face places fewer than requested (each face is two triangles of `count`)
+    gs_ctrl.decorations_count[0].store(500, Ordering::Relaxed);
+    gs_ctrl.decorations_size[0].store(0.2f32.to_bits(), Ordering::Relaxed);
+    // Degenerate door geometry falls back to the defaults
+    gs_ctrl.door_hole_radius_fraction.store(2.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(placed(), spawned_per_face(&mut app));
+    assert!(placed()[0] < 2 * 500, "{:?}", placed());
+
+    let hole = |gs: &shared::SharedGameStructure| f32::from_bits(gs.door_hole_radius_fraction.load(Ordering::Relaxed));
+    assert_eq!(hole(gs_game), DOOR_HOLE_RADIUS_FRACTION, "applied");
+    assert_eq!(hole(gs_ctrl), 2.0, "requested stays as written");
+}
@@ -0,0 +1,100 @@
+//! Approach direction: the turning direction is detected from the recent yaw across the wrap, and a
+//! check with `required_approach_sign` wins only while the pyramid turns the required way, as does the
+//! shaping dwell.
+use crate::common;
+
+use bevy::time::TimeUpdateStrategy;
+use core::f32::consts::PI;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::utils::alignment::approach_sign;
+use shared::constants::game_constants::APPROACH_MIN_YAW;
+use shared::SharedMemoryHandle;
+
+#[test]
+fn direction_is_the_sign_of_the_net_turn() {
+    assert_eq!(approach_sign([0.0, 0.05, 0.1], APPROACH_MIN_YAW), 1);
+    assert_eq!(approach_sign([0.1, 0.05, 0.0], APPROACH_MIN_YAW), -1);
+    // Still, jitter within the minimum turn, or too short a history
+    assert_eq!(approach_sign([0.3; 5], APPROACH_MIN_YAW), 0);
+    assert_eq!(approach_sign([0.0, 0.004, -0.003, 0.002], APPROACH_MIN_YAW), 0);
+    assert_eq!(approach_sign([0.3], APPROACH_MIN_YAW), 0);
+    assert_eq!(approach_sign([], APPROACH_MIN_YAW), 0);
+    // A back-and-forth counts by its net turn
+    assert_eq!(approach_sign([0.0, 0.2, 0.1], APPROACH_MIN_YAW), 1);
+    // Turning through +-PI keeps its direction
+    assert_eq!(approach_sign([PI - 0.05, PI - 0.01, -PI + 0.03], APPROACH_MIN_YAW), 1);
+    assert_eq!(approach_sign([-PI + 0.03, PI - 0.01, PI - 0.05], APPROACH_MIN_YAW), -1);
+}
+
+/// Resets into a trial any alignment wins, requiring `sign`, turns with the `turn` flag held (none
+/// when `None`) and checks while still holding it. Returns (approach_sign, won, blocked)
+fn check_while_turning(name: &str, sign: i32, turn: Option<bool>) -> (i32, bool, bool) {
+    let (mut app, handle) = common::headless_app(name);
+    let gs_control = &handle.get().game_structure_control;
+    gs_control.required_approach_sign.store(sign, Ordering::Relaxed);
+    gs_control.cosine_alignment_threshold.store((-1.0f32).to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+
+    let commands = &handle.get().commands;
+    let flag = turn.map(|right| if right { &commands.rotate_right } else { &commands.rotate_left });
+    if let Some(flag) = flag {
+        flag.store(true, Ordering::Relaxed);
+    }
+    for _ in 0..3 {
+        app.update();
+    }
+    common::check_alignment(&mut app, &handle);
+    if let Some(flag) = flag {
+        flag.store(false, Ordering::Relaxed);
+    }
+    outcome(&handle)
+}
+
+fn outcome(handle: &SharedMemoryHandle) -> (i32, bool, bool) {
+    let gs_game = &handle.get().game_structure_game;
+    (
+        gs_game.approach_sign.load(Ordering::Relaxed),
+        gs_game.win_time.load(Ordering::Relaxed) != 0,
+        gs_game.win_blocked_by_approach.load(Ordering::Relaxed),
+    )
+}
+
+#[test]
+fn checks_win_only_turning_the_required_way() {
+    // rotate_right increases the yaw
+    assert_eq!(check_while_turning("test_approach_right", 1, Some(true)), (1, true, false));
+    assert_eq!(check_while_turning("test_approach_left", 1, Some(false)), (-1, false, true));
+    assert_eq!(check_while_turning("test_approach_left_required", -1, Some(false)), (-1, true, false));
+    // Still satisfies no requirement, and without one every direction wins
+    assert_eq!(check_while_turning("test_approach_still", -1, None), (0, false, true));
+    assert_eq!(check_while_turning("test_approach_none", 0, Some(false)), (-1, true, false));
+}
+
+#[test]
+fn shaping_dwell_wins_only_turning_the_required_way() {
+    let (mut app, handle) = common::headless_app("test_approach_dwell");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_control = &handle.get().game_structure_control;
+    let gs_game = &handle.get().game_structure_game;
+    gs_control.required_approach_sign.store(1, Ordering::Relaxed);
+    gs_control.shaping_dwell_secs.store(0.3f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+
+    // Held still on the target, long past the dwell: no direction, so no win
+    common::face_target(&mut app);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(outcome(&handle), (0, false, false));
+    assert_eq!(f32::from_bits(gs_game.shaping_dwell_elapsed.load(Ordering::Relaxed)), 0.0);
+
+    // Without the requirement the same dwell wins
+    gs_game.required_approach_sign.store(0, Ordering::Relaxed);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert!(outcome(&handle).1, "the dwell did not win without a required direction");
+}
@@ -0,0 +1,32 @@
+//! Backdrop wall: persistent, shown or hidden at every reset by the trial's `backdrop_enabled`.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::Backdrop;
+
+fn backdrop_visibility(app: &mut App) -> Visibility {
+    let world = app.world_mut();
+    *world.query_filtered::<&Visibility, With<Backdrop>>().single(world).expect("one backdrop")
+}
+
+#[test]
+fn backdrop_follows_the_trial_config() {
+    let (mut app, handle) = common::headless_app("test_backdrop");
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(backdrop_visibility(&mut app), Visibility::Inherited, "shown by default");
+
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.backdrop_enabled.store(false, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(backdrop_visibility(&mut app), Visibility::Hidden);
+    assert!(!handle.get().game_structure_game.backdrop_enabled.load(Ordering::Relaxed));
+
+    // Back on with the next trial, the same entity
+    gs_ctrl.backdrop_enabled.store(true, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(backdrop_visibility(&mut app), Visibility::Inherited);
+}
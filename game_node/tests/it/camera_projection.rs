@@ -0,0 +1,66 @@
+//! Camera projection per trial: the configured FOV (clamped to the supported range) and clip planes
+//! are applied on every reset and emitted as the active viewport, zero keeps the Bevy default, and
+//! the validator rejects out-of-range values.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicU32, Ordering};
+use game_node::utils::objects::PersistentCamera;
+use shared::constants::camera_3d_constants::CAMERA_3D_FOV_RANGE_DEGREES;
+use shared::{validate_camera_projection, SharedMemoryHandle};
+
+/// Active (FOV degrees, near, far) emitted after a reset with the configured `projection`
+fn play(app: &mut App, handle: &SharedMemoryHandle, projection: [f32; 3]) -> [f32; 3] {
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (slot, value) in [&gs_ctrl.camera_fov_degrees, &gs_ctrl.camera_near, &gs_ctrl.camera_far].into_iter().zip(projection) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    common::reset(app, handle);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    let load = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+    [load(&gs_game.active_fov_degrees), load(&gs_game.active_near), load(&gs_game.active_far)]
+}
+
+fn camera_projection(app: &mut App) -> PerspectiveProjection {
+    let world = app.world_mut();
+    match world.query_filtered::<&Projection, With<PersistentCamera>>().single(world).unwrap() {
+        Projection::Perspective(perspective) => perspective.clone(),
+        other => panic!("expected a perspective camera, got {other:?}"),
+    }
+}
+
+#[test]
+fn configured_projection_is_applied_and_emitted_on_every_reset() {
+    let (mut app, handle) = common::headless_app("test_camera_projection");
+    let default = PerspectiveProjection::default();
+    let emitted = play(&mut app, &handle, [0.0; 3]);
+    assert!((emitted[0] - default.fov.to_degrees()).abs() < 1e-4);
+    assert_eq!(emitted[1..], [default.near, default.far]);
+
+    let emitted = play(&mut app, &handle, [70.0, 0.5, 200.0]);
+    assert!((emitted[0] - 70.0).abs() < 1e-4);
+    assert_eq!(emitted[1..], [0.5, 200.0]);
+    let camera = camera_projection(&mut app);
+    assert!((camera.fov - 70.0_f32.to_radians()).abs() < 1e-6);
+    assert_eq!((camera.near, camera.far), (0.5, 200.0));
+
+    // Re-applied on the next reset
+    assert!((play(&mut app, &handle, [30.0, 0.0, 0.0])[0] - 30.0).abs() < 1e-4);
+    assert_eq!(camera_projection(&mut app).near, default.near);
+
+    // Values that bypass the validator are clamped, inverted clip planes fall back to the defaults
+    let emitted = play(&mut app, &handle, [170.0, 50.0, 10.0]);
+    assert!((emitted[0] - CAMERA_3D_FOV_RANGE_DEGREES[1]).abs() < 1e-4);
+    assert_eq!(emitted[1..], [default.near, default.far]);
+}
+
+#[test]
+fn out_of_range_projections_are_rejected() {
+    assert!(validate_camera_projection(0.0, 0.0, 0.0).is_ok());
+    assert!(validate_camera_projection(60.0, 0.1, 100.0).is_ok());
+    assert!(validate_camera_projection(CAMERA_3D_FOV_RANGE_DEGREES[0] - 1.0, 0.0, 0.0).is_err());
+    assert!(validate_camera_projection(CAMERA_3D_FOV_RANGE_DEGREES[1] + 1.0, 0.0, 0.0).is_err());
+    assert!(validate_camera_projection(60.0, 10.0, 5.0).is_err(), "near past far");
+    assert!(validate_camera_projection(60.0, -1.0, 0.0).is_err());
+}
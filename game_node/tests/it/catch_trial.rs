@@ -0,0 +1,51 @@
+//! Catch trials: no door or face is the target, so no alignment wins.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{PendingCheckAlignment, SharedMemResource};
+use game_node::utils::game_functions::apply_pending_check_alignment;
+use game_node::utils::objects::{BaseDoor, DoorWinEntities};
+use shared::create_shared_memory;
+
+/// Checks from every 30° around the pyramid, with the target door facing the camera at 0°.
+/// Returns whether any check won, and the attempts counted.
+fn check_all_around(name: &str, is_catch: bool) -> (bool, u32) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let shm = handle.get();
+    shm.game_structure_control.is_catch.store(is_catch, Ordering::Relaxed);
+    shm.game_structure_game.reset_all_fields(&shm.game_structure_control);
+
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(PendingCheckAlignment(false))
+        .init_resource::<DoorWinEntities>()
+        .add_systems(Update, apply_pending_check_alignment);
+    let camera = app.world_mut().spawn((Camera3d::default(), Transform::default())).id();
+    app.world_mut().spawn((Transform::default(), BaseDoor { door_index: 0, normal: Vec3::NEG_Z, is_open: false }));
+    // Let time advance so a win would store a non-zero win time
+    app.update();
+
+    let mut won = false;
+    for step in 0..12 {
+        let yaw = step as f32 * 30f32.to_radians();
+        *app.world_mut().get_mut::<Transform>(camera).unwrap() =
+            Transform::from_xyz(15.0 * yaw.sin(), 1.0, 15.0 * yaw.cos()).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y);
+        app.world_mut().resource_mut::<PendingCheckAlignment>().0 = true;
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        app.update();
+        won |= shm.game_structure_game.win_time.load(Ordering::Relaxed) != 0;
+    }
+    (won, shm.game_structure_game.attempts.load(Ordering::Relaxed))
+}
+
+#[test]
+fn no_alignment_wins_a_catch_trial() {
+    let (won, attempts) = check_all_around("test_catch_trial", true);
+    assert!(!won, "catch trial was won");
+    assert_eq!(attempts, 12, "every check still counts as an attempt");
+
+    // Same sweep on a regular trial wins when facing the door
+    let (won, _) = check_all_around("test_catch_trial_control", false);
+    assert!(won);
+}
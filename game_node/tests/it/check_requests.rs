@@ -0,0 +1,91 @@
+//! Check request counter: the game counts every check the controller requested through
+//! `check_requests` but never saw into `dropped_checks`, and a held flag is not a new request.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CheckRequests, CommandHandlerPlugin, PendingCheckAlignment, SharedMemResource};
+use shared::create_shared_memory;
+
+#[test]
+fn reconcile_counts_requests_beyond_the_seen_check() {
+    let mut requests = CheckRequests::default();
+    assert_eq!(requests.reconcile(1, true), 0);
+    // Held flag, no new request
+    assert_eq!(requests.reconcile(1, true), 0);
+    // Set and cleared before the frame
+    assert_eq!(requests.reconcile(2, false), 1);
+    // Three in one frame, one acted on
+    assert_eq!(requests.reconcile(5, true), 2);
+    // Counter wrapping around
+    let mut requests = CheckRequests::default();
+    requests.reconcile(u32::MAX, true);
+    assert_eq!(requests.reconcile(1, true), 1);
+}
+
+#[test]
+fn a_flag_seen_before_its_request_is_not_a_drop() {
+    let mut requests = CheckRequests::default();
+    // Flag read before the controller counted it, the count read on the next frame
+    assert_eq!(requests.reconcile(0, true), 0);
+    assert_eq!(requests.reconcile(1, false), 0);
+    // Likewise with the flag still up when the count lands
+    assert_eq!(requests.reconcile(1, false), 0);
+    assert_eq!(requests.reconcile(1, true), 0);
+    assert_eq!(requests.reconcile(2, true), 0);
+    // The credit is used up: a later request set and cleared unseen is dropped
+    assert_eq!(requests.reconcile(3, false), 1);
+    // A held flag is not an early check either
+    assert_eq!(requests.reconcile(4, true), 0);
+    assert_eq!(requests.reconcile(4, true), 0);
+    assert_eq!(requests.reconcile(5, false), 1);
+}
+
+#[test]
+fn dropped_checks_are_emitted() {
+    let handle = create_shared_memory("test_check_requests").expect("shared memory");
+    let commands = &handle.get().commands;
+    let dropped = || handle.get().game_structure_game.dropped_checks.load(Ordering::Relaxed);
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((MinimalPlugins, CommandHandlerPlugin));
+    app.update();
+
+    // A check the way the controller writes it: flag, then request
+    let request = |check: bool| {
+        commands.check_alignment.store(check, Ordering::Relaxed);
+        commands.check_requests.fetch_add(1, Ordering::Release);
+    };
+    request(true);
+    app.update();
+    assert!(app.world().resource::<PendingCheckAlignment>().0);
+    assert_eq!(dropped(), 0);
+
+    // Requested, then cleared by the next write before the game read it
+    request(true);
+    commands.check_alignment.store(false, Ordering::Relaxed);
+    app.update();
+    assert!(!app.world().resource::<PendingCheckAlignment>().0);
+    assert_eq!(dropped(), 1);
+
+    // Two requests in one frame act once
+    request(true);
+    request(true);
+    app.update();
+    assert!(app.world().resource::<PendingCheckAlignment>().0);
+    assert_eq!(dropped(), 2);
+
+    // A flag held over frames (or set by a controller that does not count) drops nothing
+    app.update();
+    app.update();
+    assert_eq!(dropped(), 2);
+
+    // The game reading the flag before the controller counted its request drops nothing either
+    commands.check_alignment.store(false, Ordering::Relaxed);
+    app.update();
+    commands.check_alignment.store(true, Ordering::Relaxed);
+    app.update();
+    assert!(app.world().resource::<PendingCheckAlignment>().0);
+    commands.check_requests.fetch_add(1, Ordering::Release);
+    app.update();
+    assert_eq!(dropped(), 2);
+}
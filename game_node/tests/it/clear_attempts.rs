@@ -0,0 +1,90 @@
+//! Clearing the attempts counter without resetting the trial, and rejecting checks made while blanked.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{GameEntity, PersistentCamera};
+use game_node::utils::systems_logic::BlankScreenState;
+
+#[test]
+fn clear_attempts_zeroes_counters_and_acks_once() {
+    let (mut app, handle) = common::headless_app("test_clear_attempts");
+    let shm = handle.get();
+    let gs_game = &shm.game_structure_game;
+    gs_game.attempts.store(3, Ordering::Relaxed);
+    gs_game.rejected_checks.store(2, Ordering::Relaxed);
+    let reset_ack = gs_game.reset_ack.load(Ordering::Acquire);
+
+    shm.commands.clear_attempts.store(true, Ordering::Relaxed);
+    app.update();
+    app.update();
+
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 0);
+    assert_eq!(gs_game.rejected_checks.load(Ordering::Relaxed), 0);
+    assert_eq!(gs_game.clear_attempts_ack.load(Ordering::Acquire), 1, "acknowledged exactly once");
+    assert!(!shm.commands.clear_attempts.load(Ordering::Relaxed), "consumed by the game");
+    assert_eq!(gs_game.reset_ack.load(Ordering::Acquire), reset_ack, "no trial reset");
+}
+
+#[test]
+fn clearing_leaves_the_stimulus_and_camera_alone() {
+    let (mut app, handle) = common::headless_app("test_clear_attempts_stimulus");
+    let shm = handle.get();
+    let gs_game = &shm.game_structure_game;
+    common::reset(&mut app, &handle);
+    app.update();
+
+    // Two missed checks from the side opposite the target
+    common::face_target(&mut app);
+    let world = app.world_mut();
+    let mut camera = world.query_filtered::<&mut Transform, With<PersistentCamera>>().single_mut(world).unwrap();
+    camera.rotate_around(Vec3::ZERO, Quat::from_rotation_y(std::f32::consts::PI));
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 2);
+    let before = scene(&mut app);
+
+    shm.commands.clear_attempts.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 0);
+    assert_eq!(scene(&mut app), before, "pyramid, decorations and camera untouched");
+}
+
+/// One check facing the target while the screen is blanked, with or without the suppression.
+/// Returns (attempts, rejected_checks, won)
+fn check_while_blanked(name: &str, suppress: bool) -> (u32, u32, bool) {
+    let (mut app, handle) = common::headless_app(name);
+    let shm = handle.get();
+    shm.game_structure_control.suppress_blanked_attempts.store(suppress, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    common::face_target(&mut app);
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = true;
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    let gs_game = &shm.game_structure_game;
+    (
+        gs_game.attempts.load(Ordering::Relaxed),
+        gs_game.rejected_checks.load(Ordering::Relaxed),
+        gs_game.win_time.load(Ordering::Relaxed) != 0,
+    )
+}
+
+#[test]
+fn check_while_blanked_is_rejected_when_suppressed() {
+    assert_eq!(check_while_blanked("test_blanked_check_suppressed", true), (0, 1, false), "rejected, not an attempt");
+    assert_eq!(check_while_blanked("test_blanked_check_counted", false), (1, 0, true), "counted as usual");
+}
+
+/// Transforms of the round's entities (pyramid, doors, decorations) and of the camera
+fn scene(app: &mut App) -> (Vec<(Entity, Transform)>, Transform) {
+    let world = app.world_mut();
+    let camera = *world.query_filtered::<&Transform, With<PersistentCamera>>().single(world).unwrap();
+    let mut entities: Vec<(Entity, Transform)> =
+        world.query_filtered::<(Entity, &Transform), With<GameEntity>>().iter(world).map(|(e, t)| (e, *t)).collect();
+    entities.sort_by_key(|(entity, _)| *entity);
+    (entities, camera)
+}
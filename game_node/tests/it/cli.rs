@@ -0,0 +1,65 @@
+//! Command line arguments of the game binary and the window they describe.
+use bevy::math::IVec2;
+use bevy::window::{Monitor, MonitorSelection, PresentMode, WindowMode};
+use clap::Parser;
+use game_node::cli::{monitor_order, parse_window_size, Cli};
+
+#[test]
+fn defaults_are_fullscreen_on_the_primary_monitor_with_vsync() {
+    let cli = Cli::try_parse_from(["game_node"]).unwrap();
+    assert_eq!(cli.shm_name, "monkey_game");
+    assert_eq!(cli.stimulus_archive, None);
+    let window = cli.window();
+    assert_eq!(window.mode, WindowMode::BorderlessFullscreen(MonitorSelection::Primary));
+    assert_eq!(window.present_mode, PresentMode::AutoVsync);
+    assert_eq!(window.title, "Monkey 3D Game");
+}
+
+#[test]
+fn arguments_feed_the_window() {
+    let cli = Cli::try_parse_from([
+        "game_node", "--windowed", "1280x720", "--monitor", "1", "--no-vsync", "--shm-name", "rig_b", "--title", "Rig B",
+        "--hidpi-scale", "1.0", "--stimulus-archive", "stimuli",
+    ])
+    .unwrap();
+    assert_eq!(cli.shm_name, "rig_b");
+    assert_eq!(cli.stimulus_archive.as_deref(), Some(std::path::Path::new("stimuli")));
+    let window = cli.window();
+    assert_eq!(window.mode, WindowMode::Windowed);
+    assert_eq!(window.present_mode, PresentMode::AutoNoVsync);
+    assert_eq!((window.resolution.physical_width(), window.resolution.physical_height()), (1280, 720));
+    assert_eq!(window.resolution.scale_factor(), 1.0);
+    assert_eq!(window.title, "Rig B");
+
+    // Fullscreen on a chosen monitor
+    let window = Cli::try_parse_from(["game_node", "--monitor", "2"]).unwrap().window();
+    assert_eq!(window.mode, WindowMode::BorderlessFullscreen(MonitorSelection::Index(2)));
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    assert_eq!(parse_window_size("1920X1080"), Ok((1920, 1080)));
+    for size in ["1920", "0x1080", "axb", "1920x"] {
+        assert!(parse_window_size(size).is_err(), "{size}");
+    }
+    assert!(Cli::try_parse_from(["game_node", "--hidpi-scale", "0"]).is_err());
+    assert!(Cli::try_parse_from(["game_node", "--monitor", "-1"]).is_err());
+}
+
+#[test]
+fn monitors_are_indexed_by_position_then_name() {
+    let monitor = |name: &str, x: i32, y: i32| Monitor {
+        name: Some(name.to_string()),
+        physical_height: 1080,
+        physical_width: 1920,
+        physical_position: IVec2::new(x, y),
+        refresh_rate_millihertz: Some(60_000),
+        scale_factor: 1.0,
+        video_modes: Vec::new(),
+    };
+    // As winit might enumerate them, the subject display first
+    let mut monitors = [monitor("DP-2", 1920, 0), monitor("HDMI-1", 0, 1080), monitor("DP-1", 0, 0), monitor("DP-3", 1920, 0)];
+    monitors.sort_by(monitor_order);
+    let names: Vec<_> = monitors.iter().map(|m| m.name.as_deref().unwrap()).collect();
+    assert_eq!(names, ["DP-1", "HDMI-1", "DP-2", "DP-3"]);
+}
@@ -0,0 +1,43 @@
+//! Config colors read as sRGB or as linear values depending on `color_space`.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::FaceMarker;
+use shared::ColorSpace;
+
+const GREY: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// Base color of face 0's material after a reset with the given color space
+fn face_color(app: &mut App, handle: &shared::SharedMemoryHandle, color_space: ColorSpace) -> Color {
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (c, value) in GREY.iter().enumerate() {
+        gs_ctrl.colors[c].store(value.to_bits(), Ordering::Relaxed);
+    }
+    gs_ctrl.color_space.store(color_space as u32, Ordering::Relaxed);
+    common::reset(app, handle);
+    // Seen released, so the next reset is a new one
+    app.update();
+
+    let world = app.world_mut();
+    let mut faces = world.query::<(&FaceMarker, &MeshMaterial3d<StandardMaterial>)>();
+    let material = faces
+        .iter(world)
+        .find(|(face, _)| face.face_index == 0)
+        .map(|(_, material)| material.0.clone())
+        .expect("face 0 spawned");
+    world.resource::<Assets<StandardMaterial>>().get(&material).unwrap().base_color
+}
+
+#[test]
+fn same_triple_differs_between_srgb_and_linear() {
+    let (mut app, handle) = common::headless_app("test_color_space");
+    let srgb = face_color(&mut app, &handle, ColorSpace::Srgb);
+    let linear = face_color(&mut app, &handle, ColorSpace::Linear);
+
+    assert_eq!(srgb, Color::srgba(0.5, 0.5, 0.5, 1.0));
+    assert_eq!(linear, Color::linear_rgba(0.5, 0.5, 0.5, 1.0));
+    // Read as sRGB, 0.5 is gamma encoded and emits about a fifth of full light
+    assert!(srgb.to_linear().red < 0.25);
+    assert_ne!(srgb.to_linear(), linear.to_linear());
+}
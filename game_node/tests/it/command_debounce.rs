@@ -0,0 +1,141 @@
+//! Trigger debounce: activations of check_alignment, reset and blank_screen inside their command's
+//! `debounce_frames` window are counted in `debounced_count` instead of acted on, a new trial
+//! closes every window but the reset's, and the default of 0 frames accepts everything.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicBool, Ordering};
+use game_node::command_handler::{
+    CommandDebounce, CommandHandlerPlugin, PendingBlankScreen, PendingCheckAlignment, PendingReset, PendingRotation,
+    SharedMemResource,
+};
+use shared::constants::game_constants::DEBOUNCE_FRAMES;
+use shared::{create_shared_memory, DebouncedCommand, SharedMemoryHandle};
+
+fn handler_app(name: &str, windows: [u32; 3]) -> (App, SharedMemoryHandle) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    for (slot, frames) in handle.get().game_structure_game.debounce_frames.iter().zip(windows) {
+        slot.store(frames, Ordering::Relaxed);
+    }
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((MinimalPlugins, CommandHandlerPlugin));
+    app.update();
+    (app, handle)
+}
+
+/// Feeds a flag sequence, one value per frame, and returns the frames it was acted on
+fn feed<R: Resource>(app: &mut App, flag: &AtomicBool, sequence: &[u8], acted: fn(&R) -> bool) -> Vec<usize> {
+    let mut frames = Vec::new();
+    for (frame, &set) in sequence.iter().enumerate() {
+        flag.store(set == 1, Ordering::Relaxed);
+        app.update();
+        if acted(app.world().resource::<R>()) {
+            frames.push(frame);
+        }
+    }
+    flag.store(false, Ordering::Relaxed);
+    frames
+}
+
+fn debounced(handle: &SharedMemoryHandle) -> u32 {
+    handle.get().game_structure_game.debounced_count.load(Ordering::Relaxed)
+}
+
+#[test]
+fn windows_count_activations_from_the_last_accepted_one() {
+    let mut debounce = CommandDebounce::default();
+    debounce.tick(0);
+    assert!(debounce.accept(DebouncedCommand::CheckAlignment, 2));
+    debounce.tick(0);
+    assert!(!debounce.accept(DebouncedCommand::CheckAlignment, 2));
+    // Rejections do not extend the window
+    debounce.tick(0);
+    assert!(debounce.accept(DebouncedCommand::CheckAlignment, 2));
+    // Commands have their own windows
+    assert!(debounce.accept(DebouncedCommand::BlankScreen, 2));
+    assert!(debounce.accept(DebouncedCommand::Reset, 2));
+    // A new trial closes every window but the reset's, which the reset that started it opened
+    debounce.tick(1);
+    assert!(debounce.accept(DebouncedCommand::CheckAlignment, 2));
+    assert!(debounce.accept(DebouncedCommand::BlankScreen, 2));
+    assert!(!debounce.accept(DebouncedCommand::Reset, 2));
+    debounce.tick(1);
+    assert!(!debounce.accept(DebouncedCommand::CheckAlignment, 2), "open again within the trial");
+    assert!(debounce.accept(DebouncedCommand::Reset, 2));
+}
+
+#[test]
+fn bouncing_triggers_act_once_per_window() {
+    let (mut app, handle) = handler_app("test_command_debounce", [4, 3, 2]);
+    let commands = &handle.get().commands;
+    let bounce = [1, 0, 1, 1, 0, 1, 0, 0, 1];
+
+    let checks = feed(&mut app, &commands.check_alignment, &bounce, |p: &PendingCheckAlignment| p.0);
+    assert_eq!(checks, vec![0, 5]);
+    assert_eq!(debounced(&handle), 3);
+
+    // A reset held over several frames is one activation
+    let resets = feed(&mut app, &commands.reset, &bounce, |p: &PendingReset| p.0);
+    assert_eq!(resets, vec![0, 5, 8]);
+    assert_eq!(debounced(&handle), 4);
+
+    let blanks = feed(&mut app, &commands.blank_screen, &bounce, |p: &PendingBlankScreen| p.0);
+    assert_eq!(blanks, vec![0, 2, 5, 8]);
+    assert_eq!(debounced(&handle), 5);
+
+    // Continuous commands are never debounced
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    for _ in 0..3 {
+        app.update();
+        assert!(app.world().resource::<PendingRotation>().0 < 0.0);
+    }
+    commands.rotate_left.store(false, Ordering::Relaxed);
+    assert_eq!(debounced(&handle), 5);
+}
+
+#[test]
+fn a_new_trial_closes_the_check_window() {
+    let (mut app, handle) = handler_app("test_command_debounce_trial", [10, 0, 0]);
+    let commands = &handle.get().commands;
+    assert_eq!(feed(&mut app, &commands.check_alignment, &[1, 0, 1], |p: &PendingCheckAlignment| p.0), vec![0]);
+
+    // The next trial's first check is accepted inside the old window
+    handle.get().game_structure_game.reset_ack.fetch_add(1, Ordering::Relaxed);
+    assert_eq!(feed(&mut app, &commands.check_alignment, &[1, 1], |p: &PendingCheckAlignment| p.0), vec![0]);
+    assert_eq!(debounced(&handle), 2);
+}
+
+#[test]
+fn a_bouncing_reset_resets_once_across_its_ack() {
+    let (mut app, handle) = common::headless_app("test_command_debounce_reset_ack");
+    let shm = handle.get();
+    shm.game_structure_control.debounce_frames[DebouncedCommand::Reset as usize].store(6, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    for _ in 0..6 {
+        app.update();
+    }
+
+    // The first contact resets and moves reset_ack on, the bounces after it land in the next trial
+    let reset_ack = shm.game_structure_game.reset_ack.load(Ordering::Acquire);
+    let acks = [1, 0, 1, 0, 1]
+        .iter()
+        .map(|&set| {
+            shm.commands.reset.store(set == 1, Ordering::Release);
+            app.update();
+            shm.game_structure_game.reset_ack.load(Ordering::Acquire)
+        })
+        .collect::<Vec<_>>();
+    shm.commands.reset.store(false, Ordering::Release);
+    assert_eq!(acks, [reset_ack + 1; 5], "one reset for the whole bounce");
+    assert_eq!(debounced(&handle), 2);
+}
+
+#[test]
+fn disabled_by_default() {
+    let (mut app, handle) = handler_app("test_command_debounce_default", DEBOUNCE_FRAMES);
+    let commands = &handle.get().commands;
+    let bounce = [1, 0, 1, 1, 0, 1];
+    assert_eq!(feed(&mut app, &commands.check_alignment, &bounce, |p: &PendingCheckAlignment| p.0), vec![0, 2, 3, 5]);
+    assert_eq!(feed(&mut app, &commands.reset, &bounce, |p: &PendingReset| p.0), vec![0, 2, 5]);
+    assert_eq!(debounced(&handle), 0);
+}
@@ -0,0 +1,65 @@
+//! Command readback: the game reports the commands it read set on each frame as a mask, counts the
+//! frames each was seen, and counts a consumed trigger once however long it stays written.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::json_api::state_json;
+use shared::{SeenCommand, SharedMemoryHandle, NO_PROBE_FACE, SEEN_COMMAND_NAMES};
+
+fn seen(handle: &SharedMemoryHandle) -> u32 {
+    handle.get().game_structure_game.commands_seen.load(Ordering::Relaxed)
+}
+
+fn count(handle: &SharedMemoryHandle, command: SeenCommand) -> u32 {
+    handle.get().game_structure_game.commands_seen_counts[command as usize].load(Ordering::Relaxed)
+}
+
+#[test]
+fn seen_mask_and_counts_follow_the_commands_read() {
+    let (mut app, handle) = common::headless_app("test_command_readback");
+    let commands = &handle.get().commands;
+    app.update();
+    assert_eq!(seen(&handle), 0);
+
+    // Held flags are seen on every frame they stay set
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    commands.zoom_out.store(true, Ordering::Relaxed);
+    for _ in 0..3 {
+        app.update();
+        assert_eq!(seen(&handle), SeenCommand::RotateLeft.bit() | SeenCommand::ZoomOut.bit());
+    }
+    assert_eq!(count(&handle, SeenCommand::RotateLeft), 3);
+    assert_eq!(count(&handle, SeenCommand::ZoomOut), 3);
+    assert_eq!(commands.raw_values()[SeenCommand::RotateLeft as usize], 1);
+    commands.rotate_left.store(false, Ordering::Relaxed);
+    commands.zoom_out.store(false, Ordering::Relaxed);
+    app.update();
+    assert_eq!(seen(&handle), 0);
+    assert_eq!(count(&handle, SeenCommand::RotateLeft), 3, "counts are kept");
+
+    // A consumed trigger is seen on one frame only, and reads back cleared
+    commands.clear_attempts.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(seen(&handle), SeenCommand::ClearAttempts.bit());
+    assert_eq!(commands.raw_values()[SeenCommand::ClearAttempts as usize], 0);
+    app.update();
+    assert_eq!(seen(&handle), 0);
+    assert_eq!(count(&handle, SeenCommand::ClearAttempts), 1);
+
+    // An idle u32 command is not seen, a written one is
+    assert_eq!(commands.raw_values()[SeenCommand::ProbeFace as usize], NO_PROBE_FACE);
+    commands.probe_face.store(0, Ordering::Release);
+    app.update();
+    assert_eq!(seen(&handle), SeenCommand::ProbeFace.bit());
+    assert_eq!(count(&handle, SeenCommand::ProbeFace), 1);
+
+    // The state JSON names the seen commands and counts them all
+    commands.zoom_in.store(true, Ordering::Relaxed);
+    app.update();
+    let state = state_json(handle.get());
+    assert_eq!(state["commands_seen"], serde_json::json!(["zoom_in"]));
+    let counts = state["commands_seen_counts"].as_object().unwrap();
+    assert_eq!(counts.len(), SEEN_COMMAND_NAMES.len());
+    assert_eq!(counts["rotate_left"], 3);
+    assert_eq!(counts["zoom_in"], 1);
+}
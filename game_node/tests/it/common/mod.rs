@@ -0,0 +1,68 @@
+//! Harness shared by the integration tests: the headless game (see headless.rs) over a test SHM
+//! region, stepped frame by frame with `app.update()`.
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::SharedMemResource;
+use game_node::headless::HeadlessPlugin;
+use game_node::utils::game_functions::{target_normal, AlignmentTargets};
+use game_node::utils::macros::set_log_dir;
+use game_node::utils::objects::PersistentCamera;
+use shared::{create_shared_memory, SharedMemoryHandle};
+
+/// Session log directory of the tests, in the temp directory rather than the working tree
+pub fn log_dir() -> std::path::PathBuf {
+    std::env::temp_dir().join("monkey_game_test_logs")
+}
+
+/// Empty app for a test, logging to `log_dir()`
+pub fn new_app() -> App {
+    set_log_dir(log_dir());
+    App::new()
+}
+
+/// Boots the headless game on its own SHM region `name` and runs the startup frame
+pub fn headless_app(name: &str) -> (App, SharedMemoryHandle) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let mut app = new_app();
+    app.insert_resource(SharedMemResource(handle.clone())).add_plugins(HeadlessPlugin);
+    app.update();
+    (app, handle)
+}
+
+/// Holds a reset for one frame, like a controller key press, so the controller region is applied
+pub fn reset(app: &mut App, handle: &SharedMemoryHandle) {
+    let commands = &handle.get().commands;
+    commands.reset.store(true, Ordering::Release);
+    app.update();
+    commands.reset.store(false, Ordering::Release);
+}
+
+/// Sends one alignment check
+pub fn check_alignment(app: &mut App, handle: &SharedMemoryHandle) {
+    let commands = &handle.get().commands;
+    commands.check_alignment.store(true, Ordering::Relaxed);
+    app.update();
+    commands.check_alignment.store(false, Ordering::Relaxed);
+}
+
+/// Moves the camera on its orbit (same radius and height) to look straight at the current target
+pub fn face_target(app: &mut App) {
+    let normal = app
+        .world_mut()
+        .run_system_once(|shm: Res<SharedMemResource>, targets: AlignmentTargets| {
+            target_normal(&shm.0.get().game_structure_game, &targets)
+        })
+        .expect("alignment targets query")
+        .expect("target spawned");
+    let inward = Vec3::new(normal.x, 0.0, normal.z).normalize();
+
+    let world = app.world_mut();
+    let mut camera = world.query_filtered::<&mut Transform, With<PersistentCamera>>();
+    let mut transform = camera.single_mut(world).expect("persistent camera");
+    let radius = transform.translation.xz().length();
+    let height = transform.translation.y;
+    *transform = Transform::from_translation(-inward * radius + Vec3::Y * height)
+        .looking_at(Vec3::new(0.0, height, 0.0), Vec3::Y);
+}
@@ -0,0 +1,57 @@
+//! `dump_config` appends the current trial's config as one JSON line, the `config_arguments` of the
+//! game region, which `apply_config_json` takes back, valid JSON even with non-finite values.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use game_node::utils::systems_logic::ConfigDumpPath;
+use serde_json::{Map, Value};
+use shared::json_api::{apply_config_json, config_arguments, config_json};
+use shared::SharedMemoryHandle;
+use std::path::Path;
+
+/// Requests a dump and returns the lines of the dump file after the next frame
+fn dump(app: &mut bevy::prelude::App, handle: &SharedMemoryHandle, path: &Path) -> Vec<String> {
+    handle.get().commands.dump_config.store(true, Ordering::Relaxed);
+    app.update();
+    handle.get().commands.dump_config.store(false, Ordering::Relaxed);
+    std::fs::read_to_string(path).unwrap_or_default().lines().map(str::to_string).collect()
+}
+
+fn parse(line: &str) -> Map<String, Value> {
+    serde_json::from_str(line).expect("every line is a JSON object")
+}
+
+#[test]
+fn dumps_append_the_applied_config() {
+    let path = std::env::temp_dir().join("test_config_dump.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let (mut app, handle) = common::headless_app("test_config_dump");
+    app.insert_resource(ConfigDumpPath(path.clone()));
+    let shm = handle.get();
+
+    shm.game_structure_control.target_door.store(2, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    let lines = dump(&mut app, &handle, &path);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0], Value::Object(config_arguments(&shm.game_structure_game)).to_string());
+    let config = parse(&lines[0]);
+    assert_eq!(config["target_door"], 2, "the applied config");
+    assert!(config.contains_key("decorations_size") && config.contains_key("door_hole_radius_fraction"), "every config field");
+
+    // The dumped line replays as the same config, but for the trial ID only kept as its hash
+    let replay = shared::create_shared_memory("test_config_dump_replay").expect("shared memory");
+    apply_config_json(replay.get(), &lines[0]).expect("the dump is accepted back");
+    let without_id = |gs| {
+        let mut config = config_json(gs);
+        config.remove("trial_id_hash");
+        config
+    };
+    assert_eq!(without_id(&replay.get().game_structure_control), without_id(&shm.game_structure_game));
+
+    // Non-finite values still give a valid line
+    shm.game_structure_game.cosine_alignment_threshold.store(f32::NAN.to_bits(), Ordering::Relaxed);
+    let lines = dump(&mut app, &handle, &path);
+    assert_eq!(lines.len(), 2, "appended");
+    assert_eq!(parse(&lines[1])["cosine_alignment_threshold"], Value::Null);
+}
@@ -0,0 +1,78 @@
+//! The `--config-only` handshake: a controller stages one trial, resets into it, waits for the trial
+//! to start and leaves. The game then keeps playing that trial, driven by whatever else writes the
+//! commands, without another reset.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::utils::objects::RotableComponent;
+use shared::{Phase, SharedMemoryHandle};
+use std::time::Instant;
+
+const BASE_RADIUS: f32 = 2.75;
+const TARGET_DOOR: u32 = 2;
+
+/// The controller side, as push_config does it: stage, reset with promotion, wait for the trial, release
+fn push_config(handle: SharedMemoryHandle) -> bool {
+    let shm = handle.get();
+    let (gs_game, commands) = (&shm.game_structure_game, &shm.commands);
+    let last_ack = gs_game.reset_ack.load(Ordering::Acquire);
+    let staged = &shm.game_structure_staged;
+    staged.reset_all_fields(&shm.game_structure_control);
+    staged.base_radius.store(BASE_RADIUS.to_bits(), Ordering::Relaxed);
+    staged.target_door.store(TARGET_DOOR, Ordering::Relaxed);
+    commands.promote_staged.store(true, Ordering::Release);
+    commands.reset.store(true, Ordering::Release);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let started = loop {
+        if gs_game.reset_ack.load(Ordering::Acquire) != last_ack {
+            break true;
+        }
+        if Instant::now() >= deadline {
+            break false;
+        }
+        std::thread::yield_now();
+    };
+    commands.reset.store(false, Ordering::Release);
+    started
+}
+
+#[test]
+fn pushed_trial_keeps_playing_after_the_controller_leaves() {
+    let (mut app, handle) = common::headless_app("test_config_only");
+    let gs_game = &handle.get().game_structure_game;
+    let first_ack = gs_game.reset_ack.load(Ordering::Acquire);
+
+    let controller = {
+        let handle = handle.clone();
+        std::thread::spawn(move || push_config(handle))
+    };
+    while !controller.is_finished() {
+        app.update();
+    }
+    assert!(controller.join().unwrap(), "the trial started before the timeout");
+
+    let ack = gs_game.reset_ack.load(Ordering::Acquire);
+    assert_ne!(ack, first_ack);
+    assert_eq!(f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed)), BASE_RADIUS);
+    assert_eq!(gs_game.target_door.load(Ordering::Relaxed), TARGET_DOOR);
+
+    // Another input source takes over: the trial runs on, with no further reset
+    let commands = &handle.get().commands;
+    let yaw = |app: &mut App| {
+        let world = app.world_mut();
+        world.query_filtered::<&Transform, With<RotableComponent>>().iter(world).next().unwrap().rotation
+    };
+    let before = yaw(&mut app);
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    for _ in 0..10 {
+        app.update();
+    }
+    commands.rotate_left.store(false, Ordering::Relaxed);
+    assert_ne!(yaw(&mut app), before);
+    assert_eq!(gs_game.reset_ack.load(Ordering::Acquire), ack);
+    assert_eq!(gs_game.phase.load(Ordering::Acquire), Phase::Playing as u32);
+    assert_eq!(f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed)), BASE_RADIUS);
+}
@@ -0,0 +1,70 @@
+//! Mirrored control mappings: sign of the yaw / radius change for a given command.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, SharedMemResource};
+use game_node::utils::camera::{apply_pending_rotation, apply_pending_zoom};
+use game_node::utils::objects::RotableComponent;
+use shared::create_shared_memory;
+
+/// Runs one frame with a single command held and returns the (yaw, camera radius) change.
+fn run_command(name: &str, mirror_rotation: bool, mirror_zoom: bool, command: &str) -> (f32, f32) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let shm = handle.get();
+
+    // Mirroring is latched into the game region at reset
+    shm.game_structure_control.control_mirror_rotation.store(mirror_rotation, Ordering::Relaxed);
+    shm.game_structure_control.control_mirror_zoom.store(mirror_zoom, Ordering::Relaxed);
+    shm.game_structure_game.reset_all_fields(&shm.game_structure_control);
+
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins(CommandHandlerPlugin)
+        .add_systems(Update, (apply_pending_rotation, apply_pending_zoom));
+    let pyramid = app.world_mut().spawn((Transform::default(), RotableComponent)).id();
+    let camera = app
+        .world_mut()
+        .spawn((Camera3d::default(), Transform::from_xyz(0.0, 1.0, 15.0).looking_at(Vec3::ZERO, Vec3::Y)))
+        .id();
+
+    let cmd = &shm.commands;
+    match command {
+        "rotate_left" => cmd.rotate_left.store(true, Ordering::Relaxed),
+        "rotate_right" => cmd.rotate_right.store(true, Ordering::Relaxed),
+        "zoom_in" => cmd.zoom_in.store(true, Ordering::Relaxed),
+        "zoom_out" => cmd.zoom_out.store(true, Ordering::Relaxed),
+        _ => unreachable!(),
+    }
+    app.update();
+
+    let yaw = app.world().get::<Transform>(pyramid).unwrap().rotation.to_euler(EulerRot::YXZ).0;
+    let radius = app.world().get::<Transform>(camera).unwrap().translation.xz().length();
+    (yaw, radius - 15.0)
+}
+
+#[test]
+fn rotation_sign_under_both_mappings() {
+    let (yaw, _) = run_command("test_mirror_rot_left", false, false, "rotate_left");
+    assert!(yaw < 0.0, "normal left should decrease yaw, got {yaw}");
+    let (yaw, _) = run_command("test_mirror_rot_right", false, false, "rotate_right");
+    assert!(yaw > 0.0, "normal right should increase yaw, got {yaw}");
+
+    let (yaw, _) = run_command("test_mirror_rot_left_m", true, false, "rotate_left");
+    assert!(yaw > 0.0, "mirrored left should increase yaw, got {yaw}");
+    let (yaw, _) = run_command("test_mirror_rot_right_m", true, false, "rotate_right");
+    assert!(yaw < 0.0, "mirrored right should decrease yaw, got {yaw}");
+}
+
+#[test]
+fn zoom_sign_under_both_mappings() {
+    let (_, dr) = run_command("test_mirror_zoom_in", false, false, "zoom_in");
+    assert!(dr < 0.0, "normal zoom in should reduce the radius, got {dr}");
+
+    let (_, dr) = run_command("test_mirror_zoom_in_m", false, true, "zoom_in");
+    assert!(dr > 0.0, "mirrored zoom in should increase the radius, got {dr}");
+
+    // Rotation mirroring leaves zoom untouched
+    let (_, dr) = run_command("test_mirror_zoom_in_r", true, false, "zoom_in");
+    assert!(dr < 0.0);
+}
@@ -0,0 +1,66 @@
+//! Controller heartbeat watchdog: a controller that goes quiet while holding a rotate flag stops the
+//! camera after the timeout, is reported lost, and is back at its next heartbeat.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::PendingRotation;
+use game_node::utils::systems_logic::BlankScreenState;
+
+fn rotation(app: &App) -> f32 {
+    app.world().resource::<PendingRotation>().0
+}
+
+#[test]
+fn quiet_controller_stops_the_camera() {
+    let (mut app, handle) = common::headless_app("test_controller_watchdog");
+    let commands = &handle.get().commands;
+    let gs_game = &handle.get().game_structure_game;
+    commands.controller_timeout_frames.store(3, Ordering::Relaxed);
+    commands.controller_lost_blank.store(true, Ordering::Relaxed);
+
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    for _ in 0..5 {
+        commands.heartbeat.fetch_add(1, Ordering::Relaxed);
+        app.update();
+        assert_ne!(rotation(&app), 0.0, "rotates while the heartbeat moves");
+    }
+    assert!(!gs_game.controller_lost.load(Ordering::Relaxed));
+
+    // Quiet: keeps rotating until the timeout, then stops with the flag cleared
+    for _ in 0..2 {
+        app.update();
+        assert_ne!(rotation(&app), 0.0);
+    }
+    app.update();
+    assert_eq!(rotation(&app), 0.0);
+    assert!(!commands.rotate_left.load(Ordering::Relaxed), "level flag cleared in shared memory");
+    assert!(gs_game.controller_lost.load(Ordering::Relaxed));
+    assert_eq!(gs_game.controller_outages.load(Ordering::Relaxed), 1);
+    app.update();
+    assert!(app.world().resource::<BlankScreenState>().is_active, "blanked on request");
+
+    // A stale write of the flag does not move the camera while still lost
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(rotation(&app), 0.0);
+    assert_eq!(gs_game.controller_outages.load(Ordering::Relaxed), 1, "one outage");
+
+    commands.heartbeat.fetch_add(1, Ordering::Relaxed);
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    app.update();
+    assert!(!gs_game.controller_lost.load(Ordering::Relaxed));
+    assert_ne!(rotation(&app), 0.0, "moves again once the controller is back");
+}
+
+#[test]
+fn disabled_by_default() {
+    let (mut app, handle) = common::headless_app("test_controller_watchdog_disabled");
+    let commands = &handle.get().commands;
+    commands.rotate_left.store(true, Ordering::Relaxed);
+    for _ in 0..200 {
+        app.update();
+    }
+    assert_ne!(rotation(&app), 0.0);
+    assert!(!handle.get().game_structure_game.controller_lost.load(Ordering::Relaxed));
+}
@@ -0,0 +1,59 @@
+//! Decoration color jitter: each decoration's color is offset from its face's by up to the jitter,
+//! the same for a given seed, without moving any decoration; 0 keeps one color per face.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use shared::SharedMemoryHandle;
+
+const RED: [f32; 4] = [0.6, 0.2, 0.2, 1.0];
+
+/// Decoration (face, position, color), in face and position order
+fn decorations(app: &mut App) -> Vec<(usize, [f32; 3], [f32; 4])> {
+    let world = app.world_mut();
+    let mut decorations: Vec<_> = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(marker, transform)| (marker.face_index, transform.translation.to_array(), marker.color))
+        .collect();
+    decorations.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+    decorations
+}
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, jitter: f32) -> Vec<(usize, [f32; 3], [f32; 4])> {
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (slot, value) in gs_ctrl.decoration_colors.iter().zip([RED; 3].as_flattened()) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    gs_ctrl.decoration_color_jitter.store(jitter.to_bits(), Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    decorations(app)
+}
+
+#[test]
+fn colors_vary_within_the_jitter_and_repeat_per_seed() {
+    let (mut app, handle) = common::headless_app("test_decoration_color_jitter");
+    let uniform = play(&mut app, &handle, 0.0);
+    assert!(uniform.iter().all(|d| d.2 == RED), "one color per face without jitter");
+
+    let jittered = play(&mut app, &handle, 0.1);
+    let positions = |decorations: &[(usize, [f32; 3], [f32; 4])]| decorations.iter().map(|d| (d.0, d.1)).collect::<Vec<_>>();
+    assert_eq!(positions(&jittered), positions(&uniform), "the layout does not move");
+    for (_, _, color) in &jittered {
+        assert!(color.iter().zip(RED).take(3).all(|(c, base)| (c - base).abs() <= 0.1 + 1e-6), "{color:?}");
+        assert_eq!(color[3], 1.0, "alpha is kept");
+    }
+    let distinct: std::collections::HashSet<_> = jittered.iter().map(|d| d.2.map(f32::to_bits)).collect();
+    assert!(distinct.len() > jittered.len() / 2, "colors vary within a face");
+
+    // Same seeds, same colors; the echoed face color stays the base one
+    assert_eq!(play(&mut app, &handle, 0.1), jittered);
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(f32::from_bits(gs_game.decoration_colors[0].load(Ordering::Relaxed)), RED[0]);
+
+    // Out of range falls back to no jitter, echoed
+    assert_eq!(play(&mut app, &handle, 1.5), uniform);
+    assert_eq!(f32::from_bits(gs_game.decoration_color_jitter.load(Ordering::Relaxed)), 0.0);
+}
@@ -0,0 +1,83 @@
+//! Configured decoration colors: a face with a color shows it on every decoration, a face without
+//! one keeps the drawn color, the applied config reports the colors shown, and the layout is the
+//! same either way.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use shared::{SharedGameStructure, SharedMemoryHandle};
+
+const RED: [f32; 4] = [0.9, 0.1, 0.1, 1.0];
+const BLUE: [f32; 4] = [0.1, 0.2, 0.8, 1.0];
+const DRAWN: [f32; 4] = [0.0; 4];
+
+/// Decoration (face, position, color), in face and position order
+fn decorations(app: &mut App) -> Vec<(usize, [f32; 3], [f32; 4])> {
+    let world = app.world_mut();
+    let mut decorations: Vec<_> = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(marker, transform)| (marker.face_index, transform.translation.to_array(), marker.color))
+        .collect();
+    decorations.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+    decorations
+}
+
+fn decoration_colors(gs: &SharedGameStructure) -> [[f32; 4]; 3] {
+    std::array::from_fn(|face| std::array::from_fn(|c| f32::from_bits(gs.decoration_colors[face * 4 + c].load(Ordering::Relaxed))))
+}
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, colors: [[f32; 4]; 3]) -> Vec<(usize, [f32; 3], [f32; 4])> {
+    for (slot, value) in handle.get().game_structure_control.decoration_colors.iter().zip(colors.as_flattened()) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    common::reset(app, handle);
+    app.update();
+    decorations(app)
+}
+
+fn assert_close(a: [f32; 4], b: [f32; 4]) {
+    assert!(a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5), "{a:?} != {b:?}");
+}
+
+#[test]
+fn configured_faces_show_their_color_and_the_rest_report_the_drawn_one() {
+    let (mut app, handle) = common::headless_app("test_decoration_colors");
+    let decorations = play(&mut app, &handle, [RED, DRAWN, BLUE]);
+    let applied = decoration_colors(&handle.get().game_structure_game);
+
+    for (face, reported) in applied.into_iter().enumerate() {
+        let colors: Vec<_> = decorations.iter().filter(|d| d.0 == face).map(|d| d.2).collect();
+        assert!(!colors.is_empty(), "face {face} undecorated");
+        assert!(colors.iter().all(|color| *color == colors[0]), "face {face} has one color");
+        assert_close(reported, colors[0]);
+    }
+    assert_close(applied[0], RED);
+    assert_close(applied[2], BLUE);
+    assert_eq!(applied[1][3], 1.0, "drawn color reported opaque");
+    assert_eq!(decoration_colors(&handle.get().game_structure_control)[1], DRAWN, "requested stays as written");
+}
+
+#[test]
+fn colors_leave_the_layout_unchanged() {
+    let (mut app, handle) = common::headless_app("test_decoration_colors_layout");
+    let positions = |decorations: &[(usize, [f32; 3], [f32; 4])]| decorations.iter().map(|d| (d.0, d.1)).collect::<Vec<_>>();
+    let drawn = play(&mut app, &handle, [DRAWN; 3]);
+    assert_eq!(positions(&play(&mut app, &handle, [RED, BLUE, RED])), positions(&drawn));
+
+    // The reported colors, given back as the config, replay the drawn round
+    let reported = decoration_colors(&handle.get().game_structure_game);
+    play(&mut app, &handle, [DRAWN; 3]);
+    let reported_drawn = decoration_colors(&handle.get().game_structure_game);
+    assert_ne!(reported, reported_drawn);
+    assert_eq!(play(&mut app, &handle, reported_drawn), drawn);
+}
+
+#[test]
+fn out_of_range_channels_fall_back_to_drawn_colors() {
+    let (mut app, handle) = common::headless_app("test_decoration_colors_invalid");
+    let drawn = play(&mut app, &handle, [DRAWN; 3]);
+    let invalid = play(&mut app, &handle, [[1.5, 0.0, 0.0, 1.0], RED, RED]);
+    assert_eq!(invalid, drawn);
+}
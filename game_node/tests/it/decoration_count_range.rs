@@ -0,0 +1,42 @@
+//! Per-face decoration count ranges, drawn from the face seed at reset.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use game_node::utils::pyramid::face_decoration_count;
+
+#[test]
+fn count_is_seeded_and_within_range() {
+    assert_eq!(face_decoration_count(7, 12, [0, 0]), 12, "disabled range keeps the fixed count");
+    for seed in 0..200 {
+        let count = face_decoration_count(seed, 12, [3, 9]);
+        assert!((3..=9).contains(&count), "seed {seed} drew {count}");
+        assert_eq!(count, face_decoration_count(seed, 12, [3, 9]), "same seed, same count");
+    }
+    let counts: std::collections::HashSet<u32> = (0..200).map(|seed| face_decoration_count(seed, 0, [3, 9])).collect();
+    assert_eq!(counts.len(), 7, "the whole range is used");
+}
+
+#[test]
+fn each_face_draws_from_its_own_range() {
+    let (mut app, handle) = common::headless_app("test_decoration_count_range");
+    let shm = handle.get();
+    let ranges = [[0, 0], [5, 5], [10, 40]];
+    for (face, [min, max]) in ranges.iter().enumerate() {
+        shm.game_structure_control.decorations_count_range[face * 2].store(*min, Ordering::Relaxed);
+        shm.game_structure_control.decorations_count_range[face * 2 + 1].store(*max, Ordering::Relaxed);
+    }
+    let fixed = shm.game_structure_control.decorations_count[0].load(Ordering::Relaxed);
+
+    common::reset(&mut app, &handle);
+    let counts = || -> Vec<u32> {
+        shm.game_structure_game.decorations_count.iter().map(|c| c.load(Ordering::Relaxed)).collect()
+    };
+    let first = counts();
+    assert_eq!(first[0], fixed);
+    assert_eq!(first[1], 5);
+    assert!((10..=40).contains(&first[2]));
+
+    // Same seeds, same draw
+    common::reset(&mut app, &handle);
+    assert_eq!(counts(), first);
+}
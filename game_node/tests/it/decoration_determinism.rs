@@ -0,0 +1,52 @@
+//! Decorations depend on the trial config only: the same config spawns bit-identical decorations
+//! whatever trials ran before it in the session.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use shared::{PyramidType, SharedMemoryHandle};
+
+/// (face, translation bits) of every decoration, sorted
+fn decoration_positions(app: &mut App) -> Vec<(usize, [u32; 3])> {
+    let world = app.world_mut();
+    let mut positions: Vec<(usize, [u32; 3])> = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(decoration, transform)| (decoration.face_index, transform.translation.to_array().map(f32::to_bits)))
+        .collect();
+    positions.sort_unstable();
+    positions
+}
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, seeds: [u64; 3], pyramid_type: PyramidType) -> Vec<(usize, [u32; 3])> {
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (slot, seed) in gs_ctrl.decoration_seeds.iter().zip(seeds) {
+        slot.store(seed, Ordering::Relaxed);
+    }
+    gs_ctrl.pyramid_type.store(pyramid_type as u32, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    decoration_positions(app)
+}
+
+#[test]
+fn same_config_spawns_the_same_decorations_in_any_session() {
+    let seeds = [11, 22, 33];
+    let (mut fresh, fresh_handle) = common::headless_app("test_decoration_determinism_fresh");
+    let expected = play(&mut fresh, &fresh_handle, seeds, PyramidType::Type1);
+    assert!(!expected.is_empty(), "decorations spawned");
+
+    // Another session with other trials first, including other types and counts
+    let (mut app, handle) = common::headless_app("test_decoration_determinism_history");
+    let gs_ctrl = &handle.get().game_structure_control;
+    assert_ne!(play(&mut app, &handle, [1, 2, 3], PyramidType::Type1), expected, "placements follow the seeds");
+    play(&mut app, &handle, [1, 2, 3], PyramidType::Type3);
+    gs_ctrl.decorations_count[0].store(2, Ordering::Relaxed);
+    play(&mut app, &handle, [4, 5, 6], PyramidType::Type2);
+    gs_ctrl.decorations_count[0].store(fresh_handle.get().game_structure_control.decorations_count[0].load(Ordering::Relaxed), Ordering::Relaxed);
+
+    assert_eq!(play(&mut app, &handle, seeds, PyramidType::Type1), expected);
+    // And again after itself
+    assert_eq!(play(&mut app, &handle, seeds, PyramidType::Type1), expected);
+}
@@ -0,0 +1,121 @@
+//! Decoration motion: the pulse follows its size formula from each decoration's phase, the phases
+//! come from the face seeds alone, and the motion clock stops while paused or blanked.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::game_functions::decoration_pose;
+use game_node::utils::objects::{AnimatedDecoration, DecorationMarker};
+use game_node::utils::systems_logic::BlankScreenState;
+use shared::{DecorationMotion, SharedMemoryHandle};
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+fn motion_secs(handle: &SharedMemoryHandle) -> f32 {
+    f32::from_bits(handle.get().game_structure_game.decoration_motion_secs.load(Ordering::Relaxed))
+}
+
+/// Decorations as (face, phase, scale), in face and phase order
+fn decorations(app: &mut App) -> Vec<(usize, f32, f32)> {
+    let world = app.world_mut();
+    let mut decorations: Vec<_> = world
+        .query::<(&DecorationMarker, &AnimatedDecoration, &Transform)>()
+        .iter(world)
+        .map(|(marker, animated, transform)| (marker.face_index, animated.phase, transform.scale.x))
+        .collect();
+    decorations.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+    decorations
+}
+
+fn pulse_app(name: &str) -> (App, SharedMemoryHandle) {
+    let (mut app, handle) = common::headless_app(name);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.decoration_motion.store(DecorationMotion::Pulse as u32, Ordering::Relaxed);
+    gs_ctrl.decoration_pulse_amplitude.store(0.3f32.to_bits(), Ordering::Relaxed);
+    gs_ctrl.decoration_pulse_hz.store(0.7f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    (app, handle)
+}
+
+#[test]
+fn poses_follow_the_motion() {
+    assert_eq!(decoration_pose(DecorationMotion::None, 2.0, 0.3, 1.0, 0.25, 3.0), (0.0, 1.0));
+    assert_eq!(decoration_pose(DecorationMotion::Spin, 0.0, 0.3, 1.0, 0.25, 3.0), (0.0, 1.0));
+    let (angle, scale) = decoration_pose(DecorationMotion::Spin, 2.0, 0.3, 1.0, 0.25, 3.0);
+    assert!((angle - (TAU * 0.25 + 6.0)).abs() < 1e-5 && scale == 1.0);
+    let (angle, scale) = decoration_pose(DecorationMotion::Pulse, 2.0, 0.3, 1.0, 0.25, 3.0);
+    assert!(angle == 0.0 && (scale - 1.3).abs() < 1e-5, "peak of the pulse, got {scale}");
+}
+
+#[test]
+fn decorations_pulse_from_their_phase() {
+    let (mut app, handle) = pulse_app("test_decoration_pulse");
+    for _ in 0..3 {
+        app.update();
+    }
+    let secs = motion_secs(&handle);
+    assert!(secs > 0.0);
+    let decorations = decorations(&mut app);
+    assert!(!decorations.is_empty());
+    for (_, phase, scale) in decorations {
+        let expected = 1.0 + 0.3 * (TAU * (0.7 * secs + phase)).sin();
+        assert!((scale - expected).abs() < 1e-4, "scale {scale} instead of {expected}");
+    }
+}
+
+#[test]
+fn phases_follow_the_seeds() {
+    let (mut app, handle) = pulse_app("test_decoration_phases");
+    app.update();
+    let phases: Vec<_> = decorations(&mut app).iter().map(|&(face, phase, _)| (face, phase)).collect();
+    assert!(phases.iter().all(|&(_, phase)| (0.0..1.0).contains(&phase)));
+    assert!(phases.windows(2).any(|pair| pair[0].1 != pair[1].1), "decorations share one phase");
+
+    // Same seeds, same phases
+    common::reset(&mut app, &handle);
+    app.update();
+    let replayed: Vec<_> = decorations(&mut app).iter().map(|&(face, phase, _)| (face, phase)).collect();
+    assert_eq!(phases, replayed);
+
+    // Other seeds, other phases
+    for seed in &handle.get().game_structure_control.decoration_seeds {
+        seed.fetch_add(1, Ordering::Relaxed);
+    }
+    common::reset(&mut app, &handle);
+    app.update();
+    let reseeded: Vec<_> = decorations(&mut app).iter().map(|&(face, phase, _)| (face, phase)).collect();
+    assert_ne!(phases, reseeded);
+}
+
+#[test]
+fn motion_stops_while_paused_or_blanked() {
+    let (mut app, handle) = pulse_app("test_decoration_motion_pause");
+    app.update();
+    let commands = &handle.get().commands;
+
+    commands.stop_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    let (secs, scales) = (motion_secs(&handle), decorations(&mut app));
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(motion_secs(&handle), secs, "motion clock ran while paused");
+    assert_eq!(decorations(&mut app), scales);
+    commands.stop_rendering.store(false, Ordering::Relaxed);
+    commands.resume_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    commands.resume_rendering.store(false, Ordering::Relaxed);
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = true;
+    let secs = motion_secs(&handle);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(motion_secs(&handle), secs, "motion clock ran while blanked");
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = false;
+    app.update();
+    assert!(motion_secs(&handle) > secs);
+}
@@ -0,0 +1,84 @@
+//! Decoration color probe: a face's decorations take the probe color for the probe duration, then
+//! get their own colors back, also when a new probe replaces a running one.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::utils::objects::DecorationMarker;
+use shared::SharedMemoryHandle;
+
+const PROBE: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+
+/// (face, color) of every decoration
+fn decoration_colors(app: &mut App) -> Vec<(usize, Color)> {
+    let world = app.world_mut();
+    let handles: Vec<(usize, Handle<StandardMaterial>)> = world
+        .query::<(&DecorationMarker, &MeshMaterial3d<StandardMaterial>)>()
+        .iter(world)
+        .map(|(decoration, material)| (decoration.face_index, material.0.clone()))
+        .collect();
+    let materials = world.resource::<Assets<StandardMaterial>>();
+    handles.iter().map(|(face, handle)| (*face, materials.get(handle).unwrap().base_color)).collect()
+}
+
+fn probe(app: &mut App, handle: &SharedMemoryHandle, face: u32, secs: f32) {
+    let commands = &handle.get().commands;
+    for (slot, value) in commands.probe_color.iter().zip(PROBE) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    commands.probe_duration_secs.store(secs.to_bits(), Ordering::Relaxed);
+    commands.probe_face.store(face, Ordering::Release);
+    app.update();
+}
+
+#[test]
+fn probe_recolors_one_face_then_reverts() {
+    let (mut app, handle) = common::headless_app("test_decoration_probe");
+    common::reset(&mut app, &handle);
+    app.update();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(50)));
+    let original = decoration_colors(&mut app);
+    assert!(original.iter().any(|(face, _)| *face == 1), "face 1 has decorations");
+
+    probe(&mut app, &handle, 1, 0.2);
+    assert_eq!(handle.get().commands.probe_face.load(Ordering::Relaxed), shared::NO_PROBE_FACE, "swapped back once read");
+    let probed = decoration_colors(&mut app);
+    for ((face, before), (_, now)) in original.iter().zip(&probed) {
+        if *face == 1 {
+            assert_eq!(*now, Color::srgba(PROBE[0], PROBE[1], PROBE[2], PROBE[3]));
+        } else {
+            assert_eq!(now, before, "other faces keep their colors");
+        }
+    }
+
+    // Still on before the duration, reverted after it
+    app.update();
+    assert_eq!(decoration_colors(&mut app), probed);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(decoration_colors(&mut app), original);
+}
+
+#[test]
+fn new_probe_restores_the_running_one_first() {
+    let (mut app, handle) = common::headless_app("test_decoration_probe_replace");
+    common::reset(&mut app, &handle);
+    app.update();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(50)));
+    let original = decoration_colors(&mut app);
+
+    probe(&mut app, &handle, 0, 1.0);
+    probe(&mut app, &handle, 2, 0.1);
+    let probed = decoration_colors(&mut app);
+    for ((face, before), (_, now)) in original.iter().zip(&probed) {
+        assert_eq!(*face == 2, now != before, "only face 2 probed now");
+    }
+
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(decoration_colors(&mut app), original, "face 0 was not left with the probe color");
+}
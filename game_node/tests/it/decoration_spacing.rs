@@ -0,0 +1,55 @@
+//! Decoration spacing multiplier: 1 keeps the default packing, looser packing places fewer
+//! decorations on a crowded face, and an invalid value falls back to the default.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use shared::constants::pyramid_constants::PYRAMID_DECORATIONS_SPACING;
+use shared::SharedMemoryHandle;
+
+/// Decorations spawned per face
+fn placed(app: &mut App) -> [usize; 3] {
+    let world = app.world_mut();
+    let mut counts = [0; 3];
+    for decoration in world.query::<&DecorationMarker>().iter(world) {
+        counts[decoration.face_index] += 1;
+    }
+    counts
+}
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, spacing: f32) -> [usize; 3] {
+    handle.get().game_structure_control.decoration_spacing.store(spacing.to_bits(), Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    placed(app)
+}
+
+#[test]
+fn spacing_scales_the_packing() {
+    let (mut app, handle) = common::headless_app("test_decoration_spacing");
+    let gs_ctrl = &handle.get().game_structure_control;
+    // Crowded enough that the default packing already runs out of room
+    for (count, size) in gs_ctrl.decorations_count.iter().zip(&gs_ctrl.decorations_size) {
+        count.store(200, Ordering::Relaxed);
+        size.store(0.15f32.to_bits(), Ordering::Relaxed);
+    }
+
+    let default = play(&mut app, &handle, PYRAMID_DECORATIONS_SPACING);
+    let tight = play(&mut app, &handle, 0.5);
+    let loose = play(&mut app, &handle, 2.0);
+    for face in 0..3 {
+        assert!(tight[face] > default[face], "face {face}: {tight:?} vs {default:?}");
+        assert!(loose[face] < default[face], "face {face}: {loose:?} vs {default:?}");
+    }
+    assert_eq!(play(&mut app, &handle, 1.0), default, "1 reproduces the default packing");
+}
+
+#[test]
+fn invalid_spacing_falls_back_to_the_default() {
+    let (mut app, handle) = common::headless_app("test_decoration_spacing_invalid");
+    let default = play(&mut app, &handle, PYRAMID_DECORATIONS_SPACING);
+    assert_eq!(play(&mut app, &handle, 0.0), default);
+    let applied = f32::from_bits(handle.get().game_structure_game.decoration_spacing.load(Ordering::Relaxed));
+    assert_eq!(applied, PYRAMID_DECORATIONS_SPACING, "the applied value is echoed back");
+}
@@ -0,0 +1,59 @@
+//! Decoration spin: each decoration turns in place around its face normal at
+//! `decoration_spin_rate`, and a rate of 0 keeps them static.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{AnimatedDecoration, DecorationMarker};
+use std::time::Duration;
+
+/// Decorations as (entity, axis, transform), sorted by entity
+fn decorations(app: &mut App) -> Vec<(Entity, Dir3, Transform)> {
+    let world = app.world_mut();
+    let mut decorations: Vec<_> = world
+        .query_filtered::<(Entity, &AnimatedDecoration, &Transform), With<DecorationMarker>>()
+        .iter(world)
+        .map(|(entity, animated, transform)| (entity, animated.axis, *transform))
+        .collect();
+    decorations.sort_by_key(|(entity, ..)| *entity);
+    decorations
+}
+
+#[test]
+fn decorations_spin_around_their_face_normal() {
+    let (mut app, handle) = common::headless_app("test_decoration_spin");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.decoration_spin_rate.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    common::reset(&mut app, &handle);
+    app.update();
+    let before = decorations(&mut app);
+    assert!(!before.is_empty());
+    for _ in 0..5 {
+        app.update();
+    }
+    let after = decorations(&mut app);
+    assert_eq!(before.len(), after.len());
+
+    for ((_, axis, start), (_, _, end)) in before.iter().zip(&after) {
+        assert_eq!(start.translation, end.translation, "a spinning decoration moved");
+        let (spin_axis, angle) = (end.rotation * start.rotation.inverse()).to_axis_angle();
+        assert!((angle - 0.5).abs() < 1e-3, "spun by {angle} rad instead of 0.5");
+        assert!(spin_axis.dot(axis.as_vec3()) > 0.999, "spun around {spin_axis} instead of {axis:?}");
+    }
+
+    // 0 keeps them still
+    gs_ctrl.decoration_spin_rate.store(0.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    let before = decorations(&mut app);
+    for _ in 0..5 {
+        app.update();
+    }
+    let after = decorations(&mut app);
+    for ((_, _, start), (_, _, end)) in before.iter().zip(&after) {
+        assert_eq!(start.rotation, end.rotation);
+    }
+}
@@ -0,0 +1,72 @@
+//! Symmetric decoration layout: every decoration has a mirror image across its face's vertical
+//! axis, the placed count includes the mirrors, and the layout follows from the seeds.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{DecorationMarker, FaceMarker};
+use shared::SharedMemoryHandle;
+
+/// Decoration positions per face, in the face's frame
+fn layout(app: &mut App) -> [Vec<Vec3>; 3] {
+    let world = app.world_mut();
+    let mut faces: [Vec<Vec3>; 3] = Default::default();
+    for (decoration, transform) in world.query::<(&DecorationMarker, &Transform)>().iter(world) {
+        faces[decoration.face_index].push(transform.translation);
+    }
+    faces
+}
+
+/// Normals of the faces, in their own frame
+fn face_normals(app: &mut App) -> [Vec3; 3] {
+    let world = app.world_mut();
+    let mut normals = [Vec3::ZERO; 3];
+    for face in world.query::<&FaceMarker>().iter(world) {
+        normals[face.face_index] = face.normal;
+    }
+    normals
+}
+
+fn play_symmetric(app: &mut App, handle: &SharedMemoryHandle) -> [Vec<Vec3>; 3] {
+    handle.get().game_structure_control.decoration_symmetry.store(true, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    layout(app)
+}
+
+#[test]
+fn decorations_mirror_across_the_vertical_axis() {
+    let (mut app, handle) = common::headless_app("test_decoration_symmetry");
+    let faces = play_symmetric(&mut app, &handle);
+    let normals = face_normals(&mut app);
+    let gs_game = &handle.get().game_structure_game;
+
+    for (face, positions) in faces.iter().enumerate() {
+        assert!(!positions.is_empty(), "face {face} undecorated");
+        assert_eq!(positions.len() % 2, 0, "face {face}: unpaired decoration");
+        assert_eq!(gs_game.decorations_placed[face].load(Ordering::Relaxed) as usize, positions.len(), "mirrors counted");
+
+        // The vertical axis of a prism face lies in the vertical plane through the prism's axis
+        // (the origin) along the face normal, so mirroring flips the horizontal in-face component
+        let across = normals[face].cross(Vec3::Y).normalize();
+        for &position in positions {
+            let offset = position.dot(across);
+            assert!(offset.abs() > 1e-3, "face {face}: decoration on the axis");
+            let mirrored = position - 2.0 * offset * across;
+            let closest = positions.iter().map(|other| other.distance(mirrored)).fold(f32::MAX, f32::min);
+            assert!(closest < 1e-4, "face {face}: no mirror of {position}, closest {closest} away");
+        }
+    }
+}
+
+#[test]
+fn symmetric_layout_follows_the_seeds() {
+    let (mut app, handle) = common::headless_app("test_decoration_symmetry_seeds");
+    let first = play_symmetric(&mut app, &handle);
+    assert_eq!(play_symmetric(&mut app, &handle), first, "same seeds, same layout");
+
+    for seed in &handle.get().game_structure_control.decoration_seeds {
+        seed.fetch_add(1, Ordering::Relaxed);
+    }
+    assert_ne!(play_symmetric(&mut app, &handle), first);
+}
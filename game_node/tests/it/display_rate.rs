@@ -0,0 +1,87 @@
+//! Nominal frame counts and speeds converted to the display rate, and the rates emitted for controllers.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::command_handler::{CommandHandlerPlugin, PendingRotation, SharedMemResource};
+use game_node::display_rate::DisplayRate;
+use game_node::fixed_rate_diagnostics::FixedRateMonitor;
+use game_node::utils::game_functions::flicker_phase;
+use shared::constants::camera_3d_constants::CAMERA_3D_SPEED_ROTATE;
+use shared::constants::timing::frames_at_rate;
+use shared::create_shared_memory;
+
+const PANEL_HZ: f64 = 120.0;
+
+#[test]
+fn frame_counts_last_as_long_at_the_display_rate() {
+    assert_eq!(frames_at_rate(60, 60.0), 60);
+    assert_eq!(frames_at_rate(60, 144.0), 144);
+    assert_eq!(frames_at_rate(30, 59.94), 30);
+
+    let nominal = DisplayRate::default();
+    assert_eq!(nominal.frames(45), 45, "nominal until detected");
+    let panel = DisplayRate { display_hz: Some(PANEL_HZ) };
+    assert_eq!(panel.frames(45), 90);
+    assert_eq!(panel.per_frame(1.0), 0.5);
+
+    // A 10 Hz flicker completes a cycle every 12 frames on the panel, not every 6
+    assert!(flicker_phase(12, 10.0, PANEL_HZ).abs() < 1e-6);
+    assert!((flicker_phase(6, 10.0, PANEL_HZ) - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn latched_motion_keeps_its_speed_and_timeout_on_a_faster_panel() {
+    let handle = create_shared_memory("test_display_rate_latch").expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(DisplayRate { display_hz: Some(PANEL_HZ) })
+        .add_plugins(CommandHandlerPlugin);
+    app.update();
+
+    let cmd = &handle.get().commands;
+    cmd.motion_latched.store(true, Ordering::Relaxed);
+    cmd.motion_latch_timeout_frames.store(3, Ordering::Relaxed);
+    cmd.rotate_start_right.store(true, Ordering::Relaxed);
+    cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+    app.update();
+    assert_eq!(app.world().resource::<PendingRotation>().0, CAMERA_3D_SPEED_ROTATE / 2.0, "half per frame");
+
+    // 3 nominal frames are 6 frames on the panel
+    let mut frames = 0;
+    while app.world().resource::<PendingRotation>().0 != 0.0 {
+        app.update();
+        frames += 1;
+        assert!(frames <= 6, "stopped within the timeout");
+    }
+    assert_eq!(frames, 6);
+}
+
+#[test]
+fn headless_reports_the_nominal_fixed_rate_and_no_display() {
+    let (mut app, handle) = common::headless_app("test_display_rate_headless");
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    let fixed_hz = f32::from_bits(gs_game.fixed_hz.load(Ordering::Relaxed));
+    assert!((fixed_hz - 60.0).abs() < 1e-3, "{fixed_hz}");
+    assert_eq!(f32::from_bits(gs_game.display_hz.load(Ordering::Relaxed)), 0.0);
+}
+
+#[test]
+fn render_rate_is_measured_next_to_the_fixed_rate() {
+    // 144 rendered frames per second with a fixed update on every other one or so
+    let mut monitor = FixedRateMonitor::default();
+    let mut fixed = 0.0;
+    for frame in 1..=288u32 {
+        fixed += 60.0 / 144.0;
+        while fixed >= 1.0 {
+            monitor.count_fixed_update();
+            fixed -= 1.0;
+        }
+        monitor.sample(Duration::from_secs_f64(frame as f64 / 144.0));
+    }
+    let render = monitor.render_rate().expect("a window has passed");
+    assert!((render - 144.0).abs() < 0.5, "{render}");
+}
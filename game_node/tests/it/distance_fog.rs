@@ -0,0 +1,58 @@
+//! Distance fog: a fog trial puts linear fog with its parameters on the scene camera, a trial
+//! without it takes it off, and start >= end is rejected by the config API and disabled by the game.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::PersistentCamera;
+use shared::json_api::apply_config_json;
+use shared::SharedMemoryHandle;
+
+const FOG_COLOR: [f32; 4] = [0.5, 0.6, 0.7, 1.0];
+
+fn camera_fog(app: &mut App) -> Option<DistanceFog> {
+    let world = app.world_mut();
+    world.query_filtered::<Option<&DistanceFog>, With<PersistentCamera>>().single(world).unwrap().cloned()
+}
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, enabled: bool, start: f32, end: f32) -> Option<DistanceFog> {
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.fog_enabled.store(enabled, Ordering::Relaxed);
+    for (slot, value) in gs_ctrl.fog_color.iter().zip(FOG_COLOR) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    gs_ctrl.fog_start.store(start.to_bits(), Ordering::Relaxed);
+    gs_ctrl.fog_end.store(end.to_bits(), Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    camera_fog(app)
+}
+
+#[test]
+fn fog_follows_each_trial() {
+    let (mut app, handle) = common::headless_app("test_distance_fog");
+    assert!(camera_fog(&mut app).is_none(), "no fog by default");
+
+    let fog = play(&mut app, &handle, true, 4.0, 12.0).expect("fog on the camera");
+    assert!(matches!(fog.falloff, FogFalloff::Linear { start: 4.0, end: 12.0 }), "{:?}", fog.falloff);
+    let shown = fog.color.to_srgba().to_f32_array();
+    assert!(shown.iter().zip(FOG_COLOR).all(|(a, b)| (a - b).abs() < 1e-5), "{shown:?}");
+    assert!(handle.get().game_structure_game.fog_enabled.load(Ordering::Relaxed));
+
+    // Re-applied with the next trial's distances, and taken off without fog
+    let fog = play(&mut app, &handle, true, 2.0, 6.0).expect("fog on the camera");
+    assert!(matches!(fog.falloff, FogFalloff::Linear { start: 2.0, end: 6.0 }));
+    assert!(play(&mut app, &handle, false, 2.0, 6.0).is_none());
+}
+
+#[test]
+fn start_at_or_beyond_end_is_rejected() {
+    let (mut app, handle) = common::headless_app("test_distance_fog_invalid");
+    let shm = handle.get();
+    assert!(apply_config_json(shm, r#"{"fog_enabled": true, "fog_start": 8.0, "fog_end": 8.0}"#).is_err());
+    assert!(!shm.game_structure_control.fog_enabled.load(Ordering::Relaxed), "rejected write left untouched");
+
+    // Written directly, the game shows no fog and reports it off
+    assert!(play(&mut app, &handle, true, 9.0, 3.0).is_none());
+    assert!(!shm.game_structure_game.fog_enabled.load(Ordering::Relaxed));
+}
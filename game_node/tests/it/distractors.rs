@@ -0,0 +1,90 @@
+//! Ground distractors: the layout comes from the seed alone, stays on its ring clear of the base and
+//! the camera orbit without overlaps, and the spawned objects match it, stay put while the pyramid
+//! turns and leave the faces alone.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::distractors::{distractor_layout, distractor_layout_hash};
+use game_node::utils::objects::{Distractor, FaceMarker, RotableComponent};
+use shared::constants::camera_3d_constants::CAMERA_3D_MIN_RADIUS;
+use shared::constants::pyramid_constants::BASE_RADIUS;
+use shared::DistractorShape;
+
+const SIZES: [f32; 2] = [0.3, 0.6];
+const RING: [f32; 2] = [6.0, 8.5];
+
+#[test]
+fn layouts_are_seeded_on_the_ring_and_apart() {
+    let layout = distractor_layout(5, 8, DistractorShape::Mixed, SIZES, RING);
+    assert_eq!(layout, distractor_layout(5, 8, DistractorShape::Mixed, SIZES, RING));
+    assert_ne!(layout, distractor_layout(6, 8, DistractorShape::Mixed, SIZES, RING));
+    assert_eq!(distractor_layout_hash(&layout), distractor_layout_hash(&layout.clone()));
+    assert_ne!(distractor_layout_hash(&layout), distractor_layout_hash(&layout[1..]));
+    assert!(!layout.is_empty());
+
+    for (i, p) in layout.iter().enumerate() {
+        let radius = p.position.length();
+        assert!(radius >= RING[0].max(BASE_RADIUS + SIZES[1]) && radius <= RING[1].min(CAMERA_3D_MIN_RADIUS - SIZES[1]), "{p:?}");
+        assert!((SIZES[0]..=SIZES[1]).contains(&p.size));
+        assert_ne!(p.shape, DistractorShape::Mixed, "mixed draws a concrete shape");
+        for q in &layout[i + 1..] {
+            assert!(p.position.distance(q.position) >= p.size + q.size, "{p:?} overlaps {q:?}");
+        }
+    }
+
+    let cubes = distractor_layout(5, 8, DistractorShape::Cube, SIZES, RING);
+    assert!(cubes.iter().all(|p| p.shape == DistractorShape::Cube));
+    assert!(distractor_layout(5, 0, DistractorShape::Cube, SIZES, RING).is_empty());
+    assert!(distractor_layout(5, 8, DistractorShape::Cube, SIZES, [9.0, 7.0]).is_empty(), "empty ring");
+}
+
+#[test]
+fn spawned_distractors_match_the_layout_and_stay_fixed() {
+    let (mut app, handle) = common::headless_app("test_distractors");
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.distractor_count.store(6, Ordering::Relaxed);
+    gs_ctrl.distractor_shape.store(DistractorShape::Sphere as u32, Ordering::Relaxed);
+    gs_ctrl.distractor_seed.store(42, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+
+    let gs_game = &handle.get().game_structure_game;
+    let size_range = gs_game.distractor_size_range.each_ref().map(|v| f32::from_bits(v.load(Ordering::Relaxed)));
+    let ring = gs_game.distractor_ring.each_ref().map(|v| f32::from_bits(v.load(Ordering::Relaxed)));
+    let layout = distractor_layout(42, 6, DistractorShape::Sphere, size_range, ring);
+    assert_eq!(gs_game.distractor_hash.load(Ordering::Relaxed), distractor_layout_hash(&layout));
+
+    let world = app.world_mut();
+    let mut spawned: Vec<_> = world
+        .query_filtered::<&Transform, (With<Distractor>, Without<RotableComponent>, Without<FaceMarker>)>()
+        .iter(world)
+        .map(|t| t.translation.xz())
+        .collect();
+    assert_eq!(spawned.len(), layout.len());
+    assert_eq!(world.query::<&Distractor>().iter(world).count(), layout.len(), "never rotable nor a face");
+    assert_eq!(world.query::<&FaceMarker>().iter(world).count(), 3);
+    let by_position = |a: &Vec2, b: &Vec2| a.x.partial_cmp(&b.x).unwrap();
+    spawned.sort_by(by_position);
+    let mut expected: Vec<_> = layout.iter().map(|p| p.position).collect();
+    expected.sort_by(by_position);
+    assert_eq!(spawned, expected);
+
+    // The pyramid turns, the distractors do not
+    handle.get().commands.rotate_left.store(true, Ordering::Relaxed);
+    for _ in 0..10 {
+        app.update();
+    }
+    handle.get().commands.rotate_left.store(false, Ordering::Relaxed);
+    let world = app.world_mut();
+    let mut after: Vec<_> = world.query_filtered::<&Transform, With<Distractor>>().iter(world).map(|t| t.translation.xz()).collect();
+    after.sort_by(by_position);
+    assert_eq!(after, expected);
+
+    // No distractors by default
+    gs_ctrl.distractor_count.store(0, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(world.query::<&Distractor>().iter(world).count(), 0);
+}
@@ -0,0 +1,46 @@
+//! Door animation easing: each curve passes through its key points within the fades, the closing
+//! fade plays it back, and the three-phase timing is the same for every curve.
+use game_node::utils::game_functions::door_animation_intensity;
+use shared::AnimationEasing;
+
+/// (t, eased t) samples of every curve
+const SAMPLES: [(AnimationEasing, [(f32, f32); 5]); 3] = [
+    (AnimationEasing::Linear, [(0.0, 0.0), (0.25, 0.25), (0.5, 0.5), (0.75, 0.75), (1.0, 1.0)]),
+    (AnimationEasing::EaseInOut, [(0.0, 0.0), (0.25, 0.0625), (0.5, 0.5), (0.75, 0.9375), (1.0, 1.0)]),
+    (AnimationEasing::Exponential, [(0.0, 0.0), (0.5, 0.03125), (0.8, 0.25), (0.9, 0.5), (1.0, 1.0)]),
+];
+
+#[test]
+fn curves_pass_through_their_key_points() {
+    for (easing, samples) in SAMPLES {
+        for (t, expected) in samples {
+            let eased = easing.apply(t);
+            assert!((eased - expected).abs() < 1e-5, "{easing:?} at {t}: {eased}, expected {expected}");
+        }
+        // Clamped outside the fade
+        assert_eq!(easing.apply(-1.0), 0.0);
+        assert_eq!(easing.apply(2.0), 1.0);
+    }
+}
+
+#[test]
+fn fades_follow_the_curve_within_unchanged_phases() {
+    // Fade out over 1 s, stay open 2 s, fade in over 1 s
+    let intensity = |elapsed: f32, easing| door_animation_intensity(elapsed, 1.0, 3.0, 4.0, easing);
+    for (easing, samples) in SAMPLES {
+        for (t, expected) in samples.into_iter().filter(|&(t, _)| t < 1.0) {
+            assert!((intensity(t, easing) - expected).abs() < 1e-5, "{easing:?} opening at {t}");
+            assert!((intensity(4.0 - t, easing) - expected).abs() < 1e-5, "{easing:?} closing at {t}");
+        }
+        assert_eq!(intensity(1.0, easing), 1.0);
+        assert_eq!(intensity(2.5, easing), 1.0);
+        assert_eq!(intensity(4.0, easing), 0.0);
+    }
+}
+
+#[test]
+fn unknown_codes_are_rejected() {
+    assert_eq!(AnimationEasing::from_u32(0), Some(AnimationEasing::Linear));
+    assert_eq!(AnimationEasing::from_u32(2), Some(AnimationEasing::Exponential));
+    assert_eq!(AnimationEasing::from_u32(3), None);
+}
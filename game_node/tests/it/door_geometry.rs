@@ -0,0 +1,92 @@
+//! Door geometry: the hole, frame tunnel and door inset are built from the applied config and
+//! echoed in the game region, degenerate values are rejected by the validator, and values that
+//! reach the game anyway fall back to the defaults.
+use crate::common;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicU32, Ordering};
+use game_node::utils::objects::{BaseFrame, HoleEmissive};
+use shared::constants::pyramid_constants::{DOOR_FRAME_THICKNESS, DOOR_HOLE_RADIUS_FRACTION, DOOR_INSET, PYRAMID_BASE_RADIUS};
+use shared::{base_panel_apothem, validate_door_geometry, widest_base_radius, ConfigMode, SharedMemoryHandle};
+
+fn positions(app: &App, mesh: &Mesh3d) -> Vec<Vec3> {
+    let meshes = app.world().resource::<Assets<Mesh>>();
+    match meshes.get(&mesh.0).unwrap().attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.iter().map(|p| Vec3::from_array(*p)).collect(),
+        other => panic!("unexpected positions {other:?}"),
+    }
+}
+
+/// (Vertices of the first frame mesh, widest extent of the first door hole) after a reset with `geometry`
+fn play(app: &mut App, handle: &SharedMemoryHandle, geometry: [f32; 3]) -> (usize, f32) {
+    let gs_ctrl = &handle.get().game_structure_control;
+    let slots = [&gs_ctrl.door_hole_radius_fraction, &gs_ctrl.door_frame_thickness, &gs_ctrl.door_inset];
+    for (slot, value) in slots.into_iter().zip(geometry) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    common::reset(app, handle);
+    app.update();
+
+    let world = app.world_mut();
+    let frame = world
+        .query::<(&BaseFrame, &Mesh3d)>()
+        .iter(world)
+        .find(|(frame, _)| frame.door_index == 0)
+        .map(|(_, mesh)| mesh.clone())
+        .unwrap();
+    let hole = world.query_filtered::<&Mesh3d, With<HoleEmissive>>().iter(world).next().unwrap().clone();
+    let hole = positions(app, &hole);
+    let extent = hole.iter().flat_map(|a| hole.iter().map(move |b| a.distance(*b))).fold(0.0, f32::max);
+    (positions(app, &frame).len(), extent)
+}
+
+fn applied(handle: &SharedMemoryHandle) -> [f32; 3] {
+    let gs_game = &handle.get().game_structure_game;
+    let load = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+    [load(&gs_game.door_hole_radius_fraction), load(&gs_game.door_frame_thickness), load(&gs_game.door_inset)]
+}
+
+#[test]
+fn degenerate_door_geometry_is_rejected() {
+    let radius = PYRAMID_BASE_RADIUS;
+    assert!(validate_door_geometry(radius, DOOR_HOLE_RADIUS_FRACTION, DOOR_FRAME_THICKNESS, DOOR_INSET).is_ok());
+    assert!(validate_door_geometry(radius, 1.0, 0.0, 0.0).is_err(), "hole as large as the face");
+    assert!(validate_door_geometry(radius, 0.0, 0.0, 0.0).is_err(), "no hole");
+    assert!(validate_door_geometry(radius, 0.5, -0.1, 0.0).is_err());
+    assert!(validate_door_geometry(radius, 0.5, 100.0, 0.0).is_err(), "tunnel past the base center");
+    assert!(validate_door_geometry(radius, 0.5, 0.0, f32::NAN).is_err());
+
+    // The tunnel and a glow behind the panel must stop short of the pyramid, so a wider one leaves less depth
+    let clearance = base_panel_apothem() - radius;
+    assert!(validate_door_geometry(radius, 0.5, clearance - 0.01, clearance - 0.01).is_ok());
+    assert!(validate_door_geometry(radius, 0.5, clearance + 0.01, 0.0).is_err(), "tunnel into the pyramid");
+    assert!(validate_door_geometry(radius, 0.5, 0.0, clearance + 0.01).is_err(), "glow inside the pyramid");
+    assert!(validate_door_geometry(radius + 0.5, 0.5, clearance - 0.01, 0.0).is_err(), "wider pyramid");
+    // In front of the panel the glow only has to stay on the base's side
+    assert!(validate_door_geometry(radius, 0.5, 0.0, -(clearance + 0.01)).is_ok());
+
+    // A randomized trial is checked against the widest radius it can draw
+    let randomized = ConfigMode::Randomized as u32;
+    assert_eq!(widest_base_radius(randomized, radius, [1.0, 4.0]), 4.0);
+    assert_eq!(widest_base_radius(ConfigMode::Explicit as u32, radius, [1.0, 4.0]), radius);
+}
+
+#[test]
+fn doors_are_built_from_the_applied_geometry() {
+    let (mut app, handle) = common::headless_app("test_door_geometry");
+    let defaults = [DOOR_HOLE_RADIUS_FRACTION, DOOR_FRAME_THICKNESS, DOOR_INSET];
+    let (flat_vertices, default_extent) = play(&mut app, &handle, defaults);
+    assert_eq!(applied(&handle), defaults);
+
+    let custom = [0.6, 0.3, 0.05];
+    let (vertices, extent) = play(&mut app, &handle, custom);
+    assert_eq!(applied(&handle), custom);
+    assert_eq!(vertices, flat_vertices + 5 * 4, "one tunnel wall quad per hole edge");
+    assert!((extent / default_extent - 0.6 / DOOR_HOLE_RADIUS_FRACTION).abs() < 1e-3, "{extent} vs {default_extent}");
+
+    // A hole larger than the face that bypasses the validator is replaced by the defaults
+    let (vertices, extent) = play(&mut app, &handle, [1.5, 0.3, 0.05]);
+    assert_eq!(applied(&handle), defaults);
+    assert_eq!((vertices, extent), (flat_vertices, default_extent));
+}
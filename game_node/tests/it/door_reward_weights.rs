@@ -0,0 +1,69 @@
+//! Door reward weights: a win reports the weight of the door it was credited to, the target door or
+//! the door under the target face, and a reset clears it.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::pyramid::door_for_face;
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+use shared::{SharedMemoryHandle, TargetKind};
+
+/// A distinct weight per door
+const WEIGHTS: [f32; 8] = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0];
+
+fn reward_weight(handle: &SharedMemoryHandle) -> f32 {
+    f32::from_bits(handle.get().game_structure_game.reward_weight.load(Ordering::Relaxed))
+}
+
+/// Resets into the round, looks straight at its target and checks
+fn win(app: &mut App, handle: &SharedMemoryHandle) {
+    common::reset(app, handle);
+    app.update();
+    assert_eq!(reward_weight(handle), 0.0, "no reward before the win");
+    common::face_target(app);
+    app.update();
+    common::check_alignment(app, handle);
+    assert_ne!(handle.get().game_structure_game.win_time.load(Ordering::Relaxed), 0, "aligned check wins");
+}
+
+fn weighted_app(name: &str) -> (App, SharedMemoryHandle) {
+    let (app, handle) = common::headless_app(name);
+    for (slot, weight) in handle.get().game_structure_control.door_reward_weights.iter().zip(WEIGHTS) {
+        slot.store(weight.to_bits(), Ordering::Relaxed);
+    }
+    (app, handle)
+}
+
+#[test]
+fn winning_on_a_door_reports_its_weight() {
+    let (mut app, handle) = weighted_app("test_door_reward_weights");
+    for (door, weight) in WEIGHTS.into_iter().enumerate().take(BASE_NR_SIDES) {
+        handle.get().game_structure_control.target_door.store(door as u32, Ordering::Relaxed);
+        win(&mut app, &handle);
+        assert_eq!(reward_weight(&handle), weight, "door {door}");
+    }
+    common::reset(&mut app, &handle);
+    assert_eq!(reward_weight(&handle), 0.0, "cleared by the reset");
+}
+
+#[test]
+fn face_targets_report_the_weight_of_their_door() {
+    let (mut app, handle) = weighted_app("test_door_reward_weights_face");
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.target_kind.store(TargetKind::Face as u32, Ordering::Relaxed);
+    for face in 0..3 {
+        gs_ctrl.target_face.store(face as u32, Ordering::Relaxed);
+        win(&mut app, &handle);
+        assert_eq!(reward_weight(&handle), WEIGHTS[door_for_face(face)], "face {face}");
+    }
+}
+
+#[test]
+fn invalid_weights_fall_back_to_the_defaults() {
+    let (mut app, handle) = weighted_app("test_door_reward_weights_invalid");
+    handle.get().game_structure_control.door_reward_weights[2].store((-1.0f32).to_bits(), Ordering::Relaxed);
+    win(&mut app, &handle);
+    let applied = &handle.get().game_structure_game.door_reward_weights;
+    assert!(applied.iter().all(|weight| f32::from_bits(weight.load(Ordering::Relaxed)) == 1.0));
+    assert_eq!(reward_weight(&handle), 1.0);
+}
@@ -0,0 +1,74 @@
+//! Experimenter keyboard override: gated behind Ctrl+Shift+E, routed through the pending
+//! resources, and counted in the game region.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::RenderingPaused;
+use game_node::experimenter_override::{ExperimenterOverridePlugin, OverrideBorder};
+use game_node::utils::systems_logic::BlankScreenState;
+
+/// Presses `keys` for one frame, then releases them
+fn tap(app: &mut App, keys: &[KeyCode]) {
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    for &key in keys {
+        input.press(key);
+    }
+    app.update();
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release_all();
+    input.clear();
+}
+
+fn blanked(app: &App) -> bool {
+    app.world().resource::<BlankScreenState>().is_active
+}
+
+fn border_shown(app: &mut App) -> bool {
+    app.world_mut().query_filtered::<(), With<OverrideBorder>>().iter(app.world()).count() == 1
+}
+
+#[test]
+fn override_keys_only_act_after_the_chord() {
+    let (mut app, handle) = common::headless_app("test_experimenter_override");
+    app.init_resource::<ButtonInput<KeyCode>>().add_plugins(ExperimenterOverridePlugin);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+
+    tap(&mut app, &[KeyCode::KeyB]);
+    tap(&mut app, &[KeyCode::KeyE]);
+    assert!(!blanked(&app), "keys are ignored outside override mode");
+    assert_eq!(gs_game.override_activations.load(Ordering::Relaxed), 0);
+
+    tap(&mut app, &[KeyCode::ControlLeft, KeyCode::ShiftRight, KeyCode::KeyE]);
+    assert_eq!(gs_game.override_activations.load(Ordering::Relaxed), 1);
+    assert!(border_shown(&mut app));
+
+    tap(&mut app, &[KeyCode::KeyB]);
+    assert!(blanked(&app));
+    tap(&mut app, &[KeyCode::KeyB]);
+    assert!(!blanked(&app));
+
+    let reset_ack = gs_game.reset_ack.load(Ordering::Acquire);
+    tap(&mut app, &[KeyCode::KeyR]);
+    app.update();
+    assert_eq!(gs_game.reset_ack.load(Ordering::Acquire), reset_ack + 1);
+
+    // Pause holds across frames until resumed
+    tap(&mut app, &[KeyCode::KeyP]);
+    app.update();
+    assert!(app.world().resource::<RenderingPaused>().0);
+    tap(&mut app, &[KeyCode::KeyO]);
+    assert!(!app.world().resource::<RenderingPaused>().0);
+    assert_eq!(gs_game.override_actions.load(Ordering::Relaxed), 5);
+
+    // Leaving hands control back: the pause is released and the keys do nothing again
+    tap(&mut app, &[KeyCode::KeyP]);
+    tap(&mut app, &[KeyCode::Escape]);
+    app.update();
+    assert!(!app.world().resource::<RenderingPaused>().0);
+    assert!(!border_shown(&mut app));
+    tap(&mut app, &[KeyCode::KeyB]);
+    assert!(!blanked(&app));
+    assert_eq!(gs_game.override_actions.load(Ordering::Relaxed), 6);
+}
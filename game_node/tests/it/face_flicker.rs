@@ -0,0 +1,112 @@
+//! Face flicker tagging: a flickering face's color swings around its spawned color within the depth,
+//! the phase of every face is emitted each frame and matches the color shown in that frame, and the
+//! faces show their spawned color while paused or blanked.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::game_functions::flicker_phase;
+use game_node::utils::objects::FaceMarker;
+use game_node::utils::systems_logic::BlankScreenState;
+use shared::constants::game_constants::REFRESH_RATE_HZ;
+use shared::SharedMemoryHandle;
+
+const HZ: f32 = 10.0;
+const DEPTH: f32 = 0.5;
+
+/// (Spawned, shown) linear colors of every face, in face order
+fn face_colors(app: &mut App) -> Vec<(LinearRgba, LinearRgba)> {
+    let world = app.world_mut();
+    let mut faces: Vec<_> = world
+        .query::<(&FaceMarker, &MeshMaterial3d<StandardMaterial>)>()
+        .iter(world)
+        .map(|(face, material)| (face.face_index, face.base_color.to_linear(), material.0.clone()))
+        .collect();
+    faces.sort_by_key(|face| face.0);
+    let materials = world.resource::<Assets<StandardMaterial>>();
+    faces.into_iter().map(|(_, base, handle)| (base, materials.get(&handle).unwrap().base_color.to_linear())).collect()
+}
+
+fn emitted_phases(handle: &SharedMemoryHandle) -> [f32; 3] {
+    let gs_game = &handle.get().game_structure_game;
+    std::array::from_fn(|i| f32::from_bits(gs_game.flicker_phase[i].load(Ordering::Relaxed)))
+}
+
+fn flickering_app(name: &str) -> (App, SharedMemoryHandle) {
+    let (mut app, handle) = common::headless_app(name);
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.flicker_hz[0].store(HZ.to_bits(), Ordering::Relaxed);
+    gs_ctrl.flicker_depth[0].store(DEPTH.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    (app, handle)
+}
+
+#[test]
+fn flickering_face_swings_within_its_depth_and_emits_its_phase() {
+    let (mut app, handle) = flickering_app("test_face_flicker");
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(f32::from_bits(gs_game.flicker_hz[0].load(Ordering::Relaxed)), HZ);
+
+    let mut shown = Vec::new();
+    for _ in 0..6 {
+        app.update();
+        let frame = gs_game.frame_number.load(Ordering::Acquire);
+        let phases = emitted_phases(&handle);
+        assert!((phases[0] - flicker_phase(frame, HZ, REFRESH_RATE_HZ)).abs() < 1e-6, "frame {frame}: {phases:?}");
+        assert_eq!(phases[1..], [0.0, 0.0], "faces without a frequency stay at phase 0");
+
+        let colors = face_colors(&mut app);
+        let (base, color) = colors[0];
+        let gain = color.red / base.red;
+        let expected = 1.0 + DEPTH * (phases[0] * std::f32::consts::TAU).sin();
+        assert!((gain - expected).abs() < 1e-4, "frame {frame}: shown gain {gain}, emitted phase gives {expected}");
+        let scaled = [base.red, base.green, base.blue].map(|c| c * gain);
+        assert!(scaled.iter().zip([color.red, color.green, color.blue]).all(|(a, b)| (a - b).abs() < 1e-4), "scaled as a whole");
+        assert_eq!(color.alpha, base.alpha);
+        assert_eq!(colors[1].1, colors[1].0, "face 1 does not flicker");
+        shown.push(gain);
+    }
+    // One full cycle at 60 frames per second: the gain rises above and falls below the spawned color
+    assert!(shown.iter().any(|&gain| gain > 1.1) && shown.iter().any(|&gain| gain < 0.9), "{shown:?}");
+}
+
+/// Whether every face shows its spawned color
+fn unmodulated(app: &mut App) -> bool {
+    face_colors(app).iter().all(|(base, shown)| base == shown)
+}
+
+#[test]
+fn faces_show_their_spawned_color_while_paused_or_blanked() {
+    let (mut app, handle) = flickering_app("test_face_flicker_pause");
+    let commands = &handle.get().commands;
+
+    // Mid-swing before the pause (a quarter cycle in at 10 Hz on 60 frames per second)
+    app.update();
+    assert!(!unmodulated(&mut app), "not flickering before the pause");
+    commands.stop_rendering.store(true, Ordering::Relaxed);
+    for _ in 0..3 {
+        app.update();
+        assert!(unmodulated(&mut app), "modulated while paused");
+    }
+    commands.stop_rendering.store(false, Ordering::Relaxed);
+    commands.resume_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    commands.resume_rendering.store(false, Ordering::Relaxed);
+    app.update();
+    assert!(!unmodulated(&mut app), "still held after the pause");
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = true;
+    for _ in 0..3 {
+        app.update();
+        assert!(unmodulated(&mut app), "modulated while blanked");
+    }
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = false;
+    let mut resumed = Vec::new();
+    for _ in 0..3 {
+        app.update();
+        resumed.push(unmodulated(&mut app));
+    }
+    assert!(resumed.contains(&false), "still held after the blank");
+}
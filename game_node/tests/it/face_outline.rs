@@ -0,0 +1,78 @@
+//! Face outlines: with a width, every face gets a border child lying just off the face, inside its
+//! edges, in the configured color; a width of 0 draws none, and the outlines leave the win alone.
+use crate::common;
+
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{FaceMarker, FaceOutline};
+use shared::constants::pyramid_constants::PYRAMID_OUTLINE_OFFSET;
+
+/// Outlines as (face normal, distances of its vertices from the face plane, color), by face
+fn outlines(app: &mut App) -> Vec<(Vec3, Vec<f32>, Color)> {
+    let world = app.world_mut();
+    let faces: Vec<(Entity, usize, Vec3, Vec3)> = world
+        .query::<(Entity, &FaceMarker, &Mesh3d)>()
+        .iter(world)
+        .map(|(entity, face, mesh)| {
+            let meshes = world.resource::<Assets<Mesh>>();
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                meshes.get(&mesh.0).unwrap().attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                panic!("face without positions")
+            };
+            (entity, face.face_index, face.normal, Vec3::from(positions[0]))
+        })
+        .collect();
+
+    let mut outlines: Vec<(usize, Vec3, Vec<f32>, Color)> = world
+        .query::<(&FaceOutline, &ChildOf, &Mesh3d, &MeshMaterial3d<StandardMaterial>)>()
+        .iter(world)
+        .map(|(outline, child_of, mesh, material)| {
+            let &(_, face_index, normal, on_face) =
+                faces.iter().find(|face| face.0 == child_of.parent()).expect("outline not under a face");
+            assert_eq!(face_index, outline.face_index, "outline under the wrong face");
+            let meshes = world.resource::<Assets<Mesh>>();
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                meshes.get(&mesh.0).unwrap().attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                panic!("outline without positions")
+            };
+            let heights = positions.iter().map(|&p| (Vec3::from(p) - on_face).dot(normal)).collect();
+            let color = world.resource::<Assets<StandardMaterial>>().get(&material.0).unwrap().base_color;
+            (face_index, normal, heights, color)
+        })
+        .collect();
+    outlines.sort_by_key(|outline| outline.0);
+    outlines.into_iter().map(|(_, normal, heights, color)| (normal, heights, color)).collect()
+}
+
+#[test]
+fn faces_are_outlined_with_the_configured_border() {
+    let (mut app, handle) = common::headless_app("test_face_outline");
+    common::reset(&mut app, &handle);
+    app.update();
+    assert!(outlines(&mut app).is_empty(), "outlined with a width of 0");
+
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.outline_width.store(0.05f32.to_bits(), Ordering::Relaxed);
+    for (channel, value) in gs_ctrl.outline_color.iter().zip([1.0f32, 0.0, 0.0, 1.0]) {
+        channel.store(value.to_bits(), Ordering::Relaxed);
+    }
+    common::reset(&mut app, &handle);
+    app.update();
+
+    let outlines = outlines(&mut app);
+    assert_eq!(outlines.len(), 3);
+    for (_, heights, color) in &outlines {
+        // Lifted off the face, outward (the face normal points into the pyramid)
+        assert_eq!(heights.len(), 16);
+        assert!(heights.iter().all(|h| (h + PYRAMID_OUTLINE_OFFSET).abs() < 1e-5), "{heights:?}");
+        assert_eq!(color.to_srgba(), Srgba::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    // Not part of the alignment: still won by facing the target face
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    assert_ne!(handle.get().game_structure_game.win_time.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,66 @@
+//! Feedback delay: the door animation of a check starts `feedback_delay_ms` after it, with
+//! `feedback_pending` raised during the gap, and right away by default.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DoorWinEntities;
+use shared::SharedMemoryHandle;
+use std::time::Duration;
+
+const FRAME_MS: u64 = 100;
+
+fn door_light_on(app: &mut App) -> bool {
+    let light = app.world().resource::<DoorWinEntities>().winning_light.expect("winning light");
+    app.world().get::<Visibility>(light) == Some(&Visibility::Visible)
+}
+
+/// Checks facing the target with `delay_ms` and counts the frames until the door animation shows,
+/// asserting `feedback_pending` is raised exactly until then
+fn frames_to_feedback(app: &mut App, handle: &SharedMemoryHandle, delay_ms: u32) -> u32 {
+    let gs_game = &handle.get().game_structure_game;
+    handle.get().game_structure_control.feedback_delay_ms.store(delay_ms, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    common::face_target(app);
+    common::check_alignment(app, handle);
+    assert_ne!(gs_game.win_time.load(Ordering::Relaxed), 0, "the outcome is decided at the check");
+
+    let mut frames = 0;
+    while !door_light_on(app) {
+        assert!(gs_game.feedback_pending.load(Ordering::Relaxed), "pending during the gap");
+        assert!(frames < 100, "feedback never started");
+        app.update();
+        frames += 1;
+    }
+    assert!(!gs_game.feedback_pending.load(Ordering::Relaxed));
+    frames
+}
+
+#[test]
+fn animation_starts_after_the_configured_delay() {
+    let (mut app, handle) = common::headless_app("test_feedback_delay");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(FRAME_MS)));
+
+    assert_eq!(frames_to_feedback(&mut app, &handle, 0), 0, "immediate by default");
+    assert_eq!(frames_to_feedback(&mut app, &handle, 500), (500 / FRAME_MS) as u32);
+    assert_eq!(frames_to_feedback(&mut app, &handle, 1200), (1200 / FRAME_MS) as u32);
+}
+
+#[test]
+fn reset_during_the_gap_clears_it() {
+    let (mut app, handle) = common::headless_app("test_feedback_delay_reset");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(FRAME_MS)));
+    let gs_game = &handle.get().game_structure_game;
+    handle.get().game_structure_control.feedback_delay_ms.store(1000, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    common::check_alignment(&mut app, &handle);
+    assert!(gs_game.feedback_pending.load(Ordering::Relaxed));
+
+    common::reset(&mut app, &handle);
+    app.update();
+    assert!(!gs_game.feedback_pending.load(Ordering::Relaxed));
+    assert!(!gs_game.is_animating.load(Ordering::Relaxed));
+}
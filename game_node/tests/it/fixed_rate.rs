@@ -0,0 +1,53 @@
+//! Fixed update rate measured against real time, and its drift when frames take too long.
+use crate::common;
+
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::fixed_rate_diagnostics::FixedRateMonitor;
+
+/// Rate reported by `sample` after `secs` of frames at `fps`, with `per_frame` fixed updates each
+fn measure(fps: f32, per_frame: u32, secs: f32) -> Option<f32> {
+    let mut monitor = FixedRateMonitor::default();
+    let mut rate = None;
+    for frame in 1..=(fps * secs) as u32 {
+        for _ in 0..per_frame {
+            monitor.count_fixed_update();
+        }
+        rate = monitor.sample(Duration::from_secs_f32(frame as f32 / fps));
+    }
+    rate
+}
+
+#[test]
+fn rate_is_reported_once_a_window_has_passed() {
+    assert_eq!(measure(60.0, 1, 0.5), None, "no rate before the first window");
+    let rate = measure(60.0, 1, 3.0).unwrap();
+    assert!((rate - 60.0).abs() < 0.1, "{rate}");
+    let rate = measure(25.0, 2, 3.0).unwrap();
+    assert!((rate - 50.0).abs() < 0.1, "{rate}");
+}
+
+/// Runs the headless game with frames of `frame` real time and returns the emitted rate
+fn emitted_rate(name: &str, frame: Duration) -> f32 {
+    let (mut app, handle) = common::headless_app(name);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+    let frames = (2.0 / frame.as_secs_f32()) as u32 + 2;
+    for _ in 0..frames {
+        app.update();
+    }
+    f32::from_bits(handle.get().game_structure_game.measured_hz.load(Ordering::Relaxed))
+}
+
+#[test]
+fn on_time_frames_keep_the_nominal_rate() {
+    let rate = emitted_rate("test_fixed_rate_nominal", Duration::from_secs_f64(1.0 / 60.0));
+    assert!((rate - 60.0).abs() < 1.0, "{rate}");
+}
+
+#[test]
+fn frames_too_long_to_catch_up_lower_the_rate() {
+    // Virtual time advances at most 250 ms per frame, so half-second frames run half the fixed updates
+    let rate = emitted_rate("test_fixed_rate_overloaded", Duration::from_millis(500));
+    assert!((rate - 30.0).abs() < 1.0, "{rate}");
+}
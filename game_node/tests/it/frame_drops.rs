@@ -0,0 +1,85 @@
+//! Late and dropped frames counted per trial from the real frame time, with injected long frames.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::frame_drops::FrameDropStats;
+use shared::SharedMemoryHandle;
+
+/// Runs one frame lasting `ms` of real time
+fn frame(app: &mut App, ms: f64) {
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(ms / 1000.0)));
+    app.update();
+}
+
+/// (late, dropped, worst ms) as emitted
+fn emitted(handle: &SharedMemoryHandle) -> (u32, u32, f32) {
+    let gs_game = &handle.get().game_structure_game;
+    (
+        gs_game.late_frames_trial.load(Ordering::Relaxed),
+        gs_game.dropped_frames_trial.load(Ordering::Relaxed),
+        f32::from_bits(gs_game.worst_frame_ms.load(Ordering::Relaxed)),
+    )
+}
+
+#[test]
+fn classifies_frames_against_the_period() {
+    let period = Duration::from_secs_f64(1.0 / 60.0);
+    let mut stats = FrameDropStats::default();
+    for ms in [16.7, 20.0, 26.0, 33.0, 34.0, 100.0] {
+        stats.record(Duration::from_secs_f64(ms / 1000.0), period);
+    }
+    assert_eq!((stats.late_frames, stats.dropped_frames), (2, 2));
+    assert_eq!(stats.worst_frame, Duration::from_millis(100));
+}
+
+#[test]
+fn long_frames_are_counted_per_trial() {
+    let (mut app, handle) = common::headless_app("test_frame_drops");
+    common::reset(&mut app, &handle);
+    for _ in 0..5 {
+        frame(&mut app, 1000.0 / 60.0);
+    }
+    let (late, dropped, worst) = emitted(&handle);
+    assert_eq!((late, dropped), (0, 0));
+    assert!(worst < 17.0, "{worst}");
+
+    // A missed vsync interval, then a late frame
+    frame(&mut app, 40.0);
+    frame(&mut app, 30.0);
+    frame(&mut app, 1000.0 / 60.0);
+    let (late, dropped, worst) = emitted(&handle);
+    assert_eq!((late, dropped), (1, 1));
+    assert!((worst - 40.0).abs() < 0.01, "{worst}");
+
+    // The next trial starts over
+    common::reset(&mut app, &handle);
+    frame(&mut app, 1000.0 / 60.0);
+    let (late, dropped, worst) = emitted(&handle);
+    assert_eq!((late, dropped), (0, 0));
+    assert!(worst < 17.0, "{worst}");
+}
+
+#[test]
+fn a_slow_reset_is_not_a_drop_of_the_new_trial() {
+    let (mut app, handle) = common::headless_app("test_frame_drops_slow_reset");
+    common::reset(&mut app, &handle);
+    frame(&mut app, 1000.0 / 60.0);
+
+    // The reset frame stalls (a long setup_round): the delta after it spans the hitch
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(120)));
+    common::reset(&mut app, &handle);
+    frame(&mut app, 120.0);
+    let (late, dropped, worst) = emitted(&handle);
+    assert_eq!((late, dropped), (0, 0));
+    assert_eq!(worst, 0.0);
+
+    // Later long frames of the trial still count
+    frame(&mut app, 1000.0 / 60.0);
+    frame(&mut app, 40.0);
+    let (late, dropped, worst) = emitted(&handle);
+    assert_eq!((late, dropped), (0, 1));
+    assert!((worst - 40.0).abs() < 0.01, "{worst}");
+}
@@ -0,0 +1,49 @@
+//! Game heartbeat: advances every frame, also while paused, and starts over when a restarted game
+//! recreates the segment, as seen by a controller that opened it.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, SharedMemResource};
+use game_node::state_emitter::StateEmitterPlugin;
+use game_node::utils::objects::RoundStartTimestamp;
+use shared::{create_shared_memory, open_shared_memory, SharedMemoryHandle};
+
+fn game(handle: &SharedMemoryHandle) -> App {
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(RoundStartTimestamp::default())
+        .add_plugins((CommandHandlerPlugin, StateEmitterPlugin));
+    app
+}
+
+#[test]
+fn heartbeat_runs_while_paused_and_starts_over_on_restart() {
+    let name = "test_game_heartbeat";
+    let handle = create_shared_memory(name).expect("shared memory");
+    let controller = open_shared_memory(name).expect("open the game's segment");
+    let heartbeat = || controller.get().game_structure_game.game_heartbeat.load(Ordering::Relaxed);
+
+    let mut app = game(&handle);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(heartbeat(), 3);
+
+    handle.get().commands.stop_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    app.update();
+    assert_eq!(handle.get().game_structure_game.frame_number.load(Ordering::Relaxed), 3, "frames hold");
+    assert_eq!(heartbeat(), 5, "heartbeat does not");
+
+    // The game restarts: a new process recreates the segment and counts from 0 again
+    drop(app);
+    drop(handle);
+    let handle = create_shared_memory(name).expect("shared memory");
+    let mut app = game(&handle);
+    app.update();
+    // Recreated in place, so the old mapping sees it too; a reopened one does in any case
+    let reopened = open_shared_memory(name).expect("reopen");
+    assert_eq!(reopened.get().game_structure_game.game_heartbeat.load(Ordering::Relaxed), 1);
+    assert_eq!(heartbeat(), 1, "the heartbeat starts over");
+}
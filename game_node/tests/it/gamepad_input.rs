@@ -0,0 +1,87 @@
+//! Browser gamepad: the stick moves the camera like the rotate and zoom buttons once past the dead
+//! zone, the check button requests one check per press, and the connection is emitted. The browser
+//! polling is replaced by setting the reading it writes.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{
+    CommandHandlerPlugin, PendingCheckAlignment, PendingRotation, PendingZoom, SharedMemResource,
+};
+use game_node::web_adapter::{GamepadInput, WebAdapterPlugin};
+use shared::constants::camera_3d_constants::{GAMEPAD_DEAD_ZONE, GAMEPAD_ROTATE_GAIN, GAMEPAD_ZOOM_GAIN};
+use shared::create_shared_memory;
+
+fn pending(app: &App) -> (f32, f32, bool) {
+    let world = app.world();
+    (
+        world.resource::<PendingRotation>().0,
+        world.resource::<PendingZoom>().0,
+        world.resource::<PendingCheckAlignment>().0,
+    )
+}
+
+fn set_pad(app: &mut App, rotate: f32, zoom: f32, check: bool) {
+    let mut gamepad = app.world_mut().resource_mut::<GamepadInput>();
+    gamepad.pad = Some("test pad".to_string());
+    (gamepad.rotate_axis, gamepad.zoom_axis, gamepad.check_button) = (rotate, zoom, check);
+}
+
+#[test]
+fn gamepad_drives_the_pending_commands() {
+    let handle = create_shared_memory("test_gamepad_input").expect("shared memory");
+    let commands = &handle.get().commands;
+    let gs_game = &handle.get().game_structure_game;
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((MinimalPlugins, CommandHandlerPlugin, WebAdapterPlugin));
+    app.update();
+    assert!(!gs_game.gamepad_connected.load(Ordering::Relaxed));
+
+    // Button speeds to compare against
+    commands.rotate_right.store(true, Ordering::Relaxed);
+    commands.zoom_out.store(true, Ordering::Relaxed);
+    app.update();
+    let (rotate_speed, zoom_speed, _) = pending(&app);
+    commands.rotate_right.store(false, Ordering::Relaxed);
+    commands.zoom_out.store(false, Ordering::Relaxed);
+    assert!(rotate_speed > 0.0 && zoom_speed > 0.0);
+
+    // Full deflection moves at the gain times the button speed, up zooms in
+    set_pad(&mut app, 1.0, -1.0, false);
+    app.update();
+    assert!(gs_game.gamepad_connected.load(Ordering::Relaxed));
+    let (rotation, zoom, check) = pending(&app);
+    assert!((rotation - GAMEPAD_ROTATE_GAIN * rotate_speed).abs() < 1e-6);
+    assert!((zoom + GAMEPAD_ZOOM_GAIN * zoom_speed).abs() < 1e-6);
+    assert!(!check);
+
+    // Inside the dead zone nothing moves, past it motion starts from 0
+    set_pad(&mut app, GAMEPAD_DEAD_ZONE * 0.9, -GAMEPAD_DEAD_ZONE, false);
+    app.update();
+    assert_eq!(pending(&app), (0.0, 0.0, false));
+    set_pad(&mut app, -(GAMEPAD_DEAD_ZONE + 0.01), 0.0, false);
+    app.update();
+    let (rotation, ..) = pending(&app);
+    assert!(rotation < 0.0 && rotation.abs() < 0.05 * rotate_speed);
+
+    // A held button checks once, a new press checks again
+    let checks: Vec<bool> = [true, true, true, false, true]
+        .into_iter()
+        .map(|pressed| {
+            set_pad(&mut app, 0.0, 0.0, pressed);
+            app.update();
+            pending(&app).2
+        })
+        .collect();
+    assert_eq!(checks, [true, false, false, false, true]);
+
+    // Unplugged: no input, flag cleared
+    {
+        let mut gamepad = app.world_mut().resource_mut::<GamepadInput>();
+        gamepad.pad = None;
+        gamepad.rotate_axis = 1.0;
+    }
+    app.update();
+    assert_eq!(pending(&app), (0.0, 0.0, false));
+    assert!(!gs_game.gamepad_connected.load(Ordering::Relaxed));
+}
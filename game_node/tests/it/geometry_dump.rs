@@ -0,0 +1,71 @@
+//! Geometry dump: `dump_geometry` writes the face and decoration meshes on screen with their
+//! transforms, once per request, in a file that reads back to the exact same geometry.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::geometry_dump::{GeometryDump, GeometryDumpDir};
+use game_node::utils::objects::{DecorationMarker, FaceMarker};
+use shared::{SeenCommand, SharedMemoryHandle};
+
+/// Requests a dump and returns the files in `dir` after the next frame
+fn dump(app: &mut App, handle: &SharedMemoryHandle, dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    handle.get().commands.dump_geometry.store(true, Ordering::Relaxed);
+    app.update();
+    assert!(!handle.get().commands.dump_geometry.load(Ordering::Relaxed), "consumed on read");
+    let mut files: Vec<_> = std::fs::read_dir(dir).map(|entries| entries.map(|e| e.unwrap().path()).collect()).unwrap_or_default();
+    files.sort();
+    files
+}
+
+#[test]
+fn dumps_read_back_to_the_geometry_on_screen() {
+    let dir = std::env::temp_dir().join("test_geometry_dump");
+    let _ = std::fs::remove_dir_all(&dir);
+    let (mut app, handle) = common::headless_app("test_geometry_dump");
+    app.insert_resource(GeometryDumpDir(dir.clone()));
+
+    // Nothing to dump before the first trial
+    assert!(dump(&mut app, &handle, &dir).is_empty());
+
+    common::reset(&mut app, &handle);
+    app.update();
+    let files = dump(&mut app, &handle, &dir);
+    assert_eq!(files.len(), 1);
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(gs_game.commands_seen_counts[SeenCommand::DumpGeometry as usize].load(Ordering::Relaxed), 2);
+
+    let text = std::fs::read_to_string(&files[0]).unwrap();
+    let dumped = GeometryDump::from_json(&text).expect("the dump reads back");
+    assert_eq!(GeometryDump::from_json(&dumped.to_json()).unwrap(), dumped);
+    assert_eq!(dumped.trial, gs_game.reset_ack.load(Ordering::Acquire));
+    assert_eq!(dumped.stimulus_hash, gs_game.stimulus_hash.load(Ordering::Relaxed));
+    assert_eq!(files[0].file_name().unwrap().to_str().unwrap(), dumped.file_name());
+
+    // Every face quad, with a normal per vertex
+    assert_eq!(dumped.faces.iter().map(|f| f.face).collect::<Vec<_>>(), [0, 1, 2]);
+    let world = app.world_mut();
+    for (marker, transform) in world.query::<(&FaceMarker, &Transform)>().iter(world) {
+        let face = &dumped.faces[marker.face_index];
+        assert_eq!((face.mesh.positions.len(), face.mesh.normals.len(), face.mesh.indices.len()), (4, 4, 6));
+        assert!(face.mesh.normals.iter().all(|n| *n == face.mesh.normals[0]), "flat face");
+        assert_eq!(face.transform.to_transform(), *transform);
+    }
+
+    // Every decoration, at the exact place it was spawned
+    let mut spawned: Vec<_> = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(marker, transform)| (marker.face_index as u32, transform.translation.to_array()))
+        .collect();
+    spawned.sort_by(|a, b| (a.0, a.1).partial_cmp(&(b.0, b.1)).unwrap());
+    let dumped_decorations: Vec<_> = dumped.decorations.iter().map(|d| (d.face, d.transform.translation)).collect();
+    assert!(!spawned.is_empty());
+    assert_eq!(dumped_decorations, spawned);
+    assert!(dumped.decorations.iter().all(|d| !d.mesh.positions.is_empty() && d.mesh.indices.len() % 3 == 0));
+
+    // A broken mesh is refused
+    let mut broken = dumped.clone();
+    broken.faces[0].mesh.indices[0] = 99;
+    assert!(GeometryDump::from_json(&broken.to_json()).is_err());
+}
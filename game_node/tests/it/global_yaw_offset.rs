@@ -0,0 +1,75 @@
+//! Global yaw offset: turns the live pyramid (and the next round's) on top of the controls, while
+//! the emitted `pyramid_yaw` stays the yaw from the controls alone.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::Pyramid;
+use shared::SharedMemoryHandle;
+
+/// Yaw of the pyramid entities, which all turn together
+fn pyramid_yaw(app: &mut App) -> f32 {
+    let world = app.world_mut();
+    let yaws: Vec<f32> = world
+        .query_filtered::<&Transform, With<Pyramid>>()
+        .iter(world)
+        .map(|transform| transform.rotation.to_euler(EulerRot::YXZ).0)
+        .collect();
+    assert!(!yaws.is_empty());
+    assert!(yaws.iter().all(|yaw| (yaw - yaws[0]).abs() < 1e-5), "pyramid entities disagree: {yaws:?}");
+    yaws[0]
+}
+
+fn emitted(handle: &SharedMemoryHandle) -> (f32, f32) {
+    let gs_game = &handle.get().game_structure_game;
+    (
+        f32::from_bits(gs_game.pyramid_yaw.load(Ordering::Relaxed)),
+        f32::from_bits(gs_game.global_yaw_offset.load(Ordering::Relaxed)),
+    )
+}
+
+fn set_offset(app: &mut App, handle: &SharedMemoryHandle, radians: f32) {
+    handle.get().commands.global_yaw_offset.store(radians.to_bits(), Ordering::Relaxed);
+    app.update();
+    app.update();
+}
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+}
+
+#[test]
+fn offset_turns_the_pyramid_but_not_the_logged_yaw() {
+    let (mut app, handle) = common::headless_app("test_global_yaw_offset");
+    common::reset(&mut app, &handle);
+    app.update();
+    let start = pyramid_yaw(&mut app);
+
+    set_offset(&mut app, &handle, 0.5);
+    assert_near(pyramid_yaw(&mut app), start + 0.5);
+    let (yaw, offset) = emitted(&handle);
+    assert_near(yaw, start);
+    assert_near(offset, 0.5);
+
+    // Controls turn on top of the offset, the emitted yaw follows the controls only
+    let commands = &handle.get().commands;
+    commands.rotate_right.store(true, Ordering::Relaxed);
+    app.update();
+    commands.rotate_right.store(false, Ordering::Relaxed);
+    app.update();
+    let (turned, _) = emitted(&handle);
+    assert!((turned - start).abs() > 1e-3, "the controls turned the pyramid");
+    assert_near(pyramid_yaw(&mut app), turned + 0.5);
+
+    // Changed live: only the difference is applied
+    set_offset(&mut app, &handle, -0.25);
+    assert_near(pyramid_yaw(&mut app), turned - 0.25);
+    assert_near(emitted(&handle).0, turned);
+
+    // The next round's pyramid gets the offset as well, with the controls' yaw back at the start
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_near(pyramid_yaw(&mut app), start - 0.25);
+    assert_near(emitted(&handle).0, start);
+    assert_near(emitted(&handle).1, -0.25);
+}
@@ -0,0 +1,41 @@
+//! Command to state pipeline end to end: a reset config written to the controller region shows
+//! up in the game state, and a check aligned with the target wins.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+use shared::Phase;
+
+#[test]
+fn reset_config_reaches_state_and_aligned_check_wins() {
+    let (mut app, handle) = common::headless_app("test_headless_round");
+    let shm = handle.get();
+    let gs_ctrl = &shm.game_structure_control;
+    gs_ctrl.decoration_seeds[0].store(42, Ordering::Relaxed);
+    gs_ctrl.target_door.store(2, Ordering::Relaxed);
+    gs_ctrl.base_radius.store(2.5f32.to_bits(), Ordering::Relaxed);
+    gs_ctrl.cosine_alignment_threshold.store(0.99f32.to_bits(), Ordering::Relaxed);
+
+    common::reset(&mut app, &handle);
+    const FRAMES: u64 = 5;
+    for _ in 0..FRAMES {
+        app.update();
+    }
+
+    let gs_game = &shm.game_structure_game;
+    assert_eq!(gs_game.reset_ack.load(Ordering::Acquire), 1);
+    assert_eq!(gs_game.frame_number.load(Ordering::Relaxed), FRAMES + 1, "one state per frame since the reset");
+    assert_eq!(gs_game.decoration_seeds[0].load(Ordering::Relaxed), 42);
+    assert_eq!(gs_game.target_door.load(Ordering::Relaxed), 2);
+    assert_eq!(f32::from_bits(gs_game.base_radius.load(Ordering::Relaxed)), 2.5);
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0, "nothing won yet");
+    assert_eq!(gs_game.num_faces.load(Ordering::Relaxed), 3);
+    assert_eq!(gs_game.num_doors.load(Ordering::Relaxed), BASE_NR_SIDES as u32);
+
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1);
+    assert!(f32::from_bits(gs_game.current_alignment.load(Ordering::Relaxed)) > 0.999);
+    assert!(f32::from_bits(gs_game.win_time.load(Ordering::Relaxed)) > 0.0, "aligned check wins");
+    assert_eq!(Phase::from_u32(gs_game.phase.load(Ordering::Relaxed)), Some(Phase::Won));
+}
@@ -0,0 +1,103 @@
+//! The `--headless` game binary driven through a scripted win over shared memory only, the way a
+//! CI job or batch verification script would. Frames are stepped one at a time while paused, so
+//! the script is deterministic despite the game running on its own clock.
+use crate::common;
+use core::sync::atomic::Ordering;
+use shared::{open_shared_memory, shared_memory_path, Phase, SharedMemory};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+const SHM_NAME: &str = "test_headless_scripted_win";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Kills the game when the test ends, including on a failed assert
+struct Game(Child);
+
+impl Drop for Game {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn wait_until(what: &str, mut condition: impl FnMut() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < TIMEOUT, "timed out waiting for {what}");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Advances the paused game by exactly one frame and waits for its state
+fn step(shm: &SharedMemory) {
+    let frame = shm.game_structure_game.frame_number.load(Ordering::Relaxed);
+    shm.commands.step_frame.store(true, Ordering::Relaxed);
+    wait_until("a stepped frame", || shm.game_structure_game.frame_number.load(Ordering::Relaxed) > frame);
+}
+
+fn alignment(shm: &SharedMemory) -> f32 {
+    f32::from_bits(shm.game_structure_game.current_alignment.load(Ordering::Relaxed))
+}
+
+#[test]
+fn headless_binary_plays_a_scripted_win() {
+    // The game creates the region, a stale one from an earlier run must not be mapped meanwhile
+    let _ = std::fs::remove_file(shared_memory_path(SHM_NAME));
+    let _game = Game(
+        Command::new(env!("CARGO_BIN_EXE_game_node"))
+            .args(["--headless", "--shm-name", SHM_NAME])
+            .arg("--log-dir")
+            .arg(common::log_dir())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("game binary"),
+    );
+    let mut handle = None;
+    wait_until("the game's shared memory", || {
+        handle = open_shared_memory(SHM_NAME).ok();
+        handle.is_some()
+    });
+    let handle = handle.unwrap();
+    let shm = handle.get();
+    wait_until("the game to emit frames", || shm.game_structure_game.frame_number.load(Ordering::Relaxed) > 0);
+
+    let gs_ctrl = &shm.game_structure_control;
+    gs_ctrl.target_door.store(1, Ordering::Relaxed);
+    gs_ctrl.cosine_alignment_threshold.store(0.95f32.to_bits(), Ordering::Relaxed);
+    let ack = shm.game_structure_game.reset_ack.load(Ordering::Acquire);
+    shm.commands.reset.store(true, Ordering::Release);
+    wait_until("the reset ack", || shm.game_structure_game.reset_ack.load(Ordering::Acquire) > ack);
+    shm.commands.reset.store(false, Ordering::Release);
+    assert_eq!(shm.game_structure_game.target_door.load(Ordering::Relaxed), 1);
+
+    // Lockstep from here on
+    shm.commands.stop_rendering.store(true, Ordering::Relaxed);
+    step(shm);
+    let start_alignment = alignment(shm);
+
+    // Turn until the target door is in front: one rotation step per frame, a full turn at most
+    shm.commands.rotate_right.store(true, Ordering::Relaxed);
+    let mut steps = 0;
+    while alignment(shm) < 0.99 {
+        assert!(steps < 200, "never aligned with the target (last alignment {})", alignment(shm));
+        step(shm);
+        steps += 1;
+    }
+    shm.commands.rotate_right.store(false, Ordering::Relaxed);
+    assert!(steps > 0 || start_alignment >= 0.99);
+
+    // One check, on one frame
+    shm.commands.check_alignment.store(true, Ordering::Relaxed);
+    step(shm);
+    shm.commands.check_alignment.store(false, Ordering::Relaxed);
+
+    let gs_game = &shm.game_structure_game;
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1);
+    assert!(alignment(shm) >= 0.95, "still aligned at the check");
+    assert!(f32::from_bits(gs_game.win_time.load(Ordering::Relaxed)) > 0.0, "aligned check wins");
+    assert_eq!(Phase::from_u32(gs_game.phase.load(Ordering::Relaxed)), Some(Phase::Won));
+
+    step(shm);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1, "the check counted once");
+}
@@ -0,0 +1,67 @@
+//! Input lock: rotation and zoom commands are ignored from a decided check, through its feedback
+//! delay, door animation and the inter-trial blank, and apply again once the next trial plays.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::PersistentCamera;
+use shared::{Phase, SharedMemoryHandle};
+use std::time::Duration;
+
+/// Emitted pyramid yaw and camera position
+fn view(app: &mut App, handle: &SharedMemoryHandle) -> (f32, Vec3) {
+    let yaw = f32::from_bits(handle.get().game_structure_game.pyramid_yaw.load(Ordering::Relaxed));
+    let world = app.world_mut();
+    let camera = world.query_filtered::<&Transform, With<PersistentCamera>>().single(world).unwrap().translation;
+    (yaw, camera)
+}
+
+fn hold_controls(handle: &SharedMemoryHandle, held: bool) {
+    let commands = &handle.get().commands;
+    commands.rotate_right.store(held, Ordering::Relaxed);
+    commands.zoom_in.store(held, Ordering::Relaxed);
+}
+
+#[test]
+fn controls_are_ignored_while_locked() {
+    let (mut app, handle) = common::headless_app("test_inputs_locked");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_game = &handle.get().game_structure_game;
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.feedback_delay_ms.store(500, Ordering::Relaxed);
+    gs_ctrl.iti_frames.store(10, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    common::face_target(&mut app);
+    app.update();
+    assert!(!gs_game.inputs_locked.load(Ordering::Relaxed));
+
+    common::check_alignment(&mut app, &handle);
+    assert!(gs_game.inputs_locked.load(Ordering::Relaxed), "locked from the decided check");
+    let locked_view = view(&mut app, &handle);
+
+    // Feedback delay, door animation and blank: the held controls do nothing
+    hold_controls(&handle, true);
+    let mut phases = Vec::new();
+    for _ in 0..1000 {
+        app.update();
+        let phase = gs_game.phase.load(Ordering::Relaxed);
+        if phase == Phase::Playing as u32 {
+            break;
+        }
+        phases.push(phase);
+        assert!(gs_game.inputs_locked.load(Ordering::Relaxed));
+        assert_eq!(view(&mut app, &handle), locked_view, "view moved while locked");
+    }
+    assert!(phases.contains(&(Phase::Won as u32)) && phases.contains(&(Phase::InterTrial as u32)), "{phases:?}");
+
+    // The next trial plays: the lock lifts and the controls apply again
+    assert!(!gs_game.inputs_locked.load(Ordering::Relaxed));
+    let free_view = view(&mut app, &handle);
+    app.update();
+    hold_controls(&handle, false);
+    let moved = view(&mut app, &handle);
+    assert!((moved.0 - free_view.0).abs() > 1e-4, "rotation applies once unlocked");
+    assert!(moved.1.distance(free_view.1) > 1e-4, "zoom applies once unlocked");
+}
@@ -0,0 +1,158 @@
+//! Inter-trial interval run by the game: blank for iti_frames fixed ticks after a won or failed
+//! trial, then back to playing, into a queued next trial, or held until reset.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::systems_logic::BlankScreenState;
+use shared::{Phase, PostWinMode, SharedMemoryHandle};
+
+/// Each update advances the clock by `ticks` fixed timesteps, i.e. renders at 1/`ticks` the fixed rate
+fn ticks_per_update(app: &mut App, ticks: u32) {
+    let timestep = app.world().resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep * ticks));
+}
+
+fn headless_app(name: &str, mode: PostWinMode, iti_frames: u32) -> (App, SharedMemoryHandle) {
+    let (mut app, handle) = common::headless_app(name);
+    ticks_per_update(&mut app, 1);
+    common::reset(&mut app, &handle);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    gs_game.iti_frames.store(iti_frames, Ordering::Relaxed);
+    gs_game.post_win_mode.store(mode as u32, Ordering::Relaxed);
+    (app, handle)
+}
+
+fn phase(handle: &SharedMemoryHandle) -> Phase {
+    Phase::from_u32(handle.get().game_structure_game.phase.load(Ordering::Relaxed)).unwrap()
+}
+
+fn blanked(app: &App) -> bool {
+    app.world().resource::<BlankScreenState>().is_active
+}
+
+/// Steps until the interval starts, then counts the frames spent blank in it (up to `limit`)
+fn frames_in_interval(app: &mut App, handle: &SharedMemoryHandle, limit: u32) -> u32 {
+    for _ in 0..3 {
+        app.update();
+        if phase(handle) == Phase::InterTrial {
+            break;
+        }
+    }
+    assert_eq!(phase(handle), Phase::InterTrial);
+    let mut frames = 0;
+    while phase(handle) == Phase::InterTrial && frames < limit {
+        assert!(blanked(app));
+        app.update();
+        frames += 1;
+    }
+    frames
+}
+
+#[test]
+fn win_returns_to_playing_after_iti() {
+    let (mut app, handle) = headless_app("test_iti_auto", PostWinMode::AutoReturn, 3);
+    handle.get().game_structure_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    assert_eq!(frames_in_interval(&mut app, &handle, 100), 3, "blank for exactly iti_frames");
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert!(!blanked(&app));
+
+    // The same win does not start another interval
+    app.update();
+    assert_eq!(phase(&handle), Phase::Playing);
+}
+
+#[test]
+fn interval_counts_fixed_ticks_not_rendered_frames() {
+    let (mut app, handle) = headless_app("test_iti_fixed_ticks", PostWinMode::AutoReturn, 6);
+    let gs_game = &handle.get().game_structure_game;
+    // Rendering at half the fixed rate, two ticks pass per frame
+    ticks_per_update(&mut app, 2);
+    gs_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    assert_eq!(frames_in_interval(&mut app, &handle, 100), 3, "6 ticks in 3 frames");
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert_eq!(gs_game.iti_remaining_frames.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn remaining_ticks_are_emitted_during_the_interval() {
+    let (mut app, handle) = headless_app("test_iti_remaining", PostWinMode::AutoReturn, 4);
+    let gs_game = &handle.get().game_structure_game;
+    gs_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    let mut remaining = Vec::new();
+    for _ in 0..10 {
+        app.update();
+        if phase(&handle) == Phase::InterTrial {
+            remaining.push(gs_game.iti_remaining_frames.load(Ordering::Relaxed));
+        }
+    }
+    assert_eq!(remaining, [4, 3, 2, 1]);
+}
+
+#[test]
+fn failed_trial_applies_queued_next_trial() {
+    let (mut app, handle) = headless_app("test_iti_queue", PostWinMode::HoldBlank, 2);
+    let shm = handle.get();
+    shm.game_structure_game.max_attempts.store(1, Ordering::Relaxed);
+    shm.game_structure_game.attempts.store(1, Ordering::Relaxed);
+    app.update();
+    assert_eq!(phase(&handle), Phase::Failed);
+
+    // Staged during the interval, applied as a reset once it ends
+    let reset_ack = shm.game_structure_game.reset_ack.load(Ordering::Acquire);
+    shm.commands.queue_next.store(true, Ordering::Relaxed);
+    let frames = frames_in_interval(&mut app, &handle, 100);
+    assert_eq!(frames, 2, "the full interval ran before the next trial");
+    assert_eq!(phase(&handle), Phase::Resetting, "on the frame the queued reset lands");
+    assert!(blanked(&app), "still blank until the next trial plays");
+    app.update();
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert!(!blanked(&app));
+    assert_eq!(shm.game_structure_game.reset_ack.load(Ordering::Acquire), reset_ack + 1);
+    assert_eq!(shm.game_structure_game.attempts.load(Ordering::Relaxed), 0, "next trial loaded");
+}
+
+#[test]
+fn hold_blank_waits_for_reset() {
+    let (mut app, handle) = headless_app("test_iti_hold", PostWinMode::HoldBlank, 3);
+    let shm = handle.get();
+    shm.game_structure_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    assert_eq!(frames_in_interval(&mut app, &handle, 20), 20, "held well past iti_frames");
+    assert!(blanked(&app));
+
+    shm.commands.reset.store(true, Ordering::Release);
+    app.update();
+    shm.commands.reset.store(false, Ordering::Release);
+    app.update();
+    app.update();
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert!(!blanked(&app));
+}
+
+#[test]
+fn failed_trial_stays_unwon_through_its_interval() {
+    let (mut app, handle) = headless_app("test_iti_failed_unwon", PostWinMode::HoldBlank, 3);
+    let gs_game = &handle.get().game_structure_game;
+    gs_game.shaping_dwell_secs.store(0.05f32.to_bits(), Ordering::Relaxed);
+    gs_game.max_attempts.store(1, Ordering::Relaxed);
+    gs_game.attempts.store(1, Ordering::Relaxed);
+    app.update();
+    assert_eq!(phase(&handle), Phase::Failed);
+
+    // Aligned under the held blank, long past the dwell, and checking too
+    common::face_target(&mut app);
+    for _ in 0..30 {
+        app.update();
+    }
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    assert_eq!(phase(&handle), Phase::InterTrial);
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0, "the failed trial was won");
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1, "the check was counted");
+}
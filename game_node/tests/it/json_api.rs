@@ -0,0 +1,101 @@
+//! JSON controller API: the state is keyed like the Python state dictionary, config writes are
+//! partial and validated as a whole, and commands dispatch to the controller methods by name.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use serde_json::Value;
+use shared::json_api::{apply_command_json, apply_config_json, config_json, state_json};
+use shared::HIDE_IMAGE;
+
+#[test]
+fn config_writes_are_partial_and_validated() {
+    let handle = shared::create_shared_memory("test_json_api_config").expect("shared memory");
+    let shm = handle.get();
+    let gs_ctrl = &shm.game_structure_control;
+    let before = config_json(gs_ctrl);
+
+    apply_config_json(shm, r#"{"base_radius": 3.5, "colors": [[1, 0, 0, 1], [0, 1, 0, 1], [0, 0, 1, 1]], "trial_id": "a-1"}"#).unwrap();
+    let after = config_json(gs_ctrl);
+    assert_eq!(after["base_radius"], 3.5);
+    assert_eq!(after["colors"][1], serde_json::json!([0.0, 1.0, 0.0, 1.0]));
+    assert_eq!(after["trial_id_hash"], shared::trial_id_hash("a-1"));
+    // Keys left out are kept
+    assert_eq!(after["height"], before["height"]);
+
+    // A rejected write leaves the whole config untouched
+    for bad in [
+        r#"{"height": 2.0, "target_face": 3}"#,
+        r#"{"height": 2.0, "colors": [[1, 0, 0, 1]]}"#,
+        r#"{"height": 2.0, "no_such_key": 1}"#,
+        r#"{"height": 2.0, "camera_fov_degrees": 150.0}"#,
+        r#"{"height": 2.0, "door_hole_radius_fraction": 1.2}"#,
+        r#"{"height": 2.0, "projection_mode": 1, "ortho_scale_range": [2.0, 1.0]}"#,
+        r#"{"height": "tall"}"#,
+        r#"[1, 2]"#,
+    ] {
+        assert!(apply_config_json(shm, bad).is_err(), "{bad} accepted");
+        assert_eq!(config_json(gs_ctrl), after);
+    }
+
+    // Staged writes go to the staged block
+    apply_config_json(shm, r#"{"height": 1.25, "staged": true}"#).unwrap();
+    assert_eq!(config_json(&shm.game_structure_staged)["height"], 1.25);
+    assert_eq!(config_json(gs_ctrl)["height"], after["height"]);
+}
+
+#[test]
+fn commands_dispatch_by_method() {
+    let handle = shared::create_shared_memory("test_json_api_commands").expect("shared memory");
+    let shm = handle.get();
+    let cmd = &shm.commands;
+    let heartbeat = cmd.heartbeat.load(Ordering::Relaxed);
+
+    apply_command_json(shm, r#"{"method": "write_commands", "rotate_left": true, "queue_next": true}"#).unwrap();
+    assert!(cmd.rotate_left.load(Ordering::Relaxed));
+    assert!(cmd.queue_next.load(Ordering::Relaxed));
+    assert_eq!(cmd.heartbeat.load(Ordering::Relaxed), heartbeat + 1);
+    // Flags left out are false
+    apply_command_json(shm, r#"{"method": "write_commands", "zoom_in": true}"#).unwrap();
+    assert!(!cmd.rotate_left.load(Ordering::Relaxed));
+    assert!(cmd.zoom_in.load(Ordering::Relaxed));
+
+    apply_command_json(shm, r#"{"method": "start_rotation", "direction": -1}"#).unwrap();
+    assert!(cmd.rotate_start_left.load(Ordering::Relaxed));
+    apply_command_json(shm, r#"{"method": "stop_rotation"}"#).unwrap();
+    assert!(!cmd.rotate_start_left.load(Ordering::Relaxed) && cmd.rotate_stop.load(Ordering::Relaxed));
+    apply_command_json(shm, r#"{"method": "hide_reference_image"}"#).unwrap();
+    assert_eq!(cmd.show_image.load(Ordering::Relaxed), HIDE_IMAGE);
+
+    for bad in [
+        r#"{"method": "start_zoom", "direction": 2}"#,
+        r#"{"method": "write_commands", "rotate": true}"#,
+        r#"{"method": "launch"}"#,
+        r#"{"rotate_left": true}"#,
+    ] {
+        assert!(apply_command_json(shm, bad).is_err(), "{bad} accepted");
+    }
+}
+
+#[test]
+fn state_matches_the_game() {
+    let (mut app, handle) = common::headless_app("test_json_api_state");
+    common::reset(&mut app, &handle);
+    app.update();
+
+    let gs = &handle.get().game_structure_game;
+    let state = state_json(handle.get());
+    assert_eq!(state["frame_number"], gs.frame_number.load(Ordering::Relaxed));
+    assert_eq!(state["reset_ack"], gs.reset_ack.load(Ordering::Relaxed));
+    assert_eq!(state["base_radius"], state["applied"]["base_radius"]);
+    assert!(state["applied"]["decorations_placed"].is_array());
+    assert!(state["requested"].is_object());
+    // No reference image shown, not won yet
+    assert_eq!(state["reference_image"], Value::Null);
+    assert_eq!(state["win_elapsed_secs"], 0.0);
+
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    let state = state_json(handle.get());
+    assert_eq!(state["win_elapsed_secs"], f32::from_bits(gs.win_time.load(Ordering::Relaxed)));
+    assert!(state["win_elapsed_secs"].as_f64().unwrap() > 0.0);
+}
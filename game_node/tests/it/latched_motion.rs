@@ -0,0 +1,87 @@
+//! Opt-in latched rotation/zoom: keeps moving between controller writes until a stop or the
+//! heartbeat timeout, and does nothing for level-based controllers.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, PendingRotation, PendingZoom, SharedMemResource};
+use shared::constants::camera_3d_constants::{CAMERA_3D_SPEED_ROTATE, CAMERA_3D_SPEED_ZOOM};
+use shared::{create_shared_memory, SharedMemoryHandle};
+
+fn app(name: &str) -> (App, SharedMemoryHandle) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins(CommandHandlerPlugin);
+    app.update();
+    (app, handle)
+}
+
+fn rotation(app: &App) -> f32 {
+    app.world().resource::<PendingRotation>().0
+}
+
+#[test]
+fn latched_rotation_runs_until_stop() {
+    let (mut app, handle) = app("test_latched_motion_stop");
+    let cmd = &handle.get().commands;
+    cmd.motion_latched.store(true, Ordering::Relaxed);
+    cmd.motion_latch_timeout_frames.store(0, Ordering::Relaxed);
+
+    cmd.rotate_start_left.store(true, Ordering::Relaxed);
+    cmd.zoom_start_out.store(true, Ordering::Relaxed);
+    for _ in 0..5 {
+        app.update();
+        assert_eq!(rotation(&app), -CAMERA_3D_SPEED_ROTATE, "keeps rotating without further writes");
+        assert_eq!(app.world().resource::<PendingZoom>().0, CAMERA_3D_SPEED_ZOOM);
+    }
+    assert!(!cmd.rotate_start_left.load(Ordering::Relaxed), "start consumed");
+    assert_eq!(handle.get().game_structure_game.latched_rotation.load(Ordering::Relaxed), -1);
+    assert_eq!(handle.get().game_structure_game.latched_zoom.load(Ordering::Relaxed), 1);
+
+    cmd.rotate_stop.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(rotation(&app), 0.0);
+    assert_eq!(handle.get().game_structure_game.latched_rotation.load(Ordering::Relaxed), 0);
+    assert_eq!(app.world().resource::<PendingZoom>().0, CAMERA_3D_SPEED_ZOOM, "zoom has its own stop");
+}
+
+#[test]
+fn latched_motion_stops_without_heartbeat() {
+    let (mut app, handle) = app("test_latched_motion_timeout");
+    let cmd = &handle.get().commands;
+    cmd.motion_latched.store(true, Ordering::Relaxed);
+    cmd.motion_latch_timeout_frames.store(3, Ordering::Relaxed);
+    cmd.rotate_start_right.store(true, Ordering::Relaxed);
+
+    // A live controller keeps it going
+    for _ in 0..10 {
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        app.update();
+        assert_eq!(rotation(&app), CAMERA_3D_SPEED_ROTATE);
+    }
+
+    // A silent one stops it after the timeout
+    let mut frames = 0;
+    while rotation(&app) != 0.0 {
+        app.update();
+        frames += 1;
+        assert!(frames <= 3, "stopped within the timeout");
+    }
+    assert_eq!(frames, 3);
+}
+
+#[test]
+fn starts_are_ignored_unless_opted_in() {
+    let (mut app, handle) = app("test_latched_motion_opt_in");
+    let cmd = &handle.get().commands;
+    cmd.rotate_start_left.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(rotation(&app), 0.0);
+    assert!(!cmd.rotate_start_left.load(Ordering::Relaxed), "dropped, not kept for later");
+
+    // Level commands work as before
+    cmd.rotate_right.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(rotation(&app), CAMERA_3D_SPEED_ROTATE);
+}
@@ -0,0 +1,78 @@
+//! Live hot/cold feedback: the bar fills with the target alignment scaled by the gain, hides during
+//! blanking, reports whether it is shown, is absent unless enabled, and the best-facing door is emitted.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::game_functions::live_feedback_level;
+use game_node::utils::objects::{LiveFeedbackBar, LiveFeedbackFill, PersistentCamera};
+use game_node::utils::systems_logic::BlankScreenState;
+use shared::SharedMemoryHandle;
+
+/// Runs one fixed step, where the bar is driven, whatever the wall clock did
+fn fixed_step(app: &mut App) {
+    app.update();
+    app.world_mut().run_schedule(FixedUpdate);
+}
+
+fn level(app: &mut App) -> f32 {
+    let world = app.world_mut();
+    world.query::<&LiveFeedbackFill>().single(world).unwrap().level
+}
+
+fn feedback_app(name: &str, enabled: bool) -> (App, SharedMemoryHandle) {
+    let (mut app, handle) = common::headless_app(name);
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.live_feedback_enabled.store(enabled, Ordering::Relaxed);
+    gs_ctrl.live_feedback_gain.store(2.0_f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    (app, handle)
+}
+
+#[test]
+fn level_follows_the_alignment_and_gain() {
+    assert_eq!(live_feedback_level(1.0, 1.0), 1.0);
+    assert_eq!(live_feedback_level(0.5, 1.0), 0.5);
+    assert_eq!(live_feedback_level(0.5, 2.0), 0.0);
+    assert_eq!(live_feedback_level(-1.0, 1.0), 0.0, "clamped");
+}
+
+#[test]
+fn bar_tracks_the_target_and_hides_while_blanked() {
+    let (mut app, handle) = feedback_app("test_live_feedback", true);
+    let gs_game = &handle.get().game_structure_game;
+    assert!(gs_game.live_feedback_enabled.load(Ordering::Relaxed));
+
+    common::face_target(&mut app);
+    fixed_step(&mut app);
+    assert!((level(&mut app) - 1.0).abs() < 1e-3, "full when facing the target");
+    assert!(gs_game.live_feedback_visible.load(Ordering::Relaxed));
+    assert_eq!(gs_game.best_door.load(Ordering::Relaxed), gs_game.target_door.load(Ordering::Relaxed));
+
+    // Turned 30 degrees away, the bar drops twice as fast as the alignment at gain 2
+    let world = app.world_mut();
+    let mut camera = world.query_filtered::<&mut Transform, With<PersistentCamera>>().single_mut(world).unwrap();
+    camera.rotate_around(Vec3::ZERO, Quat::from_rotation_y(std::f32::consts::FRAC_PI_6));
+    fixed_step(&mut app);
+    assert!((level(&mut app) - live_feedback_level(30.0_f32.to_radians().cos(), 2.0)).abs() < 1e-3, "{}", level(&mut app));
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = true;
+    fixed_step(&mut app);
+    assert!(!gs_game.live_feedback_visible.load(Ordering::Relaxed));
+    let world = app.world_mut();
+    assert_eq!(*world.query_filtered::<&Visibility, With<LiveFeedbackBar>>().single(world).unwrap(), Visibility::Hidden);
+
+    app.world_mut().resource_mut::<BlankScreenState>().is_active = false;
+    fixed_step(&mut app);
+    assert!(gs_game.live_feedback_visible.load(Ordering::Relaxed));
+}
+
+#[test]
+fn no_bar_unless_enabled() {
+    let (mut app, handle) = feedback_app("test_live_feedback_off", false);
+    fixed_step(&mut app);
+    let world = app.world_mut();
+    assert_eq!(world.query::<&LiveFeedbackBar>().iter(world).count(), 0);
+    assert!(!handle.get().game_structure_game.live_feedback_visible.load(Ordering::Relaxed));
+}
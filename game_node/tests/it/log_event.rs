@@ -0,0 +1,43 @@
+//! Categorized log events: masks from names or numbers, and a controller set mask filtering what
+//! reaches the session log file.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use game_node::log_event;
+use game_node::utils::macros::{log_categories, session_log_path};
+use shared::{LogCategory, LOG_CATEGORIES_ALL, NO_LOG_CATEGORIES_CHANGE};
+
+#[test]
+fn parses_category_masks() {
+    let reset_shm = LogCategory::Reset.bit() | LogCategory::Shm.bit();
+    assert_eq!(LogCategory::parse_mask("reset, shm"), Ok(reset_shm));
+    assert_eq!(LogCategory::parse_mask(&reset_shm.to_string()), Ok(reset_shm));
+    assert_eq!(LogCategory::parse_mask("all"), Ok(LOG_CATEGORIES_ALL));
+    assert_eq!(LogCategory::parse_mask("none"), Ok(0));
+    assert!(LogCategory::parse_mask("resets").is_err());
+    assert!(LogCategory::parse_mask(&(LOG_CATEGORIES_ALL + 1).to_string()).is_err());
+    for category in LogCategory::ALL {
+        assert_eq!(LogCategory::from_u32(category as u32), Some(category));
+    }
+}
+
+#[test]
+fn controller_mask_filters_the_session_log() {
+    let (mut app, handle) = common::headless_app("test_log_event");
+    let commands = &handle.get().commands;
+
+    let mask = LogCategory::Reset.bit() | LogCategory::Error.bit();
+    commands.log_categories.store(mask, Ordering::Relaxed);
+    app.update();
+    assert_eq!(log_categories(), mask);
+    assert_eq!(commands.log_categories.load(Ordering::Relaxed), NO_LOG_CATEGORIES_CHANGE, "swapped back once read");
+
+    log_event!(commands, "filtered out {}", 1);
+    log_event!(reset, "kept {}", 2);
+    let path = session_log_path().expect("session log");
+    assert!(path.starts_with(std::env::temp_dir()), "tests log outside the working tree: {}", path.display());
+    let log = std::fs::read_to_string(path).unwrap();
+    assert!(!log.contains("filtered out 1"));
+    let line = log.lines().find(|line| line.ends_with("[reset] kept 2")).expect("reset event in the session log");
+    assert!(line.starts_with('[') && line.contains("s f"), "stamped with time and frame: {line}");
+}
@@ -0,0 +1,87 @@
+//! Integration tests of the game, one module per feature. They form a single test crate, so the
+//! game is linked once for all of them.
+
+mod common;
+
+mod alignment_geometry;
+mod applied_config;
+mod approach_direction;
+mod backdrop;
+mod camera_projection;
+mod catch_trial;
+mod check_requests;
+mod clear_attempts;
+mod cli;
+mod color_space;
+mod command_debounce;
+mod command_readback;
+mod config_dump;
+mod config_only;
+mod control_mirror;
+mod controller_watchdog;
+mod decoration_color_jitter;
+mod decoration_colors;
+mod decoration_count_range;
+mod decoration_determinism;
+mod decoration_motion;
+mod decoration_probe;
+mod decoration_spacing;
+mod decoration_spin;
+mod decoration_symmetry;
+mod display_rate;
+mod distance_fog;
+mod distractors;
+mod door_easing;
+mod door_geometry;
+mod door_reward_weights;
+mod experimenter_override;
+mod face_flicker;
+mod face_outline;
+mod feedback_delay;
+mod fixed_rate;
+mod frame_drops;
+mod game_heartbeat;
+mod gamepad_input;
+mod geometry_dump;
+mod global_yaw_offset;
+mod headless_round;
+mod headless_scripted_win;
+mod inputs_locked;
+mod inter_trial;
+mod json_api;
+mod latched_motion;
+mod live_feedback;
+mod log_event;
+mod min_view_time;
+mod mirror_x;
+mod orthographic_projection;
+mod pause_mode;
+mod pyramid_type_codes;
+mod pyramid_type_flip;
+mod randomized_geometry;
+mod recenter_on_reset;
+mod reference_image;
+mod render_quality;
+mod reset_ack;
+mod reset_profile;
+mod reticle_tint;
+mod scene_lights;
+mod score_bar;
+mod session_info;
+mod session_persistence;
+mod shaping_dwell;
+mod socket_commands;
+mod staged_config;
+mod standby;
+mod state_snapshot;
+mod status_http;
+mod step_frame;
+mod stimulus_description;
+mod stimulus_replay;
+mod tab_visibility;
+mod target_kind;
+mod threshold_cone;
+mod trial_id;
+mod ui_scale;
+mod win_glow;
+mod win_radius;
@@ -0,0 +1,68 @@
+//! Minimum viewing time: a perfectly aligned check before `min_view_secs` counts as an attempt but
+//! does not win, the same check once the time has passed does. The shaping dwell only counts from then.
+use crate::common;
+
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use std::time::Duration;
+
+#[test]
+fn early_check_does_not_win() {
+    let (mut app, handle) = common::headless_app("test_min_view_time");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_ctrl = &handle.get().game_structure_control;
+    let gs_game = &handle.get().game_structure_game;
+    gs_ctrl.min_view_secs.store(1.0f32.to_bits(), Ordering::Relaxed);
+
+    common::reset(&mut app, &handle);
+    app.update();
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    assert!(f32::from_bits(gs_game.current_alignment.load(Ordering::Relaxed)) > 0.999);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1);
+    assert!(gs_game.check_too_early.load(Ordering::Relaxed));
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0, "won before min_view_secs");
+
+    for _ in 0..10 {
+        app.update();
+    }
+    common::check_alignment(&mut app, &handle);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 2);
+    assert!(!gs_game.check_too_early.load(Ordering::Relaxed));
+    assert_ne!(gs_game.win_time.load(Ordering::Relaxed), 0);
+
+    // The flag is cleared by the next reset, and 0 disables the minimum
+    gs_ctrl.min_view_secs.store(0.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert!(!gs_game.check_too_early.load(Ordering::Relaxed));
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    assert!(!gs_game.check_too_early.load(Ordering::Relaxed));
+    assert_ne!(gs_game.win_time.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn shaping_dwell_counts_only_after_min_view_time() {
+    let (mut app, handle) = common::headless_app("test_min_view_time_dwell");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_ctrl = &handle.get().game_structure_control;
+    let gs_game = &handle.get().game_structure_game;
+    gs_ctrl.min_view_secs.store(1.0f32.to_bits(), Ordering::Relaxed);
+    gs_ctrl.shaping_dwell_secs.store(0.3f32.to_bits(), Ordering::Relaxed);
+
+    common::reset(&mut app, &handle);
+    app.update();
+    common::face_target(&mut app);
+    for _ in 0..8 {
+        app.update();
+    }
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0, "dwelt to a win before min_view_secs");
+    assert_eq!(f32::from_bits(gs_game.shaping_dwell_elapsed.load(Ordering::Relaxed)), 0.0);
+
+    for _ in 0..6 {
+        app.update();
+    }
+    assert_ne!(gs_game.win_time.load(Ordering::Relaxed), 0, "the dwell after min_view_secs did not win");
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,121 @@
+//! Mirror-image presentation: reflecting twice gives back the original transforms and meshes, a
+//! `mirror_x` round is the mirror image of the same trial unmirrored, and its emitted yaw is the
+//! unmirrored one.
+use crate::common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::mesh::{Indices, VertexAttributeValues};
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::SharedMemResource;
+use game_node::utils::game_functions::{target_normal, AlignmentTargets};
+use game_node::utils::mirror::{mirror_mesh, mirror_transform, mirror_vec};
+use game_node::utils::objects::{DecorationMarker, GameEntity, Mirrored, Pyramid};
+use shared::SharedMemoryHandle;
+
+fn positions(mesh: &Mesh) -> Vec<[f32; 3]> {
+    match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions.clone(),
+        _ => panic!("no positions"),
+    }
+}
+
+fn indices(mesh: &Mesh) -> Vec<u32> {
+    match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|&i| u32::from(i)).collect(),
+        Some(Indices::U32(indices)) => indices.clone(),
+        None => panic!("no indices"),
+    }
+}
+
+#[test]
+fn mirroring_twice_is_the_identity() {
+    let transform = Transform::from_xyz(1.0, 2.0, -3.0)
+        .with_rotation(Quat::from_euler(EulerRot::YXZ, 0.7, -0.3, 0.2))
+        .with_scale(Vec3::new(0.5, 1.0, 2.0));
+    let mirrored = mirror_transform(&transform);
+    assert_eq!(mirrored.translation, Vec3::new(-1.0, 2.0, -3.0));
+    let point = Vec3::new(0.3, -0.4, 0.9);
+    // The conjugated transform places the reflected point where the reflection of the placed point is
+    assert!(mirrored.transform_point(mirror_vec(point)).abs_diff_eq(mirror_vec(transform.transform_point(point)), 1e-5));
+    let back = mirror_transform(&mirrored);
+    assert!(back.translation.abs_diff_eq(transform.translation, 1e-6));
+    assert!(back.rotation.abs_diff_eq(transform.rotation, 1e-6));
+    assert_eq!(back.scale, transform.scale);
+
+    let mesh = Mesh::from(Cuboid::new(1.0, 2.0, 3.0)).translated_by(Vec3::new(0.5, 0.0, 0.0));
+    let mirrored = mirror_mesh(&mesh);
+    assert!(positions(&mirrored).iter().zip(positions(&mesh)).all(|(m, p)| *m == [-p[0], p[1], p[2]]));
+    assert_ne!(indices(&mirrored), indices(&mesh), "winding not reversed");
+    let back = mirror_mesh(&mirrored);
+    assert_eq!(positions(&back), positions(&mesh));
+    assert_eq!(indices(&back), indices(&mesh));
+}
+
+/// Target normal, decoration translations (sorted) and emitted yaw of a round
+fn play(app: &mut App, handle: &SharedMemoryHandle, mirror_x: bool) -> (Vec3, Vec<[f32; 3]>, f32) {
+    handle.get().game_structure_control.mirror_x.store(mirror_x, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+
+    // Turn it a bit, the same controls in both rounds
+    let commands = &handle.get().commands;
+    commands.rotate_right.store(true, Ordering::Relaxed);
+    for _ in 0..5 {
+        app.update();
+    }
+    commands.rotate_right.store(false, Ordering::Relaxed);
+    app.update();
+
+    let normal = app
+        .world_mut()
+        .run_system_once(|shm: Res<SharedMemResource>, targets: AlignmentTargets| {
+            target_normal(&shm.0.get().game_structure_game, &targets)
+        })
+        .unwrap()
+        .unwrap();
+    let world = app.world_mut();
+    let mut decorations: Vec<[f32; 3]> = world
+        .query_filtered::<&GlobalTransform, With<DecorationMarker>>()
+        .iter(world)
+        .map(|transform| transform.translation().to_array())
+        .collect();
+    decorations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let yaw = f32::from_bits(handle.get().game_structure_game.pyramid_yaw.load(Ordering::Relaxed));
+    (normal, decorations, yaw)
+}
+
+#[test]
+fn mirrored_round_is_the_mirror_image() {
+    let (mut app, handle) = common::headless_app("test_mirror_x");
+    let (normal, decorations, yaw) = play(&mut app, &handle, false);
+    assert!(yaw.abs() > 0.01, "the pyramid did not turn");
+
+    let (mirrored_normal, mirrored_decorations, mirrored_yaw) = play(&mut app, &handle, true);
+    assert!(mirrored_normal.abs_diff_eq(mirror_vec(normal), 1e-4), "{mirrored_normal} is not the mirror of {normal}");
+    let mut expected: Vec<[f32; 3]> = decorations.iter().map(|&d| mirror_vec(Vec3::from(d)).to_array()).collect();
+    expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(mirrored_decorations.len(), expected.len());
+    for (mirrored, expected) in mirrored_decorations.iter().zip(&expected) {
+        assert!(Vec3::from(*mirrored).abs_diff_eq(Vec3::from(*expected), 1e-4));
+    }
+    // Logged as the unmirrored yaw, shown turned the other way
+    assert!((mirrored_yaw - yaw).abs() < 1e-4, "{mirrored_yaw} != {yaw}");
+    let world = app.world_mut();
+    let shown = world.query_filtered::<&Transform, With<Pyramid>>().iter(world).next().unwrap().rotation;
+    assert!((shown.to_euler(EulerRot::YXZ).0 + yaw).abs() < 1e-4);
+    let unmarked = world
+        .query_filtered::<(), (With<GameEntity>, With<Transform>, Without<Node>, Without<Mirrored>)>()
+        .iter(world)
+        .count();
+    assert_eq!(unmarked, 0);
+
+    // Still winnable by facing the (mirrored) target
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    assert_ne!(handle.get().game_structure_game.win_time.load(Ordering::Relaxed), 0);
+
+    // Unmirrored again
+    let (normal_again, ..) = play(&mut app, &handle, false);
+    assert!(normal_again.abs_diff_eq(normal, 1e-4));
+}
@@ -0,0 +1,91 @@
+//! Orthographic trials: the camera spawns with an orthographic projection, zoom changes its scale
+//! within the configured range instead of the orbit radius, the emitted radius carries the scale
+//! with its flag set, and rotation still turns the pyramid.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::{AtomicBool, Ordering};
+use game_node::utils::objects::{PersistentCamera, RotableComponent};
+use shared::{ProjectionMode, SharedMemoryHandle};
+
+const SCALE_RANGE: [f32; 2] = [0.5, 2.0];
+
+fn play(app: &mut App, handle: &SharedMemoryHandle, mode: ProjectionMode) {
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.projection_mode.store(mode as u32, Ordering::Relaxed);
+    for (slot, value) in gs_ctrl.ortho_scale_range.iter().zip(SCALE_RANGE) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    common::reset(app, handle);
+    app.update();
+}
+
+/// Holds `command` for `frames` frames
+fn hold(app: &mut App, command: &AtomicBool, frames: usize) {
+    command.store(true, Ordering::Relaxed);
+    for _ in 0..frames {
+        app.update();
+    }
+    command.store(false, Ordering::Relaxed);
+    app.update();
+}
+
+/// (Emitted radius, whether it is a scale, camera position)
+fn camera(app: &mut App, handle: &SharedMemoryHandle) -> (f32, bool, Vec3) {
+    let gs_game = &handle.get().game_structure_game;
+    let world = app.world_mut();
+    let position = world.query_filtered::<&Transform, With<PersistentCamera>>().single(world).unwrap().translation;
+    (f32::from_bits(gs_game.camera_radius.load(Ordering::Relaxed)), gs_game.camera_radius_is_scale.load(Ordering::Relaxed), position)
+}
+
+#[test]
+fn orthographic_zoom_scales_the_projection_within_its_range() {
+    let (mut app, handle) = common::headless_app("test_orthographic_projection");
+    let commands = &handle.get().commands;
+    play(&mut app, &handle, ProjectionMode::Orthographic);
+    let world = app.world_mut();
+    assert!(matches!(
+        world.query_filtered::<&Projection, With<PersistentCamera>>().single(world).unwrap(),
+        Projection::Orthographic(_)
+    ));
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(f32::from_bits(gs_game.active_fov_degrees.load(Ordering::Relaxed)), 0.0, "no FOV");
+
+    let (scale, is_scale, position) = camera(&mut app, &handle);
+    assert_eq!((scale, is_scale), (1.0, true));
+
+    hold(&mut app, &commands.zoom_out, 5);
+    let (zoomed, _, zoomed_position) = camera(&mut app, &handle);
+    assert_ne!(zoomed, scale);
+    assert_eq!(zoomed_position, position, "the camera stays on its orbit");
+
+    // Clamped to the range both ways
+    for command in [&commands.zoom_in, &commands.zoom_out] {
+        hold(&mut app, command, 500);
+        let (scale, is_scale, _) = camera(&mut app, &handle);
+        assert!(is_scale && SCALE_RANGE.contains(&scale), "{scale}");
+    }
+
+    // Rotation is unchanged
+    let yaw = |app: &mut App| {
+        let world = app.world_mut();
+        world.query_filtered::<&Transform, With<RotableComponent>>().iter(world).next().unwrap().rotation
+    };
+    let before = yaw(&mut app);
+    hold(&mut app, &commands.rotate_left, 5);
+    assert_ne!(yaw(&mut app), before);
+}
+
+#[test]
+fn perspective_zoom_moves_the_camera() {
+    let (mut app, handle) = common::headless_app("test_orthographic_projection_back");
+    play(&mut app, &handle, ProjectionMode::Orthographic);
+    play(&mut app, &handle, ProjectionMode::Perspective);
+    let (radius, is_scale, position) = camera(&mut app, &handle);
+    assert!(!is_scale);
+    assert!((radius - position.xz().length()).abs() < 1e-4);
+
+    hold(&mut app, &handle.get().commands.zoom_out, 5);
+    let (zoomed, is_scale, _) = camera(&mut app, &handle);
+    assert!(!is_scale && zoomed != radius);
+}
@@ -0,0 +1,87 @@
+//! What pausing (stop_rendering) hides under each `pause_mode`, and that resuming restores it.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{HoleLight, PersistentCamera, Pyramid};
+use shared::{PauseMode, SharedMemoryHandle};
+
+fn camera_visibility(app: &mut App) -> Visibility {
+    let world = app.world_mut();
+    *world.query_filtered::<&Visibility, With<PersistentCamera>>().single(world).unwrap()
+}
+
+fn pyramid_visibility(app: &mut App) -> Visibility {
+    let world = app.world_mut();
+    // Meshes only get a Visibility from the render plugins, Inherited is the same as none
+    let visibility = world.query_filtered::<Option<&Visibility>, With<Pyramid>>().iter(world).next().expect("pyramid spawned");
+    visibility.copied().unwrap_or_default()
+}
+
+fn hole_light_visibility(app: &mut App) -> Visibility {
+    let world = app.world_mut();
+    *world.query_filtered::<&Visibility, With<HoleLight>>().iter(world).next().expect("hole light spawned")
+}
+
+/// Resets into `mode` and stops rendering
+fn pause(app: &mut App, handle: &SharedMemoryHandle, mode: PauseMode) {
+    handle.get().game_structure_control.pause_mode.store(mode as u32, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    handle.get().commands.stop_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    app.update();
+}
+
+fn resume(app: &mut App, handle: &SharedMemoryHandle) {
+    let commands = &handle.get().commands;
+    commands.stop_rendering.store(false, Ordering::Relaxed);
+    commands.resume_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    commands.resume_rendering.store(false, Ordering::Relaxed);
+    app.update();
+}
+
+#[test]
+fn hide_camera_blanks_the_scene() {
+    let (mut app, handle) = common::headless_app("test_pause_hide_camera");
+    pause(&mut app, &handle, PauseMode::HideCamera);
+    assert_eq!(camera_visibility(&mut app), Visibility::Hidden);
+    assert_ne!(pyramid_visibility(&mut app), Visibility::Hidden);
+
+    resume(&mut app, &handle);
+    assert_eq!(camera_visibility(&mut app), Visibility::Visible);
+}
+
+#[test]
+fn hide_game_entities_keeps_the_camera() {
+    let (mut app, handle) = common::headless_app("test_pause_hide_game_entities");
+    pause(&mut app, &handle, PauseMode::HideGameEntities);
+    assert_ne!(camera_visibility(&mut app), Visibility::Hidden);
+    assert_eq!(pyramid_visibility(&mut app), Visibility::Hidden);
+
+    // A reset during the pause spawns a new pyramid, hidden as well
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(pyramid_visibility(&mut app), Visibility::Hidden);
+
+    // Entities hidden before the pause stay hidden, the rest come back as they were
+    resume(&mut app, &handle);
+    assert_ne!(pyramid_visibility(&mut app), Visibility::Hidden);
+    assert_eq!(hole_light_visibility(&mut app), Visibility::Hidden);
+}
+
+#[test]
+fn freeze_hides_nothing() {
+    let (mut app, handle) = common::headless_app("test_pause_freeze");
+    let frame = handle.get().game_structure_game.frame_number.load(Ordering::Relaxed);
+    pause(&mut app, &handle, PauseMode::Freeze);
+    assert_ne!(camera_visibility(&mut app), Visibility::Hidden);
+    assert_ne!(pyramid_visibility(&mut app), Visibility::Hidden);
+
+    // Still paused: the frame counter holds
+    let paused_frame = handle.get().game_structure_game.frame_number.load(Ordering::Relaxed);
+    app.update();
+    assert!(paused_frame > frame);
+    assert_eq!(handle.get().game_structure_game.frame_number.load(Ordering::Relaxed), paused_frame);
+}
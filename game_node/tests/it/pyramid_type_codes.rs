@@ -0,0 +1,39 @@
+//! Pyramid type codes: converted with TryFrom, rejected by the config validation when unknown or
+//! when Type3 colors differ, and an unknown code that reaches the game is built and echoed as Type1.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::json_api::{apply_config_json, config_json};
+use shared::{validate_pyramid_type, PyramidType, PyramidTypeError};
+
+const COLORS: [[f32; 4]; 3] = [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+
+#[test]
+fn codes_convert_both_ways() {
+    for pyramid_type in [PyramidType::Type1, PyramidType::Type2, PyramidType::Type3] {
+        assert_eq!(PyramidType::try_from(pyramid_type as u32), Ok(pyramid_type));
+    }
+    assert_eq!(PyramidType::try_from(3), Err(PyramidTypeError::UnknownCode(3)));
+    assert_eq!(PyramidType::from_u32(3), None);
+
+    assert_eq!(validate_pyramid_type(1, &COLORS), Ok(PyramidType::Type2));
+    assert_eq!(validate_pyramid_type(2, &[COLORS[0]; 3]), Ok(PyramidType::Type3));
+    assert_eq!(validate_pyramid_type(2, &COLORS), Err(PyramidTypeError::Type3ColorsDiffer(COLORS)));
+    assert_eq!(validate_pyramid_type(9, &COLORS).unwrap_err().to_string(), "unknown pyramid_type code 9");
+}
+
+#[test]
+fn invalid_codes_are_rejected_and_never_applied() {
+    let (mut app, handle) = common::headless_app("test_pyramid_type_codes");
+    let shm = handle.get();
+    let before = config_json(&shm.game_structure_control);
+    assert!(apply_config_json(shm, r#"{"pyramid_type": 5}"#).is_err());
+    assert!(apply_config_json(shm, r#"{"pyramid_type": 2, "colors": [[1, 0, 0, 1], [0, 1, 0, 1], [0, 0, 1, 1]]}"#).is_err());
+    assert_eq!(config_json(&shm.game_structure_control), before);
+
+    // Written past the validation, the game builds Type1 and reports it
+    shm.game_structure_control.pyramid_type.store(5, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(shm.game_structure_game.pyramid_type.load(Ordering::Relaxed), PyramidType::Type1 as u32);
+}
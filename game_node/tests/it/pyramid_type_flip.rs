@@ -0,0 +1,104 @@
+//! Switching the live pyramid between Type1 and Type2 without a reset derives the faces from the type,
+//! while a reset builds the configured ones.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{PendingPyramidType, SharedMemResource};
+use game_node::utils::game_functions::handle_set_pyramid_type_command;
+use game_node::utils::objects::{BaseFrame, DoorWinEntities, FaceMarker, HoleEmissive, HoleLight};
+use game_node::utils::pyramid::door_for_face;
+use shared::{create_shared_memory, PyramidType, TargetKind};
+
+const COLORS: [[f32; 4]; 3] = [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+
+#[test]
+fn switching_to_type2_duplicates_a_color_and_retargets() {
+    let handle = create_shared_memory("test_pyramid_type_flip").expect("shared memory");
+    let shm = handle.get();
+    let gs_ctrl = &shm.game_structure_control;
+    for (face, color) in COLORS.iter().enumerate() {
+        for (c, value) in color.iter().enumerate() {
+            gs_ctrl.colors[face * 4 + c].store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+    gs_ctrl.pyramid_type.store(PyramidType::Type1 as u32, Ordering::Relaxed);
+    gs_ctrl.target_kind.store(TargetKind::Face as u32, Ordering::Relaxed);
+    gs_ctrl.target_face.store(0, Ordering::Relaxed);
+    shm.game_structure_game.reset_all_fields(gs_ctrl);
+
+    let mut app = common::new_app();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<StandardMaterial>()
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(PendingPyramidType(Some(PyramidType::Type2 as u32)))
+        .init_resource::<DoorWinEntities>()
+        .add_systems(Update, handle_set_pyramid_type_command);
+
+    let faces: Vec<Entity> = (0..3)
+        .map(|i| {
+            let [r, g, b, a] = COLORS[i];
+            let material = app.world_mut().resource_mut::<Assets<StandardMaterial>>().add(StandardMaterial::default());
+            app.world_mut()
+                .spawn((MeshMaterial3d(material), FaceMarker { face_index: i, normal: Vec3::Z, base_color: Color::srgba(r, g, b, a) }))
+                .id()
+        })
+        .collect();
+    let target_door = door_for_face(1);
+    let frame = app.world_mut().spawn(BaseFrame { door_index: target_door }).id();
+    let light = app.world_mut().spawn((HoleLight, ChildOf(frame))).id();
+    let emissive = app.world_mut().spawn((HoleEmissive, ChildOf(frame))).id();
+
+    app.update();
+
+    let gs_game = &shm.game_structure_game;
+    let color = |face: usize| -> Vec<u32> { (0..4).map(|c| gs_game.colors[face * 4 + c].load(Ordering::Relaxed)).collect() };
+    assert_eq!(color(0), color(2), "Type2 repeats a face color");
+    assert_ne!(color(0), color(1));
+    assert_eq!(gs_game.target_face.load(Ordering::Relaxed), 1, "the unique face is the target");
+    assert_eq!(gs_game.pyramid_type.load(Ordering::Relaxed), PyramidType::Type2 as u32);
+    let marker = |face: Entity| app.world().get::<FaceMarker>(face).unwrap().base_color;
+    assert_eq!(marker(faces[0]), marker(faces[2]), "faces recolored in place");
+
+    let wins = app.world().resource::<DoorWinEntities>();
+    assert_eq!(wins.winning_light, Some(light), "face mode follows the new target face");
+    assert_eq!(wins.winning_emissive, Some(emissive));
+
+    // Back to Type1 restores the configured colors and target
+    app.world_mut().resource_mut::<PendingPyramidType>().0 = Some(PyramidType::Type1 as u32);
+    app.update();
+    assert_ne!(color(0), color(2));
+    assert_eq!(gs_game.target_face.load(Ordering::Relaxed), 0);
+}
+
+#[test]
+fn reset_keeps_the_configured_faces() {
+    let (mut app, handle) = common::headless_app("test_pyramid_type_reset");
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (face, color) in COLORS.iter().enumerate() {
+        for (c, value) in color.iter().enumerate() {
+            gs_ctrl.colors[face * 4 + c].store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+    gs_ctrl.target_face.store(0, Ordering::Relaxed);
+
+    let gs_game = &handle.get().game_structure_game;
+    for pyramid_type in [PyramidType::Type1, PyramidType::Type2, PyramidType::Type3] {
+        gs_ctrl.pyramid_type.store(pyramid_type as u32, Ordering::Relaxed);
+        common::reset(&mut app, &handle);
+        app.update();
+
+        let colors = if pyramid_type == PyramidType::Type3 { [COLORS[0]; 3] } else { COLORS };
+        let applied: [[f32; 4]; 3] = std::array::from_fn(|face| {
+            std::array::from_fn(|c| f32::from_bits(gs_game.colors[face * 4 + c].load(Ordering::Relaxed)))
+        });
+        assert_eq!(applied, colors, "{pyramid_type:?}");
+        assert_eq!(gs_game.target_face.load(Ordering::Relaxed), 0, "{pyramid_type:?}");
+
+        let world = app.world_mut();
+        let mut shown: Vec<_> = world.query::<&FaceMarker>().iter(world).map(|f| (f.face_index, f.base_color)).collect();
+        shown.sort_by_key(|(face, _)| *face);
+        let shown: Vec<_> = shown.into_iter().map(|(_, color)| color).collect();
+        assert_eq!(shown[0] == shown[2], pyramid_type == PyramidType::Type3, "{pyramid_type:?}");
+    }
+}
@@ -0,0 +1,129 @@
+//! Randomized config mode: the game draws the pyramid's radius, height and start orientation within
+//! their ranges from the geometry seed, reports them in the applied config, and falls back to the
+//! explicit values when the ranges are invalid. The geometry seed and the decoration seeds are drawn
+//! from separately, so either can change while the other part of the stimulus stays.
+use crate::common;
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use game_node::utils::objects::DecorationMarker;
+use game_node::utils::setup::draw_geometry;
+use shared::json_api::{apply_config_json, config_json};
+use shared::{validate_geometry_ranges, ConfigMode, SharedMemoryHandle};
+
+const RANGES: [[f32; 2]; 3] = [[1.5, 3.5], [2.0, 6.0], [0.5, 1.0]];
+
+fn store_ranges(handle: &SharedMemoryHandle, ranges: [[f32; 2]; 3]) {
+    let gs_ctrl = &handle.get().game_structure_control;
+    let slots = [&gs_ctrl.base_radius_range, &gs_ctrl.height_range, &gs_ctrl.start_orient_range];
+    for (slot, range) in slots.into_iter().zip(ranges) {
+        for (bound, value) in slot.iter().zip(range) {
+            bound.store(value.to_bits(), Ordering::Relaxed);
+        }
+    }
+}
+
+/// Applied (base radius, height, start orientation) after a reset with `seed`
+fn play(app: &mut bevy::prelude::App, handle: &SharedMemoryHandle, seed: u64) -> [f32; 3] {
+    handle.get().game_structure_control.geometry_seed.store(seed, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    let load = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+    [load(&gs_game.base_radius), load(&gs_game.height), load(&gs_game.start_orient)]
+}
+
+#[test]
+fn draws_are_seeded_and_within_range() {
+    for seed in 0..50 {
+        let drawn = draw_geometry(seed, RANGES);
+        assert_eq!(draw_geometry(seed, RANGES), drawn);
+        for (value, [min, max]) in drawn.into_iter().zip(RANGES) {
+            assert!((min..=max).contains(&value), "{value} not in [{min}, {max}]");
+        }
+    }
+    assert_ne!(draw_geometry(1, RANGES), draw_geometry(2, RANGES));
+    assert_eq!(draw_geometry(7, [[2.5; 2], [4.0; 2], [0.0; 2]]), [2.5, 4.0, 0.0]);
+
+    assert!(validate_geometry_ranges(RANGES[0], RANGES[1], RANGES[2]).is_ok());
+    assert!(validate_geometry_ranges([3.0, 2.0], RANGES[1], RANGES[2]).is_err(), "min > max");
+    assert!(validate_geometry_ranges(RANGES[0], [0.5, 6.0], RANGES[2]).is_err(), "below the limits");
+    assert!(validate_geometry_ranges(RANGES[0], RANGES[1], [0.0, 7.0]).is_err(), "past a full turn");
+}
+
+#[test]
+fn randomized_resets_report_the_drawn_geometry() {
+    let (mut app, handle) = common::headless_app("test_randomized_geometry");
+    let shm = handle.get();
+    let explicit = play(&mut app, &handle, 0);
+
+    store_ranges(&handle, RANGES);
+    shm.game_structure_control.config_mode.store(ConfigMode::Randomized as u32, Ordering::Relaxed);
+    let drawn = play(&mut app, &handle, 11);
+    assert_eq!(drawn, draw_geometry(11, RANGES));
+    assert_eq!(shm.game_structure_game.config_mode.load(Ordering::Relaxed), ConfigMode::Randomized as u32);
+    assert_eq!(play(&mut app, &handle, 11), drawn, "the same seed gives the same pyramid");
+    assert_ne!(play(&mut app, &handle, 12), drawn);
+    // The control region keeps the explicit values
+    let gs_ctrl = &shm.game_structure_control;
+    assert_eq!(f32::from_bits(gs_ctrl.base_radius.load(Ordering::Relaxed)), explicit[0]);
+
+    // Invalid ranges that reach the game fall back to the explicit values, echoed as Explicit
+    store_ranges(&handle, [[3.0, 2.0], RANGES[1], RANGES[2]]);
+    assert_eq!(play(&mut app, &handle, 11), explicit);
+    assert_eq!(shm.game_structure_game.config_mode.load(Ordering::Relaxed), ConfigMode::Explicit as u32);
+}
+
+#[test]
+fn invalid_ranges_are_rejected_in_randomized_mode() {
+    let (_app, handle) = common::headless_app("test_randomized_geometry_json");
+    let shm = handle.get();
+    let before = config_json(&shm.game_structure_control);
+    assert!(apply_config_json(shm, r#"{"config_mode": 2}"#).is_err());
+    assert!(apply_config_json(shm, r#"{"config_mode": 1, "height_range": [5.0, 3.0]}"#).is_err());
+    assert_eq!(config_json(&shm.game_structure_control), before);
+
+    // Explicit trials do not read the ranges
+    assert!(apply_config_json(shm, r#"{"config_mode": 0, "height_range": [5.0, 3.0]}"#).is_ok());
+    assert!(apply_config_json(shm, r#"{"config_mode": 1, "height_range": [3.0, 5.0], "geometry_seed": 9}"#).is_ok());
+    assert_eq!(config_json(&shm.game_structure_control)["geometry_seed"], 9);
+}
+
+/// (face, shape, color bits) of every spawned decoration, sorted
+fn decoration_layout(app: &mut bevy::prelude::App) -> Vec<(usize, u32, [u32; 4])> {
+    let world = app.world_mut();
+    let mut layout: Vec<_> = world
+        .query::<&DecorationMarker>()
+        .iter(world)
+        .map(|marker| (marker.face_index, marker.shape as u32, marker.color.map(f32::to_bits)))
+        .collect();
+    layout.sort();
+    layout
+}
+
+#[test]
+fn geometry_and_decoration_seeds_vary_independently() {
+    let (mut app, handle) = common::headless_app("test_randomized_geometry_seeds");
+    let shm = handle.get();
+    // Only the orientation is drawn: how many decorations fit depends on the face size
+    store_ranges(&handle, [[2.5, 2.5], [4.0, 4.0], RANGES[2]]);
+    shm.game_structure_control.config_mode.store(ConfigMode::Randomized as u32, Ordering::Relaxed);
+    let geometry = play(&mut app, &handle, 11);
+    let layout = decoration_layout(&mut app);
+    assert!(!layout.is_empty());
+
+    // Other decoration seeds keep the pyramid
+    shm.game_structure_control.decoration_seeds[0].fetch_add(1, Ordering::Relaxed);
+    assert_eq!(play(&mut app, &handle, 11), geometry);
+    let redecorated = decoration_layout(&mut app);
+    shm.game_structure_control.decoration_seeds[0].fetch_sub(1, Ordering::Relaxed);
+
+    // Another geometry seed keeps the decorations
+    assert_ne!(play(&mut app, &handle, 12), geometry);
+    assert_eq!(decoration_layout(&mut app), layout);
+    assert_ne!(redecorated, layout);
+
+    // Both are reported with the applied config
+    let applied = config_json(&shm.game_structure_game);
+    assert_eq!(applied["geometry_seed"], 12);
+    assert_eq!(applied["decoration_seeds"], config_json(&shm.game_structure_control)["decoration_seeds"]);
+}
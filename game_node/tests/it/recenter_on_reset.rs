@@ -0,0 +1,88 @@
+//! Recentering on reset: by default every trial starts from the configured camera and pyramid yaw,
+//! and without it both carry over from the previous trial.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{PersistentCamera, Pyramid};
+use shared::SharedMemoryHandle;
+
+/// Yaw of the pyramid entities, which all turn together
+fn pyramid_yaw(app: &mut App) -> f32 {
+    let world = app.world_mut();
+    let yaws: Vec<f32> = world
+        .query_filtered::<&Transform, With<Pyramid>>()
+        .iter(world)
+        .map(|transform| transform.rotation.to_euler(EulerRot::YXZ).0)
+        .collect();
+    assert!(!yaws.is_empty());
+    assert!(yaws.iter().all(|yaw| (yaw - yaws[0]).abs() < 1e-5), "pyramid entities disagree: {yaws:?}");
+    yaws[0]
+}
+
+fn camera_position(app: &mut App) -> Vec3 {
+    let world = app.world_mut();
+    world.query_filtered::<&Transform, With<PersistentCamera>>().single(world).unwrap().translation
+}
+
+/// Turns the pyramid and zooms the camera in for a few frames
+fn move_around(app: &mut App, handle: &SharedMemoryHandle) {
+    let commands = &handle.get().commands;
+    commands.rotate_right.store(true, Ordering::Relaxed);
+    commands.zoom_in.store(true, Ordering::Relaxed);
+    for _ in 0..5 {
+        app.update();
+    }
+    commands.rotate_right.store(false, Ordering::Relaxed);
+    commands.zoom_in.store(false, Ordering::Relaxed);
+    app.update();
+}
+
+fn start_round(app: &mut App, handle: &SharedMemoryHandle, recenter: bool) {
+    handle.get().game_structure_control.recenter_on_reset.store(recenter, Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+    app.update();
+}
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+}
+
+#[test]
+fn reset_recenters_by_default() {
+    let (mut app, handle) = common::headless_app("test_recenter_on_reset");
+    assert!(handle.get().game_structure_control.recenter_on_reset.load(Ordering::Relaxed));
+    start_round(&mut app, &handle, true);
+    let (start_yaw, start_camera) = (pyramid_yaw(&mut app), camera_position(&mut app));
+
+    move_around(&mut app, &handle);
+    assert!((pyramid_yaw(&mut app) - start_yaw).abs() > 1e-3, "controls turned the pyramid");
+    assert!(camera_position(&mut app).distance(start_camera) > 1e-3, "controls moved the camera");
+
+    start_round(&mut app, &handle, true);
+    assert_near(pyramid_yaw(&mut app), start_yaw);
+    assert!(camera_position(&mut app).distance(start_camera) < 1e-4);
+}
+
+#[test]
+fn without_recentering_the_view_carries_over() {
+    let (mut app, handle) = common::headless_app("test_recenter_on_reset_off");
+    start_round(&mut app, &handle, false);
+    let (start_yaw, start_camera) = (pyramid_yaw(&mut app), camera_position(&mut app));
+
+    move_around(&mut app, &handle);
+    let (moved_yaw, moved_camera) = (pyramid_yaw(&mut app), camera_position(&mut app));
+    assert!((moved_yaw - start_yaw).abs() > 1e-3);
+
+    start_round(&mut app, &handle, false);
+    assert_near(pyramid_yaw(&mut app), moved_yaw);
+    assert!(camera_position(&mut app).distance(moved_camera) < 1e-4, "camera kept its place");
+    let emitted = f32::from_bits(handle.get().game_structure_game.pyramid_yaw.load(Ordering::Relaxed));
+    assert_near(emitted, moved_yaw);
+
+    // A recentering trial starts from the configured view again
+    start_round(&mut app, &handle, true);
+    assert_near(pyramid_yaw(&mut app), start_yaw);
+    assert!(camera_position(&mut app).distance(start_camera) < 1e-4);
+}
@@ -0,0 +1,63 @@
+//! Reference images: shown full screen above the blank on request, hidden on request and at every
+//! reset, and echoed through `reference_image`.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::systems_logic::ReferenceImageOverlay;
+use shared::{HIDE_IMAGE, NO_IMAGE_CHANGE};
+
+/// Indices and z-indices of the reference image overlays
+fn overlays(app: &mut App) -> Vec<(u32, i32)> {
+    let world = app.world_mut();
+    world
+        .query::<(&ReferenceImageOverlay, &GlobalZIndex)>()
+        .iter(world)
+        .map(|(overlay, z)| (overlay.index, z.0))
+        .collect()
+}
+
+#[test]
+fn reference_image_shows_hides_and_clears_at_reset() {
+    let (mut app, handle) = common::headless_app("test_reference_image");
+    common::reset(&mut app, &handle);
+    app.update();
+    let shm = handle.get();
+    let gs_game = &shm.game_structure_game;
+    assert!(overlays(&mut app).is_empty());
+    assert_eq!(gs_game.reference_image.load(Ordering::Relaxed), HIDE_IMAGE);
+
+    shm.commands.show_image.store(1, Ordering::Relaxed);
+    app.update();
+    assert_eq!(shm.commands.show_image.load(Ordering::Relaxed), NO_IMAGE_CHANGE, "consumed on read");
+    let shown = overlays(&mut app);
+    assert_eq!(shown.len(), 1);
+    assert_eq!(shown[0].0, 1);
+    assert!(shown[0].1 > 1000, "above the blank overlay");
+    assert_eq!(gs_game.reference_image.load(Ordering::Relaxed), 1);
+
+    // Another index replaces it
+    shm.commands.show_image.store(0, Ordering::Relaxed);
+    app.update();
+    assert_eq!(overlays(&mut app).iter().map(|o| o.0).collect::<Vec<_>>(), [0]);
+
+    shm.commands.show_image.store(HIDE_IMAGE, Ordering::Relaxed);
+    app.update();
+    assert!(overlays(&mut app).is_empty());
+    assert_eq!(gs_game.reference_image.load(Ordering::Relaxed), HIDE_IMAGE);
+
+    // Out of range: nothing shown
+    shm.commands.show_image.store(7, Ordering::Relaxed);
+    app.update();
+    assert!(overlays(&mut app).is_empty());
+    assert_eq!(gs_game.reference_image.load(Ordering::Relaxed), HIDE_IMAGE);
+
+    // The next reset hides it
+    shm.commands.show_image.store(0, Ordering::Relaxed);
+    app.update();
+    assert_eq!(overlays(&mut app).len(), 1);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert!(overlays(&mut app).is_empty());
+    assert_eq!(gs_game.reference_image.load(Ordering::Relaxed), HIDE_IMAGE);
+}
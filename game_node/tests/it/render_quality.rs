@@ -0,0 +1,64 @@
+//! Render quality: a level switches MSAA on the scene camera and shadows on every spotlight live,
+//! holds for the door lights of later rounds, and is emitted in the state; unknown levels are ignored.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::PersistentCamera;
+use shared::json_api::apply_command_json;
+use shared::{RenderQuality, SharedMemoryHandle};
+
+fn set_quality(app: &mut App, handle: &SharedMemoryHandle, level: u32) {
+    handle.get().commands.render_quality.store(level, Ordering::Relaxed);
+    app.update();
+}
+
+/// Emitted level, camera MSAA and the shadows of every spotlight
+fn rendering(app: &mut App, handle: &SharedMemoryHandle) -> (u32, Msaa, Vec<bool>) {
+    let world = app.world_mut();
+    // Without a render app nothing inserts Msaa before the first switch, Bevy's default applies
+    let msaa = world.query_filtered::<Option<&Msaa>, With<PersistentCamera>>().single(world).unwrap().copied().unwrap_or_default();
+    let shadows = world.query::<&SpotLight>().iter(world).map(|light| light.shadows_enabled).collect();
+    (handle.get().game_structure_game.render_quality.load(Ordering::Relaxed), msaa, shadows)
+}
+
+#[test]
+fn levels_switch_msaa_and_shadows_live() {
+    let (mut app, handle) = common::headless_app("test_render_quality");
+    common::reset(&mut app, &handle);
+    app.update();
+    let (level, msaa, shadows) = rendering(&mut app, &handle);
+    assert_eq!(level, RenderQuality::High as u32, "starts high");
+    assert_eq!(msaa, Msaa::Sample4);
+    assert!(shadows.len() > 1 && shadows.iter().all(|&on| on));
+
+    set_quality(&mut app, &handle, RenderQuality::Low as u32);
+    let (level, msaa, shadows) = rendering(&mut app, &handle);
+    assert_eq!((level, msaa), (RenderQuality::Low as u32, Msaa::Off));
+    assert!(shadows.iter().all(|&on| !on));
+
+    // The next round's door lights follow the level too
+    common::reset(&mut app, &handle);
+    app.update();
+    let (level, _, shadows) = rendering(&mut app, &handle);
+    assert_eq!(level, RenderQuality::Low as u32, "held across trials");
+    assert!(shadows.iter().all(|&on| !on));
+
+    set_quality(&mut app, &handle, RenderQuality::Medium as u32);
+    let (level, msaa, shadows) = rendering(&mut app, &handle);
+    assert_eq!((level, msaa), (RenderQuality::Medium as u32, Msaa::Sample2));
+    assert!(shadows.iter().all(|&on| on));
+}
+
+#[test]
+fn unknown_levels_are_ignored() {
+    let (mut app, handle) = common::headless_app("test_render_quality_unknown");
+    set_quality(&mut app, &handle, RenderQuality::Low as u32);
+    set_quality(&mut app, &handle, 7);
+    assert_eq!(rendering(&mut app, &handle).0, RenderQuality::Low as u32);
+
+    assert!(apply_command_json(handle.get(), r#"{"method": "set_render_quality", "level": 3}"#).is_err());
+    apply_command_json(handle.get(), r#"{"method": "set_render_quality", "level": 2}"#).unwrap();
+    app.update();
+    assert_eq!(rendering(&mut app, &handle).1, Msaa::Sample4);
+}
@@ -0,0 +1,97 @@
+//! Reset acknowledgement handshake between the controller and the game.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::Phase;
+
+#[test]
+fn reset_ack_increments_once_per_reset() {
+    let (mut app, handle) = common::headless_app("test_reset_ack");
+    let shm = handle.get();
+    let ack = || shm.game_structure_game.reset_ack.load(Ordering::Acquire);
+
+    app.update();
+    assert_eq!(ack(), 0, "no reset requested yet");
+
+    // Reset pulse: acknowledged exactly once, together with the new round state
+    shm.commands.reset.store(true, Ordering::Release);
+    app.update();
+    shm.commands.reset.store(false, Ordering::Release);
+    assert_eq!(ack(), 1);
+    assert_eq!(shm.game_structure_game.frame_number.load(Ordering::Relaxed), 1);
+
+    app.update();
+    app.update();
+    assert_eq!(ack(), 1, "ack only advances on resets");
+
+    shm.commands.reset.store(true, Ordering::Release);
+    app.update();
+    shm.commands.reset.store(false, Ordering::Release);
+    assert_eq!(ack(), 2);
+    // Counted once the new trial plays, the frame after its ack
+    assert_eq!(shm.game_structure_game.trial_generation.load(Ordering::Acquire), 1);
+    app.update();
+    assert_eq!(shm.game_structure_game.trial_generation.load(Ordering::Acquire), 2, "one trial counted per reset");
+}
+
+#[test]
+fn a_held_reset_is_acknowledged_once() {
+    let (mut app, handle) = common::headless_app("test_reset_ack_held");
+    let shm = handle.get();
+    let ack = || shm.game_structure_game.reset_ack.load(Ordering::Acquire);
+
+    // As reset_and_wait holds it until the ack, and a frame or more past it
+    shm.commands.reset.store(true, Ordering::Release);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(ack(), 1);
+    assert_eq!(shm.game_structure_game.phase.load(Ordering::Relaxed), Phase::Playing as u32);
+
+    // Released and set again: the next reset
+    shm.commands.reset.store(false, Ordering::Release);
+    app.update();
+    assert_eq!(ack(), 1);
+    common::reset(&mut app, &handle);
+    assert_eq!(ack(), 2);
+    // Counted once the new trial plays, the frame after its ack
+    assert_eq!(shm.game_structure_game.trial_generation.load(Ordering::Acquire), 1);
+    app.update();
+    assert_eq!(shm.game_structure_game.trial_generation.load(Ordering::Acquire), 2, "one trial counted per reset");
+}
+
+#[test]
+fn reset_after_the_end_emits_resetting_until_the_new_trial_plays() {
+    let (mut app, handle) = common::headless_app("test_reset_resetting");
+    let gs_game = &handle.get().game_structure_game;
+    let phase = || Phase::from_u32(gs_game.phase.load(Ordering::Relaxed)).unwrap();
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(phase(), Phase::Playing, "a reset while playing goes straight on");
+
+    // Without an interval the reset lands right as the won trial ends
+    gs_game.iti_frames.store(0, Ordering::Relaxed);
+    gs_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+    app.update();
+    assert_eq!(phase(), Phase::Won);
+
+    // The frame the reset is acknowledged on still belongs to the reset, the next one to the trial,
+    // which is counted as it starts playing
+    let generation = gs_game.trial_generation.load(Ordering::Acquire);
+    common::reset(&mut app, &handle);
+    assert_eq!(phase(), Phase::Resetting);
+    assert_eq!(gs_game.trial_generation.load(Ordering::Acquire), generation);
+    app.update();
+    assert_eq!(phase(), Phase::Playing);
+    assert_eq!(gs_game.trial_generation.load(Ordering::Acquire), generation + 1);
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0);
+
+    // A trial returning from its interval without a reset is the same trial, not counted again
+    gs_game.iti_frames.store(0, Ordering::Relaxed);
+    gs_game.win_time.store(1.0f32.to_bits(), Ordering::Relaxed);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(phase(), Phase::Playing);
+    assert_eq!(gs_game.trial_generation.load(Ordering::Acquire), generation + 1);
+}
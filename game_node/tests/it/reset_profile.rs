@@ -0,0 +1,67 @@
+//! Reset profiling: every reset's section times are appended to the CSV with the decorations it
+//! placed, and the slowest resets are kept, slowest first.
+#![cfg(all(feature = "profiling", not(target_arch = "wasm32")))]
+use crate::common;
+
+use bevy::prelude::*;
+use clap::Parser;
+use core::sync::atomic::Ordering;
+use game_node::cli::Cli;
+use game_node::command_handler::SharedMemResource;
+use game_node::headless::HeadlessPlugin;
+use game_node::profiling::{default_profile_path, ProfilingPlugin, ResetProfiler, ResetTiming};
+use shared::constants::game_constants::RESET_PROFILE_WORST_KEPT;
+use shared::{create_shared_memory, SharedMemoryHandle};
+
+/// Resets into a trial with `count` decorations per face triangle and returns those placed per face
+fn reset_with_decorations(app: &mut App, handle: &SharedMemoryHandle, count: u32) -> Vec<f64> {
+    let shm = handle.get();
+    for face in &shm.game_structure_control.decorations_count {
+        face.store(count, Ordering::Relaxed);
+    }
+    shm.commands.reset.store(true, Ordering::Release);
+    app.update();
+    shm.commands.reset.store(false, Ordering::Release);
+    app.update();
+    shm.game_structure_game.decorations_placed.iter().map(|placed| placed.load(Ordering::Relaxed) as f64).collect()
+}
+
+#[test]
+fn resets_are_timed_into_the_csv() {
+    let csv = std::env::temp_dir().join("test_reset_profile.csv");
+    let handle = create_shared_memory("test_reset_profile").expect("shared memory");
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((HeadlessPlugin, ProfilingPlugin { csv: csv.clone() }));
+    app.update();
+
+    let counts = [2, 40, 0, 10, 5, 20, 1];
+    let placed: Vec<_> = counts.iter().map(|&count| reset_with_decorations(&mut app, &handle, count)).collect();
+    assert!(placed[1][0] > placed[0][0] && placed[2][0] == 0.0);
+
+    let text = std::fs::read_to_string(&csv).unwrap();
+    let mut lines = text.lines();
+    assert_eq!(lines.next().unwrap(), ResetTiming::csv_header());
+    let rows: Vec<Vec<f64>> = lines.map(|line| line.split(',').map(|v| v.parse().unwrap()).collect()).collect();
+    assert_eq!(rows.len(), counts.len(), "one row per reset");
+    for (row, placed) in rows.iter().zip(&placed) {
+        let (setup, pyramid, decorations) = (row[1], row[2], row[3]);
+        assert!(setup >= pyramid && pyramid >= decorations && decorations >= 0.0, "{row:?}");
+        assert_eq!(&row[4..], placed.as_slice(), "decorations placed per face");
+    }
+    assert_eq!(rows.iter().map(|row| row[0] as u32).collect::<Vec<_>>(), (1..=counts.len() as u32).collect::<Vec<_>>());
+
+    let profiler = app.world().resource::<ResetProfiler>();
+    assert_eq!(profiler.resets(), counts.len() as u32);
+    let worst = profiler.worst();
+    assert_eq!(worst.len(), RESET_PROFILE_WORST_KEPT);
+    assert!(worst.windows(2).all(|pair| pair[0].total_ms() >= pair[1].total_ms()), "slowest first");
+
+    // --profile with and without a file
+    let cli = Cli::try_parse_from(["game_node", "--profile"]).unwrap();
+    assert_eq!(cli.profile, Some(None));
+    assert_eq!(default_profile_path().file_name().unwrap(), "reset_profile.csv");
+    let cli = Cli::try_parse_from(["game_node", "--profile", "timings.csv"]).unwrap();
+    assert_eq!(cli.profile, Some(Some("timings.csv".into())));
+    assert_eq!(Cli::try_parse_from(["game_node"]).unwrap().profile, None);
+}
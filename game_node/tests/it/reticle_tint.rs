@@ -0,0 +1,43 @@
+//! Reticle alignment tint: it follows the best-facing door whichever it is, so it never singles
+//! out the target, and fades as the camera turns away from every door.
+use bevy::prelude::*;
+use game_node::utils::game_functions::best_door_alignment;
+use game_node::utils::objects::BaseDoor;
+use shared::constants::pyramid_constants::BASE_NR_SIDES;
+
+/// Doors evenly spread around the pyramid, facing outward like the spawned ones
+fn doors() -> Vec<(BaseDoor, Transform)> {
+    (0..BASE_NR_SIDES)
+        .map(|door_index| {
+            let angle = door_index as f32 * std::f32::consts::TAU / BASE_NR_SIDES as f32;
+            let normal = Vec3::new(angle.cos(), 0.0, angle.sin());
+            (BaseDoor { door_index, normal, is_open: false }, Transform::IDENTITY)
+        })
+        .collect()
+}
+
+fn alignment(doors: &[(BaseDoor, Transform)], camera_forward: Vec3) -> f32 {
+    best_door_alignment(doors.iter().map(|(door, transform)| (door, transform)), camera_forward).unwrap()
+}
+
+#[test]
+fn any_door_faced_head_on_is_fully_aligned() {
+    let doors = doors();
+    for (door, _) in &doors {
+        assert!((alignment(&doors, door.normal) - 1.0).abs() < 1e-5, "door {}", door.door_index);
+    }
+}
+
+#[test]
+fn alignment_fades_halfway_between_two_doors() {
+    let doors = doors();
+    let half_step = std::f32::consts::PI / BASE_NR_SIDES as f32;
+    let between = Quat::from_rotation_y(half_step) * doors[0].0.normal;
+    let faded = alignment(&doors, between);
+    assert!((faded - half_step.cos()).abs() < 1e-5, "alignment {faded}");
+}
+
+#[test]
+fn no_alignment_without_doors() {
+    assert_eq!(best_door_alignment(std::iter::empty(), Vec3::X), None);
+}
@@ -0,0 +1,94 @@
+//! Scene lights: without a SetupConfig the round has the overhead spotlight driven by the trial,
+//! configured lights replace it at their positions aimed at the origin, and bad files are rejected.
+use crate::common;
+
+use bevy::prelude::*;
+use clap::Parser;
+use core::sync::atomic::Ordering;
+use game_node::cli::Cli;
+use game_node::utils::objects::SceneLight;
+use game_node::utils::setup::{LightConfig, SetupConfig};
+use shared::constants::lighting_constants::{SPOTLIGHT_OUTER_ANGLE, SPOTLIGHT_POSITION};
+
+/// Scene spotlights as (position, forward, light), by ascending x
+fn lights(app: &mut App) -> Vec<(Vec3, Vec3, SpotLight)> {
+    let world = app.world_mut();
+    let mut lights: Vec<_> = world
+        .query_filtered::<(&Transform, &SpotLight), With<SceneLight>>()
+        .iter(world)
+        .map(|(transform, light)| (transform.translation, *transform.forward(), *light))
+        .collect();
+    lights.sort_by(|a, b| a.0.x.total_cmp(&b.0.x));
+    lights
+}
+
+#[test]
+fn default_round_has_the_overhead_spotlight() {
+    let (mut app, handle) = common::headless_app("test_scene_lights_default");
+    handle.get().game_structure_control.main_spotlight_intensity.store(1234.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+
+    let lights = lights(&mut app);
+    assert_eq!(lights.len(), 1);
+    let (position, forward, light) = &lights[0];
+    assert_eq!(*position, Vec3::from_array(SPOTLIGHT_POSITION));
+    assert!(forward.distance(-Vec3::Y) < 1e-5, "straight down");
+    assert_eq!(light.intensity, 1234.0);
+    assert_eq!(light.outer_angle, SPOTLIGHT_OUTER_ANGLE);
+}
+
+#[test]
+fn configured_lights_replace_it_every_round() {
+    let (mut app, handle) = common::headless_app("test_scene_lights_configured");
+    let config = SetupConfig::from_json(
+        r#"{"lights": [
+            {"position": [-6.0, 4.0, 0.0], "intensity": 1000.0, "angle": 0.5, "color": [1.0, 0.0, 0.0]},
+            {"position": [6.0, 4.0, 6.0]}
+        ]}"#,
+    )
+    .unwrap();
+    app.insert_resource(config);
+    for _ in 0..2 {
+        common::reset(&mut app, &handle);
+        app.update();
+        let lights = lights(&mut app);
+        assert_eq!(lights.len(), 2, "the last round's lights are replaced");
+        for (position, forward, _) in &lights {
+            assert!(forward.distance(-position.normalize()) < 1e-5, "aimed at the origin");
+        }
+        assert_eq!(lights[0].0, Vec3::new(-6.0, 4.0, 0.0));
+        assert_eq!((lights[0].2.intensity, lights[0].2.outer_angle), (1000.0, 0.5));
+        assert_eq!(lights[0].2.color, Color::srgb(1.0, 0.0, 0.0));
+        assert_eq!(lights[1].2.outer_angle, SPOTLIGHT_OUTER_ANGLE);
+        assert_eq!(lights[1].2.color, Color::WHITE);
+    }
+}
+
+#[test]
+fn invalid_configs_are_rejected() {
+    assert_eq!(SetupConfig::from_json("{}"), Ok(SetupConfig::default()));
+    for json in [
+        r#"{"lights": {}}"#,
+        r#"{"lights": [{"intensity": 10.0}]}"#,
+        r#"{"lights": [{"position": [0.0, 0.0, 0.0]}]}"#,
+        r#"{"lights": [{"position": [1.0, 2.0]}]}"#,
+        r#"{"lights": [{"position": [1.0, 2.0, 3.0], "angle": 2.0}]}"#,
+        r#"{"lights": [{"position": [1.0, 2.0, 3.0], "intensity": -1.0}]}"#,
+        r#"{"lights": [{"position": [1.0, 2.0, 3.0], "color": [2.0, 0.0, 0.0]}]}"#,
+    ] {
+        assert!(SetupConfig::from_json(json).is_err(), "{json}");
+    }
+
+    // From the command line, a bad file stops the launch
+    let dir = std::env::temp_dir();
+    let good = dir.join("test_scene_lights_good.json");
+    std::fs::write(&good, r#"{"lights": [{"position": [0.0, 5.0, 5.0], "intensity": 10.0}]}"#).unwrap();
+    let cli = Cli::try_parse_from(["game_node", "--lights", good.to_str().unwrap()]).unwrap();
+    let lights = cli.setup_config.unwrap().lights;
+    assert_eq!(lights, vec![LightConfig { position: Vec3::new(0.0, 5.0, 5.0), intensity: 10.0, angle: SPOTLIGHT_OUTER_ANGLE, color: Color::WHITE }]);
+    let bad = dir.join("test_scene_lights_bad.json");
+    std::fs::write(&bad, r#"{"lights": [{"intensity": 10.0}]}"#).unwrap();
+    assert!(Cli::try_parse_from(["game_node", "--lights", bad.to_str().unwrap()]).is_err());
+    assert!(Cli::try_parse_from(["game_node", "--lights", dir.join("missing.json").to_str().unwrap()]).is_err());
+}
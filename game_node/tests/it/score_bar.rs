@@ -0,0 +1,93 @@
+//! Score bar layout: the defaults keep the bar at the top with its constant size, a configured bar
+//! sits at the chosen edge with its size and an optional percentage, and invalid layouts fall back.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{ScoreBarFill, ScoreBarText, ScoreBarUI};
+use shared::constants::game_constants::{SCORE_BAR_HEIGHT, SCORE_BAR_TOP_OFFSET, SCORE_BAR_WIDTH_PERCENT};
+use shared::json_api::apply_config_json;
+use shared::{ScoreBarAnchor, SharedMemoryHandle};
+use std::time::Duration;
+
+/// Bar (width, height) and its container's (top, bottom)
+fn layout(app: &mut App) -> ((Val, Val), (Val, Val)) {
+    let world = app.world_mut();
+    let (bar, parent) = world.query_filtered::<(&Node, &ChildOf), With<ScoreBarUI>>().single(world).unwrap();
+    let (size, parent) = ((bar.width, bar.height), parent.parent());
+    let container = world.get::<Node>(parent).unwrap();
+    (size, (container.top, container.bottom))
+}
+
+fn numeric_text(app: &mut App) -> Option<String> {
+    let world = app.world_mut();
+    world.query_filtered::<&Text, With<ScoreBarText>>().iter(world).next().map(|text| text.0.clone())
+}
+
+fn configure(handle: &SharedMemoryHandle, anchor: ScoreBarAnchor, width: f32, height: f32, numeric: bool) {
+    let gs_ctrl = &handle.get().game_structure_control;
+    gs_ctrl.score_bar_anchor.store(anchor as u32, Ordering::Relaxed);
+    gs_ctrl.score_bar_width_percent.store(width.to_bits(), Ordering::Relaxed);
+    gs_ctrl.score_bar_height.store(height.to_bits(), Ordering::Relaxed);
+    gs_ctrl.score_bar_show_numeric.store(numeric, Ordering::Relaxed);
+}
+
+fn start_round(app: &mut App, handle: &SharedMemoryHandle) {
+    common::reset(app, handle);
+    app.update();
+}
+
+#[test]
+fn defaults_keep_the_top_bar() {
+    let (mut app, handle) = common::headless_app("test_score_bar_defaults");
+    start_round(&mut app, &handle);
+    assert_eq!(
+        layout(&mut app),
+        ((Val::Percent(SCORE_BAR_WIDTH_PERCENT), Val::Px(SCORE_BAR_HEIGHT)), (Val::Px(SCORE_BAR_TOP_OFFSET), Val::Auto))
+    );
+    assert_eq!(numeric_text(&mut app), None, "no percentage by default");
+}
+
+#[test]
+fn configured_bar_sits_at_the_bottom_with_a_percentage() {
+    let (mut app, handle) = common::headless_app("test_score_bar_configured");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    configure(&handle, ScoreBarAnchor::Bottom, 60.0, 32.0, true);
+    start_round(&mut app, &handle);
+    assert_eq!(layout(&mut app), ((Val::Percent(60.0), Val::Px(32.0)), (Val::Auto, Val::Px(SCORE_BAR_TOP_OFFSET))));
+    assert_eq!(numeric_text(&mut app).as_deref(), Some(""), "empty until a check");
+
+    // The percentage follows the fill during the check's animation, and the respawned bar keeps the layout
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(layout(&mut app), ((Val::Percent(60.0), Val::Px(32.0)), (Val::Auto, Val::Px(SCORE_BAR_TOP_OFFSET))));
+    let world = app.world_mut();
+    let Val::Percent(fill) = world.query_filtered::<&Node, With<ScoreBarFill>>().single(world).unwrap().width else {
+        panic!("fill width in percent");
+    };
+    assert!(fill > 0.0);
+    assert_eq!(numeric_text(&mut app), Some(format!("{fill:.0}%")));
+}
+
+#[test]
+fn invalid_layouts_fall_back_to_the_default_bar() {
+    let (mut app, handle) = common::headless_app("test_score_bar_invalid");
+    let shm = handle.get();
+    assert!(apply_config_json(shm, r#"{"score_bar_width_percent": 0.0}"#).is_err());
+    assert!(apply_config_json(shm, r#"{"score_bar_anchor": 5}"#).is_err());
+
+    // Written directly, the game draws and reports the default bar
+    configure(&handle, ScoreBarAnchor::Bottom, 150.0, 32.0, false);
+    start_round(&mut app, &handle);
+    assert_eq!(
+        layout(&mut app),
+        ((Val::Percent(SCORE_BAR_WIDTH_PERCENT), Val::Px(SCORE_BAR_HEIGHT)), (Val::Px(SCORE_BAR_TOP_OFFSET), Val::Auto))
+    );
+    let gs_game = &shm.game_structure_game;
+    assert_eq!(gs_game.score_bar_anchor.load(Ordering::Relaxed), ScoreBarAnchor::Top as u32);
+    assert_eq!(f32::from_bits(gs_game.score_bar_width_percent.load(Ordering::Relaxed)), SCORE_BAR_WIDTH_PERCENT);
+}
@@ -0,0 +1,54 @@
+//! Session block: the controller's session context is echoed into the game region and the state
+//! JSON, can change mid-trial, and survives resets.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::json_api::state_json;
+use shared::SharedMemoryHandle;
+
+fn write_session(handle: &SharedMemoryHandle, trial: u32, total: u32, block: u32, session: u64, subject: u32) {
+    let gs_control = &handle.get().game_structure_control;
+    gs_control.current_trial_index.store(trial, Ordering::Relaxed);
+    gs_control.total_trials.store(total, Ordering::Relaxed);
+    gs_control.block_index.store(block, Ordering::Relaxed);
+    gs_control.session_id.store(session, Ordering::Relaxed);
+    gs_control.subject_id.store(subject, Ordering::Relaxed);
+}
+
+fn echoed(handle: &SharedMemoryHandle) -> (u32, u32, u32, u64, u32) {
+    let gs_game = &handle.get().game_structure_game;
+    (
+        gs_game.current_trial_index.load(Ordering::Relaxed),
+        gs_game.total_trials.load(Ordering::Relaxed),
+        gs_game.block_index.load(Ordering::Relaxed),
+        gs_game.session_id.load(Ordering::Relaxed),
+        gs_game.subject_id.load(Ordering::Relaxed),
+    )
+}
+
+#[test]
+fn session_context_is_echoed_and_spans_rounds() {
+    let (mut app, handle) = common::headless_app("test_session_info");
+    app.update();
+    assert_eq!(echoed(&handle), (0, 0, 0, 0, 0));
+
+    // Written between frames, echoed on the next one
+    let session = 20261017143005;
+    write_session(&handle, 37, 200, 2, session, 7);
+    app.update();
+    assert_eq!(echoed(&handle), (37, 200, 2, session, 7));
+    let state = state_json(handle.get());
+    assert_eq!(state["current_trial_index"], 37);
+    assert_eq!(state["total_trials"], 200);
+    assert_eq!(state["block_index"], 2);
+    assert_eq!(state["session_id"], session);
+    assert_eq!(state["subject_id"], 7);
+
+    // A reset keeps it, the next trial's write replaces it
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(echoed(&handle), (37, 200, 2, session, 7));
+    write_session(&handle, 38, 200, 2, session, 7);
+    app.update();
+    assert_eq!(echoed(&handle).0, 38);
+}
@@ -0,0 +1,69 @@
+//! Browser session persistence: the progress record round-trips through its JSON, stale records are
+//! not fresh, and the game counts wins and saves a record every SESSION_SAVE_INTERVAL_FRAMES frames.
+//! localStorage itself only exists in the browser; natively the last record is only kept in memory.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, SharedMemResource};
+use game_node::web_adapter::{SessionProgress, SessionRecord, WebAdapterPlugin};
+use shared::constants::game_constants::SESSION_SAVE_INTERVAL_FRAMES;
+use shared::create_shared_memory;
+
+#[test]
+fn record_json_and_age() {
+    let record = SessionRecord {
+        trial_id_hash: u64::MAX - 1,
+        generation: 12,
+        wins: 7,
+        attempts: 3,
+        elapsed_secs: 4.5,
+        saved_at_ms: 1_700_000_000_123.0,
+    };
+    assert_eq!(SessionRecord::from_json(&record.to_json()), Some(record.clone()));
+    assert!(record.to_json().contains("\"trial_id_hash\":\"fffffffffffffffe\""), "the hash as hex");
+    assert_eq!(SessionRecord::from_json("{\"wins\": 1}"), None);
+    assert_eq!(SessionRecord::from_json("garbage"), None);
+
+    assert!(record.is_fresh(record.saved_at_ms + 10_000.0, 60.0));
+    assert!(!record.is_fresh(record.saved_at_ms + 61_000.0, 60.0));
+    assert!(!record.is_fresh(record.saved_at_ms - 1_000.0, 60.0));
+}
+
+#[test]
+fn progress_is_saved_periodically() {
+    let handle = create_shared_memory("test_session_persistence").expect("shared memory");
+    let gs_game = &handle.get().game_structure_game;
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((MinimalPlugins, CommandHandlerPlugin, WebAdapterPlugin));
+
+    gs_game.trial_generation.store(4, Ordering::Relaxed);
+    gs_game.trial_id_hash.store(0xabc, Ordering::Relaxed);
+    gs_game.attempts.store(2, Ordering::Relaxed);
+    for _ in 1..SESSION_SAVE_INTERVAL_FRAMES {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<SessionProgress>().last_saved, None);
+    app.update();
+    let saved = app.world().resource::<SessionProgress>().last_saved.clone().expect("saved");
+    assert_eq!((saved.trial_id_hash, saved.generation, saved.wins, saved.attempts), (0xabc, 4, 0, 2));
+
+    // A won trial counts once, however long its win lasts
+    gs_game.win_time.store(1, Ordering::Relaxed);
+    for _ in 0..SESSION_SAVE_INTERVAL_FRAMES {
+        app.update();
+    }
+    gs_game.trial_generation.store(5, Ordering::Relaxed);
+    gs_game.win_time.store(0, Ordering::Relaxed);
+    for _ in 0..SESSION_SAVE_INTERVAL_FRAMES {
+        app.update();
+    }
+    let saved = app.world().resource::<SessionProgress>().last_saved.clone().expect("saved");
+    assert_eq!((saved.generation, saved.wins), (5, 1));
+
+    // Starting over forgets the wins and the saved record
+    let mut progress = app.world_mut().resource_mut::<SessionProgress>();
+    assert!(progress.has_record());
+    progress.start_over();
+    assert!(!progress.has_record() && progress.wins == 0);
+}
@@ -0,0 +1,33 @@
+//! Shaping dwell accumulation used by the timer-based auto-win.
+use game_node::utils::game_functions::accumulate_dwell;
+use shared::constants::game_constants::SHAPING_COSINE_TOLERANCE;
+
+const FRAME_SECS: f32 = 1.0 / 60.0;
+
+#[test]
+fn dwell_accumulates_while_within_tolerance() {
+    let mut dwell = 0.0;
+    for _ in 0..60 {
+        dwell = accumulate_dwell(dwell, FRAME_SECS, 0.9, SHAPING_COSINE_TOLERANCE);
+    }
+    assert!((dwell - 1.0).abs() < 1e-4, "expected ~1s of dwell, got {dwell}");
+}
+
+#[test]
+fn dwell_restarts_when_leaving_tolerance() {
+    let mut dwell = 0.0;
+    for _ in 0..30 {
+        dwell = accumulate_dwell(dwell, FRAME_SECS, 0.9, SHAPING_COSINE_TOLERANCE);
+    }
+    dwell = accumulate_dwell(dwell, FRAME_SECS, SHAPING_COSINE_TOLERANCE - 0.1, SHAPING_COSINE_TOLERANCE);
+    assert_eq!(dwell, 0.0);
+
+    dwell = accumulate_dwell(dwell, FRAME_SECS, 0.9, SHAPING_COSINE_TOLERANCE);
+    assert!((dwell - FRAME_SECS).abs() < 1e-6);
+}
+
+#[test]
+fn dwell_requires_strictly_exceeding_tolerance() {
+    let dwell = accumulate_dwell(0.5, FRAME_SECS, SHAPING_COSINE_TOLERANCE, SHAPING_COSINE_TOLERANCE);
+    assert_eq!(dwell, 0.0);
+}
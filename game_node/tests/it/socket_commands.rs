@@ -0,0 +1,132 @@
+//! Commands over the Unix domain socket, alongside shared memory.
+#![cfg(all(feature = "socket_commands", unix))]
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, PendingReferenceImage, PendingReset, PendingRotation, SharedMemResource};
+use game_node::socket_commands::{CommandSocket, SocketCommandsPlugin};
+use game_node::state_emitter::StateEmitterPlugin;
+use game_node::utils::objects::RoundStartTimestamp;
+use shared::{create_shared_memory, DebouncedCommand, SeenCommand};
+use std::io::Write;
+use std::os::unix::net::{UnixListener, UnixStream};
+
+#[test]
+fn socket_pulses_holds_and_survives_bad_input() {
+    let path = std::env::temp_dir().join("monkey_game_test_socket_commands.sock");
+    let handle = create_shared_memory("test_socket_commands").expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle))
+        .insert_resource(CommandSocket::bind(&path).expect("bind socket"))
+        .add_plugins((CommandHandlerPlugin, SocketCommandsPlugin));
+
+    let mut client = UnixStream::connect(&path).expect("connect");
+    client.write_all(b"rotate_left\nbogus\nreset 7\n\xff\nreset 1\n").unwrap();
+    app.update();
+    assert!(app.world().resource::<PendingRotation>().0 < 0.0, "pulse applied");
+    assert!(app.world().resource::<PendingReset>().0, "held command applied");
+
+    // Pulse lasts one frame, the held command stays until released
+    app.update();
+    assert_eq!(app.world().resource::<PendingRotation>().0, 0.0);
+    assert!(app.world().resource::<PendingReset>().0);
+
+    // Disconnecting releases everything the client held
+    drop(client);
+    app.update();
+    assert!(!app.world().resource::<PendingReset>().0);
+}
+
+#[test]
+fn bind_only_replaces_a_stale_socket() {
+    let path = std::env::temp_dir().join("monkey_game_test_socket_bind.sock");
+    let _ = std::fs::remove_file(&path);
+
+    // A file that is not a socket is left alone
+    std::fs::write(&path, "not a socket").unwrap();
+    assert!(CommandSocket::bind(&path).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+    std::fs::remove_file(&path).unwrap();
+
+    // So is a socket something still listens on
+    let live = UnixListener::bind(&path).unwrap();
+    assert!(CommandSocket::bind(&path).is_err());
+    assert!(UnixStream::connect(&path).is_ok(), "the live socket is kept");
+
+    // Once its owner is gone, the socket file is stale and replaced
+    drop(live);
+    let socket = CommandSocket::bind(&path).expect("bind over a stale socket");
+    assert!(UnixStream::connect(&path).is_ok());
+    drop(socket);
+    assert!(!path.exists());
+}
+
+#[test]
+fn socket_step_frame_advances_one_frame_under_a_held_stop() {
+    let path = std::env::temp_dir().join("monkey_game_test_socket_step.sock");
+    let handle = create_shared_memory("test_socket_step").expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(RoundStartTimestamp::default())
+        .insert_resource(CommandSocket::bind(&path).expect("bind socket"))
+        .add_plugins((CommandHandlerPlugin, SocketCommandsPlugin, StateEmitterPlugin));
+    let frame = || handle.get().game_structure_game.frame_number.load(Ordering::Relaxed);
+
+    let mut client = UnixStream::connect(&path).expect("connect");
+    client.write_all(b"stop_rendering 1\n").unwrap();
+    app.update();
+    app.update();
+    let paused_frame = frame();
+    app.update();
+    assert_eq!(frame(), paused_frame, "held stop pauses");
+
+    // One step, then paused again while the stop is still held
+    client.write_all(b"step_frame\n").unwrap();
+    app.update();
+    assert_eq!(frame(), paused_frame + 1);
+    app.update();
+    app.update();
+    assert_eq!(frame(), paused_frame + 1);
+
+    // A resume pulse also wins over the held stop for its frame
+    client.write_all(b"resume_rendering\n").unwrap();
+    app.update();
+    assert_eq!(frame(), paused_frame + 2);
+}
+
+#[test]
+fn socket_commands_are_debounced_seen_and_carry_values() {
+    let path = std::env::temp_dir().join("monkey_game_test_socket_intake.sock");
+    let handle = create_shared_memory("test_socket_intake").expect("shared memory");
+    let gs_game = &handle.get().game_structure_game;
+    gs_game.debounce_frames[DebouncedCommand::Reset as usize].store(10, Ordering::Relaxed);
+    handle.get().game_structure_staged.iti_frames.store(42, Ordering::Relaxed);
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(CommandSocket::bind(&path).expect("bind socket"))
+        .add_plugins((CommandHandlerPlugin, SocketCommandsPlugin));
+    let seen_count = |command: SeenCommand| gs_game.commands_seen_counts[command as usize].load(Ordering::Relaxed);
+
+    let mut client = UnixStream::connect(&path).expect("connect");
+    client.write_all(b"reset\nshow_image 1\nshow_image x\npromote_staged\n").unwrap();
+    app.update();
+    assert!(app.world().resource::<PendingReset>().0);
+    assert_eq!(app.world().resource::<PendingReferenceImage>().0, Some(1));
+    assert_eq!(handle.get().game_structure_control.iti_frames.load(Ordering::Relaxed), 42, "staged config promoted");
+    let seen = gs_game.commands_seen.load(Ordering::Relaxed);
+    for command in [SeenCommand::Reset, SeenCommand::ShowImage, SeenCommand::PromoteStaged] {
+        assert_ne!(seen & command.bit(), 0, "{command:?} reported");
+        assert_eq!(seen_count(command), 1);
+    }
+
+    // A second reset inside the window is seen and counted, not acted on
+    client.write_all(b"reset\n").unwrap();
+    app.update();
+    assert!(!app.world().resource::<PendingReset>().0);
+    assert_eq!(gs_game.debounced_count.load(Ordering::Relaxed), 1);
+    assert_eq!(gs_game.commands_seen.load(Ordering::Relaxed), SeenCommand::Reset.bit());
+    assert_eq!(seen_count(SeenCommand::Reset), 2);
+}
@@ -0,0 +1,90 @@
+//! Staged config block: written while a trial runs, promoted atomically for the next reset.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, SharedMemResource};
+use shared::{create_shared_memory, SharedGameStructure, SharedMemory};
+
+/// Writes config `k` into the staged block the way the Python helper does, then latches the promotion
+fn stage(shm: &SharedMemory, k: u32) {
+    let gs = &shm.game_structure_staged;
+    for seed in &gs.decoration_seeds {
+        seed.store(k as u64, Ordering::Relaxed);
+    }
+    gs.base_radius.store((k as f32).to_bits(), Ordering::Relaxed);
+    gs.height.store((k as f32).to_bits(), Ordering::Relaxed);
+    gs.target_door.store(k % 3, Ordering::Relaxed);
+    gs.max_attempts.store(k, Ordering::Relaxed);
+    gs.distractor_seed.store(k as u64, Ordering::Relaxed);
+    shm.commands.promote_staged.store(true, Ordering::Release);
+}
+
+/// The config `k` every field was written from, panicking if the block mixes two configs
+fn config_id(gs: &SharedGameStructure) -> u32 {
+    let k = gs.max_attempts.load(Ordering::Relaxed);
+    for seed in &gs.decoration_seeds {
+        assert_eq!(seed.load(Ordering::Relaxed), k as u64, "torn decoration seeds");
+    }
+    assert_eq!(f32::from_bits(gs.base_radius.load(Ordering::Relaxed)), k as f32, "torn base_radius");
+    assert_eq!(f32::from_bits(gs.height.load(Ordering::Relaxed)), k as f32, "torn height");
+    assert_eq!(gs.target_door.load(Ordering::Relaxed), k % 3, "torn target_door");
+    assert_eq!(gs.distractor_seed.load(Ordering::Relaxed), k as u64, "torn distractor_seed");
+    k
+}
+
+#[test]
+fn promote_with_reset_starts_the_round_on_the_staged_config() {
+    let (mut app, handle) = common::headless_app("test_staged_config_reset");
+    let shm = handle.get();
+    for k in 1..=20 {
+        // Staged while the current round keeps running: nothing changes yet
+        stage(shm, k);
+        if k > 1 {
+            assert_eq!(config_id(&shm.game_structure_game), k - 1);
+        }
+
+        common::reset(&mut app, &handle);
+        assert!(!shm.commands.promote_staged.load(Ordering::Acquire), "promotion consumed");
+        assert_eq!(config_id(&shm.game_structure_game), k, "round {k} uses its staged config");
+        app.update();
+    }
+}
+
+#[test]
+fn rapid_staging_never_tears_the_promoted_config() {
+    let handle = create_shared_memory("test_staged_config_stress").expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins(CommandHandlerPlugin);
+
+    const CONFIGS: u32 = 2000;
+    let writer = {
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            let shm = handle.get();
+            for k in 1..=CONFIGS {
+                // Same handshake as write_staged_config: wait for the previous promotion
+                while shm.commands.promote_staged.load(Ordering::Acquire) {
+                    std::thread::yield_now();
+                }
+                stage(shm, k);
+            }
+        })
+    };
+
+    let shm = handle.get();
+    let mut last = 0;
+    loop {
+        app.update();
+        let k = config_id(&shm.game_structure_control);
+        assert!(k >= last, "promoted configs arrive in order");
+        last = k;
+        if writer.is_finished() && !shm.commands.promote_staged.load(Ordering::Acquire) {
+            break;
+        }
+    }
+    writer.join().unwrap();
+    assert_eq!(config_id(&shm.game_structure_control), CONFIGS);
+}
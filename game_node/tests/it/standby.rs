@@ -0,0 +1,92 @@
+//! Standby: a fresh game dims the screen and refuses checks (and the shaping dwell) until the first
+//! reset loads a trial, and `--autostart` loads the compiled-in one by itself.
+use crate::common;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use clap::Parser;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::cli::Cli;
+use game_node::command_handler::SharedMemResource;
+use game_node::headless::HeadlessPlugin;
+use game_node::utils::game_functions::target_index;
+use game_node::utils::objects::BaseDoor;
+use game_node::utils::systems_logic::{Autostart, StandbyOverlay};
+use shared::json_api::state_json;
+use shared::{create_shared_memory, Phase, SharedMemoryHandle};
+
+fn phase(handle: &SharedMemoryHandle) -> Phase {
+    Phase::from_u32(handle.get().game_structure_game.phase.load(Ordering::Relaxed)).unwrap()
+}
+
+fn overlay_shown(app: &mut App) -> bool {
+    let world = app.world_mut();
+    world.query_filtered::<(), With<StandbyOverlay>>().iter(world).count() == 1
+}
+
+#[test]
+fn stands_by_until_the_first_reset() {
+    let (mut app, handle) = common::headless_app("test_standby");
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(phase(&handle), Phase::Standby);
+    assert!(overlay_shown(&mut app));
+
+    // Refused and counted, not an attempt
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(gs_game.standby_checks.load(Ordering::Relaxed), 1);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 0);
+    assert_eq!(state_json(handle.get())["standby_checks"], 1);
+
+    common::reset(&mut app, &handle);
+    assert_eq!(phase(&handle), Phase::Resetting);
+    app.update();
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert!(!overlay_shown(&mut app));
+
+    // Checks count as attempts from then on
+    common::check_alignment(&mut app, &handle);
+    assert_eq!(gs_game.attempts.load(Ordering::Relaxed), 1);
+    assert_eq!(gs_game.standby_checks.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn shaping_dwell_does_not_win_in_standby() {
+    let (mut app, handle) = common::headless_app("test_standby_dwell");
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)));
+    let gs_game = &handle.get().game_structure_game;
+    gs_game.shaping_dwell_secs.store(0.3f32.to_bits(), Ordering::Relaxed);
+    app.update();
+    // Nothing is loaded in standby, so give the dwell a target door to look at
+    let door_index = target_index(gs_game);
+    app.world_mut()
+        .spawn((Transform::default(), BaseDoor { door_index, normal: Vec3::NEG_Z, is_open: false }));
+    common::face_target(&mut app);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(phase(&handle), Phase::Standby);
+    assert_eq!(gs_game.win_time.load(Ordering::Relaxed), 0, "dwelt to a win in standby");
+    assert!(!gs_game.is_animating.load(Ordering::Relaxed));
+}
+
+#[test]
+fn autostart_plays_without_a_reset() {
+    let cli = Cli::try_parse_from(["game_node", "--autostart"]).unwrap();
+    assert!(cli.autostart);
+    assert!(!Cli::try_parse_from(["game_node"]).unwrap().autostart);
+
+    let handle = create_shared_memory("test_standby_autostart").expect("shared memory");
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone())).insert_resource(Autostart).add_plugins(HeadlessPlugin);
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(phase(&handle), Phase::Playing);
+    assert!(!overlay_shown(&mut app));
+    assert_eq!(handle.get().game_structure_game.reset_ack.load(Ordering::Acquire), 1);
+}
@@ -0,0 +1,68 @@
+//! Double-buffered state snapshot: published once per frame with the emitted fields, and never
+//! torn for a reader racing the publisher.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::snapshot::{SharedStateSnapshot, StateSnapshot};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+#[test]
+fn snapshot_matches_the_emitted_fields() {
+    let (mut app, handle) = common::headless_app("test_state_snapshot");
+    let published = || handle.get().state_snapshot.sequence.load(Ordering::Relaxed);
+    let startup = published();
+    assert!(startup > 0, "published from the startup frame on");
+
+    common::reset(&mut app, &handle);
+    app.update();
+    let gs_game = &handle.get().game_structure_game;
+    let snapshot = handle.read_snapshot().expect("published");
+    assert_eq!(snapshot, StateSnapshot::capture(gs_game));
+    assert_eq!((snapshot.reset_ack, snapshot.trial_generation), (1, 1));
+    assert!(snapshot.camera_radius > 0.0);
+    assert_eq!(published(), startup + 2, "one publication per frame");
+
+    let frame = snapshot.frame_number;
+    common::check_alignment(&mut app, &handle);
+    let snapshot = handle.read_snapshot().expect("published");
+    assert_eq!(snapshot.frame_number, frame + 1);
+    assert_eq!(snapshot.attempts, 1);
+}
+
+#[test]
+fn concurrent_reads_see_whole_snapshots() {
+    let snapshot = Arc::new(SharedStateSnapshot::new());
+    assert_eq!(snapshot.read(), None, "nothing published yet");
+    let done = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let (snapshot, done) = (snapshot.clone(), done.clone());
+        std::thread::spawn(move || {
+            for frame in 1..=20_000u64 {
+                let value = frame as f32;
+                snapshot.publish(&StateSnapshot {
+                    frame_number: frame,
+                    attempts: frame as u32,
+                    elapsed_secs: value,
+                    camera_position: [value; 3],
+                    pyramid_yaw: value,
+                    ..Default::default()
+                });
+            }
+            done.store(true, Ordering::Release);
+        })
+    };
+
+    let mut last = 0;
+    while !done.load(Ordering::Acquire) {
+        let Some(read) = snapshot.read() else { continue };
+        let value = read.frame_number as f32;
+        assert_eq!(read.attempts as u64, read.frame_number);
+        assert_eq!((read.elapsed_secs, read.pyramid_yaw), (value, value));
+        assert_eq!(read.camera_position, [value; 3]);
+        assert!(read.frame_number >= last, "never goes back to an older snapshot");
+        last = read.frame_number;
+    }
+    writer.join().unwrap();
+    assert_eq!(snapshot.read().map(|read| read.frame_number), Some(20_000));
+}
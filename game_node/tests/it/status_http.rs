@@ -0,0 +1,70 @@
+//! HTTP status endpoint: serves the game's latest state snapshot as JSON from its own thread, and
+//! answers only GET.
+#![cfg(all(feature = "status_http", not(target_arch = "wasm32")))]
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use game_node::status_http::StatusServer;
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+/// Sends one raw request and returns the status line and the body
+fn request(addr: SocketAddr, request: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(addr).expect("connect");
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").expect("headers end");
+    (head.lines().next().unwrap().to_string(), body.to_string())
+}
+
+fn status(addr: SocketAddr) -> Value {
+    let (status_line, body) = request(addr, "GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert_eq!(status_line, "HTTP/1.1 200 OK");
+    serde_json::from_str(&body).expect("JSON body")
+}
+
+#[test]
+fn serves_the_latest_snapshot() {
+    let (mut app, handle) = common::headless_app("test_status_http");
+    let server = StatusServer::spawn("127.0.0.1:0", handle.clone()).expect("bind");
+    let addr = server.local_addr();
+    app.insert_resource(server);
+
+    common::reset(&mut app, &handle);
+    app.update();
+    let first = status(addr);
+    let gs_game = &handle.get().game_structure_game;
+    assert_eq!(first["trial_index"], gs_game.trial_generation.load(Ordering::Relaxed));
+    assert_eq!(first["attempts"], 0);
+    assert_eq!(first["has_won"], false);
+    assert_eq!(first["phase"], first["state"]["phase"]);
+    assert_eq!(first["frame"], first["state"]["frame_number"]);
+
+    // A winning check shows up on the next poll
+    common::face_target(&mut app);
+    common::check_alignment(&mut app, &handle);
+    app.update();
+    let won = status(addr);
+    assert_eq!(won["attempts"], 1);
+    assert_eq!(won["has_won"], true);
+    assert!(won["frame"].as_u64() > first["frame"].as_u64());
+}
+
+#[test]
+fn only_get_is_served() {
+    let handle = shared::create_shared_memory("test_status_http_methods").expect("shared memory");
+    let server = StatusServer::spawn("127.0.0.1:0", handle).expect("bind");
+    let addr = server.local_addr();
+
+    // Before the game's first frame the fields are null
+    assert!(status(addr)["frame"].is_null());
+    assert_eq!(request(addr, "GET / HTTP/1.1\r\n\r\n").0, "HTTP/1.1 200 OK");
+    assert_eq!(request(addr, "GET /config HTTP/1.1\r\n\r\n").0, "HTTP/1.1 404 Not Found");
+    assert_eq!(request(addr, "POST /status HTTP/1.1\r\n\r\n").0, "HTTP/1.1 405 Method Not Allowed");
+
+    // Dropping the server closes the port
+    drop(server);
+    assert!(TcpStream::connect(addr).is_err());
+}
@@ -0,0 +1,41 @@
+//! Single-frame stepping while rendering is stopped.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, SharedMemResource};
+use game_node::state_emitter::StateEmitterPlugin;
+use game_node::utils::objects::RoundStartTimestamp;
+use shared::create_shared_memory;
+
+#[test]
+fn step_frame_advances_exactly_one_frame() {
+    let handle = create_shared_memory("test_step_frame").expect("shared memory");
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(RoundStartTimestamp::default())
+        .add_plugins((CommandHandlerPlugin, StateEmitterPlugin));
+
+    let shm = handle.get();
+    let frame = || shm.game_structure_game.frame_number.load(Ordering::Relaxed);
+
+    // Running: frames advance
+    app.update();
+    app.update();
+    assert_eq!(frame(), 2);
+
+    // Stopped: frames hold
+    shm.commands.stop_rendering.store(true, Ordering::Relaxed);
+    app.update();
+    app.update();
+    let paused_frame = frame();
+    assert_eq!(paused_frame, 2);
+
+    // One step, then paused again even though nothing cleared the stop command
+    shm.commands.step_frame.store(true, Ordering::Relaxed);
+    app.update();
+    assert_eq!(frame(), paused_frame + 1);
+    app.update();
+    app.update();
+    assert_eq!(frame(), paused_frame + 1);
+}
@@ -0,0 +1,168 @@
+//! Stimulus description: the hash is pinned (so it stays the same across platforms and releases),
+//! independent of the decoration order and sensitive to every part of the stimulus; each reset emits
+//! it and, with an archive directory, writes the description to the trial's file.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use game_node::utils::stimulus::{StimulusArchive, StimulusDecoration, StimulusDescription, LEGACY_DECORATION_COLOR};
+use shared::SharedGameStructure;
+
+fn decorations() -> Vec<StimulusDecoration> {
+    vec![
+        StimulusDecoration { face: 0, shape: 1, size: 0.1, color: [0.0, 0.0, 0.0, 1.0], position: [0.25, 0.5, -0.125] },
+        StimulusDecoration { face: 2, shape: 3, size: 0.05, color: [1.0, 1.0, 1.0, 1.0], position: [-0.5, 0.75, 0.0] },
+        StimulusDecoration { face: 0, shape: 1, size: 0.1, color: [0.0, 0.0, 0.0, 1.0], position: [-0.25, 0.5, 0.375] },
+    ]
+}
+
+/// Hash of the default config with `decorations()` as archived before the decoration colors
+/// (version 0), must never change
+const PINNED_HASH: u64 = 0x220c2a3b851a9b42;
+
+/// Hash of the same stimulus with its decoration colors (version 1), must never change either
+const PINNED_COLORED_HASH: u64 = 0x218eb782871909d2;
+
+#[test]
+fn hash_is_stable_and_covers_the_stimulus() {
+    let gs = SharedGameStructure::new();
+    let description = StimulusDescription::build(&gs, decorations());
+    assert_eq!(description.hash(), PINNED_COLORED_HASH, "{:#018x}", description.hash());
+
+    // Entity query order does not matter
+    let mut reversed = decorations();
+    reversed.reverse();
+    assert_eq!(StimulusDescription::build(&gs, reversed), description);
+
+    // A moved or recolored decoration, a recolored face or another seed is another stimulus
+    let mut moved = decorations();
+    moved[1].position[1] += 1e-3;
+    assert_ne!(StimulusDescription::build(&gs, moved).hash(), PINNED_COLORED_HASH);
+    let mut recolored = decorations();
+    recolored[0].color[0] = 0.5;
+    assert_ne!(StimulusDescription::build(&gs, recolored).hash(), PINNED_COLORED_HASH);
+    assert_ne!(StimulusDescription::build(&gs, decorations()[..2].to_vec()).hash(), PINNED_COLORED_HASH);
+    gs.colors[5].store(0.5f32.to_bits(), Ordering::Relaxed);
+    assert_ne!(StimulusDescription::build(&gs, decorations()).hash(), PINNED_COLORED_HASH);
+    let gs = SharedGameStructure::new();
+    gs.decoration_seeds[1].fetch_add(1, Ordering::Relaxed);
+    assert_ne!(StimulusDescription::build(&gs, decorations()).hash(), PINNED_COLORED_HASH);
+}
+
+#[test]
+fn archives_without_decoration_colors_keep_their_hash() {
+    let description = StimulusDescription::build(&SharedGameStructure::new(), decorations());
+    let mut older: serde_json::Value = serde_json::from_str(&description.to_json()).unwrap();
+    older.as_object_mut().unwrap().remove("version").expect("version field");
+    for decoration in older["decorations"].as_array_mut().unwrap() {
+        decoration.as_object_mut().unwrap().remove("color").expect("color field");
+    }
+
+    let loaded = StimulusDescription::from_json(&older.to_string()).unwrap();
+    assert_eq!(loaded.version, 0);
+    assert!(loaded.decorations.iter().all(|d| d.color == LEGACY_DECORATION_COLOR));
+    assert_eq!(loaded.hash(), PINNED_HASH, "{:#018x}", loaded.hash());
+}
+
+#[test]
+fn presentation_is_described_hashed_and_applied() {
+    let gs = SharedGameStructure::new();
+    gs.fog_enabled.store(true, Ordering::Relaxed);
+    gs.fog_start.store(2.0f32.to_bits(), Ordering::Relaxed);
+    gs.fog_end.store(9.0f32.to_bits(), Ordering::Relaxed);
+    gs.mirror_x.store(true, Ordering::Relaxed);
+    let description = StimulusDescription::build(&gs, decorations());
+    assert!(description.fog_enabled && description.mirror_x);
+    assert_eq!(description.fog_range, [2.0, 9.0]);
+    assert_ne!(description.hash(), PINNED_COLORED_HASH, "fog and mirroring are part of the stimulus");
+
+    // An archive written before the presentation fields reads as static, unmirrored and fog-free,
+    // with the hash it was archived with
+    let plain = StimulusDescription::build(&SharedGameStructure::new(), decorations());
+    let mut older: serde_json::Value = serde_json::from_str(&plain.to_json()).unwrap();
+    let presentation = [
+        "decoration_motion", "decoration_spin_rate", "decoration_pulse", "mirror_x", "outline_width", "outline_color",
+        "fog_enabled", "fog_color", "fog_range",
+    ];
+    for field in presentation {
+        older.as_object_mut().unwrap().remove(field).expect("presentation field");
+    }
+    assert_eq!(StimulusDescription::from_json(&older.to_string()).unwrap().hash(), PINNED_COLORED_HASH);
+
+    // Replaying it brings the fog and the mirroring back
+    let replayed = SharedGameStructure::new();
+    StimulusDescription::from_json(&description.to_json()).unwrap().apply_to(&replayed);
+    assert!(replayed.fog_enabled.load(Ordering::Relaxed) && replayed.mirror_x.load(Ordering::Relaxed));
+    assert_eq!(f32::from_bits(replayed.fog_end.load(Ordering::Relaxed)), 9.0);
+    assert_eq!(StimulusDescription::build(&replayed, decorations()), description);
+}
+
+/// Description of the round as spawned, rebuilt from the decoration entities
+fn spawned_description(app: &mut App, gs: &SharedGameStructure) -> StimulusDescription {
+    let world = app.world_mut();
+    let decorations = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(marker, transform)| StimulusDecoration {
+            face: marker.face_index as u32,
+            shape: marker.shape as u32,
+            size: marker.size,
+            color: marker.color,
+            position: transform.translation.to_array(),
+        })
+        .collect();
+    StimulusDescription::build(gs, decorations)
+}
+
+#[test]
+fn every_reset_emits_and_archives_the_stimulus() {
+    let (mut app, handle) = common::headless_app("test_stimulus_description");
+    let dir = std::env::temp_dir().join(format!("test_stimulus_archive_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    app.insert_resource(StimulusArchive(dir.clone()));
+    let gs_game = &handle.get().game_structure_game;
+    let gs_ctrl = &handle.get().game_structure_control;
+
+    common::reset(&mut app, &handle);
+    app.update();
+    let description = spawned_description(&mut app, gs_game);
+    assert!(!description.decorations.is_empty());
+    let hash = gs_game.stimulus_hash.load(Ordering::Relaxed);
+    assert_eq!(hash, description.hash());
+
+    // Named by the trial generation it was acknowledged with and the stimulus hash
+    let trial = gs_game.reset_ack.load(Ordering::Relaxed);
+    assert!(description.file_name(trial).ends_with(&format!("_{hash:016x}.json")));
+    let contents = std::fs::read_to_string(dir.join(description.file_name(trial))).expect("archive file");
+    let archived: serde_json::Value = serde_json::from_str(&contents).expect("archive is JSON");
+    assert_eq!(archived["trial"], trial);
+    assert_eq!(archived["stimulus_hash"], hash);
+    assert_eq!(archived["stimulus"], serde_json::from_str::<serde_json::Value>(&description.to_json()).unwrap());
+
+    // The same config draws the same stimulus, another seed another one
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(gs_game.stimulus_hash.load(Ordering::Relaxed), hash);
+    gs_ctrl.decoration_seeds[0].fetch_add(1, Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_ne!(gs_game.stimulus_hash.load(Ordering::Relaxed), hash);
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 3);
+
+    // A later session restarts reset_ack: a different stimulus with the same seeds gets its own file,
+    // and an archive with the same name is not overwritten
+    let earlier = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+    let mut other = description.clone();
+    other.colors[0][0] += 0.5;
+    assert_ne!(other.file_name(trial), description.file_name(trial));
+    let (mut app, handle) = common::headless_app("test_stimulus_description_next_session");
+    app.insert_resource(StimulusArchive(dir.clone()));
+    for path in &earlier {
+        std::fs::write(path, "kept\n").unwrap();
+    }
+    common::reset(&mut app, &handle);
+    app.update();
+    assert!(earlier.iter().all(|path| std::fs::read_to_string(path).unwrap() == "kept\n"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,118 @@
+//! Replaying an archived stimulus: a round loaded from its archive file is the archived pyramid,
+//! whatever the trial's own seeds and colors, and a file that cannot be replayed falls back to the
+//! procedural round with its status flagged.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::DecorationMarker;
+use game_node::utils::stimulus::{StimulusArchive, StimulusDecoration, StimulusDescription};
+use shared::{SharedGameStructure, SharedMemoryHandle, StimulusFileStatus};
+
+/// Spawned decorations as (face, shape, translation bits, rotation bits), sorted
+fn spawned_decorations(app: &mut App) -> Vec<(usize, u32, [u32; 3], [u32; 4])> {
+    let world = app.world_mut();
+    let mut decorations: Vec<_> = world
+        .query::<(&DecorationMarker, &Transform)>()
+        .iter(world)
+        .map(|(marker, transform)| {
+            (
+                marker.face_index,
+                marker.shape as u32,
+                transform.translation.to_array().map(f32::to_bits),
+                transform.rotation.to_array().map(f32::to_bits),
+            )
+        })
+        .collect();
+    decorations.sort();
+    decorations
+}
+
+fn status(gs_game: &SharedGameStructure) -> Option<StimulusFileStatus> {
+    StimulusFileStatus::from_u32(gs_game.stimulus_file_status.load(Ordering::Relaxed))
+}
+
+fn round(app: &mut App, handle: &SharedMemoryHandle) {
+    common::reset(app, handle);
+    app.update();
+}
+
+#[test]
+fn json_round_trips() {
+    let (mut app, handle) = common::headless_app("test_stimulus_json");
+    round(&mut app, &handle);
+    let gs_game = &handle.get().game_structure_game;
+    let world = app.world_mut();
+    let decorations = world
+        .query::<&DecorationMarker>()
+        .iter(world)
+        .map(|marker| StimulusDecoration {
+            face: marker.face_index as u32,
+            shape: marker.shape as u32,
+            size: marker.size,
+            color: marker.color,
+            position: [0.1, 0.2, 0.3],
+        })
+        .collect();
+    let description = StimulusDescription::build(gs_game, decorations);
+    assert_eq!(StimulusDescription::from_json(&description.to_json()), Ok(description.clone()));
+
+    let line = format!("{{\"trial\": 3, \"stimulus_hash\": 1, \"stimulus\": {}}}\n", description.to_json());
+    assert_eq!(StimulusDescription::from_json(&line), Ok(description));
+    assert!(StimulusDescription::from_json("{\"pyramid_type\": 1}").is_err());
+    assert!(StimulusDescription::from_json("not json").is_err());
+}
+
+#[test]
+fn archived_stimulus_replays_exactly() {
+    let (mut app, handle) = common::headless_app("test_stimulus_replay");
+    let dir = std::env::temp_dir().join(format!("test_stimulus_replay_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    app.insert_resource(StimulusArchive(dir.clone()));
+    let gs_game = &handle.get().game_structure_game;
+    let gs_ctrl = &handle.get().game_structure_control;
+
+    // Archive a procedural round
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::Procedural));
+    let archived_decorations = spawned_decorations(&mut app);
+    let archived_hash = gs_game.stimulus_hash.load(Ordering::Relaxed);
+    let archived_seeds = gs_game.decoration_seeds.each_ref().map(|seed| seed.load(Ordering::Relaxed));
+    let trial = gs_game.reset_ack.load(Ordering::Relaxed);
+    let path = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    assert!(path.ends_with(format!("trial_{trial:05}_seeds_{}_{}_{}_{archived_hash:016x}.json", archived_seeds[0], archived_seeds[1], archived_seeds[2])));
+
+    // Another trial config, replaced by the file
+    for seed in &gs_ctrl.decoration_seeds {
+        seed.fetch_add(7, Ordering::Relaxed);
+    }
+    gs_ctrl.colors[0].store(0.25f32.to_bits(), Ordering::Relaxed);
+    gs_ctrl.set_stimulus_file(path.to_str().unwrap());
+    gs_ctrl.stimulus_expected_hash.store(archived_hash, Ordering::Relaxed);
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::Loaded));
+    assert_eq!(spawned_decorations(&mut app), archived_decorations);
+    assert_eq!(gs_game.stimulus_hash.load(Ordering::Relaxed), archived_hash);
+    assert_eq!(gs_game.decoration_seeds.each_ref().map(|seed| seed.load(Ordering::Relaxed)), archived_seeds);
+
+    // A wrong hash is refused, the trial's own config is drawn instead
+    gs_ctrl.stimulus_expected_hash.store(archived_hash ^ 1, Ordering::Relaxed);
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::HashMismatch));
+    assert_ne!(gs_game.stimulus_hash.load(Ordering::Relaxed), archived_hash);
+    assert_ne!(spawned_decorations(&mut app), archived_decorations);
+
+    gs_ctrl.set_stimulus_file(dir.join("missing.json").to_str().unwrap());
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::Missing));
+
+    std::fs::write(dir.join("broken.json"), "{\"stimulus\": [").unwrap();
+    gs_ctrl.set_stimulus_file(dir.join("broken.json").to_str().unwrap());
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::Invalid));
+
+    gs_ctrl.set_stimulus_file("");
+    round(&mut app, &handle);
+    assert_eq!(status(gs_game), Some(StimulusFileStatus::Procedural));
+    let _ = std::fs::remove_dir_all(&dir);
+}
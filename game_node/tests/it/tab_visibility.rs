@@ -0,0 +1,76 @@
+//! Browser tab visibility: while hidden the game and virtual time are paused, refocusing resumes
+//! without a fixed timestep catch-up, and the hidden time of the trial is emitted. The page listener
+//! is replaced by setting the flag it writes.
+use crate::common;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{CommandHandlerPlugin, RenderingPaused, SharedMemResource};
+use game_node::web_adapter::{TabVisibility, WebAdapterPlugin};
+use shared::create_shared_memory;
+use std::time::Duration;
+
+#[derive(Resource, Default)]
+struct FixedUpdates(u32);
+
+fn set_frame_time(app: &mut App, millis: u64) {
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(millis)));
+}
+
+/// Runs one frame and returns the fixed updates it ran
+fn frame(app: &mut App) -> u32 {
+    app.world_mut().resource_mut::<FixedUpdates>().0 = 0;
+    app.update();
+    app.world().resource::<FixedUpdates>().0
+}
+
+#[test]
+fn hidden_tab_pauses_and_resumes_without_catch_up() {
+    let handle = create_shared_memory("test_tab_visibility").expect("shared memory");
+    let gs_game = &handle.get().game_structure_game;
+    let mut app = common::new_app();
+    app.insert_resource(SharedMemResource(handle.clone()))
+        .add_plugins((MinimalPlugins, CommandHandlerPlugin, WebAdapterPlugin))
+        .insert_resource(Time::<Fixed>::from_hz(60.0))
+        .init_resource::<FixedUpdates>()
+        .add_systems(FixedUpdate, |mut count: ResMut<FixedUpdates>| count.0 += 1);
+    set_frame_time(&mut app, 16);
+    for _ in 0..5 {
+        frame(&mut app);
+    }
+    assert!(!app.world().resource::<RenderingPaused>().0);
+    let hidden = app.world().resource::<TabVisibility>().hidden.clone();
+
+    // Hidden: throttled to one frame a second, nothing advances
+    hidden.store(true, Ordering::Relaxed);
+    frame(&mut app);
+    assert!(app.world().resource::<RenderingPaused>().0);
+    assert!(gs_game.tab_hidden.load(Ordering::Relaxed));
+    let paused_at = app.world().resource::<Time<Virtual>>().elapsed();
+    set_frame_time(&mut app, 1000);
+    for _ in 0..3 {
+        assert_eq!(frame(&mut app), 0);
+        assert!(app.world().resource::<RenderingPaused>().0);
+    }
+    assert_eq!(app.world().resource::<Time<Virtual>>().elapsed(), paused_at);
+    let hidden_secs = f32::from_bits(gs_game.tab_hidden_secs_trial.load(Ordering::Relaxed));
+    assert!((hidden_secs - 3.0).abs() < 1e-3, "hidden {hidden_secs}s");
+
+    // Refocused after another throttled second: no burst, then the normal rate
+    hidden.store(false, Ordering::Relaxed);
+    assert_eq!(frame(&mut app), 0);
+    assert!(!app.world().resource::<RenderingPaused>().0);
+    assert!(!gs_game.tab_hidden.load(Ordering::Relaxed));
+    set_frame_time(&mut app, 16);
+    let updates: u32 = (0..60).map(|_| frame(&mut app)).sum();
+    assert!((57..=60).contains(&updates), "{updates} fixed updates in 60 frames");
+    let resumed = app.world().resource::<Time<Virtual>>().elapsed() - paused_at;
+    assert!(resumed <= Duration::from_millis(61 * 16), "virtual time jumped by {resumed:?}");
+    let hidden_secs = f32::from_bits(gs_game.tab_hidden_secs_trial.load(Ordering::Relaxed));
+    assert!((hidden_secs - 4.0).abs() < 1e-3, "hidden {hidden_secs}s");
+
+    // The next trial starts from zero
+    gs_game.reset_ack.fetch_add(1, Ordering::Relaxed);
+    frame(&mut app);
+    assert_eq!(f32::from_bits(gs_game.tab_hidden_secs_trial.load(Ordering::Relaxed)), 0.0);
+}
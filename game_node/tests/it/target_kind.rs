@@ -0,0 +1,54 @@
+//! Alignment check against the target door or the target face, depending on the target kind.
+use crate::common;
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::{PendingCheckAlignment, SharedMemResource};
+use game_node::utils::game_functions::apply_pending_check_alignment;
+use game_node::utils::objects::{BaseDoor, DoorWinEntities, FaceMarker};
+use shared::{TargetKind, create_shared_memory};
+
+/// Runs one alignment check with the camera looking down -Z at a door facing the camera
+/// and a face turned away from it. Returns the stored target alignment.
+fn check_alignment(name: &str, target_kind: TargetKind, face_facing_camera: bool) -> f32 {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let shm = handle.get();
+    shm.game_structure_control.target_kind.store(target_kind as u32, Ordering::Relaxed);
+    shm.game_structure_control.target_door.store(0, Ordering::Relaxed);
+    shm.game_structure_control.target_face.store(1, Ordering::Relaxed);
+    shm.game_structure_game.reset_all_fields(&shm.game_structure_control);
+
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(PendingCheckAlignment(true))
+        .init_resource::<DoorWinEntities>()
+        .add_systems(Update, apply_pending_check_alignment);
+
+    app.world_mut()
+        .spawn((Camera3d::default(), Transform::from_xyz(0.0, 1.0, 15.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y)));
+    // Inward normals: -Z faces the camera
+    app.world_mut().spawn((Transform::default(), BaseDoor { door_index: 0, normal: Vec3::NEG_Z, is_open: false }));
+    let face_normal = if face_facing_camera { Vec3::NEG_Z } else { Vec3::Z };
+    app.world_mut().spawn((
+        Transform::default(),
+        FaceMarker { face_index: 1, normal: face_normal, base_color: Color::WHITE },
+    ));
+
+    app.update();
+    f32::from_bits(shm.game_structure_game.current_alignment.load(Ordering::Relaxed))
+}
+
+#[test]
+fn door_mode_checks_the_target_door() {
+    let alignment = check_alignment("test_target_kind_door", TargetKind::Door, false);
+    assert!((alignment - 1.0).abs() < 1e-5, "door faces the camera, got {alignment}");
+}
+
+#[test]
+fn face_mode_checks_the_target_face() {
+    let alignment = check_alignment("test_target_kind_face_away", TargetKind::Face, false);
+    assert!((alignment + 1.0).abs() < 1e-5, "face turned away, door ignored, got {alignment}");
+
+    let alignment = check_alignment("test_target_kind_face", TargetKind::Face, true);
+    assert!((alignment - 1.0).abs() < 1e-5, "face faces the camera, got {alignment}");
+}
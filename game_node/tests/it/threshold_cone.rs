@@ -0,0 +1,42 @@
+//! Threshold cone overlay: its half-angle bounds exactly the directions the check accepts, and the
+//! overlay follows the held command.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::command_handler::ShowThresholdCone;
+use game_node::utils::alignment::{is_win, threshold_half_angle, xz_alignment};
+
+#[test]
+fn half_angle_bounds_the_winning_directions() {
+    let normal = Vec3::new(0.6, 0.0, -0.8);
+    for threshold in [-0.5, 0.0, 0.5, 0.9, 0.99] {
+        let half_angle = threshold_half_angle(threshold);
+        assert!((half_angle.cos() - threshold).abs() < 1e-6);
+        for side in [-1.0, 1.0] {
+            let inside = Quat::from_rotation_y(side * (half_angle - 1e-3)) * normal;
+            let outside = Quat::from_rotation_y(side * (half_angle + 1e-3)) * normal;
+            assert!(is_win(xz_alignment(normal, inside), threshold, 0, 0), "threshold {threshold}");
+            assert!(!is_win(xz_alignment(normal, outside), threshold, 0, 0), "threshold {threshold}");
+        }
+    }
+    // Thresholds out of the cosine range give an empty or a full window
+    assert_eq!(threshold_half_angle(1.5), 0.0);
+    assert_eq!(threshold_half_angle(-1.5), std::f32::consts::PI);
+}
+
+#[test]
+fn overlay_follows_the_held_command() {
+    let (mut app, handle) = common::headless_app("test_threshold_cone");
+    assert!(!app.world().resource::<ShowThresholdCone>().0);
+
+    let commands = &handle.get().commands;
+    commands.show_threshold_cone.store(true, Ordering::Relaxed);
+    app.update();
+    app.update();
+    assert!(app.world().resource::<ShowThresholdCone>().0, "stays on while held");
+
+    commands.show_threshold_cone.store(false, Ordering::Relaxed);
+    app.update();
+    assert!(!app.world().resource::<ShowThresholdCone>().0);
+}
@@ -0,0 +1,25 @@
+//! Trial ID: the hash of the controller's trial ID is stable, and the game applies it with the rest
+//! of the trial config at reset.
+use crate::common;
+
+use core::sync::atomic::Ordering;
+use shared::trial_id_hash;
+
+#[test]
+fn trial_id_hash_reaches_the_game() {
+    // FNV-1a reference values, so IDs hash the same in every session and on every platform
+    assert_eq!(trial_id_hash(""), 0);
+    assert_eq!(trial_id_hash("a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(trial_id_hash("foobar"), 0x85944171f73967e8);
+
+    let (mut app, handle) = common::headless_app("test_trial_id");
+    let gs_ctrl = &handle.get().game_structure_control;
+    let gs_game = &handle.get().game_structure_game;
+    let hash = trial_id_hash("session_03/block_2/trial_017");
+    gs_ctrl.trial_id_hash.store(hash, Ordering::Relaxed);
+    assert_eq!(gs_game.trial_id_hash.load(Ordering::Relaxed), 0);
+
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_eq!(gs_game.trial_id_hash.load(Ordering::Relaxed), hash);
+}
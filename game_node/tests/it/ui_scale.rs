@@ -0,0 +1,81 @@
+//! UI scaling: the UiScale follows the smaller of the width and height ratios of the primary window
+//! (or the scene camera's viewport) to the reference resolution, clamped, and ignores other windows.
+use crate::common;
+
+use bevy::camera::Viewport;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResolution};
+use core::sync::atomic::Ordering;
+use game_node::utils::game_functions::ui_scale_for;
+use game_node::utils::objects::PersistentCamera;
+use shared::constants::game_constants::UI_SCALE_RANGE;
+
+fn window(width: u32, height: u32) -> Window {
+    Window { resolution: WindowResolution::new(width, height), ..default() }
+}
+
+fn ui_scale(app: &App) -> f32 {
+    app.world().resource::<UiScale>().0
+}
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+}
+
+#[test]
+fn scale_fits_the_reference_both_ways() {
+    for (size, expected) in [
+        ((1920.0, 1080.0), 1.0),
+        ((1280.0, 720.0), 2.0 / 3.0),
+        ((3840.0, 2160.0), 2.0),
+        // Ultrawide: height bound, tall: width bound
+        ((3440.0, 1440.0), 1440.0 / 1080.0),
+        ((5120.0, 1080.0), 1.0),
+        ((1080.0, 1920.0), 1080.0 / 1920.0),
+        ((2560.0, 1600.0), 2560.0 / 1920.0),
+        // Clamped at both ends
+        ((640.0, 360.0), UI_SCALE_RANGE[0]),
+        ((7680.0, 4320.0), UI_SCALE_RANGE[1]),
+    ] {
+        assert_near(ui_scale_for(Vec2::new(size.0, size.1)), expected);
+    }
+}
+
+#[test]
+fn primary_window_drives_the_scale_and_others_are_ignored() {
+    let (mut app, _handle) = common::headless_app("test_ui_scale");
+    let primary = app.world_mut().spawn((window(3440, 1440), PrimaryWindow)).id();
+    app.update();
+    assert_near(ui_scale(&app), 1440.0 / 1080.0);
+
+    // A second window (experimenter display) changes nothing
+    app.world_mut().spawn(window(800, 600));
+    app.update();
+    assert_near(ui_scale(&app), 1440.0 / 1080.0);
+
+    app.world_mut().get_mut::<Window>(primary).unwrap().resolution.set(1280.0, 1024.0);
+    app.update();
+    assert_near(ui_scale(&app), 1280.0 / 1920.0);
+
+    // A viewport on the scene camera scales the UI to it
+    let world = app.world_mut();
+    let mut camera = world.query_filtered::<&mut Camera, With<PersistentCamera>>().single_mut(world).unwrap();
+    camera.viewport = Some(Viewport { physical_size: UVec2::new(960, 540), ..default() });
+    app.update();
+    assert_near(ui_scale(&app), UI_SCALE_RANGE[0]);
+}
+
+#[test]
+fn configured_scale_overrides_the_window() {
+    let (mut app, handle) = common::headless_app("test_ui_scale_config");
+    app.world_mut().spawn((window(1920, 1080), PrimaryWindow));
+    handle.get().game_structure_control.ui_scale.store(1.5f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_near(ui_scale(&app), 1.5);
+
+    handle.get().game_structure_control.ui_scale.store(0.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(&mut app, &handle);
+    app.update();
+    assert_near(ui_scale(&app), 1.0);
+}
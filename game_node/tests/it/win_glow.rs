@@ -0,0 +1,60 @@
+//! Winning door glow: the configured win_glow_color replaces the hole spotlight's own color for the
+//! light and the emissive of the door animation.
+use crate::common;
+
+use bevy::prelude::*;
+use core::sync::atomic::Ordering;
+use game_node::utils::objects::{DoorWinEntities, HoleLight};
+use shared::SharedMemoryHandle;
+
+const GLOW: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+/// Resets with `glow` and a near instant door opening, then runs the animation into its stay-open phase
+fn animate_with_glow(app: &mut App, handle: &SharedMemoryHandle, glow: [f32; 4]) {
+    let gs_ctrl = &handle.get().game_structure_control;
+    for (slot, value) in gs_ctrl.win_glow_color.iter().zip(glow) {
+        slot.store(value.to_bits(), Ordering::Relaxed);
+    }
+    gs_ctrl.door_anim_fade_out.store(1e-6f32.to_bits(), Ordering::Relaxed);
+    gs_ctrl.door_anim_stay_open.store(100.0f32.to_bits(), Ordering::Relaxed);
+    common::reset(app, handle);
+    app.update();
+
+    let commands = &handle.get().commands;
+    commands.animation_door.store(true, Ordering::Relaxed);
+    app.update();
+    commands.animation_door.store(false, Ordering::Relaxed);
+    app.update();
+    app.update();
+    assert!(handle.get().game_structure_game.is_animating.load(Ordering::Relaxed));
+}
+
+/// Color of the winning hole light and emissive of the winning door
+fn glow_colors(app: &mut App) -> (LinearRgba, LinearRgba) {
+    let wins = app.world().resource::<DoorWinEntities>();
+    let (light, emissive) = (wins.winning_light.unwrap(), wins.winning_emissive.unwrap());
+    let world = app.world_mut();
+    let light_color = world.query_filtered::<&SpotLight, With<HoleLight>>().get(world, light).unwrap().color.to_linear();
+    let material = world.entity(emissive).get::<MeshMaterial3d<StandardMaterial>>().unwrap().0.clone();
+    let emissive = world.resource::<Assets<StandardMaterial>>().get(&material).unwrap().emissive;
+    (light_color, emissive)
+}
+
+#[test]
+fn stay_open_glow_uses_the_configured_color() {
+    let (mut app, handle) = common::headless_app("test_win_glow");
+    animate_with_glow(&mut app, &handle, GLOW);
+    let (light, emissive) = glow_colors(&mut app);
+    assert_eq!(light, Color::srgba(GLOW[0], GLOW[1], GLOW[2], GLOW[3]).to_linear());
+    assert!(emissive.red > 0.0, "{emissive:?}");
+    assert_eq!((emissive.green, emissive.blue), (0.0, 0.0), "only the configured red glows");
+}
+
+#[test]
+fn unset_glow_keeps_the_spotlight_color() {
+    let (mut app, handle) = common::headless_app("test_win_glow_unset");
+    animate_with_glow(&mut app, &handle, [0.0; 4]);
+    let (light, emissive) = glow_colors(&mut app);
+    assert_eq!(light, SpotLight::default().color.to_linear());
+    assert!(emissive.red > 0.0 && emissive.red == emissive.green && emissive.green == emissive.blue, "{emissive:?}");
+}
@@ -0,0 +1,84 @@
+//! Radius band requirement on top of the angular alignment for a win, through an explicit check and
+//! through the shaping dwell.
+use crate::common;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use core::sync::atomic::Ordering;
+use core::time::Duration;
+use game_node::command_handler::{PendingCheckAlignment, SharedMemResource};
+use game_node::utils::game_functions::{apply_pending_check_alignment, apply_shaping_dwell};
+use game_node::utils::objects::{BaseDoor, DoorWinEntities};
+use shared::create_shared_memory;
+
+/// How the aligned camera tries to win
+#[derive(Clone, Copy, Debug)]
+enum WinPath {
+    Check,
+    Dwell,
+}
+
+/// Keeps the camera aligned at orbit radius 15 with the given band, checking every frame or for a
+/// second of 0.5 s shaping dwell. Returns (radius_at_check, blocked, won)
+fn win_with_band(name: &str, band: [f32; 2], path: WinPath) -> (f32, bool, bool) {
+    let handle = create_shared_memory(name).expect("shared memory");
+    let shm = handle.get();
+    shm.game_structure_control.win_radius_min.store(band[0].to_bits(), Ordering::Relaxed);
+    shm.game_structure_control.win_radius_max.store(band[1].to_bits(), Ordering::Relaxed);
+    if let WinPath::Dwell = path {
+        shm.game_structure_control.shaping_dwell_secs.store(0.5f32.to_bits(), Ordering::Relaxed);
+    }
+    shm.game_structure_game.reset_all_fields(&shm.game_structure_control);
+
+    let mut app = common::new_app();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+        .insert_resource(SharedMemResource(handle.clone()))
+        .insert_resource(PendingCheckAlignment(matches!(path, WinPath::Check)))
+        .init_resource::<DoorWinEntities>()
+        .add_systems(Update, (apply_pending_check_alignment, apply_shaping_dwell));
+
+    app.world_mut()
+        .spawn((Camera3d::default(), Transform::from_xyz(0.0, 1.0, 15.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y)));
+    app.world_mut().spawn((Transform::default(), BaseDoor { door_index: 0, normal: Vec3::NEG_Z, is_open: false }));
+
+    // A win is stamped with the elapsed time, still 0 on the first frame
+    let frames = match path {
+        WinPath::Check => 2,
+        WinPath::Dwell => 10,
+    };
+    for _ in 0..frames {
+        app.update();
+    }
+    let gs_game = &shm.game_structure_game;
+    (
+        f32::from_bits(gs_game.radius_at_check.load(Ordering::Relaxed)),
+        gs_game.win_blocked_by_radius.load(Ordering::Relaxed),
+        gs_game.win_time.load(Ordering::Relaxed) != 0,
+    )
+}
+
+#[test]
+fn aligned_check_outside_the_band_is_blocked() {
+    let (radius, blocked, won) = win_with_band("test_win_radius_outside", [12.0, 14.0], WinPath::Check);
+    assert!((radius - 15.0).abs() < 1e-4, "got radius {radius}");
+    assert!(blocked);
+    assert!(!won);
+}
+
+#[test]
+fn band_inside_or_disabled_does_not_block() {
+    let (_, blocked, won) = win_with_band("test_win_radius_inside", [14.0, 16.0], WinPath::Check);
+    assert!(!blocked && won);
+    let (_, blocked, won) = win_with_band("test_win_radius_disabled", [0.0, 0.0], WinPath::Check);
+    assert!(!blocked && won);
+}
+
+#[test]
+fn shaping_dwell_wins_only_inside_the_band() {
+    let (_, _, won) = win_with_band("test_win_radius_dwell_outside", [12.0, 14.0], WinPath::Dwell);
+    assert!(!won, "dwelt outside the band");
+    let (_, _, won) = win_with_band("test_win_radius_dwell_inside", [14.0, 16.0], WinPath::Dwell);
+    assert!(won, "dwelt inside the band");
+    let (_, _, won) = win_with_band("test_win_radius_dwell_disabled", [0.0, 0.0], WinPath::Dwell);
+    assert!(won, "dwelt without a band");
+}
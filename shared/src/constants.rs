@@ -9,18 +9,170 @@ pub mod game_constants {
     // Cosine alignment with door to win
     pub const COSINE_ALIGNMENT_TO_WIN: f32 = 0.95; // approx ~8 degrees
 
+    // Shaping: seconds the target door must stay within the wider tolerance to auto-win (0 disables it)
+    pub const SHAPING_DWELL_SECS: f32 = 0.0;
+    pub const SHAPING_COSINE_TOLERANCE: f32 = 0.7; // approx ~45 degrees
+
+    // Catch trials: no door or face is the target, so no check can win. Trials end after
+    // max_attempts checks or trial_timeout_secs (0 = unlimited), which catch trials require
+    pub const IS_CATCH: bool = false;
+    pub const MAX_ATTEMPTS: u32 = 0;
+    pub const TRIAL_TIMEOUT_SECS: f32 = 0.0;
+
+    // Count checks made while the screen is blanked as rejected instead of as attempts
+    pub const SUPPRESS_BLANKED_ATTEMPTS: bool = false;
+
+    // Orbit radius band the camera must be in for a check to win (a max of 0 disables the requirement)
+    pub const WIN_RADIUS_MIN: f32 = 0.0;
+    pub const WIN_RADIUS_MAX: f32 = 0.0;
+
+    // Direction the pyramid must be turning in at a check for it to win: -1 while its yaw decreases
+    // (rotate_left with the default mapping), 1 while it increases; 0 disables the requirement
+    pub const REQUIRED_APPROACH_SIGN: i32 = 0;
+    // Frames of yaw history the approach direction is detected from, and the net turn (radians)
+    // over them below which the view counts as still
+    pub const APPROACH_WINDOW_FRAMES: usize = 10;
+    pub const APPROACH_MIN_YAW: f32 = 0.01;
+
+    // Seconds after a reset before a check can win, so the stimulus is seen first (0 disables it)
+    pub const MIN_VIEW_SECS: f32 = 0.0;
+
+    // Blank gap between a check and the onset of its feedback (door animation, score bar), in ms
+    pub const FEEDBACK_DELAY_MS: u32 = 0;
+
+    // Minimum nominal frames between accepted check_alignment, reset and blank_screen activations,
+    // absorbing the bounce of response levers (0 accepts every activation)
+    pub const DEBOUNCE_FRAMES: [u32; 3] = [0, 0, 0];
+
+    // Tutorial sequence for new animals: thresholds and decoration counts ramp from the first
+    // to the last trial, with the practice aids (shaping dwell, live feedback) on at the start
+    pub const TUTORIAL_TRIAL_COUNT: usize = 20;
+    pub const TUTORIAL_PRACTICE_TRIALS: usize = 5;
+    pub const TUTORIAL_COSINE_RANGE: [f32; 2] = [0.7, COSINE_ALIGNMENT_TO_WIN];
+    pub const TUTORIAL_DECORATIONS_RANGE: [u32; 2] = [2, 20];
+    pub const TUTORIAL_SHAPING_DWELL_SECS: f32 = 1.0;
+
+    // Generated face color palettes (controller --palette): default size and Rec.709 luma, accepted
+    // lumas (away from black and white, where no hue keeps any chroma) and the tolerance met
+    pub const PALETTE_SIZE: usize = 3;
+    pub const PALETTE_LUMA: f32 = 0.5;
+    pub const PALETTE_LUMA_RANGE: [f32; 2] = [0.1, 0.9];
+    pub const PALETTE_LUMA_TOLERANCE: f32 = 0.005;
+    // Hues (degrees) of the Okabe-Ito orange, sky blue and reddish purple, distinguishable with
+    // deuteranopia: the deuteranopia-safe preset draws them at one luma
+    pub const PALETTE_DEUTERANOPIA_HUES: [f32; 3] = [41.0, 202.0, 326.0];
+
+    // Adaptive staircase on the alignment threshold (controller --staircase): after STAIRCASE_DOWN
+    // consecutive wins the threshold rises by one step (harder), after STAIRCASE_UP losses it drops
+    pub const STAIRCASE_STEP: f32 = 0.01;
+    pub const STAIRCASE_DOWN: u32 = 2;
+    pub const STAIRCASE_UP: u32 = 1;
+    pub const STAIRCASE_COSINE_RANGE: [f32; 2] = [0.7, 0.999];
+
     // Seeds for the random number generator, one per face.
     // If two faces share the same seed (and same count/size), they get identical decorations.
     pub const DECORATION_SEEDS: [u64; 3] = [69, 70, 71];
 
-    // UI responsive design reference
+    // UI responsive design reference: the UI scales by the smaller of the width and height ratios of
+    // the viewport to this resolution, clamped to UI_SCALE_RANGE. A positive UI_SCALE overrides it
+    pub const UI_REFERENCE_WIDTH: f32 = 1920.0;
     pub const UI_REFERENCE_HEIGHT: f32 = 1080.0; // 1080p as reference
+    pub const UI_SCALE_RANGE: [f32; 2] = [0.5, 2.0];
+    pub const UI_SCALE: f32 = 0.0; // 0 = scale with the viewport
 
     // Score bar UI constants (scaled values)
     pub const SCORE_BAR_WIDTH_PERCENT: f32 = 40.0; // 40% of screen width
     pub const SCORE_BAR_HEIGHT: f32 = 20.0; // pixels (scaled by UiScale)
     pub const SCORE_BAR_TOP_OFFSET: f32 = 50.0; // pixels from top (scaled by UiScale)
     pub const SCORE_BAR_BORDER_THICKNESS: f32 = 2.0; // pixels (scaled by UiScale)
+    pub const SCORE_BAR_ANCHOR: u32 = 0; // 0 = top, 1 = bottom, SCORE_BAR_TOP_OFFSET from that edge
+    pub const SCORE_BAR_SHOW_NUMERIC: bool = false; // alignment percentage next to the bar
+    pub const SCORE_BAR_TEXT_GAP: f32 = 8.0; // pixels between the score bar and its percentage
+    pub const RADIUS_MARKER_HEIGHT: f32 = 6.0; // pixels (scaled by UiScale), drawn under the score bar
+    pub const RADIUS_MARKER_GAP: f32 = 6.0; // pixels between the score bar and the radius marker
+
+    // Aiming reticle at screen center (style: 0 = dot, 1 = cross, 2 = circle)
+    pub const RETICLE_ENABLED: bool = false;
+    pub const RETICLE_SIZE: f32 = 24.0; // pixels (scaled by UiScale)
+    pub const RETICLE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.8];
+    pub const RETICLE_STYLE: u32 = 1;
+    pub const RETICLE_THICKNESS: f32 = 2.0; // pixels (scaled by UiScale)
+    // Tint the reticle toward green as the best-facing door (any door) lines up, up to the threshold
+    pub const RETICLE_ALIGNMENT_TINT: bool = false;
+
+    // Always-on "hot/cold" bar tracking the live target alignment (distinct from the score bar)
+    pub const LIVE_FEEDBACK_ENABLED: bool = false;
+    pub const LIVE_FEEDBACK_GAIN: f32 = 1.0; // Scales how fast the bar empties as the camera turns away
+    pub const LIVE_FEEDBACK_SMOOTHING: f32 = 0.0; // Fraction of the previous level kept per fixed step, [0, 1)
+    pub const LIVE_FEEDBACK_BAR_WIDTH_PERCENT: f32 = 30.0; // 30% of screen width
+    pub const LIVE_FEEDBACK_BAR_HEIGHT: f32 = 6.0; // pixels (scaled by UiScale)
+    pub const LIVE_FEEDBACK_BAR_BOTTOM_OFFSET: f32 = 30.0; // pixels from bottom (scaled by UiScale)
+
+    // File the dump_config command appends trial lines to (relative to the game's working directory)
+    pub const CONFIG_DUMP_PATH: &str = "dumped_trials.jsonl";
+    // Directory the dump_geometry command writes its files to (relative to the game's working directory)
+    pub const GEOMETRY_DUMP_DIR: &str = "geometry_dumps";
+
+    // Reference images the controller can show between trials (show_image), by index, relative to the
+    // game's assets directory. Loaded once at startup
+    pub const REFERENCE_IMAGES: [&str; 2] = ["reference/fixation.png", "reference/instructions.png"];
+
+    // Longest stimulus description path (bytes, UTF-8) a trial can replay instead of a procedural pyramid
+    pub const STIMULUS_FILE_MAX_LEN: usize = 256;
+
+    // Unix socket command reader (game_node `socket_commands` feature): env var holding the socket
+    // path, and the longest message accepted before an unterminated buffer is discarded
+    pub const COMMAND_SOCKET_ENV: &str = "MONKEY_GAME_SOCKET";
+    pub const COMMAND_SOCKET_MAX_LINE: usize = 256;
+
+    // HTTP status endpoint (game_node `status_http` feature): env var holding the port, the port used
+    // when it is unset, and the address bound (loopback, set 0.0.0.0 to serve other machines)
+    pub const STATUS_HTTP_PORT_ENV: &str = "MONKEY_GAME_STATUS_PORT";
+    pub const STATUS_HTTP_DEFAULT_PORT: u16 = 8787;
+    pub const STATUS_HTTP_HOST: &str = "127.0.0.1";
+
+    // Reset profiling (game_node `profiling` feature, `--profile`): CSV of per-reset section timings
+    // written in LOG_DIR unless a file is given, and how many of the slowest resets are kept and logged
+    pub const RESET_PROFILE_FILE: &str = "reset_profile.csv";
+    pub const RESET_PROFILE_WORST_KEPT: usize = 5;
+
+    // Latched rotation/zoom stops after this many game frames without a controller heartbeat (0 = never)
+    pub const MOTION_LATCH_TIMEOUT_FRAMES: u32 = 30;
+    // Controller watchdog: frames without a heartbeat before the controller counts as lost, when enabled
+    pub const CONTROLLER_TIMEOUT_FRAMES: u32 = 60;
+
+    // Experimenter override mode (Ctrl+Shift+E in the game window): red border around the screen
+    pub const OVERRIDE_BORDER_THICKNESS: f32 = 6.0; // pixels
+    pub const OVERRIDE_BORDER_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+    // Fixed update rate diagnostic: measured over a rolling window of real time, a relative drift
+    // from the configured fixed rate (or of the render rate from it) beyond the tolerance is logged
+    // once it has lasted the sustain time
+    pub const FIXED_RATE_WINDOW_SECS: f32 = 1.0;
+    pub const FIXED_RATE_DRIFT_TOLERANCE: f32 = 0.02; // 2% of the fixed rate
+    pub const FIXED_RATE_SUSTAIN_SECS: f32 = 2.0;
+
+    // Frame drop detection: a rendered frame longer than these multiples of the display period is
+    // late, or dropped (a vsync interval missed)
+    pub const FRAME_LATE_FACTOR: f32 = 1.5;
+    pub const FRAME_DROP_FACTOR: f32 = 2.0;
+
+    // Log events (log_event!): env var holding the initial category mask (see LogCategory::parse_mask,
+    // all categories when unset), and the session log files written next to the console on native.
+    // A session file is rotated once it reaches the size limit, keeping the newest rotated files
+    pub const LOG_CATEGORIES_ENV: &str = "MONKEY_GAME_LOG";
+    pub const LOG_DIR: &str = "logs"; // relative to the game's working directory, unless --log-dir is given
+    pub const LOG_FILE_MAX_BYTES: u64 = 8 * 1024 * 1024;
+    pub const LOG_FILE_KEEP: usize = 3;
+
+    // Decoration color probe: how long a probed face's decorations keep the probe color by default
+    pub const PROBE_DURATION_SECS: f32 = 0.2;
+
+    // Browser session persistence: the progress record saved to localStorage every interval
+    // (0 = never), and the age past which restore_session ignores it
+    pub const SESSION_STORAGE_KEY: &str = "monkey_game_session";
+    pub const SESSION_SAVE_INTERVAL_FRAMES: u32 = 60;
+    pub const SESSION_MAX_AGE_SECS: f32 = 3600.0;
 
     // Loading screen duration in seconds (time for scene to render/stabilize)
     pub const LOADING_DURATION_SECS: f32 = 0.3;
@@ -37,6 +189,38 @@ pub mod camera_3d_constants {
     pub const CAMERA_3D_SPEED_ROTATE: f32 = 0.05;
     pub const CAMERA_3D_SPEED_ZOOM: f32 = 0.10;
 
+    // Inverted control mappings (sensorimotor adaptation blocks)
+    pub const CONTROL_MIRROR_ROTATION: bool = false;
+    pub const CONTROL_MIRROR_ZOOM: bool = false;
+
+    // Mirror-image presentation: the scene is shown flipped left to right (hemifield experiments)
+    pub const MIRROR_X: bool = false;
+
+    // Whether a reset returns the camera and the pyramid yaw to the trial's start. Off, both carry
+    // over from the previous trial (continuity experiments)
+    pub const RECENTER_ON_RESET: bool = true;
+
+    // Browser gamepad (standard mapping: left stick, A button). Stick deflection past the dead zone
+    // moves the camera at the gain times the button speed, the button checks alignment when pressed
+    pub const GAMEPAD_ROTATE_AXIS: u32 = 0;
+    pub const GAMEPAD_ZOOM_AXIS: u32 = 1; // up (negative) zooms in
+    pub const GAMEPAD_CHECK_BUTTON: u32 = 0;
+    pub const GAMEPAD_DEAD_ZONE: f32 = 0.15;
+    pub const GAMEPAD_ROTATE_GAIN: f32 = 1.0;
+    pub const GAMEPAD_ZOOM_GAIN: f32 = 1.0;
+
+    // Perspective projection per trial (0 = Bevy default: 45° FOV, 0.1 near, 1000 far)
+    pub const CAMERA_3D_FOV_DEGREES: f32 = 0.0; // vertical FOV
+    pub const CAMERA_3D_NEAR: f32 = 0.0;
+    pub const CAMERA_3D_FAR: f32 = 0.0;
+    pub const CAMERA_3D_FOV_RANGE_DEGREES: [f32; 2] = [20.0, 120.0]; // min, max
+
+    // Projection mode (0 = perspective, 1 = orthographic). In orthographic mode zoom changes the
+    // projection scale within the range instead of the orbit radius
+    pub const CAMERA_3D_PROJECTION_MODE: u32 = 0;
+    pub const CAMERA_3D_ORTHO_SCALE_RANGE: [f32; 2] = [0.5, 2.0]; // min, max
+    pub const CAMERA_3D_ORTHO_VIEWPORT_HEIGHT: f32 = 12.0; // world units visible vertically at scale 1
+
     // Radius range for the camera's orbit.
     pub const CAMERA_3D_MIN_RADIUS: f32 = 12.0;
     pub const CAMERA_3D_MAX_RADIUS: f32 = 20.0;
@@ -46,6 +230,18 @@ pub mod camera_3d_constants {
 pub mod object_constants {
     // Y position from the ground plane.
     pub const GROUND_Y: f32 = 0.0;
+
+    // Distractors scattered on the ground (shape: 0 = cube, 1 = sphere, 2 = small pyramid, 3 = mixed)
+    pub const DISTRACTOR_COUNT: u32 = 0;
+    pub const DISTRACTOR_SHAPE: u32 = 3;
+    pub const DISTRACTOR_SIZE_RANGE: [f32; 2] = [0.3, 0.6]; // min, max
+    pub const DISTRACTOR_COLOR: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+    // Placement ring around the pyramid, clamped between the base footprint and the camera orbit
+    pub const DISTRACTOR_RING: [f32; 2] = [6.0, 8.5]; // inner, outer radius
+    pub const DISTRACTOR_SEED: u64 = 1234;
+
+    // Curved backdrop wall behind the pyramid (off for an empty background)
+    pub const BACKDROP_ENABLED: bool = true;
 }
 
 /// Pyramid object
@@ -59,6 +255,18 @@ pub mod pyramid_constants {
     pub const PYRAMID_ANGLE_OFFSET_RAD_MIN: f32 = 0.0 * (std::f32::consts::PI / 180.0);
     pub const PYRAMID_ANGLE_OFFSET_RAD_MAX: f32 = 360.0 * (std::f32::consts::PI / 180.0);
 
+    // Trial config mode, as `ConfigMode`: 0 builds the pyramid from base_radius, height and
+    // start_orient as given, 1 draws them at reset within the ranges below from GEOMETRY_SEED
+    pub const CONFIG_MODE: u32 = 0;
+    pub const GEOMETRY_SEED: u64 = 0;
+    pub const PYRAMID_BASE_RADIUS_RANGE: [f32; 2] = [PYRAMID_BASE_RADIUS; 2];
+    pub const PYRAMID_HEIGHT_RANGE: [f32; 2] = [PYRAMID_HEIGHT; 2];
+    pub const PYRAMID_START_ORIENT_RANGE: [f32; 2] = [PYRAMID_ANGLE_OFFSET_RAD_MIN, PYRAMID_ANGLE_OFFSET_RAD_MAX];
+    // Bounds of those ranges (the orientation's are the PYRAMID_ANGLE_OFFSET_RAD ones), keeping the
+    // pyramid on its wooden base and in view
+    pub const PYRAMID_BASE_RADIUS_LIMITS: [f32; 2] = [1.0, 4.0];
+    pub const PYRAMID_HEIGHT_LIMITS: [f32; 2] = [1.0, 8.0];
+
     // Angle increment of each side of the pyramid's base in radians
     pub const PYRAMID_ANGLE_INCREMENT_RAD: f32 = 120.0 * (std::f32::consts::PI / 180.0);
 
@@ -68,22 +276,62 @@ pub mod pyramid_constants {
     [0.0, 0.0, 1.0, 1.0], // blue
     ];
 
+    // ColorSpace code of the config colors (faces, reticle, distractors): 0 = sRGB, 1 = linear
+    pub const COLOR_SPACE: u32 = 0;
+
     // Number of decorations on each pyramid side
     pub const PYRAMID_DECORATIONS_COUNT: [u32; 3] = [
         50,
         20,
         10,
     ];
+    // Optional per-face [min, max] decoration count, drawn from the face's decoration seed at reset.
+    // A max of 0 keeps the fixed PYRAMID_DECORATIONS_COUNT for that face
+    pub const PYRAMID_DECORATIONS_COUNT_RANGE: [[u32; 2]; 3] = [[0, 0]; 3];
+    // RGBA of each face's decorations, in COLOR_SPACE. An alpha of 0 draws the color from the face's
+    // decoration seed instead
+    pub const PYRAMID_DECORATION_COLORS: [[f32; 4]; 3] = [[0.0; 4]; 3];
+    // Largest offset of each RGB channel of a decoration from its face's decoration color, drawn per
+    // decoration from the face seed (0.0 = every decoration of a face has its color)
+    pub const PYRAMID_DECORATION_COLOR_JITTER: f32 = 0.0;
     // Size of decorations per face
     pub const PYRAMID_DECORATIONS_SIZE: [f32; 3] = [
         0.1,
         0.2,
         0.3,
     ];
+    // Scales the decoration spacing rules (edge margin and distance between centers) of all faces:
+    // below 1 packs tighter, above 1 looser. Looser packing may place fewer decorations than the count
+    pub const PYRAMID_DECORATIONS_SPACING: f32 = 1.0;
+    // Spin of every decoration around its face normal, in radians per second (0.0 = static)
+    pub const PYRAMID_DECORATION_SPIN_RATE: f32 = 0.0;
+    // Decoration motion, as `DecorationMotion` (0 none, 1 spin at the spin rate, 2 pulse), and the pulse's
+    // scale amplitude (fraction of the size, below 1) and frequency in Hz
+    pub const PYRAMID_DECORATION_MOTION: u32 = 1;
+    pub const PYRAMID_DECORATION_PULSE_AMPLITUDE: f32 = 0.2;
+    pub const PYRAMID_DECORATION_PULSE_HZ: f32 = 0.5;
+    // Decorations laid out mirror-symmetric about each face's vertical axis
+    pub const PYRAMID_DECORATION_SYMMETRY: bool = false;
+    // Border drawn inside every face along its edges, in world units (0.0 = no outline), and its RGBA color
+    pub const PYRAMID_OUTLINE_WIDTH: f32 = 0.0;
+    pub const PYRAMID_OUTLINE_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    // Lift of the outline off its face along the normal, against z-fighting
+    pub const PYRAMID_OUTLINE_OFFSET: f32 = 0.002;
+
+    // Luminance flicker tagging per face (0.0 Hz = no flicker), depth as a fraction of the face color
+    pub const PYRAMID_FLICKER_HZ: [f32; 3] = [0.0, 0.0, 0.0];
+    pub const PYRAMID_FLICKER_DEPTH: [f32; 3] = [0.0, 0.0, 0.0];
 
     // Index of the target door of the pyramid
     pub const PYRAMID_TARGET_DOOR_INDEX: usize = 0;
 
+    // PyramidType code (0 = Type1, 1 = Type2, 2 = Type3)
+    pub const PYRAMID_TYPE: u32 = 0;
+
+    // What the alignment check targets (0 = door, 1 = face), and the target face in face mode
+    pub const TARGET_KIND: u32 = 0;
+    pub const PYRAMID_TARGET_FACE_INDEX: u32 = 0;
+
     // Decorations
     pub const DECORATION_COUNT: u32 = 50;
     // Wooden base
@@ -94,11 +342,20 @@ pub mod pyramid_constants {
     pub const BASE_HOLES_LIGHT_Y_OFFSET: f32 = 0.0; // Y offset of the light holes from the Y of the holes itself
     pub const BASE_HOLES_LIGHT_OFFSET_CENTER: f32 = -0.4; // Offset of the light holes from the normal of center of the hole
 
+    // Door geometry
+    pub const DOOR_HOLE_RADIUS_FRACTION: f32 = 0.4; // Hole diameter as a fraction of the panel's smaller side
+    pub const DOOR_FRAME_THICKNESS: f32 = 0.0; // Depth of the hole tunnel into the base (0 = flat panel)
+    pub const DOOR_INSET: f32 = -0.01; // Door glow distance behind the panel surface (negative = in front)
+    // Reward weight of a win at each door, reported as `reward_weight` for the controller to scale the reward
+    pub const DOOR_REWARD_WEIGHTS: [f32; 8] = [1.0; 8];
+
 
     // Door animation timing
     pub const DOOR_ANIM_FADE_OUT: f32 = 0.5; // seconds
     pub const DOOR_ANIM_STAY_OPEN: f32 = 0.5; // seconds
     pub const DOOR_ANIM_FADE_IN: f32 = 0.5; // seconds
+    // Curve of the glow within the fade phases, as `AnimationEasing` (0 linear, 1 ease-in-out, 2 exponential)
+    pub const DOOR_ANIM_EASING: u32 = 0;
 }
 
 /// Lighting constants
@@ -112,23 +369,75 @@ pub mod lighting_constants {
     pub const SPOTLIGHT_LIGHT_INTENSITY: f32 = 5_000_000.0;
     pub const GLOBAL_AMBIENT_LIGHT_INTENSITY: f32 = 200.0;
     pub const MAX_SPOTLIGHT_INTENSITY: f32 = 1000000.0;
+
+    // Default overhead spotlight position, aimed at the origin
+    pub const SPOTLIGHT_POSITION: [f32; 3] = [0.0, 15.0, 0.0];
+
+    // Main spotlight cone: outer angle in radians (at most PI/2) and range in world units
+    pub const SPOTLIGHT_OUTER_ANGLE: f32 = std::f32::consts::PI / 3.0;
+    pub const SPOTLIGHT_RANGE: f32 = 45.0;
+
+    // Winning door glow (hole spotlight and emissive) RGBA, all 0 = the spotlight's own color
+    pub const WIN_GLOW_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.0];
+
+    // Linear distance fog on the scene camera: RGBA and the start/end distances in world units
+    pub const FOG_ENABLED: bool = false;
+    pub const FOG_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+    pub const FOG_START: f32 = 10.0;
+    pub const FOG_END: f32 = 30.0;
 }
 
 
 /// Shared timing constants for stimulus experiments.
 pub mod timing {
     use super::game_constants::REFRESH_RATE_HZ;
+    use core::time::Duration;
+
+    /// Inter-trial interval: frames the screen stays blank after a won or failed trial. Default for `iti_frames`
+    pub const ITI_FRAMES: u32 = 60;
+
+    /// What follows the ITI without a reset or queued trial, as `PostWinMode` (0 auto-return, 1 hold until reset)
+    pub const POST_WIN_MODE: u32 = 0;
 
-    /// Duration to show black screen after win (in frames)
-    pub const WIN_BLANK_DURATION_FRAMES: u64 = 60;
+    /// What pausing (stop_rendering) hides, as `PauseMode` (0 the camera, 1 the game entities, 2 nothing)
+    pub const PAUSE_MODE: u32 = 0;
     
     /// Convert frames to approximate seconds 
     pub const fn frames_to_seconds(frames: u64) -> f32 {
         frames as f32 / REFRESH_RATE_HZ as f32
     }
     
-    /// Convert seconds to frames
+    /// Convert seconds to frames, truncating (0.016 s is 0 frames, see seconds_to_frames_rounded)
     pub const fn seconds_to_frames(seconds: f32) -> u64 {
         (seconds * REFRESH_RATE_HZ as f32) as u64
     }
+
+    /// Convert seconds to the nearest number of frames (negative seconds are 0 frames)
+    pub const fn seconds_to_frames_rounded(seconds: f32) -> u64 {
+        (seconds * REFRESH_RATE_HZ as f32 + 0.5) as u64
+    }
+
+    /// Nominal duration of a number of frames
+    pub const fn frames_to_duration(frames: u64) -> Duration {
+        Duration::from_nanos((frames as f64 * 1e9 / REFRESH_RATE_HZ + 0.5) as u64)
+    }
+
+    /// Nearest number of frames in a duration
+    pub const fn duration_to_frames(duration: Duration) -> u64 {
+        (duration.as_secs_f64() * REFRESH_RATE_HZ + 0.5) as u64
+    }
+
+    /// Frames at `hz` lasting as long as `frames` nominal frames, rounded. Frame counts in the
+    /// config are nominal, the game converts them to the rate its frames actually run at
+    pub const fn frames_at_rate(frames: u32, hz: f64) -> u32 {
+        (frames as f64 * hz / REFRESH_RATE_HZ + 0.5) as u32
+    }
+
+    /// Nominal frame period
+    pub const FRAME_PERIOD: Duration = frames_to_duration(1);
+
+    /// FrameClock: frames the period is fitted over, and how many periods late a frame may be seen
+    /// before the clock restarts (the game was paused or stalled)
+    pub const FRAME_CLOCK_WINDOW: usize = 120;
+    pub const FRAME_CLOCK_RESYNC_FRAMES: u32 = 10;
 }
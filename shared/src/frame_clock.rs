@@ -0,0 +1,236 @@
+//! Wall-clock estimate of the game's frames, so a controller can act on frame boundaries.
+//!
+//! A controller pairs each new `frame_number` it reads with the time it first saw it. A least
+//! squares line through the recent pairs gives the game's actual frame period (which drifts from
+//! the nominal REFRESH_RATE_HZ with the display clock) and the time any frame is due, averaging out
+//! the jitter of when the controller happened to poll.
+//!
+//! The frame number restarts at 0 on every reset and holds while the game is paused, so a frame
+//! number going back or a frame seen far later than predicted restarts the estimate.
+use crate::constants::timing::{FRAME_CLOCK_RESYNC_FRAMES, FRAME_CLOCK_WINDOW, FRAME_PERIOD};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct FrameClock {
+    /// (frame number, time first seen), oldest first
+    samples: VecDeque<(u64, Instant)>,
+    window: usize,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        Self::with_window(FRAME_CLOCK_WINDOW)
+    }
+
+    /// Clock fitted over the last `window` observed frames (at least 2)
+    pub fn with_window(window: usize) -> Self {
+        Self { samples: VecDeque::new(), window: window.max(2) }
+    }
+
+    /// Records that `frame` was read at `at`. Repeated reads of a frame are ignored, the first is
+    /// the closest to when it was emitted.
+    pub fn observe(&mut self, frame: u64, at: Instant) {
+        if let Some(&(last, _)) = self.samples.back() {
+            if frame == last {
+                return;
+            }
+            let stalled = self
+                .frame_time(frame)
+                .is_some_and(|due| at.saturating_duration_since(due) > self.period() * FRAME_CLOCK_RESYNC_FRAMES);
+            if frame < last || stalled {
+                self.samples.clear();
+            }
+        }
+        self.samples.push_back((frame, at));
+        while self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Newest frame observed since the estimate (re)started
+    pub fn last_frame(&self) -> Option<u64> {
+        self.samples.back().map(|&(frame, _)| frame)
+    }
+
+    /// Seconds from the oldest sample to its fitted frame time, and the fitted period in seconds
+    fn fit(&self) -> Option<(f64, f64)> {
+        let &(first_frame, first_time) = self.samples.front()?;
+        let points = || {
+            self.samples
+                .iter()
+                .map(move |&(frame, at)| ((frame - first_frame) as f64, at.saturating_duration_since(first_time).as_secs_f64()))
+        };
+        let n = self.samples.len() as f64;
+        let (mean_frame, mean_time) = points().fold((0.0, 0.0), |(f, t), (frame, time)| (f + frame / n, t + time / n));
+        let (covariance, variance) = points().fold((0.0, 0.0), |(c, v), (frame, time)| {
+            (c + (frame - mean_frame) * (time - mean_time), v + (frame - mean_frame) * (frame - mean_frame))
+        });
+
+        let period = if variance > 0.0 && covariance > 0.0 { covariance / variance } else { FRAME_PERIOD.as_secs_f64() };
+        Some((mean_time - period * mean_frame, period))
+    }
+
+    /// Estimated frame period, the nominal one until two frames have been observed
+    pub fn period(&self) -> Duration {
+        self.fit().map_or(FRAME_PERIOD, |(_, period)| Duration::from_secs_f64(period))
+    }
+
+    /// Predicted time `frame` is (or was) read, None before any observation
+    pub fn frame_time(&self, frame: u64) -> Option<Instant> {
+        let &(first_frame, first_time) = self.samples.front()?;
+        let (offset, period) = self.fit()?;
+        let secs = offset + period * (frame as f64 - first_frame as f64);
+        if secs >= 0.0 {
+            first_time.checked_add(Duration::from_secs_f64(secs))
+        } else {
+            first_time.checked_sub(Duration::from_secs_f64(-secs))
+        }
+    }
+
+    /// First frame predicted after `now` and its time, None before any observation
+    pub fn next_frame(&self, now: Instant) -> Option<(u64, Instant)> {
+        let last = self.last_frame()?;
+        let due = self.frame_time(last)?;
+        let period = self.period().as_secs_f64();
+        let ahead = (now.saturating_duration_since(due).as_secs_f64() / period).floor() as u64 + 1;
+        let frame = last + ahead;
+        self.frame_time(frame).map(|at| (frame, at))
+    }
+
+    /// Blocks until `frame` is due. Returns false at once without an estimate
+    pub fn sleep_until_frame(&self, frame: u64) -> bool {
+        let Some(due) = self.frame_time(frame) else {
+            return false;
+        };
+        let now = Instant::now();
+        if due > now {
+            std::thread::sleep(due - now);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::timing::{duration_to_frames, frames_to_duration, seconds_to_frames, seconds_to_frames_rounded};
+
+    /// A display running slightly slow of the nominal 60 Hz
+    const TRUE_PERIOD: f64 = 1.0 / 59.94;
+    /// Polling delays are spread over [0, 4) ms, so reads lag the frames by 2 ms on average
+    const MEAN_LAG: f64 = 0.002;
+
+    /// Deterministic jitter in [0, 4) ms
+    fn jitter(i: u64) -> Duration {
+        let hash = i.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407) >> 33;
+        Duration::from_secs_f64((hash % 4000) as f64 * 1e-6)
+    }
+
+    fn at(start: Instant, frame: u64) -> Instant {
+        start + Duration::from_secs_f64(frame as f64 * TRUE_PERIOD)
+    }
+
+    /// A controller reading two frames out of three, each late by some jitter
+    fn jittery_clock(start: Instant, frames: u64) -> FrameClock {
+        let mut clock = FrameClock::new();
+        for frame in (0..frames).filter(|f| f % 3 != 2) {
+            clock.observe(frame, at(start, frame) + jitter(frame));
+            // A second read of the same frame is ignored
+            clock.observe(frame, at(start, frame) + jitter(frame) + Duration::from_millis(5));
+        }
+        clock
+    }
+
+    fn secs_between(a: Instant, b: Instant) -> f64 {
+        match a.checked_duration_since(b) {
+            Some(d) => d.as_secs_f64(),
+            None => -b.duration_since(a).as_secs_f64(),
+        }
+    }
+
+    #[test]
+    fn rounding_converters() {
+        assert_eq!(seconds_to_frames(0.016), 0, "truncates");
+        assert_eq!(seconds_to_frames_rounded(0.016), 1);
+        assert_eq!(seconds_to_frames_rounded(0.2), 12);
+        assert_eq!(seconds_to_frames_rounded(-1.0), 0);
+        assert_eq!(frames_to_duration(60), Duration::from_secs(1));
+        assert_eq!(FRAME_PERIOD, frames_to_duration(1));
+        assert_eq!(duration_to_frames(Duration::from_millis(250)), 15);
+        assert_eq!(duration_to_frames(Duration::from_millis(16)), 1);
+        assert_eq!(duration_to_frames(frames_to_duration(1234)), 1234);
+    }
+
+    #[test]
+    fn fits_the_actual_period_through_jitter() {
+        let start = Instant::now();
+        let clock = jittery_clock(start, 240);
+
+        let period = clock.period().as_secs_f64();
+        assert!((period / TRUE_PERIOD - 1.0).abs() < 1e-3, "period {period} vs {TRUE_PERIOD}");
+        assert!((period - FRAME_PERIOD.as_secs_f64()).abs() > 1e-6, "not the nominal period");
+        assert_eq!(clock.last_frame(), Some(238));
+
+        // A frame a second ahead is predicted within a millisecond of its average read time
+        let predicted = clock.frame_time(300).unwrap();
+        let expected = at(start, 300) + Duration::from_secs_f64(MEAN_LAG);
+        assert!(secs_between(predicted, expected).abs() < 1e-3, "off by {}s", secs_between(predicted, expected));
+    }
+
+    #[test]
+    fn next_frame_is_the_first_one_due_after_now() {
+        let start = Instant::now();
+        let clock = jittery_clock(start, 120);
+        let now = at(start, 150) + Duration::from_secs_f64(TRUE_PERIOD / 2.0);
+        let (frame, due) = clock.next_frame(now).unwrap();
+        assert!(due > now);
+        assert!(clock.frame_time(frame - 1).unwrap() <= now);
+        assert!(frame == 151 || frame == 150, "frame {frame}");
+    }
+
+    #[test]
+    fn restarts_on_reset_and_stall() {
+        let start = Instant::now();
+        let mut clock = jittery_clock(start, 120);
+
+        // Reset: the frame number starts over
+        clock.observe(0, at(start, 120));
+        assert_eq!(clock.last_frame(), Some(0));
+        assert_eq!(clock.period(), FRAME_PERIOD, "nominal again with one sample");
+
+        // Pause: frame 2 is seen a second after frame 1, far later than predicted
+        clock.observe(1, at(start, 121));
+        clock.observe(2, at(start, 182));
+        assert_eq!(clock.last_frame(), Some(2));
+        assert_eq!(clock.frame_time(2), Some(at(start, 182)));
+    }
+
+    #[test]
+    fn sleeps_only_with_an_estimate() {
+        let mut clock = FrameClock::new();
+        assert!(!clock.sleep_until_frame(10));
+        assert!(clock.frame_time(10).is_none() && clock.next_frame(Instant::now()).is_none());
+
+        // Already past: returns at once
+        let now = Instant::now();
+        clock.observe(5, now);
+        let before = Instant::now();
+        assert!(clock.sleep_until_frame(4));
+        assert!(before.elapsed() < Duration::from_millis(10));
+
+        // Two frames ahead, about two nominal periods
+        assert!(clock.sleep_until_frame(7));
+        assert!(now.elapsed() >= FRAME_PERIOD * 2 - Duration::from_millis(1));
+    }
+}
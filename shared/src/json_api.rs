@@ -0,0 +1,1082 @@
+//! JSON form of the controller interface, with the schema of the Python bindings (python.rs), for
+//! controllers that cannot load them: the WASM build exports it to JS (web.rs), so a fetch or
+//! WebSocket bridge can drive the game with the messages the Python controller would send.
+//!
+//! `state_json` is the dictionary of `read_game_structure` and `config_json` its config part.
+//! `apply_config_json` takes the keyword arguments of `write_game_structure`, `apply_command_json`
+//! the name of a controller method under `"method"` next to its keyword arguments. Unlike the
+//! Python calls, config keys left out keep their current value and command flags left out are false.
+//! u64 fields (seeds, hashes) are written exactly, past the integer range of a JS number.
+use core::sync::atomic::{AtomicU32, Ordering};
+use serde_json::{json, Map, Value};
+
+use crate::constants::game_constants::{CONTROLLER_TIMEOUT_FRAMES, MOTION_LATCH_TIMEOUT_FRAMES, PROBE_DURATION_SECS, REFERENCE_IMAGES};
+use crate::{
+    validate_camera_projection, validate_decoration_colors, validate_decoration_pulse, validate_door_geometry, validate_door_reward_weights, widest_base_radius,
+    validate_fog, validate_geometry_ranges, validate_pyramid_type, validate_score_bar, validate_stimulus_file, AnimationEasing,
+    ColorSpace, ConfigMode, DecorationMotion, DistractorShape, PauseMode, PostWinMode, ProjectionMode, PyramidType,
+    RenderQuality, ReticleStyle, ScoreBarAnchor, SharedCommands, SharedGameStructure, SharedMemory, TargetKind, HIDE_IMAGE,
+    LOG_CATEGORIES_ALL, SEEN_COMMAND_NAMES, seen_command_names,
+};
+
+fn f32_of(value: &AtomicU32) -> f32 {
+    f32::from_bits(value.load(Ordering::Relaxed))
+}
+
+fn f32s_of(values: &[AtomicU32]) -> Vec<f32> {
+    values.iter().map(f32_of).collect()
+}
+
+fn u32s_of(values: &[AtomicU32]) -> Vec<u32> {
+    values.iter().map(|value| value.load(Ordering::Relaxed)).collect()
+}
+
+/// Trial config fields of a game structure region, keyed like python.rs `config_to_dict`
+pub fn config_json(gs: &SharedGameStructure) -> Map<String, Value> {
+    let colors = f32s_of(&gs.colors);
+    let count_range = u32s_of(&gs.decorations_count_range);
+    let fields = vec![
+        // Fixed vars in trial
+        ("decoration_seeds", json!(gs.decoration_seeds.iter().map(|seed| seed.load(Ordering::Relaxed)).collect::<Vec<u64>>())),
+        ("base_radius", json!(f32_of(&gs.base_radius))),
+        ("height", json!(f32_of(&gs.height))),
+        ("start_orient", json!(f32_of(&gs.start_orient))),
+        ("config_mode", json!(gs.config_mode.load(Ordering::Relaxed))),
+        ("geometry_seed", json!(gs.geometry_seed.load(Ordering::Relaxed))),
+        ("base_radius_range", json!(f32s_of(&gs.base_radius_range))),
+        ("height_range", json!(f32s_of(&gs.height_range))),
+        ("start_orient_range", json!(f32s_of(&gs.start_orient_range))),
+        ("target_door", json!(gs.target_door.load(Ordering::Relaxed))),
+        ("pyramid_type", json!(gs.pyramid_type.load(Ordering::Relaxed))),
+        ("target_kind", json!(gs.target_kind.load(Ordering::Relaxed))),
+        ("target_face", json!(gs.target_face.load(Ordering::Relaxed))),
+        ("colors", json!(colors.chunks(4).collect::<Vec<_>>())),
+        ("color_space", json!(gs.color_space.load(Ordering::Relaxed))),
+        ("main_spotlight_intensity", json!(f32_of(&gs.main_spotlight_intensity))),
+        ("ambient_brightness", json!(f32_of(&gs.ambient_brightness))),
+        ("max_spotlight_intensity", json!(f32_of(&gs.max_spotlight_intensity))),
+        ("spotlight_outer_angle", json!(f32_of(&gs.spotlight_outer_angle))),
+        ("spotlight_range", json!(f32_of(&gs.spotlight_range))),
+        ("win_glow_color", json!(f32s_of(&gs.win_glow_color))),
+        ("fog_enabled", json!(gs.fog_enabled.load(Ordering::Relaxed))),
+        ("fog_color", json!(f32s_of(&gs.fog_color))),
+        ("fog_start", json!(f32_of(&gs.fog_start))),
+        ("fog_end", json!(f32_of(&gs.fog_end))),
+        ("decoration_count", json!(u32s_of(&gs.decorations_count))),
+        ("decorations_count_range", json!(count_range.chunks(2).collect::<Vec<_>>())),
+        ("decoration_size", json!(f32s_of(&gs.decorations_size))),
+        ("decoration_spacing", json!(f32_of(&gs.decoration_spacing))),
+        ("decoration_spin_rate", json!(f32_of(&gs.decoration_spin_rate))),
+        ("decoration_motion", json!(gs.decoration_motion.load(Ordering::Relaxed))),
+        ("decoration_pulse_amplitude", json!(f32_of(&gs.decoration_pulse_amplitude))),
+        ("decoration_pulse_hz", json!(f32_of(&gs.decoration_pulse_hz))),
+        ("decoration_symmetry", json!(gs.decoration_symmetry.load(Ordering::Relaxed))),
+        ("decoration_colors", json!(f32s_of(&gs.decoration_colors).chunks(4).collect::<Vec<_>>())),
+        ("decoration_color_jitter", json!(f32_of(&gs.decoration_color_jitter))),
+        ("outline_width", json!(f32_of(&gs.outline_width))),
+        ("outline_color", json!(f32s_of(&gs.outline_color))),
+        ("door_hole_radius_fraction", json!(f32_of(&gs.door_hole_radius_fraction))),
+        ("door_frame_thickness", json!(f32_of(&gs.door_frame_thickness))),
+        ("door_inset", json!(f32_of(&gs.door_inset))),
+        ("door_reward_weights", json!(f32s_of(&gs.door_reward_weights))),
+        ("flicker_hz", json!(f32s_of(&gs.flicker_hz))),
+        ("reticle_enabled", json!(gs.reticle_enabled.load(Ordering::Relaxed))),
+        ("ui_scale", json!(f32_of(&gs.ui_scale))),
+        ("score_bar_anchor", json!(gs.score_bar_anchor.load(Ordering::Relaxed))),
+        ("score_bar_width_percent", json!(f32_of(&gs.score_bar_width_percent))),
+        ("score_bar_height", json!(f32_of(&gs.score_bar_height))),
+        ("score_bar_show_numeric", json!(gs.score_bar_show_numeric.load(Ordering::Relaxed))),
+        ("reticle_size", json!(f32_of(&gs.reticle_size))),
+        ("reticle_color", json!(f32s_of(&gs.reticle_color))),
+        ("reticle_style", json!(gs.reticle_style.load(Ordering::Relaxed))),
+        ("reticle_alignment_tint", json!(gs.reticle_alignment_tint.load(Ordering::Relaxed))),
+        ("live_feedback_enabled", json!(gs.live_feedback_enabled.load(Ordering::Relaxed))),
+        ("live_feedback_gain", json!(f32_of(&gs.live_feedback_gain))),
+        ("live_feedback_smoothing", json!(f32_of(&gs.live_feedback_smoothing))),
+        ("control_mirror_rotation", json!(gs.control_mirror_rotation.load(Ordering::Relaxed))),
+        ("control_mirror_zoom", json!(gs.control_mirror_zoom.load(Ordering::Relaxed))),
+        ("mirror_x", json!(gs.mirror_x.load(Ordering::Relaxed))),
+        ("recenter_on_reset", json!(gs.recenter_on_reset.load(Ordering::Relaxed))),
+        ("camera_fov_degrees", json!(f32_of(&gs.camera_fov_degrees))),
+        ("camera_near", json!(f32_of(&gs.camera_near))),
+        ("camera_far", json!(f32_of(&gs.camera_far))),
+        ("projection_mode", json!(gs.projection_mode.load(Ordering::Relaxed))),
+        ("ortho_scale_range", json!(f32s_of(&gs.ortho_scale_range))),
+        ("distractor_count", json!(gs.distractor_count.load(Ordering::Relaxed))),
+        ("distractor_shape", json!(gs.distractor_shape.load(Ordering::Relaxed))),
+        ("distractor_size_range", json!(f32s_of(&gs.distractor_size_range))),
+        ("distractor_color", json!(f32s_of(&gs.distractor_color))),
+        ("distractor_ring", json!(f32s_of(&gs.distractor_ring))),
+        ("distractor_seed", json!(gs.distractor_seed.load(Ordering::Relaxed))),
+        ("backdrop_enabled", json!(gs.backdrop_enabled.load(Ordering::Relaxed))),
+        ("stimulus_file", json!(gs.stimulus_file())),
+        ("stimulus_expected_hash", json!(gs.stimulus_expected_hash.load(Ordering::Relaxed))),
+        ("trial_id_hash", json!(gs.trial_id_hash.load(Ordering::Relaxed))),
+        ("flicker_depth", json!(f32s_of(&gs.flicker_depth))),
+        // Dynamic vars in trial
+        ("cosine_alignment_threshold", json!(f32_of(&gs.cosine_alignment_threshold))),
+        ("shaping_dwell_secs", json!(f32_of(&gs.shaping_dwell_secs))),
+        ("suppress_blanked_attempts", json!(gs.suppress_blanked_attempts.load(Ordering::Relaxed))),
+        ("is_catch", json!(gs.is_catch.load(Ordering::Relaxed))),
+        ("max_attempts", json!(gs.max_attempts.load(Ordering::Relaxed))),
+        ("trial_timeout_secs", json!(f32_of(&gs.trial_timeout_secs))),
+        ("win_radius_min", json!(f32_of(&gs.win_radius_min))),
+        ("win_radius_max", json!(f32_of(&gs.win_radius_max))),
+        ("required_approach_sign", json!(gs.required_approach_sign.load(Ordering::Relaxed))),
+        ("min_view_secs", json!(f32_of(&gs.min_view_secs))),
+        ("feedback_delay_ms", json!(gs.feedback_delay_ms.load(Ordering::Relaxed))),
+        ("debounce_frames", json!(u32s_of(&gs.debounce_frames))),
+        ("iti_frames", json!(gs.iti_frames.load(Ordering::Relaxed))),
+        ("post_win_mode", json!(gs.post_win_mode.load(Ordering::Relaxed))),
+        ("pause_mode", json!(gs.pause_mode.load(Ordering::Relaxed))),
+        ("door_anim_fade_out", json!(f32_of(&gs.door_anim_fade_out))),
+        ("door_anim_stay_open", json!(f32_of(&gs.door_anim_stay_open))),
+        ("door_anim_fade_in", json!(f32_of(&gs.door_anim_fade_in))),
+        ("door_anim_easing", json!(gs.door_anim_easing.load(Ordering::Relaxed))),
+    ];
+    fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect()
+}
+
+/// State of the game region, keyed like python.rs `game_structure_to_dict`: the applied config and
+/// the state at the top level, the config alone under `applied` and the controller region's config
+/// under `requested`
+pub fn state_json(shm: &SharedMemory) -> Value {
+    let gs = &shm.game_structure_game;
+    let decorations_placed = json!(u32s_of(&gs.decorations_placed));
+    let mut applied = config_json(gs);
+    applied.insert("decorations_placed".to_string(), decorations_placed.clone());
+
+    let mut state = config_json(gs);
+    // None while no reference image is shown
+    let reference_image = gs.reference_image.load(Ordering::Relaxed);
+    let fields = vec![
+        ("applied", Value::Object(applied)),
+        ("requested", Value::Object(config_json(&shm.game_structure_control))),
+        ("decorations_placed", decorations_placed),
+        ("frame_number", json!(gs.frame_number.load(Ordering::Relaxed))),
+        ("elapsed_secs", json!(f32_of(&gs.elapsed_secs))),
+        // Orbit radius in perspective mode, orthographic scale otherwise
+        ("camera_radius", json!(f32_of(&gs.camera_radius))),
+        ("camera_radius_is_scale", json!(gs.camera_radius_is_scale.load(Ordering::Relaxed))),
+        ("camera_position", json!([f32_of(&gs.camera_x), f32_of(&gs.camera_y), f32_of(&gs.camera_z)])),
+        ("pyramid_yaw", json!(f32_of(&gs.pyramid_yaw))),
+        ("global_yaw_offset", json!(f32_of(&gs.global_yaw_offset))),
+        ("nr_attempts", json!(gs.attempts.load(Ordering::Relaxed))),
+        ("rejected_checks", json!(gs.rejected_checks.load(Ordering::Relaxed))),
+        ("cosine_alignment", json!(f32_of(&gs.current_alignment))),
+        ("current_angle", json!(f32_of(&gs.current_angle))),
+        ("best_door", json!(gs.best_door.load(Ordering::Relaxed))),
+        ("num_faces", json!(gs.num_faces.load(Ordering::Relaxed))),
+        ("num_doors", json!(gs.num_doors.load(Ordering::Relaxed))),
+        ("controller_lost", json!(gs.controller_lost.load(Ordering::Relaxed))),
+        ("controller_outages", json!(gs.controller_outages.load(Ordering::Relaxed))),
+        ("game_heartbeat", json!(gs.game_heartbeat.load(Ordering::Relaxed))),
+        ("reference_image", json!((reference_image != HIDE_IMAGE).then_some(reference_image))),
+        ("live_feedback_visible", json!(gs.live_feedback_visible.load(Ordering::Relaxed))),
+        ("is_animating", json!(gs.is_animating.load(Ordering::Relaxed))),
+        ("win_elapsed_secs", json!(f32_of(&gs.win_time))),
+        ("reward_weight", json!(f32_of(&gs.reward_weight))),
+        ("radius_at_check", json!(f32_of(&gs.radius_at_check))),
+        ("win_blocked_by_radius", json!(gs.win_blocked_by_radius.load(Ordering::Relaxed))),
+        ("approach_sign", json!(gs.approach_sign.load(Ordering::Relaxed))),
+        ("win_blocked_by_approach", json!(gs.win_blocked_by_approach.load(Ordering::Relaxed))),
+        ("check_too_early", json!(gs.check_too_early.load(Ordering::Relaxed))),
+        ("feedback_pending", json!(gs.feedback_pending.load(Ordering::Relaxed))),
+        ("inputs_locked", json!(gs.inputs_locked.load(Ordering::Relaxed))),
+        ("phase", json!(gs.phase.load(Ordering::Relaxed))),
+        ("iti_remaining_frames", json!(gs.iti_remaining_frames.load(Ordering::Relaxed))),
+        ("active_fov_degrees", json!(f32_of(&gs.active_fov_degrees))),
+        ("active_near", json!(f32_of(&gs.active_near))),
+        ("active_far", json!(f32_of(&gs.active_far))),
+        ("window_resolution", json!([gs.window_width.load(Ordering::Relaxed), gs.window_height.load(Ordering::Relaxed)])),
+        ("vsync", json!(gs.vsync.load(Ordering::Relaxed))),
+        ("flicker_phase", json!(f32s_of(&gs.flicker_phase))),
+        ("shaping_dwell_elapsed", json!(f32_of(&gs.shaping_dwell_elapsed))),
+        ("distractor_hash", json!(gs.distractor_hash.load(Ordering::Relaxed))),
+        ("stimulus_hash", json!(gs.stimulus_hash.load(Ordering::Relaxed))),
+        ("stimulus_file_status", json!(gs.stimulus_file_status.load(Ordering::Relaxed))),
+        ("reset_ack", json!(gs.reset_ack.load(Ordering::Relaxed))),
+        ("trial_generation", json!(gs.trial_generation.load(Ordering::Relaxed))),
+        ("clear_attempts_ack", json!(gs.clear_attempts_ack.load(Ordering::Relaxed))),
+        ("latched_rotation", json!(gs.latched_rotation.load(Ordering::Relaxed))),
+        ("latched_zoom", json!(gs.latched_zoom.load(Ordering::Relaxed))),
+        ("override_activations", json!(gs.override_activations.load(Ordering::Relaxed))),
+        ("override_actions", json!(gs.override_actions.load(Ordering::Relaxed))),
+        ("measured_hz", json!(f32_of(&gs.measured_hz))),
+        ("display_hz", json!(f32_of(&gs.display_hz))),
+        ("fixed_hz", json!(f32_of(&gs.fixed_hz))),
+        ("late_frames_trial", json!(gs.late_frames_trial.load(Ordering::Relaxed))),
+        ("dropped_frames_trial", json!(gs.dropped_frames_trial.load(Ordering::Relaxed))),
+        ("worst_frame_ms", json!(f32_of(&gs.worst_frame_ms))),
+        ("render_quality", json!(gs.render_quality.load(Ordering::Relaxed))),
+        ("tab_hidden", json!(gs.tab_hidden.load(Ordering::Relaxed))),
+        ("tab_hidden_secs_trial", json!(f32_of(&gs.tab_hidden_secs_trial))),
+        ("gamepad_connected", json!(gs.gamepad_connected.load(Ordering::Relaxed))),
+        ("dropped_checks", json!(gs.dropped_checks.load(Ordering::Relaxed))),
+        ("debounced_count", json!(gs.debounced_count.load(Ordering::Relaxed))),
+        ("standby_checks", json!(gs.standby_checks.load(Ordering::Relaxed))),
+        ("commands_seen", json!(seen_command_names(gs.commands_seen.load(Ordering::Relaxed)))),
+        (
+            "commands_seen_counts",
+            Value::Object(
+                SEEN_COMMAND_NAMES
+                    .iter()
+                    .zip(&gs.commands_seen_counts)
+                    .map(|(name, count)| (name.to_string(), json!(count.load(Ordering::Relaxed))))
+                    .collect(),
+            ),
+        ),
+        ("decoration_motion_secs", json!(f32_of(&gs.decoration_motion_secs))),
+        ("current_trial_index", json!(gs.current_trial_index.load(Ordering::Relaxed))),
+        ("total_trials", json!(gs.total_trials.load(Ordering::Relaxed))),
+        ("block_index", json!(gs.block_index.load(Ordering::Relaxed))),
+        ("session_id", json!(gs.session_id.load(Ordering::Relaxed))),
+        ("subject_id", json!(gs.subject_id.load(Ordering::Relaxed))),
+    ];
+    state.extend(fields.into_iter().map(|(key, value)| (key.to_string(), value)));
+    Value::Object(state)
+}
+
+fn parse_object(json: &str) -> Result<Map<String, Value>, String> {
+    match serde_json::from_str(json) {
+        Ok(Value::Object(object)) => Ok(object),
+        Ok(other) => Err(format!("expected a JSON object, got {other}")),
+        Err(e) => Err(format!("invalid JSON: {e}")),
+    }
+}
+
+fn bool_of(key: &str, value: &Value) -> Result<bool, String> {
+    value.as_bool().ok_or_else(|| format!("{key} must be a boolean, got {value}"))
+}
+
+fn u32_of(key: &str, value: &Value) -> Result<u32, String> {
+    value
+        .as_u64()
+        .and_then(|value| u32::try_from(value).ok())
+        .ok_or_else(|| format!("{key} must be an integer in [0, {}], got {value}", u32::MAX))
+}
+
+/// Direction: -1, 0 or 1
+fn sign_of(key: &str, value: &Value) -> Result<i32, String> {
+    match value.as_i64() {
+        Some(sign @ -1..=1) => Ok(sign as i32),
+        _ => Err(format!("{key} must be -1, 0 or 1, got {value}")),
+    }
+}
+
+fn u64_of(key: &str, value: &Value) -> Result<u64, String> {
+    value.as_u64().ok_or_else(|| format!("{key} must be a non-negative integer, got {value}"))
+}
+
+fn f32_bits(key: &str, value: &Value) -> Result<u32, String> {
+    value.as_f64().map(|value| (value as f32).to_bits()).ok_or_else(|| format!("{key} must be a number, got {value}"))
+}
+
+/// String, or None for null
+fn optional_str<'a>(key: &str, value: &'a Value) -> Result<Option<&'a str>, String> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(text) => Ok(Some(text)),
+        _ => Err(format!("{key} must be a string or null, got {value}")),
+    }
+}
+
+/// Elements of a JSON array of `len` elements
+fn elements<'a>(key: &str, value: &'a Value, len: usize) -> Result<Vec<&'a Value>, String> {
+    match value.as_array() {
+        Some(items) if items.len() == len => Ok(items.iter().collect()),
+        _ => Err(format!("{key} must be a list of {len} elements, got {value}")),
+    }
+}
+
+/// Elements of a `rows` x `cols` JSON matrix, row after row
+fn matrix<'a>(key: &str, value: &'a Value, rows: usize, cols: usize) -> Result<Vec<&'a Value>, String> {
+    let mut flat = Vec::with_capacity(rows * cols);
+    for row in elements(key, value, rows)? {
+        flat.extend(elements(key, row, cols).map_err(|_| format!("{key} must be a {rows}x{cols} matrix, got {value}"))?);
+    }
+    Ok(flat)
+}
+
+fn store_all(
+    key: &str,
+    slots: &[AtomicU32],
+    values: Vec<&Value>,
+    convert: fn(&str, &Value) -> Result<u32, String>,
+) -> Result<(), String> {
+    for (slot, value) in slots.iter().zip(values) {
+        slot.store(convert(key, value)?, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Stores one `write_game_structure` argument
+fn store_config_field(gs: &SharedGameStructure, key: &str, value: &Value) -> Result<(), String> {
+    let relaxed = Ordering::Relaxed;
+    match key {
+        "decoration_seeds" => {
+            for (slot, seed) in gs.decoration_seeds.iter().zip(elements(key, value, 3)?) {
+                slot.store(u64_of(key, seed)?, relaxed);
+            }
+        }
+        "base_radius" => gs.base_radius.store(f32_bits(key, value)?, relaxed),
+        "height" => gs.height.store(f32_bits(key, value)?, relaxed),
+        "start_orient" => gs.start_orient.store(f32_bits(key, value)?, relaxed),
+        "config_mode" => gs.config_mode.store(u32_of(key, value)?, relaxed),
+        "geometry_seed" => gs.geometry_seed.store(u64_of(key, value)?, relaxed),
+        "base_radius_range" => store_all(key, &gs.base_radius_range, elements(key, value, 2)?, f32_bits)?,
+        "height_range" => store_all(key, &gs.height_range, elements(key, value, 2)?, f32_bits)?,
+        "start_orient_range" => store_all(key, &gs.start_orient_range, elements(key, value, 2)?, f32_bits)?,
+        "target_door" => gs.target_door.store(u32_of(key, value)?, relaxed),
+        "colors" => store_all(key, &gs.colors, matrix(key, value, 3, 4)?, f32_bits)?,
+        "decorations_count" => store_all(key, &gs.decorations_count, elements(key, value, 3)?, u32_of)?,
+        "decorations_size" => store_all(key, &gs.decorations_size, elements(key, value, 3)?, f32_bits)?,
+        "cosine_alignment_threshold" => gs.cosine_alignment_threshold.store(f32_bits(key, value)?, relaxed),
+        "door_anim_fade_out" => gs.door_anim_fade_out.store(f32_bits(key, value)?, relaxed),
+        "door_anim_stay_open" => gs.door_anim_stay_open.store(f32_bits(key, value)?, relaxed),
+        "door_anim_fade_in" => gs.door_anim_fade_in.store(f32_bits(key, value)?, relaxed),
+        "door_anim_easing" => gs.door_anim_easing.store(u32_of(key, value)?, relaxed),
+        "main_spotlight_intensity" => gs.main_spotlight_intensity.store(f32_bits(key, value)?, relaxed),
+        "ambient_brightness" => gs.ambient_brightness.store(f32_bits(key, value)?, relaxed),
+        "max_spotlight_intensity" => gs.max_spotlight_intensity.store(f32_bits(key, value)?, relaxed),
+        "flicker_hz" => store_all(key, &gs.flicker_hz, elements(key, value, 3)?, f32_bits)?,
+        "flicker_depth" => store_all(key, &gs.flicker_depth, elements(key, value, 3)?, f32_bits)?,
+        "decorations_count_range" => store_all(key, &gs.decorations_count_range, matrix(key, value, 3, 2)?, u32_of)?,
+        "decoration_spacing" => gs.decoration_spacing.store(f32_bits(key, value)?, relaxed),
+        "decoration_spin_rate" => gs.decoration_spin_rate.store(f32_bits(key, value)?, relaxed),
+        "decoration_motion" => gs.decoration_motion.store(u32_of(key, value)?, relaxed),
+        "decoration_pulse_amplitude" => gs.decoration_pulse_amplitude.store(f32_bits(key, value)?, relaxed),
+        "decoration_pulse_hz" => gs.decoration_pulse_hz.store(f32_bits(key, value)?, relaxed),
+        "decoration_symmetry" => gs.decoration_symmetry.store(bool_of(key, value)?, relaxed),
+        "decoration_colors" => store_all(key, &gs.decoration_colors, matrix(key, value, 3, 4)?, f32_bits)?,
+        "decoration_color_jitter" => gs.decoration_color_jitter.store(f32_bits(key, value)?, relaxed),
+        "outline_width" => gs.outline_width.store(f32_bits(key, value)?, relaxed),
+        "outline_color" => store_all(key, &gs.outline_color, elements(key, value, 4)?, f32_bits)?,
+        "shaping_dwell_secs" => gs.shaping_dwell_secs.store(f32_bits(key, value)?, relaxed),
+        "suppress_blanked_attempts" => gs.suppress_blanked_attempts.store(bool_of(key, value)?, relaxed),
+        "is_catch" => gs.is_catch.store(bool_of(key, value)?, relaxed),
+        "max_attempts" => gs.max_attempts.store(u32_of(key, value)?, relaxed),
+        "trial_timeout_secs" => gs.trial_timeout_secs.store(f32_bits(key, value)?, relaxed),
+        "win_radius_min" => gs.win_radius_min.store(f32_bits(key, value)?, relaxed),
+        "win_radius_max" => gs.win_radius_max.store(f32_bits(key, value)?, relaxed),
+        "required_approach_sign" => gs.required_approach_sign.store(sign_of(key, value)?, relaxed),
+        "min_view_secs" => gs.min_view_secs.store(f32_bits(key, value)?, relaxed),
+        "feedback_delay_ms" => gs.feedback_delay_ms.store(u32_of(key, value)?, relaxed),
+        "debounce_frames" => store_all(key, &gs.debounce_frames, elements(key, value, 3)?, u32_of)?,
+        "iti_frames" => gs.iti_frames.store(u32_of(key, value)?, relaxed),
+        "post_win_mode" => gs.post_win_mode.store(u32_of(key, value)?, relaxed),
+        "pause_mode" => gs.pause_mode.store(u32_of(key, value)?, relaxed),
+        "reticle_enabled" => gs.reticle_enabled.store(bool_of(key, value)?, relaxed),
+        "ui_scale" => gs.ui_scale.store(f32_bits(key, value)?, relaxed),
+        "score_bar_anchor" => gs.score_bar_anchor.store(u32_of(key, value)?, relaxed),
+        "score_bar_width_percent" => gs.score_bar_width_percent.store(f32_bits(key, value)?, relaxed),
+        "score_bar_height" => gs.score_bar_height.store(f32_bits(key, value)?, relaxed),
+        "score_bar_show_numeric" => gs.score_bar_show_numeric.store(bool_of(key, value)?, relaxed),
+        "reticle_size" => gs.reticle_size.store(f32_bits(key, value)?, relaxed),
+        "reticle_color" => store_all(key, &gs.reticle_color, elements(key, value, 4)?, f32_bits)?,
+        "reticle_style" => gs.reticle_style.store(u32_of(key, value)?, relaxed),
+        "reticle_alignment_tint" => gs.reticle_alignment_tint.store(bool_of(key, value)?, relaxed),
+        "live_feedback_enabled" => gs.live_feedback_enabled.store(bool_of(key, value)?, relaxed),
+        "live_feedback_gain" => gs.live_feedback_gain.store(f32_bits(key, value)?, relaxed),
+        "live_feedback_smoothing" => gs.live_feedback_smoothing.store(f32_bits(key, value)?, relaxed),
+        "control_mirror_rotation" => gs.control_mirror_rotation.store(bool_of(key, value)?, relaxed),
+        "control_mirror_zoom" => gs.control_mirror_zoom.store(bool_of(key, value)?, relaxed),
+        "mirror_x" => gs.mirror_x.store(bool_of(key, value)?, relaxed),
+        "recenter_on_reset" => gs.recenter_on_reset.store(bool_of(key, value)?, relaxed),
+        "distractor_count" => gs.distractor_count.store(u32_of(key, value)?, relaxed),
+        "distractor_shape" => gs.distractor_shape.store(u32_of(key, value)?, relaxed),
+        "distractor_size_range" => store_all(key, &gs.distractor_size_range, elements(key, value, 2)?, f32_bits)?,
+        "distractor_color" => store_all(key, &gs.distractor_color, elements(key, value, 4)?, f32_bits)?,
+        "distractor_ring" => store_all(key, &gs.distractor_ring, elements(key, value, 2)?, f32_bits)?,
+        "distractor_seed" => gs.distractor_seed.store(u64_of(key, value)?, relaxed),
+        "pyramid_type" => gs.pyramid_type.store(u32_of(key, value)?, relaxed),
+        "door_hole_radius_fraction" => gs.door_hole_radius_fraction.store(f32_bits(key, value)?, relaxed),
+        "door_frame_thickness" => gs.door_frame_thickness.store(f32_bits(key, value)?, relaxed),
+        "door_inset" => gs.door_inset.store(f32_bits(key, value)?, relaxed),
+        "door_reward_weights" => store_all(key, &gs.door_reward_weights, elements(key, value, 8)?, f32_bits)?,
+        "target_kind" => gs.target_kind.store(u32_of(key, value)?, relaxed),
+        "target_face" => gs.target_face.store(u32_of(key, value)?, relaxed),
+        "camera_fov_degrees" => gs.camera_fov_degrees.store(f32_bits(key, value)?, relaxed),
+        "camera_near" => gs.camera_near.store(f32_bits(key, value)?, relaxed),
+        "camera_far" => gs.camera_far.store(f32_bits(key, value)?, relaxed),
+        "spotlight_outer_angle" => gs.spotlight_outer_angle.store(f32_bits(key, value)?, relaxed),
+        "spotlight_range" => gs.spotlight_range.store(f32_bits(key, value)?, relaxed),
+        "win_glow_color" => store_all(key, &gs.win_glow_color, elements(key, value, 4)?, f32_bits)?,
+        "fog_enabled" => gs.fog_enabled.store(bool_of(key, value)?, relaxed),
+        "fog_color" => store_all(key, &gs.fog_color, elements(key, value, 4)?, f32_bits)?,
+        "fog_start" => gs.fog_start.store(f32_bits(key, value)?, relaxed),
+        "fog_end" => gs.fog_end.store(f32_bits(key, value)?, relaxed),
+        "projection_mode" => gs.projection_mode.store(u32_of(key, value)?, relaxed),
+        "ortho_scale_range" => store_all(key, &gs.ortho_scale_range, elements(key, value, 2)?, f32_bits)?,
+        "color_space" => gs.color_space.store(u32_of(key, value)?, relaxed),
+        "backdrop_enabled" => gs.backdrop_enabled.store(bool_of(key, value)?, relaxed),
+        "stimulus_file" => {
+            let path = optional_str(key, value)?.unwrap_or("");
+            validate_stimulus_file(path)?;
+            gs.set_stimulus_file(path);
+        }
+        "stimulus_expected_hash" => gs.stimulus_expected_hash.store(u64_of(key, value)?, relaxed),
+        "trial_id" => gs.trial_id_hash.store(crate::trial_id_hash(optional_str(key, value)?.unwrap_or("")), relaxed),
+        _ => return Err(format!("write_game_structure() got an unexpected argument \"{key}\"")),
+    }
+    Ok(())
+}
+
+/// The checks of python.rs `write_game_structure`, on a whole config
+fn validate_config(gs: &SharedGameStructure) -> Result<(), String> {
+    let colors = f32s_of(&gs.colors);
+    let face_colors = std::array::from_fn(|face| std::array::from_fn(|c| colors[face * 4 + c]));
+    validate_pyramid_type(gs.pyramid_type.load(Ordering::Relaxed), &face_colors).map_err(|e| e.to_string())?;
+    let config_mode = gs.config_mode.load(Ordering::Relaxed);
+    match ConfigMode::from_u32(config_mode) {
+        None => return Err(format!("unknown config_mode code {config_mode}")),
+        Some(ConfigMode::Randomized) => {
+            let range = |range: &[AtomicU32]| [f32_of(&range[0]), f32_of(&range[1])];
+            validate_geometry_ranges(range(&gs.base_radius_range), range(&gs.height_range), range(&gs.start_orient_range))?;
+        }
+        Some(ConfigMode::Explicit) => {}
+    }
+    let color_space = gs.color_space.load(Ordering::Relaxed);
+    if ColorSpace::from_u32(color_space).is_none() {
+        return Err(format!("unknown color_space code {color_space}"));
+    }
+    let target_kind = gs.target_kind.load(Ordering::Relaxed);
+    if TargetKind::from_u32(target_kind).is_none() {
+        return Err(format!("unknown target_kind code {target_kind}"));
+    }
+    let count_range = u32s_of(&gs.decorations_count_range);
+    if let Some(face) = count_range.chunks(2).position(|range| range[1] != 0 && range[0] > range[1]) {
+        return Err(format!(
+            "decorations_count_range of face {face} must satisfy min <= max (max 0 disables it), got {:?}",
+            &count_range[face * 2..face * 2 + 2]
+        ));
+    }
+    let decoration_spacing = f32_of(&gs.decoration_spacing);
+    if !(decoration_spacing > 0.0 && decoration_spacing.is_finite()) {
+        return Err(format!("decoration_spacing must be positive, got {decoration_spacing}"));
+    }
+    validate_decoration_colors(&std::array::from_fn(|face| {
+        std::array::from_fn(|channel| f32_of(&gs.decoration_colors[face * 4 + channel]))
+    }))?;
+    let decoration_spin_rate = f32_of(&gs.decoration_spin_rate);
+    if !decoration_spin_rate.is_finite() {
+        return Err(format!("decoration_spin_rate must be finite, got {decoration_spin_rate}"));
+    }
+    let decoration_motion = gs.decoration_motion.load(Ordering::Relaxed);
+    if DecorationMotion::from_u32(decoration_motion).is_none() {
+        return Err(format!("unknown decoration_motion code {decoration_motion}"));
+    }
+    validate_decoration_pulse(f32_of(&gs.decoration_pulse_amplitude), f32_of(&gs.decoration_pulse_hz))?;
+    let decoration_color_jitter = f32_of(&gs.decoration_color_jitter);
+    if !(0.0..=1.0).contains(&decoration_color_jitter) {
+        return Err(format!("decoration_color_jitter must be in [0, 1], got {decoration_color_jitter}"));
+    }
+    let outline_width = f32_of(&gs.outline_width);
+    if !(outline_width >= 0.0 && outline_width.is_finite()) {
+        return Err(format!("outline_width must be >= 0 (0 = no outline), got {outline_width}"));
+    }
+    let ui_scale = f32_of(&gs.ui_scale);
+    if !(ui_scale >= 0.0 && ui_scale.is_finite()) {
+        return Err(format!("ui_scale must be >= 0 (0 = scale with the viewport), got {ui_scale}"));
+    }
+    let target_face = gs.target_face.load(Ordering::Relaxed);
+    if target_face >= 3 {
+        return Err(format!("target_face must be 0, 1 or 2, got {target_face}"));
+    }
+    let base_radius = widest_base_radius(config_mode, f32_of(&gs.base_radius), [f32_of(&gs.base_radius_range[0]), f32_of(&gs.base_radius_range[1])]);
+    validate_door_geometry(
+        base_radius,
+        f32_of(&gs.door_hole_radius_fraction),
+        f32_of(&gs.door_frame_thickness),
+        f32_of(&gs.door_inset),
+    )?;
+    validate_door_reward_weights(&f32s_of(&gs.door_reward_weights))?;
+    validate_fog(f32_of(&gs.fog_start), f32_of(&gs.fog_end))?;
+    validate_score_bar(
+        gs.score_bar_anchor.load(Ordering::Relaxed),
+        f32_of(&gs.score_bar_width_percent),
+        f32_of(&gs.score_bar_height),
+    )?;
+    validate_camera_projection(f32_of(&gs.camera_fov_degrees), f32_of(&gs.camera_near), f32_of(&gs.camera_far))?;
+    let spotlight_outer_angle = f32_of(&gs.spotlight_outer_angle);
+    if !(spotlight_outer_angle > 0.0 && spotlight_outer_angle <= std::f32::consts::FRAC_PI_2) {
+        return Err(format!("spotlight_outer_angle must be in (0, PI/2] radians, got {spotlight_outer_angle}"));
+    }
+    let projection_mode = gs.projection_mode.load(Ordering::Relaxed);
+    if ProjectionMode::from_u32(projection_mode).is_none() {
+        return Err(format!("unknown projection_mode code {projection_mode}"));
+    }
+    let ortho_scale_range = f32s_of(&gs.ortho_scale_range);
+    if !(ortho_scale_range[0] > 0.0 && ortho_scale_range[0] <= ortho_scale_range[1]) {
+        return Err(format!("ortho_scale_range must be positive with min <= max, got {ortho_scale_range:?}"));
+    }
+    let spotlight_range = f32_of(&gs.spotlight_range);
+    if spotlight_range.is_nan() || spotlight_range <= 0.0 {
+        return Err(format!("spotlight_range must be positive, got {spotlight_range}"));
+    }
+    let trial_timeout_secs = f32_of(&gs.trial_timeout_secs);
+    if trial_timeout_secs.is_nan() || trial_timeout_secs < 0.0 {
+        return Err(format!("trial_timeout_secs must be >= 0 (0 = no timeout), got {trial_timeout_secs}"));
+    }
+    // A catch trial can never be won, so it needs another way to end
+    if gs.is_catch.load(Ordering::Relaxed) && gs.max_attempts.load(Ordering::Relaxed) == 0 && trial_timeout_secs == 0.0 {
+        return Err("catch trials cannot be won and need max_attempts or trial_timeout_secs to end".to_string());
+    }
+    let (win_radius_min, win_radius_max) = (f32_of(&gs.win_radius_min), f32_of(&gs.win_radius_max));
+    if win_radius_max != 0.0 && !(win_radius_min >= 0.0 && win_radius_min <= win_radius_max) {
+        return Err(format!(
+            "win radius band must satisfy 0 <= min <= max (max 0 disables it), got [{win_radius_min}, {win_radius_max}]"
+        ));
+    }
+    let min_view_secs = f32_of(&gs.min_view_secs);
+    if !(min_view_secs >= 0.0 && min_view_secs.is_finite()) {
+        return Err(format!("min_view_secs must be >= 0 (0 = no minimum), got {min_view_secs}"));
+    }
+    let post_win_mode = gs.post_win_mode.load(Ordering::Relaxed);
+    if PostWinMode::from_u32(post_win_mode).is_none() {
+        return Err(format!("unknown post_win_mode code {post_win_mode}"));
+    }
+    let pause_mode = gs.pause_mode.load(Ordering::Relaxed);
+    if PauseMode::from_u32(pause_mode).is_none() {
+        return Err(format!("unknown pause_mode code {pause_mode}"));
+    }
+    let door_anim_easing = gs.door_anim_easing.load(Ordering::Relaxed);
+    if AnimationEasing::from_u32(door_anim_easing).is_none() {
+        return Err(format!("unknown door_anim_easing code {door_anim_easing}"));
+    }
+    let live_feedback_gain = f32_of(&gs.live_feedback_gain);
+    if live_feedback_gain.is_nan() || live_feedback_gain <= 0.0 {
+        return Err(format!("live_feedback_gain must be positive, got {live_feedback_gain}"));
+    }
+    let live_feedback_smoothing = f32_of(&gs.live_feedback_smoothing);
+    if !(0.0..1.0).contains(&live_feedback_smoothing) {
+        return Err(format!("live_feedback_smoothing must be in [0, 1), got {live_feedback_smoothing}"));
+    }
+    Ok(())
+}
+
+/// Codes of an enum among the first 256, found with `is_code`
+fn codes(is_code: impl Fn(u32) -> bool) -> Value {
+    json!((0..=u8::MAX as u32).filter(|&code| is_code(code)).collect::<Vec<u32>>())
+}
+
+/// `value` with every integer in it replaced by `integer`
+fn with_integers(value: &Value, integer: &Value) -> Value {
+    match value {
+        Value::Array(items) => Value::Array(items.iter().map(|item| with_integers(item, integer)).collect()),
+        Value::Number(number) if !number.is_f64() => integer.clone(),
+        other => other.clone(),
+    }
+}
+
+/// `bounds` of a scalar, nested under `items` as deep as the arrays of `value` go
+fn nested_like(value: &Value, bounds: Value) -> Value {
+    match value.as_array().and_then(|items| items.first()) {
+        Some(first) => json!({ "items": nested_like(first, bounds) }),
+        None => bounds,
+    }
+}
+
+/// `config_json` keyed like the `write_game_structure` arguments, which name the decoration counts
+/// and sizes in the plural and take the trial ID itself (null here, only its hash is kept)
+pub fn config_arguments(gs: &SharedGameStructure) -> Map<String, Value> {
+    config_json(gs)
+        .into_iter()
+        .map(|(key, value)| match key.as_str() {
+            "decoration_count" => ("decorations_count".to_string(), value),
+            "decoration_size" => ("decorations_size".to_string(), value),
+            "trial_id_hash" => ("trial_id".to_string(), Value::Null),
+            _ => (key, value),
+        })
+        .collect()
+}
+
+/// JSON Schema type of a stored config value, arrays sized like it. Null stands for the optional
+/// strings (read with optional_str), the only arguments stored from null
+fn schema_of(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "type": ["string", "null"] }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(number) if number.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => {
+            let mut schema = json!({ "type": "array", "minItems": items.len(), "maxItems": items.len() });
+            if let Some(first) = items.first() {
+                schema["items"] = schema_of(first);
+            }
+            schema
+        }
+        Value::Object(_) => json!({ "type": "object" }),
+    }
+}
+
+/// `schema` with `bounds` added, the bounds of array items merged into the item schema
+fn merge_schema(schema: Value, bounds: Value) -> Value {
+    match (schema, bounds) {
+        (Value::Object(mut schema), Value::Object(bounds)) => {
+            for (key, bound) in bounds {
+                let merged = match schema.remove(&key) {
+                    Some(existing) => merge_schema(existing, bound),
+                    None => bound,
+                };
+                schema.insert(key, merged);
+            }
+            Value::Object(schema)
+        }
+        (_, bounds) => bounds,
+    }
+}
+
+/// JSON Schema bounds of the `write_game_structure` arguments, as far as each can be checked on its
+/// own: enum codes from the enums, ranges from the constants validate_config checks them against,
+/// and the integer ranges the arguments are stored in. Cross-field rules (fog_start below fog_end,
+/// near below far, min <= max, catch trials needing an end, door depths clearing the pyramid, ...)
+/// stay with validate_config.
+/// reticle_style and distractor_shape list their codes, though unknown ones fall back in the game
+pub fn config_field_bounds() -> Map<String, Value> {
+    use crate::constants::camera_3d_constants::CAMERA_3D_FOV_RANGE_DEGREES;
+    use crate::constants::game_constants::STIMULUS_FILE_MAX_LEN;
+    use crate::constants::pyramid_constants::{
+        PYRAMID_ANGLE_OFFSET_RAD_MAX, PYRAMID_ANGLE_OFFSET_RAD_MIN, PYRAMID_BASE_RADIUS_LIMITS, PYRAMID_HEIGHT_LIMITS,
+    };
+    let within = |[min, max]: [f32; 2]| json!({ "items": { "minimum": min, "maximum": max } });
+    let unit = json!({ "minimum": 0.0, "maximum": 1.0 });
+    let non_negative = json!({ "minimum": 0.0 });
+    let positive = json!({ "exclusiveMinimum": 0.0 });
+    let apothem = crate::base_panel_apothem();
+    let [min_fov, max_fov] = CAMERA_3D_FOV_RANGE_DEGREES;
+
+    let mut bounds: Map<String, Value> = [
+        ("pyramid_type", json!({ "enum": codes(|code| PyramidType::from_u32(code).is_some()) })),
+        ("config_mode", json!({ "enum": codes(|code| ConfigMode::from_u32(code).is_some()) })),
+        ("color_space", json!({ "enum": codes(|code| ColorSpace::from_u32(code).is_some()) })),
+        ("target_kind", json!({ "enum": codes(|code| TargetKind::from_u32(code).is_some()) })),
+        ("decoration_motion", json!({ "enum": codes(|code| DecorationMotion::from_u32(code).is_some()) })),
+        ("score_bar_anchor", json!({ "enum": codes(|code| ScoreBarAnchor::from_u32(code).is_some()) })),
+        ("projection_mode", json!({ "enum": codes(|code| ProjectionMode::from_u32(code).is_some()) })),
+        ("post_win_mode", json!({ "enum": codes(|code| PostWinMode::from_u32(code).is_some()) })),
+        ("pause_mode", json!({ "enum": codes(|code| PauseMode::from_u32(code).is_some()) })),
+        ("door_anim_easing", json!({ "enum": codes(|code| AnimationEasing::from_u32(code).is_some()) })),
+        ("reticle_style", json!({ "enum": codes(|code| ReticleStyle::from_u32(code) as u32 == code) })),
+        ("distractor_shape", json!({ "enum": codes(|code| DistractorShape::from_u32(code) as u32 == code) })),
+        ("required_approach_sign", json!({ "enum": [-1, 0, 1] })),
+        ("target_face", json!({ "minimum": 0, "maximum": 2 })),
+        ("base_radius_range", within(PYRAMID_BASE_RADIUS_LIMITS)),
+        ("height_range", within(PYRAMID_HEIGHT_LIMITS)),
+        ("start_orient_range", within([PYRAMID_ANGLE_OFFSET_RAD_MIN, PYRAMID_ANGLE_OFFSET_RAD_MAX])),
+        ("decoration_spacing", positive.clone()),
+        ("decoration_colors", json!({ "items": { "items": unit } })),
+        ("decoration_pulse_amplitude", json!({ "minimum": 0.0, "exclusiveMaximum": 1.0 })),
+        ("decoration_pulse_hz", non_negative.clone()),
+        ("decoration_color_jitter", unit.clone()),
+        ("outline_width", non_negative.clone()),
+        ("ui_scale", non_negative.clone()),
+        ("door_hole_radius_fraction", json!({ "exclusiveMinimum": 0.0, "exclusiveMaximum": 1.0 })),
+        ("door_frame_thickness", non_negative.clone()),
+        ("door_inset", json!({ "exclusiveMinimum": -apothem })),
+        ("door_reward_weights", json!({ "items": non_negative })),
+        ("fog_start", non_negative.clone()),
+        ("score_bar_width_percent", json!({ "exclusiveMinimum": 0.0, "maximum": 100.0 })),
+        ("score_bar_height", positive.clone()),
+        ("camera_fov_degrees", json!({ "anyOf": [{ "const": 0.0 }, { "minimum": min_fov, "maximum": max_fov }] })),
+        ("camera_near", non_negative.clone()),
+        ("camera_far", non_negative.clone()),
+        ("spotlight_outer_angle", json!({ "exclusiveMinimum": 0.0, "maximum": std::f32::consts::FRAC_PI_2 })),
+        ("spotlight_range", positive.clone()),
+        ("ortho_scale_range", json!({ "items": positive })),
+        ("trial_timeout_secs", non_negative.clone()),
+        ("min_view_secs", non_negative),
+        ("live_feedback_gain", positive),
+        ("live_feedback_smoothing", json!({ "minimum": 0.0, "exclusiveMaximum": 1.0 })),
+        ("stimulus_file", json!({ "maxLength": STIMULUS_FILE_MAX_LEN })),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
+
+    // The other integer arguments are bounded by what they are stored in
+    let scratch = SharedGameStructure::new();
+    let stores = |key: &str, value: &Value| store_config_field(&scratch, key, value).is_ok();
+    for (key, default) in config_arguments(&scratch) {
+        if bounds.contains_key(&key) || with_integers(&default, &Value::Null) == default || !stores(&key, &default) {
+            continue;
+        }
+        let mut range = Map::new();
+        if !stores(&key, &with_integers(&default, &json!(-1))) {
+            range.insert("minimum".to_string(), json!(0));
+        }
+        if !stores(&key, &with_integers(&default, &json!(u32::MAX as u64 + 1))) {
+            range.insert("maximum".to_string(), json!(u32::MAX));
+        }
+        bounds.insert(key, nested_like(&default, Value::Object(range)));
+    }
+    bounds
+}
+
+/// JSON Schema of each `write_game_structure` argument: the type and shape of the value the game
+/// stores it as (config_json), with config_field_bounds
+pub fn config_field_schemas() -> Map<String, Value> {
+    let mut bounds = config_field_bounds();
+    config_arguments(&SharedGameStructure::new())
+        .into_iter()
+        .map(|(key, default)| {
+            let schema = schema_of(&default);
+            let schema = match bounds.remove(&key) {
+                Some(bounds) => merge_schema(schema, bounds),
+                None => schema,
+            };
+            (key, schema)
+        })
+        .collect()
+}
+
+/// `write_game_structure` with the arguments of `args`, over the current config of the region written.
+/// The config is validated as a whole and left untouched if any argument is rejected
+fn write_config(shm: &SharedMemory, mut args: Map<String, Value>) -> Result<(), String> {
+    let staged = args.remove("staged").map(|staged| bool_of("staged", &staged)).transpose()?.unwrap_or(false);
+    let target = if staged {
+        // The game is still copying the previous staged config
+        if shm.commands.promote_staged.load(Ordering::Acquire) {
+            return Err("previous staged config has not been promoted yet".to_string());
+        }
+        &shm.game_structure_staged
+    } else {
+        &shm.game_structure_control
+    };
+
+    let config = SharedGameStructure::new();
+    config.reset_all_fields(target);
+    for (key, value) in &args {
+        store_config_field(&config, key, value)?;
+    }
+    validate_config(&config)?;
+    target.reset_all_fields(&config);
+    Ok(())
+}
+
+/// Applies a JSON object of `write_game_structure` arguments (with `"staged": true` to write the
+/// staged config instead). Keys left out keep their current value
+pub fn apply_config_json(shm: &SharedMemory, json: &str) -> Result<(), String> {
+    write_config(shm, parse_object(json)?)
+}
+
+/// Keyword arguments of a controller method, checked against its parameter names
+struct Kwargs<'a> {
+    args: &'a Map<String, Value>,
+}
+
+impl<'a> Kwargs<'a> {
+    fn new(method: &str, args: &'a Map<String, Value>, params: &[&str]) -> Result<Self, String> {
+        match args.keys().find(|key| !params.contains(&key.as_str())) {
+            Some(key) => Err(format!("{method}() got an unexpected argument \"{key}\"")),
+            None => Ok(Self { args }),
+        }
+    }
+
+    fn required(&self, key: &str) -> Result<&'a Value, String> {
+        self.args.get(key).ok_or_else(|| format!("missing argument \"{key}\""))
+    }
+
+    fn bool(&self, key: &str) -> Result<bool, String> {
+        self.args.get(key).map(|value| bool_of(key, value)).unwrap_or(Ok(false))
+    }
+
+    fn u32_or(&self, key: &str, default: u32) -> Result<u32, String> {
+        self.args.get(key).map(|value| u32_of(key, value)).unwrap_or(Ok(default))
+    }
+
+    fn f32(&self, key: &str) -> Result<f32, String> {
+        f32_bits(key, self.required(key)?).map(f32::from_bits)
+    }
+
+    fn direction(&self) -> Result<i64, String> {
+        match self.required("direction")?.as_i64() {
+            Some(direction @ (-1 | 1)) => Ok(direction),
+            _ => Err(format!("direction must be -1 or 1, got {}", self.args["direction"])),
+        }
+    }
+}
+
+const WRITE_COMMANDS_PARAMS: [&str; 20] = [
+    "rotate_left", "rotate_right", "zoom_in", "zoom_out", "check", "reset", "blank_screen", "stop_rendering",
+    "resume_rendering", "animation_door", "step_frame", "debug_normals", "dump_config", "clear_attempts",
+    "queue_next", "set_pyramid_type", "promote_staged", "log_categories", "show_threshold_cone",
+    "dump_geometry",
+];
+
+/// python.rs `write_commands`
+fn write_commands(cmd: &SharedCommands, args: &Kwargs) -> Result<(), String> {
+    let log_categories = args.args.get("log_categories").map(|mask| u32_of("log_categories", mask)).transpose()?;
+    if let Some(mask) = log_categories {
+        if mask & !LOG_CATEGORIES_ALL != 0 {
+            return Err(format!("log_categories has bits above LOG_CATEGORIES_ALL ({LOG_CATEGORIES_ALL}), got {mask}"));
+        }
+    }
+    let set_pyramid_type = match args.args.get("set_pyramid_type") {
+        None | Some(Value::Null) => None,
+        Some(code) => Some(u32_of("set_pyramid_type", code)?),
+    };
+    // Every argument parsed before any is stored, so a rejected call changes nothing
+    let rotate_left = args.bool("rotate_left")?;
+    let rotate_right = args.bool("rotate_right")?;
+    let zoom_in = args.bool("zoom_in")?;
+    let zoom_out = args.bool("zoom_out")?;
+    let check = args.bool("check")?;
+    let reset = args.bool("reset")?;
+    let blank_screen = args.bool("blank_screen")?;
+    let stop_rendering = args.bool("stop_rendering")?;
+    let resume_rendering = args.bool("resume_rendering")?;
+    let animation_door = args.bool("animation_door")?;
+    let step_frame = args.bool("step_frame")?;
+    let debug_normals = args.bool("debug_normals")?;
+    let show_threshold_cone = args.bool("show_threshold_cone")?;
+    let dump_config = args.bool("dump_config")?;
+    let dump_geometry = args.bool("dump_geometry")?;
+    let clear_attempts = args.bool("clear_attempts")?;
+    let queue_next = args.bool("queue_next")?;
+    let promote_staged = args.bool("promote_staged")?;
+    cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+
+    // Latched before reset is stored, so the game never sees the reset without its staged config
+    if promote_staged {
+        cmd.promote_staged.store(true, Ordering::Release);
+    }
+    cmd.rotate_left.store(rotate_left, Ordering::Relaxed);
+    cmd.rotate_right.store(rotate_right, Ordering::Relaxed);
+    cmd.zoom_in.store(zoom_in, Ordering::Relaxed);
+    cmd.zoom_out.store(zoom_out, Ordering::Relaxed);
+    cmd.check_alignment.store(check, Ordering::Relaxed);
+    // After the flag, so a game that sees the request also sees the flag
+    if check {
+        cmd.check_requests.fetch_add(1, Ordering::Release);
+    }
+    cmd.reset.store(reset, Ordering::Release);
+    cmd.blank_screen.store(blank_screen, Ordering::Relaxed);
+    cmd.stop_rendering.store(stop_rendering, Ordering::Relaxed);
+    cmd.resume_rendering.store(resume_rendering, Ordering::Relaxed);
+    cmd.animation_door.store(animation_door, Ordering::Relaxed);
+    cmd.step_frame.store(step_frame, Ordering::Relaxed);
+    cmd.debug_normals.store(debug_normals, Ordering::Relaxed);
+    cmd.show_threshold_cone.store(show_threshold_cone, Ordering::Relaxed);
+    // Latched flags: only set here, the game clears them once handled
+    if dump_config {
+        cmd.dump_config.store(true, Ordering::Relaxed);
+    }
+    if dump_geometry {
+        cmd.dump_geometry.store(true, Ordering::Relaxed);
+    }
+    if clear_attempts {
+        cmd.clear_attempts.store(true, Ordering::Relaxed);
+    }
+    if queue_next {
+        cmd.queue_next.store(true, Ordering::Relaxed);
+    }
+    if let Some(code) = set_pyramid_type {
+        cmd.set_pyramid_type.store(code, Ordering::Relaxed);
+    }
+    if let Some(mask) = log_categories {
+        cmd.log_categories.store(mask, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Calls the controller method named by `"method"` with the other keys of the JSON object as its
+/// keyword arguments, as the Python controller calls it on its shared memory wrapper
+pub fn apply_command_json(shm: &SharedMemory, json: &str) -> Result<(), String> {
+    let mut args = parse_object(json)?;
+    let method = match args.remove("method") {
+        Some(Value::String(method)) => method,
+        other => return Err(format!("expected the method name as a string under \"method\", got {other:?}")),
+    };
+    let cmd = &shm.commands;
+    let kwargs = |params: &[&str]| Kwargs::new(&method, &args, params);
+    match method.as_str() {
+        "write_commands" => write_commands(cmd, &kwargs(&WRITE_COMMANDS_PARAMS)?)?,
+        "write_game_structure" => return write_config(shm, args),
+        "write_staged_config" => {
+            args.insert("staged".to_string(), Value::Bool(true));
+            return write_config(shm, args);
+        }
+        "set_motion_latched" => {
+            let args = kwargs(&["enabled", "timeout_frames"])?;
+            let enabled = bool_of("enabled", args.required("enabled")?)?;
+            cmd.motion_latch_timeout_frames.store(args.u32_or("timeout_frames", MOTION_LATCH_TIMEOUT_FRAMES)?, Ordering::Relaxed);
+            cmd.motion_latched.store(enabled, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "set_controller_watchdog" => {
+            let args = kwargs(&["timeout_frames", "blank"])?;
+            let timeout_frames = args.u32_or("timeout_frames", CONTROLLER_TIMEOUT_FRAMES)?;
+            cmd.controller_lost_blank.store(args.bool("blank")?, Ordering::Relaxed);
+            cmd.controller_timeout_frames.store(timeout_frames, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "set_global_yaw_offset" => {
+            let radians = kwargs(&["radians"])?.f32("radians")?;
+            if !radians.is_finite() {
+                return Err(format!("global yaw offset must be finite, got {radians}"));
+            }
+            cmd.global_yaw_offset.store(radians.to_bits(), Ordering::Relaxed);
+        }
+        "probe_decorations" => {
+            let args = kwargs(&["face", "color", "duration_secs"])?;
+            let face = u32_of("face", args.required("face")?)?;
+            let color = elements("color", args.required("color")?, 4)?
+                .into_iter()
+                .map(|channel| f32_bits("color", channel))
+                .collect::<Result<Vec<u32>, _>>()?;
+            let duration_secs = match args.args.get("duration_secs") {
+                Some(_) => args.f32("duration_secs")?,
+                None => PROBE_DURATION_SECS,
+            };
+            if face > 2 {
+                return Err(format!("probe face must be 0, 1 or 2, got {face}"));
+            }
+            if !(duration_secs.is_finite() && duration_secs > 0.0) {
+                return Err(format!("probe duration must be positive, got {duration_secs}"));
+            }
+            for (slot, value) in cmd.probe_color.iter().zip(color) {
+                slot.store(value, Ordering::Relaxed);
+            }
+            cmd.probe_duration_secs.store(duration_secs.to_bits(), Ordering::Relaxed);
+            cmd.probe_face.store(face, Ordering::Release);
+        }
+        "show_reference_image" => {
+            let index = u32_of("index", kwargs(&["index"])?.required("index")?)?;
+            if index as usize >= REFERENCE_IMAGES.len() {
+                return Err(format!("reference image must be below {}, got {index}", REFERENCE_IMAGES.len()));
+            }
+            cmd.show_image.store(index, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "hide_reference_image" => {
+            kwargs(&[])?;
+            cmd.show_image.store(HIDE_IMAGE, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "set_render_quality" => {
+            let level = u32_of("level", kwargs(&["level"])?.required("level")?)?;
+            if RenderQuality::from_u32(level).is_none() {
+                return Err(format!("unknown render quality {level}"));
+            }
+            cmd.render_quality.store(level, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "start_rotation" | "start_zoom" => {
+            let direction = kwargs(&["direction"])?.direction()?;
+            let (negative, positive, stop) = if method == "start_rotation" {
+                (&cmd.rotate_start_left, &cmd.rotate_start_right, &cmd.rotate_stop)
+            } else {
+                (&cmd.zoom_start_in, &cmd.zoom_start_out, &cmd.zoom_stop)
+            };
+            let (start, other) = if direction < 0 { (negative, positive) } else { (positive, negative) };
+            other.store(false, Ordering::Relaxed);
+            stop.store(false, Ordering::Relaxed);
+            start.store(true, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        "stop_rotation" | "stop_zoom" => {
+            kwargs(&[])?;
+            let (negative, positive, stop) = if method == "stop_rotation" {
+                (&cmd.rotate_start_left, &cmd.rotate_start_right, &cmd.rotate_stop)
+            } else {
+                (&cmd.zoom_start_in, &cmd.zoom_start_out, &cmd.zoom_stop)
+            };
+            negative.store(false, Ordering::Relaxed);
+            positive.store(false, Ordering::Relaxed);
+            stop.store(true, Ordering::Relaxed);
+            cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        }
+        _ => return Err(format!("unknown method \"{method}\"")),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether `write_game_structure` takes `key` set to `value` over the default config, made
+    /// Randomized so that the geometry ranges are checked too
+    fn accepts(key: &str, value: Value) -> bool {
+        let shm = Box::new(SharedMemory::new());
+        let mut args = Map::new();
+        args.insert("config_mode".to_string(), json!(ConfigMode::Randomized as u32));
+        args.insert(key.to_string(), value);
+        write_config(&shm, args).is_ok()
+    }
+
+    /// `value` with every number in it replaced by `number`
+    fn with_numbers(value: &Value, number: f64, integer: bool) -> Value {
+        match value {
+            Value::Array(items) => Value::Array(items.iter().map(|item| with_numbers(item, number, integer)).collect()),
+            _ if integer => json!(number as i64),
+            _ => json!(number),
+        }
+    }
+
+    #[test]
+    fn config_field_schemas_match_validate_config() {
+        let defaults = config_arguments(&SharedGameStructure::new());
+        let bounds = config_field_bounds();
+        for key in ["pyramid_type", "decorations_count", "decorations_count_range", "iti_frames", "decoration_seeds"] {
+            assert!(bounds.contains_key(key), "{key} has no bounds");
+        }
+        let schemas = config_field_schemas();
+        for key in bounds.keys() {
+            assert!(schemas.contains_key(key), "{key} is bounded but not an argument");
+        }
+        assert_eq!(schemas["trial_id"], json!({ "type": ["string", "null"] }));
+        assert_eq!(schemas["colors"]["items"]["type"], json!("array"));
+        assert_eq!(schemas["decoration_seeds"]["items"]["type"], json!("integer"));
+
+        for (key, fragment) in &schemas {
+            let default = defaults.get(key).cloned().unwrap_or(Value::Null);
+            let mut scalar = fragment;
+            while let Some(items) = scalar.get("items") {
+                scalar = items;
+            }
+            let integer = with_integers(&default, &Value::Null) != default;
+            let step = if integer { 1.0 } else { 1e-3 };
+            let at = |number: f64| accepts(key, with_numbers(&default, number, integer));
+            let number = |name: &str| scalar.get(name).and_then(Value::as_f64);
+
+            if let Some(codes) = scalar.get("enum").and_then(Value::as_array) {
+                let codes: Vec<i64> = codes.iter().filter_map(Value::as_i64).collect();
+                // Which pyramid type fits depends on the colors
+                if key != "pyramid_type" {
+                    assert!(codes.iter().all(|&code| at(code as f64)), "{key}: a code is rejected");
+                }
+                // Unknown reticle styles and distractor shapes fall back instead
+                if !["reticle_style", "distractor_shape"].contains(&key.as_str()) {
+                    assert!(!at(*codes.iter().max().unwrap() as f64 + 1.0), "{key}: an unknown code is taken");
+                }
+            }
+            if let Some(min) = number("minimum") {
+                assert!(at(min) && !at(min - step), "{key}: minimum {min}");
+            }
+            if let Some(min) = number("exclusiveMinimum") {
+                assert!(!at(min) && at(min + step), "{key}: exclusive minimum {min}");
+            }
+            if let Some(max) = number("maximum") {
+                assert!(at(max) && !at(max + step), "{key}: maximum {max}");
+            }
+            if let Some(max) = number("exclusiveMaximum") {
+                assert!(!at(max) && at(max - step), "{key}: exclusive maximum {max}");
+            }
+            if let Some(any_of) = scalar.get("anyOf").and_then(Value::as_array) {
+                let [zero, range] = [&any_of[0], &any_of[1]];
+                let (min, max) = (range["minimum"].as_f64().unwrap(), range["maximum"].as_f64().unwrap());
+                assert!(at(zero["const"].as_f64().unwrap()) && at(min) && at(max), "{key}: {any_of:?}");
+                assert!(!at(min - step) && !at(max + step), "{key}: {any_of:?}");
+            }
+            if let Some(len) = scalar.get("maxLength").and_then(Value::as_u64) {
+                assert!(accepts(key, json!("a".repeat(len as usize))) && !accepts(key, json!("a".repeat(len as usize + 1))));
+            }
+            if scalar.get("type") == Some(&json!(["string", "null"])) {
+                assert!(accepts(key, Value::Null) && accepts(key, json!("trial.json")), "{key}");
+            }
+        }
+    }
+}
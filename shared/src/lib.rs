@@ -9,12 +9,30 @@
 //!     commands: SharedCommands,                 // Controller -> Game (one-way)
 //!     game_structure_contr: SharedGameStructure // Controller -> Game (one-way)
 //!     game_structure_game: SharedGameStructure  // Game ->  Controller (one-way)
-//!
+//!     ...
+//!     state_snapshot: SharedStateSnapshot       // Game ->  Controller, double-buffered (see snapshot.rs)
 //! }
+//!
+//! ## Byte order
+//!
+//! The region is little-endian: every field is stored in the native byte order of the writer, and
+//! floats travel as their IEEE 754 bits in an AtomicU32 (`f32::to_bits`). Both processes have to
+//! agree on it, so a big-endian target fails to compile rather than reading garbled floats.
 //! 
-use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64};
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicU64, AtomicU8};
 use std::sync::atomic::Ordering;
 pub mod constants;
+pub mod json_api;
+pub mod palette;
+pub mod session;
+pub mod snapshot;
+pub mod staircase;
+pub mod tutorial;
+
+use snapshot::SharedStateSnapshot;
+
+// The layout is little-endian only, see "Byte order" above
+const _: () = assert!(cfg!(target_endian = "little"), "the shared memory layout is little-endian only");
 
 
 /// Commands sent from Controller to Game.
@@ -28,13 +46,94 @@ pub struct SharedCommands {
     pub zoom_out: AtomicBool,
     /// Trigger once
     pub check_alignment: AtomicBool,
+    /// Incremented by the controller after setting `check_alignment`, once per check requested. The
+    /// game counts the requests it never saw (set and cleared between two frames, or several in one)
+    /// into `dropped_checks`
+    pub check_requests: AtomicU32,
     pub reset: AtomicBool,
     pub blank_screen: AtomicBool,
     pub stop_rendering: AtomicBool,
     pub resume_rendering: AtomicBool,
     pub animation_door: AtomicBool,
+    /// Advance exactly one frame while rendering is stopped
+    pub step_frame: AtomicBool,
+    /// Draw the face/door normals and camera forward compared by the alignment check
+    pub debug_normals: AtomicBool,
+    /// Draw the region around the target normal within which a check wins, of half-angle
+    /// `acos(cosine_alignment_threshold)`. Held by the controller
+    pub show_threshold_cone: AtomicBool,
+    /// Append the current trial's config as a JSON line to CONFIG_DUMP_PATH.
+    /// Latched by the controller and cleared by the game once handled
+    pub dump_config: AtomicBool,
+    /// Write the vertices and normals of the pyramid faces and the decorations, with their transforms,
+    /// as shown, to a JSON file in GEOMETRY_DUMP_DIR. Latched by the controller and cleared by the game once handled
+    pub dump_geometry: AtomicBool,
+    /// Zero the attempts and rejected checks without resetting the trial.
+    /// Latched by the controller and cleared by the game once handled
+    pub clear_attempts: AtomicBool,
+    /// The next trial's config is staged in the controller region: apply it when the
+    /// inter-trial interval ends. Latched by the controller and cleared by the game once handled
+    pub queue_next: AtomicBool,
+    /// Switch the live pyramid to this PyramidType code, recoloring the faces in place.
+    /// NO_PYRAMID_TYPE_CHANGE when idle, swapped back to it by the game once read
+    pub set_pyramid_type: AtomicU32,
+    /// Copy the staged config block over the controller region, so the next reset uses it.
+    /// Latched by the controller once the staged block is fully written, cleared by the game once copied
+    pub promote_staged: AtomicBool,
+    /// Opt-in latched motion: a rotate/zoom start keeps the camera moving until the matching stop,
+    /// instead of only while the level flags above are held. Held by the controller
+    pub motion_latched: AtomicBool,
+    /// Latched motion starts and stops. Set by the controller and cleared by the game once read
+    pub rotate_start_left: AtomicBool,
+    pub rotate_start_right: AtomicBool,
+    pub rotate_stop: AtomicBool,
+    pub zoom_start_in: AtomicBool,
+    pub zoom_start_out: AtomicBool,
+    pub zoom_stop: AtomicBool,
+    /// Incremented by the controller on every command write. Latched motion stops once it has not
+    /// changed for `motion_latch_timeout_frames` game frames (0 disables the timeout)
+    pub heartbeat: AtomicU32,
+    pub motion_latch_timeout_frames: AtomicU32,
+    /// Controller watchdog: once `heartbeat` has not changed for this many game frames, the level
+    /// rotate/zoom commands and latched motion are cleared and `controller_lost` is raised, blanking
+    /// the screen as well with `controller_lost_blank`. 0 (the default) disables it. Held by the controller
+    pub controller_timeout_frames: AtomicU32,
+    pub controller_lost_blank: AtomicBool,
+    /// Log event categories the game writes, as a LogCategory bitmask.
+    /// NO_LOG_CATEGORIES_CHANGE when idle, swapped back to it by the game once read
+    pub log_categories: AtomicU32,
+    /// Yaw (radians, f32 bits) added to every rotable entity on top of the controls, to line the
+    /// pyramid up with a rotated display. Held by the controller, applied live without a reset
+    pub global_yaw_offset: AtomicU32,
+    /// Recolor the decorations of this face (0..3) with `probe_color` (RGBA f32 bits, in the trial's
+    /// color space) for `probe_duration_secs` (f32 bits), then revert them. The color and duration
+    /// are written first; NO_PROBE_FACE when idle, swapped back to it by the game once read
+    pub probe_face: AtomicU32,
+    pub probe_color: [AtomicU32; 4],
+    pub probe_duration_secs: AtomicU32,
+    /// Show the reference image `REFERENCE_IMAGES[show_image]` full screen, above the scene and the
+    /// blank overlay (e.g. a fixation target during the inter-trial interval), or hide it with
+    /// HIDE_IMAGE. Every reset hides it. NO_IMAGE_CHANGE when idle, swapped back to it by the game once read
+    pub show_image: AtomicU32,
+    /// Switch the rendering to this RenderQuality code, live, to trade fidelity for frame stability.
+    /// NO_RENDER_QUALITY_CHANGE when idle, swapped back to it by the game once read
+    pub render_quality: AtomicU32,
 }
 
+/// Idle value of `SharedCommands::set_pyramid_type`
+pub const NO_PYRAMID_TYPE_CHANGE: u32 = u32::MAX;
+
+/// Idle value of `SharedCommands::probe_face`
+pub const NO_PROBE_FACE: u32 = u32::MAX;
+
+/// Idle value of `SharedCommands::show_image`
+pub const NO_IMAGE_CHANGE: u32 = u32::MAX;
+/// `SharedCommands::show_image` value hiding the reference image, and `reference_image` while none is shown
+pub const HIDE_IMAGE: u32 = u32::MAX - 1;
+
+/// Idle value of `SharedCommands::render_quality`
+pub const NO_RENDER_QUALITY_CHANGE: u32 = u32::MAX;
+
 impl SharedCommands {
     pub const fn new() -> Self {
         Self {
@@ -43,11 +142,39 @@ impl SharedCommands {
             zoom_in: AtomicBool::new(false),
             zoom_out: AtomicBool::new(false),
             check_alignment: AtomicBool::new(false),
+            check_requests: AtomicU32::new(0),
             reset: AtomicBool::new(false),
             blank_screen: AtomicBool::new(false),
             stop_rendering: AtomicBool::new(false),
             resume_rendering: AtomicBool::new(false),
             animation_door: AtomicBool::new(false),
+            step_frame: AtomicBool::new(false),
+            debug_normals: AtomicBool::new(false),
+            show_threshold_cone: AtomicBool::new(false),
+            clear_attempts: AtomicBool::new(false),
+            dump_config: AtomicBool::new(false),
+            dump_geometry: AtomicBool::new(false),
+            queue_next: AtomicBool::new(false),
+            set_pyramid_type: AtomicU32::new(NO_PYRAMID_TYPE_CHANGE),
+            promote_staged: AtomicBool::new(false),
+            motion_latched: AtomicBool::new(false),
+            rotate_start_left: AtomicBool::new(false),
+            rotate_start_right: AtomicBool::new(false),
+            rotate_stop: AtomicBool::new(false),
+            zoom_start_in: AtomicBool::new(false),
+            zoom_start_out: AtomicBool::new(false),
+            zoom_stop: AtomicBool::new(false),
+            heartbeat: AtomicU32::new(0),
+            motion_latch_timeout_frames: AtomicU32::new(constants::game_constants::MOTION_LATCH_TIMEOUT_FRAMES),
+            controller_timeout_frames: AtomicU32::new(0),
+            controller_lost_blank: AtomicBool::new(false),
+            log_categories: AtomicU32::new(NO_LOG_CATEGORIES_CHANGE),
+            global_yaw_offset: AtomicU32::new(0),
+            probe_face: AtomicU32::new(NO_PROBE_FACE),
+            probe_color: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
+            probe_duration_secs: AtomicU32::new(constants::game_constants::PROBE_DURATION_SECS.to_bits()),
+            show_image: AtomicU32::new(NO_IMAGE_CHANGE),
+            render_quality: AtomicU32::new(NO_RENDER_QUALITY_CHANGE),
         }
     }
 }
@@ -56,20 +183,735 @@ impl Default for SharedCommands {
     fn default() -> Self { Self::new() }
 }
 
+/// Commands whose reads the game reports back (`SharedGameStructure::commands_seen`), named like
+/// their `SharedCommands` fields in SEEN_COMMAND_NAMES. The u32 commands are seen when not idle
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeenCommand {
+    RotateLeft = 0,
+    RotateRight = 1,
+    ZoomIn = 2,
+    ZoomOut = 3,
+    CheckAlignment = 4,
+    Reset = 5,
+    BlankScreen = 6,
+    StopRendering = 7,
+    ResumeRendering = 8,
+    AnimationDoor = 9,
+    StepFrame = 10,
+    DebugNormals = 11,
+    ShowThresholdCone = 12,
+    DumpConfig = 13,
+    ClearAttempts = 14,
+    QueueNext = 15,
+    PromoteStaged = 16,
+    SetPyramidType = 17,
+    ProbeFace = 18,
+    ShowImage = 19,
+    RenderQuality = 20,
+    LogCategories = 21,
+    DumpGeometry = 22,
+}
+
+pub const SEEN_COMMAND_COUNT: usize = 23;
+
+/// `SharedCommands` field of each SeenCommand, in bit order
+pub const SEEN_COMMAND_NAMES: [&str; SEEN_COMMAND_COUNT] = [
+    "rotate_left",
+    "rotate_right",
+    "zoom_in",
+    "zoom_out",
+    "check_alignment",
+    "reset",
+    "blank_screen",
+    "stop_rendering",
+    "resume_rendering",
+    "animation_door",
+    "step_frame",
+    "debug_normals",
+    "show_threshold_cone",
+    "dump_config",
+    "clear_attempts",
+    "queue_next",
+    "promote_staged",
+    "set_pyramid_type",
+    "probe_face",
+    "show_image",
+    "render_quality",
+    "log_categories",
+    "dump_geometry",
+];
+
+/// Trigger commands with a debounce window, in `debounce_frames` order
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebouncedCommand {
+    CheckAlignment = 0,
+    Reset = 1,
+    BlankScreen = 2,
+}
+
+pub const DEBOUNCED_COMMAND_COUNT: usize = 3;
+
+impl SeenCommand {
+    /// Bit of the command in `commands_seen`
+    pub const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// SEEN_COMMAND_NAMES of the bits set in a `commands_seen` mask
+pub fn seen_command_names(mask: u32) -> Vec<&'static str> {
+    SEEN_COMMAND_NAMES.iter().enumerate().filter(|(bit, _)| mask & (1 << bit) != 0).map(|(_, name)| *name).collect()
+}
+
+impl SharedCommands {
+    /// Current values of the SeenCommand fields, in bit order: 0/1 for the flags, the raw value
+    /// (idle included) for the u32 commands
+    pub fn raw_values(&self) -> [u32; SEEN_COMMAND_COUNT] {
+        let flag = |flag: &AtomicBool| u32::from(flag.load(Ordering::Relaxed));
+        let value = |value: &AtomicU32| value.load(Ordering::Relaxed);
+        [
+            flag(&self.rotate_left),
+            flag(&self.rotate_right),
+            flag(&self.zoom_in),
+            flag(&self.zoom_out),
+            flag(&self.check_alignment),
+            flag(&self.reset),
+            flag(&self.blank_screen),
+            flag(&self.stop_rendering),
+            flag(&self.resume_rendering),
+            flag(&self.animation_door),
+            flag(&self.step_frame),
+            flag(&self.debug_normals),
+            flag(&self.show_threshold_cone),
+            flag(&self.dump_config),
+            flag(&self.clear_attempts),
+            flag(&self.queue_next),
+            flag(&self.promote_staged),
+            value(&self.set_pyramid_type),
+            value(&self.probe_face),
+            value(&self.show_image),
+            value(&self.render_quality),
+            value(&self.log_categories),
+            flag(&self.dump_geometry),
+        ]
+    }
+}
+
 /// Pyramid types.
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PyramidType {
     Type1 = 0,
     Type2 = 1,
+    /// All faces share one color, the target face is defined by its decoration shape
+    Type3 = 2,
 }
 
-/// Game phases.
+impl PyramidType {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        Self::try_from(value).ok()
+    }
+}
+
+impl TryFrom<u32> for PyramidType {
+    type Error = PyramidTypeError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Type1),
+            1 => Ok(Self::Type2),
+            2 => Ok(Self::Type3),
+            _ => Err(PyramidTypeError::UnknownCode(value)),
+        }
+    }
+}
+
+/// Why a pyramid type in a trial config was rejected
+#[derive(Clone, Debug, PartialEq)]
+pub enum PyramidTypeError {
+    /// Not a PyramidType code
+    UnknownCode(u32),
+    /// Type3 targets are defined by decoration only, differing colors would give them away
+    Type3ColorsDiffer([[f32; 4]; 3]),
+}
+
+impl core::fmt::Display for PyramidTypeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Self::UnknownCode(code) => write!(f, "unknown pyramid_type code {code}"),
+            Self::Type3ColorsDiffer(colors) => {
+                write!(f, "pyramid_type 2 (Type3) requires identical face colors, got {colors:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PyramidTypeError {}
+
+/// Checks a trial's pyramid type code against its face colors, returning the type
+pub fn validate_pyramid_type(code: u32, colors: &[[f32; 4]; 3]) -> Result<PyramidType, PyramidTypeError> {
+    match PyramidType::try_from(code)? {
+        PyramidType::Type3 if colors.iter().any(|face| face != &colors[0]) => {
+            Err(PyramidTypeError::Type3ColorsDiffer(*colors))
+        }
+        pyramid_type => Ok(pyramid_type),
+    }
+}
+
+/// What the alignment check compares the camera against.
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    Door = 0,
+    /// The target face (e.g. the unique-colored one) instead of a door
+    Face = 1,
+}
+
+impl TargetKind {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Door),
+            1 => Some(Self::Face),
+            _ => None,
+        }
+    }
+}
+
+/// Face colors and target face for a pyramid type, derived from a trial's configured ones.
+/// Type2 copies face 0's color onto face 2, so face 1 is the unique (target) face, Type3 uses
+/// face 0's color everywhere and Type1 keeps the configuration as is.
+pub fn derive_pyramid_faces(pyramid_type: PyramidType, colors: [[f32; 4]; 3], target_face: u32) -> ([[f32; 4]; 3], u32) {
+    match pyramid_type {
+        PyramidType::Type1 => (colors, target_face),
+        PyramidType::Type2 => ([colors[0], colors[1], colors[0]], 1),
+        PyramidType::Type3 => ([colors[0]; 3], target_face),
+    }
+}
+
+/// How a trial's pyramid geometry (base radius, height, start orientation) is given.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConfigMode {
+    /// Built from the configured values
+    #[default]
+    Explicit = 0,
+    /// Drawn at reset within the configured ranges from the geometry seed, the drawn values
+    /// reported in the game region
+    Randomized = 1,
+}
+
+impl ConfigMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Explicit),
+            1 => Some(Self::Randomized),
+            _ => None,
+        }
+    }
+}
+
+/// Checks the [min, max] ranges randomized trials draw their geometry from: ordered and within the
+/// PYRAMID_BASE_RADIUS_LIMITS, PYRAMID_HEIGHT_LIMITS and PYRAMID_ANGLE_OFFSET_RAD bounds
+pub fn validate_geometry_ranges(base_radius: [f32; 2], height: [f32; 2], start_orient: [f32; 2]) -> Result<(), String> {
+    use constants::pyramid_constants::{
+        PYRAMID_ANGLE_OFFSET_RAD_MAX, PYRAMID_ANGLE_OFFSET_RAD_MIN, PYRAMID_BASE_RADIUS_LIMITS, PYRAMID_HEIGHT_LIMITS,
+    };
+    for (name, [min, max], [low, high]) in [
+        ("base_radius_range", base_radius, PYRAMID_BASE_RADIUS_LIMITS),
+        ("height_range", height, PYRAMID_HEIGHT_LIMITS),
+        ("start_orient_range", start_orient, [PYRAMID_ANGLE_OFFSET_RAD_MIN, PYRAMID_ANGLE_OFFSET_RAD_MAX]),
+    ] {
+        if !(low <= min && min <= max && max <= high) {
+            return Err(format!("{name} must be [min, max] with {low} <= min <= max <= {high}, got [{min}, {max}]"));
+        }
+    }
+    Ok(())
+}
+
+/// Distance from the base center to a wooden base panel, which bounds the door frame and inset
+pub fn base_panel_apothem() -> f32 {
+    use constants::pyramid_constants::{BASE_NR_SIDES, BASE_RADIUS};
+    BASE_RADIUS * (std::f32::consts::PI / BASE_NR_SIDES as f32).cos()
+}
+
+/// Largest pyramid base radius a config builds: the top of its range in Randomized mode, where the
+/// radius is drawn at reset, else `base_radius`
+pub fn widest_base_radius(config_mode: u32, base_radius: f32, base_radius_range: [f32; 2]) -> f32 {
+    match ConfigMode::from_u32(config_mode) {
+        Some(ConfigMode::Randomized) => base_radius_range[1],
+        _ => base_radius,
+    }
+}
+
+/// Checks that a door geometry fits the wooden base panels around a pyramid of `base_radius`: the
+/// hole must fit within the panel, and neither the frame tunnel nor the door glow may reach from the
+/// panel into the pyramid. A positive door_inset sits behind the panel surface, towards the pyramid,
+/// a negative one in front of it, away from the base.
+pub fn validate_door_geometry(base_radius: f32, hole_radius_fraction: f32, frame_thickness: f32, door_inset: f32) -> Result<(), String> {
+    let apothem = base_panel_apothem();
+    // Depth behind the panels left free by the pyramid
+    let clearance = apothem - base_radius;
+
+    if !(hole_radius_fraction > 0.0 && hole_radius_fraction < 1.0) {
+        return Err(format!("hole_radius_fraction must be in (0, 1) to fit the panel, got {hole_radius_fraction}"));
+    }
+    if !(0.0..clearance).contains(&frame_thickness) {
+        return Err(format!("frame_thickness must be in [0, {clearance}) around a pyramid of radius {base_radius}, got {frame_thickness}"));
+    }
+    if !(door_inset > -apothem && door_inset < clearance) {
+        return Err(format!("door_inset must be within (-{apothem}, {clearance}) around a pyramid of radius {base_radius}, got {door_inset}"));
+    }
+    Ok(())
+}
+
+/// Checks that every decoration color channel is in [0, 1]
+pub fn validate_decoration_colors(colors: &[[f32; 4]; 3]) -> Result<(), String> {
+    match colors.iter().position(|color| !color.iter().all(|channel| (0.0..=1.0).contains(channel))) {
+        Some(face) => Err(format!("decoration_colors channels must be in [0, 1], got {:?} for face {face}", colors[face])),
+        None => Ok(()),
+    }
+}
+
+/// Checks that every door reward weight is finite and not negative
+pub fn validate_door_reward_weights(weights: &[f32]) -> Result<(), String> {
+    match weights.iter().find(|weight| !(weight.is_finite() && **weight >= 0.0)) {
+        Some(weight) => Err(format!("door_reward_weights must be finite and >= 0, got {weight}")),
+        None => Ok(()),
+    }
+}
+
+/// Checks the fog distances: finite, start >= 0 and start < end
+pub fn validate_fog(start: f32, end: f32) -> Result<(), String> {
+    if !(start.is_finite() && end.is_finite() && start >= 0.0 && start < end) {
+        return Err(format!("fog_start must be >= 0 and below fog_end, got {start} and {end}"));
+    }
+    Ok(())
+}
+
+/// Checks the score bar layout: a known anchor, a width in (0, 100] percent and a positive height
+pub fn validate_score_bar(anchor: u32, width_percent: f32, height: f32) -> Result<(), String> {
+    if ScoreBarAnchor::from_u32(anchor).is_none() {
+        return Err(format!("unknown score_bar_anchor code {anchor}"));
+    }
+    if !(width_percent > 0.0 && width_percent <= 100.0) {
+        return Err(format!("score_bar_width_percent must be in (0, 100], got {width_percent}"));
+    }
+    if !(height > 0.0 && height.is_finite()) {
+        return Err(format!("score_bar_height must be positive, got {height}"));
+    }
+    Ok(())
+}
+
+/// Checks that a stimulus file path fits `stimulus_file`: at most STIMULUS_FILE_MAX_LEN bytes, no NUL
+pub fn validate_stimulus_file(path: &str) -> Result<(), String> {
+    use constants::game_constants::STIMULUS_FILE_MAX_LEN;
+    if path.len() > STIMULUS_FILE_MAX_LEN {
+        return Err(format!("stimulus_file must be at most {STIMULUS_FILE_MAX_LEN} bytes, got {}", path.len()));
+    }
+    if path.contains('\0') {
+        return Err("stimulus_file must not contain NUL".to_string());
+    }
+    Ok(())
+}
+
+/// Hash of a controller trial ID for `trial_id_hash`: FNV-1a over its UTF-8 bytes, stable across
+/// platforms and runs. The empty ID (no ID) hashes to 0
+pub fn trial_id_hash(trial_id: &str) -> u64 {
+    if trial_id.is_empty() {
+        return 0;
+    }
+    trial_id.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Outcome of loading the trial's `stimulus_file`, emitted as `stimulus_file_status`. Any failure
+/// falls back to the procedural pyramid of the trial config
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StimulusFileStatus {
+    /// No stimulus file, the pyramid was generated from the config
+    #[default]
+    Procedural = 0,
+    Loaded = 1,
+    Missing = 2,
+    /// Not a stimulus description
+    Invalid = 3,
+    /// The description's hash differs from `stimulus_expected_hash`
+    HashMismatch = 4,
+}
+
+impl StimulusFileStatus {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Procedural),
+            1 => Some(Self::Loaded),
+            2 => Some(Self::Missing),
+            3 => Some(Self::Invalid),
+            4 => Some(Self::HashMismatch),
+            _ => None,
+        }
+    }
+}
+
+/// What the game does once the inter-trial interval is over, when the controller has neither
+/// reset nor queued the next trial during it.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PostWinMode {
+    /// Lift the blank and return to playing
+    #[default]
+    AutoReturn = 0,
+    /// Keep the screen blank until the next reset
+    HoldBlank = 1,
+}
+
+impl PostWinMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::AutoReturn),
+            1 => Some(Self::HoldBlank),
+            _ => None,
+        }
+    }
+}
+
+/// What the game hides while paused (stop_rendering). Game logic is stopped in every mode.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseMode {
+    /// Hide the 3D camera, blanking the whole scene
+    #[default]
+    HideCamera = 0,
+    /// Hide the game entities (pyramid, doors, decorations), the environment and UI stay visible
+    HideGameEntities = 1,
+    /// Hide nothing, the scene stays frozen in place
+    Freeze = 2,
+}
+
+impl PauseMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::HideCamera),
+            1 => Some(Self::HideGameEntities),
+            2 => Some(Self::Freeze),
+            _ => None,
+        }
+    }
+}
+
+/// How decorations move on their faces. Each decoration is offset by its own phase, drawn from its
+/// face's decoration seed in placement order, so the motion follows from the config alone.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DecorationMotion {
+    /// Static decorations, whatever the spin rate
+    None = 0,
+    /// Turning around the face normal at `decoration_spin_rate` (static at the default rate of 0)
+    #[default]
+    Spin = 1,
+    /// Scale oscillating around the size by `decoration_pulse_amplitude` at `decoration_pulse_hz`
+    Pulse = 2,
+}
+
+impl DecorationMotion {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Spin),
+            2 => Some(Self::Pulse),
+            _ => None,
+        }
+    }
+}
+
+/// Checks the pulse of `DecorationMotion::Pulse`: the amplitude must keep the scale positive and
+/// the frequency must not be negative
+pub fn validate_decoration_pulse(amplitude: f32, hz: f32) -> Result<(), String> {
+    if !(0.0..1.0).contains(&amplitude) {
+        return Err(format!("decoration_pulse_amplitude must be in [0, 1), got {amplitude}"));
+    }
+    if !(hz >= 0.0 && hz.is_finite()) {
+        return Err(format!("decoration_pulse_hz must be >= 0, got {hz}"));
+    }
+    Ok(())
+}
+
+/// Interpolation of the door glow within the fade phases of the door animation. The opening fade
+/// follows the curve from 0 to 1, the closing fade plays it back from 1 to 0.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationEasing {
+    #[default]
+    Linear = 0,
+    /// Cubic, slow at both ends of the fade
+    EaseInOut = 1,
+    /// Slow start then a steep rise, doubling every tenth of the fade
+    Exponential = 2,
+}
+
+impl AnimationEasing {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Linear),
+            1 => Some(Self::EaseInOut),
+            2 => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+
+    /// Eased progress for a linear progress `t`, both in [0, 1] (`t` is clamped). 0 and 1 are fixed points
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Self::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+            Self::Exponential if t == 0.0 => 0.0,
+            Self::Exponential => 2f32.powf(10.0 * t - 10.0),
+        }
+    }
+}
+
+/// Camera projection modes.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionMode {
+    Perspective = 0,
+    /// Zoom changes the projection scale, removing distance cues to object size
+    Orthographic = 1,
+}
+
+impl ProjectionMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Perspective),
+            1 => Some(Self::Orthographic),
+            _ => None,
+        }
+    }
+}
+
+/// Render quality levels, switched live by `SharedCommands::render_quality`
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderQuality {
+    /// No MSAA and no shadows
+    Low = 0,
+    /// 2x MSAA with shadows
+    Medium = 1,
+    /// 4x MSAA with shadows, as the game starts
+    #[default]
+    High = 2,
+}
+
+impl RenderQuality {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Low),
+            1 => Some(Self::Medium),
+            2 => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+/// Checks a perspective configuration: a non-zero FOV must lie in the supported range,
+/// clip planes must be positive (or 0 for the default) and near must stay below far.
+pub fn validate_camera_projection(fov_degrees: f32, near: f32, far: f32) -> Result<(), String> {
+    use constants::camera_3d_constants::CAMERA_3D_FOV_RANGE_DEGREES;
+    let [min_fov, max_fov] = CAMERA_3D_FOV_RANGE_DEGREES;
+
+    if fov_degrees != 0.0 && !(min_fov..=max_fov).contains(&fov_degrees) {
+        return Err(format!("fov_degrees must be 0 (default) or in [{min_fov}, {max_fov}], got {fov_degrees}"));
+    }
+    if !(near.is_finite() && near >= 0.0 && far.is_finite() && far >= 0.0) {
+        return Err(format!("near/far must be 0 (default) or positive, got {near}/{far}"));
+    }
+    if near != 0.0 && far != 0.0 && near >= far {
+        return Err(format!("near ({near}) must be smaller than far ({far})"));
+    }
+    Ok(())
+}
+
+/// How config colors are read. sRGB values are gamma encoded, as picked in most color tools;
+/// linear values are proportional to emitted light (e.g. from a photometric calibration), so the
+/// same triple renders brighter in its mid tones when read as linear.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    #[default]
+    Srgb = 0,
+    Linear = 1,
+}
+
+impl ColorSpace {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Srgb),
+            1 => Some(Self::Linear),
+            _ => None,
+        }
+    }
+}
+
+/// Categories of game log events, each one bit of a log category mask. Only the categories and
+/// their mask live here, for the controllers to set: shared itself never logs (it returns its
+/// errors), so the `log_event!` macro and its session file are game_node's (utils/macros.rs).
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogCategory {
+    Commands = 0,
+    Reset = 1,
+    Animation = 2,
+    Shm = 3,
+    Error = 4,
+}
+
+/// Every log category enabled
+pub const LOG_CATEGORIES_ALL: u32 = (1 << LogCategory::ALL.len()) - 1;
+
+/// Idle value of `SharedCommands::log_categories`
+pub const NO_LOG_CATEGORIES_CHANGE: u32 = u32::MAX;
+
+impl LogCategory {
+    pub const ALL: [Self; 5] = [Self::Commands, Self::Reset, Self::Animation, Self::Shm, Self::Error];
+
+    pub fn from_u32(value: u32) -> Option<Self> {
+        Self::ALL.get(value as usize).copied()
+    }
+
+    /// Bit of this category in a log category mask
+    pub const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Commands => "commands",
+            Self::Reset => "reset",
+            Self::Animation => "animation",
+            Self::Shm => "shm",
+            Self::Error => "error",
+        }
+    }
+
+    /// Parses a log category mask: comma separated category names, `all`, `none` or the mask as a number
+    pub fn parse_mask(value: &str) -> Result<u32, String> {
+        let value = value.trim();
+        if let Ok(mask) = value.parse::<u32>() {
+            return match mask & !LOG_CATEGORIES_ALL {
+                0 => Ok(mask),
+                _ => Err(format!("log category mask {} has bits above {}", mask, LOG_CATEGORIES_ALL)),
+            };
+        }
+        value.split(',').map(str::trim).filter(|name| !name.is_empty()).try_fold(0, |mask, name| {
+            match name {
+                "all" => Ok(LOG_CATEGORIES_ALL),
+                "none" => Ok(mask),
+                _ => Self::ALL
+                    .iter()
+                    .find(|category| category.name() == name)
+                    .map(|category| mask | category.bit())
+                    .ok_or_else(|| format!("unknown log category '{}'", name)),
+            }
+        })
+    }
+}
+
+/// Aiming reticle styles.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReticleStyle {
+    Dot = 0,
+    Cross = 1,
+    Circle = 2,
+}
+
+impl ReticleStyle {
+    /// Unknown values fall back to the cross
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::Dot,
+            2 => Self::Circle,
+            _ => Self::Cross,
+        }
+    }
+}
+
+/// Screen edge the score bar is anchored to.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScoreBarAnchor {
+    #[default]
+    Top = 0,
+    Bottom = 1,
+}
+
+impl ScoreBarAnchor {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Top),
+            1 => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Distractor object shapes.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistractorShape {
+    Cube = 0,
+    Sphere = 1,
+    Pyramid = 2,
+    /// Drawn per object from the seed
+    Mixed = 3,
+}
+
+impl DistractorShape {
+    /// Unknown values fall back to mixed
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            0 => Self::Cube,
+            1 => Self::Sphere,
+            2 => Self::Pyramid,
+            _ => Self::Mixed,
+        }
+    }
+}
+
+/// Game phases.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum Phase {
     Playing = 0,
+    /// Won, while the door animation plays
     Won = 1,
+    /// Out of attempts or time, while the last check's animation plays
+    Failed = 2,
+    /// Blank inter-trial interval of `iti_frames`, then the next trial
+    InterTrial = 3,
+    /// Reset applied on this frame, the new trial starts playing on the next one
+    Resetting = 4,
+    /// No trial loaded yet: from launch until the first reset, nothing is spawned and checks are
+    /// refused (counted in `standby_checks`)
+    #[default]
+    Standby = 5,
+}
+
+impl Phase {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Playing),
+            1 => Some(Self::Won),
+            2 => Some(Self::Failed),
+            3 => Some(Self::InterTrial),
+            4 => Some(Self::Resetting),
+            5 => Some(Self::Standby),
+            _ => None,
+        }
+    }
 }
 
 /// Shared atomic game structure for game state communication (1 for each Controller and Game, 2 in total, read-write respectively).
@@ -85,38 +927,326 @@ pub struct SharedGameStructure {
     pub base_radius: AtomicU32,
     pub height: AtomicU32,
     pub start_orient: AtomicU32,
+    /// ConfigMode as u32. In Randomized mode the game draws base_radius, height and start_orient
+    /// within their [min, max] ranges (f32 bits) from `geometry_seed`, and the game region holds
+    /// the drawn values
+    pub config_mode: AtomicU32,
+    pub geometry_seed: AtomicU64,
+    pub base_radius_range: [AtomicU32; 2],
+    pub height_range: [AtomicU32; 2],
+    pub start_orient_range: [AtomicU32; 2],
     pub target_door: AtomicU32,
+    /// PyramidType as u32
+    pub pyramid_type: AtomicU32,
+    /// TargetKind as u32, and the target face used in face mode
+    pub target_kind: AtomicU32,
+    pub target_face: AtomicU32,
     /// Colors: 3 faces * 4 channels (RGBA) = 12 floats as u32 bits
     pub colors: [AtomicU32; 12],
+    /// ColorSpace as u32, for the face, reticle and distractor colors
+    pub color_space: AtomicU32,
 
-    pub decorations_count: [AtomicU32; 3], // per face
+    pub decorations_count: [AtomicU32; 3], // per face, the drawn count in the game region when a range is set
+    /// Per-face [min, max] decoration count, 3 faces * 2 (max 0 = use decorations_count)
+    pub decorations_count_range: [AtomicU32; 6],
     pub decorations_size: [AtomicU32; 3], // per face
+    /// Multiplier of the decoration spacing rules, f32 bits (1 = the default packing)
+    pub decoration_spacing: AtomicU32,
+    /// Decorations spin around their face normal at this rate, radians per second as f32 bits (0 = static)
+    pub decoration_spin_rate: AtomicU32,
+    /// DecorationMotion as u32, and the pulse amplitude (fraction of the size) and frequency (Hz) as f32 bits
+    pub decoration_motion: AtomicU32,
+    pub decoration_pulse_amplitude: AtomicU32,
+    pub decoration_pulse_hz: AtomicU32,
+    /// Decoration layout of every face mirror-symmetric about its vertical axis
+    pub decoration_symmetry: AtomicBool,
+    /// Decoration RGBA of each face (3x4 f32 bits, in `color_space`), an alpha of 0 draws it from the
+    /// face seed. The game region holds the colors shown, drawn ones included
+    pub decoration_colors: [AtomicU32; 12],
+    /// Largest per-channel offset of each decoration's color from its face's, in [0, 1] as f32 bits
+    /// (0 = one color per face)
+    pub decoration_color_jitter: AtomicU32,
+    /// Width of the border outlining every face (f32 bits, 0 = none) and its RGBA color (f32 bits,
+    /// in `color_space`)
+    pub outline_width: AtomicU32,
+    pub outline_color: [AtomicU32; 4],
+
+    /// Door geometry, f32 bits
+    pub door_hole_radius_fraction: AtomicU32,
+    pub door_frame_thickness: AtomicU32,
+    pub door_inset: AtomicU32,
+    /// Reward weight of a win at each door (f32 bits), reported in `reward_weight` on a win so the
+    /// controller can dispense proportionally
+    pub door_reward_weights: [AtomicU32; 8],
+
+    /// Per-face flicker frequency (Hz) and depth, f32 bits. 0 Hz disables flicker
+    pub flicker_hz: [AtomicU32; 3],
+    pub flicker_depth: [AtomicU32; 3],
 
     // Logic
     pub cosine_alignment_threshold: AtomicU32,
+    /// Shaping dwell (seconds) before an automatic win, f32 bits. 0 disables it
+    pub shaping_dwell_secs: AtomicU32,
+    /// Catch trial: no target, every check is a non-win
+    pub is_catch: AtomicBool,
+    /// Trial end conditions, after which the game enters the Failed phase. 0 means unlimited (timeout as f32 bits)
+    pub max_attempts: AtomicU32,
+    pub trial_timeout_secs: AtomicU32,
+    /// Checks made while the screen is blanked are counted as rejected instead of as attempts
+    pub suppress_blanked_attempts: AtomicBool,
+    /// Orbit radius band (f32 bits) a check, or the shaping dwell, must be made from to win. A max of 0 disables it
+    pub win_radius_min: AtomicU32,
+    pub win_radius_max: AtomicU32,
+    /// Direction the pyramid must be turning in at a check (or through the shaping dwell) for it to win, from the recent yaw:
+    /// -1 decreasing (rotate_left with the default mapping), 1 increasing, 0 for no constraint
+    pub required_approach_sign: AtomicI32,
+    /// Seconds from the reset before a check can win (f32 bits); earlier checks count but never win, and the
+    /// shaping dwell only counts from then. 0 disables it
+    pub min_view_secs: AtomicU32,
+    /// Milliseconds between a decided check and the onset of its feedback (door animation and score bar)
+    pub feedback_delay_ms: AtomicU32,
+    /// Per DebouncedCommand, the nominal frames after an accepted activation during which further
+    /// ones are rejected and counted in `debounced_count`. 0 disables it
+    pub debounce_frames: [AtomicU32; DEBOUNCED_COMMAND_COUNT],
+    /// Inter-trial interval in nominal frames (run as fixed ticks), and the PostWinMode (as u32) applied when it ends
+    pub iti_frames: AtomicU32,
+    pub post_win_mode: AtomicU32,
+    /// PauseMode (as u32) applied when rendering is stopped
+    pub pause_mode: AtomicU32,
 
     // Animation Durations
     pub door_anim_fade_out: AtomicU32,   
     pub door_anim_stay_open: AtomicU32,  
     pub door_anim_fade_in: AtomicU32,    
+    /// AnimationEasing (as u32) of the fade phases
+    pub door_anim_easing: AtomicU32,
 
     // Lighting
     pub main_spotlight_intensity: AtomicU32, 
     pub ambient_brightness: AtomicU32,      
     pub max_spotlight_intensity: AtomicU32, 
+    pub spotlight_outer_angle: AtomicU32,
+    pub spotlight_range: AtomicU32,
+    /// Winning door glow RGBA (f32 bits, in the trial's color space), all 0 for the spotlight's own color
+    pub win_glow_color: [AtomicU32; 4],
+    /// Linear distance fog on the scene camera, with its RGBA and start/end distances (f32 bits)
+    pub fog_enabled: AtomicBool,
+    pub fog_color: [AtomicU32; 4],
+    pub fog_start: AtomicU32,
+    pub fog_end: AtomicU32,
+
+    /// UiScale applied to the overlay UI (f32 bits), 0 scales it with the viewport (see
+    /// UI_REFERENCE_WIDTH/HEIGHT)
+    pub ui_scale: AtomicU32,
+    /// Score bar: ScoreBarAnchor as u32, width in percent of the screen and height in pixels (f32
+    /// bits), and whether the alignment percentage is shown next to it
+    pub score_bar_anchor: AtomicU32,
+    pub score_bar_width_percent: AtomicU32,
+    pub score_bar_height: AtomicU32,
+    pub score_bar_show_numeric: AtomicBool,
+
+    // Reticle
+    pub reticle_enabled: AtomicBool,
+    pub reticle_size: AtomicU32,
+    /// RGBA as f32 bits
+    pub reticle_color: [AtomicU32; 4],
+    /// ReticleStyle as u32
+    pub reticle_style: AtomicU32,
+    pub reticle_alignment_tint: AtomicBool,
+
+    // Live feedback bar: gain and smoothing as f32 bits
+    pub live_feedback_enabled: AtomicBool,
+    pub live_feedback_gain: AtomicU32,
+    pub live_feedback_smoothing: AtomicU32,
+
+    // Controls: invert rotation / zoom direction, latched at reset
+    pub control_mirror_rotation: AtomicBool,
+    pub control_mirror_zoom: AtomicBool,
+    /// Scene shown mirrored left to right, applied at reset. Yaws stay those of the unmirrored scene
+    pub mirror_x: AtomicBool,
+    /// Whether the reset puts the camera and the pyramid yaw back at the trial's start, rather than
+    /// carrying them over from the previous trial
+    pub recenter_on_reset: AtomicBool,
+
+    /// Camera projection, f32 bits. 0 uses the Bevy default
+    pub camera_fov_degrees: AtomicU32,
+    pub camera_near: AtomicU32,
+    pub camera_far: AtomicU32,
+    /// ProjectionMode as u32, and the orthographic min/max scale as f32 bits
+    pub projection_mode: AtomicU32,
+    pub ortho_scale_range: [AtomicU32; 2],
+
+    // Distractors
+    pub distractor_count: AtomicU32,
+    /// DistractorShape as u32
+    pub distractor_shape: AtomicU32,
+    /// Min/max size as f32 bits
+    pub distractor_size_range: [AtomicU32; 2],
+    /// RGBA as f32 bits
+    pub distractor_color: [AtomicU32; 4],
+    /// Inner/outer placement radius as f32 bits
+    pub distractor_ring: [AtomicU32; 2],
+    pub distractor_seed: AtomicU64,
+
+    // Backdrop
+    pub backdrop_enabled: AtomicBool,
+
+    // Replay of an archived stimulus description (see game_node's stimulus.rs)
+    /// Path of the description to build the pyramid from instead of the seeds, UTF-8 padded with
+    /// NUL; empty for a procedural pyramid. See stimulus_file() and set_stimulus_file()
+    pub stimulus_file: [AtomicU8; constants::game_constants::STIMULUS_FILE_MAX_LEN],
+    /// Hash the replayed description must have, 0 to skip the check
+    pub stimulus_expected_hash: AtomicU64,
+
+    // Trial identity
+    /// trial_id_hash() of the controller's trial ID, echoed in the game's logs so sessions can be
+    /// joined on it; 0 without an ID
+    pub trial_id_hash: AtomicU64,
 
     // Dynamic trials fields
     pub frame_number: AtomicU64,
     pub elapsed_secs: AtomicU32,
+    /// Orbit radius, or the orthographic scale when `camera_radius_is_scale` is set
     pub camera_radius: AtomicU32,
+    pub camera_radius_is_scale: AtomicBool,
     pub camera_x: AtomicU32,
     pub camera_y: AtomicU32,
     pub camera_z: AtomicU32,
+    /// Pyramid yaw from the controls (radians, f32 bits, within [-PI, PI)), without the global yaw offset
+    pub pyramid_yaw: AtomicU32,
     pub attempts: AtomicU32,
+    /// Checks ignored because the screen was blanked (see `suppress_blanked_attempts`)
+    pub rejected_checks: AtomicU32,
     pub current_alignment: AtomicU32,
     pub current_angle: AtomicU32,
+    /// Door the camera currently faces most directly, updated every frame
+    pub best_door: AtomicU32,
+    /// Whether the live feedback bar is currently shown (hidden during animations and blanking)
+    pub live_feedback_visible: AtomicBool,
     pub is_animating: AtomicBool,
     pub win_time: AtomicU32,
+    /// `door_reward_weights` entry of the door the round was won at (f32 bits), 0 until a win
+    pub reward_weight: AtomicU32,
+    /// Orbit radius at the last check (f32 bits), and whether an aligned check missed the radius band
+    pub radius_at_check: AtomicU32,
+    pub win_blocked_by_radius: AtomicBool,
+    /// Turning direction detected from the recent `pyramid_yaw` (-1 decreasing, 1 increasing, 0 still),
+    /// updated every frame, and whether an aligned check missed `required_approach_sign`
+    pub approach_sign: AtomicI32,
+    pub win_blocked_by_approach: AtomicBool,
+    /// Whether the last check came before `min_view_secs` and so could not win
+    pub check_too_early: AtomicBool,
+    /// Whether a decided check is waiting out `feedback_delay_ms` before its feedback starts
+    pub feedback_pending: AtomicBool,
+    /// Whether rotation and zoom commands are ignored, from a decided check through its feedback
+    /// and the inter-trial blank. Not copied by reset_all_fields, like the phase
+    pub inputs_locked: AtomicBool,
+    /// Phase as u32, and the fixed ticks left in the inter-trial interval. Not copied by
+    /// reset_all_fields, since the interval spans the reset into the next round
+    pub phase: AtomicU32,
+    pub iti_remaining_frames: AtomicU32,
+    /// Per-face flicker phase in cycles [0, 1), f32 bits
+    pub flicker_phase: [AtomicU32; 3],
+    /// Seconds the camera has dwelt near the target door, f32 bits
+    pub shaping_dwell_elapsed: AtomicU32,
+    /// Hash of the realized distractor layout (positions, sizes, shapes) for stimulus reconstruction
+    pub distractor_hash: AtomicU64,
+    /// Projection actually in use (f32 bits) and the window size in physical pixels, for visual-angle conversion
+    pub active_fov_degrees: AtomicU32,
+    pub active_near: AtomicU32,
+    pub active_far: AtomicU32,
+    pub window_width: AtomicU32,
+    pub window_height: AtomicU32,
+    /// Whether the window presents with vsync (set at launch, see the game_node CLI)
+    pub vsync: AtomicBool,
+    /// Incremented by the game once a reset command has been applied and its first state is emitted.
+    /// Not copied by reset_all_fields, so it keeps counting across rounds
+    pub reset_ack: AtomicU32,
+    /// Trial generation: incremented once a newly set up trial starts playing, which is the frame after
+    /// its reset_ack for a trial loaded after the previous one ended (and after its interval if it is
+    /// still blank). Controllers read it before a reset and time the trial once it has moved on. Not
+    /// copied either
+    pub trial_generation: AtomicU32,
+    /// Incremented by the game once a clear_attempts command has been applied. Not copied either
+    pub clear_attempts_ack: AtomicU32,
+    /// Latched rotation (-1 left, 1 right) and zoom (-1 in, 1 out) direction, 0 when stopped.
+    /// Not copied by reset_all_fields, latched motion is controller state that spans rounds
+    pub latched_rotation: AtomicI32,
+    pub latched_zoom: AtomicI32,
+    /// Experimenter override activations and the actions taken in it (keyboard in the game window),
+    /// so sessions with manual intervention can be flagged. Not copied either, they count per session
+    pub override_activations: AtomicU32,
+    pub override_actions: AtomicU32,
+    /// Fixed updates per second of real time over the last FIXED_RATE_WINDOW_SECS (f32 bits),
+    /// 0 until the first window has passed. Not copied, it is a running measurement
+    pub measured_hz: AtomicU32,
+    /// Refresh rate of the game's monitor (f32 bits), 0 until detected and in headless runs. Under
+    /// vsync frame numbers advance once per displayed frame, so they count at this rate. Not copied
+    pub display_hz: AtomicU32,
+    /// Active fixed update rate (f32 bits): REFRESH_RATE_HZ, or display_hz with --match-display. Not copied
+    pub fixed_hz: AtomicU32,
+    /// Rendered frames of the current trial longer than FRAME_LATE_FACTOR and FRAME_DROP_FACTOR
+    /// display periods, and its longest frame (ms, f32 bits). Not copied, they start over at reset
+    pub late_frames_trial: AtomicU32,
+    pub dropped_frames_trial: AtomicU32,
+    pub worst_frame_ms: AtomicU32,
+    /// RenderQuality code the game renders at. Not copied, it is switched live and spans rounds
+    pub render_quality: AtomicU32,
+    /// Global yaw offset (radians, f32 bits) applied to the live pyramid. Not copied, the
+    /// controller holds it across rounds
+    pub global_yaw_offset: AtomicU32,
+    /// Faces and doors of the pyramid actually spawned, i.e. the targets the alignment fields refer
+    /// to, updated every frame (0 without a pyramid). Not copied, they describe the live scene
+    pub num_faces: AtomicU32,
+    pub num_doors: AtomicU32,
+    /// Whether the controller watchdog currently counts the controller as lost, and the outages so
+    /// far. Not copied, they describe the controller link over the session
+    pub controller_lost: AtomicBool,
+    pub controller_outages: AtomicU32,
+    /// Bumped by the game every frame, also while paused (unlike `frame_number`), so a controller can
+    /// tell a game that stopped or restarted (it starts over from 0). Not copied
+    pub game_heartbeat: AtomicU32,
+    /// Decorations actually placed per face (over its two triangles), fewer than requested when
+    /// placement ran out of room. Not copied, written by the game at every reset
+    pub decorations_placed: [AtomicU32; 3],
+    /// Reference image shown (index into REFERENCE_IMAGES), HIDE_IMAGE while none. Not copied
+    pub reference_image: AtomicU32,
+    /// Hash of the trial's complete stimulus description (see game_node's stimulus.rs), written
+    /// with the round. Not copied
+    pub stimulus_hash: AtomicU64,
+    /// StimulusFileStatus of the round's stimulus file. Not copied, written by the game at every reset
+    pub stimulus_file_status: AtomicU32,
+    /// Whether the browser tab is hidden (the game pauses meanwhile, see game_node's web_adapter.rs),
+    /// and the seconds it was hidden during the current trial (f32 bits). Not copied; always unset
+    /// outside the browser build
+    pub tab_hidden: AtomicBool,
+    pub tab_hidden_secs_trial: AtomicU32,
+    /// Whether a browser gamepad is connected and driving the camera. Not copied; always unset
+    /// outside the browser build
+    pub gamepad_connected: AtomicBool,
+    /// Check requests (`SharedCommands::check_requests`) the game never acted on, over the session.
+    /// Not copied
+    pub dropped_checks: AtomicU32,
+    /// Trigger activations rejected inside their `debounce_frames` window, over the session. Not copied
+    pub debounced_count: AtomicU32,
+    /// Checks refused in the Standby phase, before any trial was loaded. Not copied
+    pub standby_checks: AtomicU32,
+    /// SeenCommand bits of the commands the game read set on its latest frame. Not copied
+    pub commands_seen: AtomicU32,
+    /// Per SeenCommand, the frames the game read it set since the region was created: the times
+    /// a trigger was consumed, the frames a held flag was held. Not copied
+    pub commands_seen_counts: [AtomicU32; SEEN_COMMAND_COUNT],
+    /// Seconds the decoration motion has run in the current trial (f32 bits): it stops while paused
+    /// or blanked, and each decoration's pose follows from it and its phase. Not copied
+    pub decoration_motion_secs: AtomicU32,
+    /// Session context, written by the controller at any time (e.g. "trial 37/200, block 2") in the
+    /// control region and echoed into the game region every frame, for the logs and the HUD. The
+    /// game never acts on it. Not copied by reset_all_fields, it spans rounds
+    pub current_trial_index: AtomicU32,
+    pub total_trials: AtomicU32,
+    pub block_index: AtomicU32,
+    pub session_id: AtomicU64,
+    pub subject_id: AtomicU32,
 }
 
 impl SharedGameStructure {
@@ -125,18 +1255,83 @@ impl SharedGameStructure {
         use constants::{
             game_constants::{
                 DECORATION_SEEDS,
-                COSINE_ALIGNMENT_TO_WIN},
+                COSINE_ALIGNMENT_TO_WIN,
+                SHAPING_DWELL_SECS,
+                SUPPRESS_BLANKED_ATTEMPTS,
+                IS_CATCH,
+                MAX_ATTEMPTS,
+                TRIAL_TIMEOUT_SECS,
+                WIN_RADIUS_MIN,
+                WIN_RADIUS_MAX,
+                REQUIRED_APPROACH_SIGN,
+                MIN_VIEW_SECS,
+                FEEDBACK_DELAY_MS,
+                DEBOUNCE_FRAMES,
+                UI_SCALE,
+                SCORE_BAR_ANCHOR,
+                SCORE_BAR_WIDTH_PERCENT,
+                SCORE_BAR_HEIGHT,
+                SCORE_BAR_SHOW_NUMERIC,
+                RETICLE_ENABLED,
+                RETICLE_SIZE,
+                RETICLE_COLOR,
+                RETICLE_STYLE,
+                RETICLE_ALIGNMENT_TINT,
+                LIVE_FEEDBACK_ENABLED,
+                LIVE_FEEDBACK_GAIN,
+                LIVE_FEEDBACK_SMOOTHING},
+            timing::{
+                ITI_FRAMES,
+                POST_WIN_MODE,
+                PAUSE_MODE,
+            },
             pyramid_constants::{
                 PYRAMID_BASE_RADIUS,
                 PYRAMID_HEIGHT,
                 PYRAMID_START_ANGLE_OFFSET_RAD,
+                CONFIG_MODE,
+                GEOMETRY_SEED,
+                PYRAMID_BASE_RADIUS_RANGE,
+                PYRAMID_HEIGHT_RANGE,
+                PYRAMID_START_ORIENT_RANGE,
                 PYRAMID_TARGET_DOOR_INDEX,
+                PYRAMID_TYPE,
+                TARGET_KIND,
+                PYRAMID_TARGET_FACE_INDEX,
                 PYRAMID_COLORS,
+                COLOR_SPACE,
                 PYRAMID_DECORATIONS_COUNT,
+                PYRAMID_DECORATIONS_COUNT_RANGE,
                 PYRAMID_DECORATIONS_SIZE,
+                PYRAMID_DECORATIONS_SPACING,
+                PYRAMID_DECORATION_SPIN_RATE,
+                PYRAMID_DECORATION_MOTION,
+                PYRAMID_DECORATION_PULSE_AMPLITUDE,
+                PYRAMID_DECORATION_PULSE_HZ,
+                PYRAMID_DECORATION_SYMMETRY,
+                PYRAMID_DECORATION_COLORS,
+                PYRAMID_DECORATION_COLOR_JITTER,
+                PYRAMID_OUTLINE_WIDTH,
+                PYRAMID_OUTLINE_COLOR,
+                PYRAMID_FLICKER_HZ,
+                PYRAMID_FLICKER_DEPTH,
+                DOOR_HOLE_RADIUS_FRACTION,
+                DOOR_FRAME_THICKNESS,
+                DOOR_INSET,
+                DOOR_REWARD_WEIGHTS,
                 DOOR_ANIM_FADE_IN,
                 DOOR_ANIM_FADE_OUT,
-                DOOR_ANIM_STAY_OPEN
+                DOOR_ANIM_STAY_OPEN,
+                DOOR_ANIM_EASING
+            },
+            object_constants::{
+                DISTRACTOR_COUNT,
+                DISTRACTOR_SHAPE,
+                DISTRACTOR_SIZE_RANGE,
+                DISTRACTOR_COLOR,
+                DISTRACTOR_RING,
+                DISTRACTOR_SEED,
+                BACKDROP_ENABLED,
             },
             lighting_constants::{
                 SPOTLIGHT_LIGHT_INTENSITY,
@@ -147,6 +1342,15 @@ impl SharedGameStructure {
                 CAMERA_3D_INITIAL_Y,
                 CAMERA_3D_INITIAL_Z,
                 CAMERA_3D_INITIAL_RADIUS,
+                CONTROL_MIRROR_ROTATION,
+                CONTROL_MIRROR_ZOOM,
+                MIRROR_X,
+                RECENTER_ON_RESET,
+                CAMERA_3D_FOV_DEGREES,
+                CAMERA_3D_NEAR,
+                CAMERA_3D_FAR,
+                CAMERA_3D_PROJECTION_MODE,
+                CAMERA_3D_ORTHO_SCALE_RANGE,
             }
 
         };
@@ -161,47 +1365,294 @@ impl SharedGameStructure {
             base_radius: AtomicU32::new(PYRAMID_BASE_RADIUS.to_bits()),
             height: AtomicU32::new(PYRAMID_HEIGHT.to_bits()),
             start_orient: AtomicU32::new(PYRAMID_START_ANGLE_OFFSET_RAD.to_bits()),
+            config_mode: AtomicU32::new(CONFIG_MODE),
+            geometry_seed: AtomicU64::new(GEOMETRY_SEED),
+            base_radius_range: [
+                AtomicU32::new(PYRAMID_BASE_RADIUS_RANGE[0].to_bits()),
+                AtomicU32::new(PYRAMID_BASE_RADIUS_RANGE[1].to_bits()),
+            ],
+            height_range: [AtomicU32::new(PYRAMID_HEIGHT_RANGE[0].to_bits()), AtomicU32::new(PYRAMID_HEIGHT_RANGE[1].to_bits())],
+            start_orient_range: [
+                AtomicU32::new(PYRAMID_START_ORIENT_RANGE[0].to_bits()),
+                AtomicU32::new(PYRAMID_START_ORIENT_RANGE[1].to_bits()),
+            ],
             target_door: AtomicU32::new(PYRAMID_TARGET_DOOR_INDEX as u32),
+            pyramid_type: AtomicU32::new(PYRAMID_TYPE),
+            target_kind: AtomicU32::new(TARGET_KIND),
+            target_face: AtomicU32::new(PYRAMID_TARGET_FACE_INDEX),
             colors: [
                 AtomicU32::new(PYRAMID_COLORS[0][0].to_bits()), AtomicU32::new(PYRAMID_COLORS[0][1].to_bits()), AtomicU32::new(PYRAMID_COLORS[0][2].to_bits()), AtomicU32::new(PYRAMID_COLORS[0][3].to_bits()),
                 AtomicU32::new(PYRAMID_COLORS[1][0].to_bits()), AtomicU32::new(PYRAMID_COLORS[1][1].to_bits()), AtomicU32::new(PYRAMID_COLORS[1][2].to_bits()), AtomicU32::new(PYRAMID_COLORS[1][3].to_bits()),
                 AtomicU32::new(PYRAMID_COLORS[2][0].to_bits()), AtomicU32::new(PYRAMID_COLORS[2][1].to_bits()), AtomicU32::new(PYRAMID_COLORS[2][2].to_bits()), AtomicU32::new(PYRAMID_COLORS[2][3].to_bits()),
             ],
+            color_space: AtomicU32::new(COLOR_SPACE),
 
             decorations_count: [
                 AtomicU32::new(PYRAMID_DECORATIONS_COUNT[0]),
                 AtomicU32::new(PYRAMID_DECORATIONS_COUNT[1]),
                 AtomicU32::new(PYRAMID_DECORATIONS_COUNT[2]),
             ],
+            decorations_count_range: [
+                AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[0][0]), AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[0][1]),
+                AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[1][0]), AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[1][1]),
+                AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[2][0]), AtomicU32::new(PYRAMID_DECORATIONS_COUNT_RANGE[2][1]),
+            ],
             
             decorations_size: [
                 AtomicU32::new(PYRAMID_DECORATIONS_SIZE[0].to_bits()),
                 AtomicU32::new(PYRAMID_DECORATIONS_SIZE[1].to_bits()),
                 AtomicU32::new(PYRAMID_DECORATIONS_SIZE[2].to_bits()),
             ],
+            decoration_spacing: AtomicU32::new(PYRAMID_DECORATIONS_SPACING.to_bits()),
+            decoration_spin_rate: AtomicU32::new(PYRAMID_DECORATION_SPIN_RATE.to_bits()),
+            decoration_motion: AtomicU32::new(PYRAMID_DECORATION_MOTION),
+            decoration_pulse_amplitude: AtomicU32::new(PYRAMID_DECORATION_PULSE_AMPLITUDE.to_bits()),
+            decoration_pulse_hz: AtomicU32::new(PYRAMID_DECORATION_PULSE_HZ.to_bits()),
+            decoration_symmetry: AtomicBool::new(PYRAMID_DECORATION_SYMMETRY),
+            decoration_colors: [
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[0][0].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[0][1].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[0][2].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[0][3].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[1][0].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[1][1].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[1][2].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[1][3].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[2][0].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[2][1].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[2][2].to_bits()),
+                AtomicU32::new(PYRAMID_DECORATION_COLORS[2][3].to_bits()),
+            ],
+            decoration_color_jitter: AtomicU32::new(PYRAMID_DECORATION_COLOR_JITTER.to_bits()),
+            outline_width: AtomicU32::new(PYRAMID_OUTLINE_WIDTH.to_bits()),
+            outline_color: [
+                AtomicU32::new(PYRAMID_OUTLINE_COLOR[0].to_bits()),
+                AtomicU32::new(PYRAMID_OUTLINE_COLOR[1].to_bits()),
+                AtomicU32::new(PYRAMID_OUTLINE_COLOR[2].to_bits()),
+                AtomicU32::new(PYRAMID_OUTLINE_COLOR[3].to_bits()),
+            ],
+
+            door_hole_radius_fraction: AtomicU32::new(DOOR_HOLE_RADIUS_FRACTION.to_bits()),
+            door_frame_thickness: AtomicU32::new(DOOR_FRAME_THICKNESS.to_bits()),
+            door_inset: AtomicU32::new(DOOR_INSET.to_bits()),
+            door_reward_weights: [
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[0].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[1].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[2].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[3].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[4].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[5].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[6].to_bits()),
+                AtomicU32::new(DOOR_REWARD_WEIGHTS[7].to_bits()),
+            ],
+
+            flicker_hz: [
+                AtomicU32::new(PYRAMID_FLICKER_HZ[0].to_bits()),
+                AtomicU32::new(PYRAMID_FLICKER_HZ[1].to_bits()),
+                AtomicU32::new(PYRAMID_FLICKER_HZ[2].to_bits()),
+            ],
+            flicker_depth: [
+                AtomicU32::new(PYRAMID_FLICKER_DEPTH[0].to_bits()),
+                AtomicU32::new(PYRAMID_FLICKER_DEPTH[1].to_bits()),
+                AtomicU32::new(PYRAMID_FLICKER_DEPTH[2].to_bits()),
+            ],
 
             cosine_alignment_threshold: AtomicU32::new(COSINE_ALIGNMENT_TO_WIN.to_bits()), // 0.9 approx
+            shaping_dwell_secs: AtomicU32::new(SHAPING_DWELL_SECS.to_bits()),
+            is_catch: AtomicBool::new(IS_CATCH),
+            max_attempts: AtomicU32::new(MAX_ATTEMPTS),
+            trial_timeout_secs: AtomicU32::new(TRIAL_TIMEOUT_SECS.to_bits()),
+            suppress_blanked_attempts: AtomicBool::new(SUPPRESS_BLANKED_ATTEMPTS),
+            win_radius_min: AtomicU32::new(WIN_RADIUS_MIN.to_bits()),
+            win_radius_max: AtomicU32::new(WIN_RADIUS_MAX.to_bits()),
+            required_approach_sign: AtomicI32::new(REQUIRED_APPROACH_SIGN),
+            min_view_secs: AtomicU32::new(MIN_VIEW_SECS.to_bits()),
+            feedback_delay_ms: AtomicU32::new(FEEDBACK_DELAY_MS),
+            debounce_frames: [
+                AtomicU32::new(DEBOUNCE_FRAMES[0]),
+                AtomicU32::new(DEBOUNCE_FRAMES[1]),
+                AtomicU32::new(DEBOUNCE_FRAMES[2]),
+            ],
+            iti_frames: AtomicU32::new(ITI_FRAMES),
+            post_win_mode: AtomicU32::new(POST_WIN_MODE),
+            pause_mode: AtomicU32::new(PAUSE_MODE),
             
             door_anim_fade_out: AtomicU32::new(DOOR_ANIM_FADE_OUT.to_bits()),
             door_anim_stay_open: AtomicU32::new(DOOR_ANIM_STAY_OPEN.to_bits()),
             door_anim_fade_in: AtomicU32::new(DOOR_ANIM_FADE_IN.to_bits()),
+            door_anim_easing: AtomicU32::new(DOOR_ANIM_EASING),
             
             main_spotlight_intensity: AtomicU32::new(SPOTLIGHT_LIGHT_INTENSITY.to_bits()),
             ambient_brightness: AtomicU32::new(GLOBAL_AMBIENT_LIGHT_INTENSITY.to_bits()),
             max_spotlight_intensity: AtomicU32::new(constants::lighting_constants::MAX_SPOTLIGHT_INTENSITY.to_bits()),
+            spotlight_outer_angle: AtomicU32::new(constants::lighting_constants::SPOTLIGHT_OUTER_ANGLE.to_bits()),
+            spotlight_range: AtomicU32::new(constants::lighting_constants::SPOTLIGHT_RANGE.to_bits()),
+            win_glow_color: [
+                AtomicU32::new(constants::lighting_constants::WIN_GLOW_COLOR[0].to_bits()),
+                AtomicU32::new(constants::lighting_constants::WIN_GLOW_COLOR[1].to_bits()),
+                AtomicU32::new(constants::lighting_constants::WIN_GLOW_COLOR[2].to_bits()),
+                AtomicU32::new(constants::lighting_constants::WIN_GLOW_COLOR[3].to_bits()),
+            ],
+            fog_enabled: AtomicBool::new(constants::lighting_constants::FOG_ENABLED),
+            fog_color: [
+                AtomicU32::new(constants::lighting_constants::FOG_COLOR[0].to_bits()),
+                AtomicU32::new(constants::lighting_constants::FOG_COLOR[1].to_bits()),
+                AtomicU32::new(constants::lighting_constants::FOG_COLOR[2].to_bits()),
+                AtomicU32::new(constants::lighting_constants::FOG_COLOR[3].to_bits()),
+            ],
+            fog_start: AtomicU32::new(constants::lighting_constants::FOG_START.to_bits()),
+            fog_end: AtomicU32::new(constants::lighting_constants::FOG_END.to_bits()),
+
+            ui_scale: AtomicU32::new(UI_SCALE.to_bits()),
+            score_bar_anchor: AtomicU32::new(SCORE_BAR_ANCHOR),
+            score_bar_width_percent: AtomicU32::new(SCORE_BAR_WIDTH_PERCENT.to_bits()),
+            score_bar_height: AtomicU32::new(SCORE_BAR_HEIGHT.to_bits()),
+            score_bar_show_numeric: AtomicBool::new(SCORE_BAR_SHOW_NUMERIC),
+            reticle_enabled: AtomicBool::new(RETICLE_ENABLED),
+            reticle_size: AtomicU32::new(RETICLE_SIZE.to_bits()),
+            reticle_color: [
+                AtomicU32::new(RETICLE_COLOR[0].to_bits()),
+                AtomicU32::new(RETICLE_COLOR[1].to_bits()),
+                AtomicU32::new(RETICLE_COLOR[2].to_bits()),
+                AtomicU32::new(RETICLE_COLOR[3].to_bits()),
+            ],
+            reticle_style: AtomicU32::new(RETICLE_STYLE),
+            reticle_alignment_tint: AtomicBool::new(RETICLE_ALIGNMENT_TINT),
+            live_feedback_enabled: AtomicBool::new(LIVE_FEEDBACK_ENABLED),
+            live_feedback_gain: AtomicU32::new(LIVE_FEEDBACK_GAIN.to_bits()),
+            live_feedback_smoothing: AtomicU32::new(LIVE_FEEDBACK_SMOOTHING.to_bits()),
+
+            control_mirror_rotation: AtomicBool::new(CONTROL_MIRROR_ROTATION),
+            control_mirror_zoom: AtomicBool::new(CONTROL_MIRROR_ZOOM),
+            mirror_x: AtomicBool::new(MIRROR_X),
+            recenter_on_reset: AtomicBool::new(RECENTER_ON_RESET),
+
+            camera_fov_degrees: AtomicU32::new(CAMERA_3D_FOV_DEGREES.to_bits()),
+            camera_near: AtomicU32::new(CAMERA_3D_NEAR.to_bits()),
+            camera_far: AtomicU32::new(CAMERA_3D_FAR.to_bits()),
+            projection_mode: AtomicU32::new(CAMERA_3D_PROJECTION_MODE),
+            ortho_scale_range: [
+                AtomicU32::new(CAMERA_3D_ORTHO_SCALE_RANGE[0].to_bits()),
+                AtomicU32::new(CAMERA_3D_ORTHO_SCALE_RANGE[1].to_bits()),
+            ],
+
+            distractor_count: AtomicU32::new(DISTRACTOR_COUNT),
+            distractor_shape: AtomicU32::new(DISTRACTOR_SHAPE),
+            distractor_size_range: [
+                AtomicU32::new(DISTRACTOR_SIZE_RANGE[0].to_bits()),
+                AtomicU32::new(DISTRACTOR_SIZE_RANGE[1].to_bits()),
+            ],
+            distractor_color: [
+                AtomicU32::new(DISTRACTOR_COLOR[0].to_bits()),
+                AtomicU32::new(DISTRACTOR_COLOR[1].to_bits()),
+                AtomicU32::new(DISTRACTOR_COLOR[2].to_bits()),
+                AtomicU32::new(DISTRACTOR_COLOR[3].to_bits()),
+            ],
+            distractor_ring: [
+                AtomicU32::new(DISTRACTOR_RING[0].to_bits()),
+                AtomicU32::new(DISTRACTOR_RING[1].to_bits()),
+            ],
+            distractor_seed: AtomicU64::new(DISTRACTOR_SEED),
+
+            backdrop_enabled: AtomicBool::new(BACKDROP_ENABLED),
+            stimulus_file: [const { AtomicU8::new(0) }; constants::game_constants::STIMULUS_FILE_MAX_LEN],
+            stimulus_expected_hash: AtomicU64::new(0),
+            trial_id_hash: AtomicU64::new(0),
 
             // Dynamic trials fields
             frame_number: AtomicU64::new(0),
             elapsed_secs: AtomicU32::new(0),
             camera_radius: AtomicU32::new(CAMERA_3D_INITIAL_RADIUS.to_bits()),
+            camera_radius_is_scale: AtomicBool::new(false),
             camera_x: AtomicU32::new(CAMERA_3D_INITIAL_X.to_bits()),
             camera_y: AtomicU32::new(CAMERA_3D_INITIAL_Y.to_bits()),
             camera_z: AtomicU32::new(CAMERA_3D_INITIAL_Z.to_bits()),
+            pyramid_yaw: AtomicU32::new(0),
             attempts: AtomicU32::new(0),
+            rejected_checks: AtomicU32::new(0),
             current_alignment: AtomicU32::new(f32::to_bits(0.0)),
             current_angle: AtomicU32::new(0),
+            best_door: AtomicU32::new(0),
+            live_feedback_visible: AtomicBool::new(false),
             is_animating: AtomicBool::new(false),
             win_time: AtomicU32::new(0),
+            reward_weight: AtomicU32::new(0),
+            radius_at_check: AtomicU32::new(0),
+            win_blocked_by_radius: AtomicBool::new(false),
+            approach_sign: AtomicI32::new(0),
+            win_blocked_by_approach: AtomicBool::new(false),
+            check_too_early: AtomicBool::new(false),
+            feedback_pending: AtomicBool::new(false),
+            inputs_locked: AtomicBool::new(false),
+            phase: AtomicU32::new(Phase::Standby as u32),
+            iti_remaining_frames: AtomicU32::new(0),
+            flicker_phase: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
+            shaping_dwell_elapsed: AtomicU32::new(0),
+            distractor_hash: AtomicU64::new(0),
+            active_fov_degrees: AtomicU32::new(0),
+            active_near: AtomicU32::new(0),
+            active_far: AtomicU32::new(0),
+            window_width: AtomicU32::new(0),
+            window_height: AtomicU32::new(0),
+            vsync: AtomicBool::new(false),
+            reset_ack: AtomicU32::new(0),
+            trial_generation: AtomicU32::new(0),
+            clear_attempts_ack: AtomicU32::new(0),
+            latched_rotation: AtomicI32::new(0),
+            latched_zoom: AtomicI32::new(0),
+            override_activations: AtomicU32::new(0),
+            override_actions: AtomicU32::new(0),
+            measured_hz: AtomicU32::new(0),
+            display_hz: AtomicU32::new(0),
+            fixed_hz: AtomicU32::new(0),
+            late_frames_trial: AtomicU32::new(0),
+            dropped_frames_trial: AtomicU32::new(0),
+            worst_frame_ms: AtomicU32::new(0),
+            render_quality: AtomicU32::new(RenderQuality::High as u32),
+            global_yaw_offset: AtomicU32::new(0),
+            num_faces: AtomicU32::new(0),
+            num_doors: AtomicU32::new(0),
+            controller_lost: AtomicBool::new(false),
+            controller_outages: AtomicU32::new(0),
+            game_heartbeat: AtomicU32::new(0),
+            decorations_placed: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
+            reference_image: AtomicU32::new(HIDE_IMAGE),
+            stimulus_hash: AtomicU64::new(0),
+            stimulus_file_status: AtomicU32::new(StimulusFileStatus::Procedural as u32),
+            tab_hidden: AtomicBool::new(false),
+            tab_hidden_secs_trial: AtomicU32::new(0),
+            gamepad_connected: AtomicBool::new(false),
+            dropped_checks: AtomicU32::new(0),
+            debounced_count: AtomicU32::new(0),
+            standby_checks: AtomicU32::new(0),
+            commands_seen: AtomicU32::new(0),
+            commands_seen_counts: [const { AtomicU32::new(0) }; SEEN_COMMAND_COUNT],
+            decoration_motion_secs: AtomicU32::new(0),
+            current_trial_index: AtomicU32::new(0),
+            total_trials: AtomicU32::new(0),
+            block_index: AtomicU32::new(0),
+            session_id: AtomicU64::new(0),
+            subject_id: AtomicU32::new(0),
+        }
+    }
+
+    /// Stimulus description to replay, None for a procedural pyramid
+    pub fn stimulus_file(&self) -> Option<String> {
+        let bytes: Vec<u8> = self
+            .stimulus_file
+            .iter()
+            .map(|byte| byte.load(Ordering::Relaxed))
+            .take_while(|&byte| byte != 0)
+            .collect();
+        (!bytes.is_empty()).then(|| String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Stores the stimulus description to replay (empty for none), checked with validate_stimulus_file.
+    /// Bytes past STIMULUS_FILE_MAX_LEN are dropped
+    pub fn set_stimulus_file(&self, path: &str) {
+        let mut bytes = path.bytes();
+        for byte in &self.stimulus_file {
+            byte.store(bytes.next().unwrap_or(0), Ordering::Relaxed);
         }
     }
 
@@ -212,34 +1663,167 @@ impl SharedGameStructure {
         self.base_radius.store(other.base_radius.load(Ordering::Relaxed), Ordering::Relaxed);
         self.height.store(other.height.load(Ordering::Relaxed), Ordering::Relaxed);
         self.start_orient.store(other.start_orient.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.config_mode.store(other.config_mode.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.geometry_seed.store(other.geometry_seed.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..2 {
+            self.base_radius_range[i].store(other.base_radius_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+            self.height_range[i].store(other.height_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+            self.start_orient_range[i].store(other.start_orient_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
         self.target_door.store(other.target_door.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.pyramid_type.store(other.pyramid_type.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.target_kind.store(other.target_kind.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.target_face.store(other.target_face.load(Ordering::Relaxed), Ordering::Relaxed);
         for i in 0..12 {
             self.colors[i].store(other.colors[i].load(Ordering::Relaxed), Ordering::Relaxed);
         }
+        self.color_space.store(other.color_space.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..6 {
+            self.decorations_count_range[i].store(other.decorations_count_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
         for i in 0..3 {
             self.decorations_count[i].store(other.decorations_count[i].load(Ordering::Relaxed), Ordering::Relaxed);
             self.decorations_size[i].store(other.decorations_size[i].load(Ordering::Relaxed), Ordering::Relaxed);
+            self.flicker_hz[i].store(other.flicker_hz[i].load(Ordering::Relaxed), Ordering::Relaxed);
+            self.flicker_depth[i].store(other.flicker_depth[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.decoration_spacing.store(other.decoration_spacing.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.decoration_spin_rate.store(other.decoration_spin_rate.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.decoration_motion.store(other.decoration_motion.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.decoration_pulse_amplitude.store(other.decoration_pulse_amplitude.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.decoration_pulse_hz.store(other.decoration_pulse_hz.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.decoration_symmetry.store(other.decoration_symmetry.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..12 {
+            self.decoration_colors[i].store(other.decoration_colors[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.decoration_color_jitter.store(other.decoration_color_jitter.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.outline_width.store(other.outline_width.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..4 {
+            self.outline_color[i].store(other.outline_color[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.door_hole_radius_fraction.store(other.door_hole_radius_fraction.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.door_frame_thickness.store(other.door_frame_thickness.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.door_inset.store(other.door_inset.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..8 {
+            self.door_reward_weights[i].store(other.door_reward_weights[i].load(Ordering::Relaxed), Ordering::Relaxed);
         }
         self.cosine_alignment_threshold.store(other.cosine_alignment_threshold.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.shaping_dwell_secs.store(other.shaping_dwell_secs.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.is_catch.store(other.is_catch.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.max_attempts.store(other.max_attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.trial_timeout_secs.store(other.trial_timeout_secs.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.suppress_blanked_attempts.store(other.suppress_blanked_attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.win_radius_min.store(other.win_radius_min.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.win_radius_max.store(other.win_radius_max.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.required_approach_sign.store(other.required_approach_sign.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.min_view_secs.store(other.min_view_secs.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.feedback_delay_ms.store(other.feedback_delay_ms.load(Ordering::Relaxed), Ordering::Relaxed);
+        for (frames, other) in self.debounce_frames.iter().zip(&other.debounce_frames) {
+            frames.store(other.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.iti_frames.store(other.iti_frames.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.post_win_mode.store(other.post_win_mode.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.pause_mode.store(other.pause_mode.load(Ordering::Relaxed), Ordering::Relaxed);
         self.door_anim_fade_out.store(other.door_anim_fade_out.load(Ordering::Relaxed), Ordering::Relaxed);
         self.door_anim_stay_open.store(other.door_anim_stay_open.load(Ordering::Relaxed), Ordering::Relaxed);
         self.door_anim_fade_in.store(other.door_anim_fade_in.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.door_anim_easing.store(other.door_anim_easing.load(Ordering::Relaxed), Ordering::Relaxed);
         
         self.main_spotlight_intensity.store(other.main_spotlight_intensity.load(Ordering::Relaxed), Ordering::Relaxed);
         self.ambient_brightness.store(other.ambient_brightness.load(Ordering::Relaxed), Ordering::Relaxed);
         self.max_spotlight_intensity.store(other.max_spotlight_intensity.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.spotlight_outer_angle.store(other.spotlight_outer_angle.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.spotlight_range.store(other.spotlight_range.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..4 {
+            self.win_glow_color[i].store(other.win_glow_color[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.fog_enabled.store(other.fog_enabled.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..4 {
+            self.fog_color[i].store(other.fog_color[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.fog_start.store(other.fog_start.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.fog_end.store(other.fog_end.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        self.ui_scale.store(other.ui_scale.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.score_bar_anchor.store(other.score_bar_anchor.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.score_bar_width_percent.store(other.score_bar_width_percent.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.score_bar_height.store(other.score_bar_height.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.score_bar_show_numeric.store(other.score_bar_show_numeric.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.reticle_enabled.store(other.reticle_enabled.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.reticle_size.store(other.reticle_size.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..4 {
+            self.reticle_color[i].store(other.reticle_color[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.reticle_style.store(other.reticle_style.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.reticle_alignment_tint.store(other.reticle_alignment_tint.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.live_feedback_enabled.store(other.live_feedback_enabled.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.live_feedback_gain.store(other.live_feedback_gain.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.live_feedback_smoothing.store(other.live_feedback_smoothing.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        self.control_mirror_rotation.store(other.control_mirror_rotation.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.control_mirror_zoom.store(other.control_mirror_zoom.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.mirror_x.store(other.mirror_x.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.recenter_on_reset.store(other.recenter_on_reset.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        self.camera_fov_degrees.store(other.camera_fov_degrees.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.camera_near.store(other.camera_near.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.camera_far.store(other.camera_far.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.projection_mode.store(other.projection_mode.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..2 {
+            self.ortho_scale_range[i].store(other.ortho_scale_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+
+        self.distractor_count.store(other.distractor_count.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.distractor_shape.store(other.distractor_shape.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..2 {
+            self.distractor_size_range[i].store(other.distractor_size_range[i].load(Ordering::Relaxed), Ordering::Relaxed);
+            self.distractor_ring[i].store(other.distractor_ring[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        for i in 0..4 {
+            self.distractor_color[i].store(other.distractor_color[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.distractor_seed.store(other.distractor_seed.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        self.backdrop_enabled.store(other.backdrop_enabled.load(Ordering::Relaxed), Ordering::Relaxed);
+        for (byte, other_byte) in self.stimulus_file.iter().zip(&other.stimulus_file) {
+            byte.store(other_byte.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.stimulus_expected_hash.store(other.stimulus_expected_hash.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.trial_id_hash.store(other.trial_id_hash.load(Ordering::Relaxed), Ordering::Relaxed);
 
         self.frame_number.store(other.frame_number.load(Ordering::Relaxed), Ordering::Relaxed);
         self.elapsed_secs.store(other.elapsed_secs.load(Ordering::Relaxed), Ordering::Relaxed);
         self.camera_radius.store(other.camera_radius.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.camera_radius_is_scale.store(other.camera_radius_is_scale.load(Ordering::Relaxed), Ordering::Relaxed);
         self.camera_x.store(other.camera_x.load(Ordering::Relaxed), Ordering::Relaxed);
         self.camera_y.store(other.camera_y.load(Ordering::Relaxed), Ordering::Relaxed);
         self.camera_z.store(other.camera_z.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.pyramid_yaw.store(other.pyramid_yaw.load(Ordering::Relaxed), Ordering::Relaxed);
         self.attempts.store(other.attempts.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.rejected_checks.store(other.rejected_checks.load(Ordering::Relaxed), Ordering::Relaxed);
         self.current_alignment.store(other.current_alignment.load(Ordering::Relaxed), Ordering::Relaxed);
         self.current_angle.store(other.current_angle.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.best_door.store(other.best_door.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.live_feedback_visible.store(other.live_feedback_visible.load(Ordering::Relaxed), Ordering::Relaxed);
         self.is_animating.store(other.is_animating.load(Ordering::Relaxed), Ordering::Relaxed);
         self.win_time.store(other.win_time.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.reward_weight.store(other.reward_weight.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.radius_at_check.store(other.radius_at_check.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.win_blocked_by_radius.store(other.win_blocked_by_radius.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.win_blocked_by_approach.store(other.win_blocked_by_approach.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.check_too_early.store(other.check_too_early.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.feedback_pending.store(other.feedback_pending.load(Ordering::Relaxed), Ordering::Relaxed);
+        for i in 0..3 {
+            self.flicker_phase[i].store(other.flicker_phase[i].load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+        self.shaping_dwell_elapsed.store(other.shaping_dwell_elapsed.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.distractor_hash.store(other.distractor_hash.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.active_fov_degrees.store(other.active_fov_degrees.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.active_near.store(other.active_near.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.active_far.store(other.active_far.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.window_width.store(other.window_width.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.window_height.store(other.window_height.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.vsync.store(other.vsync.load(Ordering::Relaxed), Ordering::Relaxed);
     }
 
 }
@@ -255,7 +1839,13 @@ impl Default for SharedGameStructure {
 pub struct SharedMemory {
     pub commands: SharedCommands,
     pub game_structure_game: SharedGameStructure,
+    /// Config read by the game at reset
     pub game_structure_control: SharedGameStructure,
+    /// Next trial's config, written by the controller while the current trial runs and
+    /// promoted into `game_structure_control` by the game on `promote_staged`
+    pub game_structure_staged: SharedGameStructure,
+    /// Per-frame state published as a whole by the game, for consistent multi-field reads
+    pub state_snapshot: SharedStateSnapshot,
 }
 
 impl SharedMemory {
@@ -264,6 +1854,8 @@ impl SharedMemory {
             commands: SharedCommands::new(),
             game_structure_game: SharedGameStructure::new(),
             game_structure_control: SharedGameStructure::new(),
+            game_structure_staged: SharedGameStructure::new(),
+            state_snapshot: SharedStateSnapshot::new(),
         }
     }
 }
@@ -282,6 +1874,8 @@ cfg_if::cfg_if! {
         mod native;
         pub use native::*;
 
+        pub mod frame_clock;
+
         #[cfg(feature = "python")]
         pub mod python;
     } else {
@@ -1,7 +1,10 @@
 use crate::SharedMemory;
+use crate::snapshot::StateSnapshot;
 use std::fs::{OpenOptions};
 use std::io::Write;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// Wrapper for file-based shared memory on native platforms (UNIX).
 /// Location shared data structure: /data/local/tmp/monkey_shm_*
@@ -10,10 +13,15 @@ pub struct NativeSharedMemory {
     ptr: *mut SharedMemory,
 }
 
+/// File backing the region `name`
+pub fn shared_memory_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("monkey_shm_{}", name))
+}
+
 // Initialize shared memory region (by creating or opening existing)
 impl NativeSharedMemory {
     pub fn new(name: &str) -> std::io::Result<Self> {
-        let path = std::env::temp_dir().join(format!("monkey_shm_{}", name));
+        let path = shared_memory_path(name);
         let size = std::mem::size_of::<SharedMemory>();
         
         let mut file =  OpenOptions::new()
@@ -27,27 +35,49 @@ impl NativeSharedMemory {
         file.write_all(&zeroes)?;
         file.sync_all()?;
         
+        let ptr = Self::map(&file)?;
+        
+        unsafe {
+            std::ptr::write(ptr, SharedMemory::new());
+        }
+
+
+        Ok(Self {ptr})
+    }
+
+    /// Maps a region another process created, leaving its contents as they are
+    pub fn open(name: &str) -> std::io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(shared_memory_path(name))?;
+        let size = std::mem::size_of::<SharedMemory>() as u64;
+        let len = file.metadata()?.len();
+        if len != size {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("shared memory '{}' is {} bytes, expected {} (not created yet, or another build)", name, len, size),
+            ));
+        }
+        Ok(Self { ptr: Self::map(&file)? })
+    }
+
+    /// Maps the whole region of `file`, or the OS error when the kernel refuses
+    fn map(file: &std::fs::File) -> std::io::Result<*mut SharedMemory> {
         #[cfg(unix)]
-        let ptr = unsafe {
+        unsafe {
             use std::os::unix::io::AsRawFd;
             let fd = file.as_raw_fd();
             let ptr = libc::mmap(
                 std::ptr::null_mut(),
-                size,
+                std::mem::size_of::<SharedMemory>(),
                 libc::PROT_READ | libc::PROT_WRITE,
                 libc::MAP_SHARED,
                 fd,
                 0,
             );
-            ptr as *mut SharedMemory
-        };
-        
-        unsafe {
-            std::ptr::write(ptr, SharedMemory::new());
+            if ptr == libc::MAP_FAILED {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(ptr as *mut SharedMemory)
         }
-
-
-        Ok(Self {ptr})
     }
 
     pub fn get(&self) -> &SharedMemory {
@@ -57,6 +87,30 @@ impl NativeSharedMemory {
     pub fn get_mut(&mut self) -> &mut SharedMemory {
         unsafe { &mut *self.ptr }
     }
+
+    /// Latest state snapshot published by the game (see snapshot.rs), `None` before the first frame
+    pub fn read_snapshot(&self) -> Option<StateSnapshot> {
+        self.get().state_snapshot.read()
+    }
+
+    /// Spins until the game emits a frame after `last_frame` and returns its number, `None` once
+    /// `deadline` passes. The frame number restarts at 0 on every reset, so any other number is a
+    /// new frame, and so is any frame of a reset acknowledged during the wait, even one landing back
+    /// on `last_frame`.
+    pub fn wait_next_frame(&self, last_frame: u64, deadline: Option<Instant>) -> Option<u64> {
+        let gs = &self.get().game_structure_game;
+        let reset_ack = gs.reset_ack.load(Ordering::Acquire);
+        loop {
+            let frame = gs.frame_number.load(Ordering::Acquire);
+            if frame != last_frame || gs.reset_ack.load(Ordering::Acquire) != reset_ack {
+                return Some(frame);
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return None;
+            }
+            std::thread::yield_now();
+        }
+    }
 }
 
 // Drop the shared memory mapping
@@ -83,3 +137,77 @@ pub type SharedMemoryHandle = Arc<NativeSharedMemory>;
 pub fn create_shared_memory(name: &str) -> std::io::Result<SharedMemoryHandle> {
     Ok(Arc::new(NativeSharedMemory::new(name)?))
 }
+
+// Open shm created by another process (e.g. a running game) without resetting it
+pub fn open_shared_memory(name: &str) -> std::io::Result<SharedMemoryHandle> {
+    Ok(Arc::new(NativeSharedMemory::open(name)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Plays a game publishing `frames` from another thread, `(frame, reset)` per frame
+    fn play(handle: &SharedMemoryHandle, frames: Vec<(u64, bool)>) -> std::thread::JoinHandle<()> {
+        let handle = handle.clone();
+        std::thread::spawn(move || {
+            let gs = &handle.get().game_structure_game;
+            for (frame, reset) in frames {
+                std::thread::sleep(Duration::from_millis(5));
+                gs.frame_number.store(frame, Ordering::Release);
+                // Acknowledged once the reset's first frame is out, as the game does
+                if reset {
+                    gs.reset_ack.fetch_add(1, Ordering::Release);
+                }
+            }
+        })
+    }
+
+    fn deadline() -> Option<Instant> {
+        Some(Instant::now() + Duration::from_secs(2))
+    }
+
+    #[test]
+    fn next_frame_is_read_across_a_reset() {
+        let handle = create_shared_memory("test_wait_next_frame_reset").unwrap();
+        let gs = &handle.get().game_structure_game;
+        gs.frame_number.store(40, Ordering::Release);
+
+        // The reset restarts the count below the frame the reader last saw
+        let game = play(&handle, vec![(0, true), (1, false), (2, false)]);
+        let mut last = 40;
+        let mut read = Vec::new();
+        while let Some(frame) = handle.wait_next_frame(last, deadline()) {
+            read.push(frame);
+            last = frame;
+            if frame == 2 {
+                break;
+            }
+        }
+        game.join().unwrap();
+        assert_eq!(read.last(), Some(&2), "{read:?}");
+        assert_eq!(read.first(), Some(&0), "{read:?}");
+    }
+
+    #[test]
+    fn reset_back_onto_the_last_frame_is_a_new_frame() {
+        let handle = create_shared_memory("test_wait_next_frame_same_number").unwrap();
+        let gs = &handle.get().game_structure_game;
+
+        // Frame 0 of the old round, then frame 0 of the next one
+        let game = play(&handle, vec![(0, true)]);
+        assert_eq!(handle.wait_next_frame(0, deadline()), Some(0));
+        game.join().unwrap();
+        assert_eq!(gs.reset_ack.load(Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn times_out_while_the_frame_holds() {
+        let handle = create_shared_memory("test_wait_next_frame_timeout").unwrap();
+        handle.get().game_structure_game.frame_number.store(7, Ordering::Release);
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(handle.wait_next_frame(7, Some(deadline)), None);
+        assert!(Instant::now() >= deadline);
+    }
+}
@@ -0,0 +1,250 @@
+//! Face color palettes that are approximately equiluminant and discriminable, for trial generation.
+//!
+//! A generated color is built in the plane of its hue: the gray of the target luma plus a multiple
+//! of the fully saturated hue minus its own gray. That keeps the Rec.709 luma at the target and the
+//! hue exact, and the multiple is the largest that keeps every channel in [0, 1]. The hues are
+//! evenly spread from a seeded start, so the colors are as far apart in hue as their count allows.
+//!
+//! Trials without explicit colors draw their face colors from a palette with `sample_face_colors`.
+use crate::constants::game_constants::{PALETTE_DEUTERANOPIA_HUES, PALETTE_LUMA_RANGE, PALETTE_LUMA_TOLERANCE};
+use crate::PyramidType;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Rec.709 luma of an RGB(A) color, on its (gamma-encoded) channels
+pub fn rec709_luma(color: [f32; 4]) -> f32 {
+    0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2]
+}
+
+/// HSV hue of a color in degrees, in [0, 360). Grays have hue 0
+pub fn hue_degrees(color: [f32; 4]) -> f32 {
+    let [r, g, b, _] = color;
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    if chroma <= 0.0 {
+        return 0.0;
+    }
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    (sector * 60.0).rem_euclid(360.0)
+}
+
+/// Distance between two hues around the color wheel, in degrees (at most 180)
+pub fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+/// Fully saturated, full value color of a hue in degrees
+fn saturated(hue: f32) -> [f32; 3] {
+    let x = |n: f32| {
+        let k = (n + hue / 60.0).rem_euclid(6.0);
+        1.0 - k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    [x(5.0), x(3.0), x(1.0)]
+}
+
+/// Opaque color of `hue` (degrees) with Rec.709 luma `luma`, as saturated as the [0, 1] channels allow
+pub fn equiluminant_color(hue: f32, luma: f32) -> [f32; 4] {
+    let pure = saturated(hue);
+    let pure_luma = rec709_luma([pure[0], pure[1], pure[2], 1.0]);
+    // Largest s with every channel luma + s * (pure - pure_luma) within [0, 1]
+    let s = ((1.0 - luma) / (1.0 - pure_luma)).min(luma / pure_luma).min(1.0);
+    let [r, g, b] = pure.map(|c| (luma + s * (c - pure_luma)).clamp(0.0, 1.0));
+    [r, g, b, 1.0]
+}
+
+/// `count` opaque colors with Rec.709 luma within PALETTE_LUMA_TOLERANCE of `luma`, their hues
+/// `hue_separation` degrees apart from a start drawn from `seed` (360 / count, the widest spread,
+/// when None). The same arguments always give the same colors
+pub fn generate_palette(count: usize, luma: f32, hue_separation: Option<f32>, seed: u64) -> Result<Vec<[f32; 4]>, String> {
+    if count == 0 {
+        return Err("a palette needs at least one color".to_string());
+    }
+    let widest = 360.0 / count as f32;
+    let separation = hue_separation.unwrap_or(widest);
+    if !(separation > 0.0 && separation <= widest) {
+        return Err(format!("hue separation of {count} colors must be in (0, {widest}] degrees, got {separation}"));
+    }
+
+    let start = ChaCha8Rng::seed_from_u64(seed).random::<f32>() * 360.0;
+    equiluminant_palette((0..count).map(|i| (start + i as f32 * separation) % 360.0), luma)
+}
+
+/// Deuteranopia-safe preset: the PALETTE_DEUTERANOPIA_HUES drawn with Rec.709 luma within
+/// PALETTE_LUMA_TOLERANCE of `luma`, so it is as equiluminant as a generated palette
+pub fn deuteranopia_safe_palette(luma: f32) -> Result<Vec<[f32; 4]>, String> {
+    equiluminant_palette(PALETTE_DEUTERANOPIA_HUES, luma)
+}
+
+/// Colors of `hues` at `luma`, checked against PALETTE_LUMA_RANGE and PALETTE_LUMA_TOLERANCE
+fn equiluminant_palette(hues: impl IntoIterator<Item = f32>, luma: f32) -> Result<Vec<[f32; 4]>, String> {
+    let [min_luma, max_luma] = PALETTE_LUMA_RANGE;
+    if !(min_luma..=max_luma).contains(&luma) {
+        return Err(format!("palette luma must be in [{min_luma}, {max_luma}], got {luma}"));
+    }
+    let colors: Vec<_> = hues.into_iter().map(|hue| equiluminant_color(hue, luma)).collect();
+    match colors.iter().find(|color| (rec709_luma(**color) - luma).abs() > PALETTE_LUMA_TOLERANCE) {
+        Some(color) => Err(format!("color {color:?} misses the luma {luma} by more than {PALETTE_LUMA_TOLERANCE}")),
+        None => Ok(colors),
+    }
+}
+
+/// Face colors of a `pyramid_type` pyramid drawn from `palette` with `seed`: three different colors
+/// for Type1, two for Type2 (the first repeated on the third face) and one on every face for Type3.
+/// A palette with fewer colors than that repeats some. The same arguments always give the same colors
+pub fn sample_face_colors(palette: &[[f32; 4]], seed: u64, pyramid_type: PyramidType) -> Result<[[f32; 4]; 3], String> {
+    if palette.is_empty() {
+        return Err("cannot sample face colors from an empty palette".to_string());
+    }
+    let distinct = match pyramid_type {
+        PyramidType::Type1 => 3,
+        PyramidType::Type2 => 2,
+        PyramidType::Type3 => 1,
+    };
+
+    // Partial Fisher-Yates shuffle of the palette indices, with replacement once they run out
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut indices: Vec<usize> = (0..palette.len()).collect();
+    let drawn: Vec<[f32; 4]> = (0..distinct)
+        .map(|i| {
+            if i >= indices.len() {
+                return palette[rng.random_range(0..palette.len())];
+            }
+            let pick = rng.random_range(i..indices.len());
+            indices.swap(i, pick);
+            palette[indices[i]]
+        })
+        .collect();
+
+    Ok(match pyramid_type {
+        PyramidType::Type1 => [drawn[0], drawn[1], drawn[2]],
+        PyramidType::Type2 => [drawn[0], drawn[1], drawn[0]],
+        PyramidType::Type3 => [drawn[0]; 3],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::game_constants::PALETTE_LUMA;
+
+    const PALETTE: [[f32; 4]; 5] = [
+        [1.0, 0.0, 0.0, 1.0],
+        [0.0, 1.0, 0.0, 1.0],
+        [0.0, 0.0, 1.0, 1.0],
+        [1.0, 1.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0, 1.0],
+    ];
+
+    #[test]
+    fn face_colors_follow_the_pyramid_type() {
+        for seed in 0..50 {
+            let [a, b, c] = sample_face_colors(&PALETTE, seed, PyramidType::Type1).unwrap();
+            assert!(a != b && b != c && a != c, "seed {seed}");
+            let [a, b, c] = sample_face_colors(&PALETTE, seed, PyramidType::Type2).unwrap();
+            assert!(a != b && a == c, "seed {seed}");
+            let [a, b, c] = sample_face_colors(&PALETTE, seed, PyramidType::Type3).unwrap();
+            assert!(a == b && b == c, "seed {seed}");
+            for color in [a, b, c] {
+                assert!(PALETTE.contains(&color));
+            }
+        }
+    }
+
+    #[test]
+    fn face_colors_repeat_per_seed_and_cover_the_palette() {
+        assert_eq!(sample_face_colors(&PALETTE, 7, PyramidType::Type1), sample_face_colors(&PALETTE, 7, PyramidType::Type1));
+        let drawn: Vec<_> = (0..100).map(|seed| sample_face_colors(&PALETTE, seed, PyramidType::Type1).unwrap()).collect();
+        assert!(drawn.windows(2).any(|pair| pair[0] != pair[1]), "seeds change the draw");
+        for color in PALETTE {
+            assert!(drawn.iter().any(|faces| faces[0] == color), "{color:?} never drawn first");
+        }
+    }
+
+    #[test]
+    fn small_palettes_repeat_colors() {
+        let one = [PALETTE[0]];
+        assert_eq!(sample_face_colors(&one, 3, PyramidType::Type1).unwrap(), [PALETTE[0]; 3]);
+        let two = &PALETTE[..2];
+        let [a, b, c] = sample_face_colors(two, 3, PyramidType::Type1).unwrap();
+        assert!(a != b && two.contains(&c));
+        assert!(sample_face_colors(&[], 3, PyramidType::Type1).is_err());
+    }
+
+    /// Smallest hue distance between two colors of the palette
+    fn min_hue_distance(palette: &[[f32; 4]]) -> f32 {
+        let mut min = f32::INFINITY;
+        for (i, a) in palette.iter().enumerate() {
+            for b in &palette[i + 1..] {
+                min = min.min(hue_distance(hue_degrees(*a), hue_degrees(*b)));
+            }
+        }
+        min
+    }
+
+    #[test]
+    fn hues_and_lumas_are_measured_on_the_wheel() {
+        assert_eq!(hue_degrees([1.0, 0.0, 0.0, 1.0]), 0.0);
+        assert_eq!(hue_degrees([0.0, 1.0, 0.0, 1.0]), 120.0);
+        assert_eq!(hue_degrees([0.0, 0.0, 1.0, 1.0]), 240.0);
+        assert_eq!(hue_distance(350.0, 10.0), 20.0);
+        assert!((rec709_luma([1.0, 1.0, 1.0, 1.0]) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn palettes_are_equiluminant_and_spread_in_hue() {
+        for count in 1..=8 {
+            for luma in [PALETTE_LUMA_RANGE[0], 0.3, 0.5, 0.7, PALETTE_LUMA_RANGE[1]] {
+                for seed in 0..20 {
+                    let palette = generate_palette(count, luma, None, seed).unwrap();
+                    assert_eq!(palette.len(), count);
+                    for color in &palette {
+                        assert!((rec709_luma(*color) - luma).abs() <= PALETTE_LUMA_TOLERANCE, "{color:?} at luma {luma}");
+                        assert!(color.iter().all(|c| (0.0..=1.0).contains(c)) && color[3] == 1.0, "{color:?}");
+                    }
+                    if count > 1 {
+                        let widest = 360.0 / count as f32;
+                        assert!(min_hue_distance(&palette) >= widest - 0.5, "{count} colors at luma {luma}, seed {seed}");
+                    }
+                }
+            }
+        }
+
+        let narrow = generate_palette(4, 0.5, Some(30.0), 7).unwrap();
+        assert!(min_hue_distance(&narrow) >= 30.0 - 0.5);
+        assert!(min_hue_distance(&narrow) < 60.0);
+    }
+
+    #[test]
+    fn palettes_repeat_per_seed() {
+        assert_eq!(generate_palette(3, 0.5, None, 11), generate_palette(3, 0.5, None, 11));
+        assert_ne!(generate_palette(3, 0.5, None, 11), generate_palette(3, 0.5, None, 12));
+    }
+
+    #[test]
+    fn invalid_palettes_are_rejected() {
+        assert!(generate_palette(0, 0.5, None, 0).is_err());
+        assert!(generate_palette(3, 0.95, None, 0).is_err(), "too close to white for any chroma");
+        assert!(generate_palette(3, 0.5, Some(150.0), 0).is_err(), "three hues cannot be 150 degrees apart");
+        assert!(generate_palette(3, 0.5, Some(0.0), 0).is_err());
+        assert!(deuteranopia_safe_palette(0.95).is_err());
+    }
+
+    #[test]
+    fn deuteranopia_preset_is_equiluminant_and_distinct() {
+        for luma in [PALETTE_LUMA_RANGE[0], PALETTE_LUMA, PALETTE_LUMA_RANGE[1]] {
+            let palette = deuteranopia_safe_palette(luma).unwrap();
+            for (color, hue) in palette.iter().zip(PALETTE_DEUTERANOPIA_HUES) {
+                assert!((rec709_luma(*color) - luma).abs() <= PALETTE_LUMA_TOLERANCE, "{color:?} at luma {luma}");
+                assert!(hue_distance(hue_degrees(*color), hue) < 0.5, "{color:?} keeps hue {hue}");
+            }
+            assert!(min_hue_distance(&palette) >= 60.0);
+        }
+    }
+}
@@ -1,13 +1,44 @@
 //! Python bindings for shared memroy of native.rs
-use crate::{SharedMemoryHandle, create_shared_memory};
-use std::sync::atomic::Ordering;
-use pyo3::exceptions::PyValueError;
+use crate::{
+    AnimationEasing, ColorSpace, ConfigMode, DecorationMotion, LogCategory, LOG_CATEGORIES_ALL, PauseMode, Phase, PostWinMode, ProjectionMode, PyramidType, RenderQuality, ScoreBarAnchor, SharedGameStructure, SharedMemoryHandle, StimulusFileStatus, SEEN_COMMAND_NAMES, seen_command_names, TargetKind, HIDE_IMAGE, create_shared_memory, open_shared_memory, validate_camera_projection,
+    validate_decoration_colors, validate_decoration_pulse, validate_door_geometry, validate_door_reward_weights, widest_base_radius, validate_fog, validate_geometry_ranges, validate_pyramid_type, validate_score_bar, validate_stimulus_file,
+};
+use crate::constants::pyramid_constants::{
+    COLOR_SPACE, DOOR_ANIM_EASING, DOOR_FRAME_THICKNESS, DOOR_HOLE_RADIUS_FRACTION, DOOR_INSET, DOOR_REWARD_WEIGHTS, PYRAMID_DECORATIONS_COUNT_RANGE, PYRAMID_DECORATIONS_SPACING, PYRAMID_DECORATION_MOTION, PYRAMID_DECORATION_PULSE_AMPLITUDE, PYRAMID_DECORATION_PULSE_HZ, PYRAMID_DECORATION_SPIN_RATE, PYRAMID_DECORATION_SYMMETRY, PYRAMID_DECORATION_COLORS, PYRAMID_DECORATION_COLOR_JITTER, PYRAMID_FLICKER_DEPTH, PYRAMID_OUTLINE_COLOR, PYRAMID_OUTLINE_WIDTH, PYRAMID_FLICKER_HZ, PYRAMID_TARGET_FACE_INDEX,
+    PYRAMID_TYPE, TARGET_KIND, CONFIG_MODE, GEOMETRY_SEED, PYRAMID_BASE_RADIUS_RANGE, PYRAMID_HEIGHT_RANGE,
+    PYRAMID_START_ORIENT_RANGE,
+};
+use crate::constants::camera_3d_constants::{
+    CAMERA_3D_FAR, CAMERA_3D_FOV_DEGREES, CAMERA_3D_NEAR, CAMERA_3D_ORTHO_SCALE_RANGE, CAMERA_3D_PROJECTION_MODE,
+    CONTROL_MIRROR_ROTATION, CONTROL_MIRROR_ZOOM, MIRROR_X, RECENTER_ON_RESET,
+};
+use crate::constants::object_constants::{
+    BACKDROP_ENABLED, DISTRACTOR_COLOR, DISTRACTOR_COUNT, DISTRACTOR_RING, DISTRACTOR_SEED, DISTRACTOR_SHAPE,
+    DISTRACTOR_SIZE_RANGE,
+};
+use crate::constants::game_constants::{
+    LIVE_FEEDBACK_ENABLED, LIVE_FEEDBACK_GAIN, LIVE_FEEDBACK_SMOOTHING, RETICLE_ALIGNMENT_TINT, RETICLE_COLOR,
+    DEBOUNCE_FRAMES, FEEDBACK_DELAY_MS, IS_CATCH, MAX_ATTEMPTS, MIN_VIEW_SECS, RETICLE_ENABLED, RETICLE_SIZE, RETICLE_STYLE, SHAPING_DWELL_SECS, UI_SCALE, STAIRCASE_COSINE_RANGE,
+    SCORE_BAR_ANCHOR, SCORE_BAR_HEIGHT, SCORE_BAR_SHOW_NUMERIC, SCORE_BAR_WIDTH_PERCENT,
+    CONTROLLER_TIMEOUT_FRAMES, MOTION_LATCH_TIMEOUT_FRAMES, PROBE_DURATION_SECS, STAIRCASE_DOWN, STAIRCASE_STEP, STAIRCASE_UP, SUPPRESS_BLANKED_ATTEMPTS,
+    PALETTE_LUMA, PALETTE_SIZE, REQUIRED_APPROACH_SIGN, TRIAL_TIMEOUT_SECS, WIN_RADIUS_MAX, WIN_RADIUS_MIN,
+};
+use crate::constants::lighting_constants::{FOG_COLOR, FOG_ENABLED, FOG_END, FOG_START, SPOTLIGHT_OUTER_ANGLE, SPOTLIGHT_RANGE, WIN_GLOW_COLOR};
+use crate::frame_clock::FrameClock;
+use crate::snapshot::StateSnapshot;
+use crate::constants::timing::{ITI_FRAMES, PAUSE_MODE, POST_WIN_MODE};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::types::{PyDict, PyTuple};
 use pyo3::{prelude::*};
 
 // Python class wrapper of SharedMemoryHandle implementation
 #[pyclass]
 struct SharedMemoryWrapper {
     inner: SharedMemoryHandle,
+    /// Fed with every frame number read, to schedule on the game's frame boundaries
+    frame_clock: FrameClock,
 }
 
 // Python wrapper around methods for SharedMemoryHandle
@@ -20,80 +51,160 @@ impl SharedMemoryWrapper {
         let res = create_shared_memory(name);
 
         match res {
-            Ok(handle) => Ok(SharedMemoryWrapper { inner: handle }),
+            Ok(handle) => Ok(SharedMemoryWrapper { inner: handle, frame_clock: FrameClock::new() }),
             Err(e) => Err(PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string())),
         }
     }
 
+    /// Open a segment the game created, without resetting it (to reconnect to a running game)
+    #[staticmethod]
+    fn open(name: &str) -> PyResult<Self> {
+        open_shared_memory(name)
+            .map(|handle| SharedMemoryWrapper { inner: handle, frame_clock: FrameClock::new() })
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
+    }
+
     /// Read the full game structure from shared memory as a dictionary.
     /// It reads one written by the game.
     /// Some values need to be read as f32 from bits
-    fn read_game_structure(&self) -> PyResult<Py<PyAny>> {
+    fn read_game_structure(&mut self) -> PyResult<Py<PyAny>> {
         let shm = self.inner.get();
-        let gs= &shm.game_structure_game;
+        let gs = &shm.game_structure_game;
+        self.frame_clock.observe(gs.frame_number.load(Ordering::Acquire), Instant::now());
+        Python::attach(|py| game_structure_to_dict(py, gs, &shm.game_structure_control))
+    }
 
-        Python::attach(|py| {
-            let dict = pyo3::types::PyDict::new(py);
+    /// Read the per-frame fields from the game's double-buffered snapshot (see snapshot.rs): all from
+    /// the same frame, with the keys of the full state dictionary. None before the first frame
+    fn read_snapshot(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyAny>>> {
+        let Some(snapshot) = self.inner.read_snapshot() else { return Ok(None) };
+        self.frame_clock.observe(snapshot.frame_number, Instant::now());
+        snapshot_to_dict(py, &snapshot).map(Some)
+    }
 
-            // Fixed vars in trial
-            dict.set_item("decoration_seeds", [
-                gs.decoration_seeds[0].load(Ordering::Relaxed),
-                gs.decoration_seeds[1].load(Ordering::Relaxed),
-                gs.decoration_seeds[2].load(Ordering::Relaxed),
-            ])?;
-            dict.set_item("base_radius", f32::from_bits(gs.base_radius.load(Ordering::Relaxed)))?;
-            dict.set_item("height", f32::from_bits(gs.height.load(Ordering::Relaxed)))?;
-            dict.set_item("start_orient", f32::from_bits(gs.start_orient.load(Ordering::Relaxed)))?;
-            dict.set_item("target_door", gs.target_door.load(Ordering::Relaxed))?;
-            let mut colors: Vec<Vec<f32>> = Vec::with_capacity(3);  // Colors as 3x4 list
-            for face_idx in 0..3 {
-                let mut face_colors: Vec<f32> = Vec::with_capacity(4);
-                for channel_idx in 0..4 {
-                    let index = face_idx * 4 + channel_idx;
-                    face_colors.push(f32::from_bits(gs.colors[index].load(Ordering::Relaxed)));
-                }
-                colors.push(face_colors);
-            }
-            dict.set_item("colors", colors)?;
-
-            dict.set_item("main_spotlight_intensity", f32::from_bits(gs.main_spotlight_intensity.load(Ordering::Relaxed)))?;
-            dict.set_item("ambient_brightness", f32::from_bits(gs.ambient_brightness.load(Ordering::Relaxed)))?;
-            dict.set_item("max_spotlight_intensity", f32::from_bits(gs.max_spotlight_intensity.load(Ordering::Relaxed)))?;
-            dict.set_item("decoration_count", [
-                gs.decorations_count[0].load(Ordering::Relaxed),
-                gs.decorations_count[1].load(Ordering::Relaxed),
-                gs.decorations_count[2].load(Ordering::Relaxed)
-            ])?;
-            dict.set_item("decoration_size", [
-                f32::from_bits(gs.decorations_size[0].load(Ordering::Relaxed)),
-                f32::from_bits(gs.decorations_size[1].load(Ordering::Relaxed)),
-                f32::from_bits(gs.decorations_size[2].load(Ordering::Relaxed))
-            ])?;
-
-            // Dynamic vars in trial
-            dict.set_item("cosine_alignment_threshold", f32::from_bits(gs.cosine_alignment_threshold.load(Ordering::Relaxed)))?;
-            dict.set_item("door_anim_fade_out", f32::from_bits(gs.door_anim_fade_out.load(Ordering::Relaxed)))?;
-            dict.set_item("door_anim_stay_open", f32::from_bits(gs.door_anim_stay_open.load(Ordering::Relaxed)))?;
-            dict.set_item("door_anim_fade_in", f32::from_bits(gs.door_anim_fade_in.load(Ordering::Relaxed)))?;
-            dict.set_item("frame_number", gs.frame_number.load(Ordering::Relaxed))?;
-            dict.set_item("elapsed_secs", f32::from_bits(gs.elapsed_secs.load(Ordering::Relaxed)))?;
-            dict.set_item("camera_radius", f32::from_bits(gs.camera_radius.load(Ordering::Relaxed)))?;
-            dict.set_item("camera_position", vec![
-                f32::from_bits(gs.camera_x.load(Ordering::Relaxed)),
-                f32::from_bits(gs.camera_y.load(Ordering::Relaxed)),
-                f32::from_bits(gs.camera_z.load(Ordering::Relaxed)),
-            ])?;
-            dict.set_item("nr_attempts", gs.attempts.load(Ordering::Relaxed))?;
-            dict.set_item("cosine_alignment", f32::from_bits(gs.current_alignment.load(Ordering::Relaxed)))?;
-            dict.set_item("current_angle", f32::from_bits(gs.current_angle.load(Ordering::Relaxed)))?;
-            dict.set_item("is_animating", gs.is_animating.load(Ordering::Relaxed))?;
-            dict.set_item("win_elapsed_secs", f32::from_bits(gs.win_time.load(Ordering::Relaxed)))?;
-
-            Ok(dict.into())
+    /// Block until the game emits a frame after `last_frame`, then return its state dictionary.
+    /// The GIL is released while waiting. Returns None if `timeout_secs` elapses first.
+    /// The frame number restarts at 0 on every reset, so the frames after a reset count as new.
+    ///
+    /// Recommended frame-locked loop (no dropped or double-read frames):
+    ///
+    /// ```python
+    /// last = shm.read_game_structure()["frame_number"]
+    /// while running:
+    ///     state = shm.read_next_frame(last, 1.0)
+    ///     if state is None:
+    ///         continue  # game stalled or paused
+    ///     last = state["frame_number"]
+    ///     ...  # react to exactly this frame
+    /// ```
+    #[pyo3(signature = (last_frame, timeout_secs=None))]
+    fn read_next_frame(&mut self, py: Python<'_>, last_frame: u64, timeout_secs: Option<f32>) -> PyResult<Option<Py<PyAny>>> {
+        let deadline = wait_deadline(timeout_secs)?;
+        let inner = &self.inner;
+        let Some(frame) = py.detach(|| inner.wait_next_frame(last_frame, deadline)) else {
+            return Ok(None);
+        };
+        self.frame_clock.observe(frame, Instant::now());
+        let shm = self.inner.get();
+        game_structure_to_dict(py, &shm.game_structure_game, &shm.game_structure_control).map(Some)
+    }
+
+    /// Estimated game frame period in seconds, from the frames read so far (nominal until then).
+    fn frame_period(&self) -> f64 {
+        self.frame_clock.period().as_secs_f64()
+    }
+
+    /// Seconds until `frame` is predicted to be readable (negative once past), None before any frame was read.
+    fn seconds_until_frame(&self, frame: u64) -> Option<f64> {
+        let due = self.frame_clock.frame_time(frame)?;
+        let now = Instant::now();
+        Some(match due.checked_duration_since(now) {
+            Some(ahead) => ahead.as_secs_f64(),
+            None => -now.duration_since(due).as_secs_f64(),
         })
     }
 
+    /// The next frame predicted to be readable and the seconds until then, None before any frame was read.
+    fn next_frame(&self) -> Option<(u64, f64)> {
+        let now = Instant::now();
+        let (frame, due) = self.frame_clock.next_frame(now)?;
+        Some((frame, due.saturating_duration_since(now).as_secs_f64()))
+    }
+
+    /// Block (without holding the GIL) until `frame` is predicted to be readable.
+    /// Returns false at once before any frame was read.
+    fn sleep_until_frame(&self, py: Python<'_>, frame: u64) -> bool {
+        let clock = self.frame_clock.clone();
+        py.detach(move || clock.sleep_until_frame(frame))
+    }
+
+    /// Attempts and rejected checks of the current trial, and the times the game cleared them
+    /// (`clear_attempts_ack`), without reading the whole state.
+    fn read_attempts(&self) -> (u32, u32, u32) {
+        let gs = &self.inner.get().game_structure_game;
+        (
+            gs.attempts.load(Ordering::Relaxed),
+            gs.rejected_checks.load(Ordering::Relaxed),
+            gs.clear_attempts_ack.load(Ordering::Acquire),
+        )
+    }
+
+    /// Number of resets the game has completed.
+    fn read_reset_ack(&self) -> u32 {
+        self.inner.get().game_structure_game.reset_ack.load(Ordering::Acquire)
+    }
+
+    /// Block (without holding the GIL) until the game acknowledges a reset newer than `last_ack`.
+    /// Returns false on timeout.
+    #[pyo3(signature = (last_ack, timeout_secs=None))]
+    fn wait_reset_ack(&self, py: Python<'_>, last_ack: u32, timeout_secs: Option<f32>) -> PyResult<bool> {
+        let gs = &self.inner.get().game_structure_game;
+        let deadline = wait_deadline(timeout_secs)?;
+
+        Ok(py.detach(|| loop {
+            if gs.reset_ack.load(Ordering::Acquire) != last_ack {
+                return true;
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return false;
+            }
+            std::thread::yield_now();
+        }))
+    }
+
+    /// Number of trials the game has set up, read before a reset to wait on the next one.
+    fn read_trial_generation(&self) -> u32 {
+        self.inner.get().game_structure_game.trial_generation.load(Ordering::Acquire)
+    }
+
+    /// Block (without holding the GIL) until the trial after generation `last_generation` is playing.
+    /// Returns false on timeout.
+    #[pyo3(signature = (last_generation, timeout_secs=None))]
+    fn wait_trial_start(&self, py: Python<'_>, last_generation: u32, timeout_secs: Option<f32>) -> PyResult<bool> {
+        let gs = &self.inner.get().game_structure_game;
+        let deadline = wait_deadline(timeout_secs)?;
+
+        Ok(py.detach(|| loop {
+            if gs.trial_generation.load(Ordering::Acquire) != last_generation {
+                return true;
+            }
+            if deadline.is_some_and(|d| Instant::now() >= d) {
+                return false;
+            }
+            std::thread::yield_now();
+        }))
+    }
+
     /// Write commands to shared memory.
+    // One keyword argument per command is the Python API, see the signature
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        rotate_left, rotate_right, zoom_in, zoom_out, check, reset,
+        blank_screen, stop_rendering, resume_rendering, animation_door, step_frame=false,
+        debug_normals=false, dump_config=false, clear_attempts=false, queue_next=false,
+        set_pyramid_type=None, promote_staged=false, log_categories=None, show_threshold_cone=false,
+        dump_geometry=false,
+    ))]
     fn write_commands(
         &mut self,
         rotate_left: bool,
@@ -106,25 +217,261 @@ impl SharedMemoryWrapper {
         stop_rendering: bool,
         resume_rendering: bool,
         animation_door: bool,
-    ) {
+        step_frame: bool,
+        debug_normals: bool,
+        dump_config: bool,
+        clear_attempts: bool,
+        queue_next: bool,
+        set_pyramid_type: Option<u32>,
+        promote_staged: bool,
+        log_categories: Option<u32>,
+        show_threshold_cone: bool,
+        dump_geometry: bool,
+    ) -> PyResult<()> {
+        if let Some(mask) = log_categories {
+            if mask & !LOG_CATEGORIES_ALL != 0 {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "log_categories has bits above LOG_CATEGORIES_ALL ({LOG_CATEGORIES_ALL}), got {mask}"
+                )));
+            }
+        }
         let shm = self.inner.get();
         let cmd = &shm.commands;
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+
+        // Latched before reset is stored, so the game never sees the reset without its staged config
+        if promote_staged {
+            cmd.promote_staged.store(true, Ordering::Release);
+        }
 
         cmd.rotate_left.store(rotate_left, Ordering::Relaxed);
         cmd.rotate_right.store(rotate_right, Ordering::Relaxed);
         cmd.zoom_in.store(zoom_in, Ordering::Relaxed);
         cmd.zoom_out.store(zoom_out, Ordering::Relaxed);    
         cmd.check_alignment.store(check, Ordering::Relaxed);
+        // After the flag, so a game that sees the request also sees the flag
+        if check {
+            cmd.check_requests.fetch_add(1, Ordering::Release);
+        }
         cmd.reset.store(reset, Ordering::Release);
         cmd.blank_screen.store(blank_screen, Ordering::Relaxed);
         cmd.stop_rendering.store(stop_rendering, Ordering::Relaxed);
         cmd.resume_rendering.store(resume_rendering, Ordering::Relaxed);
         cmd.animation_door.store(animation_door, Ordering::Relaxed);
-        
+        cmd.step_frame.store(step_frame, Ordering::Relaxed);
+        cmd.debug_normals.store(debug_normals, Ordering::Relaxed);
+        cmd.show_threshold_cone.store(show_threshold_cone, Ordering::Relaxed);
+        // Only set here: the game clears it once the dump is written, so no request is lost between frames
+        if dump_config {
+            cmd.dump_config.store(true, Ordering::Relaxed);
+        }
+        if dump_geometry {
+            cmd.dump_geometry.store(true, Ordering::Relaxed);
+        }
+        if clear_attempts {
+            cmd.clear_attempts.store(true, Ordering::Relaxed);
+        }
+        if queue_next {
+            cmd.queue_next.store(true, Ordering::Relaxed);
+        }
+        // Left pending until the game swaps it back, like the latched flags above
+        if let Some(code) = set_pyramid_type {
+            cmd.set_pyramid_type.store(code, Ordering::Relaxed);
+        }
+        if let Some(mask) = log_categories {
+            cmd.log_categories.store(mask, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Opt in to (or out of) latched rotation/zoom. The game stops latched motion after
+    /// `timeout_frames` frames without any command write from this controller (0 = never).
+    #[pyo3(signature = (enabled, timeout_frames=MOTION_LATCH_TIMEOUT_FRAMES))]
+    fn set_motion_latched(&mut self, enabled: bool, timeout_frames: u32) {
+        let cmd = &self.inner.get().commands;
+        cmd.motion_latch_timeout_frames.store(timeout_frames, Ordering::Relaxed);
+        cmd.motion_latched.store(enabled, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Arm (or with `timeout_frames=0` disarm) the game's controller watchdog: after `timeout_frames`
+    /// frames without any command write from this controller the game stops rotation and zoom, flags
+    /// `controller_lost` and, with `blank`, blanks the screen. Command writes keep it fed.
+    #[pyo3(signature = (timeout_frames=CONTROLLER_TIMEOUT_FRAMES, blank=false))]
+    fn set_controller_watchdog(&mut self, timeout_frames: u32, blank: bool) {
+        let cmd = &self.inner.get().commands;
+        cmd.controller_lost_blank.store(blank, Ordering::Relaxed);
+        cmd.controller_timeout_frames.store(timeout_frames, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Turn every rotable entity by `radians` on top of the controls, live, to line the pyramid up with
+    /// a rotated display. Held until set again; the emitted `pyramid_yaw` excludes it.
+    fn set_global_yaw_offset(&mut self, radians: f32) -> PyResult<()> {
+        if !radians.is_finite() {
+            return Err(PyErr::new::<PyValueError, _>(format!("global yaw offset must be finite, got {radians}")));
+        }
+        self.inner.get().commands.global_yaw_offset.store(radians.to_bits(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Write the session context shown in the game's logs and HUD ("trial 37/200, block 2"). Any time,
+    /// held until written again; the game only echoes it into the state.
+    #[pyo3(signature = (current_trial_index, total_trials, block_index=0, session_id=0, subject_id=0))]
+    fn write_session_info(&mut self, current_trial_index: u32, total_trials: u32, block_index: u32, session_id: u64, subject_id: u32) {
+        let gs = &self.inner.get().game_structure_control;
+        gs.current_trial_index.store(current_trial_index, Ordering::Relaxed);
+        gs.total_trials.store(total_trials, Ordering::Relaxed);
+        gs.block_index.store(block_index, Ordering::Relaxed);
+        gs.session_id.store(session_id, Ordering::Relaxed);
+        gs.subject_id.store(subject_id, Ordering::Relaxed);
+    }
+
+    /// Recolor the decorations of `face` (0-2) with `color` (RGBA, in the trial's color space) for
+    /// `duration_secs`, then revert them. A probe sent while one is running replaces it.
+    #[pyo3(signature = (face, color, duration_secs=PROBE_DURATION_SECS))]
+    fn probe_decorations(&mut self, face: u32, color: [f32; 4], duration_secs: f32) -> PyResult<()> {
+        if face > 2 {
+            return Err(PyErr::new::<PyValueError, _>(format!("probe face must be 0, 1 or 2, got {face}")));
+        }
+        if !(duration_secs.is_finite() && duration_secs > 0.0) {
+            return Err(PyErr::new::<PyValueError, _>(format!("probe duration must be positive, got {duration_secs}")));
+        }
+        let cmd = &self.inner.get().commands;
+        for (slot, value) in cmd.probe_color.iter().zip(color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        cmd.probe_duration_secs.store(duration_secs.to_bits(), Ordering::Relaxed);
+        cmd.probe_face.store(face, Ordering::Release);
+        Ok(())
+    }
+
+    /// Show reference image `index` (into REFERENCE_IMAGES) full screen, above the scene and the
+    /// blank, until hide_reference_image or the next reset.
+    fn show_reference_image(&mut self, index: u32) -> PyResult<()> {
+        let count = crate::constants::game_constants::REFERENCE_IMAGES.len();
+        if index as usize >= count {
+            return Err(PyErr::new::<PyValueError, _>(format!("reference image must be below {count}, got {index}")));
+        }
+        let cmd = &self.inner.get().commands;
+        cmd.show_image.store(index, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn hide_reference_image(&mut self) {
+        let cmd = &self.inner.get().commands;
+        cmd.show_image.store(HIDE_IMAGE, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Switch the rendering to RENDER_QUALITY_LOW, _MEDIUM or _HIGH (MSAA and shadows), live. The
+    /// state's `render_quality` reports the level applied.
+    fn set_render_quality(&mut self, level: u32) -> PyResult<()> {
+        if RenderQuality::from_u32(level).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown render quality {level}")));
+        }
+        let cmd = &self.inner.get().commands;
+        cmd.render_quality.store(level, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Keep rotating (-1 left, 1 right) until stop_rotation. Needs set_motion_latched(True).
+    fn start_rotation(&mut self, direction: i32) -> PyResult<()> {
+        let cmd = &self.inner.get().commands;
+        let (start, other) = match direction {
+            -1 => (&cmd.rotate_start_left, &cmd.rotate_start_right),
+            1 => (&cmd.rotate_start_right, &cmd.rotate_start_left),
+            _ => return Err(PyErr::new::<PyValueError, _>(format!("direction must be -1 or 1, got {direction}"))),
+        };
+        other.store(false, Ordering::Relaxed);
+        cmd.rotate_stop.store(false, Ordering::Relaxed);
+        start.store(true, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn stop_rotation(&mut self) {
+        let cmd = &self.inner.get().commands;
+        cmd.rotate_start_left.store(false, Ordering::Relaxed);
+        cmd.rotate_start_right.store(false, Ordering::Relaxed);
+        cmd.rotate_stop.store(true, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Keep zooming (-1 in, 1 out) until stop_zoom. Needs set_motion_latched(True).
+    fn start_zoom(&mut self, direction: i32) -> PyResult<()> {
+        let cmd = &self.inner.get().commands;
+        let (start, other) = match direction {
+            -1 => (&cmd.zoom_start_in, &cmd.zoom_start_out),
+            1 => (&cmd.zoom_start_out, &cmd.zoom_start_in),
+            _ => return Err(PyErr::new::<PyValueError, _>(format!("direction must be -1 or 1, got {direction}"))),
+        };
+        other.store(false, Ordering::Relaxed);
+        cmd.zoom_stop.store(false, Ordering::Relaxed);
+        start.store(true, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn stop_zoom(&mut self) {
+        let cmd = &self.inner.get().commands;
+        cmd.zoom_start_in.store(false, Ordering::Relaxed);
+        cmd.zoom_start_out.store(false, Ordering::Relaxed);
+        cmd.zoom_stop.store(true, Ordering::Relaxed);
+        cmd.heartbeat.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Write game structure config fields to shared memory.
     /// Write in controller region
+    // One keyword argument per config field is the Python API, see the signature
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (
+        decoration_seeds, base_radius, height, start_orient, target_door, colors,
+        decorations_count, decorations_size, cosine_alignment_threshold,
+        door_anim_fade_out, door_anim_stay_open, door_anim_fade_in,
+        main_spotlight_intensity, ambient_brightness, max_spotlight_intensity,
+        flicker_hz=PYRAMID_FLICKER_HZ, flicker_depth=PYRAMID_FLICKER_DEPTH,
+        decorations_count_range=PYRAMID_DECORATIONS_COUNT_RANGE, decoration_spacing=PYRAMID_DECORATIONS_SPACING,
+        decoration_spin_rate=PYRAMID_DECORATION_SPIN_RATE, decoration_motion=PYRAMID_DECORATION_MOTION,
+        decoration_pulse_amplitude=PYRAMID_DECORATION_PULSE_AMPLITUDE, decoration_pulse_hz=PYRAMID_DECORATION_PULSE_HZ,
+        decoration_symmetry=PYRAMID_DECORATION_SYMMETRY, decoration_colors=PYRAMID_DECORATION_COLORS,
+        decoration_color_jitter=PYRAMID_DECORATION_COLOR_JITTER,
+        outline_width=PYRAMID_OUTLINE_WIDTH, outline_color=PYRAMID_OUTLINE_COLOR,
+        shaping_dwell_secs=SHAPING_DWELL_SECS, suppress_blanked_attempts=SUPPRESS_BLANKED_ATTEMPTS,
+        is_catch=IS_CATCH, max_attempts=MAX_ATTEMPTS, trial_timeout_secs=TRIAL_TIMEOUT_SECS,
+        win_radius_min=WIN_RADIUS_MIN, win_radius_max=WIN_RADIUS_MAX, required_approach_sign=REQUIRED_APPROACH_SIGN,
+        min_view_secs=MIN_VIEW_SECS,
+        feedback_delay_ms=FEEDBACK_DELAY_MS,
+        debounce_frames=DEBOUNCE_FRAMES,
+        iti_frames=ITI_FRAMES, post_win_mode=POST_WIN_MODE, pause_mode=PAUSE_MODE, door_anim_easing=DOOR_ANIM_EASING,
+        ui_scale=UI_SCALE, score_bar_anchor=SCORE_BAR_ANCHOR, score_bar_width_percent=SCORE_BAR_WIDTH_PERCENT,
+        score_bar_height=SCORE_BAR_HEIGHT, score_bar_show_numeric=SCORE_BAR_SHOW_NUMERIC,
+        reticle_enabled=RETICLE_ENABLED, reticle_size=RETICLE_SIZE, reticle_color=RETICLE_COLOR,
+        reticle_style=RETICLE_STYLE, reticle_alignment_tint=RETICLE_ALIGNMENT_TINT,
+        live_feedback_enabled=LIVE_FEEDBACK_ENABLED, live_feedback_gain=LIVE_FEEDBACK_GAIN,
+        live_feedback_smoothing=LIVE_FEEDBACK_SMOOTHING,
+        control_mirror_rotation=CONTROL_MIRROR_ROTATION, control_mirror_zoom=CONTROL_MIRROR_ZOOM, mirror_x=MIRROR_X,
+        recenter_on_reset=RECENTER_ON_RESET,
+        distractor_count=DISTRACTOR_COUNT, distractor_shape=DISTRACTOR_SHAPE,
+        distractor_size_range=DISTRACTOR_SIZE_RANGE, distractor_color=DISTRACTOR_COLOR,
+        distractor_ring=DISTRACTOR_RING, distractor_seed=DISTRACTOR_SEED,
+        pyramid_type=PYRAMID_TYPE,
+        door_hole_radius_fraction=DOOR_HOLE_RADIUS_FRACTION, door_frame_thickness=DOOR_FRAME_THICKNESS,
+        door_inset=DOOR_INSET, door_reward_weights=DOOR_REWARD_WEIGHTS,
+        target_kind=TARGET_KIND, target_face=PYRAMID_TARGET_FACE_INDEX,
+        camera_fov_degrees=CAMERA_3D_FOV_DEGREES, camera_near=CAMERA_3D_NEAR, camera_far=CAMERA_3D_FAR,
+        spotlight_outer_angle=SPOTLIGHT_OUTER_ANGLE, spotlight_range=SPOTLIGHT_RANGE, win_glow_color=WIN_GLOW_COLOR,
+        fog_enabled=FOG_ENABLED, fog_color=FOG_COLOR, fog_start=FOG_START, fog_end=FOG_END,
+        projection_mode=CAMERA_3D_PROJECTION_MODE, ortho_scale_range=CAMERA_3D_ORTHO_SCALE_RANGE,
+        color_space=COLOR_SPACE, backdrop_enabled=BACKDROP_ENABLED,
+        config_mode=CONFIG_MODE, geometry_seed=GEOMETRY_SEED, base_radius_range=PYRAMID_BASE_RADIUS_RANGE,
+        height_range=PYRAMID_HEIGHT_RANGE, start_orient_range=PYRAMID_START_ORIENT_RANGE,
+        stimulus_file=None, stimulus_expected_hash=0,
+        trial_id=None,
+        staged=false,
+    ))]
     fn write_game_structure(
         &mut self,
         decoration_seeds: [u64; 3],
@@ -142,6 +489,87 @@ impl SharedMemoryWrapper {
         main_spotlight_intensity: f32,
         ambient_brightness: f32,
         max_spotlight_intensity: f32,
+        flicker_hz: [f32; 3],
+        flicker_depth: [f32; 3],
+        decorations_count_range: [[u32; 2]; 3],
+        decoration_spacing: f32,
+        decoration_spin_rate: f32,
+        decoration_motion: u32,
+        decoration_pulse_amplitude: f32,
+        decoration_pulse_hz: f32,
+        decoration_symmetry: bool,
+        decoration_colors: [[f32; 4]; 3],
+        decoration_color_jitter: f32,
+        outline_width: f32,
+        outline_color: [f32; 4],
+        shaping_dwell_secs: f32,
+        suppress_blanked_attempts: bool,
+        is_catch: bool,
+        max_attempts: u32,
+        trial_timeout_secs: f32,
+        win_radius_min: f32,
+        win_radius_max: f32,
+        required_approach_sign: i32,
+        min_view_secs: f32,
+        feedback_delay_ms: u32,
+        debounce_frames: [u32; 3],
+        iti_frames: u32,
+        post_win_mode: u32,
+        pause_mode: u32,
+        door_anim_easing: u32,
+        ui_scale: f32,
+        score_bar_anchor: u32,
+        score_bar_width_percent: f32,
+        score_bar_height: f32,
+        score_bar_show_numeric: bool,
+        reticle_enabled: bool,
+        reticle_size: f32,
+        reticle_color: [f32; 4],
+        reticle_style: u32,
+        reticle_alignment_tint: bool,
+        live_feedback_enabled: bool,
+        live_feedback_gain: f32,
+        live_feedback_smoothing: f32,
+        control_mirror_rotation: bool,
+        control_mirror_zoom: bool,
+        mirror_x: bool,
+        recenter_on_reset: bool,
+        distractor_count: u32,
+        distractor_shape: u32,
+        distractor_size_range: [f32; 2],
+        distractor_color: [f32; 4],
+        distractor_ring: [f32; 2],
+        distractor_seed: u64,
+        pyramid_type: u32,
+        door_hole_radius_fraction: f32,
+        door_frame_thickness: f32,
+        door_inset: f32,
+        door_reward_weights: [f32; 8],
+        target_kind: u32,
+        target_face: u32,
+        camera_fov_degrees: f32,
+        camera_near: f32,
+        camera_far: f32,
+        spotlight_outer_angle: f32,
+        spotlight_range: f32,
+        win_glow_color: [f32; 4],
+        fog_enabled: bool,
+        fog_color: [f32; 4],
+        fog_start: f32,
+        fog_end: f32,
+        projection_mode: u32,
+        ortho_scale_range: [f32; 2],
+        color_space: u32,
+        backdrop_enabled: bool,
+        config_mode: u32,
+        geometry_seed: u64,
+        base_radius_range: [f32; 2],
+        height_range: [f32; 2],
+        start_orient_range: [f32; 2],
+        stimulus_file: Option<String>,
+        stimulus_expected_hash: u64,
+        trial_id: Option<String>,
+        staged: bool,
     ) -> PyResult<()> {
         if colors.len() != 3 || colors.iter().any(|face| face.len() != 4) {
             return Err(PyErr::new::<PyValueError, _>(format!(
@@ -149,17 +577,146 @@ impl SharedMemoryWrapper {
                 colors.iter().map(|face| face.len()).collect::<Vec<_>>()
             )));
         }
+        let face_colors = std::array::from_fn(|face| std::array::from_fn(|c| colors[face][c]));
+        if let Err(e) = validate_pyramid_type(pyramid_type, &face_colors) {
+            return Err(PyErr::new::<PyValueError, _>(e.to_string()));
+        }
+        match ConfigMode::from_u32(config_mode) {
+            None => return Err(PyErr::new::<PyValueError, _>(format!("unknown config_mode code {config_mode}"))),
+            Some(ConfigMode::Randomized) => validate_geometry_ranges(base_radius_range, height_range, start_orient_range)
+                .map_err(PyErr::new::<PyValueError, _>)?,
+            Some(ConfigMode::Explicit) => {}
+        }
+        if ColorSpace::from_u32(color_space).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown color_space code {color_space}")));
+        }
+        if TargetKind::from_u32(target_kind).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown target_kind code {target_kind}")));
+        }
+        if let Some(face) = decorations_count_range.iter().position(|[min, max]| *max != 0 && min > max) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "decorations_count_range of face {face} must satisfy min <= max (max 0 disables it), got {:?}",
+                decorations_count_range[face]
+            )));
+        }
+        if !(decoration_spacing > 0.0 && decoration_spacing.is_finite()) {
+            return Err(PyErr::new::<PyValueError, _>(format!("decoration_spacing must be positive, got {decoration_spacing}")));
+        }
+        validate_decoration_colors(&decoration_colors).map_err(PyErr::new::<PyValueError, _>)?;
+        if !(0.0..=1.0).contains(&decoration_color_jitter) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "decoration_color_jitter must be in [0, 1], got {decoration_color_jitter}"
+            )));
+        }
+        if !decoration_spin_rate.is_finite() {
+            return Err(PyErr::new::<PyValueError, _>(format!("decoration_spin_rate must be finite, got {decoration_spin_rate}")));
+        }
+        if DecorationMotion::from_u32(decoration_motion).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown decoration_motion code {decoration_motion}")));
+        }
+        validate_decoration_pulse(decoration_pulse_amplitude, decoration_pulse_hz).map_err(PyErr::new::<PyValueError, _>)?;
+        if !(outline_width >= 0.0 && outline_width.is_finite()) {
+            return Err(PyErr::new::<PyValueError, _>(format!("outline_width must be >= 0 (0 = no outline), got {outline_width}")));
+        }
+        if !(ui_scale >= 0.0 && ui_scale.is_finite()) {
+            return Err(PyErr::new::<PyValueError, _>(format!("ui_scale must be >= 0 (0 = scale with the viewport), got {ui_scale}")));
+        }
+        if target_face >= 3 {
+            return Err(PyErr::new::<PyValueError, _>(format!("target_face must be 0, 1 or 2, got {target_face}")));
+        }
+        // Degenerate doors (hole larger than the panel) would produce broken meshes
+        let widest_radius = widest_base_radius(config_mode, base_radius, base_radius_range);
+        validate_door_geometry(widest_radius, door_hole_radius_fraction, door_frame_thickness, door_inset)
+            .map_err(PyErr::new::<PyValueError, _>)?;
+        validate_door_reward_weights(&door_reward_weights).map_err(PyErr::new::<PyValueError, _>)?;
+        validate_fog(fog_start, fog_end).map_err(PyErr::new::<PyValueError, _>)?;
+        validate_score_bar(score_bar_anchor, score_bar_width_percent, score_bar_height).map_err(PyErr::new::<PyValueError, _>)?;
+        if let Some(path) = &stimulus_file {
+            validate_stimulus_file(path).map_err(PyErr::new::<PyValueError, _>)?;
+        }
+        validate_camera_projection(camera_fov_degrees, camera_near, camera_far)
+            .map_err(PyErr::new::<PyValueError, _>)?;
+        if !(spotlight_outer_angle > 0.0 && spotlight_outer_angle <= std::f32::consts::FRAC_PI_2) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "spotlight_outer_angle must be in (0, PI/2] radians, got {spotlight_outer_angle}"
+            )));
+        }
+        if ProjectionMode::from_u32(projection_mode).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown projection_mode code {projection_mode}")));
+        }
+        if !(ortho_scale_range[0] > 0.0 && ortho_scale_range[0] <= ortho_scale_range[1]) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "ortho_scale_range must be positive with min <= max, got {ortho_scale_range:?}"
+            )));
+        }
+        if spotlight_range <= 0.0 || spotlight_range.is_nan() {
+            return Err(PyErr::new::<PyValueError, _>(format!("spotlight_range must be positive, got {spotlight_range}")));
+        }
+        if trial_timeout_secs < 0.0 || trial_timeout_secs.is_nan() {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "trial_timeout_secs must be >= 0 (0 = no timeout), got {trial_timeout_secs}"
+            )));
+        }
+        // A catch trial can never be won, so it needs another way to end
+        if is_catch && max_attempts == 0 && trial_timeout_secs == 0.0 {
+            return Err(PyErr::new::<PyValueError, _>(
+                "catch trials cannot be won and need max_attempts or trial_timeout_secs to end",
+            ));
+        }
+        if win_radius_max != 0.0 && !(win_radius_min >= 0.0 && win_radius_min <= win_radius_max) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "win radius band must satisfy 0 <= min <= max (max 0 disables it), got [{win_radius_min}, {win_radius_max}]"
+            )));
+        }
+        if !(-1..=1).contains(&required_approach_sign) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "required_approach_sign must be -1, 0 or 1, got {required_approach_sign}"
+            )));
+        }
+        if !(min_view_secs >= 0.0 && min_view_secs.is_finite()) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "min_view_secs must be >= 0 (0 = no minimum), got {min_view_secs}"
+            )));
+        }
+        if PostWinMode::from_u32(post_win_mode).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown post_win_mode code {post_win_mode}")));
+        }
+        if PauseMode::from_u32(pause_mode).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown pause_mode code {pause_mode}")));
+        }
+        if AnimationEasing::from_u32(door_anim_easing).is_none() {
+            return Err(PyErr::new::<PyValueError, _>(format!("unknown door_anim_easing code {door_anim_easing}")));
+        }
+        if live_feedback_gain <= 0.0 || live_feedback_gain.is_nan() {
+            return Err(PyErr::new::<PyValueError, _>(format!("live_feedback_gain must be positive, got {live_feedback_gain}")));
+        }
+        if !(0.0..1.0).contains(&live_feedback_smoothing) {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "live_feedback_smoothing must be in [0, 1), got {live_feedback_smoothing}"
+            )));
+        }
 
         let shm = self.inner.get();
-        let gs = &shm.game_structure_control;
+        let gs = if staged {
+            // The game is still copying the previous staged config
+            if shm.commands.promote_staged.load(Ordering::Acquire) {
+                return Err(PyErr::new::<PyRuntimeError, _>("previous staged config has not been promoted yet"));
+            }
+            &shm.game_structure_staged
+        } else {
+            &shm.game_structure_control
+        };
 
-        for i in 0..3 {
-            gs.decoration_seeds[i].store(decoration_seeds[i], Ordering::Relaxed);
+        for (slot, seed) in gs.decoration_seeds.iter().zip(decoration_seeds) {
+            slot.store(seed, Ordering::Relaxed);
         }
         gs.base_radius.store(base_radius.to_bits(), Ordering::Relaxed);
         gs.height.store(height.to_bits(), Ordering::Relaxed);
         gs.start_orient.store(start_orient.to_bits(), Ordering::Relaxed);
         gs.target_door.store(target_door, Ordering::Relaxed);
+        gs.pyramid_type.store(pyramid_type, Ordering::Relaxed);
+        gs.target_kind.store(target_kind, Ordering::Relaxed);
+        gs.target_face.store(target_face, Ordering::Relaxed);
 
         for (face_idx, face) in colors.iter().enumerate() {
             for (channel_idx, value) in face.iter().enumerate() {
@@ -167,62 +724,847 @@ impl SharedMemoryWrapper {
                 gs.colors[index].store(value.to_bits(), Ordering::Relaxed);
             }
         }
+        gs.color_space.store(color_space, Ordering::Relaxed);
         
         // Store decorations
         for i in 0..3 {
             gs.decorations_count[i].store(decorations_count[i], Ordering::Relaxed);
+            gs.decorations_count_range[i * 2].store(decorations_count_range[i][0], Ordering::Relaxed);
+            gs.decorations_count_range[i * 2 + 1].store(decorations_count_range[i][1], Ordering::Relaxed);
             gs.decorations_size[i].store(decorations_size[i].to_bits(), Ordering::Relaxed);
+            gs.flicker_hz[i].store(flicker_hz[i].to_bits(), Ordering::Relaxed);
+            gs.flicker_depth[i].store(flicker_depth[i].to_bits(), Ordering::Relaxed);
+        }
+        gs.decoration_spacing.store(decoration_spacing.to_bits(), Ordering::Relaxed);
+        gs.decoration_spin_rate.store(decoration_spin_rate.to_bits(), Ordering::Relaxed);
+        gs.decoration_motion.store(decoration_motion, Ordering::Relaxed);
+        gs.decoration_pulse_amplitude.store(decoration_pulse_amplitude.to_bits(), Ordering::Relaxed);
+        gs.decoration_pulse_hz.store(decoration_pulse_hz.to_bits(), Ordering::Relaxed);
+        gs.decoration_symmetry.store(decoration_symmetry, Ordering::Relaxed);
+        for (face, color) in decoration_colors.iter().enumerate() {
+            for (channel, value) in color.iter().enumerate() {
+                gs.decoration_colors[face * 4 + channel].store(value.to_bits(), Ordering::Relaxed);
+            }
+        }
+        gs.decoration_color_jitter.store(decoration_color_jitter.to_bits(), Ordering::Relaxed);
+        gs.outline_width.store(outline_width.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs.outline_color.iter().zip(outline_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs.door_hole_radius_fraction.store(door_hole_radius_fraction.to_bits(), Ordering::Relaxed);
+        gs.door_frame_thickness.store(door_frame_thickness.to_bits(), Ordering::Relaxed);
+        gs.door_inset.store(door_inset.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs.door_reward_weights.iter().zip(door_reward_weights) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
         }
         gs.cosine_alignment_threshold.store(cosine_alignment_threshold.to_bits(), Ordering::Relaxed);
+        gs.shaping_dwell_secs.store(shaping_dwell_secs.to_bits(), Ordering::Relaxed);
+        gs.suppress_blanked_attempts.store(suppress_blanked_attempts, Ordering::Relaxed);
+        gs.is_catch.store(is_catch, Ordering::Relaxed);
+        gs.max_attempts.store(max_attempts, Ordering::Relaxed);
+        gs.trial_timeout_secs.store(trial_timeout_secs.to_bits(), Ordering::Relaxed);
+        gs.win_radius_min.store(win_radius_min.to_bits(), Ordering::Relaxed);
+        gs.win_radius_max.store(win_radius_max.to_bits(), Ordering::Relaxed);
+        gs.required_approach_sign.store(required_approach_sign, Ordering::Relaxed);
+        gs.min_view_secs.store(min_view_secs.to_bits(), Ordering::Relaxed);
+        gs.feedback_delay_ms.store(feedback_delay_ms, Ordering::Relaxed);
+        for (slot, frames) in gs.debounce_frames.iter().zip(debounce_frames) {
+            slot.store(frames, Ordering::Relaxed);
+        }
+        gs.iti_frames.store(iti_frames, Ordering::Relaxed);
+        gs.post_win_mode.store(post_win_mode, Ordering::Relaxed);
+        gs.pause_mode.store(pause_mode, Ordering::Relaxed);
         gs.door_anim_fade_out.store(door_anim_fade_out.to_bits(), Ordering::Relaxed);
         gs.door_anim_stay_open.store(door_anim_stay_open.to_bits(), Ordering::Relaxed);
         gs.door_anim_fade_in.store(door_anim_fade_in.to_bits(), Ordering::Relaxed);
+        gs.door_anim_easing.store(door_anim_easing, Ordering::Relaxed);
         gs.main_spotlight_intensity.store(main_spotlight_intensity.to_bits(), Ordering::Relaxed);
         gs.ambient_brightness.store(ambient_brightness.to_bits(), Ordering::Relaxed);
         gs.max_spotlight_intensity.store(max_spotlight_intensity.to_bits(), Ordering::Relaxed);
+        gs.spotlight_outer_angle.store(spotlight_outer_angle.to_bits(), Ordering::Relaxed);
+        gs.spotlight_range.store(spotlight_range.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs.win_glow_color.iter().zip(win_glow_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs.fog_enabled.store(fog_enabled, Ordering::Relaxed);
+        for (slot, value) in gs.fog_color.iter().zip(fog_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs.fog_start.store(fog_start.to_bits(), Ordering::Relaxed);
+        gs.fog_end.store(fog_end.to_bits(), Ordering::Relaxed);
+
+        gs.ui_scale.store(ui_scale.to_bits(), Ordering::Relaxed);
+        gs.score_bar_anchor.store(score_bar_anchor, Ordering::Relaxed);
+        gs.score_bar_width_percent.store(score_bar_width_percent.to_bits(), Ordering::Relaxed);
+        gs.score_bar_height.store(score_bar_height.to_bits(), Ordering::Relaxed);
+        gs.score_bar_show_numeric.store(score_bar_show_numeric, Ordering::Relaxed);
+        gs.reticle_enabled.store(reticle_enabled, Ordering::Relaxed);
+        gs.reticle_size.store(reticle_size.to_bits(), Ordering::Relaxed);
+        for (slot, value) in gs.reticle_color.iter().zip(reticle_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs.reticle_style.store(reticle_style, Ordering::Relaxed);
+        gs.reticle_alignment_tint.store(reticle_alignment_tint, Ordering::Relaxed);
+        gs.live_feedback_enabled.store(live_feedback_enabled, Ordering::Relaxed);
+        gs.live_feedback_gain.store(live_feedback_gain.to_bits(), Ordering::Relaxed);
+        gs.live_feedback_smoothing.store(live_feedback_smoothing.to_bits(), Ordering::Relaxed);
+        gs.control_mirror_rotation.store(control_mirror_rotation, Ordering::Relaxed);
+        gs.control_mirror_zoom.store(control_mirror_zoom, Ordering::Relaxed);
+        gs.mirror_x.store(mirror_x, Ordering::Relaxed);
+        gs.recenter_on_reset.store(recenter_on_reset, Ordering::Relaxed);
+        gs.camera_fov_degrees.store(camera_fov_degrees.to_bits(), Ordering::Relaxed);
+        gs.camera_near.store(camera_near.to_bits(), Ordering::Relaxed);
+        gs.camera_far.store(camera_far.to_bits(), Ordering::Relaxed);
+        gs.projection_mode.store(projection_mode, Ordering::Relaxed);
+        for (slot, value) in gs.ortho_scale_range.iter().zip(ortho_scale_range) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+
+        gs.distractor_count.store(distractor_count, Ordering::Relaxed);
+        gs.distractor_shape.store(distractor_shape, Ordering::Relaxed);
+        for i in 0..2 {
+            gs.distractor_size_range[i].store(distractor_size_range[i].to_bits(), Ordering::Relaxed);
+            gs.distractor_ring[i].store(distractor_ring[i].to_bits(), Ordering::Relaxed);
+        }
+        for (slot, value) in gs.distractor_color.iter().zip(distractor_color) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        gs.distractor_seed.store(distractor_seed, Ordering::Relaxed);
+        gs.backdrop_enabled.store(backdrop_enabled, Ordering::Relaxed);
+        gs.config_mode.store(config_mode, Ordering::Relaxed);
+        gs.geometry_seed.store(geometry_seed, Ordering::Relaxed);
+        for i in 0..2 {
+            gs.base_radius_range[i].store(base_radius_range[i].to_bits(), Ordering::Relaxed);
+            gs.height_range[i].store(height_range[i].to_bits(), Ordering::Relaxed);
+            gs.start_orient_range[i].store(start_orient_range[i].to_bits(), Ordering::Relaxed);
+        }
+        gs.set_stimulus_file(stimulus_file.as_deref().unwrap_or(""));
+        gs.stimulus_expected_hash.store(stimulus_expected_hash, Ordering::Relaxed);
+        gs.trial_id_hash.store(crate::trial_id_hash(trial_id.as_deref().unwrap_or("")), Ordering::Relaxed);
         Ok(())
     }
 
+    /// Write the next trial's config into the staged block (same arguments as write_game_structure).
+    /// It takes effect once promoted with `write_commands(..., promote_staged=True)`.
+    #[pyo3(signature = (*args, **kwargs))]
+    fn write_staged_config(
+        slf: &Bound<'_, Self>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<()> {
+        let kwargs = match kwargs {
+            Some(kwargs) => kwargs.copy()?,
+            None => PyDict::new(slf.py()),
+        };
+        kwargs.set_item("staged", true)?;
+        slf.call_method("write_game_structure", args, Some(&kwargs))?;
+        Ok(())
+    }
+
+    /// Whether a promote_staged request is still waiting for the game to copy the staged block.
+    fn staged_pending(&self) -> bool {
+        self.inner.get().commands.promote_staged.load(Ordering::Acquire)
+    }
+
+    /// Command readback, to tell a command the game never saw from one it ignored:
+    /// `raw` holds the command fields as currently written (flags as 0/1, u32 commands with their
+    /// idle value), `seen` the commands the game read set on its latest frame (`seen_mask` as bits)
+    /// and `counts` the frames each was read set since the region was created.
+    /// Keys are SEEN_COMMAND_NAMES.
+    fn read_commands(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let shm = self.inner.get();
+        let gs = &shm.game_structure_game;
+        let raw = PyDict::new(py);
+        for (name, value) in SEEN_COMMAND_NAMES.iter().zip(shm.commands.raw_values()) {
+            raw.set_item(name, value)?;
+        }
+        let mask = gs.commands_seen.load(Ordering::Relaxed);
+        let dict = PyDict::new(py);
+        dict.set_item("raw", raw)?;
+        dict.set_item("seen", seen_command_names(mask))?;
+        dict.set_item("seen_mask", mask)?;
+        dict.set_item("counts", seen_counts_dict(py, gs)?)?;
+        Ok(dict.into())
+    }
+
 
 }
 
+/// Deadline `timeout_secs` from now, None to wait forever; a negative timeout has already passed.
+/// Infinite and NaN timeouts are rejected, since no Duration holds them
+fn wait_deadline(timeout_secs: Option<f32>) -> PyResult<Option<Instant>> {
+    match timeout_secs {
+        Some(secs) if !secs.is_finite() => {
+            Err(PyErr::new::<PyValueError, _>(format!("timeout_secs must be finite (None waits forever), got {secs}")))
+        }
+        Some(secs) => Ok(Some(Instant::now() + Duration::from_secs_f32(secs.max(0.0)))),
+        None => Ok(None),
+    }
+}
+
+/// Tutorial trials as trial config dicts, to be merged over the default config.
+/// Without a decoration seed, the geometry seed draws the decorations too.
+#[pyfunction]
+#[pyo3(signature = (geometry_seed, decoration_seed=None))]
+fn generate_tutorial_trials(py: Python<'_>, geometry_seed: u64, decoration_seed: Option<u64>) -> PyResult<Vec<Py<PyAny>>> {
+    use crate::constants::game_constants::TUTORIAL_SHAPING_DWELL_SECS;
+    crate::tutorial::generate_tutorial_trials(geometry_seed, decoration_seed)
+        .into_iter()
+        .map(|trial| {
+            let dict = pyo3::types::PyDict::new(py);
+            dict.set_item("decoration_seeds", trial.decoration_seeds)?;
+            dict.set_item("start_orient", trial.start_orient)?;
+            dict.set_item("target_door", trial.target_door)?;
+            dict.set_item("cosine_alignment_threshold", trial.cosine_alignment_threshold)?;
+            dict.set_item("decorations_count", trial.decorations_count)?;
+            dict.set_item("shaping_dwell_secs", if trial.practice { TUTORIAL_SHAPING_DWELL_SECS } else { 0.0 })?;
+            dict.set_item("live_feedback_enabled", trial.practice)?;
+            Ok(dict.into_any().unbind())
+        })
+        .collect()
+}
+
+/// Hash the game echoes as `trial_id_hash` for a trial ID (0 for None or "")
+#[pyfunction]
+#[pyo3(signature = (trial_id=None))]
+fn trial_id_hash(trial_id: Option<&str>) -> u64 {
+    crate::trial_id_hash(trial_id.unwrap_or(""))
+}
+
+/// Approximately equiluminant face colors (RGBA lists) with evenly spread hues, see palette.rs
+#[pyfunction]
+#[pyo3(signature = (count=PALETTE_SIZE, luma=PALETTE_LUMA, hue_separation=None, seed=0))]
+fn generate_palette(count: usize, luma: f32, hue_separation: Option<f32>, seed: u64) -> PyResult<Vec<[f32; 4]>> {
+    crate::palette::generate_palette(count, luma, hue_separation, seed).map_err(PyErr::new::<PyValueError, _>)
+}
+
+/// Deuteranopia-safe preset face colors (RGBA lists) at one luma, see palette.rs
+#[pyfunction]
+#[pyo3(signature = (luma=PALETTE_LUMA))]
+fn deuteranopia_safe_palette(luma: f32) -> PyResult<Vec<[f32; 4]>> {
+    crate::palette::deuteranopia_safe_palette(luma).map_err(PyErr::new::<PyValueError, _>)
+}
+
+/// Face colors of a pyramid type drawn from a palette of RGBA colors, see palette.rs
+#[pyfunction]
+#[pyo3(signature = (palette, seed, pyramid_type=PyramidType::Type1 as u32))]
+fn sample_face_colors(palette: Vec<[f32; 4]>, seed: u64, pyramid_type: u32) -> PyResult<[[f32; 4]; 3]> {
+    let pyramid_type = PyramidType::from_u32(pyramid_type)
+        .ok_or_else(|| PyErr::new::<PyValueError, _>(format!("unknown pyramid_type code {pyramid_type}")))?;
+    crate::palette::sample_face_colors(&palette, seed, pyramid_type).map_err(PyErr::new::<PyValueError, _>)
+}
+
+/// Rec.709 luma of an RGB(A) color
+#[pyfunction]
+fn rec709_luma(color: Vec<f32>) -> PyResult<f32> {
+    match color[..] {
+        [r, g, b] | [r, g, b, _] => Ok(crate::palette::rec709_luma([r, g, b, 1.0])),
+        _ => Err(PyErr::new::<PyValueError, _>(format!("expected an RGB or RGBA color, got {} channels", color.len()))),
+    }
+}
+
+/// JSON Schema of each write_game_structure argument as a JSON object, see json_api::config_field_schemas
+#[pyfunction]
+fn config_field_schemas() -> String {
+    serde_json::Value::Object(crate::json_api::config_field_schemas()).to_string()
+}
+
+/// Adaptive alignment threshold staircase (see staircase.rs)
+#[pyclass(name = "Staircase")]
+#[derive(Clone)]
+struct StaircaseWrapper {
+    inner: crate::staircase::Staircase,
+}
+
+#[pymethods]
+impl StaircaseWrapper {
+    #[new]
+    #[pyo3(signature = (threshold, step=STAIRCASE_STEP, down=STAIRCASE_DOWN, up=STAIRCASE_UP, range=STAIRCASE_COSINE_RANGE))]
+    fn new(threshold: f32, step: f32, down: u32, up: u32, range: [f32; 2]) -> PyResult<Self> {
+        if step <= 0.0 || step.is_nan() || range[0] > range[1] || range.iter().any(|v| v.is_nan()) || range[1] >= 1.0 {
+            return Err(PyErr::new::<PyValueError, _>(format!(
+                "staircase needs a positive step and min <= max < 1, got step {step}, range {range:?}"
+            )));
+        }
+        Ok(Self { inner: crate::staircase::Staircase::with_rule(threshold, step, down, up, range) })
+    }
+
+    /// Record a trial outcome, returning the threshold for the next trial
+    fn update(&mut self, won: bool) -> f32 {
+        self.inner.update(won)
+    }
+
+    #[getter]
+    fn threshold(&self) -> f32 {
+        self.inner.threshold
+    }
+
+    #[getter]
+    fn reversals(&self) -> u32 {
+        self.inner.reversals
+    }
+}
+
+/// Session progress for crash recovery (see session.rs), saved atomically
+#[pyclass(name = "SessionState")]
+struct SessionStateWrapper {
+    inner: crate::session::SessionState,
+}
+
+#[pymethods]
+impl SessionStateWrapper {
+    #[new]
+    #[pyo3(signature = (results_log, staircase_log="", trial_index=0, trials_done=0, wins=0, staircase=None))]
+    fn new(
+        results_log: &str,
+        staircase_log: &str,
+        trial_index: u64,
+        trials_done: u32,
+        wins: u32,
+        staircase: Option<PyRef<'_, StaircaseWrapper>>,
+    ) -> Self {
+        Self {
+            inner: crate::session::SessionState {
+                trial_index,
+                trials_done,
+                wins,
+                results_log: results_log.to_string(),
+                staircase_log: staircase_log.to_string(),
+                staircase: staircase.map(|staircase| staircase.inner.clone()),
+            },
+        }
+    }
+
+    /// Read a session file written by `save`
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        crate::session::SessionState::load(std::path::Path::new(path))
+            .map(|inner| Self { inner })
+            .map_err(PyErr::new::<PyValueError, _>)
+    }
+
+    /// Write the session file, replacing the previous one only once the new one is complete
+    fn save(&self, path: &str) -> PyResult<()> {
+        self.inner
+            .save(std::path::Path::new(path))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyIOError, _>(e.to_string()))
+    }
+
+    /// Count a finished trial and its outcome
+    fn record_trial(&mut self, won: bool) {
+        self.inner.trials_done += 1;
+        self.inner.wins += won as u32;
+    }
+
+    #[getter]
+    fn trial_index(&self) -> u64 {
+        self.inner.trial_index
+    }
+
+    #[setter]
+    fn set_trial_index(&mut self, trial_index: u64) {
+        self.inner.trial_index = trial_index;
+    }
+
+    #[getter]
+    fn trials_done(&self) -> u32 {
+        self.inner.trials_done
+    }
+
+    #[getter]
+    fn wins(&self) -> u32 {
+        self.inner.wins
+    }
+
+    #[getter]
+    fn results_log(&self) -> String {
+        self.inner.results_log.clone()
+    }
+
+    #[getter]
+    fn staircase_log(&self) -> String {
+        self.inner.staircase_log.clone()
+    }
+
+    #[setter]
+    fn set_staircase_log(&mut self, staircase_log: &str) {
+        self.inner.staircase_log = staircase_log.to_string();
+    }
+
+    /// A copy of the saved staircase, None without one
+    #[getter]
+    fn staircase(&self) -> Option<StaircaseWrapper> {
+        self.inner.staircase.clone().map(|inner| StaircaseWrapper { inner })
+    }
+
+    #[setter]
+    fn set_staircase(&mut self, staircase: Option<PyRef<'_, StaircaseWrapper>>) {
+        self.inner.staircase = staircase.map(|staircase| staircase.inner.clone());
+    }
+}
+
+/// Build the Python dictionary of a state snapshot, keyed like the full state dictionary
+fn snapshot_to_dict(py: Python<'_>, snapshot: &StateSnapshot) -> PyResult<Py<PyAny>> {
+    let dict = pyo3::types::PyDict::new(py);
+    dict.set_item("frame_number", snapshot.frame_number)?;
+    dict.set_item("reset_ack", snapshot.reset_ack)?;
+    dict.set_item("trial_generation", snapshot.trial_generation)?;
+    dict.set_item("phase", snapshot.phase)?;
+    dict.set_item("iti_remaining_frames", snapshot.iti_remaining_frames)?;
+    dict.set_item("elapsed_secs", snapshot.elapsed_secs)?;
+    dict.set_item("nr_attempts", snapshot.attempts)?;
+    dict.set_item("rejected_checks", snapshot.rejected_checks)?;
+    dict.set_item("win_elapsed_secs", snapshot.win_elapsed_secs)?;
+    dict.set_item("is_animating", snapshot.is_animating)?;
+    dict.set_item("camera_radius", snapshot.camera_radius)?;
+    dict.set_item("camera_position", snapshot.camera_position.to_vec())?;
+    dict.set_item("pyramid_yaw", snapshot.pyramid_yaw)?;
+    dict.set_item("cosine_alignment", snapshot.cosine_alignment)?;
+    dict.set_item("current_angle", snapshot.current_angle)?;
+    dict.set_item("best_door", snapshot.best_door)?;
+    dict.set_item("tab_hidden", snapshot.tab_hidden)?;
+    dict.set_item("tab_hidden_secs_trial", snapshot.tab_hidden_secs_trial)?;
+    Ok(dict.into_any().unbind())
+}
+
+/// Trial config fields of a game structure region into `dict` (f32 fields read from their bits)
+fn config_to_dict(dict: &Bound<'_, PyDict>, gs: &SharedGameStructure) -> PyResult<()> {
+    // Fixed vars in trial
+    dict.set_item("decoration_seeds", [
+        gs.decoration_seeds[0].load(Ordering::Relaxed),
+        gs.decoration_seeds[1].load(Ordering::Relaxed),
+        gs.decoration_seeds[2].load(Ordering::Relaxed),
+    ])?;
+    dict.set_item("base_radius", f32::from_bits(gs.base_radius.load(Ordering::Relaxed)))?;
+    dict.set_item("height", f32::from_bits(gs.height.load(Ordering::Relaxed)))?;
+    dict.set_item("start_orient", f32::from_bits(gs.start_orient.load(Ordering::Relaxed)))?;
+    let range = |range: &[AtomicU32; 2]| range.each_ref().map(|bound| f32::from_bits(bound.load(Ordering::Relaxed)));
+    dict.set_item("config_mode", gs.config_mode.load(Ordering::Relaxed))?;
+    dict.set_item("geometry_seed", gs.geometry_seed.load(Ordering::Relaxed))?;
+    dict.set_item("base_radius_range", range(&gs.base_radius_range))?;
+    dict.set_item("height_range", range(&gs.height_range))?;
+    dict.set_item("start_orient_range", range(&gs.start_orient_range))?;
+    dict.set_item("target_door", gs.target_door.load(Ordering::Relaxed))?;
+    dict.set_item("pyramid_type", gs.pyramid_type.load(Ordering::Relaxed))?;
+    dict.set_item("target_kind", gs.target_kind.load(Ordering::Relaxed))?;
+    dict.set_item("target_face", gs.target_face.load(Ordering::Relaxed))?;
+    let mut colors: Vec<Vec<f32>> = Vec::with_capacity(3);  // Colors as 3x4 list
+    for face_idx in 0..3 {
+        let mut face_colors: Vec<f32> = Vec::with_capacity(4);
+        for channel_idx in 0..4 {
+            let index = face_idx * 4 + channel_idx;
+            face_colors.push(f32::from_bits(gs.colors[index].load(Ordering::Relaxed)));
+        }
+        colors.push(face_colors);
+    }
+    dict.set_item("colors", colors)?;
+    dict.set_item("color_space", gs.color_space.load(Ordering::Relaxed))?;
+
+    dict.set_item("main_spotlight_intensity", f32::from_bits(gs.main_spotlight_intensity.load(Ordering::Relaxed)))?;
+    dict.set_item("ambient_brightness", f32::from_bits(gs.ambient_brightness.load(Ordering::Relaxed)))?;
+    dict.set_item("max_spotlight_intensity", f32::from_bits(gs.max_spotlight_intensity.load(Ordering::Relaxed)))?;
+    dict.set_item("spotlight_outer_angle", f32::from_bits(gs.spotlight_outer_angle.load(Ordering::Relaxed)))?;
+    dict.set_item("spotlight_range", f32::from_bits(gs.spotlight_range.load(Ordering::Relaxed)))?;
+    dict.set_item("win_glow_color", gs.win_glow_color.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("fog_enabled", gs.fog_enabled.load(Ordering::Relaxed))?;
+    dict.set_item("fog_color", gs.fog_color.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("fog_start", f32::from_bits(gs.fog_start.load(Ordering::Relaxed)))?;
+    dict.set_item("fog_end", f32::from_bits(gs.fog_end.load(Ordering::Relaxed)))?;
+    dict.set_item("decoration_count", [
+        gs.decorations_count[0].load(Ordering::Relaxed),
+        gs.decorations_count[1].load(Ordering::Relaxed),
+        gs.decorations_count[2].load(Ordering::Relaxed)
+    ])?;
+    dict.set_item("decorations_count_range", (0..3).map(|face| [
+        gs.decorations_count_range[face * 2].load(Ordering::Relaxed),
+        gs.decorations_count_range[face * 2 + 1].load(Ordering::Relaxed),
+    ]).collect::<Vec<[u32; 2]>>())?;
+    dict.set_item("decoration_size", [
+        f32::from_bits(gs.decorations_size[0].load(Ordering::Relaxed)),
+        f32::from_bits(gs.decorations_size[1].load(Ordering::Relaxed)),
+        f32::from_bits(gs.decorations_size[2].load(Ordering::Relaxed))
+    ])?;
+    dict.set_item("decoration_spacing", f32::from_bits(gs.decoration_spacing.load(Ordering::Relaxed)))?;
+    dict.set_item("decoration_spin_rate", f32::from_bits(gs.decoration_spin_rate.load(Ordering::Relaxed)))?;
+    dict.set_item("decoration_motion", gs.decoration_motion.load(Ordering::Relaxed))?;
+    dict.set_item("decoration_pulse_amplitude", f32::from_bits(gs.decoration_pulse_amplitude.load(Ordering::Relaxed)))?;
+    dict.set_item("decoration_pulse_hz", f32::from_bits(gs.decoration_pulse_hz.load(Ordering::Relaxed)))?;
+    dict.set_item("decoration_symmetry", gs.decoration_symmetry.load(Ordering::Relaxed))?;
+    dict.set_item("decoration_colors", (0..3).map(|face| {
+        (0..4).map(|channel| f32::from_bits(gs.decoration_colors[face * 4 + channel].load(Ordering::Relaxed))).collect::<Vec<f32>>()
+    }).collect::<Vec<Vec<f32>>>())?;
+    dict.set_item("decoration_color_jitter", f32::from_bits(gs.decoration_color_jitter.load(Ordering::Relaxed)))?;
+    dict.set_item("outline_width", f32::from_bits(gs.outline_width.load(Ordering::Relaxed)))?;
+    dict.set_item("outline_color", gs.outline_color.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("door_hole_radius_fraction", f32::from_bits(gs.door_hole_radius_fraction.load(Ordering::Relaxed)))?;
+    dict.set_item("door_frame_thickness", f32::from_bits(gs.door_frame_thickness.load(Ordering::Relaxed)))?;
+    dict.set_item("door_inset", f32::from_bits(gs.door_inset.load(Ordering::Relaxed)))?;
+    dict.set_item("door_reward_weights", gs.door_reward_weights.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("flicker_hz", gs.flicker_hz.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("ui_scale", f32::from_bits(gs.ui_scale.load(Ordering::Relaxed)))?;
+    dict.set_item("score_bar_anchor", gs.score_bar_anchor.load(Ordering::Relaxed))?;
+    dict.set_item("score_bar_width_percent", f32::from_bits(gs.score_bar_width_percent.load(Ordering::Relaxed)))?;
+    dict.set_item("score_bar_height", f32::from_bits(gs.score_bar_height.load(Ordering::Relaxed)))?;
+    dict.set_item("score_bar_show_numeric", gs.score_bar_show_numeric.load(Ordering::Relaxed))?;
+    dict.set_item("reticle_enabled", gs.reticle_enabled.load(Ordering::Relaxed))?;
+    dict.set_item("reticle_size", f32::from_bits(gs.reticle_size.load(Ordering::Relaxed)))?;
+    dict.set_item("reticle_color", gs.reticle_color.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("reticle_style", gs.reticle_style.load(Ordering::Relaxed))?;
+    dict.set_item("reticle_alignment_tint", gs.reticle_alignment_tint.load(Ordering::Relaxed))?;
+    dict.set_item("live_feedback_enabled", gs.live_feedback_enabled.load(Ordering::Relaxed))?;
+    dict.set_item("live_feedback_gain", f32::from_bits(gs.live_feedback_gain.load(Ordering::Relaxed)))?;
+    dict.set_item("live_feedback_smoothing", f32::from_bits(gs.live_feedback_smoothing.load(Ordering::Relaxed)))?;
+    dict.set_item("control_mirror_rotation", gs.control_mirror_rotation.load(Ordering::Relaxed))?;
+    dict.set_item("control_mirror_zoom", gs.control_mirror_zoom.load(Ordering::Relaxed))?;
+    dict.set_item("mirror_x", gs.mirror_x.load(Ordering::Relaxed))?;
+    dict.set_item("recenter_on_reset", gs.recenter_on_reset.load(Ordering::Relaxed))?;
+    dict.set_item("camera_fov_degrees", f32::from_bits(gs.camera_fov_degrees.load(Ordering::Relaxed)))?;
+    dict.set_item("camera_near", f32::from_bits(gs.camera_near.load(Ordering::Relaxed)))?;
+    dict.set_item("camera_far", f32::from_bits(gs.camera_far.load(Ordering::Relaxed)))?;
+    dict.set_item("projection_mode", gs.projection_mode.load(Ordering::Relaxed))?;
+    dict.set_item("ortho_scale_range", gs.ortho_scale_range.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("distractor_count", gs.distractor_count.load(Ordering::Relaxed))?;
+    dict.set_item("distractor_shape", gs.distractor_shape.load(Ordering::Relaxed))?;
+    dict.set_item("distractor_size_range", gs.distractor_size_range.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("distractor_color", gs.distractor_color.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("distractor_ring", gs.distractor_ring.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("distractor_seed", gs.distractor_seed.load(Ordering::Relaxed))?;
+    dict.set_item("backdrop_enabled", gs.backdrop_enabled.load(Ordering::Relaxed))?;
+    dict.set_item("stimulus_file", gs.stimulus_file())?;
+    dict.set_item("stimulus_expected_hash", gs.stimulus_expected_hash.load(Ordering::Relaxed))?;
+    dict.set_item("trial_id_hash", gs.trial_id_hash.load(Ordering::Relaxed))?;
+    dict.set_item("flicker_depth", gs.flicker_depth.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+
+    // Dynamic vars in trial
+    dict.set_item("cosine_alignment_threshold", f32::from_bits(gs.cosine_alignment_threshold.load(Ordering::Relaxed)))?;
+    dict.set_item("shaping_dwell_secs", f32::from_bits(gs.shaping_dwell_secs.load(Ordering::Relaxed)))?;
+    dict.set_item("suppress_blanked_attempts", gs.suppress_blanked_attempts.load(Ordering::Relaxed))?;
+    dict.set_item("is_catch", gs.is_catch.load(Ordering::Relaxed))?;
+    dict.set_item("max_attempts", gs.max_attempts.load(Ordering::Relaxed))?;
+    dict.set_item("trial_timeout_secs", f32::from_bits(gs.trial_timeout_secs.load(Ordering::Relaxed)))?;
+    dict.set_item("win_radius_min", f32::from_bits(gs.win_radius_min.load(Ordering::Relaxed)))?;
+    dict.set_item("win_radius_max", f32::from_bits(gs.win_radius_max.load(Ordering::Relaxed)))?;
+    dict.set_item("required_approach_sign", gs.required_approach_sign.load(Ordering::Relaxed))?;
+    dict.set_item("min_view_secs", f32::from_bits(gs.min_view_secs.load(Ordering::Relaxed)))?;
+    dict.set_item("feedback_delay_ms", gs.feedback_delay_ms.load(Ordering::Relaxed))?;
+    dict.set_item("debounce_frames", gs.debounce_frames.each_ref().map(|frames| frames.load(Ordering::Relaxed)))?;
+    dict.set_item("iti_frames", gs.iti_frames.load(Ordering::Relaxed))?;
+    dict.set_item("post_win_mode", gs.post_win_mode.load(Ordering::Relaxed))?;
+    dict.set_item("pause_mode", gs.pause_mode.load(Ordering::Relaxed))?;
+    dict.set_item("door_anim_fade_out", f32::from_bits(gs.door_anim_fade_out.load(Ordering::Relaxed)))?;
+    dict.set_item("door_anim_stay_open", f32::from_bits(gs.door_anim_stay_open.load(Ordering::Relaxed)))?;
+    dict.set_item("door_anim_fade_in", f32::from_bits(gs.door_anim_fade_in.load(Ordering::Relaxed)))?;
+    dict.set_item("door_anim_easing", gs.door_anim_easing.load(Ordering::Relaxed))?;
+
+    Ok(())
+}
+
+/// Build the Python state dictionary from the game region: the applied config and the state at the
+/// top level, plus the config alone under `applied` (with the decorations actually placed) and the
+/// controller region's config, as requested, under `requested`. The applied config is the one of
+/// trial generation `trial_generation`
+/// Per-command seen counts keyed by SEEN_COMMAND_NAMES
+fn seen_counts_dict<'py>(py: Python<'py>, gs: &SharedGameStructure) -> PyResult<Bound<'py, PyDict>> {
+    let counts = PyDict::new(py);
+    for (name, count) in SEEN_COMMAND_NAMES.iter().zip(&gs.commands_seen_counts) {
+        counts.set_item(name, count.load(Ordering::Relaxed))?;
+    }
+    Ok(counts)
+}
+
+fn game_structure_to_dict(py: Python<'_>, gs: &SharedGameStructure, requested: &SharedGameStructure) -> PyResult<Py<PyAny>> {
+    let dict = PyDict::new(py);
+    config_to_dict(&dict, gs)?;
+
+    let decorations_placed: Vec<u32> = gs.decorations_placed.iter().map(|v| v.load(Ordering::Relaxed)).collect();
+    let applied = PyDict::new(py);
+    config_to_dict(&applied, gs)?;
+    applied.set_item("decorations_placed", decorations_placed.clone())?;
+    dict.set_item("applied", applied)?;
+    let requested_dict = PyDict::new(py);
+    config_to_dict(&requested_dict, requested)?;
+    dict.set_item("requested", requested_dict)?;
+
+    dict.set_item("decorations_placed", decorations_placed)?;
+    dict.set_item("frame_number", gs.frame_number.load(Ordering::Relaxed))?;
+    dict.set_item("elapsed_secs", f32::from_bits(gs.elapsed_secs.load(Ordering::Relaxed)))?;
+    // Orbit radius in perspective mode, orthographic scale otherwise
+    dict.set_item("camera_radius", f32::from_bits(gs.camera_radius.load(Ordering::Relaxed)))?;
+    dict.set_item("camera_radius_is_scale", gs.camera_radius_is_scale.load(Ordering::Relaxed))?;
+    dict.set_item("camera_position", vec![
+        f32::from_bits(gs.camera_x.load(Ordering::Relaxed)),
+        f32::from_bits(gs.camera_y.load(Ordering::Relaxed)),
+        f32::from_bits(gs.camera_z.load(Ordering::Relaxed)),
+    ])?;
+    dict.set_item("pyramid_yaw", f32::from_bits(gs.pyramid_yaw.load(Ordering::Relaxed)))?;
+    dict.set_item("global_yaw_offset", f32::from_bits(gs.global_yaw_offset.load(Ordering::Relaxed)))?;
+    dict.set_item("nr_attempts", gs.attempts.load(Ordering::Relaxed))?;
+    dict.set_item("rejected_checks", gs.rejected_checks.load(Ordering::Relaxed))?;
+    dict.set_item("cosine_alignment", f32::from_bits(gs.current_alignment.load(Ordering::Relaxed)))?;
+    dict.set_item("current_angle", f32::from_bits(gs.current_angle.load(Ordering::Relaxed)))?;
+    dict.set_item("best_door", gs.best_door.load(Ordering::Relaxed))?;
+    dict.set_item("num_faces", gs.num_faces.load(Ordering::Relaxed))?;
+    dict.set_item("num_doors", gs.num_doors.load(Ordering::Relaxed))?;
+    dict.set_item("controller_lost", gs.controller_lost.load(Ordering::Relaxed))?;
+    dict.set_item("controller_outages", gs.controller_outages.load(Ordering::Relaxed))?;
+    dict.set_item("game_heartbeat", gs.game_heartbeat.load(Ordering::Relaxed))?;
+    // None while no reference image is shown
+    let reference_image = gs.reference_image.load(Ordering::Relaxed);
+    dict.set_item("reference_image", (reference_image != HIDE_IMAGE).then_some(reference_image))?;
+    dict.set_item("live_feedback_visible", gs.live_feedback_visible.load(Ordering::Relaxed))?;
+    dict.set_item("is_animating", gs.is_animating.load(Ordering::Relaxed))?;
+    dict.set_item("win_elapsed_secs", f32::from_bits(gs.win_time.load(Ordering::Relaxed)))?;
+    dict.set_item("reward_weight", f32::from_bits(gs.reward_weight.load(Ordering::Relaxed)))?;
+    dict.set_item("radius_at_check", f32::from_bits(gs.radius_at_check.load(Ordering::Relaxed)))?;
+    dict.set_item("win_blocked_by_radius", gs.win_blocked_by_radius.load(Ordering::Relaxed))?;
+    dict.set_item("approach_sign", gs.approach_sign.load(Ordering::Relaxed))?;
+    dict.set_item("win_blocked_by_approach", gs.win_blocked_by_approach.load(Ordering::Relaxed))?;
+    dict.set_item("check_too_early", gs.check_too_early.load(Ordering::Relaxed))?;
+    dict.set_item("feedback_pending", gs.feedback_pending.load(Ordering::Relaxed))?;
+    dict.set_item("inputs_locked", gs.inputs_locked.load(Ordering::Relaxed))?;
+    dict.set_item("phase", gs.phase.load(Ordering::Relaxed))?;
+    dict.set_item("iti_remaining_frames", gs.iti_remaining_frames.load(Ordering::Relaxed))?;
+    dict.set_item("active_fov_degrees", f32::from_bits(gs.active_fov_degrees.load(Ordering::Relaxed)))?;
+    dict.set_item("active_near", f32::from_bits(gs.active_near.load(Ordering::Relaxed)))?;
+    dict.set_item("active_far", f32::from_bits(gs.active_far.load(Ordering::Relaxed)))?;
+    dict.set_item("window_resolution", [
+        gs.window_width.load(Ordering::Relaxed),
+        gs.window_height.load(Ordering::Relaxed),
+    ])?;
+    dict.set_item("vsync", gs.vsync.load(Ordering::Relaxed))?;
+    dict.set_item("flicker_phase", gs.flicker_phase.iter().map(|v| f32::from_bits(v.load(Ordering::Relaxed))).collect::<Vec<f32>>())?;
+    dict.set_item("shaping_dwell_elapsed", f32::from_bits(gs.shaping_dwell_elapsed.load(Ordering::Relaxed)))?;
+    dict.set_item("distractor_hash", gs.distractor_hash.load(Ordering::Relaxed))?;
+    dict.set_item("stimulus_hash", gs.stimulus_hash.load(Ordering::Relaxed))?;
+    dict.set_item("stimulus_file_status", gs.stimulus_file_status.load(Ordering::Relaxed))?;
+    dict.set_item("reset_ack", gs.reset_ack.load(Ordering::Relaxed))?;
+    dict.set_item("trial_generation", gs.trial_generation.load(Ordering::Relaxed))?;
+    dict.set_item("clear_attempts_ack", gs.clear_attempts_ack.load(Ordering::Relaxed))?;
+    dict.set_item("latched_rotation", gs.latched_rotation.load(Ordering::Relaxed))?;
+    dict.set_item("latched_zoom", gs.latched_zoom.load(Ordering::Relaxed))?;
+    dict.set_item("override_activations", gs.override_activations.load(Ordering::Relaxed))?;
+    dict.set_item("override_actions", gs.override_actions.load(Ordering::Relaxed))?;
+    dict.set_item("measured_hz", f32::from_bits(gs.measured_hz.load(Ordering::Relaxed)))?;
+    dict.set_item("display_hz", f32::from_bits(gs.display_hz.load(Ordering::Relaxed)))?;
+    dict.set_item("fixed_hz", f32::from_bits(gs.fixed_hz.load(Ordering::Relaxed)))?;
+    dict.set_item("late_frames_trial", gs.late_frames_trial.load(Ordering::Relaxed))?;
+    dict.set_item("dropped_frames_trial", gs.dropped_frames_trial.load(Ordering::Relaxed))?;
+    dict.set_item("worst_frame_ms", f32::from_bits(gs.worst_frame_ms.load(Ordering::Relaxed)))?;
+    dict.set_item("render_quality", gs.render_quality.load(Ordering::Relaxed))?;
+    dict.set_item("tab_hidden", gs.tab_hidden.load(Ordering::Relaxed))?;
+    dict.set_item("tab_hidden_secs_trial", f32::from_bits(gs.tab_hidden_secs_trial.load(Ordering::Relaxed)))?;
+    dict.set_item("gamepad_connected", gs.gamepad_connected.load(Ordering::Relaxed))?;
+    dict.set_item("dropped_checks", gs.dropped_checks.load(Ordering::Relaxed))?;
+    dict.set_item("debounced_count", gs.debounced_count.load(Ordering::Relaxed))?;
+    dict.set_item("standby_checks", gs.standby_checks.load(Ordering::Relaxed))?;
+    dict.set_item("commands_seen", seen_command_names(gs.commands_seen.load(Ordering::Relaxed)))?;
+    dict.set_item("commands_seen_counts", seen_counts_dict(py, gs)?)?;
+    dict.set_item("decoration_motion_secs", f32::from_bits(gs.decoration_motion_secs.load(Ordering::Relaxed)))?;
+    dict.set_item("current_trial_index", gs.current_trial_index.load(Ordering::Relaxed))?;
+    dict.set_item("total_trials", gs.total_trials.load(Ordering::Relaxed))?;
+    dict.set_item("block_index", gs.block_index.load(Ordering::Relaxed))?;
+    dict.set_item("session_id", gs.session_id.load(Ordering::Relaxed))?;
+    dict.set_item("subject_id", gs.subject_id.load(Ordering::Relaxed))?;
+
+    Ok(dict.into())
+}
+
 #[pymodule]
 #[pyo3(name = "monkey_shared")]
 fn monkey_shared(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<SharedMemoryWrapper>()?;
+    m.add_class::<StaircaseWrapper>()?;
+    m.add_class::<SessionStateWrapper>()?;
+    m.add_function(wrap_pyfunction!(generate_tutorial_trials, m)?)?;
+    m.add_function(wrap_pyfunction!(trial_id_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(generate_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(deuteranopia_safe_palette, m)?)?;
+    m.add_function(wrap_pyfunction!(sample_face_colors, m)?)?;
+    m.add_function(wrap_pyfunction!(rec709_luma, m)?)?;
+    m.add_function(wrap_pyfunction!(config_field_schemas, m)?)?;
+
+    // Pyramid type codes (shared PyramidType repr)
+    m.add("PYRAMID_TYPE_1", PyramidType::Type1 as u32)?;
+    m.add("PYRAMID_TYPE_2", PyramidType::Type2 as u32)?;
+    m.add("PYRAMID_TYPE_3", PyramidType::Type3 as u32)?;
+    m.add("CONFIG_MODE_EXPLICIT", ConfigMode::Explicit as u32)?;
+    m.add("CONFIG_MODE_RANDOMIZED", ConfigMode::Randomized as u32)?;
+    m.add("TARGET_KIND_DOOR", TargetKind::Door as u32)?;
+    m.add("TARGET_KIND_FACE", TargetKind::Face as u32)?;
+    m.add("STIMULUS_FILE_PROCEDURAL", StimulusFileStatus::Procedural as u32)?;
+    m.add("STIMULUS_FILE_LOADED", StimulusFileStatus::Loaded as u32)?;
+    m.add("STIMULUS_FILE_MISSING", StimulusFileStatus::Missing as u32)?;
+    m.add("STIMULUS_FILE_INVALID", StimulusFileStatus::Invalid as u32)?;
+    m.add("STIMULUS_FILE_HASH_MISMATCH", StimulusFileStatus::HashMismatch as u32)?;
+    m.add("COLOR_SPACE_SRGB", ColorSpace::Srgb as u32)?;
+    m.add("COLOR_SPACE_LINEAR", ColorSpace::Linear as u32)?;
+    for category in LogCategory::ALL {
+        m.add(format!("LOG_CATEGORY_{}", category.name().to_uppercase()).as_str(), category.bit())?;
+    }
+    m.add("LOG_CATEGORIES_ALL", LOG_CATEGORIES_ALL)?;
+    m.add("PROJECTION_PERSPECTIVE", ProjectionMode::Perspective as u32)?;
+    m.add("PROJECTION_ORTHOGRAPHIC", ProjectionMode::Orthographic as u32)?;
+    m.add("RENDER_QUALITY_LOW", RenderQuality::Low as u32)?;
+    m.add("RENDER_QUALITY_MEDIUM", RenderQuality::Medium as u32)?;
+    m.add("RENDER_QUALITY_HIGH", RenderQuality::High as u32)?;
+    m.add("POST_WIN_AUTO_RETURN", PostWinMode::AutoReturn as u32)?;
+    m.add("POST_WIN_HOLD_BLANK", PostWinMode::HoldBlank as u32)?;
+    m.add("PAUSE_HIDE_CAMERA", PauseMode::HideCamera as u32)?;
+    m.add("PAUSE_HIDE_GAME_ENTITIES", PauseMode::HideGameEntities as u32)?;
+    m.add("PAUSE_FREEZE", PauseMode::Freeze as u32)?;
+    m.add("DECORATION_MOTION_NONE", DecorationMotion::None as u32)?;
+    m.add("DECORATION_MOTION_SPIN", DecorationMotion::Spin as u32)?;
+    m.add("DECORATION_MOTION_PULSE", DecorationMotion::Pulse as u32)?;
+    m.add("EASING_LINEAR", AnimationEasing::Linear as u32)?;
+    m.add("EASING_EASE_IN_OUT", AnimationEasing::EaseInOut as u32)?;
+    m.add("EASING_EXPONENTIAL", AnimationEasing::Exponential as u32)?;
+    m.add("PHASE_PLAYING", Phase::Playing as u32)?;
+    m.add("PHASE_WON", Phase::Won as u32)?;
+    m.add("PHASE_FAILED", Phase::Failed as u32)?;
+    m.add("PHASE_INTER_TRIAL", Phase::InterTrial as u32)?;
+    m.add("PHASE_RESETTING", Phase::Resetting as u32)?;
+    m.add("PHASE_STANDBY", Phase::Standby as u32)?;
 
     // Export constants from constants.rs so Python can import them directly.
     use crate::constants::game_constants;
     m.add("REFRESH_RATE_HZ", game_constants::REFRESH_RATE_HZ)?;
     m.add("DECORATION_SEEDS", game_constants::DECORATION_SEEDS.to_vec())?;
     m.add("COSINE_ALIGNMENT_TO_WIN", game_constants::COSINE_ALIGNMENT_TO_WIN)?;
+    m.add("SHAPING_DWELL_SECS", game_constants::SHAPING_DWELL_SECS)?;
+    m.add("PALETTE_SIZE", game_constants::PALETTE_SIZE)?;
+    m.add("PALETTE_LUMA", game_constants::PALETTE_LUMA)?;
+    m.add("PALETTE_LUMA_RANGE", game_constants::PALETTE_LUMA_RANGE.to_vec())?;
+    m.add("PALETTE_LUMA_TOLERANCE", game_constants::PALETTE_LUMA_TOLERANCE)?;
+    m.add("PALETTE_DEUTERANOPIA_HUES", game_constants::PALETTE_DEUTERANOPIA_HUES.to_vec())?;
+    m.add("SHAPING_COSINE_TOLERANCE", game_constants::SHAPING_COSINE_TOLERANCE)?;
+    m.add("SUPPRESS_BLANKED_ATTEMPTS", game_constants::SUPPRESS_BLANKED_ATTEMPTS)?;
+    m.add("IS_CATCH", game_constants::IS_CATCH)?;
+    m.add("MAX_ATTEMPTS", game_constants::MAX_ATTEMPTS)?;
+    m.add("TRIAL_TIMEOUT_SECS", game_constants::TRIAL_TIMEOUT_SECS)?;
+    m.add("WIN_RADIUS_MIN", game_constants::WIN_RADIUS_MIN)?;
+    m.add("WIN_RADIUS_MAX", game_constants::WIN_RADIUS_MAX)?;
+    m.add("REQUIRED_APPROACH_SIGN", game_constants::REQUIRED_APPROACH_SIGN)?;
+    m.add("MIN_VIEW_SECS", game_constants::MIN_VIEW_SECS)?;
+    m.add("FEEDBACK_DELAY_MS", game_constants::FEEDBACK_DELAY_MS)?;
+    m.add("DEBOUNCE_FRAMES", game_constants::DEBOUNCE_FRAMES.to_vec())?;
+    m.add("UI_SCALE", game_constants::UI_SCALE)?;
+    m.add("SCORE_BAR_ANCHOR", game_constants::SCORE_BAR_ANCHOR)?;
+    m.add("SCORE_BAR_ANCHOR_TOP", ScoreBarAnchor::Top as u32)?;
+    m.add("SCORE_BAR_ANCHOR_BOTTOM", ScoreBarAnchor::Bottom as u32)?;
+    m.add("SCORE_BAR_WIDTH_PERCENT", game_constants::SCORE_BAR_WIDTH_PERCENT)?;
+    m.add("SCORE_BAR_HEIGHT", game_constants::SCORE_BAR_HEIGHT)?;
+    m.add("SCORE_BAR_SHOW_NUMERIC", game_constants::SCORE_BAR_SHOW_NUMERIC)?;
+    m.add("UI_REFERENCE_WIDTH", game_constants::UI_REFERENCE_WIDTH)?;
+    m.add("UI_REFERENCE_HEIGHT", game_constants::UI_REFERENCE_HEIGHT)?;
+    m.add("UI_SCALE_RANGE", game_constants::UI_SCALE_RANGE.to_vec())?;
+    m.add("RETICLE_ENABLED", game_constants::RETICLE_ENABLED)?;
+    m.add("RETICLE_SIZE", game_constants::RETICLE_SIZE)?;
+    m.add("RETICLE_COLOR", game_constants::RETICLE_COLOR.to_vec())?;
+    m.add("RETICLE_STYLE", game_constants::RETICLE_STYLE)?;
+    m.add("RETICLE_ALIGNMENT_TINT", game_constants::RETICLE_ALIGNMENT_TINT)?;
+    m.add("LIVE_FEEDBACK_ENABLED", game_constants::LIVE_FEEDBACK_ENABLED)?;
+    m.add("LIVE_FEEDBACK_GAIN", game_constants::LIVE_FEEDBACK_GAIN)?;
+    m.add("LIVE_FEEDBACK_SMOOTHING", game_constants::LIVE_FEEDBACK_SMOOTHING)?;
+    m.add("CONFIG_DUMP_PATH", game_constants::CONFIG_DUMP_PATH)?;
+    m.add("GEOMETRY_DUMP_DIR", game_constants::GEOMETRY_DUMP_DIR)?;
+    m.add("MOTION_LATCH_TIMEOUT_FRAMES", game_constants::MOTION_LATCH_TIMEOUT_FRAMES)?;
+    m.add("CONTROLLER_TIMEOUT_FRAMES", game_constants::CONTROLLER_TIMEOUT_FRAMES)?;
+    m.add("LOG_CATEGORIES_ENV", game_constants::LOG_CATEGORIES_ENV)?;
+    m.add("FIXED_RATE_DRIFT_TOLERANCE", game_constants::FIXED_RATE_DRIFT_TOLERANCE)?;
+    m.add("FRAME_LATE_FACTOR", game_constants::FRAME_LATE_FACTOR)?;
+    m.add("SEEN_COMMAND_NAMES", SEEN_COMMAND_NAMES.to_vec())?;
+    m.add("FRAME_DROP_FACTOR", game_constants::FRAME_DROP_FACTOR)?;
+    m.add("PROBE_DURATION_SECS", game_constants::PROBE_DURATION_SECS)?;
+    m.add("REFERENCE_IMAGES", game_constants::REFERENCE_IMAGES.to_vec())?;
+    m.add("STIMULUS_FILE_MAX_LEN", game_constants::STIMULUS_FILE_MAX_LEN)?;
 
     // pyramid_constants
     use crate::constants::pyramid_constants;
     m.add("PYRAMID_BASE_RADIUS", pyramid_constants::PYRAMID_BASE_RADIUS)?;
     m.add("PYRAMID_HEIGHT", pyramid_constants::PYRAMID_HEIGHT)?;
     m.add("PYRAMID_START_ANGLE_OFFSET_RAD", pyramid_constants::PYRAMID_START_ANGLE_OFFSET_RAD)?;
+    m.add("CONFIG_MODE", pyramid_constants::CONFIG_MODE)?;
+    m.add("GEOMETRY_SEED", pyramid_constants::GEOMETRY_SEED)?;
+    m.add("PYRAMID_BASE_RADIUS_RANGE", pyramid_constants::PYRAMID_BASE_RADIUS_RANGE.to_vec())?;
+    m.add("PYRAMID_HEIGHT_RANGE", pyramid_constants::PYRAMID_HEIGHT_RANGE.to_vec())?;
+    m.add("PYRAMID_START_ORIENT_RANGE", pyramid_constants::PYRAMID_START_ORIENT_RANGE.to_vec())?;
+    m.add("PYRAMID_BASE_RADIUS_LIMITS", pyramid_constants::PYRAMID_BASE_RADIUS_LIMITS.to_vec())?;
+    m.add("PYRAMID_HEIGHT_LIMITS", pyramid_constants::PYRAMID_HEIGHT_LIMITS.to_vec())?;
     m.add("PYRAMID_TARGET_DOOR_INDEX", pyramid_constants::PYRAMID_TARGET_DOOR_INDEX)?;
+    m.add("BASE_NR_SIDES", pyramid_constants::BASE_NR_SIDES)?;
+    m.add("PYRAMID_TYPE", pyramid_constants::PYRAMID_TYPE)?;
+    m.add("TARGET_KIND", pyramid_constants::TARGET_KIND)?;
+    m.add("PYRAMID_TARGET_FACE_INDEX", pyramid_constants::PYRAMID_TARGET_FACE_INDEX)?;
+    m.add("DOOR_HOLE_RADIUS_FRACTION", pyramid_constants::DOOR_HOLE_RADIUS_FRACTION)?;
+    m.add("DOOR_FRAME_THICKNESS", pyramid_constants::DOOR_FRAME_THICKNESS)?;
+    m.add("DOOR_INSET", pyramid_constants::DOOR_INSET)?;
+    m.add("DOOR_REWARD_WEIGHTS", pyramid_constants::DOOR_REWARD_WEIGHTS.to_vec())?;
+    m.add("COLOR_SPACE", pyramid_constants::COLOR_SPACE)?;
     m.add("PYRAMID_COLORS", pyramid_constants::PYRAMID_COLORS.iter().map(|f| f.to_vec()).collect::<Vec<Vec<f32>>>())?;
     m.add("PYRAMID_DECORATIONS_COUNT", pyramid_constants::PYRAMID_DECORATIONS_COUNT.to_vec())?;
+    m.add("PYRAMID_DECORATIONS_COUNT_RANGE", pyramid_constants::PYRAMID_DECORATIONS_COUNT_RANGE.to_vec())?;
     m.add("PYRAMID_DECORATIONS_SIZE", pyramid_constants::PYRAMID_DECORATIONS_SIZE.to_vec())?;
+    m.add("PYRAMID_DECORATIONS_SPACING", pyramid_constants::PYRAMID_DECORATIONS_SPACING)?;
+    m.add("PYRAMID_DECORATION_SPIN_RATE", pyramid_constants::PYRAMID_DECORATION_SPIN_RATE)?;
+    m.add("PYRAMID_DECORATION_MOTION", pyramid_constants::PYRAMID_DECORATION_MOTION)?;
+    m.add("PYRAMID_DECORATION_PULSE_AMPLITUDE", pyramid_constants::PYRAMID_DECORATION_PULSE_AMPLITUDE)?;
+    m.add("PYRAMID_DECORATION_PULSE_HZ", pyramid_constants::PYRAMID_DECORATION_PULSE_HZ)?;
+    m.add("PYRAMID_DECORATION_SYMMETRY", pyramid_constants::PYRAMID_DECORATION_SYMMETRY)?;
+    m.add("PYRAMID_DECORATION_COLORS", pyramid_constants::PYRAMID_DECORATION_COLORS.iter().map(|f| f.to_vec()).collect::<Vec<Vec<f32>>>())?;
+    m.add("PYRAMID_DECORATION_COLOR_JITTER", pyramid_constants::PYRAMID_DECORATION_COLOR_JITTER)?;
+    m.add("PYRAMID_OUTLINE_WIDTH", pyramid_constants::PYRAMID_OUTLINE_WIDTH)?;
+    m.add("PYRAMID_OUTLINE_COLOR", pyramid_constants::PYRAMID_OUTLINE_COLOR.to_vec())?;
     m.add("DOOR_ANIM_FADE_OUT", pyramid_constants::DOOR_ANIM_FADE_OUT)?;
     m.add("DOOR_ANIM_STAY_OPEN", pyramid_constants::DOOR_ANIM_STAY_OPEN)?;
     m.add("DOOR_ANIM_FADE_IN", pyramid_constants::DOOR_ANIM_FADE_IN)?;
+    m.add("DOOR_ANIM_EASING", pyramid_constants::DOOR_ANIM_EASING)?;
+    m.add("PYRAMID_FLICKER_HZ", pyramid_constants::PYRAMID_FLICKER_HZ.to_vec())?;
+    m.add("PYRAMID_FLICKER_DEPTH", pyramid_constants::PYRAMID_FLICKER_DEPTH.to_vec())?;
 
     // lighting_constants
     use crate::constants::lighting_constants;
     m.add("SPOTLIGHT_LIGHT_INTENSITY", lighting_constants::SPOTLIGHT_LIGHT_INTENSITY)?;
     m.add("GLOBAL_AMBIENT_LIGHT_INTENSITY", lighting_constants::GLOBAL_AMBIENT_LIGHT_INTENSITY)?;
     m.add("MAX_SPOTLIGHT_INTENSITY", lighting_constants::MAX_SPOTLIGHT_INTENSITY)?;
+    m.add("SPOTLIGHT_OUTER_ANGLE", lighting_constants::SPOTLIGHT_OUTER_ANGLE)?;
+    m.add("SPOTLIGHT_RANGE", lighting_constants::SPOTLIGHT_RANGE)?;
+    m.add("WIN_GLOW_COLOR", lighting_constants::WIN_GLOW_COLOR.to_vec())?;
+    m.add("FOG_ENABLED", lighting_constants::FOG_ENABLED)?;
+    m.add("FOG_COLOR", lighting_constants::FOG_COLOR.to_vec())?;
+    m.add("FOG_START", lighting_constants::FOG_START)?;
+    m.add("FOG_END", lighting_constants::FOG_END)?;
 
     // timing
     use crate::constants::timing;
-    m.add("WIN_BLANK_DURATION_FRAMES", timing::WIN_BLANK_DURATION_FRAMES)?;
+    m.add("ITI_FRAMES", timing::ITI_FRAMES)?;
+    m.add("POST_WIN_MODE", timing::POST_WIN_MODE)?;
+    m.add("PAUSE_MODE", timing::PAUSE_MODE)?;
 
     // camera_3d_constants
     use crate::constants::camera_3d_constants;
     m.add("CAMERA_3D_INITIAL_RADIUS", camera_3d_constants::CAMERA_3D_INITIAL_RADIUS)?;
+    m.add("CAMERA_3D_SPEED_ROTATE", camera_3d_constants::CAMERA_3D_SPEED_ROTATE)?;
+    m.add("CAMERA_3D_SPEED_ZOOM", camera_3d_constants::CAMERA_3D_SPEED_ZOOM)?;
+    m.add("CONTROL_MIRROR_ROTATION", camera_3d_constants::CONTROL_MIRROR_ROTATION)?;
+    m.add("CONTROL_MIRROR_ZOOM", camera_3d_constants::CONTROL_MIRROR_ZOOM)?;
+    m.add("MIRROR_X", camera_3d_constants::MIRROR_X)?;
+    m.add("RECENTER_ON_RESET", camera_3d_constants::RECENTER_ON_RESET)?;
+    m.add("CAMERA_3D_FOV_DEGREES", camera_3d_constants::CAMERA_3D_FOV_DEGREES)?;
+    m.add("CAMERA_3D_NEAR", camera_3d_constants::CAMERA_3D_NEAR)?;
+    m.add("CAMERA_3D_FAR", camera_3d_constants::CAMERA_3D_FAR)?;
+    m.add("CAMERA_3D_FOV_RANGE_DEGREES", camera_3d_constants::CAMERA_3D_FOV_RANGE_DEGREES.to_vec())?;
+    m.add("CAMERA_3D_PROJECTION_MODE", camera_3d_constants::CAMERA_3D_PROJECTION_MODE)?;
+    m.add("CAMERA_3D_ORTHO_SCALE_RANGE", camera_3d_constants::CAMERA_3D_ORTHO_SCALE_RANGE.to_vec())?;
+
+    // object_constants
+    use crate::constants::object_constants;
+    m.add("DISTRACTOR_COUNT", object_constants::DISTRACTOR_COUNT)?;
+    m.add("DISTRACTOR_SHAPE", object_constants::DISTRACTOR_SHAPE)?;
+    m.add("DISTRACTOR_SIZE_RANGE", object_constants::DISTRACTOR_SIZE_RANGE.to_vec())?;
+    m.add("DISTRACTOR_COLOR", object_constants::DISTRACTOR_COLOR.to_vec())?;
+    m.add("DISTRACTOR_RING", object_constants::DISTRACTOR_RING.to_vec())?;
+    m.add("DISTRACTOR_SEED", object_constants::DISTRACTOR_SEED)?;
+    m.add("BACKDROP_ENABLED", object_constants::BACKDROP_ENABLED)?;
 
     Ok(())
 }
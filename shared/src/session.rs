@@ -0,0 +1,144 @@
+//! Experiment progress of a controller session, saved for crash recovery.
+//!
+//! The controller rewrites the session file whenever a trial starts or ends, and `--resume-session`
+//! restores it on startup: the trial it was at, the outcome counts, the staircase with its runs,
+//! and the result logs it keeps appending to. Unlike the browser build's session record
+//! (web_adapter.rs), which only keeps the game's counters, this is the whole experiment's progress.
+use crate::staircase::Staircase;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// Version of the session file layout, a file of another version is not restored
+pub const SESSION_STATE_VERSION: u64 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionState {
+    /// Index of the trial in play, replayed from its start on resume
+    pub trial_index: u64,
+    /// Finished trials and wins among them
+    pub trials_done: u32,
+    pub wins: u32,
+    /// Result and staircase logs of the session, appended to after a resume
+    pub results_log: String,
+    pub staircase_log: String,
+    pub staircase: Option<Staircase>,
+}
+
+impl SessionState {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "version": SESSION_STATE_VERSION,
+            "trial_index": self.trial_index,
+            "trials_done": self.trials_done,
+            "wins": self.wins,
+            "results_log": self.results_log,
+            "staircase_log": self.staircase_log,
+            "staircase": self.staircase.as_ref().map(Staircase::to_json),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let version = value.get("version").and_then(Value::as_u64);
+        if version != Some(SESSION_STATE_VERSION) {
+            return Err(format!("session state version must be {SESSION_STATE_VERSION}, got {version:?}"));
+        }
+        let count = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|count| u32::try_from(count).ok())
+                .ok_or_else(|| format!("session {key} must be a non-negative integer"))
+        };
+        let text = |key: &str| {
+            value.get(key).and_then(Value::as_str).map(str::to_string).ok_or_else(|| format!("session {key} must be a string"))
+        };
+        let staircase = match value.get("staircase") {
+            None | Some(Value::Null) => None,
+            Some(staircase) => Some(Staircase::from_json(staircase)?),
+        };
+        Ok(Self {
+            trial_index: value
+                .get("trial_index")
+                .and_then(Value::as_u64)
+                .ok_or("session trial_index must be a non-negative integer")?,
+            trials_done: count("trials_done")?,
+            wins: count("wins")?,
+            results_log: text("results_log")?,
+            staircase_log: text("staircase_log")?,
+            staircase,
+        })
+    }
+
+    /// Writes the session file atomically: a crash mid-write leaves the previous file whole, since
+    /// the state goes to `<path>.tmp` first and is renamed over `path` once it is on disk
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        use std::io::Write;
+        let mut tmp_name = path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp = Path::new(&tmp_name);
+        let mut file = std::fs::File::create(tmp)?;
+        file.write_all((self.to_json().to_string() + "\n").as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(tmp, path)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let value = serde_json::from_str(&text).map_err(|e| format!("{} is not JSON: {e}", path.display()))?;
+        Self::from_json(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.json", std::process::id()))
+    }
+
+    #[test]
+    fn session_round_trips_with_the_staircase_runs() {
+        let mut staircase = Staircase::with_rule(0.9, 0.01, 2, 1, [0.7, 0.99]);
+        for won in [true, true, false, true] {
+            staircase.update(won);
+        }
+        let session = SessionState {
+            trial_index: 41,
+            trials_done: 40,
+            wins: 29,
+            results_log: "results_20260101_120000.jsonl".to_string(),
+            staircase_log: "staircase_20260101_120000.csv".to_string(),
+            staircase: Some(staircase.clone()),
+        };
+        let path = session_path("test_session_round_trip");
+        session.save(&path).unwrap();
+        let restored = SessionState::load(&path).unwrap();
+        assert_eq!(restored, session);
+        assert!(!path.with_extension("json.tmp").exists(), "temporary file left behind");
+
+        // The pending win of the run carries over: one more win steps the threshold up
+        let mut restored_staircase = restored.staircase.unwrap();
+        assert_eq!(restored_staircase.update(true), staircase.update(true));
+
+        // A later save replaces the file
+        let later = SessionState { trial_index: 42, staircase: None, ..session };
+        later.save(&path).unwrap();
+        assert_eq!(SessionState::load(&path).unwrap(), later);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn other_layouts_and_broken_files_are_rejected() {
+        let mut json = SessionState::default().to_json();
+        assert_eq!(SessionState::from_json(&json).unwrap(), SessionState::default());
+        json["version"] = (SESSION_STATE_VERSION + 1).into();
+        assert!(SessionState::from_json(&json).is_err());
+
+        let path = session_path("test_session_broken");
+        std::fs::write(&path, "{\"version\": 1, \"trial_index\": ").unwrap();
+        assert!(SessionState::load(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(SessionState::load(&path).is_err(), "missing file");
+    }
+}
@@ -0,0 +1,246 @@
+//! Double-buffered snapshot of the per-frame game state, for readers that need several fields
+//! from the same frame without a retry loop of their own (JS in the WASM build, or any controller
+//! that opts in).
+//!
+//! The game writes the slot not being read, then publishes it by bumping `sequence`; the stable
+//! slot is `sequence % 2`. A slot is only rewritten two publications later, so a read only has to
+//! be retried when the reader was slower than a whole frame. The per-field atomics of
+//! `game_structure_game` stay the primary interface.
+use core::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, Ordering};
+
+use crate::SharedGameStructure;
+
+/// Per-frame state fields, all taken from the same emitted frame
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StateSnapshot {
+    pub frame_number: u64,
+    /// Resets acknowledged, see `SharedGameStructure::reset_ack`
+    pub reset_ack: u32,
+    /// Trials set up, see `SharedGameStructure::trial_generation`
+    pub trial_generation: u32,
+    pub phase: u32,
+    pub iti_remaining_frames: u32,
+    pub elapsed_secs: f32,
+    pub attempts: u32,
+    pub rejected_checks: u32,
+    /// Seconds since startup at the win, 0 while not won
+    pub win_elapsed_secs: f32,
+    pub is_animating: bool,
+    /// Orbit radius in perspective mode, orthographic scale otherwise
+    pub camera_radius: f32,
+    pub camera_position: [f32; 3],
+    pub pyramid_yaw: f32,
+    pub cosine_alignment: f32,
+    pub current_angle: f32,
+    pub best_door: u32,
+    /// Browser tab hidden, and seconds it was hidden during the trial
+    pub tab_hidden: bool,
+    pub tab_hidden_secs_trial: f32,
+}
+
+impl StateSnapshot {
+    /// Snapshot of the fields as currently stored in a game structure
+    pub fn capture(gs: &SharedGameStructure) -> Self {
+        let f32_of = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+        Self {
+            frame_number: gs.frame_number.load(Ordering::Relaxed),
+            reset_ack: gs.reset_ack.load(Ordering::Relaxed),
+            trial_generation: gs.trial_generation.load(Ordering::Relaxed),
+            phase: gs.phase.load(Ordering::Relaxed),
+            iti_remaining_frames: gs.iti_remaining_frames.load(Ordering::Relaxed),
+            elapsed_secs: f32_of(&gs.elapsed_secs),
+            attempts: gs.attempts.load(Ordering::Relaxed),
+            rejected_checks: gs.rejected_checks.load(Ordering::Relaxed),
+            win_elapsed_secs: f32_of(&gs.win_time),
+            is_animating: gs.is_animating.load(Ordering::Relaxed),
+            camera_radius: f32_of(&gs.camera_radius),
+            camera_position: [f32_of(&gs.camera_x), f32_of(&gs.camera_y), f32_of(&gs.camera_z)],
+            pyramid_yaw: f32_of(&gs.pyramid_yaw),
+            cosine_alignment: f32_of(&gs.current_alignment),
+            current_angle: f32_of(&gs.current_angle),
+            best_door: gs.best_door.load(Ordering::Relaxed),
+            tab_hidden: gs.tab_hidden.load(Ordering::Relaxed),
+            tab_hidden_secs_trial: f32_of(&gs.tab_hidden_secs_trial),
+        }
+    }
+
+    /// JSON object with the same keys as the Python state dictionary
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"frame_number\": {}, \"reset_ack\": {}, \"trial_generation\": {}, \"phase\": {}, \"iti_remaining_frames\": {}, \
+             \"elapsed_secs\": {}, \"nr_attempts\": {}, \"rejected_checks\": {}, \"win_elapsed_secs\": {}, \"is_animating\": {}, \
+             \"camera_radius\": {}, \"camera_position\": [{}, {}, {}], \"pyramid_yaw\": {}, \"cosine_alignment\": {}, \
+             \"current_angle\": {}, \"best_door\": {}, \"tab_hidden\": {}, \"tab_hidden_secs_trial\": {}}}",
+            self.frame_number,
+            self.reset_ack,
+            self.trial_generation,
+            self.phase,
+            self.iti_remaining_frames,
+            self.elapsed_secs,
+            self.attempts,
+            self.rejected_checks,
+            self.win_elapsed_secs,
+            self.is_animating,
+            self.camera_radius,
+            self.camera_position[0],
+            self.camera_position[1],
+            self.camera_position[2],
+            self.pyramid_yaw,
+            self.cosine_alignment,
+            self.current_angle,
+            self.best_door,
+            self.tab_hidden,
+            self.tab_hidden_secs_trial,
+        )
+    }
+}
+
+/// One buffer of the snapshot, the same fields as atomics (f32 as bits)
+#[repr(C)]
+#[derive(Debug)]
+pub struct SnapshotSlot {
+    frame_number: AtomicU64,
+    reset_ack: AtomicU32,
+    trial_generation: AtomicU32,
+    phase: AtomicU32,
+    iti_remaining_frames: AtomicU32,
+    elapsed_secs: AtomicU32,
+    attempts: AtomicU32,
+    rejected_checks: AtomicU32,
+    win_elapsed_secs: AtomicU32,
+    is_animating: AtomicBool,
+    camera_radius: AtomicU32,
+    camera_position: [AtomicU32; 3],
+    pyramid_yaw: AtomicU32,
+    cosine_alignment: AtomicU32,
+    current_angle: AtomicU32,
+    best_door: AtomicU32,
+    tab_hidden: AtomicBool,
+    tab_hidden_secs_trial: AtomicU32,
+}
+
+impl SnapshotSlot {
+    pub const fn new() -> Self {
+        Self {
+            frame_number: AtomicU64::new(0),
+            reset_ack: AtomicU32::new(0),
+            trial_generation: AtomicU32::new(0),
+            phase: AtomicU32::new(0),
+            iti_remaining_frames: AtomicU32::new(0),
+            elapsed_secs: AtomicU32::new(0),
+            attempts: AtomicU32::new(0),
+            rejected_checks: AtomicU32::new(0),
+            win_elapsed_secs: AtomicU32::new(0),
+            is_animating: AtomicBool::new(false),
+            camera_radius: AtomicU32::new(0),
+            camera_position: [AtomicU32::new(0), AtomicU32::new(0), AtomicU32::new(0)],
+            pyramid_yaw: AtomicU32::new(0),
+            cosine_alignment: AtomicU32::new(0),
+            current_angle: AtomicU32::new(0),
+            best_door: AtomicU32::new(0),
+            tab_hidden: AtomicBool::new(false),
+            tab_hidden_secs_trial: AtomicU32::new(0),
+        }
+    }
+
+    fn store(&self, snapshot: &StateSnapshot) {
+        self.frame_number.store(snapshot.frame_number, Ordering::Relaxed);
+        self.reset_ack.store(snapshot.reset_ack, Ordering::Relaxed);
+        self.trial_generation.store(snapshot.trial_generation, Ordering::Relaxed);
+        self.phase.store(snapshot.phase, Ordering::Relaxed);
+        self.iti_remaining_frames.store(snapshot.iti_remaining_frames, Ordering::Relaxed);
+        self.elapsed_secs.store(snapshot.elapsed_secs.to_bits(), Ordering::Relaxed);
+        self.attempts.store(snapshot.attempts, Ordering::Relaxed);
+        self.rejected_checks.store(snapshot.rejected_checks, Ordering::Relaxed);
+        self.win_elapsed_secs.store(snapshot.win_elapsed_secs.to_bits(), Ordering::Relaxed);
+        self.is_animating.store(snapshot.is_animating, Ordering::Relaxed);
+        self.camera_radius.store(snapshot.camera_radius.to_bits(), Ordering::Relaxed);
+        for (slot, value) in self.camera_position.iter().zip(snapshot.camera_position) {
+            slot.store(value.to_bits(), Ordering::Relaxed);
+        }
+        self.pyramid_yaw.store(snapshot.pyramid_yaw.to_bits(), Ordering::Relaxed);
+        self.cosine_alignment.store(snapshot.cosine_alignment.to_bits(), Ordering::Relaxed);
+        self.current_angle.store(snapshot.current_angle.to_bits(), Ordering::Relaxed);
+        self.best_door.store(snapshot.best_door, Ordering::Relaxed);
+        self.tab_hidden.store(snapshot.tab_hidden, Ordering::Relaxed);
+        self.tab_hidden_secs_trial.store(snapshot.tab_hidden_secs_trial.to_bits(), Ordering::Relaxed);
+    }
+
+    fn load(&self) -> StateSnapshot {
+        let f32_of = |value: &AtomicU32| f32::from_bits(value.load(Ordering::Relaxed));
+        StateSnapshot {
+            frame_number: self.frame_number.load(Ordering::Relaxed),
+            reset_ack: self.reset_ack.load(Ordering::Relaxed),
+            trial_generation: self.trial_generation.load(Ordering::Relaxed),
+            phase: self.phase.load(Ordering::Relaxed),
+            iti_remaining_frames: self.iti_remaining_frames.load(Ordering::Relaxed),
+            elapsed_secs: f32_of(&self.elapsed_secs),
+            attempts: self.attempts.load(Ordering::Relaxed),
+            rejected_checks: self.rejected_checks.load(Ordering::Relaxed),
+            win_elapsed_secs: f32_of(&self.win_elapsed_secs),
+            is_animating: self.is_animating.load(Ordering::Relaxed),
+            camera_radius: f32_of(&self.camera_radius),
+            camera_position: self.camera_position.each_ref().map(f32_of),
+            pyramid_yaw: f32_of(&self.pyramid_yaw),
+            cosine_alignment: f32_of(&self.cosine_alignment),
+            current_angle: f32_of(&self.current_angle),
+            best_door: self.best_door.load(Ordering::Relaxed),
+            tab_hidden: self.tab_hidden.load(Ordering::Relaxed),
+            tab_hidden_secs_trial: f32_of(&self.tab_hidden_secs_trial),
+        }
+    }
+}
+
+impl Default for SnapshotSlot {
+    fn default() -> Self { Self::new() }
+}
+
+/// The two snapshot buffers and their publication counters. Written by the game only
+#[repr(C)]
+#[derive(Debug)]
+pub struct SharedStateSnapshot {
+    pub slots: [SnapshotSlot; 2],
+    /// Snapshots published so far (0 before the first); the stable slot is `sequence % 2`
+    pub sequence: AtomicU64,
+    /// Publication whose slot is being written, announced before its slot is touched
+    pub writing: AtomicU64,
+}
+
+impl SharedStateSnapshot {
+    pub const fn new() -> Self {
+        Self {
+            slots: [SnapshotSlot::new(), SnapshotSlot::new()],
+            sequence: AtomicU64::new(0),
+            writing: AtomicU64::new(0),
+        }
+    }
+
+    /// Writes `snapshot` into the inactive slot and makes it the stable one
+    pub fn publish(&self, snapshot: &StateSnapshot) {
+        let next = self.sequence.load(Ordering::Relaxed) + 1;
+        self.writing.store(next, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.slots[(next % 2) as usize].store(snapshot);
+        self.sequence.store(next, Ordering::Release);
+    }
+
+    /// Latest published snapshot, `None` before the first one
+    pub fn read(&self) -> Option<StateSnapshot> {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+            if sequence == 0 {
+                return None;
+            }
+            let snapshot = self.slots[(sequence % 2) as usize].load();
+            // The slot read is only rewritten for publication sequence + 2
+            fence(Ordering::Acquire);
+            if self.writing.load(Ordering::Relaxed) < sequence + 2 {
+                return Some(snapshot);
+            }
+        }
+    }
+}
+
+impl Default for SharedStateSnapshot {
+    fn default() -> Self { Self::new() }
+}
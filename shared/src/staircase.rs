@@ -0,0 +1,158 @@
+//! Adaptive staircase on the alignment threshold, updated trial to trial from the outcomes.
+//!
+//! An N-down-M-up rule: N consecutive wins make the task harder (a higher cosine threshold),
+//! M consecutive losses make it easier. The default 2-down-1-up converges near 71% correct.
+use crate::constants::game_constants::{STAIRCASE_COSINE_RANGE, STAIRCASE_DOWN, STAIRCASE_STEP, STAIRCASE_UP};
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Staircase {
+    pub threshold: f32,
+    pub step: f32,
+    /// Consecutive wins before a harder step, and consecutive losses before an easier one
+    pub down: u32,
+    pub up: u32,
+    /// Threshold bounds as [easiest, hardest]
+    pub range: [f32; 2],
+    /// Number of direction changes so far, the usual stopping and averaging criterion
+    pub reversals: u32,
+    win_run: u32,
+    loss_run: u32,
+    /// Sign of the last step taken, 0 before the first one
+    last_direction: i8,
+}
+
+impl Staircase {
+    /// Staircase with the default rule and bounds, starting from `threshold` (clamped into range)
+    pub fn new(threshold: f32) -> Self {
+        Self::with_rule(threshold, STAIRCASE_STEP, STAIRCASE_DOWN, STAIRCASE_UP, STAIRCASE_COSINE_RANGE)
+    }
+
+    pub fn with_rule(threshold: f32, step: f32, down: u32, up: u32, range: [f32; 2]) -> Self {
+        Self {
+            threshold: threshold.clamp(range[0], range[1]),
+            step,
+            down: down.max(1),
+            up: up.max(1),
+            range,
+            reversals: 0,
+            win_run: 0,
+            loss_run: 0,
+            last_direction: 0,
+        }
+    }
+
+    /// Records one trial outcome and returns the threshold for the next trial
+    pub fn update(&mut self, won: bool) -> f32 {
+        let direction = if won {
+            self.win_run += 1;
+            self.loss_run = 0;
+            if self.win_run < self.down {
+                return self.threshold;
+            }
+            self.win_run = 0;
+            1
+        } else {
+            self.loss_run += 1;
+            self.win_run = 0;
+            if self.loss_run < self.up {
+                return self.threshold;
+            }
+            self.loss_run = 0;
+            -1
+        };
+
+        if self.last_direction != 0 && direction != self.last_direction {
+            self.reversals += 1;
+        }
+        self.last_direction = direction;
+        self.threshold = (self.threshold + direction as f32 * self.step).clamp(self.range[0], self.range[1]);
+        self.threshold
+    }
+
+    /// Full state as JSON, runs included, so a restored staircase steps exactly like this one
+    pub fn to_json(&self) -> Value {
+        json!({
+            "threshold": self.threshold,
+            "step": self.step,
+            "down": self.down,
+            "up": self.up,
+            "range": self.range,
+            "reversals": self.reversals,
+            "win_run": self.win_run,
+            "loss_run": self.loss_run,
+            "last_direction": self.last_direction,
+        })
+    }
+
+    /// Staircase from `to_json`
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let number = |key: &str| {
+            value.get(key).and_then(Value::as_f64).ok_or_else(|| format!("staircase {key} must be a number"))
+        };
+        let count = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|count| u32::try_from(count).ok())
+                .ok_or_else(|| format!("staircase {key} must be a non-negative integer"))
+        };
+        let range = match value.get("range").and_then(Value::as_array).map(Vec::as_slice) {
+            Some([min, max]) => match (min.as_f64(), max.as_f64()) {
+                (Some(min), Some(max)) => [min as f32, max as f32],
+                _ => return Err("staircase range must be two numbers".to_string()),
+            },
+            _ => return Err("staircase range must be two numbers".to_string()),
+        };
+        let last_direction = match value.get("last_direction").and_then(Value::as_i64) {
+            Some(direction @ -1..=1) => direction as i8,
+            _ => return Err("staircase last_direction must be -1, 0 or 1".to_string()),
+        };
+        Ok(Self {
+            threshold: number("threshold")? as f32,
+            step: number("step")? as f32,
+            down: count("down")?,
+            up: count("up")?,
+            range,
+            reversals: count("reversals")?,
+            win_run: count("win_run")?,
+            loss_run: count("loss_run")?,
+            last_direction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_down_one_up_steps_and_counts_reversals() {
+        let mut staircase = Staircase::with_rule(0.9, 0.01, 2, 1, [0.7, 0.99]);
+
+        assert_eq!(staircase.update(true), 0.9, "one win is not enough to step");
+        let harder = staircase.update(true);
+        assert!((harder - 0.91).abs() < 1e-6, "two wins in a row make it harder");
+
+        let easier = staircase.update(false);
+        assert!((easier - 0.9).abs() < 1e-6, "one loss makes it easier");
+        assert_eq!(staircase.reversals, 1);
+
+        // A loss resets the win run
+        staircase.update(true);
+        staircase.update(false);
+        assert!((staircase.threshold - 0.89).abs() < 1e-6);
+        assert_eq!(staircase.reversals, 1, "same direction is no reversal");
+    }
+
+    #[test]
+    fn staircase_stays_within_range() {
+        let mut staircase = Staircase::with_rule(0.98, 0.05, 1, 1, [0.7, 0.99]);
+        assert_eq!(staircase.update(true), 0.99);
+        for _ in 0..20 {
+            staircase.update(false);
+        }
+        assert_eq!(staircase.threshold, 0.7);
+        assert_eq!(Staircase::new(2.0).threshold, Staircase::new(1.0).range[1], "start is clamped");
+    }
+}
@@ -0,0 +1,99 @@
+//! Deterministic tutorial sequence for new animals: an easy to hard ramp of trials.
+//!
+//! Early trials keep the practice aids on (shaping auto-win and the live feedback bar), use a
+//! wide alignment threshold and few decorations; later trials tighten toward the regular task.
+use crate::constants::game_constants::{
+    TUTORIAL_COSINE_RANGE, TUTORIAL_DECORATIONS_RANGE, TUTORIAL_PRACTICE_TRIALS, TUTORIAL_TRIAL_COUNT,
+};
+use crate::constants::pyramid_constants::BASE_NR_SIDES;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// One tutorial trial: the fields it overrides on top of the default trial config
+#[derive(Clone, Debug, PartialEq)]
+pub struct TutorialTrial {
+    pub decoration_seeds: [u64; 3],
+    pub start_orient: f32,
+    pub target_door: u32,
+    pub cosine_alignment_threshold: f32,
+    pub decorations_count: [u32; 3],
+    /// Practice aids on: shaping auto-win and the live feedback bar
+    pub practice: bool,
+}
+
+/// Generates the tutorial sequence. With a `decoration_seed`, the geometry (start orientation and
+/// target door) and the face decoration seeds come from separate streams, so either can be held fixed
+/// while the other varies. Without one, both come from the `geometry_seed` stream, giving the trials
+/// a single seed always gave. The same seeds always yield the same trials, and the threshold and
+/// decoration count never decrease from one trial to the next.
+pub fn generate_tutorial_trials(geometry_seed: u64, decoration_seed: Option<u64>) -> Vec<TutorialTrial> {
+    let mut geometry_rng = ChaCha8Rng::seed_from_u64(geometry_seed);
+    let mut decoration_rng = decoration_seed.map(ChaCha8Rng::seed_from_u64);
+    let last = (TUTORIAL_TRIAL_COUNT - 1).max(1) as f32;
+
+    (0..TUTORIAL_TRIAL_COUNT)
+        .map(|i| {
+            let t = i as f32 / last;
+            let lerp = |range: [f32; 2]| range[0] + (range[1] - range[0]) * t;
+            let decorations = lerp([TUTORIAL_DECORATIONS_RANGE[0] as f32, TUTORIAL_DECORATIONS_RANGE[1] as f32]).round() as u32;
+            let rng = decoration_rng.as_mut().unwrap_or(&mut geometry_rng);
+            let decoration_seeds = [rng.next_u64(), rng.next_u64(), rng.next_u64()];
+            TutorialTrial {
+                decoration_seeds,
+                start_orient: geometry_rng.random::<f32>() * std::f32::consts::TAU,
+                target_door: geometry_rng.random_range(0..BASE_NR_SIDES as u32),
+                cosine_alignment_threshold: lerp(TUTORIAL_COSINE_RANGE),
+                decorations_count: [decorations; 3],
+                practice: i < TUTORIAL_PRACTICE_TRIALS,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tutorial_is_deterministic_per_seed() {
+        assert_eq!(generate_tutorial_trials(7, None), generate_tutorial_trials(7, None));
+        assert_ne!(generate_tutorial_trials(7, None), generate_tutorial_trials(8, None));
+        assert_eq!(generate_tutorial_trials(7, Some(3)), generate_tutorial_trials(7, Some(3)));
+    }
+
+    #[test]
+    fn single_seed_tutorial_draws_from_one_stream() {
+        // The order the tutorial always drew a trial in: decoration seeds, orientation, target door
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for trial in generate_tutorial_trials(7, None) {
+            assert_eq!(trial.decoration_seeds, [rng.next_u64(), rng.next_u64(), rng.next_u64()]);
+            assert_eq!(trial.start_orient, rng.random::<f32>() * std::f32::consts::TAU);
+            assert_eq!(trial.target_door, rng.random_range(0..BASE_NR_SIDES as u32));
+        }
+    }
+
+    #[test]
+    fn tutorial_geometry_and_decorations_vary_independently() {
+        let base = generate_tutorial_trials(7, Some(7));
+        let redecorated = generate_tutorial_trials(7, Some(8));
+        let moved = generate_tutorial_trials(8, Some(7));
+        for ((trial, redecorated), moved) in base.iter().zip(&redecorated).zip(&moved) {
+            assert_eq!((trial.start_orient, trial.target_door), (redecorated.start_orient, redecorated.target_door));
+            assert_ne!(trial.decoration_seeds, redecorated.decoration_seeds);
+            assert_eq!(trial.decoration_seeds, moved.decoration_seeds);
+            assert_ne!(trial.start_orient, moved.start_orient);
+        }
+    }
+
+    #[test]
+    fn tutorial_difficulty_never_decreases() {
+        let trials = generate_tutorial_trials(42, None);
+        assert!(trials.len() > 1);
+        for pair in trials.windows(2) {
+            assert!(pair[1].cosine_alignment_threshold > pair[0].cosine_alignment_threshold, "threshold tightens");
+            assert!(pair[1].decorations_count[0] >= pair[0].decorations_count[0], "decorations never drop");
+            assert!(pair[0].practice || !pair[1].practice, "practice aids only at the start");
+        }
+        assert!(trials[0].practice && !trials[trials.len() - 1].practice);
+    }
+}
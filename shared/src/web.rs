@@ -76,6 +76,7 @@ impl WebSharedMemory {
         set("door_anim_fade_out", make_offset(&gs.door_anim_fade_out as *const _));
         set("door_anim_stay_open", make_offset(&gs.door_anim_stay_open as *const _));
         set("door_anim_fade_in", make_offset(&gs.door_anim_fade_in as *const _));
+        set("door_anim_easing", make_offset(&gs.door_anim_easing as *const _));
 
         set("main_spotlight_intensity", make_offset(&gs.main_spotlight_intensity as *const _));
         set("max_spotlight_intensity", make_offset(&gs.max_spotlight_intensity as *const _));
@@ -92,10 +93,28 @@ impl WebSharedMemory {
         set("alignment", make_offset(&gs.alignment as *const _));
         set("current_angle", make_offset(&gs.current_angle as *const _));
         set("is_animating", make_offset(&gs.is_animating as *const _));
+        set("phase", make_offset(&gs.phase as *const _));
+        set("post_win_mode", make_offset(&gs.post_win_mode as *const _));
         set("win_time", make_offset(&gs.win_time as *const _));
+        set("tab_hidden", make_offset(&gs.tab_hidden as *const _));
+        set("tab_hidden_secs_trial", make_offset(&gs.tab_hidden_secs_trial as *const _));
+        set("gamepad_connected", make_offset(&gs.gamepad_connected as *const _));
+        set("dropped_checks", make_offset(&gs.dropped_checks as *const _));
+        set("commands_seen", make_offset(&gs.commands_seen as *const _));
+        set("current_trial_index", make_offset(&gs.current_trial_index as *const _));
+        set("total_trials", make_offset(&gs.total_trials as *const _));
+        set("block_index", make_offset(&gs.block_index as *const _));
+        set("session_id", make_offset(&gs.session_id as *const _));
+        set("subject_id", make_offset(&gs.subject_id as *const _));
         
         offsets.into()
     }
+
+    /// Latest state snapshot published by the game as a JSON object (see snapshot.rs), consistent
+    /// across its fields without a retry loop on the JS side. `undefined` before the first frame
+    pub fn read_snapshot_json(&self) -> Option<String> {
+        unsafe { (*self.ptr).state_snapshot.read() }.map(|snapshot| snapshot.to_json())
+    }
 }
 
 /// Handle to shared memory (wrapper for consistency with native API).
@@ -108,6 +127,32 @@ impl SharedMemoryHandle {
     }
 }
 
+/// State dictionary of the Python `read_game_structure` as a JSON string (see json_api.rs)
+#[wasm_bindgen]
+pub fn read_state_json() -> String {
+    crate::json_api::state_json(SHARED_MEMORY.get_or_init(SharedMemory::new)).to_string()
+}
+
+/// Config the game reads at the next reset, keyed like the config part of `read_state_json`
+#[wasm_bindgen]
+pub fn read_config_json() -> String {
+    let config = crate::json_api::config_json(&SHARED_MEMORY.get_or_init(SharedMemory::new).game_structure_control);
+    serde_json::Value::Object(config).to_string()
+}
+
+/// `write_game_structure` from a JSON object of its keyword arguments. Keys left out keep their value
+#[wasm_bindgen]
+pub fn write_config_json(json: &str) -> Result<(), JsValue> {
+    crate::json_api::apply_config_json(SHARED_MEMORY.get_or_init(SharedMemory::new), json).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Calls the controller method named under `"method"` with the other keys as keyword arguments,
+/// e.g. `{"method": "write_commands", "rotate_left": true}`
+#[wasm_bindgen]
+pub fn command(json: &str) -> Result<(), JsValue> {
+    crate::json_api::apply_command_json(SHARED_MEMORY.get_or_init(SharedMemory::new), json).map_err(|e| JsValue::from_str(&e))
+}
+
 pub fn open_shared_memory(_name: &str) -> std::io::Result<SharedMemoryHandle> {
     let mem = SHARED_MEMORY.get().ok_or(std::io::Error::new(
         std::io::ErrorKind::NotFound,